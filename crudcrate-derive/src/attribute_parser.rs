@@ -1,4 +1,7 @@
-use crate::traits::crudresource::structs::CRUDResourceMeta;
+use crate::codegen::models::shared::RENAME_ALL_RULES;
+use crate::traits::crudresource::structs::{
+    CRUDResourceMeta, FulltextFieldConfig, StructLevelTemporal,
+};
 use syn::parse::Parser;
 use syn::{Lit, Meta, punctuated::Punctuated, token::Comma};
 
@@ -8,6 +11,7 @@ use syn::{Lit, Meta, punctuated::Punctuated, token::Comma};
 /// Supports both legacy syntax and new hook syntax:
 /// - Legacy: `fn_delete = my_fn`
 /// - New: `create::one::pre = validate_fn`
+#[allow(clippy::too_many_lines)]
 pub(crate) fn parse_crud_resource_meta(attrs: &[syn::Attribute]) -> CRUDResourceMeta {
     let mut meta = CRUDResourceMeta::new();
 
@@ -34,6 +38,38 @@ pub(crate) fn parse_crud_resource_meta(attrs: &[syn::Attribute]) -> CRUDResource
                                         Some("fulltext_language") => {
                                             meta.fulltext_language = Some(value);
                                         }
+                                        Some("tsvector_column") => {
+                                            meta.tsvector_column = Some(value);
+                                        }
+                                        Some("fts5_table") => {
+                                            meta.fts5_table = Some(value);
+                                        }
+                                        Some("normalized_search") => {
+                                            if !cfg!(feature = "unicode") {
+                                                meta.deprecation_errors.push(syn::Error::new_spanned(
+                                                    s,
+                                                    "`normalized_search` requires crudcrate's `unicode` feature",
+                                                ));
+                                            }
+                                            meta.normalized_search = Some(value);
+                                        }
+                                        Some("view_sql") => meta.view_sql = Some(value),
+                                        Some("guard_delete") => meta.guard_delete = Some(value),
+                                        Some("security") => meta.security = Some(value),
+                                        Some("sync") => meta.sync = Some(value),
+                                        Some("rename_all") => {
+                                            if RENAME_ALL_RULES.contains(&value.as_str()) {
+                                                meta.rename_all = Some(value);
+                                            } else {
+                                                meta.deprecation_errors.push(syn::Error::new_spanned(
+                                                    s,
+                                                    format!(
+                                                        "Unknown `rename_all` rule `{value}`. Expected one of: {}",
+                                                        RENAME_ALL_RULES.join(", ")
+                                                    ),
+                                                ));
+                                            }
+                                        }
                                         _ => {}
                                     }
                                 }
@@ -45,6 +81,7 @@ pub(crate) fn parse_crud_resource_meta(attrs: &[syn::Attribute]) -> CRUDResource
                                         Some("generate_router") => meta.generate_router = value,
                                         Some("derive_partial_eq") => meta.derive_partial_eq = value,
                                         Some("derive_eq") => meta.derive_eq = value,
+                                        Some("openapi") => meta.skip_openapi = !value,
                                         _ => {}
                                     }
                                 }
@@ -58,6 +95,30 @@ pub(crate) fn parse_crud_resource_meta(attrs: &[syn::Attribute]) -> CRUDResource
                                         Some("max_page_size") => {
                                             meta.max_page_size = i.base10_parse().ok();
                                         }
+                                        Some("max_filter_length") => {
+                                            meta.max_filter_length = i.base10_parse().ok();
+                                        }
+                                        Some("max_filter_depth") => {
+                                            meta.max_filter_depth = i.base10_parse().ok();
+                                        }
+                                        Some("max_body_size") => {
+                                            meta.max_body_size = i.base10_parse().ok();
+                                        }
+                                        Some("timeout_ms") => {
+                                            meta.timeout_ms = i.base10_parse().ok();
+                                        }
+                                        Some("singleflight_ms") => {
+                                            meta.singleflight_ms = i.base10_parse().ok();
+                                        }
+                                        Some("retry_attempts") => {
+                                            meta.retry_attempts = i.base10_parse().ok();
+                                        }
+                                        Some("max_join_queries") => {
+                                            meta.max_join_queries = i.base10_parse().ok();
+                                        }
+                                        Some("max_joined_rows") => {
+                                            meta.max_joined_rows = i.base10_parse().ok();
+                                        }
                                         _ => {}
                                     }
                                 }
@@ -124,6 +185,7 @@ pub(crate) fn parse_crud_resource_meta(attrs: &[syn::Attribute]) -> CRUDResource
                                         ));
                                     }
                                     Some("operations") => meta.operations = Some(fn_path.clone()),
+                                    Some("layer") => meta.layers.push(fn_path.clone()),
                                     _ => {}
                                 }
                             }
@@ -139,6 +201,19 @@ pub(crate) fn parse_crud_resource_meta(attrs: &[syn::Attribute]) -> CRUDResource
                             Some("no_partial_eq") => meta.derive_partial_eq = false,
                             Some("no_eq") => meta.derive_eq = false,
                             Some("require_scope") => meta.require_scope = true,
+                            Some("auto_index") => meta.auto_index = true,
+                            Some("enum_case_sensitive") => meta.enum_case_sensitive = true,
+                            Some("strict_query") => meta.strict_query = true,
+                            Some("server_timing") => meta.server_timing = true,
+                            Some("unstable_sort") => meta.unstable_sort = true,
+                            Some("serialize_writes") => meta.serialize_writes = true,
+                            Some("create_builder") => meta.create_builder = true,
+                            Some("restrict_delete") => meta.restrict_delete = true,
+                            Some("cascade_delete") => meta.cascade_delete = true,
+                            Some("webhooks") => meta.webhooks = true,
+                            Some("temporal") => {
+                                meta.temporal = Some(StructLevelTemporal::default());
+                            }
                             _ => {}
                         }
                     }
@@ -154,6 +229,43 @@ pub(crate) fn parse_crud_resource_meta(attrs: &[syn::Attribute]) -> CRUDResource
                                 ));
                             }
                             meta.struct_level_joins.push(join_def);
+                        } else if list.path.is_ident("geo") {
+                            match parse_struct_level_geo(&list) {
+                                Ok(geo) => meta.struct_level_geo.push(geo),
+                                Err(e) => meta.deprecation_errors.push(e),
+                            }
+                        } else if list.path.is_ident("virtual_filter") {
+                            match parse_virtual_filter(&list) {
+                                Ok(filter) => meta.virtual_filters.push(filter),
+                                Err(e) => meta.deprecation_errors.push(e),
+                            }
+                        } else if list.path.is_ident("tree") {
+                            match parse_struct_level_tree(&list) {
+                                Ok(tree) => meta.tree = Some(tree),
+                                Err(e) => meta.deprecation_errors.push(e),
+                            }
+                        } else if list.path.is_ident("state_machine") {
+                            match parse_state_machine(&list) {
+                                Ok(machine) => meta.state_machine = Some(machine),
+                                Err(e) => meta.deprecation_errors.push(e),
+                            }
+                        } else if list.path.is_ident("temporal") {
+                            match parse_temporal(&list) {
+                                Ok(temporal) => meta.temporal = Some(temporal),
+                                Err(e) => meta.deprecation_errors.push(e),
+                            }
+                        } else if list.path.is_ident("dedupe_on") {
+                            match Punctuated::<syn::Ident, Comma>::parse_terminated
+                                .parse2(list.tokens.clone())
+                            {
+                                Ok(fields) => meta.dedupe_on.extend(fields),
+                                Err(e) => meta.deprecation_errors.push(e),
+                            }
+                        } else if list.path.is_ident("unique_together") {
+                            match parse_unique_together(&list) {
+                                Ok(unique) => meta.unique_together.push(unique),
+                                Err(e) => meta.deprecation_errors.push(e),
+                            }
                         }
                     }
                 }
@@ -161,9 +273,56 @@ pub(crate) fn parse_crud_resource_meta(attrs: &[syn::Attribute]) -> CRUDResource
         }
     }
 
+    // Without the `openapi` feature utoipa isn't a dependency of crudcrate
+    if !cfg!(feature = "openapi") {
+        meta.skip_openapi = true;
+    }
+
     meta
 }
 
+/// Parse a struct-level `unique_together(email, org_id, condition = "deleted_at IS NULL")`
+fn parse_unique_together(
+    meta_list: &syn::MetaList,
+) -> Result<crate::traits::crudresource::structs::UniqueTogether, syn::Error> {
+    let mut fields = Vec::new();
+    let mut condition = None;
+    let metas = Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())?;
+
+    for meta in metas {
+        match &meta {
+            Meta::Path(path) if path.get_ident().is_some() => {
+                fields.extend(path.get_ident().cloned());
+            }
+            Meta::NameValue(nv) if nv.path.is_ident("condition") => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }) if !s.value().trim().is_empty() => condition = Some(s.value()),
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "Expected a SQL condition, e.g. `condition = \"deleted_at IS NULL\"`",
+                    ));
+                }
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &meta,
+                    "Expected field names and an optional `condition = \"...\"`",
+                ));
+            }
+        }
+    }
+
+    if fields.is_empty() {
+        return Err(syn::Error::new_spanned(
+            meta_list,
+            "`unique_together` needs at least one field, e.g. `unique_together(email, org_id)`",
+        ));
+    }
+    Ok(crate::traits::crudresource::structs::UniqueTogether { fields, condition })
+}
+
 /// Parse a struct-level `join(name = "field", result = "Type", one, all, depth = N)` attribute.
 /// Returns `Some` only if both `name` and `result` are present (distinguishing from field-level joins).
 fn parse_struct_level_join(
@@ -181,6 +340,8 @@ fn parse_struct_level_join(
     let mut filterable_columns = Vec::new();
     let mut sortable_columns = Vec::new();
     let mut fk_column = None;
+    let mut limit = None;
+    let mut order_by = None;
     let metas = Punctuated::<Meta, Comma>::parse_terminated
         .parse2(meta_list.tokens.clone())
         .ok()?;
@@ -201,11 +362,15 @@ fn parse_struct_level_join(
                                 path = Some(s.value());
                             } else if nv.path.is_ident("fk_column") {
                                 fk_column = Some(s.value());
+                            } else if nv.path.is_ident("order_by") {
+                                order_by = Some(s.value());
                             }
                         }
                         Lit::Int(i) => {
                             if nv.path.is_ident("depth") {
                                 depth = i.base10_parse().ok();
+                            } else if nv.path.is_ident("limit") {
+                                limit = i.base10_parse().ok();
                             }
                         }
                         _ => {}
@@ -246,9 +411,298 @@ fn parse_struct_level_join(
         filterable_columns,
         sortable_columns,
         fk_column,
+        limit,
+        order_by,
     })
 }
 
+/// Parse a struct-level `geo(name = "location", lat = "latitude", lon = "longitude")` attribute.
+fn parse_struct_level_geo(
+    meta_list: &syn::MetaList,
+) -> Result<crate::traits::crudresource::structs::StructLevelGeo, syn::Error> {
+    use crate::traits::crudresource::structs::StructLevelGeo;
+
+    let mut name = None;
+    let mut lat = None;
+    let mut lon = None;
+    let metas = Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())?;
+
+    for meta in metas {
+        let target = match meta.path().get_ident().map(ToString::to_string).as_deref() {
+            Some("name") => &mut name,
+            Some("lat") => &mut lat,
+            Some("lon") => &mut lon,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &meta,
+                    "Unknown `geo(...)` option. Expected `name`, `lat` or `lon`",
+                ));
+            }
+        };
+        match &meta {
+            Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => *target = Some(s.value()),
+                other => {
+                    return Err(syn::Error::new_spanned(other, "Expected a string literal"));
+                }
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(&meta, "Expected `key = \"value\"`"));
+            }
+        }
+    }
+
+    match (name, lat, lon) {
+        (Some(name), Some(lat), Some(lon)) => Ok(StructLevelGeo { name, lat, lon }),
+        _ => Err(syn::Error::new_spanned(
+            meta_list,
+            "`geo(...)` requires `name`, `lat` and `lon`, e.g. \
+             `geo(name = \"location\", lat = \"latitude\", lon = \"longitude\")`",
+        )),
+    }
+}
+
+/// Parse a struct-level `virtual_filter(name = "active", builder = active_condition)` attribute.
+fn parse_virtual_filter(
+    meta_list: &syn::MetaList,
+) -> Result<crate::traits::crudresource::structs::VirtualFilter, syn::Error> {
+    use crate::traits::crudresource::structs::VirtualFilter;
+
+    let mut name = None;
+    let mut builder = None;
+    let mut description = None;
+    let metas = Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())?;
+
+    for meta in metas {
+        let Meta::NameValue(nv) = &meta else {
+            return Err(syn::Error::new_spanned(&meta, "Expected `key = value`"));
+        };
+        match (
+            nv.path.get_ident().map(ToString::to_string).as_deref(),
+            &nv.value,
+        ) {
+            (
+                Some(key @ ("name" | "description")),
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }),
+            ) => {
+                if key == "name" {
+                    name = Some(s.value());
+                } else {
+                    description = Some(s.value());
+                }
+            }
+            (Some("builder"), syn::Expr::Path(path)) => builder = Some(path.path.clone()),
+            (Some("name" | "description"), other) => {
+                return Err(syn::Error::new_spanned(other, "Expected a string literal"));
+            }
+            (Some("builder"), other) => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "Expected a function path, e.g. `builder = active_condition`",
+                ));
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &meta,
+                    "Unknown `virtual_filter(...)` option. Expected `name`, `builder` or `description`",
+                ));
+            }
+        }
+    }
+
+    match (name, builder) {
+        (Some(name), _)
+            if name.is_empty()
+                || name.starts_with(|c: char| c.is_ascii_digit())
+                || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+        {
+            Err(syn::Error::new_spanned(
+                meta_list,
+                format!("`virtual_filter` name `{name}` must be a snake_case identifier"),
+            ))
+        }
+        (Some(name), Some(builder)) => Ok(VirtualFilter {
+            name,
+            builder,
+            description,
+        }),
+        _ => Err(syn::Error::new_spanned(
+            meta_list,
+            "`virtual_filter(...)` requires `name` and `builder`, e.g. \
+             `virtual_filter(name = \"active\", builder = active_condition)`",
+        )),
+    }
+}
+
+/// Parse a struct-level `tree(parent_field = "parent_id", max_depth = 10)` attribute.
+fn parse_struct_level_tree(
+    meta_list: &syn::MetaList,
+) -> Result<crate::traits::crudresource::structs::StructLevelTree, syn::Error> {
+    use crate::traits::crudresource::structs::StructLevelTree;
+
+    let mut parent_field = None;
+    let mut max_depth = None;
+    let metas = Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())?;
+
+    for meta in metas {
+        let Meta::NameValue(nv) = &meta else {
+            return Err(syn::Error::new_spanned(&meta, "Expected `key = value`"));
+        };
+        match (
+            nv.path.get_ident().map(ToString::to_string).as_deref(),
+            &nv.value,
+        ) {
+            (
+                Some("parent_field"),
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }),
+            ) => parent_field = Some(s.value()),
+            (
+                Some("max_depth"),
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Int(i), ..
+                }),
+            ) => {
+                let depth: u32 = i.base10_parse()?;
+                if depth == 0 {
+                    return Err(syn::Error::new_spanned(i, "`max_depth` must be at least 1"));
+                }
+                max_depth = Some(depth);
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &meta,
+                    "Expected `parent_field = \"...\"` or `max_depth = N` inside `tree(...)`",
+                ));
+            }
+        }
+    }
+
+    parent_field
+        .map(|parent_field| StructLevelTree {
+            parent_field,
+            max_depth,
+        })
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                meta_list,
+                "`tree(...)` requires `parent_field`, e.g. `tree(parent_field = \"parent_id\")`",
+            )
+        })
+}
+
+/// Parse a struct-level `temporal(from = "starts_at", to = "ends_at")` attribute. Either
+/// key may be left out for its default, `valid_from` or `valid_to`.
+fn parse_temporal(meta_list: &syn::MetaList) -> Result<StructLevelTemporal, syn::Error> {
+    let mut temporal = StructLevelTemporal::default();
+    let metas = Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())?;
+
+    for meta in metas {
+        let Meta::NameValue(nv) = &meta else {
+            return Err(syn::Error::new_spanned(&meta, "Expected `key = value`"));
+        };
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(s), ..
+        }) = &nv.value
+        else {
+            return Err(syn::Error::new_spanned(
+                &nv.value,
+                "Expected a field name, e.g. `from = \"valid_from\"`",
+            ));
+        };
+        match nv.path.get_ident().map(ToString::to_string).as_deref() {
+            Some("from") => temporal.from = s.value(),
+            Some("to") => temporal.to = s.value(),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &meta,
+                    "Expected `from = \"...\"` or `to = \"...\"` inside `temporal(...)`",
+                ));
+            }
+        }
+    }
+    Ok(temporal)
+}
+
+/// Parse a struct-level `state_machine(field = "status", transitions("todo -> doing"))`
+/// attribute. A transition may list several targets: `"doing -> done, todo"`.
+fn parse_state_machine(
+    meta_list: &syn::MetaList,
+) -> Result<crate::traits::crudresource::structs::StructLevelStateMachine, syn::Error> {
+    use crate::traits::crudresource::structs::StructLevelStateMachine;
+
+    let mut field = None;
+    let mut transitions = Vec::new();
+    let metas = Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())?;
+
+    for meta in metas {
+        match &meta {
+            Meta::NameValue(nv) if nv.path.is_ident("field") => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => field = Some(s.value()),
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "Expected a field name, e.g. `field = \"status\"`",
+                    ));
+                }
+            },
+            Meta::List(list) if list.path.is_ident("transitions") => {
+                let entries = Punctuated::<syn::LitStr, Comma>::parse_terminated
+                    .parse2(list.tokens.clone())?;
+                for entry in entries {
+                    let value = entry.value();
+                    let parsed = value.split_once("->").and_then(|(from, targets)| {
+                        let from = from.trim();
+                        let targets: Vec<&str> = targets.split(',').map(str::trim).collect();
+                        (!from.is_empty()
+                            && targets
+                                .iter()
+                                .all(|to| !to.is_empty() && !to.contains("->")))
+                        .then(|| {
+                            targets
+                                .into_iter()
+                                .map(|to| (from.to_string(), to.to_string()))
+                        })
+                    });
+                    match parsed {
+                        Some(pairs) => transitions.extend(pairs),
+                        None => {
+                            return Err(syn::Error::new_spanned(
+                                &entry,
+                                "Expected `\"from -> to\"` or `\"from -> to, other\"`",
+                            ));
+                        }
+                    }
+                }
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &meta,
+                    "Expected `field = \"...\"` or `transitions(...)` inside `state_machine(...)`",
+                ));
+            }
+        }
+    }
+
+    match field {
+        Some(field) if !transitions.is_empty() => {
+            Ok(StructLevelStateMachine { field, transitions })
+        }
+        _ => Err(syn::Error::new_spanned(
+            meta_list,
+            "`state_machine(...)` requires `field` and `transitions`, e.g. \
+             `state_machine(field = \"status\", transitions(\"todo -> done\"))`",
+        )),
+    }
+}
+
 /// Parse a list of string literals from a nested meta list like `filterable("col1", "col2")`
 fn parse_struct_join_string_list(meta_list: &syn::MetaList) -> Vec<String> {
     Punctuated::<syn::Expr, Comma>::parse_terminated
@@ -271,7 +725,7 @@ fn parse_struct_join_string_list(meta_list: &syn::MetaList) -> Vec<String> {
 }
 
 /// Parse a path like `create::one::pre` into (operation, cardinality, phase)
-fn parse_hook_path(path: &syn::Path) -> Option<(String, String, String)> {
+pub(crate) fn parse_hook_path(path: &syn::Path) -> Option<(String, String, String)> {
     let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
 
     if segments.len() != 3 {
@@ -292,8 +746,15 @@ fn parse_hook_path(path: &syn::Path) -> Option<(String, String, String)> {
         return None;
     }
 
-    // Validate phase
-    if !matches!(phase.as_str(), "pre" | "body" | "transform" | "post") {
+    // Validate phase; reads have no side effects to move off the request, and one
+    // read condition covers both cardinalities
+    let valid_phase = match phase.as_str() {
+        "pre" | "body" | "transform" | "post" => true,
+        "post_async" => operation != "read",
+        "condition" => operation == "read" && cardinality == "many",
+        _ => false,
+    };
+    if !valid_phase {
         return None;
     }
 
@@ -327,6 +788,8 @@ fn set_hook(
         "body" => card_hooks.body = Some(fn_path),
         "transform" => card_hooks.transform = Some(fn_path),
         "post" => card_hooks.post = Some(fn_path),
+        "post_async" => card_hooks.post_async = Some(fn_path),
+        "condition" => card_hooks.condition = Some(fn_path),
         _ => {}
     }
 }
@@ -387,11 +850,29 @@ pub(crate) fn extract_table_name(attrs: &[syn::Attribute]) -> Option<String> {
 /// - `#[crudcrate(non_db_attr)]` (implicit true)
 /// - `#[crudcrate(exclude_create)]` → `create_model = false` (individual aliases)
 /// - `#[crudcrate(exclude(create, update))]` → both `create_model` and `update_model` = false
+/// - `#[crudcrate(large_text)]` → `list_model` = false
+/// - `#[crudcrate(list_only)]` → `one_model` = false
 pub(crate) fn get_crudcrate_bool(field: &syn::Field, key: &str) -> Option<bool> {
+    // Hashed fields are write-only whatever else the field says
+    if matches!(
+        key,
+        "list_model" | "one_model" | "scoped_model" | "response_model"
+    ) && get_crudcrate_expr(field, "hash_with").is_some()
+    {
+        return Some(false);
+    }
+    // List-only fields are left out of single-item and write responses
+    if key == "one_model" && field_has_crudcrate_flag(field, "list_only") {
+        return Some(false);
+    }
     // First check for exclude() configuration (most idiomatic)
     if let Some(result) = check_exclude_config(field, key) {
         return Some(result); // check_exclude_config already returns the correct boolean for the model
     }
+    // Large text is served from `/{id}/{field}` rather than in every list row
+    if key == "list_model" && field_has_crudcrate_flag(field, "large_text") {
+        return Some(false);
+    }
 
     for attr in &field.attrs {
         if attr.path().is_ident("crudcrate")
@@ -492,6 +973,16 @@ fn parse_exclude_parameters(meta_list: &syn::MetaList, target_key: &str) -> Opti
                     return Some(true); // exclude(all) excludes from both list and one
                 }
 
+                // exclude(response) keeps the field out of every serialized response
+                if path.is_ident("response")
+                    && matches!(
+                        target_key,
+                        "list_model" | "one_model" | "scoped_model" | "response_model"
+                    )
+                {
+                    return Some(true);
+                }
+
                 let excluded_type = if path.is_ident("create") {
                     "create_model"
                 } else if path.is_ident("update") {
@@ -534,9 +1025,135 @@ pub(crate) fn get_crudcrate_expr(field: &syn::Field, key: &str) -> Option<syn::E
             }
         }
     }
+    if key == "on_create" {
+        return id_generator_expr(field);
+    }
     None
 }
 
+/// Built-in `id_generator` values, each named after its function in `crudcrate::ids`
+/// (`ulid` on a `Uuid` field uses `ulid_uuid`)
+pub(crate) const ID_GENERATORS: &[&str] = &["uuid_v4", "uuid_v7", "ulid", "ksuid"];
+
+/// Generators whose keys start with a timestamp
+pub(crate) const TIME_SORTABLE_ID_GENERATORS: &[&str] = &["uuid_v7", "ulid", "ksuid"];
+
+/// The field's `id_generator`, if it names a built-in generator
+pub(crate) fn get_id_generator(field: &syn::Field) -> Option<String> {
+    get_crudcrate_strings(field, "id_generator")
+        .into_iter()
+        .last()
+        .filter(|generator| ID_GENERATORS.contains(&generator.as_str()))
+}
+
+/// The `on_create` expression an `id_generator` stands for:
+/// `id_generator = "uuid_v7"` is `on_create = crudcrate::ids::uuid_v7()`
+fn id_generator_expr(field: &syn::Field) -> Option<syn::Expr> {
+    let generator = get_id_generator(field)?;
+    let function = if generator == "ulid" && is_uuid_type(&field.ty) {
+        quote::format_ident!("ulid_uuid")
+    } else {
+        quote::format_ident!("{generator}")
+    };
+    Some(syn::parse_quote!(crudcrate::ids::#function()))
+}
+
+/// Built-in `sanitize(...)` names, each a function in `crudcrate::sanitize`
+pub(crate) const SANITIZERS: &[&str] = &["trim", "lowercase", "uppercase", "collapse_whitespace"];
+
+/// The field's `sanitize(...)` functions and `sanitize_with` paths, in the order written
+pub(crate) fn get_sanitizers(field: &syn::Field) -> Vec<proc_macro2::TokenStream> {
+    let mut sanitizers = Vec::new();
+    for attr in &field.attrs {
+        if attr.path().is_ident("crudcrate")
+            && let Meta::List(meta_list) = &attr.meta
+            && let Ok(metas) =
+                Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())
+        {
+            for meta in metas {
+                match meta {
+                    Meta::List(list) if list.path.is_ident("sanitize") => {
+                        let Ok(names) = Punctuated::<syn::Ident, Comma>::parse_terminated
+                            .parse2(list.tokens.clone())
+                        else {
+                            continue;
+                        };
+                        for name in names {
+                            if SANITIZERS.contains(&name.to_string().as_str()) {
+                                sanitizers.push(quote::quote! { crudcrate::sanitize::#name });
+                            }
+                        }
+                    }
+                    Meta::NameValue(nv) if nv.path.is_ident("sanitize_with") => {
+                        let function = nv.value;
+                        sanitizers.push(quote::quote! { #function });
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    sanitizers
+}
+
+/// Whether the field's value is masked in `Debug` output: `sensitive` fields, and
+/// `hash_with` fields, whose plain value only ever appears in create/update input
+pub(crate) fn is_sensitive(field: &syn::Field) -> bool {
+    field_has_crudcrate_flag(field, "sensitive") || get_crudcrate_expr(field, "hash_with").is_some()
+}
+
+/// Whether the type is `Uuid` (by its last path segment, so `uuid::Uuid` counts too)
+pub(crate) fn is_uuid_type(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(type_path)
+        if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Uuid"))
+}
+
+/// Whether an `on_create`/`on_update` value names an async generator function
+/// (`on_create = next_invoice_number`) rather than being an expression
+/// (`on_create = Uuid::new_v4()`).
+///
+/// Only bare paths whose last segment starts lowercase count, so enum variants and
+/// constants (`Status::Draft`, `DEFAULT_RANK`) stay expressions.
+pub(crate) fn is_async_generator(expr: &syn::Expr) -> bool {
+    if let syn::Expr::Path(expr_path) = expr
+        && expr_path.qself.is_none()
+        && let Some(segment) = expr_path.path.segments.last()
+        && segment.arguments.is_empty()
+    {
+        segment
+            .ident
+            .to_string()
+            .trim_start_matches("r#")
+            .starts_with(|c: char| c.is_ascii_lowercase())
+    } else {
+        false
+    }
+}
+
+/// Every string value given for `key` in the field's `#[crudcrate(...)]` attributes,
+/// in order. Used for keys that may repeat, like `alias`.
+pub(crate) fn get_crudcrate_strings(field: &syn::Field, key: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    for attr in &field.attrs {
+        if attr.path().is_ident("crudcrate")
+            && let Meta::List(meta_list) = &attr.meta
+            && let Ok(metas) =
+                Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())
+        {
+            for meta in metas {
+                if let Meta::NameValue(nv) = meta
+                    && nv.path.is_ident(key)
+                    && let syn::Expr::Lit(expr_lit) = &nv.value
+                    && let Lit::Str(s) = &expr_lit.lit
+                {
+                    values.push(s.value());
+                }
+            }
+        }
+    }
+    values
+}
+
 /// Extracts a string literal from a struct‐level attribute of the form:
 ///   `#[active_model = "some::path"]`
 pub(crate) fn get_string_from_attr(attr: &syn::Attribute) -> Option<String> {
@@ -575,10 +1192,171 @@ pub(crate) fn field_has_crudcrate_flag(field: &syn::Field, flag: &str) -> bool {
     false
 }
 
+/// Parses the field-level `fulltext` attribute.
+///
+/// - `fulltext` → `Some(FulltextFieldConfig { trigram_threshold: None })`
+/// - `fulltext(strategy = "trigram")` → trigram with the `pg_trgm` default threshold (0.3)
+/// - `fulltext(strategy = "trigram", threshold = 0.5)` → trigram with a custom threshold
+/// - `fulltext(mode = "boolean")` → `MATCH ... AGAINST (... IN BOOLEAN MODE)` on `MySQL`
+///
+/// Returns `Ok(None)` if the field is not a fulltext field.
+pub(crate) fn get_fulltext_config(
+    field: &syn::Field,
+) -> Result<Option<FulltextFieldConfig>, syn::Error> {
+    for attr in &field.attrs {
+        if attr.path().is_ident("crudcrate")
+            && let Meta::List(meta_list) = &attr.meta
+            && let Ok(metas) =
+                Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())
+        {
+            for meta in metas {
+                match meta {
+                    Meta::Path(path) if path.is_ident("fulltext") => {
+                        return Ok(Some(FulltextFieldConfig::default()));
+                    }
+                    Meta::List(list) if list.path.is_ident("fulltext") => {
+                        return parse_fulltext_list(&list).map(Some);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn parse_fulltext_list(list: &syn::MetaList) -> Result<FulltextFieldConfig, syn::Error> {
+    const DEFAULT_TRIGRAM_THRESHOLD: f64 = 0.3;
+
+    let metas = Punctuated::<Meta, Comma>::parse_terminated.parse2(list.tokens.clone())?;
+    let mut trigram = false;
+    let mut threshold = None;
+    let mut boolean_mode = false;
+
+    for meta in metas {
+        let Meta::NameValue(nv) = &meta else {
+            return Err(syn::Error::new_spanned(
+                &meta,
+                "Expected `strategy = \"...\"`, `threshold = 0.3` or `mode = \"...\"` inside `fulltext(...)`",
+            ));
+        };
+        let syn::Expr::Lit(expr_lit) = &nv.value else {
+            return Err(syn::Error::new_spanned(
+                &nv.value,
+                "Expected a literal value",
+            ));
+        };
+        match (
+            &expr_lit.lit,
+            nv.path.get_ident().map(ToString::to_string).as_deref(),
+        ) {
+            (Lit::Str(s), Some("strategy")) => match s.value().as_str() {
+                "trigram" => trigram = true,
+                "default" => trigram = false,
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        s,
+                        format!(
+                            "Unknown fulltext strategy `{other}`. Expected `\"default\"` or `\"trigram\"`"
+                        ),
+                    ));
+                }
+            },
+            (Lit::Str(s), Some("mode")) => match s.value().as_str() {
+                "boolean" => boolean_mode = true,
+                "natural" => boolean_mode = false,
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        s,
+                        format!(
+                            "Unknown fulltext mode `{other}`. Expected `\"natural\"` or `\"boolean\"`"
+                        ),
+                    ));
+                }
+            },
+            (Lit::Float(f), Some("threshold")) => {
+                let value: f64 = f.base10_parse()?;
+                if !(0.0..=1.0).contains(&value) {
+                    return Err(syn::Error::new_spanned(
+                        f,
+                        "Trigram `threshold` must be between 0.0 and 1.0",
+                    ));
+                }
+                threshold = Some(value);
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &nv.path,
+                    "Unknown `fulltext(...)` option. Expected `strategy = \"trigram\"`, `threshold = 0.3` or `mode = \"boolean\"`",
+                ));
+            }
+        }
+    }
+
+    if threshold.is_some() && !trigram {
+        return Err(syn::Error::new_spanned(
+            list,
+            "`threshold` only applies to `fulltext(strategy = \"trigram\")`",
+        ));
+    }
+
+    Ok(FulltextFieldConfig {
+        trigram_threshold: trigram.then_some(threshold.unwrap_or(DEFAULT_TRIGRAM_THRESHOLD)),
+        boolean_mode,
+    })
+}
+
+/// Parses the field-level `file_upload(storage = "...")` attribute.
+///
+/// Returns the storage backend name, or `Ok(None)` if the field is not a file upload.
+pub(crate) fn get_file_upload_storage(field: &syn::Field) -> Result<Option<String>, syn::Error> {
+    const EXPECTED: &str = "Expected `file_upload(storage = \"...\")`";
+
+    for attr in &field.attrs {
+        if attr.path().is_ident("crudcrate")
+            && let Meta::List(meta_list) = &attr.meta
+            && let Ok(metas) =
+                Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())
+        {
+            for meta in metas {
+                match meta {
+                    Meta::Path(path) if path.is_ident("file_upload") => {
+                        return Err(syn::Error::new_spanned(path, EXPECTED));
+                    }
+                    Meta::List(list) if list.path.is_ident("file_upload") => {
+                        let metas = Punctuated::<Meta, Comma>::parse_terminated
+                            .parse2(list.tokens.clone())?;
+                        let mut storage = None;
+                        for meta in metas {
+                            if let Meta::NameValue(nv) = &meta
+                                && nv.path.is_ident("storage")
+                                && let syn::Expr::Lit(syn::ExprLit {
+                                    lit: Lit::Str(s), ..
+                                }) = &nv.value
+                                && !s.value().is_empty()
+                            {
+                                storage = Some(s.value());
+                            } else {
+                                return Err(syn::Error::new_spanned(&meta, EXPECTED));
+                            }
+                        }
+                        return storage
+                            .map(Some)
+                            .ok_or_else(|| syn::Error::new_spanned(&list, EXPECTED));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use quote::quote;
+    use syn::parse_quote;
 
     // Helper to create a syn::Path from tokens
     fn make_path(tokens: proc_macro2::TokenStream) -> syn::Path {
@@ -645,6 +1423,21 @@ mod tests {
         assert_eq!(parse_hook_path(&path), None);
     }
 
+    #[test]
+    fn test_parse_hook_path_post_async_on_writes() {
+        let path = make_path(quote!(update::one::post_async));
+        assert_eq!(
+            parse_hook_path(&path),
+            Some((
+                "update".to_string(),
+                "one".to_string(),
+                "post_async".to_string()
+            ))
+        );
+        let path = make_path(quote!(read::many::post_async));
+        assert_eq!(parse_hook_path(&path), None);
+    }
+
     #[test]
     fn test_parse_hook_path_too_few_segments() {
         let path = make_path(quote!(create::one));
@@ -861,6 +1654,46 @@ mod tests {
         assert_eq!(parse_exclude_parameters(&meta_list, "create_model"), None);
     }
 
+    #[test]
+    fn test_hash_with_excludes_every_response() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(hash_with = argon2_hash, exclude(list))]
+            pub password: String
+        };
+        for key in ["list_model", "one_model", "scoped_model", "response_model"] {
+            assert_eq!(get_crudcrate_bool(&field, key), Some(false), "{key}");
+        }
+        assert_eq!(get_crudcrate_bool(&field, "create_model"), None);
+        // Hashed by `create_active_model`/`update_active_model`, not the conversions
+        assert!(get_sanitizers(&field).is_empty());
+    }
+
+    #[test]
+    fn test_list_only_excludes_single_item_responses() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, list_only, counted_relation = "Children")]
+            pub children_count: i64
+        };
+        assert_eq!(get_crudcrate_bool(&field, "one_model"), Some(false));
+        assert_eq!(get_crudcrate_bool(&field, "list_model"), None);
+    }
+
+    #[test]
+    fn test_parse_exclude_response_affects_every_response() {
+        let tokens = quote!(exclude(response));
+        let meta_list: syn::MetaList = syn::parse2(tokens).expect("Failed to parse");
+        for key in ["list_model", "one_model", "scoped_model", "response_model"] {
+            assert_eq!(
+                parse_exclude_parameters(&meta_list, key),
+                Some(true),
+                "{key}"
+            );
+        }
+        // The field is still writable
+        assert_eq!(parse_exclude_parameters(&meta_list, "create_model"), None);
+        assert_eq!(parse_exclude_parameters(&meta_list, "update_model"), None);
+    }
+
     #[test]
     fn test_parse_exclude_empty() {
         let tokens = quote!(exclude());
@@ -891,4 +1724,318 @@ mod tests {
         assert!(parse_hook_path(&make_path(quote!(create::one::body))).is_some());
         assert!(parse_hook_path(&make_path(quote!(create::one::post))).is_some());
     }
+
+    // ========================================================================
+    // fulltext(...) parsing tests
+    // ========================================================================
+
+    #[test]
+    fn test_file_upload_storage() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(file_upload(storage = "s3"))]
+            pub image: Option<String>
+        };
+        assert_eq!(
+            get_file_upload_storage(&field).unwrap().as_deref(),
+            Some("s3")
+        );
+
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(filterable)]
+            pub image: Option<String>
+        };
+        assert!(get_file_upload_storage(&field).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_file_upload_requires_storage() {
+        let fields: [syn::Field; 4] = [
+            syn::parse_quote! { #[crudcrate(file_upload)] pub image: String },
+            syn::parse_quote! { #[crudcrate(file_upload())] pub image: String },
+            syn::parse_quote! { #[crudcrate(file_upload(bucket = "s3"))] pub image: String },
+            syn::parse_quote! { #[crudcrate(file_upload(storage = ""))] pub image: String },
+        ];
+        for field in &fields {
+            assert!(get_file_upload_storage(field).is_err());
+        }
+    }
+
+    #[test]
+    fn test_fulltext_flag() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(fulltext)]
+            pub title: String
+        };
+        let config = get_fulltext_config(&field).unwrap().unwrap();
+        assert!(config.trigram_threshold.is_none());
+        assert!(!config.boolean_mode);
+    }
+
+    #[test]
+    fn test_fulltext_boolean_mode() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(fulltext(mode = "boolean"))]
+            pub body: String
+        };
+        let config = get_fulltext_config(&field).unwrap().unwrap();
+        assert!(config.boolean_mode);
+        assert!(config.trigram_threshold.is_none());
+
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(fulltext(mode = "fuzzy"))]
+            pub body: String
+        };
+        let err = get_fulltext_config(&field).unwrap_err();
+        assert!(err.to_string().contains("Unknown fulltext mode"));
+    }
+
+    #[test]
+    fn test_fulltext_absent() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(filterable)]
+            pub title: String
+        };
+        assert!(get_fulltext_config(&field).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fulltext_trigram_default_threshold() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(fulltext(strategy = "trigram"))]
+            pub name: String
+        };
+        let config = get_fulltext_config(&field).unwrap().unwrap();
+        assert_eq!(config.trigram_threshold, Some(0.3));
+    }
+
+    #[test]
+    fn test_fulltext_trigram_custom_threshold() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(fulltext(strategy = "trigram", threshold = 0.45))]
+            pub name: String
+        };
+        let config = get_fulltext_config(&field).unwrap().unwrap();
+        assert_eq!(config.trigram_threshold, Some(0.45));
+    }
+
+    #[test]
+    fn test_fulltext_unknown_strategy_errors() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(fulltext(strategy = "soundex"))]
+            pub name: String
+        };
+        let err = get_fulltext_config(&field).unwrap_err();
+        assert!(err.to_string().contains("Unknown fulltext strategy"));
+    }
+
+    #[test]
+    fn test_fulltext_threshold_out_of_range_errors() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(fulltext(strategy = "trigram", threshold = 1.5))]
+            pub name: String
+        };
+        assert!(get_fulltext_config(&field).is_err());
+    }
+
+    #[test]
+    fn test_fulltext_threshold_without_trigram_errors() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(fulltext(threshold = 0.5))]
+            pub name: String
+        };
+        assert!(get_fulltext_config(&field).is_err());
+    }
+
+    #[test]
+    fn test_struct_level_geo() {
+        let attrs: Vec<syn::Attribute> = vec![syn::parse_quote! {
+            #[crudcrate(geo(name = "location", lat = "latitude", lon = "longitude"))]
+        }];
+        let meta = parse_crud_resource_meta(&attrs);
+        assert!(meta.deprecation_errors.is_empty());
+        assert_eq!(meta.struct_level_geo.len(), 1);
+        assert_eq!(meta.struct_level_geo[0].name, "location");
+        assert_eq!(meta.struct_level_geo[0].lat, "latitude");
+        assert_eq!(meta.struct_level_geo[0].lon, "longitude");
+    }
+
+    #[test]
+    fn test_virtual_filter() {
+        let attrs: Vec<syn::Attribute> = vec![syn::parse_quote!(
+            #[crudcrate(
+                virtual_filter(name = "active", builder = filters::active, description = "Not archived"),
+                virtual_filter(name = "mine", builder = mine)
+            )]
+        )];
+        let meta = parse_crud_resource_meta(&attrs);
+        assert!(meta.deprecation_errors.is_empty());
+        assert_eq!(meta.virtual_filters.len(), 2);
+        assert_eq!(meta.virtual_filters[0].name, "active");
+        assert_eq!(
+            meta.virtual_filters[0].description.as_deref(),
+            Some("Not archived")
+        );
+        assert!(meta.virtual_filters[1].builder.is_ident("mine"));
+    }
+
+    #[test]
+    fn test_virtual_filter_errors() {
+        for attr in [
+            syn::parse_quote!(#[crudcrate(virtual_filter(name = "active"))]),
+            syn::parse_quote!(#[crudcrate(virtual_filter(name = "is-active", builder = f))]),
+            syn::parse_quote!(#[crudcrate(virtual_filter(name = "active", builder = "f"))]),
+            syn::parse_quote!(#[crudcrate(virtual_filter(name = "active", builder = f, sql = "x"))]),
+        ] {
+            let meta = parse_crud_resource_meta(&[attr]);
+            assert!(meta.virtual_filters.is_empty());
+            assert_eq!(meta.deprecation_errors.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_struct_level_geo_missing_lon_errors() {
+        let attrs: Vec<syn::Attribute> = vec![syn::parse_quote! {
+            #[crudcrate(geo(name = "location", lat = "latitude"))]
+        }];
+        let meta = parse_crud_resource_meta(&attrs);
+        assert!(meta.struct_level_geo.is_empty());
+        assert_eq!(meta.deprecation_errors.len(), 1);
+    }
+
+    #[test]
+    fn test_struct_level_tree() {
+        let attrs: Vec<syn::Attribute> = vec![syn::parse_quote! {
+            #[crudcrate(tree(parent_field = "parent_id", max_depth = 4))]
+        }];
+        let meta = parse_crud_resource_meta(&attrs);
+        assert!(meta.deprecation_errors.is_empty());
+        let tree = meta.tree.unwrap();
+        assert_eq!(tree.parent_field, "parent_id");
+        assert_eq!(tree.max_depth, Some(4));
+
+        for attr in [
+            syn::parse_quote! { #[crudcrate(tree(max_depth = 4))] },
+            syn::parse_quote! { #[crudcrate(tree(parent_field = "parent_id", max_depth = 0))] },
+            syn::parse_quote! { #[crudcrate(tree(parent = "parent_id"))] },
+        ] {
+            let meta = parse_crud_resource_meta(&[attr]);
+            assert!(meta.tree.is_none());
+            assert_eq!(meta.deprecation_errors.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_state_machine() {
+        let attrs: Vec<syn::Attribute> = vec![syn::parse_quote! {
+            #[crudcrate(state_machine(field = "status", transitions("todo -> doing", "doing -> done, todo")))]
+        }];
+        let meta = parse_crud_resource_meta(&attrs);
+        assert!(meta.deprecation_errors.is_empty());
+        let machine = meta.state_machine.unwrap();
+        assert_eq!(machine.field, "status");
+        assert_eq!(
+            machine.transitions,
+            [("todo", "doing"), ("doing", "done"), ("doing", "todo")]
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+        );
+
+        for attr in [
+            syn::parse_quote! { #[crudcrate(state_machine(transitions("a -> b")))] },
+            syn::parse_quote! { #[crudcrate(state_machine(field = "status"))] },
+            syn::parse_quote! { #[crudcrate(state_machine(field = "status", transitions("a b")))] },
+            syn::parse_quote! { #[crudcrate(state_machine(field = "status", transitions("a -> b ->")))] },
+            syn::parse_quote! { #[crudcrate(state_machine(field = "status", transitions("a ->")))] },
+        ] {
+            let meta = parse_crud_resource_meta(&[attr]);
+            assert!(meta.state_machine.is_none());
+            assert_eq!(meta.deprecation_errors.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_temporal() {
+        let attrs: Vec<syn::Attribute> = vec![syn::parse_quote! { #[crudcrate(temporal)] }];
+        let temporal = parse_crud_resource_meta(&attrs).temporal.unwrap();
+        assert_eq!(
+            (temporal.from.as_str(), temporal.to.as_str()),
+            ("valid_from", "valid_to")
+        );
+
+        let attrs: Vec<syn::Attribute> =
+            vec![syn::parse_quote! { #[crudcrate(temporal(to = "ends_at"))] }];
+        let temporal = parse_crud_resource_meta(&attrs).temporal.unwrap();
+        assert_eq!(
+            (temporal.from.as_str(), temporal.to.as_str()),
+            ("valid_from", "ends_at")
+        );
+
+        for attr in [
+            syn::parse_quote! { #[crudcrate(temporal(start = "starts_at"))] },
+            syn::parse_quote! { #[crudcrate(temporal(from = starts_at))] },
+        ] {
+            let meta = parse_crud_resource_meta(&[attr]);
+            assert!(meta.temporal.is_none());
+            assert_eq!(meta.deprecation_errors.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_get_crudcrate_strings_collects_repeated_keys() {
+        let field: syn::Field = syn::parse_quote! {
+            #[crudcrate(rename = "fullName", alias = "legacy_name", alias = "name")]
+            pub full_name: String
+        };
+        assert_eq!(get_crudcrate_strings(&field, "rename"), vec!["fullName"]);
+        assert_eq!(
+            get_crudcrate_strings(&field, "alias"),
+            vec!["legacy_name", "name"]
+        );
+        assert!(get_crudcrate_strings(&field, "description").is_empty());
+    }
+
+    #[test]
+    fn test_struct_level_rename_all() {
+        let attrs: Vec<syn::Attribute> = vec![syn::parse_quote! {
+            #[crudcrate(rename_all = "camelCase")]
+        }];
+        let meta = parse_crud_resource_meta(&attrs);
+        assert_eq!(meta.rename_all.as_deref(), Some("camelCase"));
+
+        let attrs: Vec<syn::Attribute> = vec![syn::parse_quote! {
+            #[crudcrate(rename_all = "camel")]
+        }];
+        let meta = parse_crud_resource_meta(&attrs);
+        assert!(meta.rename_all.is_none());
+        assert_eq!(meta.deprecation_errors.len(), 1);
+    }
+
+    #[test]
+    fn test_struct_level_layers_keep_order() {
+        let attrs: Vec<syn::Attribute> = vec![syn::parse_quote! {
+            #[crudcrate(layer = auth::require_admin, layer = small_bodies)]
+        }];
+        let meta = parse_crud_resource_meta(&attrs);
+        let layers: Vec<String> = meta
+            .layers
+            .iter()
+            .map(|path| quote::quote!(#path).to_string().replace(' ', ""))
+            .collect();
+        assert_eq!(layers, ["auth::require_admin", "small_bodies"]);
+    }
+
+    #[test]
+    fn test_is_async_generator() {
+        let generator: syn::Expr = syn::parse_quote!(next_invoice_number);
+        let qualified: syn::Expr = syn::parse_quote!(crate::numbering::next);
+        let call: syn::Expr = syn::parse_quote!(Uuid::new_v4());
+        let variant: syn::Expr = syn::parse_quote!(Status::Draft);
+        let constant: syn::Expr = syn::parse_quote!(DEFAULT_RANK);
+        let literal: syn::Expr = syn::parse_quote!(0);
+        assert!(is_async_generator(&generator));
+        assert!(is_async_generator(&qualified));
+        assert!(!is_async_generator(&call));
+        assert!(!is_async_generator(&variant));
+        assert!(!is_async_generator(&constant));
+        assert!(!is_async_generator(&literal));
+    }
 }
@@ -0,0 +1,621 @@
+//! Attribute key validation - compile errors for unknown `#[crudcrate(...)]` keys.
+//!
+//! The parsers in `attribute_parser` look up the keys they know and skip everything else,
+//! so a typo like `filtrable` would silently do nothing. Every key (and every option inside
+//! `exclude(...)` and `join(...)`) is checked here first. Unknown keys are reported at
+//! their own span, with a did-you-mean suggestion when a known key is close.
+
+use crate::attribute_parser::{ID_GENERATORS, SANITIZERS, parse_hook_path};
+use syn::parse::Parser;
+use syn::{Meta, punctuated::Punctuated, token::Comma};
+
+/// Keys accepted in struct-level `#[crudcrate(...)]`, besides `op::cardinality::phase` hooks.
+const STRUCT_KEYS: &[&str] = &[
+    "generate_router",
+    "api_struct",
+    "active_model",
+    "name_singular",
+    "name_plural",
+    "description",
+    "fulltext_language",
+    "tsvector_column",
+    "fts5_table",
+    "normalized_search",
+    "view_sql",
+    "rename_all",
+    "batch_limit",
+    "max_page_size",
+    "max_filter_length",
+    "max_filter_depth",
+    "max_body_size",
+    "timeout_ms",
+    "singleflight_ms",
+    "retry_attempts",
+    "max_join_queries",
+    "max_joined_rows",
+    "operations",
+    "layer",
+    "derive_partial_eq",
+    "derive_eq",
+    "no_partial_eq",
+    "no_eq",
+    "require_scope",
+    "auto_index",
+    "enum_case_sensitive",
+    "strict_query",
+    "server_timing",
+    "unstable_sort",
+    "serialize_writes",
+    "create_builder",
+    "restrict_delete",
+    "guard_delete",
+    "cascade_delete",
+    "openapi",
+    "security",
+    "join",
+    "geo",
+    "virtual_filter",
+    "tree",
+    "state_machine",
+    "temporal",
+    "dedupe_on",
+    "unique_together",
+    "sync",
+    "webhooks",
+    // Removed, kept so the parser can report the migration
+    "fn_get_one",
+    "fn_get_all",
+    "fn_create",
+    "fn_update",
+    "fn_delete",
+    "fn_delete_many",
+];
+
+/// Keys accepted in field-level `#[crudcrate(...)]`.
+const FIELD_KEYS: &[&str] = &[
+    "primary_key",
+    "filterable",
+    "sortable",
+    "fulltext",
+    "ci",
+    "collate",
+    "filter_with",
+    "sanitize",
+    "sanitize_with",
+    "hash_with",
+    "sensitive",
+    "geo",
+    "large_text",
+    "slug_from",
+    "slug_on_update",
+    "lookup",
+    "lookup_not_unique",
+    "exclude",
+    "rename",
+    "alias",
+    "on_create",
+    "on_update",
+    "id_generator",
+    "default",
+    "non_db_attr",
+    "computed",
+    "computed_many",
+    "counted_relation",
+    "counted_resource",
+    "list_only",
+    "join",
+    "polymorphic_join",
+    "file_upload",
+    "enum_field",
+    "use_target_models",
+    "description",
+    "example",
+    // Legacy spellings of `exclude(...)`
+    "create_model",
+    "update_model",
+    "list_model",
+    "one_model",
+    "response_model",
+    // Removed, kept so the join parser can report the migration
+    "join_filterable",
+    "join_sortable",
+];
+
+const EXCLUDE_KEYS: &[&str] = &[
+    "create", "update", "list", "one", "scoped", "response", "all",
+];
+
+const JOIN_KEYS: &[&str] = &[
+    "one",
+    "all",
+    "on_one",
+    "on_all",
+    "depth",
+    "relation",
+    "path",
+    "filterable",
+    "sortable",
+    "fk_column",
+    "limit",
+    "order_by",
+    "create",
+    "lazy",
+];
+
+/// Struct-level joins also name the generated field and its type
+const STRUCT_JOIN_KEYS: &[&str] = &[
+    "name",
+    "result",
+    "one",
+    "all",
+    "on_one",
+    "on_all",
+    "depth",
+    "relation",
+    "path",
+    "filterable",
+    "sortable",
+    "fk_column",
+    "limit",
+    "order_by",
+];
+
+const POLYMORPHIC_JOIN_KEYS: &[&str] = &["types", "type_column", "id_column"];
+
+const HOOK_OPERATIONS: &[&str] = &["create", "read", "update", "delete"];
+const HOOK_CARDINALITIES: &[&str] = &["one", "many"];
+const HOOK_PHASES: &[&str] = &[
+    "pre",
+    "body",
+    "transform",
+    "post",
+    "post_async",
+    "condition",
+];
+
+/// Validate the struct-level `#[crudcrate(...)]` attributes and every field's attributes.
+///
+/// All unknown keys are reported together.
+pub(crate) fn validate_attributes<'a>(
+    attrs: &[syn::Attribute],
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+) -> Result<(), syn::Error> {
+    let mut errors = Vec::new();
+    for meta in crudcrate_metas(attrs) {
+        check_struct_meta(&meta, &mut errors);
+    }
+    for field in fields {
+        let metas = crudcrate_metas(&field.attrs);
+        check_id_generator(&metas, &mut errors);
+        for meta in metas {
+            check_key(&meta, "field", FIELD_KEYS, &mut errors);
+            check_example(&meta, &mut errors);
+            if let Meta::List(list) = &meta {
+                if list.path.is_ident("exclude") {
+                    check_nested(list, "exclude(...)", EXCLUDE_KEYS, &mut errors);
+                } else if list.path.is_ident("join") {
+                    check_nested(list, "join(...)", JOIN_KEYS, &mut errors);
+                } else if list.path.is_ident("sanitize") {
+                    check_nested(list, "sanitize(...)", SANITIZERS, &mut errors);
+                } else if list.path.is_ident("polymorphic_join") {
+                    check_nested(
+                        list,
+                        "polymorphic_join(...)",
+                        POLYMORPHIC_JOIN_KEYS,
+                        &mut errors,
+                    );
+                }
+            }
+        }
+    }
+
+    let mut errors = errors.into_iter();
+    match errors.next() {
+        None => Ok(()),
+        Some(mut first) => {
+            first.extend(errors);
+            Err(first)
+        }
+    }
+}
+
+/// The items of every `#[crudcrate(...)]` attribute that parses as a meta list
+fn crudcrate_metas(attrs: &[syn::Attribute]) -> Vec<Meta> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("crudcrate"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::List(list) => Punctuated::<Meta, Comma>::parse_terminated
+                .parse2(list.tokens.clone())
+                .ok(),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+fn check_struct_meta(meta: &Meta, errors: &mut Vec<syn::Error>) {
+    let path = meta.path();
+    if path.segments.len() > 1 {
+        if parse_hook_path(path).is_none() {
+            errors.push(hook_error(path));
+        }
+        return;
+    }
+    check_key(meta, "struct", STRUCT_KEYS, errors);
+    if let Meta::List(list) = meta
+        && list.path.is_ident("join")
+    {
+        check_nested(list, "join(...)", STRUCT_JOIN_KEYS, errors);
+    }
+}
+
+fn check_key(meta: &Meta, level: &str, known: &[&str], errors: &mut Vec<syn::Error>) {
+    let path = meta.path();
+    let Some(ident) = path.get_ident() else {
+        let name = path_to_string(path);
+        errors.push(syn::Error::new_spanned(
+            path,
+            format!("Unknown crudcrate {level} attribute `{name}`"),
+        ));
+        return;
+    };
+    let name = ident.to_string();
+    if !known.contains(&name.as_str()) {
+        errors.push(unknown_key_error(
+            ident,
+            &format!("crudcrate {level} attribute"),
+            &name,
+            known,
+        ));
+    }
+}
+
+/// `example` is a string holding a JSON value, e.g. `example = "\"Buy milk\""`
+fn check_example(meta: &Meta, errors: &mut Vec<syn::Error>) {
+    let Meta::NameValue(nv) = meta else {
+        return;
+    };
+    if !nv.path.is_ident("example") {
+        return;
+    }
+    if let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(value),
+        ..
+    }) = &nv.value
+        && value.value().parse::<proc_macro2::TokenStream>().is_ok()
+    {
+        return;
+    }
+    errors.push(syn::Error::new_spanned(
+        &nv.value,
+        "`example` must be a string holding a JSON value, e.g. `example = \"\\\"Buy milk\\\"\"`",
+    ));
+}
+
+fn check_id_generator(metas: &[Meta], errors: &mut Vec<syn::Error>) {
+    let Some(nv) = metas.iter().find_map(|meta| match meta {
+        Meta::NameValue(nv) if nv.path.is_ident("id_generator") => Some(nv),
+        _ => None,
+    }) else {
+        return;
+    };
+    let known = if let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(value),
+        ..
+    }) = &nv.value
+    {
+        ID_GENERATORS.contains(&value.value().as_str())
+    } else {
+        false
+    };
+    if !known {
+        errors.push(syn::Error::new_spanned(
+            &nv.value,
+            format!(
+                "`id_generator` must be one of {}",
+                ID_GENERATORS
+                    .iter()
+                    .map(|generator| format!("\"{generator}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ));
+    }
+    if metas.iter().any(|meta| meta.path().is_ident("on_create")) {
+        errors.push(syn::Error::new_spanned(
+            nv,
+            "`id_generator` sets the value on create; remove `on_create`",
+        ));
+    }
+}
+
+fn check_nested(list: &syn::MetaList, context: &str, known: &[&str], errors: &mut Vec<syn::Error>) {
+    // Malformed lists are reported by the parser that reads them
+    let Ok(metas) = Punctuated::<Meta, Comma>::parse_terminated.parse2(list.tokens.clone()) else {
+        return;
+    };
+    for meta in metas {
+        let path = meta.path();
+        match path.get_ident() {
+            Some(ident) if known.contains(&ident.to_string().as_str()) => {}
+            Some(ident) => errors.push(unknown_key_error(
+                ident,
+                &format!("`{context}` option"),
+                &ident.to_string(),
+                known,
+            )),
+            None => errors.push(syn::Error::new_spanned(
+                path,
+                format!("Unknown `{context}` option `{}`", path_to_string(path)),
+            )),
+        }
+    }
+}
+
+fn unknown_key_error(ident: &syn::Ident, what: &str, name: &str, known: &[&str]) -> syn::Error {
+    let message = match suggest(name, known) {
+        Some(suggestion) => format!("Unknown {what} `{name}`. Did you mean `{suggestion}`?"),
+        None => format!(
+            "Unknown {what} `{name}`. Expected one of: {}",
+            known.join(", ")
+        ),
+    };
+    syn::Error::new(ident.span(), message)
+}
+
+/// Error for a `a::b::c` path that is not a valid hook, suggesting the closest valid hook
+fn hook_error(path: &syn::Path) -> syn::Error {
+    let name = path_to_string(path);
+    let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let suggestion = match segments.as_slice() {
+        [operation, cardinality, phase] => {
+            let fix = |segment: &str, known: &[&'static str]| {
+                if known.contains(&segment) {
+                    Some(segment.to_string())
+                } else {
+                    suggest(segment, known).map(ToString::to_string)
+                }
+            };
+            match (
+                fix(operation, HOOK_OPERATIONS),
+                fix(cardinality, HOOK_CARDINALITIES),
+                fix(phase, HOOK_PHASES),
+            ) {
+                (Some(o), Some(c), Some(p)) => Some(format!("{o}::{c}::{p}")),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    let message = match suggestion {
+        // Valid spellings that aren't hooks
+        Some(suggestion) if suggestion == name && name.ends_with("::condition") => format!(
+            "`{name}` is not supported: the `read::many::condition` hook applies to every \
+             read, including `get_one`"
+        ),
+        Some(suggestion) if suggestion == name => format!(
+            "`{name}` is not supported: `post_async` hooks only run after create, update \
+             and delete. Use a `post` hook for reads"
+        ),
+        Some(suggestion) => {
+            format!("Unknown crudcrate hook `{name}`. Did you mean `{suggestion}`?")
+        }
+        None => format!(
+            "Unknown crudcrate hook `{name}`. Hooks are written `operation::cardinality::phase` \
+             with operation one of {}, cardinality one of {} and phase one of {}",
+            HOOK_OPERATIONS.join(", "),
+            HOOK_CARDINALITIES.join(", "),
+            HOOK_PHASES.join(", ")
+        ),
+    };
+    syn::Error::new_spanned(path, message)
+}
+
+fn path_to_string(path: &syn::Path) -> String {
+    path.segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Closest known key within an edit distance of a third of the name (at least 1)
+fn suggest<'a>(name: &str, known: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    known
+        .iter()
+        .map(|candidate| (levenshtein(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn errors(attrs: &[syn::Attribute], fields: &[syn::Field]) -> Vec<String> {
+        match validate_attributes(attrs, fields) {
+            Ok(()) => vec![],
+            Err(e) => e.into_iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("filterable", "filterable"), 0);
+        assert_eq!(levenshtein("filtrable", "filterable"), 1);
+        assert_eq!(levenshtein("sortabel", "sortable"), 2);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggest_closest_key() {
+        assert_eq!(suggest("filtrable", FIELD_KEYS), Some("filterable"));
+        assert_eq!(suggest("primarykey", FIELD_KEYS), Some("primary_key"));
+        assert_eq!(
+            suggest("generate_routes", STRUCT_KEYS),
+            Some("generate_router")
+        );
+        assert_eq!(suggest("banana", FIELD_KEYS), None);
+    }
+
+    #[test]
+    fn test_known_attributes_pass() {
+        let attrs: Vec<syn::Attribute> = vec![
+            parse_quote!(#[crudcrate(generate_router, api_struct = "Post", batch_limit = 10)]),
+            parse_quote!(#[crudcrate(create::one::pre = validate, layer = my_layer)]),
+            parse_quote!(#[crudcrate(join(name = "tags", result = "Vec<Tag>", one, depth = 1))]),
+            parse_quote!(#[sea_orm(table_name = "posts")]),
+        ];
+        let fields: Vec<syn::Field> = vec![
+            parse_quote!(#[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())] pub id: Uuid),
+            parse_quote!(#[crudcrate(filterable, sortable, fulltext(strategy = "trigram"))] pub title: String),
+            parse_quote!(#[crudcrate(non_db_attr, join(one, all, depth = 1, filterable("make")))] pub cars: Vec<Car>),
+        ];
+        assert!(errors(&attrs, &fields).is_empty());
+    }
+
+    #[test]
+    fn test_example_must_be_json_string() {
+        let fields: Vec<syn::Field> = vec![
+            parse_quote!(#[crudcrate(example = "\"Buy milk\"", description = "Title")] pub title: String),
+            parse_quote!(#[crudcrate(example = "{\"lat\": 46.5, \"tags\": [\"a\"]}")] pub meta: Json),
+        ];
+        assert!(errors(&[], &fields).is_empty());
+
+        let fields: Vec<syn::Field> = vec![
+            parse_quote!(#[crudcrate(example = 3)] pub count: i32),
+            parse_quote!(#[crudcrate(example = "[1, 2")] pub ids: Vec<i32>),
+        ];
+        let errors = errors(&[], &fields);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("`example` must be a string holding a JSON value"));
+    }
+
+    #[test]
+    fn test_id_generator_checked() {
+        let fields: Vec<syn::Field> = vec![
+            parse_quote!(#[crudcrate(primary_key, id_generator = "uuid_v7")] pub id: Uuid),
+            parse_quote!(#[crudcrate(id_generator = "ksuid")] pub public_id: String),
+        ];
+        assert!(errors(&[], &fields).is_empty());
+
+        let fields: Vec<syn::Field> = vec![
+            parse_quote!(#[crudcrate(id_generator = "snowflake")] pub id: i64),
+            parse_quote!(#[crudcrate(id_generator = "uuid_v7", on_create = Uuid::new_v4())] pub id: Uuid),
+        ];
+        assert_eq!(
+            errors(&[], &fields),
+            [
+                "`id_generator` must be one of \"uuid_v4\", \"uuid_v7\", \"ulid\", \"ksuid\"",
+                "`id_generator` sets the value on create; remove `on_create`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_field_key_suggests() {
+        let fields: Vec<syn::Field> = vec![parse_quote!(#[crudcrate(filtrable)] pub title: String)];
+        assert_eq!(
+            errors(&[], &fields),
+            ["Unknown crudcrate field attribute `filtrable`. Did you mean `filterable`?"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_key_without_suggestion_lists_keys() {
+        let attrs: Vec<syn::Attribute> = vec![parse_quote!(#[crudcrate(banana = 1)])];
+        let errors = errors(&attrs, &[]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with(
+            "Unknown crudcrate struct attribute `banana`. Expected one of: generate_router"
+        ));
+    }
+
+    #[test]
+    fn test_nested_options_checked() {
+        let fields: Vec<syn::Field> = vec![
+            parse_quote!(#[crudcrate(exclude(craete))] pub a: String),
+            parse_quote!(#[crudcrate(non_db_attr, join(one, dept = 1))] pub b: Vec<B>),
+            parse_quote!(#[crudcrate(sanitize(trim, lowercse))] pub c: String),
+        ];
+        assert_eq!(
+            errors(&[], &fields),
+            [
+                "Unknown `exclude(...)` option `craete`. Did you mean `create`?",
+                "Unknown `join(...)` option `dept`. Did you mean `depth`?",
+                "Unknown `sanitize(...)` option `lowercse`. Did you mean `lowercase`?",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_errors_reported() {
+        let attrs: Vec<syn::Attribute> = vec![parse_quote!(#[crudcrate(generate_routes)])];
+        let fields: Vec<syn::Field> =
+            vec![parse_quote!(#[crudcrate(sortabel, filtrable)] pub a: String)];
+        assert_eq!(errors(&attrs, &fields).len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_hook_suggests() {
+        let attrs: Vec<syn::Attribute> =
+            vec![parse_quote!(#[crudcrate(create::ones::pre = check)])];
+        assert_eq!(
+            errors(&attrs, &[]),
+            ["Unknown crudcrate hook `create::ones::pre`. Did you mean `create::one::pre`?"]
+        );
+
+        let attrs: Vec<syn::Attribute> = vec![parse_quote!(#[crudcrate(get::one::pre = check)])];
+        let errors = errors(&attrs, &[]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("operation one of create, read, update, delete"));
+    }
+
+    #[test]
+    fn test_post_async_hook_only_on_writes() {
+        let attrs: Vec<syn::Attribute> = vec![
+            parse_quote!(#[crudcrate(delete::many::post_async = notify)]),
+            parse_quote!(#[crudcrate(read::one::post_async = notify)]),
+        ];
+        assert_eq!(
+            errors(&attrs, &[]),
+            [
+                "`read::one::post_async` is not supported: `post_async` hooks only run after \
+                 create, update and delete. Use a `post` hook for reads"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_condition_hook_only_on_read_many() {
+        let attrs: Vec<syn::Attribute> = vec![
+            parse_quote!(#[crudcrate(read::many::condition = published)]),
+            parse_quote!(#[crudcrate(read::one::condition = published)]),
+        ];
+        assert_eq!(
+            errors(&attrs, &[]),
+            [
+                "`read::one::condition` is not supported: the `read::many::condition` hook \
+                 applies to every read, including `get_one`"
+            ]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Test data factory generation (`testing` feature)
+//!
+//! Generates `<Name>Factory`, a builder over the create model whose unset fields fall
+//! back to per-type defaults, plus `create(db)`/`create_batch(db, n)` helpers that go
+//! through `CRUDResource::create` so `on_create` expressions and hooks apply.
+
+use crate::attribute_parser::{get_crudcrate_bool, get_crudcrate_expr};
+use crate::codegen::models::create::nested_create_type;
+use crate::codegen::models::shared::{resolve_dtwtz, resolve_field_type_with_target_models};
+use crate::codegen::models::should_include_in_model;
+use crate::fields::field_is_optional;
+use crate::traits::crudresource::structs::EntityFieldAnalysis;
+use quote::{format_ident, quote};
+
+const INTEGER_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize",
+];
+
+/// Types whose `Default` value is a usable placeholder in test data
+const DEFAULT_TYPES: &[&str] = &[
+    "bool",
+    "f32",
+    "f64",
+    "Decimal",
+    "Value",
+    "Json",
+    "Vec",
+    "DateTime",
+    "DateTimeUtc",
+    "DateTimeLocal",
+    "DateTimeWithTimeZone",
+    "NaiveDateTime",
+    "NaiveDate",
+    "NaiveTime",
+];
+
+/// How the factory fills a field the test didn't set
+enum Fallback {
+    /// Leave it to the create model (`None` applies `on_create`)
+    PassThrough,
+    /// A placeholder value; `uses_seq` if it depends on the sequence number
+    Value {
+        expr: proc_macro2::TokenStream,
+        uses_seq: bool,
+    },
+    /// No sensible placeholder: `build()` fails until the test sets it
+    Required,
+}
+
+fn last_segment(ty: &syn::Type) -> Option<String> {
+    if let syn::Type::Path(type_path) = ty {
+        type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+    } else {
+        None
+    }
+}
+
+fn placeholder(field: &syn::Field) -> Fallback {
+    let name = field
+        .ident
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let ty = &field.ty;
+    let Some(type_name) = last_segment(ty) else {
+        return Fallback::Required;
+    };
+
+    if type_name == "Option" {
+        Fallback::Value {
+            expr: quote! { None },
+            uses_seq: false,
+        }
+    } else if type_name == "String" {
+        let template = format!("{name} {{seq}}");
+        Fallback::Value {
+            expr: quote! { format!(#template) },
+            uses_seq: true,
+        }
+    } else if type_name == "Uuid" {
+        Fallback::Value {
+            expr: quote! { uuid::Uuid::from_u128(u128::from(seq)) },
+            uses_seq: true,
+        }
+    } else if INTEGER_TYPES.contains(&type_name.as_str()) {
+        Fallback::Value {
+            expr: quote! { <#ty as ::core::convert::TryFrom<_>>::try_from(seq).unwrap_or_default() },
+            uses_seq: true,
+        }
+    } else if matches!(
+        type_name.as_str(),
+        "OffsetDateTime" | "TimeDateTimeWithTimeZone"
+    ) {
+        // `time` types have no `Default`
+        Fallback::Value {
+            expr: quote! { <#ty>::UNIX_EPOCH },
+            uses_seq: false,
+        }
+    } else if matches!(
+        type_name.as_str(),
+        "PrimitiveDateTime" | "TimeDateTime" | "TimeDate"
+    ) {
+        Fallback::Value {
+            expr: quote! { <#ty>::MIN },
+            uses_seq: false,
+        }
+    } else if DEFAULT_TYPES.contains(&type_name.as_str()) {
+        Fallback::Value {
+            expr: quote! { Default::default() },
+            uses_seq: false,
+        }
+    } else {
+        Fallback::Required
+    }
+}
+
+/// Generate `<Name>Factory` and `<Name>::factory()`
+#[allow(clippy::too_many_lines)]
+pub(crate) fn generate_factory(
+    api_struct_name: &syn::Ident,
+    analysis: &EntityFieldAnalysis,
+) -> proc_macro2::TokenStream {
+    let factory_name = format_ident!("{}Factory", api_struct_name);
+    let create_name = format_ident!("{}Create", api_struct_name);
+    let factory_str = factory_name.to_string();
+
+    let mut factory_fields = Vec::new();
+    let mut setters = Vec::new();
+    let mut assignments = Vec::new();
+    let mut uses_seq = false;
+
+    let create_fields = analysis
+        .db_fields
+        .iter()
+        .chain(analysis.non_db_fields.iter())
+        .filter(|field| should_include_in_model(field, "create_model"));
+
+    for field in create_fields {
+        let Some(ident) = field.ident.as_ref() else {
+            continue;
+        };
+        let name = ident.to_string();
+        let is_non_db = get_crudcrate_bool(field, "non_db_attr").unwrap_or(false);
+        let ty = if let Some(nested_ty) = nested_create_type(field) {
+            nested_ty
+        } else if is_non_db {
+            resolve_field_type_with_target_models(&field.ty, field, |create, _, _| create.clone())
+        } else {
+            resolve_dtwtz(&field.ty)
+        };
+
+        let fallback = if !is_non_db && get_crudcrate_expr(field, "on_create").is_some() {
+            Fallback::PassThrough
+        } else if let Some(default) = get_crudcrate_expr(field, "default").filter(|_| is_non_db) {
+            Fallback::Value {
+                expr: quote! { #default },
+                uses_seq: false,
+            }
+        } else if is_non_db && !field_is_optional(field) {
+            Fallback::Required
+        } else {
+            placeholder(field)
+        };
+
+        factory_fields.push(quote! { #ident: Option<#ty> });
+        let doc = format!("Set `{name}`");
+        setters.push(quote! {
+            #[doc = #doc]
+            #[must_use]
+            pub fn #ident(mut self, value: impl Into<#ty>) -> Self {
+                self.#ident = Some(value.into());
+                self
+            }
+        });
+        assignments.push(match fallback {
+            Fallback::PassThrough => quote! { #ident: self.#ident },
+            Fallback::Value {
+                expr,
+                uses_seq: field_uses_seq,
+            } => {
+                uses_seq |= field_uses_seq;
+                quote! { #ident: self.#ident.unwrap_or_else(|| #expr) }
+            }
+            Fallback::Required => {
+                let message = format!("{factory_str}: set `{name}` before building");
+                quote! {
+                    #ident: self.#ident.ok_or_else(|| crudcrate::ApiError::bad_request(#message))?
+                }
+            }
+        });
+    }
+
+    let (seq_fn, seq_binding) = if uses_seq {
+        (
+            quote! {
+                fn next_seq() -> u64 {
+                    static SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                    SEQ.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1
+                }
+            },
+            quote! { let seq = Self::next_seq(); },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let struct_doc = format!(
+        "Test data factory for [`{api_struct_name}`].\n\nUnset fields get placeholder values: numbered strings, integers and UUIDs, `None` for optional fields, and `Default` for booleans, floats, decimals, JSON and dates. Fields with `on_create` use their expression. Fields of other types must be set before building."
+    );
+
+    quote! {
+        #[doc = #struct_doc]
+        #[derive(Clone, Default)]
+        pub struct #factory_name {
+            #(#factory_fields),*
+        }
+
+        impl #factory_name {
+            #(#setters)*
+
+            #seq_fn
+
+            /// Build the create model without inserting it
+            ///
+            /// # Errors
+            /// Returns `ApiError::BadRequest` if a field without a placeholder is unset.
+            pub fn build(self) -> Result<#create_name, crudcrate::ApiError> {
+                #seq_binding
+                Ok(#create_name {
+                    #(#assignments),*
+                })
+            }
+
+            /// Insert one record through `CRUDResource::create`
+            ///
+            /// # Errors
+            /// Returns `ApiError` if building fails or the insert is rejected.
+            pub async fn create(self, db: &sea_orm::DatabaseConnection) -> Result<#api_struct_name, crudcrate::ApiError> {
+                <#api_struct_name as crudcrate::traits::CRUDResource>::create(db, self.build()?).await
+            }
+
+            /// Insert `count` records, each with fresh placeholder values
+            ///
+            /// # Errors
+            /// Returns `ApiError` if building fails or an insert is rejected.
+            pub async fn create_batch(self, db: &sea_orm::DatabaseConnection, count: usize) -> Result<Vec<#api_struct_name>, crudcrate::ApiError> {
+                let mut created = Vec::with_capacity(count);
+                for _ in 0..count {
+                    created.push(self.clone().create(db).await?);
+                }
+                Ok(created)
+            }
+        }
+
+        impl #api_struct_name {
+            /// Start building test data for this resource
+            #[must_use]
+            pub fn factory() -> #factory_name {
+                #factory_name::default()
+            }
+        }
+    }
+}
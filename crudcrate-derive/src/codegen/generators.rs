@@ -0,0 +1,259 @@
+//! Async `on_create`/`on_update` generator functions
+//!
+//! `on_create = next_invoice_number` names an `async fn(&impl ConnectionTrait, &CreateModel)
+//! -> Result<T, ApiError>` instead of giving an expression. The create model's `From`
+//! conversion can't await it, so it leaves the column `NotSet` and these
+//! `create_active_model()`/`update_active_model()` overrides fill it in.
+//!
+//! `hash_with = argon2_hash` names an `async fn(String) -> Result<String, ApiError>` the
+//! same overrides await on the sanitized value the conversion set.
+//!
+//! The same overrides check the type/ID pairs of `polymorphic_join` fields and the
+//! `unique_together` constraints, which also need the database, fill in `slug_from`
+//! fields, and updates check the `state_machine` transitions against the stored row.
+
+use crate::attribute_parser::{get_crudcrate_bool, get_crudcrate_expr, is_async_generator};
+use crate::codegen::joins::polymorphic::generate_polymorphic_checks;
+use crate::codegen::models::shared::generate_active_value_assignment;
+use crate::fields::field_is_optional;
+use crate::traits::crudresource::structs::EntityFieldAnalysis;
+use heck::ToPascalCase;
+use quote::{format_ident, quote};
+
+/// The field's `key` value if it names an async generator
+fn async_generator(field: &syn::Field, key: &str) -> Option<syn::Expr> {
+    get_crudcrate_expr(field, key).filter(is_async_generator)
+}
+
+/// Assignments of a `normalized_search` column in `create_active_model` and
+/// `update_active_model`: the folded text of the fulltext fields, computed from the
+/// merged values so it follows every other write. Updates read unsent fields from
+/// `original`.
+fn generate_normalize(
+    analysis: &EntityFieldAnalysis,
+    column: &str,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let column = format_ident!("{}", column);
+    let sources: Vec<_> = analysis
+        .fulltext_fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .filter(|ident| **ident != column)
+        .collect();
+    let create = quote! {
+        model.#column = sea_orm::ActiveValue::Set(crudcrate::normalize::search_text([
+            #(crudcrate::normalize::active_text(&model.#sources)),*
+        ]));
+    };
+    let update = quote! {
+        model.#column = sea_orm::ActiveValue::Set(crudcrate::normalize::search_text([
+            #(crudcrate::normalize::active_text(if model.#sources.is_not_set() {
+                &original.#sources
+            } else {
+                &model.#sources
+            })),*
+        ]));
+    };
+    (create, update)
+}
+
+/// Generate `create_active_model()` and `update_active_model()` overrides for fields
+/// with async generators or `hash_with`, `polymorphic_join` fields, `unique_together` constraints,
+/// the `slug_from` field, the `state_machine` field and the `normalized_search`
+/// column, or nothing if there are none
+#[allow(clippy::too_many_lines)]
+pub(crate) fn generate_async_generator_impls(
+    analysis: &EntityFieldAnalysis,
+    unique_together: bool,
+    state_field: Option<&syn::Ident>,
+    normalized_search: Option<&str>,
+) -> proc_macro2::TokenStream {
+    let value: syn::Expr = syn::parse_quote!(value);
+
+    let mut create_calls = Vec::new();
+    let mut create_assignments = Vec::new();
+    let mut update_calls = Vec::new();
+    let mut update_assignments = Vec::new();
+    let mut create_hashes = Vec::new();
+    let mut update_hashes = Vec::new();
+
+    for field in &analysis.db_fields {
+        let Some(ident) = field.ident.as_ref() else {
+            continue;
+        };
+        let generated = format_ident!("{}_generated", ident);
+        let assignment = generate_active_value_assignment(ident, &value, field_is_optional(field));
+
+        if let Some(generator) = async_generator(field, "on_create") {
+            // Fields in the create model only run the generator when the client left them out
+            if get_crudcrate_bool(field, "create_model").unwrap_or(true) {
+                create_calls.push(quote! {
+                    let #generated = if data.#ident.is_none() {
+                        Some(#generator(db, &data).await?)
+                    } else {
+                        None
+                    };
+                });
+            } else {
+                create_calls.push(quote! {
+                    let #generated = Some(#generator(db, &data).await?);
+                });
+            }
+            create_assignments.push(quote! {
+                if let Some(value) = #generated {
+                    #assignment
+                }
+            });
+        }
+
+        // Only values the request set are hashed: `null` and unsent fields are left alone
+        if let Some(function) = get_crudcrate_expr(field, "hash_with") {
+            let pattern = if field_is_optional(field) {
+                quote! { Some(value) }
+            } else {
+                quote! { value }
+            };
+            let hash = quote! {
+                if let sea_orm::ActiveValue::Set(#pattern) = &model.#ident {
+                    let value = #function(value.clone()).await?;
+                    #assignment
+                }
+            };
+            create_hashes.push(hash.clone());
+            update_hashes.push(hash);
+        }
+
+        // Like `on_update` expressions, generators apply to fields outside the update model
+        if let Some(generator) = async_generator(field, "on_update")
+            && get_crudcrate_bool(field, "update_model") == Some(false)
+        {
+            update_calls.push(quote! {
+                let #generated = #generator(db, &data).await?;
+            });
+            update_assignments.push(quote! {
+                let value = #generated;
+                #assignment
+            });
+        }
+    }
+
+    let create_checks = generate_polymorphic_checks(analysis, &quote! { model });
+    let update_checks = generate_polymorphic_checks(analysis, &quote! { original });
+    let has_checks = !create_checks.is_empty();
+    let create_unique_check = unique_together.then(|| {
+        quote! { crudcrate::core::unique::ensure_unique::<Self, _>(db, &model, None).await?; }
+    });
+    let update_unique_check = unique_together.then(|| {
+        quote! { crudcrate::core::unique::ensure_unique::<Self, _>(db, &model, Some(&original)).await?; }
+    });
+
+    let (create_normalize, update_normalize) = normalized_search
+        .map(|column| generate_normalize(analysis, column))
+        .unzip();
+
+    // Before the unique checks, which may cover the slug
+    let (create_slug, update_slug) = analysis
+        .slug_field
+        .as_ref()
+        .and_then(|slug| {
+            let column = format_ident!("{}", slug.field.ident.as_ref()?.to_string().to_pascal_case());
+            let source = format_ident!("{}", slug.source.to_string().to_pascal_case());
+            let regenerate = slug.on_update;
+            Some((
+                quote! {
+                    crudcrate::core::slug::assign_slug::<Self, _>(
+                        db, &mut model, None, Self::ColumnType::#column, Self::ColumnType::#source, false,
+                    )
+                    .await?;
+                },
+                quote! {
+                    crudcrate::core::slug::assign_slug::<Self, _>(
+                        db, &mut model, Some(&original), Self::ColumnType::#column, Self::ColumnType::#source, #regenerate,
+                    )
+                    .await?;
+                },
+            ))
+        })
+        .unzip();
+    let slug = create_slug.is_some();
+
+    let create_impl = (!create_calls.is_empty()
+        || !create_hashes.is_empty()
+        || has_checks
+        || unique_together
+        || normalized_search.is_some()
+        || slug)
+        .then(|| {
+            let model_mut = (!create_assignments.is_empty()
+                || !create_hashes.is_empty()
+                || normalized_search.is_some()
+                || slug)
+                .then(|| quote! { mut });
+            quote! {
+                async fn create_active_model<C: sea_orm::ConnectionTrait>(
+                    db: &C,
+                    data: Self::CreateModel,
+                ) -> Result<Self::ActiveModelType, crudcrate::ApiError> {
+                    #(#create_calls)*
+                    let #model_mut model: Self::ActiveModelType = data.into();
+                    #(#create_assignments)*
+                    #(#create_hashes)*
+                    #create_normalize
+                    #create_slug
+                    #(#create_checks)*
+                    #create_unique_check
+                    Ok(model)
+                }
+            }
+        });
+
+    let state_check = state_field.map(|field| {
+        quote! { crudcrate::state_machine::check_update::<Self, _>(&original.#field, &model.#field)?; }
+    });
+
+    let update_impl = (!update_calls.is_empty()
+        || !update_hashes.is_empty()
+        || has_checks
+        || unique_together
+        || state_check.is_some()
+        || normalized_search.is_some()
+        || slug)
+    .then(|| {
+        let model_mut = (!update_assignments.is_empty()
+            || !update_hashes.is_empty()
+            || normalized_search.is_some()
+            || slug)
+            .then(|| quote! { mut });
+        // The update model leaves unsent columns `NotSet`; checks read them from here
+        let original = (has_checks
+            || unique_together
+            || state_check.is_some()
+            || normalized_search.is_some()
+            || slug)
+            .then(|| quote! { let original = existing.clone(); });
+        quote! {
+            async fn update_active_model<C: sea_orm::ConnectionTrait>(
+                db: &C,
+                data: Self::UpdateModel,
+                existing: Self::ActiveModelType,
+            ) -> Result<Self::ActiveModelType, crudcrate::ApiError> {
+                #(#update_calls)*
+                #original
+                let #model_mut model = crudcrate::traits::MergeIntoActiveModel::merge_into_activemodel(data, existing)?;
+                #(#update_assignments)*
+                #(#update_hashes)*
+                #update_normalize
+                #update_slug
+                #(#update_checks)*
+                #update_unique_check
+                #state_check
+                Ok(model)
+            }
+        }
+    });
+
+    quote! {
+        #create_impl
+        #update_impl
+    }
+}
@@ -1,5 +1,8 @@
 // join_generators functionality consolidated into this file to avoid duplicate/stub implementations
-use crate::traits::crudresource::structs::CRUDResourceMeta;
+use crate::codegen::handlers::retry::with_retry;
+use crate::codegen::handlers::webhooks::record_changes;
+use crate::codegen::joins::nested_create::generate_nested_creates;
+use crate::traits::crudresource::structs::{CRUDResourceMeta, EntityFieldAnalysis};
 use quote::quote;
 
 /// Generate create method implementation with hook support.
@@ -9,7 +12,17 @@ use quote::quote;
 /// - `create::one::body`: Replaces default create logic (receives `CreateModel`, returns `Self`)
 /// - `create::one::transform`: Modify the result (receives `Self`, returns `Self`)
 /// - `create::one::post`: Side effects after create (receives `&Self`)
-pub fn generate_create_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenStream {
+/// - `create::one::post_async`: Background job after create (receives `Self`)
+///
+/// With `join(create)` fields the default body inserts the parent and its nested
+/// related rows in one transaction. The default body's insert runs under
+/// `Self::retry_policy()`; the row is read back once it is committed, outside the
+/// retries, so a failed read can't insert it again.
+pub fn generate_create_impl(
+    crud_meta: &CRUDResourceMeta,
+    analysis: &EntityFieldAnalysis,
+    api_struct_name: &syn::Ident,
+) -> proc_macro2::TokenStream {
     // If operations is specified, use it (takes full control)
     if let Some(ops_path) = &crud_meta.operations {
         return quote! {
@@ -31,11 +44,35 @@ pub fn generate_create_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
     // Generate body - either custom or default
     let body = if let Some(fn_path) = &hooks.body {
         quote! { let result = #fn_path(db, data).await?; }
+    } else if let Some(nested) = generate_nested_creates(analysis, api_struct_name) {
+        let takes = &nested.takes;
+        let inserts = &nested.inserts;
+        let default = quote! {
+            use sea_orm::TransactionTrait;
+
+            let mut create_model = data;
+            #(#takes)*
+
+            let txn = db.begin().await?;
+            let active_model = Self::create_active_model(&txn, create_model).await?;
+            let parent_id = Self::EntityType::insert(active_model).exec(&txn).await?.last_insert_id;
+            #(#inserts)*
+            txn.commit().await?;
+        };
+        let insert = with_retry("create", &["data"], &default, &["parent_id"]);
+        quote! {
+            #insert
+            let result = Self::get_one(db, parent_id.into()).await?;
+        }
     } else {
+        let default = quote! {
+            let active_model = Self::create_active_model(db, data).await?;
+            let inserted_id = Self::EntityType::insert(active_model).exec(db).await?.last_insert_id;
+        };
+        let insert = with_retry("create", &["data"], &default, &["inserted_id"]);
         quote! {
-            let active_model: Self::ActiveModelType = data.into();
-            let insert_result = Self::EntityType::insert(active_model).exec(db).await?;
-            let result = Self::get_one(db, insert_result.last_insert_id.into()).await?;
+            #insert
+            let result = Self::get_one(db, inserted_id.into()).await?;
         }
     };
 
@@ -49,12 +86,32 @@ pub fn generate_create_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
         quote! { #fn_path(db, &result).await?; }
     });
 
+    let pk_ident = primary_key_ident(analysis);
+    let webhook = record_changes(
+        crud_meta,
+        "Created",
+        &quote! { std::iter::once((result.#pk_ident, &result)) },
+    );
+
+    // Generate post_async hook job (runs after the response, on owned copies)
+    let post_async_hook = hooks.post_async.as_ref().map(|fn_path| {
+        quote! {
+            crudcrate::jobs::enqueue(crudcrate::jobs::Job::new(
+                Self::RESOURCE_NAME_SINGULAR,
+                "create",
+                #fn_path(db.clone(), result.clone()),
+            ));
+        }
+    });
+
     quote! {
         async fn create(db: &sea_orm::DatabaseConnection, data: Self::CreateModel) -> Result<Self, crudcrate::ApiError> {
             #pre_hook
             #body
             #transform_hook
             #post_hook
+            #webhook
+            #post_async_hook
             Ok(result)
         }
     }
@@ -67,10 +124,15 @@ pub fn generate_create_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
 /// - `create::many::body`: Replaces default create logic (receives `Vec<CreateModel>`, returns `Vec<Self>`)
 /// - `create::many::transform`: Modify the results (receives `Vec<Self>`, returns `Vec<Self>`)
 /// - `create::many::post`: Side effects after batch create (receives `&[Self]`)
+/// - `create::many::post_async`: Background job after batch create (receives `Vec<Self>`)
 ///
 /// **Security Note**: The default implementation limits batch creates to 100 items to prevent
-/// `DoS` attacks via resource exhaustion.
-pub fn generate_create_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenStream {
+/// `DoS` attacks via resource exhaustion. It runs under `Self::retry_policy()`.
+pub fn generate_create_many_impl(
+    crud_meta: &CRUDResourceMeta,
+    analysis: &EntityFieldAnalysis,
+    api_struct_name: &syn::Ident,
+) -> proc_macro2::TokenStream {
     // If operations is specified, use it (takes full control)
     if let Some(ops_path) = &crud_meta.operations {
         return quote! {
@@ -89,11 +151,30 @@ pub fn generate_create_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
         quote! { #fn_path(db, &data).await?; }
     });
 
+    // `join(create)` rows are inserted after each parent
+    let (create_model_mut, nested_takes, nested_inserts) =
+        match generate_nested_creates(analysis, api_struct_name) {
+            Some(nested) => {
+                let pk_ident = primary_key_ident(analysis);
+                let takes = nested.takes;
+                let inserts = nested.inserts;
+                (
+                    quote! { mut },
+                    quote! { #(#takes)* },
+                    quote! {
+                        let parent_id = model.#pk_ident;
+                        #(#inserts)*
+                    },
+                )
+            }
+            None => (quote! {}, quote! {}, quote! {}),
+        };
+
     // Generate body - either custom or default
     let body = if let Some(fn_path) = &hooks.body {
         quote! { let result = #fn_path(db, data).await?; }
     } else {
-        quote! {
+        let default = quote! {
             use sea_orm::{ActiveModelTrait, TransactionTrait};
 
             if data.is_empty() {
@@ -111,14 +192,17 @@ pub fn generate_create_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
             let txn = db.begin().await?;
 
             let mut result = Vec::with_capacity(data.len());
-            for create_model in data {
-                let active_model: Self::ActiveModelType = create_model.into();
+            for #create_model_mut create_model in data {
+                #nested_takes
+                let active_model = Self::create_active_model(&txn, create_model).await?;
                 let model = active_model.insert(&txn).await?;
+                #nested_inserts
                 result.push(Self::from(model));
             }
 
             txn.commit().await?;
-        }
+        };
+        with_retry("create", &["data"], &default, &["result"])
     };
 
     // Generate transform hook call (modifies the results)
@@ -131,13 +215,42 @@ pub fn generate_create_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
         quote! { #fn_path(db, &result).await?; }
     });
 
+    let pk_ident = primary_key_ident(analysis);
+    let webhook = record_changes(
+        crud_meta,
+        "Created",
+        &quote! { result.iter().map(|item| (item.#pk_ident, item)) },
+    );
+
+    // Generate post_async hook job (runs after the response, on owned copies)
+    let post_async_hook = hooks.post_async.as_ref().map(|fn_path| {
+        quote! {
+            crudcrate::jobs::enqueue(crudcrate::jobs::Job::new(
+                Self::RESOURCE_NAME_SINGULAR,
+                "create",
+                #fn_path(db.clone(), result.clone()),
+            ));
+        }
+    });
+
     quote! {
         async fn create_many(db: &sea_orm::DatabaseConnection, data: Vec<Self::CreateModel>) -> Result<Vec<Self>, crudcrate::ApiError> {
             #pre_hook
             #body
             #transform_hook
             #post_hook
+            #webhook
+            #post_async_hook
             Ok(result)
         }
     }
 }
+
+/// Field holding the primary key on `Self`
+fn primary_key_ident(analysis: &EntityFieldAnalysis) -> syn::Ident {
+    analysis
+        .primary_key_field
+        .and_then(|f| f.ident.as_ref())
+        .cloned()
+        .unwrap_or_else(|| quote::format_ident!("id"))
+}
@@ -1,4 +1,6 @@
 // join_generators functionality consolidated into this file to avoid duplicate/stub implementations
+use crate::codegen::handlers::retry::with_retry;
+use crate::codegen::handlers::webhooks::record_deletes;
 use crate::traits::crudresource::structs::CRUDResourceMeta;
 use quote::quote;
 
@@ -9,7 +11,19 @@ use quote::quote;
 /// - `delete::one::body`: Replaces default delete logic (receives id, returns `Uuid`)
 /// - `delete::one::transform`: Modify the result (receives `Uuid`, returns `Uuid`)
 /// - `delete::one::post`: Side effects after delete (receives deleted id)
-pub fn generate_delete_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenStream {
+/// - `delete::one::post_async`: Background job after delete (receives deleted id)
+///
+/// With `file_upload` fields, the default body also deletes the record's stored files.
+/// With `restrict_delete` or `guard_delete`, dependent and locked rows are checked after
+/// the pre hook: by the default body in its transaction, whose `DELETE` also skips rows
+/// locked since, or else before the body hook.
+/// With `cascade_delete`, the default body deletes the record's children first, in the
+/// same transaction. With `sync`, it records the record's tombstone in that transaction.
+/// The default body runs under `Self::retry_policy()`.
+pub fn generate_delete_impl(
+    crud_meta: &CRUDResourceMeta,
+    has_file_uploads: bool,
+) -> proc_macro2::TokenStream {
     // If operations is specified, use it (takes full control)
     if let Some(ops_path) = &crud_meta.operations {
         return quote! {
@@ -28,22 +42,55 @@ pub fn generate_delete_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
         quote! { #fn_path(db, id).await?; }
     });
 
+    let restrict = (hooks.body.is_some()
+        && (crud_meta.restrict_delete || crud_meta.guard_delete.is_some()))
+    .then(|| {
+        quote! { <Self as crudcrate::CRUDResource>::ensure_deletable(db, &[id]).await?; }
+    });
+
     // Generate body - either custom or default
     let body = if let Some(fn_path) = &hooks.body {
         quote! { let result = #fn_path(db, id).await?; }
     } else {
-        quote! {
-            use sea_orm::EntityTrait;
+        let load_files = has_file_uploads.then(|| {
+            quote! {
+                let stored_files = match Self::EntityType::find_by_id(id).one(db).await? {
+                    Some(model) => crudcrate::CRUDResource::stored_files(&Self::from(model)),
+                    None => vec![],
+                };
+            }
+        });
+        let discard_files = has_file_uploads.then(|| {
+            quote! { crudcrate::storage::discard(&stored_files).await; }
+        });
+        let (begin, conn, commit) = delete_transaction(crud_meta, &quote! { &[id] });
+        // A row locked since the check is kept by the `DELETE`: report it as locked
+        let recheck = crud_meta.guard_delete.is_some().then(|| {
+            quote! { <Self as crudcrate::CRUDResource>::ensure_deletable(#conn, &[id]).await?; }
+        });
+        let default = quote! {
+            use sea_orm::{EntityTrait, QueryFilter};
 
-            let res = Self::EntityType::delete_by_id(id).exec(db).await?;
+            #load_files
+            #begin
+            let res = Self::EntityType::delete_by_id(id)
+                .filter(sea_orm::Condition::all().add_option(Self::delete_condition()))
+                .exec(#conn)
+                .await?;
             let result = match res.rows_affected {
-                0 => return Err(crudcrate::ApiError::not_found(
-                    Self::RESOURCE_NAME_SINGULAR,
-                    Some(id.to_string())
-                )),
+                0 => {
+                    #recheck
+                    return Err(crudcrate::ApiError::not_found(
+                        Self::RESOURCE_NAME_SINGULAR,
+                        Some(id.to_string())
+                    ));
+                }
                 _ => id,
             };
-        }
+            #commit
+            #discard_files
+        };
+        with_retry("delete", &[], &default, &["result"])
     };
 
     // Generate transform hook call (modifies the result)
@@ -56,12 +103,28 @@ pub fn generate_delete_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
         quote! { #fn_path(db, result).await?; }
     });
 
+    let webhook = record_deletes(crud_meta, &quote! { std::iter::once(result) });
+
+    // Generate post_async hook job (runs after the response, on owned copies)
+    let post_async_hook = hooks.post_async.as_ref().map(|fn_path| {
+        quote! {
+            crudcrate::jobs::enqueue(crudcrate::jobs::Job::new(
+                Self::RESOURCE_NAME_SINGULAR,
+                "delete",
+                #fn_path(db.clone(), result),
+            ));
+        }
+    });
+
     quote! {
         async fn delete(db: &sea_orm::DatabaseConnection, id: uuid::Uuid) -> Result<uuid::Uuid, crudcrate::ApiError> {
             #pre_hook
+            #restrict
             #body
             #transform_hook
             #post_hook
+            #webhook
+            #post_async_hook
             Ok(result)
         }
     }
@@ -74,10 +137,22 @@ pub fn generate_delete_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
 /// - `delete::many::body`: Replaces default delete logic (receives `Vec<Uuid>`, returns `Vec<Uuid>`)
 /// - `delete::many::transform`: Modify the results (receives `Vec<Uuid>`, returns `Vec<Uuid>`)
 /// - `delete::many::post`: Side effects after batch delete (receives deleted ids)
+/// - `delete::many::post_async`: Background job after batch delete (receives `Vec<Uuid>`)
 ///
 /// **Security Note**: The default implementation limits batch deletes to 100 items to prevent
-/// `DoS` attacks via resource exhaustion.
-pub fn generate_delete_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenStream {
+/// `DoS` attacks via resource exhaustion. With `file_upload` fields, the default body
+/// also deletes the stored files of the deleted records. With `restrict_delete` or
+/// `guard_delete`, the whole batch is refused while any of its records has dependent
+/// rows or is locked, checked by the default body in its transaction as for `delete`.
+/// With
+/// `cascade_delete`, the default body deletes the children of the existing records first,
+/// in the same transaction. With `sync`, it records their tombstones in that transaction.
+/// The default body runs under `Self::retry_policy()`.
+#[allow(clippy::too_many_lines)]
+pub fn generate_delete_many_impl(
+    crud_meta: &CRUDResourceMeta,
+    has_file_uploads: bool,
+) -> proc_macro2::TokenStream {
     // If operations is specified, use it (takes full control)
     if let Some(ops_path) = &crud_meta.operations {
         return quote! {
@@ -96,11 +171,46 @@ pub fn generate_delete_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
         quote! { #fn_path(db, &ids).await?; }
     });
 
+    let restrict = (hooks.body.is_some()
+        && (crud_meta.restrict_delete || crud_meta.guard_delete.is_some()))
+    .then(|| {
+        quote! { <Self as crudcrate::CRUDResource>::ensure_deletable(db, &ids).await?; }
+    });
+
     // Generate body - either custom or default
     let body = if let Some(fn_path) = &hooks.body {
         quote! { let result = #fn_path(db, ids).await?; }
     } else {
-        quote! {
+        let load_files = has_file_uploads.then(|| {
+            quote! {
+                let stored_files: Vec<(&'static str, String)> = Self::EntityType::find()
+                    .filter(Self::ID_COLUMN.is_in(existing_set.iter().copied().collect::<Vec<_>>()))
+                    .all(db)
+                    .await?
+                    .into_iter()
+                    .flat_map(|model| crudcrate::CRUDResource::stored_files(&Self::from(model)))
+                    .collect();
+            }
+        });
+        let discard_files = has_file_uploads.then(|| {
+            quote! { crudcrate::storage::discard(&stored_files).await; }
+        });
+        let (begin, conn, commit) = delete_transaction(
+            crud_meta,
+            &quote! { &existing_set.iter().copied().collect::<Vec<_>>() },
+        );
+        let recheck = crud_meta.guard_delete.is_some().then(|| {
+            quote! {
+                if res.rows_affected < existing_set.len() as u64 {
+                    <Self as crudcrate::CRUDResource>::ensure_deletable(
+                        #conn,
+                        &existing_set.iter().copied().collect::<Vec<_>>(),
+                    )
+                    .await?;
+                }
+            }
+        });
+        let default = quote! {
             use sea_orm::{EntityTrait, QueryFilter, QuerySelect, ColumnTrait};
 
             // Security: Limit batch size to prevent DoS attacks (uses configurable BATCH_LIMIT)
@@ -125,16 +235,23 @@ pub fn generate_delete_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
 
                 // Delete only existing IDs
                 if !existing_set.is_empty() {
-                    Self::EntityType::delete_many()
+                    #load_files
+                    #begin
+                    let res = Self::EntityType::delete_many()
                         .filter(Self::ID_COLUMN.is_in(existing_set.iter().copied().collect::<Vec<_>>()))
-                        .exec(db)
+                        .filter(sea_orm::Condition::all().add_option(Self::delete_condition()))
+                        .exec(#conn)
                         .await?;
+                    #recheck
+                    #commit
+                    #discard_files
                 }
 
                 // Return only IDs that actually existed (preserving input order)
                 ids.into_iter().filter(|id| existing_set.contains(id)).collect()
             };
-        }
+        };
+        with_retry("delete", &["ids"], &default, &["result"])
     };
 
     // Generate transform hook call (modifies the results)
@@ -147,13 +264,78 @@ pub fn generate_delete_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
         quote! { #fn_path(db, &result).await?; }
     });
 
+    let webhook = record_deletes(crud_meta, &quote! { result.iter().copied() });
+
+    // Generate post_async hook job (runs after the response, on owned copies)
+    let post_async_hook = hooks.post_async.as_ref().map(|fn_path| {
+        quote! {
+            crudcrate::jobs::enqueue(crudcrate::jobs::Job::new(
+                Self::RESOURCE_NAME_SINGULAR,
+                "delete",
+                #fn_path(db.clone(), result.clone()),
+            ));
+        }
+    });
+
     quote! {
         async fn delete_many(db: &sea_orm::DatabaseConnection, ids: Vec<uuid::Uuid>) -> Result<Vec<uuid::Uuid>, crudcrate::ApiError> {
             #pre_hook
+            #restrict
             #body
             #transform_hook
             #post_hook
+            #webhook
+            #post_async_hook
             Ok(result)
         }
     }
 }
+
+/// Statements opening the transaction a delete of `ids` runs in, checking them on it
+/// (`restrict_delete`, `guard_delete`) and deleting their children (`cascade_delete`),
+/// the connection the delete runs on, and the statements recording their tombstones
+/// (`sync`), committing and reporting the deleted children. Without any of these
+/// options, the delete runs on `db`.
+fn delete_transaction(
+    crud_meta: &CRUDResourceMeta,
+    ids: &proc_macro2::TokenStream,
+) -> (
+    Option<proc_macro2::TokenStream>,
+    proc_macro2::TokenStream,
+    Option<proc_macro2::TokenStream>,
+) {
+    let sync = crud_meta.sync.is_some();
+    let guarded = crud_meta.restrict_delete || crud_meta.guard_delete.is_some();
+    if !crud_meta.cascade_delete && !sync && !guarded {
+        return (None, quote! { db }, None);
+    }
+    let check = guarded.then(|| {
+        quote! { <Self as crudcrate::CRUDResource>::ensure_deletable(&txn, #ids).await?; }
+    });
+    let (cascade, report) = if crud_meta.cascade_delete {
+        (
+            Some(quote! {
+                let cascaded = <Self as crudcrate::CRUDResource>::cascade_delete(&txn, #ids).await?;
+            }),
+            Some(quote! {
+                crudcrate::relationships::report_cascade(Self::RESOURCE_NAME_SINGULAR, &cascaded);
+            }),
+        )
+    } else {
+        (None, None)
+    };
+    let tombstones = sync.then(|| {
+        quote! { crudcrate::sync::record_deletes::<Self, _>(&txn, #ids).await?; }
+    });
+    let begin = quote! {
+        let txn = sea_orm::TransactionTrait::begin(db).await?;
+        #check
+        #cascade
+    };
+    let commit = quote! {
+        #tombstones
+        txn.commit().await?;
+        #report
+    };
+    (Some(begin), quote! { &txn }, Some(commit))
+}
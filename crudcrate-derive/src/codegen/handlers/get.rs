@@ -1,8 +1,12 @@
+use crate::attribute_parser::{get_crudcrate_expr, get_crudcrate_strings};
+use crate::codegen::joins::get_join_config;
 use crate::codegen::joins::loading::{
     generate_get_all_batch_loading, generate_get_all_scoped_batch_loading,
-    generate_get_one_join_loading, generate_get_one_scoped_join_loading,
+    generate_get_one_join_loading, generate_get_one_scoped_join_loading, unique_join_fields,
 };
+use crate::codegen::joins::polymorphic::{generate_polymorphic_many, generate_polymorphic_one};
 use crate::codegen::models::should_include_in_model;
+use crate::codegen::type_resolution::{extract_api_struct_type_for_recursive_call, is_vec_type};
 use crate::traits::crudresource::structs::{CRUDResourceMeta, EntityFieldAnalysis};
 use convert_case::{Case, Casing};
 use quote::{format_ident, quote};
@@ -20,53 +24,203 @@ fn is_option_type(ty: &syn::Type) -> bool {
     }
 }
 
-/// Generate `select_only()` column selection for list queries.
-/// Included columns are selected normally. Excluded Option<T> columns are replaced
-/// with NULL to avoid fetching heavy data (photos, blobs) while keeping `FromQueryResult` happy.
-/// Returns `Some(token_stream)` if there are skippable columns, `None` otherwise.
-fn generate_select_only_columns(
-    analysis: &EntityFieldAnalysis,
-) -> Option<proc_macro2::TokenStream> {
-    // Check if any DB columns are excluded from list AND are Option<T>
-    let has_skippable = analysis
+/// Columns list queries select as NULL: `Option<T>` columns excluded from the list model,
+/// to avoid fetching heavy data (photos, blobs) while keeping `FromQueryResult` happy.
+fn generate_null_columns(analysis: &EntityFieldAnalysis) -> Vec<proc_macro2::TokenStream> {
+    analysis
         .db_fields
         .iter()
-        .any(|f| !should_include_in_model(f, "list_model") && is_option_type(&f.ty));
+        .filter(|field| !should_include_in_model(field, "list_model") && is_option_type(&field.ty))
+        .map(|field| {
+            let name = field.ident.as_ref().unwrap().to_string();
+            let col_ident = format_ident!("{}", name.to_case(Case::Pascal));
+            quote! { <Self as crudcrate::traits::CRUDResource>::ColumnType::#col_ident }
+        })
+        .collect()
+}
+
+/// Non-db fields with `computed = fn`, and their `computed_many = fn` if any
+fn computed_fields<'a>(
+    analysis: &'a EntityFieldAnalysis,
+) -> Vec<(&'a syn::Field, syn::Expr, Option<syn::Expr>)> {
+    analysis
+        .non_db_fields
+        .iter()
+        .filter_map(|field| {
+            get_crudcrate_expr(field, "computed")
+                .map(|computed| (*field, computed, get_crudcrate_expr(field, "computed_many")))
+        })
+        .collect()
+}
+
+/// Non-db fields with `counted_relation = "Variant"`, the `Relation` variant, and the
+/// counted resource's `read_condition()`, which the count applies
+fn counted_fields<'a>(
+    analysis: &'a EntityFieldAnalysis,
+    model_type: &str,
+) -> Vec<(&'a syn::Field, syn::Ident, proc_macro2::TokenStream)> {
+    analysis
+        .non_db_fields
+        .iter()
+        .filter(|field| should_include_in_model(field, model_type))
+        .filter_map(|field| {
+            let variant = get_crudcrate_strings(field, "counted_relation").pop()?;
+            let condition = match counted_resource(analysis, field, &variant) {
+                Ok(resource) => quote! { <#resource as crudcrate::CRUDResource>::read_condition() },
+                Err(message) => {
+                    let span = field
+                        .ident
+                        .as_ref()
+                        .map_or_else(proc_macro2::Span::call_site, syn::Ident::span);
+                    quote::quote_spanned! {span=> compile_error!(#message) }
+                }
+            };
+            Some((*field, format_ident!("{}", variant), condition))
+        })
+        .collect()
+}
 
-    if !has_skippable {
-        return None;
+/// The resource a `counted_relation` counts: `counted_resource = "path"`, or the `Vec`
+/// join field going through the same `Relation` variant
+fn counted_resource(
+    analysis: &EntityFieldAnalysis,
+    field: &syn::Field,
+    variant: &str,
+) -> Result<proc_macro2::TokenStream, String> {
+    if let Some(path) = get_crudcrate_strings(field, "counted_resource").pop() {
+        return syn::parse_str::<syn::Path>(&path)
+            .map(|path| quote! { #path })
+            .map_err(|_| format!("counted_resource = \"{path}\" is not a valid path"));
     }
+    unique_join_fields(analysis)
+        .into_iter()
+        .filter(|join| is_vec_type(&join.ty))
+        .find_map(|join| {
+            let child = extract_api_struct_type_for_recursive_call(&join.ty);
+            let child_name = child.to_string();
+            let relation = get_join_config(join).unwrap_or_default().relation;
+            let through = relation
+                .as_deref()
+                .unwrap_or_else(|| child_name.rsplit("::").next().unwrap_or_default().trim());
+            (through == variant).then_some(child)
+        })
+        .ok_or_else(|| {
+            format!(
+                "counted_relation = \"{variant}\" needs the counted resource, so rows hidden by \
+                 its read condition aren't counted: add counted_resource = \"path::to::ApiStruct\" \
+                 or a Vec join field with relation = \"{variant}\""
+            )
+        })
+}
 
-    // Build column selections: real columns for included, NULL for excluded Option<T>
-    let mut selections = Vec::new();
-    for field in &analysis.db_fields {
-        let col_ident = {
-            let name = field.ident.as_ref().unwrap().to_string();
-            format_ident!("{}", name.to_case(Case::Pascal))
-        };
-        let included = should_include_in_model(field, "list_model");
+/// The primary key field's ident, `id` if none is marked
+fn primary_key_ident(analysis: &EntityFieldAnalysis) -> syn::Ident {
+    analysis
+        .primary_key_field
+        .and_then(|field| field.ident.clone())
+        .unwrap_or_else(|| format_ident!("id"))
+}
 
-        if included || !is_option_type(&field.ty) {
-            // Include this column (real data)
-            selections.push(quote! {
-                .column(<Self as crudcrate::traits::CRUDResource>::ColumnType::#col_ident)
-            });
-        } else {
-            // Excluded Option<T> column — replace with NULL to skip data transfer
-            // Use column_as with IdenStatic::as_str() to get the correct DB column name
-            selections.push(quote! {
-                .column_as(
-                    sea_orm::sea_query::Expr::cust("NULL"),
-                    sea_orm::IdenStatic::as_str(&<Self as crudcrate::traits::CRUDResource>::ColumnType::#col_ident)
-                )
+/// Populate `computed`, `counted_relation` and `polymorphic_join` fields of `result`
+/// (a `Self`) in `get_one`
+fn generate_computed_one(analysis: &EntityFieldAnalysis) -> proc_macro2::TokenStream {
+    let pk = primary_key_ident(analysis);
+    let counts =
+        counted_fields(analysis, "one_model")
+            .into_iter()
+            .map(|(field, variant, condition)| {
+                let ident = &field.ident;
+                quote! {
+                    result.#ident = crudcrate::relationships::count_related(
+                        db,
+                        sea_orm::RelationTrait::def(&Relation::#variant),
+                        #condition,
+                        vec![result.#pk],
+                    )
+                    .await?
+                    .remove(&result.#pk)
+                    .unwrap_or(0);
+                }
             });
-        }
+    let assignments: Vec<_> = computed_fields(analysis)
+        .into_iter()
+        .filter(|(field, _, _)| should_include_in_model(field, "one_model"))
+        .map(|(field, computed, _)| {
+            let ident = &field.ident;
+            quote! { result.#ident = #computed(&result, db).await?; }
+        })
+        .chain(counts)
+        .chain(generate_polymorphic_one(analysis, "one_model"))
+        .collect();
+    if assignments.is_empty() {
+        return quote! {};
     }
+    quote! {
+        let mut result = result;
+        #(#assignments)*
+    }
+}
 
-    Some(quote! {
-        .select_only()
-        #( #selections )*
-    })
+/// Populate `computed` fields of `items` (a `Vec<Self>`) in `get_all`: one
+/// `computed_many` call per field if given, otherwise one `computed` call per item.
+/// Each `counted_relation` field takes one grouped `COUNT` query for the whole page, and
+/// each `polymorphic_join` field one query per target type.
+fn generate_computed_many(analysis: &EntityFieldAnalysis) -> proc_macro2::TokenStream {
+    let pk = primary_key_ident(analysis);
+    let counts =
+        counted_fields(analysis, "list_model")
+            .into_iter()
+            .map(|(field, variant, condition)| {
+                let ident = &field.ident;
+                quote! {
+                    let counts = crudcrate::relationships::count_related(
+                        db,
+                        sea_orm::RelationTrait::def(&Relation::#variant),
+                        #condition,
+                        items.iter().map(|item| item.#pk).collect(),
+                    )
+                    .await?;
+                    for item in &mut items {
+                        item.#ident = counts.get(&item.#pk).copied().unwrap_or(0);
+                    }
+                }
+            });
+    let assignments: Vec<_> = computed_fields(analysis)
+        .into_iter()
+        .filter(|(field, _, _)| should_include_in_model(field, "list_model"))
+        .map(|(field, computed, computed_many)| {
+            let ident = &field.ident;
+            if let Some(computed_many) = computed_many {
+                let field_name = ident.as_ref().map(ToString::to_string).unwrap_or_default();
+                quote! {
+                    let values = #computed_many(&items, db).await?;
+                    if values.len() != items.len() {
+                        return Err(crudcrate::ApiError::internal(
+                            "Failed to compute list fields",
+                            Some(format!(
+                                "computed_many for `{}` returned {} values for {} items",
+                                #field_name,
+                                values.len(),
+                                items.len()
+                            )),
+                        ));
+                    }
+                    for (item, value) in items.iter_mut().zip(values) {
+                        item.#ident = value;
+                    }
+                }
+            } else {
+                quote! {
+                    for item in &mut items {
+                        item.#ident = #computed(&*item, db).await?;
+                    }
+                }
+            }
+        })
+        .chain(counts)
+        .chain(generate_polymorphic_many(analysis, "list_model"))
+        .collect();
+    quote! { #(#assignments)* }
 }
 
 /// Generate `get_all` method implementation with hook support.
@@ -82,6 +236,7 @@ fn generate_select_only_columns(
 /// 1. Query all parents
 /// 2. Batch query all children WHERE `parent_id` IN (`parent_ids`)
 /// 3. Group children by `parent_id` in memory
+#[allow(clippy::too_many_lines)]
 pub fn generate_get_all_impl(
     crud_meta: &CRUDResourceMeta,
     analysis: &EntityFieldAnalysis,
@@ -101,6 +256,20 @@ pub fn generate_get_all_impl(
                 let ops = #ops_path;
                 crudcrate::CRUDOperations::get_all(&ops, db, condition, order_column, order_direction, offset, limit).await
             }
+
+            async fn get_all_ordered(
+                db: &sea_orm::DatabaseConnection,
+                condition: &sea_orm::Condition,
+                order: &[(sea_orm::sea_query::SimpleExpr, sea_orm::Order)],
+                offset: u64,
+                limit: u64,
+                _scoped: bool,
+            ) -> Result<Vec<Self::ListModel>, crudcrate::ApiError> {
+                // Custom operations only understand column ordering
+                let order_direction = order.first().map_or(sea_orm::Order::Asc, |(_, direction)| direction.clone());
+                let ops = #ops_path;
+                crudcrate::CRUDOperations::get_all(&ops, db, condition, Self::default_index_column(), order_direction, offset, limit).await
+            }
         };
     }
 
@@ -115,76 +284,109 @@ pub fn generate_get_all_impl(
     // Check if there are join(all) fields that need loading
     let has_join_all_fields = !analysis.join_on_all_fields.is_empty();
 
-    // Generate select_only() optimization for skipping heavy Option columns excluded from ListModel
-    let select_only_columns = generate_select_only_columns(analysis);
-    let select_clause = select_only_columns.unwrap_or_default();
+    // Heavy Option columns excluded from ListModel are selected as NULL
+    let null_columns = generate_null_columns(analysis);
 
-    // Shared body builder: given a batch-loading fragment, produce the full body.
-    // Used for both get_all (unscoped) and get_all_scoped variants so they share
-    // ordering, pagination, select_only, and hook semantics.
+    // `computed` fields are filled on the `Self` items before converting to ListModel
+    let computed_many = generate_computed_many(analysis);
+    let items_mut = (!computed_many.is_empty()).then(|| quote! { mut });
+
+    // Shared body builder: given a batch-loading fragment and an ordering slice,
+    // produce the full body. Used for get_all, get_all_scoped and get_all_ordered so they
+    // share pagination, NULL columns, and hook semantics.
     let build_body = |batch_loading: Option<(
         proc_macro2::TokenStream,
         proc_macro2::TokenStream,
-    )>| {
+    )>,
+                      ordering: &proc_macro2::TokenStream| {
         if let Some(fn_path) = &hooks.body {
             // Custom body takes full control; applies to both variants.
             quote! { let result = #fn_path(db, condition, order_column, order_direction, offset, limit).await?; }
-        } else if let Some((pre_loop_code, in_loop_code)) = batch_loading {
-            quote! {
-                use sea_orm::{QueryOrder, QuerySelect, EntityTrait, ModelTrait};
-
-                let models = Self::EntityType::find()
-                    #select_clause
-                    .filter(condition.clone())
-                    .order_by(order_column, order_direction)
-                    .offset(offset)
-                    .limit(limit)
-                    .all(db)
-                    .await?;
-
-                // Batch load all related entities (one query per join field)
-                #pre_loop_code
-
-                // Assign pre-loaded data to each model (no queries in loop)
-                let mut result = Vec::new();
-                for model in models {
-                    let item = {
-                        #in_loop_code
-                    };
-                    result.push(Self::ListModel::from(item));
-                }
-            }
         } else {
-            // Standard get_all without joins
-            quote! {
-                use sea_orm::{QueryOrder, QuerySelect, EntityTrait};
-
-                let models = Self::EntityType::find()
-                    #select_clause
-                    .filter(condition.clone())
-                    .order_by(order_column, order_direction)
-                    .offset(offset)
-                    .limit(limit)
-                    .all(db)
-                    .await?;
-                let result: Vec<Self::ListModel> = models.into_iter().map(|model| Self::ListModel::from(Self::from(model))).collect();
+            let fetch = quote! {
+                let models = crudcrate::core::crud_operations::find_page::<Self>(
+                    db,
+                    condition,
+                    #ordering,
+                    offset,
+                    limit,
+                    &[#(#null_columns),*],
+                )
+                .await?;
+            };
+            if let Some((pre_loop_code, in_loop_code)) = batch_loading {
+                quote! {
+                    #fetch
+
+                    // Batch load all related entities (one query per join field)
+                    let joins_started = std::time::Instant::now();
+                    #pre_loop_code
+
+                    // Assign pre-loaded data to each model (no queries in loop)
+                    let mut items: Vec<Self> = Vec::with_capacity(models.len());
+                    for model in models {
+                        let item = {
+                            #in_loop_code
+                        };
+                        items.push(item);
+                    }
+                    crudcrate::timing::record("joins", joins_started.elapsed());
+                    #computed_many
+                    let result: Vec<Self::ListModel> = items.into_iter().map(Self::ListModel::from).collect();
+                }
+            } else {
+                // Standard get_all without joins
+                quote! {
+                    #fetch
+                    let #items_mut items: Vec<Self> = models.into_iter().map(Self::from).collect();
+                    #computed_many
+                    let result: Vec<Self::ListModel> = items.into_iter().map(Self::ListModel::from).collect();
+                }
             }
         }
     };
 
-    let body = build_body(if has_join_all_fields {
-        Some(generate_get_all_batch_loading(analysis, api_struct_name))
-    } else {
-        None
-    });
+    let batch_loading =
+        || has_join_all_fields.then(|| generate_get_all_batch_loading(analysis, api_struct_name));
+    let scoped_batch_loading = || {
+        has_join_all_fields
+            .then(|| generate_get_all_scoped_batch_loading(analysis, api_struct_name))
+    };
+
+    let column_ordering = quote! {
+        &[crudcrate::core::crud_operations::column_order(order_column, order_direction)]
+    };
+    let expr_ordering = quote! { order };
+
+    let body = build_body(batch_loading(), &column_ordering);
+    let scoped_body = build_body(scoped_batch_loading(), &column_ordering);
+    let ordered_body = build_body(batch_loading(), &expr_ordering);
+    let scoped_ordered_body = build_body(scoped_batch_loading(), &expr_ordering);
 
-    let scoped_body = build_body(if has_join_all_fields {
-        Some(generate_get_all_scoped_batch_loading(
-            analysis,
-            api_struct_name,
-        ))
+    // A body hook replaces both variants, so only dispatch on `scoped` without one
+    let ordered_dispatch = if hooks.body.is_some() {
+        quote! {
+            let _ = scoped;
+            #ordered_body
+        }
     } else {
-        None
+        quote! {
+            let result = if scoped {
+                #scoped_ordered_body
+                result
+            } else {
+                #ordered_body
+                result
+            };
+        }
+    };
+
+    // Hooks take a column ordering; expression orderings report the default column
+    let ordered_hook_shim = (hooks.pre.is_some() || hooks.body.is_some()).then(|| {
+        quote! {
+            let order_column = Self::default_index_column();
+            let order_direction = order.first().map_or(sea_orm::Order::Asc, |(_, direction)| direction.clone());
+        }
     });
 
     // Generate transform hook call (modifies the results)
@@ -227,6 +429,22 @@ pub fn generate_get_all_impl(
             #post_hook
             Ok(result)
         }
+
+        async fn get_all_ordered(
+            db: &sea_orm::DatabaseConnection,
+            condition: &sea_orm::Condition,
+            order: &[(sea_orm::sea_query::SimpleExpr, sea_orm::Order)],
+            offset: u64,
+            limit: u64,
+            scoped: bool,
+        ) -> Result<Vec<Self::ListModel>, crudcrate::ApiError> {
+            #ordered_hook_shim
+            #pre_hook
+            #ordered_dispatch
+            #transform_hook
+            #post_hook
+            Ok(result)
+        }
     }
 }
 
@@ -274,29 +492,31 @@ pub fn generate_get_one_impl(
             use sea_orm::{EntityTrait, ModelTrait, Related};
 
             // Load the main entity first — Box::pin to keep future off the stack
-            let main_model = Box::pin(
-                Self::EntityType::find_by_id(id).one(db)
+            let model = Box::pin(
+                crudcrate::core::crud_operations::find_by_id::<Self>(db, id, None)
             ).await?;
 
-            let result = match main_model {
-                Some(model) => {
-                    #join_loading_code
-                }
-                None => return Err(crudcrate::ApiError::not_found(Self::RESOURCE_NAME_SINGULAR, Some(id.to_string()))),
+            let joins_started = std::time::Instant::now();
+            let result = {
+                #join_loading_code
             };
+            crudcrate::timing::record("joins", joins_started.elapsed());
         }
     } else {
         quote! {
-            let model = Self::EntityType::find_by_id(id)
-                .one(db)
-                .await?;
-            let result = match model {
-                Some(model) => Self::from(model),
-                None => return Err(crudcrate::ApiError::not_found(Self::RESOURCE_NAME_SINGULAR, Some(id.to_string()))),
-            };
+            let result = Self::from(
+                crudcrate::core::crud_operations::find_by_id::<Self>(db, id, None).await?
+            );
         }
     };
 
+    // `computed` fields, filled after the default body (a body hook builds its own result)
+    let computed_one = if hooks.body.is_some() {
+        quote! {}
+    } else {
+        generate_computed_one(analysis)
+    };
+
     // Generate transform hook call (modifies the result)
     let transform_hook = hooks.transform.as_ref().map(|fn_path| {
         quote! { let result = #fn_path(db, result).await?; }
@@ -314,35 +534,21 @@ pub fn generate_get_one_impl(
         quote! {
             use sea_orm::{EntityTrait, ModelTrait, Related, QueryFilter};
 
-            let scoped_condition = sea_orm::Condition::all()
-                .add(Self::ID_COLUMN.eq(id))
-                .add(scope.clone());
-
-            let main_model = Box::pin(
-                Self::EntityType::find().filter(scoped_condition).one(db)
+            let model = Box::pin(
+                crudcrate::core::crud_operations::find_one::<Self>(db, id, Some(scope))
             ).await?;
 
-            let result = match main_model {
-                Some(model) => {
-                    #join_loading_code
-                }
-                None => return Err(crudcrate::ApiError::not_found(Self::RESOURCE_NAME_SINGULAR, Some(id.to_string()))),
+            let joins_started = std::time::Instant::now();
+            let result = {
+                #join_loading_code
             };
+            crudcrate::timing::record("joins", joins_started.elapsed());
         }
     } else {
         quote! {
-            use sea_orm::QueryFilter;
-            let scoped_condition = sea_orm::Condition::all()
-                .add(Self::ID_COLUMN.eq(id))
-                .add(scope.clone());
-            let model = Self::EntityType::find()
-                .filter(scoped_condition)
-                .one(db)
-                .await?;
-            let result = match model {
-                Some(model) => Self::from(model),
-                None => return Err(crudcrate::ApiError::not_found(Self::RESOURCE_NAME_SINGULAR, Some(id.to_string()))),
-            };
+            let result = Self::from(
+                crudcrate::core::crud_operations::find_one::<Self>(db, id, Some(scope)).await?
+            );
         }
     };
 
@@ -350,6 +556,7 @@ pub fn generate_get_one_impl(
         async fn get_one(db: &sea_orm::DatabaseConnection, id: uuid::Uuid) -> Result<Self, crudcrate::ApiError> {
             #pre_hook
             #body
+            #computed_one
             #transform_hook
             #post_hook
             Ok(result)
@@ -362,6 +569,7 @@ pub fn generate_get_one_impl(
         ) -> Result<Self, crudcrate::ApiError> {
             #pre_hook
             #scoped_body
+            #computed_one
             #transform_hook
             #post_hook
             Ok(result)
@@ -1,4 +1,6 @@
 pub mod create;
 pub mod delete;
 pub mod get;
+pub mod retry;
 pub mod update;
+pub mod webhooks;
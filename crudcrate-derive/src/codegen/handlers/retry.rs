@@ -0,0 +1,36 @@
+//! Default write bodies run under `CRUDResource::retry_policy`
+use quote::{format_ident, quote};
+
+/// Statements running `body`, a default `operation` body that consumes `inputs` and binds
+/// `outputs`, under `Self::retry_policy()`. Each attempt consumes clones of the inputs;
+/// the outputs of the attempt that succeeds are bound afterwards.
+pub(crate) fn with_retry(
+    operation: &str,
+    inputs: &[&str],
+    body: &proc_macro2::TokenStream,
+    outputs: &[&str],
+) -> proc_macro2::TokenStream {
+    let inputs: Vec<_> = inputs
+        .iter()
+        .map(|input| format_ident!("{input}"))
+        .collect();
+    let outputs: Vec<_> = outputs
+        .iter()
+        .map(|output| format_ident!("{output}"))
+        .collect();
+    let bound = match outputs.as_slice() {
+        [output] => quote! { #output },
+        outputs => quote! { (#(#outputs),*) },
+    };
+    quote! {
+        let #bound = Self::retry_policy()
+            .run(Self::RESOURCE_NAME_SINGULAR, #operation, || {
+                #(let #inputs = #inputs.clone();)*
+                async move {
+                    #body
+                    Ok::<_, crudcrate::ApiError>(#bound)
+                }
+            })
+            .await?;
+    }
+}
@@ -1,4 +1,6 @@
-use crate::traits::crudresource::structs::CRUDResourceMeta;
+use crate::codegen::handlers::retry::with_retry;
+use crate::codegen::handlers::webhooks::record_changes;
+use crate::traits::crudresource::structs::{CRUDResourceMeta, EntityFieldAnalysis};
 use quote::quote;
 
 /// Generate update method implementation with hook support.
@@ -7,7 +9,10 @@ use quote::quote;
 /// - `update::one::pre`: Validation/preparation before update (receives id, `&UpdateModel`)
 /// - `update::one::body`: Replaces default update logic (receives id, `UpdateModel`, returns `Self`)
 /// - `update::one::transform`: Modify the result (receives `Self`, returns `Self`)
-/// - `update::one::post`: Side effects after update (receives `&Self`)
+/// - `update::one::post`: Side effects after update (receives `&Self`, `&ChangeSet`)
+/// - `update::one::post_async`: Background job after update (receives `Self`, `ChangeSet`)
+///
+/// The default body runs under `Self::retry_policy()`.
 pub fn generate_update_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenStream {
     // If operations is specified, use it (takes full control)
     if let Some(ops_path) = &crud_meta.operations {
@@ -27,13 +32,25 @@ pub fn generate_update_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
         quote! { #fn_path(db, id, &data).await?; }
     });
 
+    // The change set is only computed when a post hook will receive it
+    let wants_changes = hooks.post.is_some() || hooks.post_async.is_some();
+
     // Generate body - either custom or default
     let body = if let Some(fn_path) = &hooks.body {
-        quote! { let result = #fn_path(db, id, data).await?; }
-    } else {
+        // A replaced body doesn't expose the merged model, so there is nothing to diff
+        let changes =
+            wants_changes.then(|| quote! { let changes = crudcrate::ChangeSet::default(); });
         quote! {
+            let result = #fn_path(db, id, data).await?;
+            #changes
+        }
+    } else {
+        let changes = wants_changes.then(|| {
+            quote! { let changes = crudcrate::ChangeSet::between::<Self::EntityType, _>(&original, &updated_model).masking(Self::sensitive_fields()); }
+        });
+        let original = wants_changes.then(|| quote! { let original = model.clone(); });
+        let default = quote! {
             use sea_orm::{EntityTrait, IntoActiveModel, ActiveModelTrait};
-            use crudcrate::traits::MergeIntoActiveModel;
 
             let model = Self::EntityType::find_by_id(id)
                 .one(db)
@@ -42,11 +59,14 @@ pub fn generate_update_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
                     Self::RESOURCE_NAME_SINGULAR,
                     Some(id.to_string())
                 ))?;
+            #original
             let existing: Self::ActiveModelType = model.into_active_model();
-            let updated_model = data.merge_into_activemodel(existing)?;
+            let updated_model = Self::update_active_model(db, data, existing).await?;
+            #changes
             let updated = updated_model.update(db).await?;
             let result = Self::from(updated);
-        }
+        };
+        with_retry("update", &["data"], &default, outputs(wants_changes))
     };
 
     // Generate transform hook call (modifies the result)
@@ -56,7 +76,24 @@ pub fn generate_update_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
 
     // Generate post hook call
     let post_hook = hooks.post.as_ref().map(|fn_path| {
-        quote! { #fn_path(db, &result).await?; }
+        quote! { #fn_path(db, &result, &changes).await?; }
+    });
+
+    let webhook = record_changes(
+        crud_meta,
+        "Updated",
+        &quote! { std::iter::once((id, &result)) },
+    );
+
+    // Generate post_async hook job (runs after the response, on owned copies)
+    let post_async_hook = hooks.post_async.as_ref().map(|fn_path| {
+        quote! {
+            crudcrate::jobs::enqueue(crudcrate::jobs::Job::new(
+                Self::RESOURCE_NAME_SINGULAR,
+                "update",
+                #fn_path(db.clone(), result.clone(), changes.clone()),
+            ));
+        }
     });
 
     quote! {
@@ -65,6 +102,8 @@ pub fn generate_update_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
             #body
             #transform_hook
             #post_hook
+            #webhook
+            #post_async_hook
             Ok(result)
         }
     }
@@ -75,11 +114,15 @@ pub fn generate_update_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenS
 /// Hook execution order: pre → body → transform → post
 /// - `update::many::pre`: Validation/preparation before batch update (receives &[(Uuid, `UpdateModel`)])
 /// - `update::many::body`: Replaces default update logic (receives Vec<(Uuid, `UpdateModel`)>, returns Vec<Self>)
-/// - `update::many::post`: Side effects after batch update (receives &[Self])
+/// - `update::many::post`: Side effects after batch update (receives &[Self], &[`ChangeSet`])
+/// - `update::many::post_async`: Background job after batch update (receives Vec<Self>, Vec<`ChangeSet`>)
 ///
 /// **Security Note**: The default implementation limits batch updates to 100 items to prevent
-/// `DoS` attacks via resource exhaustion.
-pub fn generate_update_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::TokenStream {
+/// `DoS` attacks via resource exhaustion. It runs under `Self::retry_policy()`.
+pub fn generate_update_many_impl(
+    crud_meta: &CRUDResourceMeta,
+    analysis: &EntityFieldAnalysis,
+) -> proc_macro2::TokenStream {
     // If operations is specified, use it (takes full control)
     if let Some(ops_path) = &crud_meta.operations {
         return quote! {
@@ -98,13 +141,26 @@ pub fn generate_update_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
         quote! { #fn_path(db, &updates).await?; }
     });
 
+    let wants_changes = hooks.post.is_some() || hooks.post_async.is_some();
+
     // Generate body - either custom or default
     let body = if let Some(fn_path) = &hooks.body {
-        quote! { let result = #fn_path(db, updates).await?; }
-    } else {
+        let changes = wants_changes.then(|| {
+            quote! { let changes = vec![crudcrate::ChangeSet::default(); result.len()]; }
+        });
         quote! {
+            let result = #fn_path(db, updates).await?;
+            #changes
+        }
+    } else {
+        let changes_init =
+            wants_changes.then(|| quote! { let mut changes = Vec::with_capacity(updates.len()); });
+        let original = wants_changes.then(|| quote! { let original = model.clone(); });
+        let change = wants_changes.then(|| {
+            quote! { changes.push(crudcrate::ChangeSet::between::<Self::EntityType, _>(&original, &updated_model).masking(Self::sensitive_fields())); }
+        });
+        let default = quote! {
             use sea_orm::{EntityTrait, IntoActiveModel, ActiveModelTrait, TransactionTrait};
-            use crudcrate::traits::MergeIntoActiveModel;
 
             // Security: Limit batch size to prevent DoS attacks (uses configurable BATCH_LIMIT)
             if updates.len() > Self::batch_limit() {
@@ -117,6 +173,7 @@ pub fn generate_update_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
             let txn = db.begin().await?;
 
             let mut result = Vec::with_capacity(updates.len());
+            #changes_init
             for (id, update_model) in updates {
                 let model = Self::EntityType::find_by_id(id)
                     .one(&txn)
@@ -125,14 +182,17 @@ pub fn generate_update_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
                         Self::RESOURCE_NAME_SINGULAR,
                         Some(id.to_string())
                     ))?;
+                #original
                 let existing: Self::ActiveModelType = model.into_active_model();
-                let updated_model = update_model.merge_into_activemodel(existing)?;
+                let updated_model = Self::update_active_model(&txn, update_model, existing).await?;
+                #change
                 let updated = updated_model.update(&txn).await?;
                 result.push(Self::from(updated));
             }
 
             txn.commit().await?;
-        }
+        };
+        with_retry("update", &["updates"], &default, outputs(wants_changes))
     };
 
     // Generate transform hook call (modifies the results)
@@ -142,7 +202,29 @@ pub fn generate_update_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
 
     // Generate post hook call
     let post_hook = hooks.post.as_ref().map(|fn_path| {
-        quote! { #fn_path(db, &result).await?; }
+        quote! { #fn_path(db, &result, &changes).await?; }
+    });
+
+    let pk_ident = analysis
+        .primary_key_field
+        .and_then(|f| f.ident.as_ref())
+        .cloned()
+        .unwrap_or_else(|| quote::format_ident!("id"));
+    let webhook = record_changes(
+        crud_meta,
+        "Updated",
+        &quote! { result.iter().map(|item| (item.#pk_ident, item)) },
+    );
+
+    // Generate post_async hook job (runs after the response, on owned copies)
+    let post_async_hook = hooks.post_async.as_ref().map(|fn_path| {
+        quote! {
+            crudcrate::jobs::enqueue(crudcrate::jobs::Job::new(
+                Self::RESOURCE_NAME_SINGULAR,
+                "update",
+                #fn_path(db.clone(), result.clone(), changes.clone()),
+            ));
+        }
     });
 
     quote! {
@@ -151,7 +233,19 @@ pub fn generate_update_many_impl(crud_meta: &CRUDResourceMeta) -> proc_macro2::T
             #body
             #transform_hook
             #post_hook
+            #webhook
+            #post_async_hook
             Ok(result)
         }
     }
 }
+
+/// Bindings of a default update body: the result, and its change sets when a post hook
+/// receives them
+fn outputs(wants_changes: bool) -> &'static [&'static str] {
+    if wants_changes {
+        &["result", "changes"]
+    } else {
+        &["result"]
+    }
+}
@@ -0,0 +1,59 @@
+//! Webhook deliveries recorded by the write operations of `#[crudcrate(webhooks)]` resources
+use crate::traits::crudresource::structs::CRUDResourceMeta;
+use quote::{format_ident, quote};
+
+/// Statement recording `event` deliveries for `changes`, an iterator of `(Uuid, &Self)`,
+/// or nothing without `webhooks`. The write already succeeded, so a failure is logged
+/// instead of returned.
+pub(crate) fn record_changes(
+    crud_meta: &CRUDResourceMeta,
+    event: &str,
+    changes: &proc_macro2::TokenStream,
+) -> Option<proc_macro2::TokenStream> {
+    record(
+        crud_meta,
+        event,
+        &quote! {
+            #changes
+                .map(|(id, item)| (id, serde_json::to_value(item).unwrap_or_default()))
+                .collect()
+        },
+    )
+}
+
+/// Statement recording `deleted` deliveries for `ids`, an iterator of `Uuid`, or nothing
+/// without `webhooks`
+pub(crate) fn record_deletes(
+    crud_meta: &CRUDResourceMeta,
+    ids: &proc_macro2::TokenStream,
+) -> Option<proc_macro2::TokenStream> {
+    record(
+        crud_meta,
+        "Deleted",
+        &quote! { #ids.map(|id| (id, serde_json::Value::Null)).collect() },
+    )
+}
+
+fn record(
+    crud_meta: &CRUDResourceMeta,
+    event: &str,
+    changes: &proc_macro2::TokenStream,
+) -> Option<proc_macro2::TokenStream> {
+    if !crud_meta.webhooks {
+        return None;
+    }
+    let event = format_ident!("{}", event);
+    Some(quote! {
+        if let Err(error) = crudcrate::webhooks::record::<Self>(
+            db,
+            crudcrate::webhooks::Event::#event,
+            #changes,
+        ).await {
+            tracing::warn!(
+                resource = Self::RESOURCE_NAME_PLURAL,
+                error = %error,
+                "Failed to record webhook deliveries"
+            );
+        }
+    })
+}
@@ -0,0 +1,90 @@
+//! Application-level cascade deletes for `#[crudcrate(cascade_delete)]` resources.
+//!
+//! The children are the rows of the resource's `Vec` join fields, found through the same
+//! foreign key convention as join loading. The generated `cascade_delete` override
+//! deletes the children's own children first (through the child's `cascade_delete`),
+//! then the children, on the connection it is given: the parent delete's transaction.
+
+use crate::codegen::joins::get_join_config;
+use crate::codegen::joins::loading::derive_fk_idents;
+use crate::codegen::type_resolution::{
+    extract_api_struct_type_for_recursive_call, extract_vec_inner_type_ref,
+    get_path_from_field_type, is_vec_type,
+};
+use crate::traits::crudresource::structs::EntityFieldAnalysis;
+use quote::quote;
+
+/// The `Vec` join fields whose rows are deleted with their parent
+pub(crate) fn cascade_children<'a>(analysis: &EntityFieldAnalysis<'a>) -> Vec<&'a syn::Field> {
+    let mut children: Vec<&syn::Field> = Vec::new();
+    for field in analysis
+        .join_on_one_fields
+        .iter()
+        .chain(analysis.join_on_all_fields.iter())
+        .copied()
+    {
+        if is_vec_type(&field.ty) && !children.iter().any(|child| child.ident == field.ident) {
+            children.push(field);
+        }
+    }
+    children
+}
+
+/// The `cascade_delete` override deleting the rows of every `Vec` join field
+pub(crate) fn generate_cascade_delete_impl(
+    analysis: &EntityFieldAnalysis,
+    api_struct_name: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    let mut deletes = Vec::new();
+
+    for field in cascade_children(analysis) {
+        let join_config = get_join_config(field).unwrap_or_default();
+        let child = extract_vec_inner_type_ref(&field.ty);
+        let is_self_referencing = api_struct_name
+            == extract_api_struct_type_for_recursive_call(&field.ty)
+                .to_string()
+                .trim();
+        let (fk_column, _) = derive_fk_idents(&join_config, api_struct_name, is_self_referencing);
+        let column_path = get_path_from_field_type(&field.ty, "Column");
+
+        deletes.push(quote! {
+            let child_ids: Vec<uuid::Uuid> = <#child as crudcrate::CRUDResource>::EntityType::find()
+                .select_only()
+                .column_as(<#child as crudcrate::CRUDResource>::ID_COLUMN, "id")
+                .filter(#column_path::#fk_column.is_in(ids.iter().copied()))
+                .into_model::<crudcrate::UuidIdResult>()
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|row| row.id)
+                .collect();
+            if !child_ids.is_empty() {
+                deleted.extend(<#child as crudcrate::CRUDResource>::cascade_delete(db, &child_ids).await?);
+                <#child as crudcrate::CRUDResource>::EntityType::delete_many()
+                    .filter(<#child as crudcrate::CRUDResource>::ID_COLUMN.is_in(child_ids.clone()))
+                    .exec(db)
+                    .await?;
+                deleted.extend(child_ids.into_iter().map(|id| crudcrate::relationships::DeletedChild {
+                    resource: <#child as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR,
+                    id,
+                }));
+            }
+        });
+    }
+
+    quote! {
+        async fn cascade_delete<C: sea_orm::ConnectionTrait>(
+            db: &C,
+            ids: &[uuid::Uuid],
+        ) -> Result<Vec<crudcrate::relationships::DeletedChild>, crudcrate::ApiError> {
+            use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+
+            let mut deleted = Vec::new();
+            if ids.is_empty() {
+                return Ok(deleted);
+            }
+            #({ #deletes })*
+            Ok(deleted)
+        }
+    }
+}
@@ -2,6 +2,7 @@ use syn::{Lit, Meta, parse::Parser, punctuated::Punctuated, token::Comma};
 
 /// Configuration for join behavior on a field
 #[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct JoinConfig {
     pub on_one: bool,
     pub on_all: bool,
@@ -14,6 +15,32 @@ pub struct JoinConfig {
     pub sortable_columns: Vec<String>,
     /// Explicit FK column name override (e.g., "`OwnerUuid`" instead of convention-derived "`CustomerId`")
     pub fk_column: Option<String>,
+    /// Max children per parent in list responses (`limit = 10`)
+    pub limit: Option<u64>,
+    /// Child ordering as written, e.g. `"created_at desc"`; see [`parse_order_by`]
+    pub order_by: Option<String>,
+    /// Accept the related row nested in the create model (`create`), one-to-one only
+    pub create: bool,
+    /// Serve the children at `GET /{id}/{field}` instead of in list responses (`lazy`),
+    /// `Vec` only
+    pub lazy: bool,
+}
+
+/// Parses a join `order_by` value: a `snake_case` column name, optionally followed by
+/// `asc` or `desc` (case-insensitive). Returns the column and whether it is descending.
+pub(crate) fn parse_order_by(value: &str) -> Option<(String, bool)> {
+    let mut parts = value.split_whitespace();
+    let column = parts.next()?;
+    let descending = match parts.next().map(str::to_ascii_lowercase).as_deref() {
+        None | Some("asc") => false,
+        Some("desc") => true,
+        Some(_) => return None,
+    };
+    let valid_column = column
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !column.starts_with(|c: char| c.is_ascii_digit());
+    (parts.next().is_none() && valid_column).then(|| (column.to_string(), descending))
 }
 
 /// Result of parsing join config - may contain deprecation errors
@@ -62,14 +89,24 @@ pub(crate) fn get_join_config(field: &syn::Field) -> JoinConfigResult {
                 match &meta {
                     Meta::List(list_meta) if list_meta.path.is_ident("join") => {
                         config = parse_join_parameters(list_meta);
-                        if let Some(ref c) = config
-                            && c.depth == Some(0)
-                        {
-                            errors.push(syn::Error::new_spanned(
-                                list_meta,
-                                "Join `depth = 0` is invalid (causes infinite recursion). \
-                                     Use `depth = 1` for shallow loading.",
-                            ));
+                        if let Some(ref c) = config {
+                            if c.depth == Some(0) {
+                                errors.push(syn::Error::new_spanned(
+                                    list_meta,
+                                    "Join `depth = 0` is invalid (causes infinite recursion). \
+                                         Use `depth = 1` for shallow loading.",
+                                ));
+                            }
+                            errors.extend(check_child_paging(c, field, list_meta));
+                            if c.create
+                                && !crate::codegen::type_resolution::is_option_type(&field.ty)
+                            {
+                                errors.push(syn::Error::new_spanned(
+                                    list_meta,
+                                    "Join `create` only applies to one-to-one `Option<T>` fields",
+                                ));
+                            }
+                            errors.extend(check_lazy(c, field, list_meta));
                         }
                     }
                     Meta::List(list_meta) if list_meta.path.is_ident("join_filterable") => {
@@ -95,6 +132,71 @@ pub(crate) fn get_join_config(field: &syn::Field) -> JoinConfigResult {
     JoinConfigResult { config, errors }
 }
 
+/// `limit` and `order_by` only make sense for `Vec` children loaded by `join(all)`
+fn check_child_paging(
+    config: &JoinConfig,
+    field: &syn::Field,
+    list_meta: &syn::MetaList,
+) -> Vec<syn::Error> {
+    let mut errors = Vec::new();
+    if let Some(order_by) = &config.order_by
+        && parse_order_by(order_by).is_none()
+    {
+        errors.push(syn::Error::new_spanned(
+            list_meta,
+            format!(
+                "Invalid join `order_by = \"{order_by}\"`. Expected a column name, optionally \
+                 followed by `asc` or `desc`, e.g. `order_by = \"created_at desc\"`"
+            ),
+        ));
+    }
+    if (config.limit.is_some() || config.order_by.is_some())
+        && !crate::codegen::type_resolution::is_vec_type(&field.ty)
+    {
+        errors.push(syn::Error::new_spanned(
+            list_meta,
+            "Join `limit` and `order_by` only apply to `Vec<T>` fields",
+        ));
+    }
+    match config.limit {
+        Some(0) => errors.push(syn::Error::new_spanned(
+            list_meta,
+            "Join `limit = 0` would never load a child. Remove `all` instead.",
+        )),
+        Some(_) if !config.on_all => errors.push(syn::Error::new_spanned(
+            list_meta,
+            "Join `limit` caps children in list responses and requires `all`, \
+             e.g. `join(one, all, limit = 10)`",
+        )),
+        _ => {}
+    }
+    errors
+}
+
+/// `lazy` children are served by their own endpoint, never in list responses
+fn check_lazy(
+    config: &JoinConfig,
+    field: &syn::Field,
+    list_meta: &syn::MetaList,
+) -> Option<syn::Error> {
+    if !config.lazy {
+        None
+    } else if config.on_all {
+        Some(syn::Error::new_spanned(
+            list_meta,
+            "Join `lazy` keeps the field out of list responses and can't be combined with \
+             `all`, e.g. `join(one, lazy)`",
+        ))
+    } else if !crate::codegen::type_resolution::is_vec_type(&field.ty) {
+        Some(syn::Error::new_spanned(
+            list_meta,
+            "Join `lazy` only applies to `Vec<T>` fields",
+        ))
+    } else {
+        None
+    }
+}
+
 /// Create an error for deprecated `join_filterable/join_sortable` syntax
 fn create_join_attr_deprecation_error(
     old_attr: &str,
@@ -147,8 +249,9 @@ fn parse_string_list(meta_list: &syn::MetaList) -> Vec<String> {
 /// Parses the parameters inside join(...) function call
 ///
 /// Supports:
-/// - Flags: `one`, `all`, `on_one`, `on_all`
-/// - Named: `depth = 2`, `relation = "Name"`, `path = "crate::path"`
+/// - Flags: `one`, `all`, `on_one`, `on_all`, `create`, `lazy` (implies `one`)
+/// - Named: `depth = 2`, `relation = "Name"`, `path = "crate::path"`, `limit = 10`,
+///   `order_by = "created_at desc"`
 /// - Nested lists: `filterable("col1", "col2")`, `sortable("col1")`
 fn parse_join_parameters(meta_list: &syn::MetaList) -> Option<JoinConfig> {
     let mut config = JoinConfig::default();
@@ -158,12 +261,17 @@ fn parse_join_parameters(meta_list: &syn::MetaList) -> Option<JoinConfig> {
         Ok(nested_metas) => {
             for meta in nested_metas {
                 match meta {
-                    // Parse flags: one, all, on_one, on_all
+                    // Parse flags: one, all, on_one, on_all, create, lazy
                     Meta::Path(path) => {
                         if path.is_ident("one") || path.is_ident("on_one") {
                             config.on_one = true;
                         } else if path.is_ident("all") || path.is_ident("on_all") {
                             config.on_all = true;
+                        } else if path.is_ident("create") {
+                            config.create = true;
+                        } else if path.is_ident("lazy") {
+                            config.lazy = true;
+                            config.on_one = true;
                         }
                     }
                     // Parse named parameters: depth = 2, relation = "CustomRelation", path = "crate::path::to::module"
@@ -184,6 +292,12 @@ fn parse_join_parameters(meta_list: &syn::MetaList) -> Option<JoinConfig> {
                                 Lit::Str(str_lit) if nv.path.is_ident("fk_column") => {
                                     config.fk_column = Some(str_lit.value());
                                 }
+                                Lit::Int(int_lit) if nv.path.is_ident("limit") => {
+                                    config.limit = int_lit.base10_parse::<u64>().ok();
+                                }
+                                Lit::Str(str_lit) if nv.path.is_ident("order_by") => {
+                                    config.order_by = Some(str_lit.value());
+                                }
                                 _ => {}
                             }
                         }
@@ -212,3 +326,111 @@ fn parse_join_parameters(meta_list: &syn::MetaList) -> Option<JoinConfig> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_parse_order_by() {
+        assert_eq!(
+            parse_order_by("created_at"),
+            Some(("created_at".into(), false))
+        );
+        assert_eq!(
+            parse_order_by("created_at desc"),
+            Some(("created_at".into(), true))
+        );
+        assert_eq!(
+            parse_order_by(" title  ASC "),
+            Some(("title".into(), false))
+        );
+        assert_eq!(parse_order_by(""), None);
+        assert_eq!(parse_order_by("created_at sideways"), None);
+        assert_eq!(parse_order_by("created_at desc nulls"), None);
+        assert_eq!(parse_order_by("created-at"), None);
+        assert_eq!(parse_order_by("1st desc"), None);
+    }
+
+    #[test]
+    fn test_join_limit_and_order_by() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, join(one, all, limit = 10, order_by = "created_at desc"))]
+            pub comments: Vec<Comment>
+        };
+        let result = get_join_config(&field);
+        assert!(result.errors.is_empty());
+        let config = result.unwrap_or_default();
+        assert_eq!(config.limit, Some(10));
+        assert_eq!(config.order_by.as_deref(), Some("created_at desc"));
+    }
+
+    #[test]
+    fn test_join_limit_errors() {
+        let fields: [syn::Field; 4] = [
+            parse_quote! {
+                #[crudcrate(non_db_attr, join(one, limit = 10))]
+                pub comments: Vec<Comment>
+            },
+            parse_quote! {
+                #[crudcrate(non_db_attr, join(all, limit = 0))]
+                pub comments: Vec<Comment>
+            },
+            parse_quote! {
+                #[crudcrate(non_db_attr, join(all, order_by = "created_at"))]
+                pub author: Option<User>
+            },
+            parse_quote! {
+                #[crudcrate(non_db_attr, join(all, order_by = "created_at newest"))]
+                pub comments: Vec<Comment>
+            },
+        ];
+        for field in &fields {
+            assert_eq!(get_join_config(field).errors.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_join_create() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, join(one, all, create))]
+            pub profile: Option<Profile>
+        };
+        let result = get_join_config(&field);
+        assert!(result.errors.is_empty());
+        assert!(result.unwrap_or_default().create);
+
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, join(one, create))]
+            pub comments: Vec<Comment>
+        };
+        assert_eq!(get_join_config(&field).errors.len(), 1);
+    }
+
+    #[test]
+    fn test_join_lazy() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, join(lazy, order_by = "title"))]
+            pub books: Vec<Book>
+        };
+        let result = get_join_config(&field);
+        assert!(result.errors.is_empty());
+        let config = result.unwrap_or_default();
+        assert!(config.lazy && config.on_one && !config.on_all);
+
+        let fields: [syn::Field; 2] = [
+            parse_quote! {
+                #[crudcrate(non_db_attr, join(one, all, lazy))]
+                pub books: Vec<Book>
+            },
+            parse_quote! {
+                #[crudcrate(non_db_attr, join(one, lazy))]
+                pub author: Option<Author>
+            },
+        ];
+        for field in &fields {
+            assert_eq!(get_join_config(field).errors.len(), 1);
+        }
+    }
+}
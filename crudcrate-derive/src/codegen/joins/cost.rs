@@ -0,0 +1,78 @@
+//! Join cost estimate generation
+//!
+//! Generates the `join_cost()` override of `CRUDResource`, which
+//! `crudcrate::complexity` checks against the resource's join budget before the list
+//! and single-item handlers query. It mirrors the loading code: `get_all` batch loads
+//! `join(all)` fields with one query each, `get_one` loads every join field, and joins
+//! deeper than 1 load each related row again through the target's `get_one`.
+
+use crate::codegen::joins::get_join_config;
+use crate::codegen::type_resolution::{extract_api_struct_type_for_recursive_call, is_vec_type};
+use crate::traits::crudresource::structs::EntityFieldAnalysis;
+use quote::quote;
+
+/// Generate the `join_cost()` trait method, or nothing for resources without joins
+pub(crate) fn generate_join_cost_impl(
+    analysis: &EntityFieldAnalysis,
+    api_struct_name: &syn::Ident,
+) -> Option<proc_macro2::TokenStream> {
+    let mut relations = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for field in analysis
+        .join_on_one_fields
+        .iter()
+        .chain(analysis.join_on_all_fields.iter())
+    {
+        let Some(ident) = field.ident.as_ref() else {
+            continue;
+        };
+        if !seen.insert(ident.to_string()) {
+            continue;
+        }
+        let join = get_join_config(field).unwrap_or_default();
+
+        // Lists load `join(all)` fields, `get_one` every join field
+        let on_all = join.on_all;
+
+        let children = if !is_vec_type(&field.ty) {
+            quote! { 1 }
+        } else if let Some(limit) = join.limit {
+            quote! { if list { #limit } else { crudcrate::complexity::ASSUMED_CHILDREN } }
+        } else {
+            quote! { crudcrate::complexity::ASSUMED_CHILDREN }
+        };
+
+        // Self-references and `depth = 1` stop at the related rows
+        let target = extract_api_struct_type_for_recursive_call(&field.ty);
+        let self_referencing = *api_struct_name == target.to_string().trim();
+        let nested = if self_referencing || join.depth == Some(1) {
+            quote! { None }
+        } else {
+            quote! {
+                Some(<#target as crudcrate::CRUDResource>::join_cost(1, false, depth - 1))
+            }
+        };
+
+        relations.push(quote! {
+            if #on_all || !list {
+                cost = cost.relation(items, #children, #nested);
+            }
+        });
+    }
+
+    if relations.is_empty() {
+        return None;
+    }
+
+    Some(quote! {
+        fn join_cost(items: u64, list: bool, depth: u8) -> crudcrate::complexity::JoinCost {
+            let mut cost = crudcrate::complexity::JoinCost::default();
+            if depth == 0 {
+                return cost;
+            }
+            #(#relations)*
+            cost
+        }
+    })
+}
@@ -0,0 +1,52 @@
+//! `relation_filters()` for the `{join}__exists` and `{join}__count` filters.
+//!
+//! Every `Vec` join field is listed with its child's table, the foreign key found by the
+//! same convention as join loading, and the child's scope and read conditions.
+
+use crate::codegen::joins::cascade::cascade_children;
+use crate::codegen::joins::get_join_config;
+use crate::codegen::joins::loading::derive_fk_idents;
+use crate::codegen::type_resolution::{
+    extract_api_struct_type_for_recursive_call, extract_vec_inner_type_ref,
+    get_path_from_field_type,
+};
+use crate::traits::crudresource::structs::EntityFieldAnalysis;
+use quote::quote;
+
+/// The `relation_filters` override, or nothing for resources without `Vec` joins
+pub(crate) fn generate_relation_filters_impl(
+    analysis: &EntityFieldAnalysis,
+    api_struct_name: &syn::Ident,
+) -> Option<proc_macro2::TokenStream> {
+    let children = cascade_children(analysis);
+    if children.is_empty() {
+        return None;
+    }
+
+    let entries = children.into_iter().filter_map(|field| {
+        let join_field = field.ident.as_ref()?.to_string();
+        let join_config = get_join_config(field).unwrap_or_default();
+        let child = extract_vec_inner_type_ref(&field.ty);
+        let is_self_referencing = api_struct_name
+            == extract_api_struct_type_for_recursive_call(&field.ty)
+                .to_string()
+                .trim();
+        let (fk_column, _) = derive_fk_idents(&join_config, api_struct_name, is_self_referencing);
+        let column_path = get_path_from_field_type(&field.ty, "Column");
+        Some(quote! {
+            crudcrate::filtering::RelationFilterDef {
+                join_field: #join_field,
+                table: <#child as crudcrate::CRUDResource>::TABLE_NAME,
+                foreign_key: sea_orm::IdenStatic::as_str(&#column_path::#fk_column),
+                scope: <#child as crudcrate::ScopeFilterable>::scope_condition,
+                read: <#child as crudcrate::CRUDResource>::read_condition,
+            }
+        })
+    });
+
+    Some(quote! {
+        fn relation_filters() -> Vec<crudcrate::filtering::RelationFilterDef> {
+            vec![#(#entries),*]
+        }
+    })
+}
@@ -0,0 +1,67 @@
+//! Relation endpoints of `join(one, lazy)` fields
+//!
+//! Each lazy `Vec` join gets a `GET /{id}/{field}` route on the generated routers, whose
+//! handler lists the children through `crudcrate::relationships::lazy`.
+
+use crate::codegen::joins::get_join_config;
+use crate::codegen::joins::loading::derive_fk_idents;
+use crate::codegen::type_resolution::{
+    extract_api_struct_type_for_recursive_call, get_path_from_field_type,
+};
+use crate::traits::crudresource::structs::EntityFieldAnalysis;
+use quote::{format_ident, quote};
+
+/// One lazy join field's endpoint
+pub(crate) struct LazyRelation {
+    /// Field name, also the last path segment
+    pub(crate) field: String,
+    /// `get_{field}_relation_handler`
+    pub(crate) handler: syn::Ident,
+    /// Child resource type
+    pub(crate) child: proc_macro2::TokenStream,
+    /// Child list model, the response body
+    pub(crate) child_list: proc_macro2::TokenStream,
+    /// Child list model of scoped requests
+    pub(crate) child_scoped_list: proc_macro2::TokenStream,
+    /// Child column holding the parent's ID
+    pub(crate) fk_column: proc_macro2::TokenStream,
+}
+
+/// The endpoints of the resource's `lazy` join fields
+pub(crate) fn lazy_relations(
+    analysis: &EntityFieldAnalysis,
+    api_struct_name: &syn::Ident,
+) -> Vec<LazyRelation> {
+    analysis
+        .join_on_one_fields
+        .iter()
+        .filter_map(|field| {
+            let ident = field.ident.as_ref()?;
+            let join = get_join_config(field).config.filter(|join| join.lazy)?;
+
+            let child = extract_api_struct_type_for_recursive_call(&field.ty);
+            let child_string = child.to_string();
+            let child_name = child_string
+                .split("::")
+                .last()
+                .unwrap_or(&child_string)
+                .trim();
+            let child_list = get_path_from_field_type(&field.ty, &format!("{child_name}List"));
+            let child_scoped_list =
+                get_path_from_field_type(&field.ty, &format!("{child_name}ScopedList"));
+
+            let self_referencing = *api_struct_name == child_name;
+            let (fk_pascal, _) = derive_fk_idents(&join, api_struct_name, self_referencing);
+            let column_path = get_path_from_field_type(&field.ty, "Column");
+
+            Some(LazyRelation {
+                field: ident.to_string(),
+                handler: format_ident!("get_{}_relation_handler", ident),
+                child,
+                child_list,
+                child_scoped_list,
+                fk_column: quote! { #column_path::#fk_pascal },
+            })
+        })
+        .collect()
+}
@@ -48,7 +48,7 @@ const MAX_JOIN_DEPTH: u8 = 5;
 use crate::codegen::joins::get_join_config;
 use crate::codegen::type_resolution::{
     extract_api_struct_type_for_recursive_call, extract_option_or_direct_inner_type,
-    get_path_from_field_type, is_vec_type,
+    get_path_from_field_type, is_boxed_option_type, is_vec_type,
 };
 use crate::traits::crudresource::structs::EntityFieldAnalysis;
 use quote::quote;
@@ -83,7 +83,12 @@ fn generate_get_one_join_loading_inner(
         return quote! { Self::from(model) };
     }
 
-    // Deduplicate fields (some may have both join(one) and join(all))
+    let join_fields = unique_join_fields(analysis);
+    generate_join_loading_impl(&join_fields, "get_one", api_struct_name, scoped)
+}
+
+/// Every join field once, though some have both join(one) and join(all)
+pub(crate) fn unique_join_fields<'a>(analysis: &EntityFieldAnalysis<'a>) -> Vec<&'a syn::Field> {
     let mut seen_fields = std::collections::HashSet::new();
     let mut join_fields: Vec<&syn::Field> = Vec::new();
 
@@ -100,8 +105,7 @@ fn generate_get_one_join_loading_inner(
             join_fields.push(field);
         }
     }
-
-    generate_join_loading_impl(&join_fields, "get_one", api_struct_name, scoped)
+    join_fields
 }
 
 /// Generate batch loading code for `get_all()` method
@@ -153,6 +157,82 @@ pub fn generate_get_all_scoped_batch_loading(
     generate_batch_loading_impl(&join_fields, api_struct_name, &pk_ident, true)
 }
 
+/// Generate `load_{field}(db, ids)` for each join field, batch-loading that field for
+/// many parents at once with the same queries as `get_all()`
+///
+/// For use outside HTTP handlers, so child scopes don't apply:
+/// ```ignore
+/// let vehicles: HashMap<Uuid, Vec<Vehicle>> = Customer::load_vehicles(db, &ids).await?;
+/// ```
+pub(crate) fn generate_join_loaders(
+    analysis: &EntityFieldAnalysis,
+    api_struct_name: &syn::Ident,
+) -> Option<proc_macro2::TokenStream> {
+    let join_fields = unique_join_fields(analysis);
+    if join_fields.is_empty() {
+        return None;
+    }
+    let pk_ident = analysis
+        .primary_key_field
+        .and_then(|f| f.ident.as_ref())
+        .cloned()
+        .unwrap_or_else(|| quote::format_ident!("id"));
+    let loads = match generate_batch_loads(&join_fields, api_struct_name, &pk_ident, false) {
+        Ok(loads) => loads,
+        Err(error) => return Some(error),
+    };
+
+    let loaders = join_fields.iter().zip(loads).filter_map(|(field, load)| {
+        let field_name = field.ident.as_ref()?;
+        let loader = quote::format_ident!("load_{}", field_name);
+        let map_var = quote::format_ident!("{}_by_parent", field_name);
+        let doc = format!(
+            " Batch-load `{field_name}` for each of `ids`, keyed by parent ID. Parents \
+             without any are left out."
+        );
+        let BatchLoad {
+            statement,
+            value_type,
+            ..
+        } = load;
+        // Vec joins select children by parent ID; Option joins follow the relation
+        // from the parent rows
+        let parents = if is_vec_type(&field.ty) {
+            quote! { let parent_ids: Vec<uuid::Uuid> = ids.to_vec(); }
+        } else {
+            quote! {
+                use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+                let models = <Self as crudcrate::CRUDResource>::EntityType::find()
+                    .filter(<Self as crudcrate::CRUDResource>::ID_COLUMN.is_in(ids.iter().copied()))
+                    .all(db)
+                    .await?;
+            }
+        };
+        Some(quote! {
+            #[doc = #doc]
+            ///
+            /// # Errors
+            /// Returns `ApiError::Database` if a query fails.
+            #[allow(unused_mut)]
+            pub async fn #loader(
+                db: &sea_orm::DatabaseConnection,
+                ids: &[uuid::Uuid],
+            ) -> Result<std::collections::HashMap<uuid::Uuid, #value_type>, crudcrate::ApiError> {
+                #parents
+                #statement
+                Ok(#map_var)
+            }
+        })
+    });
+
+    Some(quote! {
+        impl #api_struct_name {
+            #(#loaders)*
+        }
+    })
+}
+
 /// Generate optimized batch loading code for `get_all()`
 ///
 /// Returns (`pre_loop_code`, `in_loop_code`) where:
@@ -168,8 +248,59 @@ fn generate_batch_loading_impl(
     pk_ident: &syn::Ident,
     scoped: bool,
 ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
-    let mut batch_loading_statements = Vec::new();
-    let mut field_assignments = Vec::new();
+    let loads = match generate_batch_loads(join_fields, api_struct_name, pk_ident, scoped) {
+        Ok(loads) => loads,
+        Err(error) => return (error, quote! {}),
+    };
+    let batch_loading_statements = loads.iter().map(|load| &load.statement);
+    let field_assignments = loads.iter().map(|load| &load.assignment);
+
+    // Only Vec<T> joins filter by parent ID; Option<T> joins go through `load_one`
+    let parent_ids = join_fields
+        .iter()
+        .any(|field| is_vec_type(&field.ty))
+        .then(|| {
+            quote! {
+                // Collect all parent IDs for batch loading
+                let parent_ids: Vec<uuid::Uuid> = models.iter().map(|m| m.#pk_ident).collect();
+            }
+        });
+    let pre_loop_code = quote! {
+        #parent_ids
+
+        #( #batch_loading_statements )*
+    };
+
+    let in_loop_code = quote! {
+        let parent_id = model.#pk_ident;
+        let mut item = Self::from(model);
+        #( #field_assignments )*
+        item
+    };
+
+    (pre_loop_code, in_loop_code)
+}
+
+/// One join field's batch load
+struct BatchLoad {
+    /// Binds `{field}_by_parent`, a map from parent ID to the loaded value. Vec joins
+    /// read `parent_ids`, Option joins the parent `models`.
+    statement: proc_macro2::TokenStream,
+    /// Moves a parent's value from the map onto `item`
+    assignment: proc_macro2::TokenStream,
+    /// The map's value type
+    value_type: proc_macro2::TokenStream,
+}
+
+/// The batch load of each join field, or a `compile_error!` for an invalid one
+#[allow(clippy::too_many_lines)]
+fn generate_batch_loads(
+    join_fields: &[&syn::Field],
+    api_struct_name: &syn::Ident,
+    pk_ident: &syn::Ident,
+    scoped: bool,
+) -> Result<Vec<BatchLoad>, proc_macro2::TokenStream> {
+    let mut loads = Vec::new();
 
     for field in join_fields {
         let Some(field_name) = &field.ident else {
@@ -192,7 +323,7 @@ fn generate_batch_loading_impl(
                 let error_msg = format!(
                     "Self-referencing field '{field_name}' in struct '{api_struct_name}' has depth={original_depth}, but self-references only support depth=1"
                 );
-                return (quote! { compile_error!(#error_msg); }, quote! {});
+                return Err(quote! { compile_error!(#error_msg); });
             }
             1
         } else {
@@ -214,7 +345,7 @@ fn generate_batch_loading_impl(
             } else {
                 let error_msg =
                     format!("Invalid join path '{custom_path}' for field '{field_name}'");
-                return (quote! { compile_error!(#error_msg); }, quote! {});
+                return Err(quote! { compile_error!(#error_msg); });
             }
         } else {
             (
@@ -233,6 +364,10 @@ fn generate_batch_loading_impl(
         // HashMap variable for storing batch-loaded data
         let map_var = quote::format_ident!("{}_by_parent", field_name);
 
+        // `order_by` sorts the batch query, so each parent's children arrive in order and
+        // `limit` can keep the first N while grouping
+        let child_order = generate_child_order(&join_config, &column_path);
+
         // When scoped, compute the child's ScopeFilterable::scope_condition()
         // once per batch query and apply it to both the SQL-level filter and
         // the depth > 1 recursive fetch.
@@ -261,6 +396,14 @@ fn generate_batch_loading_impl(
 
         if is_vec_field {
             let api_struct_type = extract_api_struct_type_for_recursive_call(&field.ty);
+            let read_filter = generate_child_read_filter(&api_struct_type);
+            let limit_guard = generate_child_limit_guard(join_config.limit, &quote! { fk_value });
+            let limit_guard_parent =
+                generate_child_limit_guard(join_config.limit, &quote! { parent_id });
+            let assignment = quote! {
+                item.#field_name = #map_var.remove(&parent_id).unwrap_or_default();
+            };
+            let value_type = quote! { Vec<#api_struct_type> };
 
             if depth_limited {
                 // Depth=1: Simple batch load without recursion
@@ -268,55 +411,65 @@ fn generate_batch_loading_impl(
                 // preventing async state machine bloat when multiple joins accumulate.
                 if is_self_referencing {
                     // Self-referencing: FK column derived via derive_fk_idents
-                    batch_loading_statements.push(quote! {
-                        let mut #map_var: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> = Box::pin(async {
-                            use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
-
-                            let query = #entity_path::find()
-                                .filter(#column_path::#fk_column_pascal.is_in(parent_ids.clone()));
-                            #scope_filter_for_vec
-                            let all_related = query.all(db).await?;
-
-                            let mut map: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> =
-                                std::collections::HashMap::new();
-                            for related_model in all_related {
-                                if let Some(parent_id) = related_model.#fk_field_snake {
-                                    map.entry(parent_id)
-                                        .or_insert_with(Vec::new)
-                                        .push(#api_struct_type::from(related_model));
+                    loads.push(BatchLoad {
+                        assignment,
+                        value_type,
+                        statement: quote! {
+                            let mut #map_var: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> = Box::pin(async {
+                                use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
+
+                                let query = #entity_path::find()
+                                    .filter(#column_path::#fk_column_pascal.is_in(parent_ids.clone()));
+                                #read_filter
+                                #scope_filter_for_vec
+                                #child_order
+                                let all_related = query.all(db).await?;
+
+                                let mut map: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> =
+                                    std::collections::HashMap::new();
+                                for related_model in all_related {
+                                    if let Some(parent_id) = related_model.#fk_field_snake {
+                                        #limit_guard_parent
+                                        map.entry(parent_id)
+                                            .or_insert_with(Vec::new)
+                                            .push(#api_struct_type::from(related_model));
+                                    }
                                 }
-                            }
-                            Ok::<_, crudcrate::ApiError>(map)
-                        }).await?;
+                                Ok::<_, crudcrate::ApiError>(map)
+                            }).await?;
+                        },
                     });
                 } else {
                     // Regular join: use derived FK column name
                     // Column enum uses PascalCase (CustomerId), field uses snake_case (customer_id)
-                    batch_loading_statements.push(quote! {
-                        let mut #map_var: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> = Box::pin(async {
-                            use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
-
-                            let query = #entity_path::find()
-                                .filter(#column_path::#fk_column_pascal.is_in(parent_ids.clone()));
-                            #scope_filter_for_vec
-                            let all_related = query.all(db).await?;
-
-                            let mut map: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> =
-                                std::collections::HashMap::new();
-                            for related_model in all_related {
-                                let fk_value = related_model.#fk_field_snake;
-                                map.entry(fk_value)
-                                    .or_insert_with(Vec::new)
-                                    .push(#api_struct_type::from(related_model));
-                            }
-                            Ok::<_, crudcrate::ApiError>(map)
-                        }).await?;
+                    loads.push(BatchLoad {
+                        assignment,
+                        value_type,
+                        statement: quote! {
+                            let mut #map_var: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> = Box::pin(async {
+                                use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
+
+                                let query = #entity_path::find()
+                                    .filter(#column_path::#fk_column_pascal.is_in(parent_ids.clone()));
+                                #read_filter
+                                #scope_filter_for_vec
+                                #child_order
+                                let all_related = query.all(db).await?;
+
+                                let mut map: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> =
+                                    std::collections::HashMap::new();
+                                for related_model in all_related {
+                                    let fk_value = related_model.#fk_field_snake;
+                                    #limit_guard
+                                    map.entry(fk_value)
+                                        .or_insert_with(Vec::new)
+                                        .push(#api_struct_type::from(related_model));
+                                }
+                                Ok::<_, crudcrate::ApiError>(map)
+                            }).await?;
+                        },
                     });
                 }
-
-                field_assignments.push(quote! {
-                    item.#field_name = #map_var.remove(&parent_id).unwrap_or_default();
-                });
             } else {
                 // Depth > 1: Need recursive loading via get_one() / get_one_scoped()
                 // When scoped, recurse via get_one_scoped so grandchildren are also
@@ -326,14 +479,14 @@ fn generate_batch_loading_impl(
                 let recursive_fetch = if scoped {
                     quote! {
                         let entity = match __child_scope.as_ref() {
-                            Some(cs) => match #api_struct_type::get_one_scoped(db, related_model.id, cs).await {
+                            Some(cs) => match <#api_struct_type as crudcrate::CRUDResource>::get_one_scoped(db, related_model.id, cs).await {
                                 Ok(e) => e,
                                 Err(e) => {
                                     tracing::warn!(error = %e, "Failed to load nested scoped relations, using flat model");
                                     #api_struct_type::from(related_model)
                                 }
                             },
-                            None => match #api_struct_type::get_one(db, related_model.id).await {
+                            None => match <#api_struct_type as crudcrate::CRUDResource>::get_one(db, related_model.id).await {
                                 Ok(e) => e,
                                 Err(e) => {
                                     tracing::warn!(error = %e, "Failed to load nested relations, using flat model");
@@ -344,7 +497,7 @@ fn generate_batch_loading_impl(
                     }
                 } else {
                     quote! {
-                        let entity = match #api_struct_type::get_one(db, related_model.id).await {
+                        let entity = match <#api_struct_type as crudcrate::CRUDResource>::get_one(db, related_model.id).await {
                             Ok(e) => e,
                             Err(e) => {
                                 tracing::warn!(error = %e, "Failed to load nested relations, using flat model");
@@ -354,13 +507,18 @@ fn generate_batch_loading_impl(
                     }
                 };
 
-                batch_loading_statements.push(quote! {
+                loads.push(BatchLoad {
+                    assignment,
+                    value_type,
+                    statement: quote! {
                         let mut #map_var: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> = Box::pin(async {
                             use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
 
                             let query = #entity_path::find()
                                 .filter(#column_path::#fk_column_pascal.is_in(parent_ids.clone()));
+                            #read_filter
                             #scope_filter_for_vec
+                            #child_order
                             let all_related_models: Vec<#model_path> = query.all(db).await?;
 
                             let mut map: std::collections::HashMap<uuid::Uuid, Vec<#api_struct_type>> =
@@ -369,6 +527,7 @@ fn generate_batch_loading_impl(
                             // For each related model, call get_one() / get_one_scoped() to load nested relations
                             for related_model in all_related_models {
                                 let fk_value = related_model.#fk_field_snake;
+                                #limit_guard
                                 #recursive_fetch
                                 map.entry(fk_value)
                                     .or_insert_with(Vec::new)
@@ -376,86 +535,118 @@ fn generate_batch_loading_impl(
                             }
                             Ok::<_, crudcrate::ApiError>(map)
                         }).await?;
-                    });
-
-                field_assignments.push(quote! {
-                    item.#field_name = #map_var.remove(&parent_id).unwrap_or_default();
+                    },
                 });
             }
         } else {
-            // Option<T> relationships (belongs_to/has_one)
-            // These are typically 1:1 and benefit less from batch loading,
-            // but we can still optimize if there are many parents
+            // Option<T> / Option<Box<T>> relationships (belongs_to/has_one). `load_one`
+            // follows the relation definition, so the FK may live on either entity.
             let target_type = extract_option_or_direct_inner_type(&field.ty);
-
-            if depth_limited {
-                batch_loading_statements.push(quote! {
-                    let mut #map_var: std::collections::HashMap<uuid::Uuid, #target_type> = Box::pin(async {
-                        use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
-
-                        let all_related = #entity_path::find()
-                            .filter(#column_path::#fk_column_pascal.is_in(parent_ids.clone()))
-                            .all(db)
-                            .await?;
-
-                        let mut map: std::collections::HashMap<uuid::Uuid, #target_type> =
-                            std::collections::HashMap::new();
-                        for related_model in all_related {
-                            let fk_value = related_model.#fk_field_snake;
-                            map.insert(fk_value, #target_type::from(related_model));
-                        }
-                        Ok::<_, crudcrate::ApiError>(map)
-                    }).await?;
-                });
+            let boxed = is_boxed_option_type(&field.ty);
+            let map_type = if boxed {
+                quote! { Box<#target_type> }
             } else {
-                batch_loading_statements.push(quote! {
-                    let mut #map_var: std::collections::HashMap<uuid::Uuid, #target_type> = Box::pin(async {
-                        use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
-
-                        let all_related_models: Vec<#model_path> = #entity_path::find()
-                            .filter(#column_path::#fk_column_pascal.is_in(parent_ids.clone()))
-                            .all(db)
-                            .await?;
-
-                        let mut map: std::collections::HashMap<uuid::Uuid, #target_type> =
+                quote! { #target_type }
+            };
+            let wrap = boxed.then(|| quote! { Box::new });
+            let read_filter = generate_child_read_filter(&target_type);
+            let assignment = quote! {
+                item.#field_name = #map_var.remove(&parent_id);
+            };
+            let value_type = map_type.clone();
+            let entity = if depth_limited {
+                quote! { #target_type::from(related_model) }
+            } else {
+                quote! {
+                    match <#target_type as crudcrate::CRUDResource>::get_one(db, related_model.id).await {
+                        Ok(e) => e,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Failed to load nested relations, using flat model");
+                            #target_type::from(related_model)
+                        }
+                    }
+                }
+            };
+
+            loads.push(BatchLoad {
+                assignment,
+                value_type,
+                statement: quote! {
+                    let mut #map_var: std::collections::HashMap<uuid::Uuid, #map_type> = Box::pin(async {
+                        let query = <#entity_path as sea_orm::EntityTrait>::find();
+                        #read_filter
+                        let all_related = sea_orm::LoaderTrait::load_one(&models, query, db).await?;
+
+                        let mut map: std::collections::HashMap<uuid::Uuid, #map_type> =
                             std::collections::HashMap::new();
-                        for related_model in all_related_models {
-                            let fk_value = related_model.#fk_field_snake;
-                            let entity = match #target_type::get_one(db, related_model.id).await {
-                                Ok(e) => e,
-                                Err(e) => {
-                                    tracing::warn!(error = %e, "Failed to load nested relations, using flat model");
-                                    #target_type::from(related_model)
-                                }
-                            };
-                            map.insert(fk_value, entity);
+                        for (parent, related_model) in models.iter().zip(all_related) {
+                            if let Some(related_model) = related_model {
+                                let related_model: #model_path = related_model;
+                                map.insert(parent.#pk_ident, #wrap(#entity));
+                            }
                         }
                         Ok::<_, crudcrate::ApiError>(map)
                     }).await?;
-                });
-            }
-
-            field_assignments.push(quote! {
-                item.#field_name = #map_var.remove(&parent_id);
+                },
             });
         }
     }
 
-    let pre_loop_code = quote! {
-        // Collect all parent IDs for batch loading
-        let parent_ids: Vec<uuid::Uuid> = models.iter().map(|m| m.#pk_ident).collect();
+    Ok(loads)
+}
 
-        #( #batch_loading_statements )*
-    };
+/// `let query = query.filter(...)` with the related resource's `read_condition()`, so
+/// rows its `read::many::condition` hides are never embedded in parents
+fn generate_child_read_filter(related: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        let query = match <#related as crudcrate::CRUDResource>::read_condition() {
+            Some(read) => sea_orm::QueryFilter::filter(query, read),
+            None => query,
+        };
+    }
+}
 
-    let in_loop_code = quote! {
-        let parent_id = model.#pk_ident;
-        let mut item = Self::from(model);
-        #( #field_assignments )*
-        item
+/// `let query = query.order_by(...)` for a join's `order_by`, or nothing
+fn generate_child_order(
+    join_config: &crate::codegen::joins::config::JoinConfig,
+    column_path: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    use convert_case::{Case, Casing};
+
+    // Invalid values were already reported by `get_join_config`
+    let Some((column, descending)) = join_config
+        .order_by
+        .as_deref()
+        .and_then(crate::codegen::joins::config::parse_order_by)
+    else {
+        return quote! {};
     };
+    let column = quote::format_ident!("{}", column.to_case(Case::Pascal));
+    let order = if descending {
+        quote! { sea_orm::Order::Desc }
+    } else {
+        quote! { sea_orm::Order::Asc }
+    };
+    quote! {
+        let query = sea_orm::QueryOrder::order_by(query, #column_path::#column, #order);
+    }
+}
 
-    (pre_loop_code, in_loop_code)
+/// Skips a batch-loaded child once its parent (`key`) already has `limit` children.
+/// Expects the grouping loop's `map`.
+fn generate_child_limit_guard(
+    limit: Option<u64>,
+    key: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let Some(limit) = limit else {
+        return quote! {};
+    };
+    let limit = proc_macro2::Literal::u64_unsuffixed(limit);
+    quote! {
+        if map.get(&#key).is_some_and(|children| children.len() >= #limit) {
+            continue;
+        }
+    }
 }
 
 /// Convert `PascalCase` to `snake_case`
@@ -472,7 +663,7 @@ fn to_snake_case(s: &str) -> String {
 /// 1. Explicit `fk_column = "..."` from join config (highest priority)
 /// 2. Self-referencing: `ParentId` / `parent_id`
 /// 3. Convention: `{ParentStructName}Id` / `{parent_struct_name}_id`
-fn derive_fk_idents(
+pub(crate) fn derive_fk_idents(
     join_config: &crate::codegen::joins::config::JoinConfig,
     api_struct_name: &syn::Ident,
     is_self_referencing: bool,
@@ -501,6 +692,7 @@ fn derive_fk_idents(
 /// When `scoped` is true, Vec<T> join queries include the child entity's
 /// `ScopeFilterable::scope_condition()` as an additional WHERE clause,
 /// filtering private children at the SQL level.
+#[allow(clippy::too_many_lines)]
 fn generate_join_loading_impl(
     join_fields: &[&syn::Field],
     _context: &str,
@@ -612,18 +804,22 @@ fn generate_join_loading_impl(
         if is_vec_field {
             // Vec<T> relationships (has_many)
             let api_struct_type = extract_api_struct_type_for_recursive_call(&field.ty);
+            let read_filter = generate_child_read_filter(&api_struct_type);
 
             if depth_limited {
                 // Depth=1: Load data, no recursion
                 let loaded_var = quote::format_ident!("loaded_{}", field_name);
                 let column_path = get_path_from_field_type(&field.ty, "Column");
+                let child_order = generate_child_order(&join_config, &column_path);
 
                 loading_statements.push(quote! {
                     let #loaded_var: Vec<#api_struct_type> = {
                         use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
                         let query = #entity_path::find()
                             .filter(#column_path::#fk_column_pascal.eq(model.id));
+                        #read_filter
                         #scope_filter
+                        #child_order
                         let related_models = Box::pin(query.all(db)).await?;
                         related_models
                             .into_iter()
@@ -638,6 +834,7 @@ fn generate_join_loading_impl(
                 //   so grandchildren remain filtered.
                 // - Unscoped: use get_one (existing behaviour).
                 let column_path = get_path_from_field_type(&field.ty, "Column");
+                let child_order = generate_child_order(&join_config, &column_path);
                 let recursive_fetch = if let Some(child_list_type) = &child_list_type_path {
                     quote! {
                         let __child_scope = <#child_list_type as crudcrate::ScopeFilterable>::scope_condition();
@@ -675,7 +872,9 @@ fn generate_join_loading_impl(
                         use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
                         let query = #entity_path::find()
                             .filter(#column_path::#fk_column_pascal.eq(model.id));
+                        #read_filter
                         #scope_filter
+                        #child_order
                         let related_models = Box::pin(query.all(db)
                         ).await?;
                         let mut result = Vec::new();
@@ -693,15 +892,19 @@ fn generate_join_loading_impl(
             // the FK direction varies: belongs_to has FK on self, has_one has FK on related.
             // find_related() resolves this correctly via the Related<E> trait definition.
             let target_type = extract_option_or_direct_inner_type(&field.ty);
+            let wrap = is_boxed_option_type(&field.ty).then(|| quote! { Box::new });
+            let read_filter = generate_child_read_filter(&target_type);
 
             if depth_limited {
                 // Depth=1: Load data, no recursion
                 let loaded_var = quote::format_ident!("loaded_{}", field_name);
                 loading_statements.push(quote! {
-                    let #loaded_var = Box::pin(
-                        model.find_related(#entity_path).one(db)
-                    ).await?
-                    .map(|m: #model_path| #target_type::from(m));
+                    let #loaded_var = {
+                        let query = model.find_related(#entity_path);
+                        #read_filter
+                        Box::pin(query.one(db)).await?
+                    }
+                    .map(|m: #model_path| #wrap(#target_type::from(m)));
                 });
                 field_assignments.push(quote! {
                     result.#field_name = #loaded_var;
@@ -714,17 +917,17 @@ fn generate_join_loading_impl(
                         let __child_scope = <#child_list_type as crudcrate::ScopeFilterable>::scope_condition();
                         match __child_scope {
                             Some(cs) => match #target_type::get_one_scoped(db, related_model.id, &cs).await {
-                                Ok(entity) => Some(entity),
+                                Ok(entity) => Some(#wrap(entity)),
                                 Err(e) => {
                                     tracing::warn!(error = %e, "Failed to load nested scoped relations, using flat model");
-                                    Some(related_model.into())
+                                    Some(#wrap(#target_type::from(related_model)))
                                 }
                             },
                             None => match #target_type::get_one(db, related_model.id).await {
-                                Ok(entity) => Some(entity),
+                                Ok(entity) => Some(#wrap(entity)),
                                 Err(e) => {
                                     tracing::warn!(error = %e, "Failed to load nested relations, using flat model");
-                                    Some(related_model.into())
+                                    Some(#wrap(#target_type::from(related_model)))
                                 }
                             },
                         }
@@ -732,19 +935,22 @@ fn generate_join_loading_impl(
                 } else {
                     quote! {
                         match #target_type::get_one(db, related_model.id).await {
-                            Ok(entity) => Some(entity),
+                            Ok(entity) => Some(#wrap(entity)),
                             Err(e) => {
                                 tracing::warn!(error = %e, "Failed to load nested relations, using flat model");
-                                Some(related_model.into())
+                                Some(#wrap(#target_type::from(related_model)))
                             }
                         }
                     }
                 };
 
                 loading_statements.push(quote! {
-                    let #field_name = match Box::pin(
-                        model.find_related(#entity_path).one(db)
-                    ).await? {
+                    let related = {
+                        let query = model.find_related(#entity_path);
+                        #read_filter
+                        Box::pin(query.one(db)).await?
+                    };
+                    let #field_name = match related {
                         Some(related_model) => { #recursive_option_fetch }
                         None => None,
                     };
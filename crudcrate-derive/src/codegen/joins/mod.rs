@@ -1,7 +1,13 @@
 //! Join relationship configuration and loading code generation.
 
+pub mod cascade;
 pub mod config;
+pub mod cost;
+pub mod exists;
+pub mod lazy;
 pub mod loading;
+pub mod nested_create;
+pub mod polymorphic;
 
 pub use config::JoinConfig;
 pub(crate) use config::get_join_config;
@@ -0,0 +1,72 @@
+//! Nested creation for one-to-one `join(create)` fields.
+//!
+//! The related row arrives inside the parent's create model as JSON. The default
+//! `create`/`create_many` take it out before building the parent, insert the parent,
+//! then insert the related row with its foreign key set to the parent's new key, all in
+//! one transaction. The foreign key follows the same convention as join loading.
+
+use crate::codegen::joins::get_join_config;
+use crate::codegen::joins::loading::derive_fk_idents;
+use crate::codegen::models::should_include_in_model;
+use crate::codegen::type_resolution::{
+    extract_api_struct_type_for_recursive_call, extract_option_or_direct_inner_type,
+};
+use crate::traits::crudresource::structs::EntityFieldAnalysis;
+use quote::{format_ident, quote};
+
+/// Statements for a resource's `join(create)` fields
+pub(crate) struct NestedCreates {
+    /// Move each nested value out of `create_model`
+    pub takes: Vec<proc_macro2::TokenStream>,
+    /// Insert each nested value on `txn`, referencing `parent_id`
+    pub inserts: Vec<proc_macro2::TokenStream>,
+}
+
+/// `None` when the resource has no `join(create)` fields
+pub(crate) fn generate_nested_creates(
+    analysis: &EntityFieldAnalysis,
+    api_struct_name: &syn::Ident,
+) -> Option<NestedCreates> {
+    let mut takes = Vec::new();
+    let mut inserts = Vec::new();
+
+    for field in &analysis.non_db_fields {
+        let Some(ident) = field.ident.as_ref() else {
+            continue;
+        };
+        let Some(join_config) = get_join_config(field).config.filter(|config| config.create) else {
+            continue;
+        };
+        if !should_include_in_model(field, "create_model") {
+            continue;
+        }
+
+        let related = extract_option_or_direct_inner_type(&field.ty);
+        let is_self_referencing = api_struct_name
+            == extract_api_struct_type_for_recursive_call(&field.ty)
+                .to_string()
+                .trim();
+        let (_, fk_field) = derive_fk_idents(&join_config, api_struct_name, is_self_referencing);
+        let fk_name = fk_field.to_string();
+        let field_name = ident.to_string();
+        let nested_var = format_ident!("nested_{}", ident);
+
+        takes.push(quote! {
+            let #nested_var = create_model.#ident.take();
+        });
+        inserts.push(quote! {
+            if let Some(value) = #nested_var {
+                let nested = crudcrate::relationships::nested_create_model::<
+                    <#related as crudcrate::CRUDResource>::CreateModel,
+                >(#field_name, value, #fk_name, serde_json::json!(parent_id))?;
+                let active_model =
+                    <#related as crudcrate::CRUDResource>::create_active_model(&txn, nested).await?;
+                <<#related as crudcrate::CRUDResource>::EntityType as sea_orm::EntityTrait>::insert(active_model)
+                    .exec(&txn)
+                    .await?;
+            }
+        });
+    }
+
+    (!takes.is_empty()).then_some(NestedCreates { takes, inserts })
+}
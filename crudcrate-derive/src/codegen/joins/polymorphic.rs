@@ -0,0 +1,422 @@
+//! Polymorphic joins: a (`subject_type`, `subject_id`) column pair that can point at
+//! rows of several resources.
+//!
+//! ```ignore
+//! pub subject_type: String,
+//! pub subject_id: Uuid,
+//!
+//! #[sea_orm(ignore)]
+//! #[crudcrate(non_db_attr, polymorphic_join(types = ["Customer", "Vehicle"]))]
+//! pub subject: Option<serde_json::Value>,
+//! ```
+//!
+//! The type column stores the last segment of one of the `types` paths. On read, the
+//! field is filled with the serialized target (one query per type for list pages); on
+//! create and update, the pair is checked against the listed types and the target table.
+
+use crate::codegen::models::should_include_in_model;
+use crate::fields::field_is_optional;
+use crate::traits::crudresource::structs::EntityFieldAnalysis;
+use convert_case::{Case, Casing};
+use quote::{format_ident, quote};
+use syn::{Lit, Meta, parse::Parser, punctuated::Punctuated, token::Comma};
+
+/// Configuration of a `polymorphic_join(...)` field
+#[derive(Debug, Clone)]
+pub struct PolymorphicConfig {
+    /// API struct paths of the possible targets
+    pub types: Vec<syn::Path>,
+    /// Column holding the target's type name, `{field}_type` by default
+    pub type_column: syn::Ident,
+    /// Column holding the target's ID, `{field}_id` by default
+    pub id_column: syn::Ident,
+}
+
+impl PolymorphicConfig {
+    /// Stored type names with their API struct paths
+    fn targets(&self) -> impl Iterator<Item = (String, &syn::Path)> {
+        self.types.iter().map(|path| {
+            let name = path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default();
+            (name, path)
+        })
+    }
+}
+
+/// Parses `#[crudcrate(polymorphic_join(types = [...], type_column = "...", id_column = "..."))]`.
+///
+/// Returns `Ok(None)` if the field has no `polymorphic_join`.
+pub(crate) fn get_polymorphic_config(
+    field: &syn::Field,
+) -> Result<Option<PolymorphicConfig>, syn::Error> {
+    for attr in &field.attrs {
+        if attr.path().is_ident("crudcrate")
+            && let Meta::List(meta_list) = &attr.meta
+            && let Ok(metas) =
+                Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())
+        {
+            for meta in metas {
+                if let Meta::List(list) = meta
+                    && list.path.is_ident("polymorphic_join")
+                {
+                    return parse_polymorphic_list(field, &list).map(Some);
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn parse_polymorphic_list(
+    field: &syn::Field,
+    list: &syn::MetaList,
+) -> Result<PolymorphicConfig, syn::Error> {
+    let field_name = field
+        .ident
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let metas = Punctuated::<Meta, Comma>::parse_terminated.parse2(list.tokens.clone())?;
+    let mut types = Vec::new();
+    let mut type_column = format_ident!("{}_type", field_name);
+    let mut id_column = format_ident!("{}_id", field_name);
+
+    for meta in metas {
+        let Meta::NameValue(nv) = &meta else {
+            return Err(syn::Error::new_spanned(
+                &meta,
+                "Expected `types = [...]`, `type_column = \"...\"` or `id_column = \"...\"` inside `polymorphic_join(...)`",
+            ));
+        };
+        match nv.path.get_ident().map(ToString::to_string).as_deref() {
+            Some("types") => {
+                let syn::Expr::Array(array) = &nv.value else {
+                    return Err(syn::Error::new_spanned(
+                        &nv.value,
+                        "`types` must be a list of type names, e.g. `types = [\"Customer\", \"Vehicle\"]`",
+                    ));
+                };
+                for element in &array.elems {
+                    let syn::Expr::Lit(syn::ExprLit {
+                        lit: Lit::Str(name),
+                        ..
+                    }) = element
+                    else {
+                        return Err(syn::Error::new_spanned(
+                            element,
+                            "Expected a type name string",
+                        ));
+                    };
+                    types.push(name.parse::<syn::Path>()?);
+                }
+            }
+            Some(key @ ("type_column" | "id_column")) => {
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(column),
+                    ..
+                }) = &nv.value
+                else {
+                    return Err(syn::Error::new_spanned(
+                        &nv.value,
+                        format!("`{key}` must be a column name string"),
+                    ));
+                };
+                let column = column.parse::<syn::Ident>()?;
+                if key == "type_column" {
+                    type_column = column;
+                } else {
+                    id_column = column;
+                }
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &nv.path,
+                    "Unknown `polymorphic_join(...)` option. Expected `types`, `type_column` or `id_column`",
+                ));
+            }
+        }
+    }
+
+    if types.is_empty() {
+        return Err(syn::Error::new_spanned(
+            list,
+            "`polymorphic_join(...)` needs at least one target, e.g. `types = [\"Customer\"]`",
+        ));
+    }
+    Ok(PolymorphicConfig {
+        types,
+        type_column,
+        id_column,
+    })
+}
+
+/// Check that every polymorphic field's type and ID columns are fields of the model
+pub(crate) fn check_polymorphic_columns(analysis: &EntityFieldAnalysis) -> Vec<syn::Error> {
+    let mut errors = Vec::new();
+    for (field, config) in &analysis.polymorphic_fields {
+        for column in [&config.type_column, &config.id_column] {
+            if db_field(analysis, column).is_none() {
+                errors.push(syn::Error::new_spanned(
+                    field,
+                    format!("polymorphic_join column `{column}` is not a field of this model"),
+                ));
+            }
+        }
+    }
+    errors
+}
+
+fn db_field<'a>(analysis: &EntityFieldAnalysis<'a>, column: &syn::Ident) -> Option<&'a syn::Field> {
+    analysis
+        .db_fields
+        .iter()
+        .copied()
+        .find(|field| field.ident.as_ref() == Some(column))
+}
+
+/// `(Option<&str>, Option<Uuid>)` from the type and ID column values (e.g.
+/// `result.subject_type`), for either plain or `Option` columns
+fn pair_expr(
+    analysis: &EntityFieldAnalysis,
+    config: &PolymorphicConfig,
+    type_value: &proc_macro2::TokenStream,
+    id_value: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let is_optional =
+        |column: &syn::Ident| db_field(analysis, column).is_some_and(field_is_optional);
+    let type_expr = if is_optional(&config.type_column) {
+        quote! { #type_value.as_deref() }
+    } else {
+        quote! { Some(#type_value.as_str()) }
+    };
+    let id_expr = if is_optional(&config.id_column) {
+        quote! { #id_value }
+    } else {
+        quote! { Some(#id_value) }
+    };
+    quote! { (#type_expr, #id_expr) }
+}
+
+/// Load the polymorphic fields of `result` (a `Self`) in `get_one`
+pub(crate) fn generate_polymorphic_one(
+    analysis: &EntityFieldAnalysis,
+    model_type: &str,
+) -> Vec<proc_macro2::TokenStream> {
+    polymorphic_fields(analysis, model_type)
+        .map(|(field, config)| {
+            let ident = &field.ident;
+            let type_column = &config.type_column;
+            let id_column = &config.id_column;
+            let pair = pair_expr(
+                analysis,
+                config,
+                &quote! { result.#type_column },
+                &quote! { result.#id_column },
+            );
+            let arms = config.targets().map(|(name, path)| {
+                quote! {
+                    (Some(#name), Some(id)) => crudcrate::relationships::load_polymorphic::<#path>(db, vec![id])
+                        .await?
+                        .remove(&id),
+                }
+            });
+            quote! {
+                result.#ident = match #pair {
+                    #(#arms)*
+                    _ => None,
+                };
+            }
+        })
+        .collect()
+}
+
+/// Load the polymorphic fields of `items` (a `Vec<Self>`) in `get_all`, with one query
+/// per target type for the whole page
+pub(crate) fn generate_polymorphic_many(
+    analysis: &EntityFieldAnalysis,
+    model_type: &str,
+) -> Vec<proc_macro2::TokenStream> {
+    polymorphic_fields(analysis, model_type)
+        .map(|(field, config)| {
+            let ident = field.ident.as_ref();
+            let type_column = &config.type_column;
+            let id_column = &config.id_column;
+            let pair = pair_expr(
+                analysis,
+                config,
+                &quote! { item.#type_column },
+                &quote! { item.#id_column },
+            );
+            let targets: Vec<_> = config
+                .targets()
+                .map(|(name, path)| {
+                    let field_name = ident.map(ToString::to_string).unwrap_or_default();
+                    let loaded = format_ident!("{}_{}", field_name, name.to_case(Case::Snake));
+                    (name, path, loaded)
+                })
+                .collect();
+            let loads = targets.iter().map(|(name, path, loaded)| {
+                quote! {
+                    let #loaded = crudcrate::relationships::load_polymorphic::<#path>(
+                        db,
+                        items
+                            .iter()
+                            .filter_map(|item| match #pair {
+                                (Some(#name), Some(id)) => Some(id),
+                                _ => None,
+                            })
+                            .collect(),
+                    )
+                    .await?;
+                }
+            });
+            let arms = targets.iter().map(|(name, _, loaded)| {
+                quote! { (Some(#name), Some(id)) => #loaded.get(&id).cloned(), }
+            });
+            quote! {
+                #(#loads)*
+                for item in &mut items {
+                    item.#ident = match #pair {
+                        #(#arms)*
+                        _ => None,
+                    };
+                }
+            }
+        })
+        .collect()
+}
+
+/// Check the type/ID pair of each polymorphic field on `model` (an `ActiveModel`) when
+/// either column is being written: the type must be one of `types`, and the ID must
+/// exist in that type's table. A column left unset on `model` is read from `original`,
+/// the stored row on update.
+pub(crate) fn generate_polymorphic_checks(
+    analysis: &EntityFieldAnalysis,
+    original: &proc_macro2::TokenStream,
+) -> Vec<proc_macro2::TokenStream> {
+    analysis
+        .polymorphic_fields
+        .iter()
+        .map(|(_, config)| {
+            let type_column = &config.type_column;
+            let id_column = &config.id_column;
+            let type_name = type_column.to_string();
+            let id_name = id_column.to_string();
+            let pair = pair_expr(
+                analysis,
+                config,
+                &quote! { type_value },
+                &quote! { *id_value },
+            );
+            let expected = config
+                .targets()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+                .join(", ");
+            let arms = config.targets().map(|(name, path)| {
+                quote! {
+                    (Some(#name), Some(id)) => {
+                        crudcrate::relationships::ensure_polymorphic_target::<#path, _>(db, #id_name, id).await?;
+                    }
+                }
+            });
+            quote! {
+                if model.#type_column.is_set() || model.#id_column.is_set() {
+                    let type_active = if model.#type_column.is_not_set() { &#original.#type_column } else { &model.#type_column };
+                    let id_active = if model.#id_column.is_not_set() { &#original.#id_column } else { &model.#id_column };
+                    if let (
+                        sea_orm::ActiveValue::Set(type_value) | sea_orm::ActiveValue::Unchanged(type_value),
+                        sea_orm::ActiveValue::Set(id_value) | sea_orm::ActiveValue::Unchanged(id_value),
+                    ) = (type_active, id_active) {
+                        match #pair {
+                            #(#arms)*
+                            (None, None) => {}
+                            (Some(other), Some(_)) => {
+                                return Err(crudcrate::ApiError::bad_request(format!(
+                                    "Invalid {} '{}'. Expected one of: {}",
+                                    #type_name, other, #expected
+                                )));
+                            }
+                            _ => {
+                                return Err(crudcrate::ApiError::bad_request(format!(
+                                    "{} and {} must be set together",
+                                    #type_name, #id_name
+                                )));
+                            }
+                        }
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+fn polymorphic_fields<'a, 'b>(
+    analysis: &'b EntityFieldAnalysis<'a>,
+    model_type: &'b str,
+) -> impl Iterator<Item = &'b (&'a syn::Field, PolymorphicConfig)> {
+    analysis
+        .polymorphic_fields
+        .iter()
+        .filter(move |(field, _)| should_include_in_model(field, model_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_polymorphic_config_defaults() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, polymorphic_join(types = ["Customer", "super::vehicle::Vehicle"]))]
+            pub subject: Option<serde_json::Value>
+        };
+        let config = get_polymorphic_config(&field).unwrap().unwrap();
+        assert_eq!(config.type_column, "subject_type");
+        assert_eq!(config.id_column, "subject_id");
+        let names: Vec<_> = config.targets().map(|(name, _)| name).collect();
+        assert_eq!(names, ["Customer", "Vehicle"]);
+    }
+
+    #[test]
+    fn test_polymorphic_config_columns() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(polymorphic_join(types = ["Customer"], type_column = "kind", id_column = "target"))]
+            pub subject: Option<serde_json::Value>
+        };
+        let config = get_polymorphic_config(&field).unwrap().unwrap();
+        assert_eq!(config.type_column, "kind");
+        assert_eq!(config.id_column, "target");
+    }
+
+    #[test]
+    fn test_polymorphic_config_errors() {
+        for field in [
+            parse_quote! {
+                #[crudcrate(polymorphic_join(types = []))]
+                pub subject: Option<serde_json::Value>
+            },
+            parse_quote! {
+                #[crudcrate(polymorphic_join(types = "Customer"))]
+                pub subject: Option<serde_json::Value>
+            },
+            parse_quote! {
+                #[crudcrate(polymorphic_join(types = ["Customer"], kind = "x"))]
+                pub subject: Option<serde_json::Value>
+            },
+        ] {
+            let field: syn::Field = field;
+            assert!(get_polymorphic_config(&field).is_err());
+        }
+
+        let plain: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr)]
+            pub subject: Option<serde_json::Value>
+        };
+        assert!(get_polymorphic_config(&plain).unwrap().is_none());
+    }
+}
@@ -0,0 +1,125 @@
+//! Registry metadata generation
+//!
+//! Generates the `field_metadata()` and `relation_metadata()` overrides of
+//! `CRUDResource`, which `crudcrate::registry` publishes at runtime.
+
+use crate::codegen::joins::get_join_config;
+use crate::codegen::models::should_include_in_model;
+use crate::codegen::type_resolution::{
+    extract_option_inner_type_ref, extract_vec_inner_type_ref, ident_to_string, is_vec_type,
+};
+use crate::fields::field_is_optional;
+use crate::traits::crudresource::structs::EntityFieldAnalysis;
+use quote::quote;
+
+/// Same cap as recursive join loading
+const MAX_JOIN_DEPTH: u8 = 5;
+
+fn contains(fields: &[&syn::Field], field: &syn::Field) -> bool {
+    fields
+        .iter()
+        .any(|candidate| candidate.ident == field.ident)
+}
+
+/// The type as written, without the spaces `quote` puts between tokens
+fn type_string(ty: &syn::Type) -> String {
+    quote!(#ty).to_string().replace(' ', "")
+}
+
+/// Last path segment of the related type, with `Vec`/`Option` stripped
+fn relation_target(ty: &syn::Type) -> String {
+    let inner = extract_option_inner_type_ref(extract_vec_inner_type_ref(ty));
+    if let syn::Type::Path(type_path) = inner
+        && let Some(segment) = type_path.path.segments.last()
+    {
+        segment.ident.to_string()
+    } else {
+        type_string(inner)
+    }
+}
+
+/// Generate the `field_metadata()` and `relation_metadata()` trait methods
+pub(crate) fn generate_metadata_impl(analysis: &EntityFieldAnalysis) -> proc_macro2::TokenStream {
+    let trigram_fields: Vec<&syn::Field> = analysis
+        .trigram_fields
+        .iter()
+        .map(|(field, _)| *field)
+        .collect();
+
+    let mut field_entries = Vec::new();
+    let mut relation_entries = Vec::new();
+
+    for field in analysis
+        .db_fields
+        .iter()
+        .chain(analysis.non_db_fields.iter())
+    {
+        let Some(ident) = field.ident.as_ref() else {
+            continue;
+        };
+        let name = ident_to_string(ident);
+
+        if let Some(join) = get_join_config(field).config {
+            let target = relation_target(&field.ty);
+            let cardinality = if is_vec_type(&field.ty) {
+                quote! { crudcrate::registry::Cardinality::Many }
+            } else {
+                quote! { crudcrate::registry::Cardinality::One }
+            };
+            let on_one = join.on_one;
+            let on_all = join.on_all;
+            let depth = join.depth.unwrap_or(1).min(MAX_JOIN_DEPTH);
+            relation_entries.push(quote! {
+                {
+                    let mut relation = crudcrate::registry::RelationMetadata::new(#name, #target, #cardinality);
+                    relation.on_one = #on_one;
+                    relation.on_all = #on_all;
+                    relation.depth = #depth;
+                    relation
+                }
+            });
+            continue;
+        }
+
+        let rust_type = type_string(&field.ty);
+        let nullable = field_is_optional(field);
+        let primary_key = analysis
+            .primary_key_field
+            .is_some_and(|pk| pk.ident == field.ident);
+        let filterable = contains(&analysis.filterable_fields, field);
+        let sortable = contains(&analysis.sortable_fields, field);
+        let fulltext =
+            contains(&analysis.fulltext_fields, field) || contains(&trigram_fields, field);
+        let create = should_include_in_model(field, "create_model");
+        let update = should_include_in_model(field, "update_model");
+        let response = should_include_in_model(field, "one_model");
+        let list = should_include_in_model(field, "list_model");
+
+        field_entries.push(quote! {
+            {
+                let mut field = crudcrate::registry::FieldMetadata::new(#name, #rust_type);
+                field.nullable = #nullable;
+                field.primary_key = #primary_key;
+                field.filterable = #filterable;
+                field.sortable = #sortable;
+                field.fulltext = #fulltext;
+                field.create = #create;
+                field.update = #update;
+                field.response = #response;
+                field.list = #list;
+                field.enum_variants = <Self as crudcrate::CRUDResource>::enum_variants(#name);
+                field
+            }
+        });
+    }
+
+    quote! {
+        fn field_metadata() -> Vec<crudcrate::registry::FieldMetadata> {
+            vec![#(#field_entries),*]
+        }
+
+        fn relation_metadata() -> Vec<crudcrate::registry::RelationMetadata> {
+            vec![#(#relation_entries),*]
+        }
+    }
+}
@@ -1,5 +1,8 @@
+pub mod factory;
+pub mod generators;
 pub mod handlers;
 pub mod joins;
+pub mod metadata;
 pub mod models;
 pub mod router;
 pub mod type_resolution;
@@ -9,15 +9,33 @@
 
 use crate::attribute_parser;
 use crate::codegen::joins::get_join_config;
-use crate::codegen::models::shared::resolve_dtwtz;
+use crate::codegen::models::shared::{
+    generate_debug, generate_field_docs, generate_rename_all_attr, generate_serde_rename_attrs,
+    is_time_crate_type, resolve_dtwtz, schema_attr,
+};
 use crate::traits::crudresource::structs::{CRUDResourceMeta, EntityFieldAnalysis};
 use quote::{ToTokens, quote};
 
+/// `exclude(response)` fields stay deserializable but are never serialized, and are
+/// left out of the `OpenAPI` schema (the API struct is nested in parent responses).
+fn response_exclusion_attrs(field: &syn::Field, skip_openapi: bool) -> proc_macro2::TokenStream {
+    if attribute_parser::get_crudcrate_bool(field, "response_model") == Some(false) {
+        let schema_ignore = schema_attr(skip_openapi, &quote! { ignore });
+        quote! {
+            #[serde(skip_serializing)]
+            #schema_ignore
+        }
+    } else {
+        quote! {}
+    }
+}
+
 /// Generates API struct fields and From<Model> conversion assignments
 /// Returns (`field_definitions`, `from_model_assignments`)
 pub(crate) fn generate_api_struct_content(
     analysis: &EntityFieldAnalysis,
     api_struct_name: &syn::Ident,
+    skip_openapi: bool,
 ) -> (Vec<proc_macro2::TokenStream>, Vec<proc_macro2::TokenStream>) {
     let mut api_struct_fields = Vec::new();
     let mut from_model_assignments = Vec::new();
@@ -38,7 +56,14 @@ pub(crate) fn generate_api_struct_content(
         // ToSchema derive (with chrono feature) recognizes it as a DateTime type.
         let resolved_type = resolve_dtwtz(field_type);
 
+        let response_attrs = response_exclusion_attrs(field, skip_openapi);
+        let docs = generate_field_docs(field, skip_openapi);
+        let rename_attrs = generate_serde_rename_attrs(field);
+
         api_struct_fields.push(quote! {
+            #response_attrs
+            #docs
+            #rename_attrs
             #(#api_field_attrs)*
             pub #field_name: #resolved_type
         });
@@ -69,17 +94,22 @@ pub(crate) fn generate_api_struct_content(
         let is_join_field = get_join_config(field).is_some();
 
         let schema_attrs = if is_join_field || is_self_referencing {
-            quote! {
-                #[schema(no_recursion)]
-            }
+            schema_attr(skip_openapi, &quote! { no_recursion })
         } else {
             quote! {}
         };
 
         let final_field_type = quote! { #field_type };
 
+        let response_attrs = response_exclusion_attrs(field, skip_openapi);
+        let docs = generate_field_docs(field, skip_openapi);
+        let rename_attrs = generate_serde_rename_attrs(field);
+
         let field_definition = quote! {
             #schema_attrs
+            #response_attrs
+            #docs
+            #rename_attrs
             #(#crudcrate_attrs)*
             pub #field_name: #final_field_type
         };
@@ -148,18 +178,33 @@ pub(crate) fn generate_api_struct(
                 || attribute_parser::get_crudcrate_bool(field, "update_model") == Some(false)
         });
 
+    let has_time_fields = analysis
+        .db_fields
+        .iter()
+        .any(|field| is_time_crate_type(&field.ty));
+
+    // `sensitive` fields replace the derived `Debug` with a masking impl
+    let (_, debug_impl) = generate_debug(
+        api_struct_name,
+        analysis
+            .db_fields
+            .iter()
+            .chain(&analysis.non_db_fields)
+            .copied(),
+    );
+
     // Build derive clause declaratively based on requirements.
     // Use fully qualified paths for derives to avoid name conflicts with user imports.
     let derives: Vec<_> = [
         (true, quote!(Clone)),
-        (true, quote!(Debug)),
+        (debug_impl.is_empty(), quote!(Debug)),
         (true, quote!(serde::Serialize)),
         (true, quote!(serde::Deserialize)),
         (true, quote!(crudcrate::ToCreateModel)),
         (true, quote!(crudcrate::ToUpdateModel)),
-        (true, quote!(utoipa::ToSchema)),
+        (!crud_meta.skip_openapi, quote!(utoipa::ToSchema)),
         (
-            has_fields_needing_default && !has_join_fields,
+            has_fields_needing_default && !has_join_fields && !has_time_fields,
             quote!(Default),
         ),
         (crud_meta.derive_partial_eq, quote!(PartialEq)),
@@ -169,13 +214,40 @@ pub(crate) fn generate_api_struct(
     .filter_map(|(include, derive)| include.then_some(derive))
     .collect();
 
+    // Forwarded to the `ToCreateModel` derive
+    let create_builder_attr = if crud_meta.create_builder {
+        quote! { #[crudcrate(create_builder)] }
+    } else {
+        quote! {}
+    };
+
+    // Forwarded to the `ToCreateModel`/`ToUpdateModel` derives
+    let openapi_attr = if crud_meta.skip_openapi {
+        quote! { #[crudcrate(openapi = false)] }
+    } else {
+        quote! {}
+    };
+
+    // Applied here and forwarded to the `ToCreateModel`/`ToUpdateModel` derives
+    let rename_all_attr = generate_rename_all_attr(crud_meta.rename_all.as_deref());
+    let forwarded_rename_all = crud_meta
+        .rename_all
+        .as_ref()
+        .map(|rule| quote! { #[crudcrate(rename_all = #rule)] });
+
     quote! {
         use sea_orm::ActiveValue;
 
         #[derive(#(#derives),*)]
         #[active_model = #active_model_path]
+        #create_builder_attr
+        #openapi_attr
+        #rename_all_attr
+        #forwarded_rename_all
         pub struct #api_struct_name {
             #(#api_struct_fields),*
         }
+
+        #debug_impl
     }
 }
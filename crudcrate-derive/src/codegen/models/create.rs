@@ -1,11 +1,18 @@
-use crate::attribute_parser::{get_crudcrate_bool, get_crudcrate_expr};
+use crate::attribute_parser::{
+    get_crudcrate_bool, get_crudcrate_expr, get_file_upload_storage, is_async_generator,
+};
+use crate::codegen::joins::get_join_config;
 use crate::codegen::models::shared::{
-    generate_active_value_set, generate_field_with_optional_default, resolve_dtwtz,
-    resolve_field_type_with_target_models,
+    generate_active_value_set, generate_debug, generate_field_docs,
+    generate_field_with_optional_default, generate_serde_rename_attrs, resolve_dtwtz,
+    resolve_field_type_with_target_models, schema_attr,
 };
 use crate::codegen::models::should_include_in_model;
+use crate::codegen::type_resolution::{
+    extract_option_inner_type_ref, ident_to_string, inner_create_type_of_option,
+};
 use crate::fields::field_is_optional;
-use quote::quote;
+use quote::{format_ident, quote};
 
 /// Generates the conversion lines for a create model to active model conversion
 pub(crate) fn generate_create_conversion_lines(
@@ -20,8 +27,30 @@ pub(crate) fn generate_create_conversion_lines(
         let include = get_crudcrate_bool(field, "create_model").unwrap_or(true);
         let is_optional = field_is_optional(field);
 
+        // Async generators are awaited by `CRUDResource::create_active_model`
+        let on_create =
+            get_crudcrate_expr(field, "on_create").filter(|expr| !is_async_generator(expr));
+        let has_async_on_create =
+            get_crudcrate_expr(field, "on_create").is_some_and(|expr| is_async_generator(&expr));
+
         if include {
-            if let Some(expr) = get_crudcrate_expr(field, "on_create") {
+            if has_async_on_create {
+                if is_optional {
+                    conv_lines.push(quote! {
+                        #ident: match create.#ident {
+                            Some(value) => sea_orm::ActiveValue::Set(value.map(Into::into)),
+                            None        => sea_orm::ActiveValue::NotSet,
+                        }
+                    });
+                } else {
+                    conv_lines.push(quote! {
+                        #ident: match create.#ident {
+                            Some(value) => sea_orm::ActiveValue::Set(value.into()),
+                            None        => sea_orm::ActiveValue::NotSet,
+                        }
+                    });
+                }
+            } else if let Some(expr) = on_create {
                 if is_optional {
                     conv_lines.push(quote! {
                         #ident: sea_orm::ActiveValue::Set(match create.#ident {
@@ -47,10 +76,10 @@ pub(crate) fn generate_create_conversion_lines(
                     #ident: sea_orm::ActiveValue::Set(create.#ident.into())
                 });
             }
-        } else if let Some(expr) = get_crudcrate_expr(field, "on_create") {
+        } else if let Some(expr) = on_create {
             conv_lines.push(generate_active_value_set(ident, &expr, is_optional));
         } else {
-            // Field is excluded from Create model and has no on_create - set to NotSet
+            // Field is excluded from Create model and has no sync on_create - set to NotSet
             // This allows the field to be set manually later in custom create functions
             conv_lines.push(quote! {
                 #ident: sea_orm::ActiveValue::NotSet
@@ -60,8 +89,17 @@ pub(crate) fn generate_create_conversion_lines(
     conv_lines
 }
 
+/// Create model type of a `join(create)` field: the related row as JSON, because its
+/// foreign key to the new parent is only known once the parent is inserted
+pub(crate) fn nested_create_type(field: &syn::Field) -> Option<proc_macro2::TokenStream> {
+    get_join_config(field)
+        .is_some_and(|config| config.create)
+        .then(|| quote! { Option<serde_json::Value> })
+}
+
 pub(crate) fn generate_create_struct_fields(
     fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    skip_openapi: bool,
 ) -> Vec<proc_macro2::TokenStream> {
     fields
         .iter()
@@ -69,7 +107,21 @@ pub(crate) fn generate_create_struct_fields(
         .map(|field| {
             let ident = &field.ident;
             let ty = &field.ty;
-            if get_crudcrate_bool(field, "non_db_attr").unwrap_or(false) {
+            let docs = generate_field_docs(field, skip_openapi);
+            let rename_attrs = generate_serde_rename_attrs(field);
+            let definition = if let Some(nested_ty) = nested_create_type(field) {
+                // Documented as the related create model; its foreign key is filled in
+                let related_create = inner_create_type_of_option(ty);
+                let schema = schema_attr(
+                    skip_openapi,
+                    &quote! { value_type = Option<#related_create> },
+                );
+                quote! {
+                    #[serde(default)]
+                    #schema
+                    pub #ident: #nested_ty
+                }
+            } else if get_crudcrate_bool(field, "non_db_attr").unwrap_or(false) {
                 // Resolve type with target models (create model)
                 let final_ty =
                     resolve_field_type_with_target_models(ty, field, |create, _, _| create.clone());
@@ -80,12 +132,238 @@ pub(crate) fn generate_create_struct_fields(
                     #[serde(default)]
                     pub #ident: Option<#resolved_ty>
                 }
+            } else if is_file_upload(field) {
+                // Only values stored by the multipart request itself
+                let default = field_is_optional(field).then(|| quote! { default, });
+                quote! {
+                    #[serde(#default deserialize_with = "crudcrate::storage::deserialize_uploaded")]
+                    pub #ident: #ty
+                }
             } else {
                 let resolved_ty = resolve_dtwtz(ty);
                 quote! {
                     pub #ident: #resolved_ty
                 }
+            };
+            quote! {
+                #docs
+                #rename_attrs
+                #definition
             }
         })
         .collect()
 }
+
+/// Whether `field` is a `file_upload` column, set only by multipart uploads
+pub(crate) fn is_file_upload(field: &syn::Field) -> bool {
+    get_file_upload_storage(field).is_ok_and(|storage| storage.is_some())
+}
+
+/// How a create model field is filled when the caller doesn't provide it
+enum CreateFallback {
+    /// The field is `Option<setter type>` (`on_create` or optional column): `None`
+    Unset,
+    /// `default = expr`, or `None` for optional non-db fields
+    Default(syn::Expr),
+    /// Must be provided
+    Required,
+}
+
+/// A create model field with its type and fallback
+struct CreateField<'a> {
+    ident: &'a syn::Ident,
+    /// Type of the create model field
+    ty: proc_macro2::TokenStream,
+    /// Type the builder setter accepts: the inner type for `Unset` fields
+    setter_ty: proc_macro2::TokenStream,
+    fallback: CreateFallback,
+}
+
+fn create_fields(
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> Vec<CreateField<'_>> {
+    fields
+        .iter()
+        .filter(|field| should_include_in_model(field, "create_model"))
+        .filter_map(|field| {
+            let ident = field.ident.as_ref()?;
+            let default = get_crudcrate_expr(field, "default");
+
+            Some(if let Some(ty) = nested_create_type(field) {
+                CreateField {
+                    ident,
+                    ty,
+                    setter_ty: quote! { serde_json::Value },
+                    fallback: CreateFallback::Unset,
+                }
+            } else if get_crudcrate_bool(field, "non_db_attr").unwrap_or(false) {
+                let ty = resolve_field_type_with_target_models(&field.ty, field, |create, _, _| {
+                    create.clone()
+                });
+                let fallback = match default {
+                    Some(expr) => CreateFallback::Default(expr),
+                    None if field_is_optional(field) => {
+                        CreateFallback::Default(syn::parse_quote!(None))
+                    }
+                    None => CreateFallback::Required,
+                };
+                CreateField {
+                    ident,
+                    setter_ty: ty.clone(),
+                    ty,
+                    fallback,
+                }
+            } else if get_crudcrate_expr(field, "on_create").is_some() {
+                let resolved_ty = resolve_dtwtz(&field.ty);
+                CreateField {
+                    ident,
+                    ty: quote! { Option<#resolved_ty> },
+                    setter_ty: resolved_ty,
+                    fallback: CreateFallback::Unset,
+                }
+            } else if let Some(expr) = default {
+                let ty = resolve_dtwtz(&field.ty);
+                CreateField {
+                    ident,
+                    setter_ty: ty.clone(),
+                    ty,
+                    fallback: CreateFallback::Default(expr),
+                }
+            } else if field_is_optional(field) {
+                CreateField {
+                    ident,
+                    ty: resolve_dtwtz(&field.ty),
+                    setter_ty: resolve_dtwtz(extract_option_inner_type_ref(&field.ty)),
+                    fallback: CreateFallback::Unset,
+                }
+            } else {
+                let ty = resolve_dtwtz(&field.ty);
+                CreateField {
+                    ident,
+                    setter_ty: ty.clone(),
+                    ty,
+                    fallback: CreateFallback::Required,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Generates `impl Default` for the create model when no field is required, i.e. every
+/// field is optional or has an `on_create` or `default` expression.
+pub(crate) fn generate_create_default_impl(
+    create_name: &syn::Ident,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> proc_macro2::TokenStream {
+    let fields = create_fields(fields);
+    let mut assignments = Vec::with_capacity(fields.len());
+    for field in &fields {
+        let ident = field.ident;
+        assignments.push(match &field.fallback {
+            CreateFallback::Unset => quote! { #ident: None },
+            CreateFallback::Default(expr) => quote! { #ident: #expr },
+            CreateFallback::Required => return quote! {},
+        });
+    }
+
+    quote! {
+        impl Default for #create_name {
+            fn default() -> Self {
+                Self {
+                    #(#assignments),*
+                }
+            }
+        }
+    }
+}
+
+/// Generates `<Name>CreateBuilder` and `<Name>Create::builder()` (struct attribute
+/// `create_builder`).
+pub(crate) fn generate_create_builder(
+    create_name: &syn::Ident,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> proc_macro2::TokenStream {
+    let builder_name = format_ident!("{}Builder", create_name);
+    let builder_str = builder_name.to_string();
+    let (debug_derive, debug_impl) = generate_debug(
+        &builder_name,
+        fields
+            .iter()
+            .filter(|field| should_include_in_model(field, "create_model")),
+    );
+    let fields = create_fields(fields);
+
+    let mut builder_fields = Vec::with_capacity(fields.len());
+    let mut setters = Vec::with_capacity(fields.len());
+    let mut assignments = Vec::with_capacity(fields.len());
+
+    for field in &fields {
+        let ident = field.ident;
+        let ty = &field.ty;
+        let setter_ty = &field.setter_ty;
+        let name = ident_to_string(ident);
+        let doc = format!("Set `{name}`");
+
+        setters.push(quote! {
+            #[doc = #doc]
+            #[must_use]
+            pub fn #ident(mut self, value: impl Into<#setter_ty>) -> Self {
+                self.#ident = Some(value.into());
+                self
+            }
+        });
+        assignments.push(match &field.fallback {
+            CreateFallback::Unset => {
+                builder_fields.push(quote! { #ident: #ty });
+                quote! { #ident: self.#ident }
+            }
+            CreateFallback::Default(expr) => {
+                builder_fields.push(quote! { #ident: Option<#ty> });
+                quote! { #ident: self.#ident.unwrap_or_else(|| #expr) }
+            }
+            CreateFallback::Required => {
+                builder_fields.push(quote! { #ident: Option<#ty> });
+                let message = format!("{builder_str}: `{name}` is required");
+                quote! {
+                    #ident: self.#ident.ok_or_else(|| crudcrate::ApiError::bad_request(#message))?
+                }
+            }
+        });
+    }
+
+    let struct_doc = format!(
+        "Builder for [`{create_name}`]. Optional fields and fields with `on_create` or `default` may be left unset."
+    );
+
+    quote! {
+        #[doc = #struct_doc]
+        #[derive(Clone, #debug_derive Default)]
+        pub struct #builder_name {
+            #(#builder_fields),*
+        }
+
+        #debug_impl
+
+        impl #builder_name {
+            #(#setters)*
+
+            /// Build the create model
+            ///
+            /// # Errors
+            /// Returns `ApiError::BadRequest` naming the first required field that is unset.
+            pub fn build(self) -> Result<#create_name, crudcrate::ApiError> {
+                Ok(#create_name {
+                    #(#assignments),*
+                })
+            }
+        }
+
+        impl #create_name {
+            /// Start building a create model field by field
+            #[must_use]
+            pub fn builder() -> #builder_name {
+                #builder_name::default()
+            }
+        }
+    }
+}
@@ -1,16 +1,20 @@
 use crate::attribute_parser::{get_crudcrate_bool, get_crudcrate_expr};
 use crate::codegen::joins::get_join_config;
 use crate::codegen::models::shared::{
-    generate_target_model_conversion, resolve_dtwtz, resolve_field_type_with_target_models,
+    generate_field_docs, generate_serde_rename_attrs, generate_target_model_conversion,
+    resolve_dtwtz, resolve_field_type_with_target_models, schema_attr,
 };
 use crate::codegen::models::should_include_in_model;
-use crate::codegen::type_resolution::{is_vec_type, transform_type_to_list_variant};
+use crate::codegen::type_resolution::{
+    is_boxed_option_type, is_option_type, is_vec_type, transform_type_to_list_variant,
+};
 use crate::traits::crudresource::structs::EntityFieldAnalysis;
 use quote::quote;
 
 pub(crate) fn generate_list_struct_fields(
     fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
     api_struct_name: &syn::Ident,
+    skip_openapi: bool,
 ) -> Vec<proc_macro2::TokenStream> {
     fields
         .iter()
@@ -29,8 +33,20 @@ pub(crate) fn generate_list_struct_fields(
                 resolve_field_type_with_target_models(ty, field, |_, _, list| list.clone())
             };
 
+            // Joined list models can refer back to this one
+            let schema_attrs = get_join_config(field)
+                .is_some()
+                .then(|| schema_attr(skip_openapi, &quote! { no_recursion }));
+
             let resolved_ty = resolve_dtwtz(&final_ty);
-            quote! { pub #ident: #resolved_ty }
+            let docs = generate_field_docs(field, skip_openapi);
+            let rename_attrs = generate_serde_rename_attrs(field);
+            quote! {
+                #schema_attrs
+                #docs
+                #rename_attrs
+                pub #ident: #resolved_ty
+            }
         })
         .collect()
 }
@@ -51,6 +67,17 @@ pub(crate) fn generate_list_from_assignments(
                     #ident: model.#ident.into_iter().map(Into::into).collect()
                 };
             }
+            // Option<T> / Option<Box<T>> join(all) fields hold the List variant too
+            if is_join_all && is_boxed_option_type(&field.ty) {
+                return quote! {
+                    #ident: model.#ident.map(|x| Box::new((*x).into()))
+                };
+            }
+            if is_join_all && is_option_type(&field.ty) {
+                return quote! {
+                    #ident: model.#ident.map(Into::into)
+                };
+            }
 
             // Try to generate target model conversion, fallback to direct assignment
             generate_target_model_conversion(field, ident.as_ref()).unwrap_or_else(|| {
@@ -102,10 +129,10 @@ pub(crate) fn generate_list_from_model_assignments(
             let is_join_all = get_join_config(field).is_some_and(|c| c.on_all);
 
             if is_join_all {
-                // Join(all) fields: Initialize with empty vec in From<Model> - they'll be populated by get_all() loading logic
-                // The ListModel struct includes them with Vec<APIStruct> type, so we initialize with vec![]
+                // Join(all) fields: Initialize empty in From<Model> - they'll be populated by get_all() loading logic
+                // The ListModel struct holds them as Vec<APIStructList> or Option<APIStructList>
                 assignments.push(quote! {
-                    #field_name: vec![]
+                    #field_name: Default::default()
                 });
             } else {
                 // Regular non-DB fields: use default or specified default
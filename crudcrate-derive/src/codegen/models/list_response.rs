@@ -3,11 +3,17 @@
 //! This module coordinates the generation of both List and Response models
 //! from entity definitions, using the dedicated list and response generators.
 
+use crate::attribute_parser::get_crudcrate_bool;
+use crate::attribute_parser::parse_crud_resource_meta;
 use crate::codegen::joins::get_join_config;
+use crate::codegen::models::shared::{
+    generate_debug, generate_field_docs, generate_rename_all_attr, generate_serde_rename_attrs,
+    schema_attr, schema_derive,
+};
 use crate::codegen::models::should_include_in_model;
 use crate::codegen::type_resolution::{
-    inner_list_type_of_option, inner_list_type_of_vec, is_option_type, is_vec_type,
-    transform_type_to_scoped_list_variant,
+    inner_list_type_of_option, inner_list_type_of_vec, is_boxed_option_type, is_option_type,
+    is_vec_type, transform_type_to_scoped_list_variant,
 };
 use crate::fields;
 use crate::traits::crudresource::structs::EntityFieldAnalysis;
@@ -28,6 +34,7 @@ fn is_bool_type(ty: &syn::Type) -> bool {
 /// `struct_level_joins` are synthetic fields from struct-level `join(...)` attributes.
 ///
 /// Returns a tuple of (`list_model_tokens`, `response_model_tokens`)
+#[allow(clippy::too_many_lines)]
 pub(crate) fn generate_list_and_response_models(
     input: &DeriveInput,
     api_struct_name: &syn::Ident,
@@ -50,22 +57,37 @@ pub(crate) fn generate_list_and_response_models(
         all_fields.push(field.clone());
     }
 
-    let list_struct_fields =
-        crate::codegen::models::list::generate_list_struct_fields(&all_fields, api_struct_name);
+    let meta = parse_crud_resource_meta(&input.attrs);
+    let list_struct_fields = crate::codegen::models::list::generate_list_struct_fields(
+        &all_fields,
+        api_struct_name,
+        meta.skip_openapi,
+    );
     let list_from_assignments =
         crate::codegen::models::list::generate_list_from_assignments(&all_fields);
     let list_from_model_assignments =
         crate::codegen::models::list::generate_list_from_model_assignments(field_analysis);
 
-    let list_derives =
-        quote! { Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema };
+    let rename_all_attr = generate_rename_all_attr(meta.rename_all.as_deref());
+    let schema_derive = schema_derive(meta.skip_openapi);
+
+    let (debug_derive, debug_impl) = generate_debug(
+        &list_name,
+        all_fields
+            .iter()
+            .filter(|f| should_include_in_model(f, "list_model")),
+    );
+    let list_derives = quote! { Clone, #debug_derive PartialEq, serde::Serialize, serde::Deserialize #schema_derive };
 
     let list_model = quote! {
         #[derive(#list_derives)]
+        #rename_all_attr
         pub struct #list_name {
             #(#list_struct_fields),*
         }
 
+        #debug_impl
+
         impl From<#api_struct_name> for #list_name {
             fn from(model: #api_struct_name) -> Self {
                 Self {
@@ -88,19 +110,28 @@ pub(crate) fn generate_list_and_response_models(
     let response_struct_fields = crate::codegen::models::response::generate_response_struct_fields(
         &all_fields,
         api_struct_name,
+        meta.skip_openapi,
     );
     let response_from_assignments =
         crate::codegen::models::response::generate_response_from_assignments(&all_fields);
 
-    let response_derives =
-        quote! { Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema };
+    let (debug_derive, debug_impl) = generate_debug(
+        &response_name,
+        all_fields
+            .iter()
+            .filter(|f| get_crudcrate_bool(f, "one_model").unwrap_or(true)),
+    );
+    let response_derives = quote! { Clone, #debug_derive PartialEq, serde::Serialize, serde::Deserialize #schema_derive };
 
     let response_model = quote! {
         #[derive(#response_derives)]
+        #rename_all_attr
         pub struct #response_name {
             #(#response_struct_fields),*
         }
 
+        #debug_impl
+
         impl From<#api_struct_name> for #response_name {
             fn from(model: #api_struct_name) -> Self {
                 Self {
@@ -133,13 +164,16 @@ pub(crate) fn generate_list_and_response_models(
             })
             .map(|f| {
                 let ident = &f.ident;
+                let rename_attrs = generate_serde_rename_attrs(f);
+                let docs = generate_field_docs(f, meta.skip_openapi);
                 let is_join_all = get_join_config(f).is_some_and(|c| c.on_all);
                 if is_join_all {
                     let scoped_ty = transform_type_to_scoped_list_variant(&f.ty, api_struct_name);
-                    quote! { pub #ident: #scoped_ty }
+                    let no_recursion = schema_attr(meta.skip_openapi, &quote! { no_recursion });
+                    quote! { #no_recursion #docs #rename_attrs pub #ident: #scoped_ty }
                 } else {
                     let ty = &f.ty;
-                    quote! { pub #ident: #ty }
+                    quote! { #docs #rename_attrs pub #ident: #ty }
                 }
             })
             .collect();
@@ -154,6 +188,16 @@ pub(crate) fn generate_list_and_response_models(
                 if is_join_all && is_vec_type(&f.ty) {
                     // ListModel.field is Vec<ChildList>, filter private children then convert to ChildScopedList
                     quote! { #ident: model.#ident.into_iter().filter(|x| crudcrate::ScopeFilterable::is_scope_visible(x)).map(Into::into).collect() }
+                } else if is_join_all && is_boxed_option_type(&f.ty) {
+                    // ListModel.field is Option<Box<ChildList>>, target is Option<Box<ChildScopedList>>
+                    quote! {
+                        #ident: model.#ident
+                            .filter(|x| crudcrate::ScopeFilterable::is_scope_visible(&**x))
+                            .map(|x| Box::new((*x).into()))
+                    }
+                } else if is_join_all && is_option_type(&f.ty) {
+                    // ListModel.field is Option<ChildList>, target is Option<ChildScopedList>
+                    quote! { #ident: model.#ident.filter(|x| crudcrate::ScopeFilterable::is_scope_visible(x)).map(Into::into) }
                 } else {
                     quote! { #ident: model.#ident }
                 }
@@ -170,13 +214,16 @@ pub(crate) fn generate_list_and_response_models(
             })
             .map(|f| {
                 let ident = &f.ident;
+                let rename_attrs = generate_serde_rename_attrs(f);
+                let docs = generate_field_docs(f, meta.skip_openapi);
                 let is_join = get_join_config(f).is_some();
                 if is_join {
                     let scoped_ty = transform_type_to_scoped_list_variant(&f.ty, api_struct_name);
-                    quote! { pub #ident: #scoped_ty }
+                    let no_recursion = schema_attr(meta.skip_openapi, &quote! { no_recursion });
+                    quote! { #no_recursion #docs #rename_attrs pub #ident: #scoped_ty }
                 } else {
                     let ty = &f.ty;
-                    quote! { pub #ident: #ty }
+                    quote! { #docs #rename_attrs pub #ident: #ty }
                 }
             })
             .collect();
@@ -200,6 +247,17 @@ pub(crate) fn generate_list_and_response_models(
                             list_item.into()
                         }).collect()
                     }
+                } else if is_join && is_boxed_option_type(&f.ty) {
+                    // Response.field is Option<Box<Child>>, target is Option<Box<ChildScopedList>>
+                    let inner_list_ty = inner_list_type_of_option(&f.ty);
+                    quote! {
+                        #ident: model.#ident
+                            .filter(|x| crudcrate::ScopeFilterable::is_scope_visible(&**x))
+                            .map(|x| {
+                                let list_item: #inner_list_ty = (*x).into();
+                                Box::new(list_item.into())
+                            })
+                    }
                 } else if is_join && is_option_type(&f.ty) {
                     // Response.field is Option<Child>, target is Option<ChildScopedList>
                     // Filter private children via ScopeFilterable before conversion
@@ -218,14 +276,32 @@ pub(crate) fn generate_list_and_response_models(
             })
             .collect();
 
-        let derives = quote! { Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema };
+        let (list_debug_derive, list_debug_impl) = generate_debug(
+            &scoped_list_name,
+            all_fields.iter().filter(|f| {
+                should_include_in_model(f, "list_model")
+                    && should_include_in_model(f, "scoped_model")
+            }),
+        );
+        let (response_debug_derive, response_debug_impl) = generate_debug(
+            &scoped_response_name,
+            all_fields.iter().filter(|f| {
+                should_include_in_model(f, "one_model")
+                    && should_include_in_model(f, "scoped_model")
+            }),
+        );
+        let list_derives = quote! { Clone, #list_debug_derive PartialEq, serde::Serialize, serde::Deserialize #schema_derive };
+        let response_derives = quote! { Clone, #response_debug_derive PartialEq, serde::Serialize, serde::Deserialize #schema_derive };
 
         quote! {
-            #[derive(#derives)]
+            #[derive(#list_derives)]
+            #rename_all_attr
             pub struct #scoped_list_name {
                 #(#scoped_list_fields),*
             }
 
+            #list_debug_impl
+
             impl From<#list_name> for #scoped_list_name {
                 fn from(model: #list_name) -> Self {
                     Self {
@@ -234,11 +310,14 @@ pub(crate) fn generate_list_and_response_models(
                 }
             }
 
-            #[derive(#derives)]
+            #[derive(#response_derives)]
+            #rename_all_attr
             pub struct #scoped_response_name {
                 #(#scoped_response_fields),*
             }
 
+            #response_debug_impl
+
             impl From<#response_name> for #scoped_response_name {
                 fn from(model: #response_name) -> Self {
                     Self {
@@ -61,11 +61,14 @@ pub(crate) fn generate_excluded_merge_code(
                 && !attribute_parser::get_crudcrate_bool(field, "non_db_attr").unwrap_or(false)
         })
         .filter_map(|field| {
-            attribute_parser::get_crudcrate_expr(field, "on_update").map(|expr| {
-                let ident = field.ident.as_ref().unwrap();
-                let is_optional = fields::field_is_optional(field);
-                generate_active_value_assignment(ident, &expr, is_optional)
-            })
+            // Async generators are awaited by `CRUDResource::update_active_model`
+            attribute_parser::get_crudcrate_expr(field, "on_update")
+                .filter(|expr| !attribute_parser::is_async_generator(expr))
+                .map(|expr| {
+                    let ident = field.ident.as_ref().unwrap();
+                    let is_optional = fields::field_is_optional(field);
+                    generate_active_value_assignment(ident, &expr, is_optional)
+                })
         })
         .collect()
 }
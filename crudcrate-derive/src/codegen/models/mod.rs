@@ -7,8 +7,9 @@ pub mod response;
 pub mod shared;
 pub mod update;
 
-use crate::attribute_parser::get_crudcrate_bool;
+use crate::attribute_parser::{get_crudcrate_bool, get_crudcrate_expr, get_crudcrate_strings};
 use crate::codegen::joins::get_join_config;
+use crate::codegen::joins::polymorphic::get_polymorphic_config;
 
 /// Shared field filtering logic for model generation
 /// Determines if a field should be included in a specific model type
@@ -16,19 +17,30 @@ pub(crate) fn should_include_in_model(field: &syn::Field, model_type: &str) -> b
     // Check the model-specific attribute (create_model, update_model, list_model)
     let include_in_model = get_crudcrate_bool(field, model_type).unwrap_or(true);
 
+    // Computed, counted and polymorphic fields are filled on read, so clients never send them
+    if matches!(model_type, "create_model" | "update_model")
+        && (get_crudcrate_expr(field, "computed").is_some()
+            || !get_crudcrate_strings(field, "counted_relation").is_empty()
+            || get_polymorphic_config(field).is_ok_and(|config| config.is_some()))
+    {
+        return false;
+    }
+
     // Handle join field exclusion based on model type
     if let Some(join_config) = get_join_config(field).config {
         match model_type {
-            "create_model" | "update_model" => {
-                // Create/Update models: exclude ALL join fields
+            // Create model: only `join(create)` fields, which take the related row
+            "create_model" if !join_config.create => {
                 return false;
             }
-            "list_model" => {
-                // List model: only exclude join(one) fields, keep join(all)
-                // Exclude if NOT loading in get_all (on_all = false)
-                if !join_config.on_all {
-                    return false;
-                }
+            "update_model" => {
+                // Update model: exclude ALL join fields
+                return false;
+            }
+            // List model: only exclude join(one) fields, keep join(all)
+            // Exclude if NOT loading in get_all (on_all = false)
+            "list_model" if !join_config.on_all => {
+                return false;
             }
             _ => {}
         }
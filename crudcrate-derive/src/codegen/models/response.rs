@@ -6,7 +6,9 @@
 
 use crate::attribute_parser::get_crudcrate_bool;
 use crate::codegen::joins::config::get_join_config;
-use crate::codegen::models::shared::resolve_dtwtz;
+use crate::codegen::models::shared::{
+    generate_field_docs, generate_serde_rename_attrs, resolve_dtwtz, schema_attr,
+};
 use quote::{ToTokens, quote};
 
 /// Generate field assignment expressions for converting API struct to Response.
@@ -37,6 +39,7 @@ pub(crate) fn generate_response_from_assignments(
 pub(crate) fn generate_response_struct_fields(
     fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
     api_struct_name: &syn::Ident,
+    skip_openapi: bool,
 ) -> Vec<proc_macro2::TokenStream> {
     fields
         .iter()
@@ -64,18 +67,17 @@ pub(crate) fn generate_response_struct_fields(
 
             // Add schema(no_recursion) for self-referencing or join fields to prevent
             // infinite recursion in OpenAPI schema generation
-            let schema_attr = if is_self_referencing || is_join_field {
-                Some(quote! {
-                    #[schema(no_recursion)]
-                })
-            } else {
-                None
-            };
+            let schema_attr = (is_self_referencing || is_join_field)
+                .then(|| schema_attr(skip_openapi, &quote! { no_recursion }));
 
             let final_ty = resolve_dtwtz(ty);
+            let docs = generate_field_docs(field, skip_openapi);
+            let rename_attrs = generate_serde_rename_attrs(field);
 
             quote! {
                 #schema_attr
+                #docs
+                #rename_attrs
                 #(#attrs)*
                 pub #ident: #final_ty
             }
@@ -1,27 +1,74 @@
 //! Shared utilities for model generation to eliminate code duplication
 
-use crate::attribute_parser::{field_has_crudcrate_flag, get_crudcrate_expr};
+use crate::attribute_parser::{
+    field_has_crudcrate_flag, get_crudcrate_expr, get_crudcrate_strings, get_id_generator,
+    get_sanitizers, is_sensitive, is_uuid_type,
+};
 use crate::fields::{resolve_target_models, resolve_target_models_with_list};
 use quote::{ToTokens, quote};
 
-/// Resolves `DateTimeWithTimeZone` to `chrono::DateTime<chrono::FixedOffset>` in a type.
+/// `SeaORM` prelude date/time aliases and the types they stand for
+const DATETIME_ALIASES: &[(&str, &str)] = &[
+    (
+        "DateTimeWithTimeZone",
+        "chrono::DateTime<chrono::FixedOffset>",
+    ),
+    (
+        "TimeDateTimeWithTimeZone",
+        "crudcrate::time::OffsetDateTime",
+    ),
+    ("TimeDateTime", "crudcrate::time::PrimitiveDateTime"),
+    ("TimeDate", "crudcrate::time::Date"),
+    ("TimeTime", "crudcrate::time::Time"),
+];
+
+/// Resolves `SeaORM`'s date/time aliases in a type, e.g. `DateTimeWithTimeZone` to
+/// `chrono::DateTime<chrono::FixedOffset>`.
 ///
-/// `SeaORM`'s `DateTimeWithTimeZone` is a type alias for `chrono::DateTime<chrono::FixedOffset>`,
-/// but utoipa's `ToSchema` derive only recognizes `DateTime` (the bare ident), not the alias.
-/// This function rewrites the type so utoipa's chrono feature can recognize it, while keeping
-/// the same underlying Rust type (no runtime conversion needed).
+/// The aliases are plain type aliases, but utoipa's `ToSchema` derive only recognizes
+/// the bare idents (`DateTime`, `OffsetDateTime`, ...). This function rewrites the type so
+/// utoipa's chrono/time features can recognize it, while keeping the same underlying Rust
+/// type (no runtime conversion needed). The `time` aliases resolve through crudcrate's
+/// re-export, so they need its `time` feature.
 ///
-/// Returns the original token stream unchanged if `DateTimeWithTimeZone` is not present.
+/// Returns the original token stream unchanged if no alias is present.
 pub(crate) fn resolve_dtwtz(ty: &impl ToTokens) -> proc_macro2::TokenStream {
     let type_str = ty.to_token_stream().to_string();
-    if !type_str.contains("DateTimeWithTimeZone") {
+    // Whole tokens only: `TimeDateTimeWithTimeZone` contains `DateTimeWithTimeZone`
+    let mut resolved_any = false;
+    let resolved: Vec<&str> = type_str
+        .split(' ')
+        .map(|token| {
+            DATETIME_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == token)
+                .map_or(token, |(_, path)| {
+                    resolved_any = true;
+                    path
+                })
+        })
+        .collect();
+    if !resolved_any {
         return ty.to_token_stream();
     }
-    let resolved = type_str.replace(
-        "DateTimeWithTimeZone",
-        "chrono::DateTime<chrono::FixedOffset>",
-    );
-    syn::parse_str::<syn::Type>(&resolved).map_or_else(|_| ty.to_token_stream(), |t| quote! { #t })
+    syn::parse_str::<syn::Type>(&resolved.join(" "))
+        .map_or_else(|_| ty.to_token_stream(), |t| quote! { #t })
+}
+
+/// Whether a type is one of the `time` crate's date/time types, which have no `Default`
+pub(crate) fn is_time_crate_type(ty: &impl ToTokens) -> bool {
+    const TIME_TYPES: &[&str] = &[
+        "TimeDateTimeWithTimeZone",
+        "TimeDateTime",
+        "TimeDate",
+        "TimeTime",
+        "OffsetDateTime",
+        "PrimitiveDateTime",
+    ];
+    ty.to_token_stream()
+        .to_string()
+        .split(' ')
+        .any(|token| TIME_TYPES.contains(&token))
 }
 
 /// Resolves the final type for a field, handling `use_target_models` transformations
@@ -72,6 +119,116 @@ fn is_vec_type(ty: &syn::Type) -> bool {
     false
 }
 
+/// Struct-level `rename_all` rules, as accepted by serde
+pub(crate) const RENAME_ALL_RULES: &[&str] = &[
+    "lowercase",
+    "UPPERCASE",
+    "PascalCase",
+    "camelCase",
+    "snake_case",
+    "SCREAMING_SNAKE_CASE",
+    "kebab-case",
+    "SCREAMING-KEBAB-CASE",
+];
+
+/// The name serde gives a `snake_case` field under `rule`
+pub(crate) fn apply_rename_all(rule: &str, field: &str) -> String {
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" | "camelCase" => {
+            let mut renamed = String::with_capacity(field.len());
+            let mut capitalize = rule == "PascalCase";
+            for ch in field.chars() {
+                if ch == '_' {
+                    capitalize = true;
+                } else if capitalize {
+                    renamed.push(ch.to_ascii_uppercase());
+                    capitalize = false;
+                } else {
+                    renamed.push(ch);
+                }
+            }
+            renamed
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.replace('_', "-").to_ascii_uppercase(),
+        _ => field.to_string(),
+    }
+}
+
+/// `#[serde(rename_all = "...")]` for the struct-level `rename_all` rule, if any
+pub(crate) fn generate_rename_all_attr(rename_all: Option<&str>) -> proc_macro2::TokenStream {
+    rename_all.map_or_else(|| quote! {}, |rule| quote! { #[serde(rename_all = #rule)] })
+}
+
+/// `, utoipa::ToSchema` to append to a derive list, or nothing with `openapi = false`
+pub(crate) fn schema_derive(skip_openapi: bool) -> proc_macro2::TokenStream {
+    if skip_openapi {
+        quote! {}
+    } else {
+        quote! { , utoipa::ToSchema }
+    }
+}
+
+/// `#[schema(...)]` field attribute, or nothing with `openapi = false` (no `ToSchema`
+/// derive would consume it)
+pub(crate) fn schema_attr(
+    skip_openapi: bool,
+    args: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if skip_openapi {
+        quote! {}
+    } else {
+        quote! { #[schema(#args)] }
+    }
+}
+
+/// `#[doc]` from the field's `description` and `#[schema(example = ...)]` from its
+/// `example`, so every generated model documents the field the same way.
+///
+/// `example` holds JSON text; it is handed to utoipa as `json!(...)`. The validator has
+/// already rejected values that don't tokenize. `String` fields with an `id_generator`
+/// get it as their `format` (`ulid`, `ksuid`); `Uuid` fields already have `format: uuid`.
+pub(crate) fn generate_field_docs(
+    field: &syn::Field,
+    skip_openapi: bool,
+) -> proc_macro2::TokenStream {
+    let description = get_crudcrate_strings(field, "description")
+        .into_iter()
+        .last()
+        .map(|description| quote! { #[doc = #description] });
+    let example = get_crudcrate_strings(field, "example")
+        .into_iter()
+        .last()
+        .and_then(|example| example.parse::<proc_macro2::TokenStream>().ok())
+        .map(|example| schema_attr(skip_openapi, &quote! { example = json!(#example) }));
+    let format = get_id_generator(field)
+        .filter(|_| !is_uuid_type(&field.ty))
+        .map(|generator| schema_attr(skip_openapi, &quote! { format = #generator }));
+    quote! {
+        #description
+        #example
+        #format
+    }
+}
+
+/// `#[serde(rename/alias)]` attributes for a field's `rename` and `alias` keys.
+///
+/// Aliases only affect deserialization, so they are harmless on response models.
+pub(crate) fn generate_serde_rename_attrs(field: &syn::Field) -> proc_macro2::TokenStream {
+    let renames = get_crudcrate_strings(field, "rename")
+        .into_iter()
+        .last()
+        .map(|rename| quote! { #[serde(rename = #rename)] });
+    let aliases = get_crudcrate_strings(field, "alias")
+        .into_iter()
+        .map(|alias| quote! { #[serde(alias = #alias)] });
+    quote! {
+        #renames
+        #(#aliases)*
+    }
+}
+
 /// Generates a field with optional default serde attribute
 pub(crate) fn generate_field_with_optional_default(
     ident: Option<&syn::Ident>,
@@ -155,11 +312,103 @@ pub(crate) fn generate_active_value_assignment(
     quote! { model.#ident = #value; }
 }
 
+/// Generates `target.ident = Sanitize::sanitize(target.ident, ..)` for every field with
+/// `sanitize(...)` or `sanitize_with`, applying its sanitizers in the order written.
+/// Empty when no field is sanitized, so callers only bind `target` mutably when needed.
+pub(crate) fn generate_sanitize_lines<'a>(
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+    target: &proc_macro2::TokenStream,
+) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let ident = field.ident.as_ref()?;
+            let sanitizers = get_sanitizers(field);
+            if sanitizers.is_empty() {
+                return None;
+            }
+            let sanitizer = if let [sanitizer] = sanitizers.as_slice() {
+                sanitizer.clone()
+            } else {
+                let sanitized = sanitizers
+                    .iter()
+                    .fold(quote! { value }, |value, sanitizer| quote! { #sanitizer(#value) });
+                quote! { |value: String| -> String { #sanitized } }
+            };
+            Some(quote! {
+                #target.#ident = crudcrate::sanitize::Sanitize::sanitize(#target.#ident, &#sanitizer);
+            })
+        })
+        .collect()
+}
+
+/// `Debug` for a model's derive list, and the impl to use instead when a field is
+/// `sensitive`: that impl prints `***` in place of the field's value, so models logged
+/// with `?model` or `{:?}` never show it.
+///
+/// Returns `(derive, impl)`; exactly one of them is empty. The derive carries its
+/// trailing comma.
+pub(crate) fn generate_debug<'a>(
+    name: &syn::Ident,
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let fields: Vec<&syn::Field> = fields.into_iter().collect();
+    if !fields.iter().any(|field| is_sensitive(field)) {
+        return (quote! { Debug, }, quote! {});
+    }
+    let entries = fields.iter().filter_map(|field| {
+        let ident = field.ident.as_ref()?;
+        let label = syn::ext::IdentExt::unraw(ident).to_string();
+        Some(if is_sensitive(field) {
+            quote! { .field(#label, &format_args!("***")) }
+        } else {
+            quote! { .field(#label, &self.#ident) }
+        })
+    });
+    let label = name.to_string();
+    let debug_impl = quote! {
+        impl ::core::fmt::Debug for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(#label)
+                    #(#entries)*
+                    .finish()
+            }
+        }
+    };
+    (quote! {}, debug_impl)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use syn::parse_quote;
 
+    // `quote!` spaces `>>` differently from a re-parsed type
+    fn tokens(ts: &proc_macro2::TokenStream) -> String {
+        ts.to_string().replace(' ', "")
+    }
+
+    #[test]
+    fn test_resolve_datetime_aliases() {
+        let ty: syn::Type = parse_quote!(Option<DateTimeWithTimeZone>);
+        assert_eq!(
+            tokens(&resolve_dtwtz(&ty)),
+            tokens(&quote!(Option<chrono::DateTime<chrono::FixedOffset>>))
+        );
+        let ty: syn::Type = parse_quote!(TimeDateTimeWithTimeZone);
+        assert_eq!(
+            tokens(&resolve_dtwtz(&ty)),
+            tokens(&quote!(crudcrate::time::OffsetDateTime))
+        );
+        let ty: syn::Type = parse_quote!(Option<TimeDate>);
+        assert_eq!(
+            tokens(&resolve_dtwtz(&ty)),
+            tokens(&quote!(Option<crudcrate::time::Date>))
+        );
+        let ty: syn::Type = parse_quote!(Option<String>);
+        assert_eq!(tokens(&resolve_dtwtz(&ty)), tokens(&quote!(Option<String>)));
+    }
+
     #[test]
     fn test_is_vec_type_true() {
         let ty: syn::Type = parse_quote!(Vec<String>);
@@ -242,4 +491,97 @@ mod tests {
         let result = generate_target_model_conversion(&field, Some(&ident));
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_apply_rename_all() {
+        assert_eq!(apply_rename_all("camelCase", "full_name"), "fullName");
+        assert_eq!(apply_rename_all("camelCase", "line2_total"), "line2Total");
+        assert_eq!(apply_rename_all("PascalCase", "full_name"), "FullName");
+        assert_eq!(apply_rename_all("kebab-case", "full_name"), "full-name");
+        assert_eq!(
+            apply_rename_all("SCREAMING_SNAKE_CASE", "full_name"),
+            "FULL_NAME"
+        );
+        assert_eq!(apply_rename_all("snake_case", "full_name"), "full_name");
+    }
+
+    #[test]
+    fn test_field_docs() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(description = "Title of the todo", example = "\"Buy milk\"")]
+            pub title: String
+        };
+        let docs = generate_field_docs(&field, false).to_string();
+        assert!(docs.contains("doc = \"Title of the todo\""), "{docs}");
+        assert!(
+            docs.contains("schema (example = json ! (\"Buy milk\"))"),
+            "{docs}"
+        );
+
+        // No ToSchema derive to consume `#[schema]`, the description stays as rustdoc
+        let docs = generate_field_docs(&field, true).to_string();
+        assert!(docs.contains("doc ="), "{docs}");
+        assert!(!docs.contains("schema"), "{docs}");
+
+        let plain: syn::Field = parse_quote! { pub title: String };
+        assert!(generate_field_docs(&plain, false).is_empty());
+    }
+
+    #[test]
+    fn test_serde_rename_attrs() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(rename = "fullName", alias = "legacy_name")]
+            pub full_name: String
+        };
+        let result = generate_serde_rename_attrs(&field).to_string();
+        assert!(result.contains("rename = \"fullName\""));
+        assert!(result.contains("alias = \"legacy_name\""));
+    }
+
+    #[test]
+    fn test_sanitize_lines() {
+        let fields: Vec<syn::Field> = vec![
+            parse_quote!(#[crudcrate(sanitize(trim, lowercase))] pub email: String),
+            parse_quote!(#[crudcrate(sanitize_with = strip_tags)] pub bio: Option<String>),
+            parse_quote!(pub name: String),
+        ];
+        let lines = generate_sanitize_lines(&fields, &quote!(create));
+        assert_eq!(lines.len(), 2);
+        let email = lines[0].to_string();
+        assert!(
+            email.contains(
+                "crudcrate :: sanitize :: lowercase (crudcrate :: sanitize :: trim (value))"
+            ),
+            "{email}"
+        );
+        assert!(
+            lines[1]
+                .to_string()
+                .contains("(create . bio , & strip_tags)")
+        );
+    }
+
+    #[test]
+    fn test_debug_masks_sensitive_fields() {
+        let name: syn::Ident = parse_quote!(PatientCreate);
+        let plain: Vec<syn::Field> = vec![parse_quote!(pub name: String)];
+        let (derive, debug_impl) = generate_debug(&name, &plain);
+        assert_eq!(derive.to_string(), "Debug ,");
+        assert!(debug_impl.is_empty());
+
+        let fields: Vec<syn::Field> = vec![
+            parse_quote!(pub name: String),
+            parse_quote!(#[crudcrate(sensitive)] pub ssn: String),
+            parse_quote!(#[crudcrate(hash_with = argon2_hash)] pub password: String),
+        ];
+        let (derive, debug_impl) = generate_debug(&name, &fields);
+        assert!(derive.is_empty());
+        let debug_impl = debug_impl.to_string();
+        assert!(
+            debug_impl.contains(". field (\"name\" , & self . name)"),
+            "{debug_impl}"
+        );
+        assert!(!debug_impl.contains("self . ssn"), "{debug_impl}");
+        assert!(!debug_impl.contains("self . password"), "{debug_impl}");
+    }
 }
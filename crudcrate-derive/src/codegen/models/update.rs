@@ -1,6 +1,8 @@
 use crate::attribute_parser::get_crudcrate_bool;
+use crate::codegen::models::create::is_file_upload;
 use crate::codegen::models::shared::{
-    generate_field_with_optional_default, resolve_dtwtz, resolve_field_type_with_target_models,
+    generate_field_docs, generate_field_with_optional_default, generate_serde_rename_attrs,
+    resolve_dtwtz, resolve_field_type_with_target_models,
 };
 use crate::codegen::models::should_include_in_model;
 use quote::quote;
@@ -8,14 +10,17 @@ use quote::quote;
 /// Generates the field declarations for an update struct
 pub(crate) fn generate_update_struct_fields(
     included_fields: &[&syn::Field],
+    skip_openapi: bool,
 ) -> Vec<proc_macro2::TokenStream> {
     included_fields
         .iter()
         .map(|field| {
             let ident = &field.ident;
             let ty = &field.ty;
+            let docs = generate_field_docs(field, skip_openapi);
+            let rename_attrs = generate_serde_rename_attrs(field);
 
-            if get_crudcrate_bool(field, "non_db_attr").unwrap_or(false) {
+            let definition = if get_crudcrate_bool(field, "non_db_attr").unwrap_or(false) {
                 // Resolve type with target models (update model)
                 let final_ty =
                     resolve_field_type_with_target_models(ty, field, |_, update, _| update.clone());
@@ -33,14 +38,28 @@ pub(crate) fn generate_update_struct_fields(
                     ty.clone()
                 };
                 let resolved_inner = resolve_dtwtz(&inner_ty);
+                // `file_upload` columns only take values stored by the multipart request
+                let with = if is_file_upload(field) {
+                    quote! {
+                        serialize_with = "crudcrate::serde_with::rust::double_option::serialize",
+                        deserialize_with = "crudcrate::storage::deserialize_uploaded"
+                    }
+                } else {
+                    quote! { with = "crudcrate::serde_with::rust::double_option" }
+                };
                 quote! {
                     #[serde(
                         default,
                         skip_serializing_if = "Option::is_none",
-                        with = "crudcrate::serde_with::rust::double_option"
+                        #with
                     )]
                     pub #ident: Option<Option<#resolved_inner>>
                 }
+            };
+            quote! {
+                #docs
+                #rename_attrs
+                #definition
             }
         })
         .collect()
@@ -1,9 +1,22 @@
+use convert_case::{Case, Casing};
 use quote::{format_ident, quote};
 
+use crate::codegen::joins::lazy::{LazyRelation, lazy_relations};
+use crate::traits::crudresource::structs::{CRUDResourceMeta, EntityFieldAnalysis};
+
+#[allow(clippy::too_many_lines)]
 pub(crate) fn generate_router_impl(
     api_struct_name: &syn::Ident,
     has_scoped_fields: bool,
+    crud_meta: &CRUDResourceMeta,
+    analysis: &EntityFieldAnalysis,
 ) -> proc_macro2::TokenStream {
+    let skip_openapi = crud_meta.skip_openapi;
+    let large_text = !analysis.large_text_fields.is_empty();
+    let slug = analysis.slug_field.is_some();
+    let lookup = !analysis.lookup_fields.is_empty();
+    let lazy_relations = lazy_relations(analysis, api_struct_name);
+    let tree = crud_meta.tree.is_some();
     let create_model_name = format_ident!("{}Create", api_struct_name);
     let update_model_name = format_ident!("{}Update", api_struct_name);
     let list_model_name = format_ident!("{}List", api_struct_name);
@@ -19,56 +32,452 @@ pub(crate) fn generate_router_impl(
         response_model_name.clone()
     };
 
-    generate_axum_router(
-        api_struct_name,
-        &create_model_name,
-        &update_model_name,
-        &list_model_name,
-        &response_model_name,
-        &scoped_list_name,
-        &scoped_response_name,
-    )
-}
+    // `#[crudcrate(max_body_size = N)]`: applied outside `layer` functions so a
+    // `DefaultBodyLimit` from one of them still takes precedence
+    let body_limit = crud_meta.max_body_size.map(|size| {
+        quote! { .layer(axum::extract::DefaultBodyLimit::max(#size)) }
+    });
 
-fn generate_axum_router(
-    api_struct_name: &syn::Ident,
-    create_model_name: &syn::Ident,
-    update_model_name: &syn::Ident,
-    list_model_name: &syn::Ident,
-    response_model_name: &syn::Ident,
-    scoped_list_name: &syn::Ident,
-    scoped_response_name: &syn::Ident,
-) -> proc_macro2::TokenStream {
-    quote! {
-        // Generate CRUD handlers using the crudcrate macro
-        crudcrate::crud_handlers!(#api_struct_name, #update_model_name, #create_model_name, #list_model_name, #response_model_name, #scoped_list_name, #scoped_response_name);
+    // `timeout_ms`: innermost, so the deadline covers the handlers but not `layer` functions
+    let timeout = quote! {
+        .layer(axum::middleware::from_fn(crudcrate::timeout::enforce::<Self>))
+    };
 
-        impl #api_struct_name {
-            /// Generate router with all CRUD endpoints
-            pub fn router(db: &sea_orm::DatabaseConnection) -> utoipa_axum::router::OpenApiRouter
-            where
-                Self: crudcrate::traits::CRUDResource,
-            {
-                use utoipa_axum::{router::OpenApiRouter, routes};
+    // `singleflight_ms`: one flight per router, outside the deadline and inside `layer`
+    // functions, so scopes they insert are part of the request key
+    let singleflight = crud_meta.singleflight_ms.map(|ms| {
+        quote! {
+            .layer(axum::middleware::from_fn_with_state(
+                crudcrate::singleflight::SingleFlight::new(std::time::Duration::from_millis(#ms)),
+                crudcrate::singleflight::share,
+            ))
+        }
+    });
 
-                tracing::info!(
-                    resource = Self::RESOURCE_NAME_PLURAL,
-                    table = Self::TABLE_NAME,
-                    batch_limit = Self::batch_limit(),
-                    max_page_size = Self::max_page_size(),
-                    "Mounting CRUD routes with security defaults: input_sanitization=enabled, sql_parameterization=enabled. See https://crudcrate.evanjt.com/latest/advanced/security.html"
-                );
+    // `Server-Timing` breakdowns, outside the deadline and sharing so `total` covers them
+    let server_timing = quote! {
+        .layer(axum::middleware::from_fn(crudcrate::timing::server_timing::<Self>))
+    };
+
+    // Request span and `traceparent` context, outside the deadline and sharing so their
+    // logs belong to the request
+    let trace = quote! {
+        .layer(axum::middleware::from_fn(crudcrate::telemetry::trace::<Self>))
+    };
+
+    // `#[crudcrate(layer = fn)]`: each fn returns a tower layer; later layers wrap earlier ones
+    let layer_calls: Vec<_> = crud_meta
+        .layers
+        .iter()
+        .map(|layer| quote! { .layer(#layer()) })
+        .collect();
+
+    // `security = "scheme"`: every documented operation of both routers requires it
+    let secure = |routes: proc_macro2::TokenStream| match &crud_meta.security {
+        Some(scheme) => quote! { crudcrate::router::with_security(#routes, #scheme) },
+        None => routes,
+    };
+
+    // `openapi = false`: plain axum handlers and `axum::Router`, no utoipa types
+    let no_openapi = skip_openapi.then(|| quote! { @no_openapi });
+    let router_type = if skip_openapi {
+        quote! { axum::Router }
+    } else {
+        quote! { utoipa_axum::router::OpenApiRouter }
+    };
+    let imports =
+        (!skip_openapi).then(|| quote! { use utoipa_axum::{router::OpenApiRouter, routes}; });
+    // `tree(...)`: `/{id}/children` and `/{id}/ancestors`, on both routers
+    let tree_handlers = tree.then(|| {
+        quote! {
+            crudcrate::tree_handlers!(#no_openapi #api_struct_name, #list_model_name, #scoped_list_name);
+        }
+    });
+    let tree_routes = match (tree, skip_openapi) {
+        (false, _) => None,
+        (true, true) => Some(quote! {
+            .route("/{id}/children", axum::routing::get(get_children_handler))
+            .route("/{id}/ancestors", axum::routing::get(get_ancestors_handler))
+        }),
+        (true, false) => Some(quote! {
+            .routes(routes!(get_children_handler))
+            .routes(routes!(get_ancestors_handler))
+        }),
+    };
+    // `large_text` fields: `/{id}/{field}`, on both routers
+    let large_text_handlers = large_text.then(|| {
+        quote! {
+            crudcrate::large_text_handlers!(#no_openapi #api_struct_name);
+        }
+    });
+    let large_text_routes = match (large_text, skip_openapi) {
+        (false, _) => None,
+        (true, true) => Some(quote! {
+            .route("/{id}/{field}", axum::routing::get(get_large_text_handler))
+        }),
+        (true, false) => Some(quote! {
+            .routes(routes!(get_large_text_handler))
+        }),
+    };
+
+    // `slug_from` field: `/slug/{slug}`, on both routers
+    let slug_handlers = slug.then(|| {
+        quote! {
+            crudcrate::slug_handlers!(#no_openapi #api_struct_name, #response_model_name, #scoped_response_name);
+        }
+    });
+    let slug_routes = match (slug, skip_openapi) {
+        (false, _) => None,
+        (true, true) => Some(quote! {
+            .route("/slug/{slug}", axum::routing::get(get_by_slug_handler))
+        }),
+        (true, false) => Some(quote! {
+            .routes(routes!(get_by_slug_handler))
+        }),
+    };
+
+    // `lookup` fields: `/by/{field}/{value}`, on both routers
+    let lookup_handlers = lookup.then(|| {
+        quote! {
+            crudcrate::lookup_handlers!(#no_openapi #api_struct_name, #response_model_name, #scoped_response_name);
+        }
+    });
+    let lookup_routes = match (lookup, skip_openapi) {
+        (false, _) => None,
+        (true, true) => Some(quote! {
+            .route("/by/{field}/{value}", axum::routing::get(get_by_lookup_handler))
+        }),
+        (true, false) => Some(quote! {
+            .routes(routes!(get_by_lookup_handler))
+        }),
+    };
 
+    // `join(one, lazy)` fields: `/{id}/{field}`, on both routers
+    let relation_handlers = lazy_relations.iter().map(|relation| {
+        let LazyRelation {
+            field,
+            handler,
+            child,
+            child_list,
+            child_scoped_list,
+            fk_column,
+        } = relation;
+        let path = format!("/{{id}}/{field}");
+        quote! {
+            crudcrate::relation_handler!(#no_openapi #api_struct_name, #child, #child_list, #child_scoped_list, #handler, #field, #path, #fk_column);
+        }
+    });
+    let relation_routes: Vec<_> = lazy_relations
+        .iter()
+        .map(|LazyRelation { field, handler, .. }| {
+            if skip_openapi {
+                let path = format!("/{{id}}/{field}");
+                quote! { .route(#path, axum::routing::get(#handler)) }
+            } else {
+                quote! { .routes(routes!(#handler)) }
+            }
+        })
+        .collect();
+
+    // `sync = "field"`: `/changes`, on both routers
+    let sync = crud_meta.sync.is_some();
+    let changes_handlers = sync.then(|| {
+        quote! {
+            crudcrate::changes_handlers!(#no_openapi #api_struct_name, #list_model_name, #scoped_list_name);
+        }
+    });
+    let changes_routes = match (sync, skip_openapi) {
+        (false, _) => None,
+        (true, true) => Some(quote! {
+            .route("/changes", axum::routing::get(get_changes_handler))
+        }),
+        (true, false) => Some(quote! {
+            .routes(routes!(get_changes_handler))
+        }),
+    };
+
+    // `dedupe_on(...)`: `/check-duplicates`, a POST, so only on the full router
+    let dedupe = !crud_meta.dedupe_on.is_empty();
+    let duplicates_handlers = dedupe.then(|| {
+        quote! {
+            crudcrate::duplicates_handlers!(#no_openapi #api_struct_name, #create_model_name, #list_model_name, #scoped_list_name);
+        }
+    });
+    let duplicates_routes = match (dedupe, skip_openapi) {
+        (false, _) => None,
+        (true, true) => Some(quote! {
+            .route("/check-duplicates", axum::routing::post(check_duplicates_handler))
+        }),
+        (true, false) => Some(quote! {
+            .routes(routes!(check_duplicates_handler))
+        }),
+    };
+
+    // `state_machine(...)`: `/{id}/transition`, a POST, so only on the full router
+    let state_machine = crud_meta.state_machine.is_some();
+    let transition_handlers = state_machine.then(|| {
+        quote! {
+            crudcrate::transition_handlers!(#no_openapi #api_struct_name, #response_model_name);
+        }
+    });
+    let transition_routes = match (state_machine, skip_openapi) {
+        (false, _) => None,
+        (true, true) => Some(quote! {
+            .route("/{id}/transition", axum::routing::post(transition_handler))
+        }),
+        (true, false) => Some(quote! {
+            .routes(routes!(transition_handler))
+        }),
+    };
+
+    // `<name>_handlers`: every handler above with its route, for custom routers
+    let mut handlers: Vec<(syn::Ident, &str, String)> = [
+        ("get_one_handler", "GET", "/{id}"),
+        ("get_all_handler", "GET", "/"),
+        ("get_distinct_handler", "GET", "/distinct/{field}"),
+        ("get_by_ids_handler", "GET", "/by-ids"),
+        ("post_by_ids_handler", "POST", "/by-ids"),
+        ("create_one_handler", "POST", "/"),
+        ("create_many_handler", "POST", "/batch"),
+        ("update_one_handler", "PUT", "/{id}"),
+        ("update_many_handler", "PATCH", "/batch"),
+        ("delete_one_handler", "DELETE", "/{id}"),
+        ("delete_many_handler", "DELETE", "/batch"),
+        ("delete_by_filter_handler", "DELETE", "/"),
+    ]
+    .into_iter()
+    .map(|(handler, method, path)| (format_ident!("{}", handler), method, path.to_string()))
+    .collect();
+    if tree {
+        handlers.push((
+            format_ident!("get_children_handler"),
+            "GET",
+            "/{id}/children".into(),
+        ));
+        handlers.push((
+            format_ident!("get_ancestors_handler"),
+            "GET",
+            "/{id}/ancestors".into(),
+        ));
+    }
+    if large_text {
+        handlers.push((
+            format_ident!("get_large_text_handler"),
+            "GET",
+            "/{id}/{field}".into(),
+        ));
+    }
+    if slug {
+        handlers.push((
+            format_ident!("get_by_slug_handler"),
+            "GET",
+            "/slug/{slug}".into(),
+        ));
+    }
+    if lookup {
+        handlers.push((
+            format_ident!("get_by_lookup_handler"),
+            "GET",
+            "/by/{field}/{value}".into(),
+        ));
+    }
+    for LazyRelation { field, handler, .. } in &lazy_relations {
+        handlers.push((handler.clone(), "GET", format!("/{{id}}/{field}")));
+    }
+    if sync {
+        handlers.push((
+            format_ident!("get_changes_handler"),
+            "GET",
+            "/changes".into(),
+        ));
+    }
+    if dedupe {
+        handlers.push((
+            format_ident!("check_duplicates_handler"),
+            "POST",
+            "/check-duplicates".into(),
+        ));
+    }
+    if state_machine {
+        handlers.push((
+            format_ident!("transition_handler"),
+            "POST",
+            "/{id}/transition".into(),
+        ));
+    }
+    let handlers_module = generate_handlers_module(api_struct_name, skip_openapi, &handlers);
+
+    let (crud_routes, read_routes) = if skip_openapi {
+        (
+            quote! {
+                axum::Router::new()
+                    .route(
+                        "/{id}",
+                        axum::routing::get(get_one_handler)
+                            .put(update_one_handler)
+                            .delete(delete_one_handler),
+                    )
+                    .route(
+                        "/",
+                        axum::routing::get(get_all_handler)
+                            .post(create_one_handler)
+                            .delete(delete_by_filter_handler),
+                    )
+                    .route("/distinct/{field}", axum::routing::get(get_distinct_handler))
+                    .route(
+                        "/by-ids",
+                        axum::routing::get(get_by_ids_handler).post(post_by_ids_handler),
+                    )
+                    .route(
+                        "/batch",
+                        axum::routing::post(create_many_handler)
+                            .patch(update_many_handler)
+                            .delete(delete_many_handler),
+                    )
+            },
+            quote! {
+                axum::Router::new()
+                    .route("/{id}", axum::routing::get(get_one_handler))
+                    .route("/", axum::routing::get(get_all_handler))
+                    .route("/distinct/{field}", axum::routing::get(get_distinct_handler))
+                    .route(
+                        "/by-ids",
+                        axum::routing::get(get_by_ids_handler).post(post_by_ids_handler),
+                    )
+            },
+        )
+    } else {
+        (
+            quote! {
                 OpenApiRouter::new()
                     .routes(routes!(get_one_handler))
                     .routes(routes!(get_all_handler))
+                    .routes(routes!(get_distinct_handler))
+                    .routes(routes!(get_by_ids_handler, post_by_ids_handler))
                     .routes(routes!(create_one_handler))
                     .routes(routes!(create_many_handler))
                     .routes(routes!(update_one_handler))
                     .routes(routes!(update_many_handler))
                     .routes(routes!(delete_one_handler))
                     .routes(routes!(delete_many_handler))
-                    .with_state(db.clone())
+                    .routes(routes!(delete_by_filter_handler))
+            },
+            quote! {
+                OpenApiRouter::new()
+                    .routes(routes!(get_one_handler))
+                    .routes(routes!(get_all_handler))
+                    .routes(routes!(get_distinct_handler))
+                    .routes(routes!(get_by_ids_handler, post_by_ids_handler))
+            },
+        )
+    };
+
+    // `view_sql = "..."`: there is no table to write to, so `router()` serves the reads
+    let crud_routes = if crud_meta.view_sql.is_some() {
+        read_routes.clone()
+    } else {
+        crud_routes
+    };
+
+    let crud_chain = secure(quote! {
+        #crud_routes
+            #duplicates_routes
+            #transition_routes
+            #tree_routes
+            #changes_routes
+            #large_text_routes
+            #slug_routes
+            #lookup_routes
+            #(#relation_routes)*
+            #timeout
+            #singleflight
+            #server_timing
+            #trace
+            #(#layer_calls)*
+            #body_limit
+            .with_state(db.clone())
+    });
+    let read_chain = secure(quote! {
+        #read_routes
+            #tree_routes
+            #changes_routes
+            #large_text_routes
+            #slug_routes
+            #lookup_routes
+            #(#relation_routes)*
+            #timeout
+            #singleflight
+            #server_timing
+            #trace
+            #(#layer_calls)*
+            #body_limit
+            .with_state(db.clone())
+    });
+
+    quote! {
+        // Generate CRUD handlers using the crudcrate macro
+        crudcrate::crud_handlers!(#no_openapi #api_struct_name, #update_model_name, #create_model_name, #list_model_name, #response_model_name, #scoped_list_name, #scoped_response_name);
+        #tree_handlers
+        #large_text_handlers
+        #slug_handlers
+        #lookup_handlers
+        #(#relation_handlers)*
+        #duplicates_handlers
+        #transition_handlers
+        #changes_handlers
+        #handlers_module
+
+        impl #api_struct_name {
+            /// Generate router with all CRUD endpoints, wrapped in any `layer = fn` layers
+            pub fn router(db: &sea_orm::DatabaseConnection) -> #router_type
+            where
+                Self: crudcrate::traits::CRUDResource,
+            {
+                #imports
+
+                tracing::info!(
+                    resource = <Self as crudcrate::traits::CRUDResource>::RESOURCE_NAME_PLURAL,
+                    table = <Self as crudcrate::traits::CRUDResource>::TABLE_NAME,
+                    batch_limit = <Self as crudcrate::traits::CRUDResource>::batch_limit(),
+                    max_page_size = <Self as crudcrate::traits::CRUDResource>::max_page_size(),
+                    "Mounting CRUD routes with security defaults: input_sanitization=enabled, sql_parameterization=enabled. See https://crudcrate.evanjt.com/latest/advanced/security.html"
+                );
+                crudcrate::registry::register::<Self>();
+
+                #crud_chain
+            }
+
+            /// Generate router whose handlers dispatch through `ops` instead of the
+            /// static [`CRUDResource`](crudcrate::traits::CRUDResource) methods.
+            ///
+            /// ```rust,ignore
+            /// let app = Todo::router_with_ops(&db, AuditedOps::new(audit_log));
+            /// ```
+            pub fn router_with_ops<O>(db: &sea_orm::DatabaseConnection, ops: O) -> #router_type
+            where
+                Self: crudcrate::traits::CRUDResource,
+                O: crudcrate::CRUDOperations<Resource = Self> + 'static,
+            {
+                Self::router(db).layer(axum::Extension(crudcrate::SharedOperations::<Self>::new(ops)))
+            }
+
+            /// Generate router over a shared application state. Handlers use its
+            /// `DatabaseConnection`, and hooks, operations and custom bodies they call
+            /// can read the whole state with [`crudcrate::app_state`].
+            ///
+            /// ```rust,ignore
+            /// let app = Todo::router_with_state(AppState { db, config });
+            /// ```
+            pub fn router_with_state<S>(state: S) -> #router_type
+            where
+                Self: crudcrate::traits::CRUDResource,
+                S: AsRef<sea_orm::DatabaseConnection> + Clone + Send + Sync + 'static,
+            {
+                Self::router(state.as_ref()).layer(axum::middleware::from_fn(
+                    move |request: axum::extract::Request, next: axum::middleware::Next| {
+                        crudcrate::state::with_app_state(state.clone(), next.run(request))
+                    },
+                ))
             }
 
             /// Generate read-only router with only GET endpoints.
@@ -84,24 +493,87 @@ fn generate_axum_router(
             ///         Condition::all().add(article::Column::IsPrivate.eq(false))
             ///     )));
             /// ```
-            pub fn read_only_router(db: &sea_orm::DatabaseConnection) -> utoipa_axum::router::OpenApiRouter
+            pub fn read_only_router(db: &sea_orm::DatabaseConnection) -> #router_type
             where
                 Self: crudcrate::traits::CRUDResource,
             {
-                use utoipa_axum::{router::OpenApiRouter, routes};
+                #imports
 
                 tracing::info!(
-                    resource = Self::RESOURCE_NAME_PLURAL,
-                    table = Self::TABLE_NAME,
-                    max_page_size = Self::max_page_size(),
+                    resource = <Self as crudcrate::traits::CRUDResource>::RESOURCE_NAME_PLURAL,
+                    table = <Self as crudcrate::traits::CRUDResource>::TABLE_NAME,
+                    max_page_size = <Self as crudcrate::traits::CRUDResource>::max_page_size(),
                     "Mounting read-only routes"
                 );
+                crudcrate::registry::register::<Self>();
 
-                OpenApiRouter::new()
-                    .routes(routes!(get_one_handler))
-                    .routes(routes!(get_all_handler))
-                    .with_state(db.clone())
+                #read_chain
             }
         }
     }
 }
+
+/// `pub mod <name>_handlers`, re-exporting the generated handlers (and their utoipa
+/// path structs) under their stable names, documented with the route each serves
+fn generate_handlers_module(
+    api_struct_name: &syn::Ident,
+    skip_openapi: bool,
+    handlers: &[(syn::Ident, &str, String)],
+) -> proc_macro2::TokenStream {
+    let module = format_ident!(
+        "{}_handlers",
+        api_struct_name.to_string().to_case(Case::Snake)
+    );
+    let rows = handlers
+        .iter()
+        .map(|(handler, method, path)| format!("| [`{handler}`] | `{method} {path}` |"));
+    let example = if skip_openapi {
+        format!(
+            "let v2 = axum::Router::new()\n    .route(\"/{{id}}\", axum::routing::get({module}::get_one_handler))\n    .with_state(db);"
+        )
+    } else {
+        format!(
+            "let v2 = OpenApiRouter::new()\n    .routes(routes!({module}::get_one_handler))\n    .with_state(db);"
+        )
+    };
+    let docs = [
+        format!(
+            "Handlers of [`{api_struct_name}`]'s generated routers, for building custom routers from them, e.g. a versioned API or one with extra middleware."
+        ),
+        String::new(),
+        "| Handler | Route |".to_string(),
+        "|---|---|".to_string(),
+    ]
+    .into_iter()
+    .chain(rows)
+    .chain([
+        String::new(),
+        "Each takes the `DatabaseConnection` as axum state:".to_string(),
+        String::new(),
+        "```rust,ignore".to_string(),
+        example,
+        "```".to_string(),
+        String::new(),
+        format!(
+            "Routes built this way skip the layers of [`{api_struct_name}::router`] (`layer` functions, `timeout_ms`, `singleflight_ms`, `max_body_size` and the request span), and don't add the resource to `crudcrate::registry`."
+        ),
+    ]);
+    let names = handlers.iter().map(|(handler, _, _)| handler);
+    let paths = (!skip_openapi).then(|| {
+        let paths = handlers
+            .iter()
+            .map(|(handler, _, _)| format_ident!("__path_{}", handler));
+        quote! {
+            #[doc(hidden)]
+            pub use super::{#(#paths),*};
+        }
+    });
+
+    quote! {
+        #(#[doc = #docs])*
+        pub mod #module {
+            pub use super::{#(#names),*};
+            #paths
+        }
+    }
+}
@@ -3,8 +3,11 @@ use heck::ToPascalCase;
 use quote::{format_ident, quote};
 
 use crate::{
-    CRUDResourceMeta, attribute_parser::get_crudcrate_bool,
-    codegen::models::should_include_in_model,
+    CRUDResourceMeta,
+    attribute_parser::{
+        field_has_crudcrate_flag, get_crudcrate_bool, get_crudcrate_expr, get_crudcrate_strings,
+    },
+    codegen::models::{shared::apply_rename_all, should_include_in_model},
 };
 
 /// Map field types to their corresponding entity or model paths
@@ -16,6 +19,7 @@ pub fn get_path_from_field_type(
     // Extract the target type from Vec<T> or Option<T> using canonical helpers
     let target_type = extract_vec_inner_type_ref(field_type);
     let target_type = extract_option_inner_type_ref(target_type);
+    let target_type = extract_box_inner_type_ref(target_type);
 
     // Handle fully qualified paths like crate::path::to::module::StructName
     if let syn::Type::Path(type_path) = target_type {
@@ -58,17 +62,18 @@ pub fn get_path_from_field_type(
 pub fn extract_api_struct_type_for_recursive_call(
     field_type: &syn::Type,
 ) -> proc_macro2::TokenStream {
-    // Recursively unwrap Vec and Option wrappers using canonical helpers
+    // Recursively unwrap Vec, Option and Box wrappers using canonical helpers
     let mut current_type = field_type;
     loop {
         let unwrapped_vec = extract_vec_inner_type_ref(current_type);
         let unwrapped_option = extract_option_inner_type_ref(unwrapped_vec);
+        let unwrapped_box = extract_box_inner_type_ref(unwrapped_option);
 
         // If no more unwrapping happened, we've reached the inner type
-        if std::ptr::eq(unwrapped_option, current_type) {
+        if std::ptr::eq(unwrapped_box, current_type) {
             break;
         }
-        current_type = unwrapped_option;
+        current_type = unwrapped_box;
     }
 
     // Handle type aliases that end with "Join" (ModuleJoin -> Module)
@@ -95,6 +100,7 @@ pub fn extract_api_struct_type_for_recursive_call(
 /// - `Vec<VehiclePart>` → `Vec<VehiclePartList>`
 /// - `Vec<crate::isolates::db::Isolate>` → `Vec<crate::isolates::db::IsolateList>`
 /// - `Option<Site>` → `Option<SiteList>`
+/// - `Option<Box<Site>>` → `Option<Box<SiteList>>`
 ///
 /// For self-referencing joins (where the inner type matches `self_api_struct_name`),
 /// returns the original type unchanged.
@@ -117,7 +123,13 @@ pub fn transform_type_to_list_variant(
         && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
         && let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first()
     {
-        let list_inner = append_list_to_type(inner_ty);
+        let list_inner = match boxed_inner_type(inner_ty) {
+            Some(boxed) => {
+                let list_inner = append_list_to_type(boxed);
+                quote! { Box<#list_inner> }
+            }
+            None => append_list_to_type(inner_ty),
+        };
         let wrapper = &segment.ident;
         return quote! { #wrapper<#list_inner> };
     }
@@ -183,7 +195,13 @@ pub fn transform_type_to_scoped_list_variant(
         && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
         && let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first()
     {
-        let scoped_inner = append_suffix_to_type(inner_ty, "ScopedList");
+        let scoped_inner = match boxed_inner_type(inner_ty) {
+            Some(boxed) => {
+                let scoped_inner = append_suffix_to_type(boxed, "ScopedList");
+                quote! { Box<#scoped_inner> }
+            }
+            None => append_suffix_to_type(inner_ty, "ScopedList"),
+        };
         let wrapper = &segment.ident;
         return quote! { #wrapper<#scoped_inner> };
     }
@@ -219,16 +237,11 @@ pub fn inner_list_type_of_vec(ty: &syn::Type) -> proc_macro2::TokenStream {
     append_suffix_to_type(inner, "List")
 }
 
+/// The API struct of a one-to-one join field: `T` for `T`, `Option<T>` and
+/// `Option<Box<T>>`
 pub fn extract_option_or_direct_inner_type(ty: &syn::Type) -> proc_macro2::TokenStream {
-    if let syn::Type::Path(type_path) = ty
-        && let Some(segment) = type_path.path.segments.last()
-        && segment.ident == "Option"
-        && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
-        && let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first()
-    {
-        return quote! { #inner_ty };
-    }
-    quote! { #ty }
+    let inner_ty = extract_box_inner_type_ref(extract_option_inner_type_ref(ty));
+    quote! { #inner_ty }
 }
 pub fn is_vec_type(ty: &syn::Type) -> bool {
     if let syn::Type::Path(type_path) = ty
@@ -250,12 +263,43 @@ pub fn is_option_type(ty: &syn::Type) -> bool {
     false
 }
 
-/// For an Option<T> type, return the "`TList`" inner type token (not wrapped in Option).
+/// For an Option<T> or Option<Box<T>> type, return the "`TList`" inner type token (not
+/// wrapped in Option or Box).
 pub fn inner_list_type_of_option(ty: &syn::Type) -> proc_macro2::TokenStream {
-    let inner = extract_option_inner_type_ref(ty);
+    let inner = extract_box_inner_type_ref(extract_option_inner_type_ref(ty));
     append_suffix_to_type(inner, "List")
 }
 
+/// For an Option<T> or Option<Box<T>> type, return the "`TCreate`" inner type token.
+pub fn inner_create_type_of_option(ty: &syn::Type) -> proc_macro2::TokenStream {
+    let inner = extract_box_inner_type_ref(extract_option_inner_type_ref(ty));
+    append_suffix_to_type(inner, "Create")
+}
+
+/// Whether a one-to-one join field is `Option<Box<T>>`, the form that lets two
+/// entities hold each other
+pub fn is_boxed_option_type(ty: &syn::Type) -> bool {
+    boxed_inner_type(extract_option_inner_type_ref(ty)).is_some()
+}
+
+/// `T` for `Box<T>`
+fn boxed_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    if let syn::Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+        && segment.ident == "Box"
+        && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first()
+    {
+        return Some(inner_ty);
+    }
+    None
+}
+
+/// Extract inner type from Box<T>, or return the type itself if not a Box
+pub fn extract_box_inner_type_ref(ty: &syn::Type) -> &syn::Type {
+    boxed_inner_type(ty).unwrap_or(ty)
+}
+
 /// Extract inner type from Vec<T>, or return the type itself if not a Vec
 /// This is the canonical implementation used across the codebase
 /// Returns a reference to the inner `syn::Type`
@@ -357,6 +401,110 @@ pub fn generate_like_filterable_entries(fields: &[&syn::Field]) -> Vec<proc_macr
         .collect()
 }
 
+/// Generate string entries for filterable fields marked `ci`, compared through the
+/// database's case-insensitive collation.
+pub fn generate_case_insensitive_entries(fields: &[&syn::Field]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter(|field| field_has_crudcrate_flag(field, "ci"))
+        .map(|field| {
+            let field_str = ident_to_string(field.ident.as_ref().unwrap());
+            quote! { #field_str }
+        })
+        .collect()
+}
+
+/// Generate `(name, collation)` entries for sortable fields declared with `collate`,
+/// ordered through that collation on `PostgreSQL`.
+pub fn generate_sort_collation_entries(fields: &[&syn::Field]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let collation = get_crudcrate_strings(field, "collate").pop()?;
+            let field_str = ident_to_string(field.ident.as_ref().unwrap());
+            Some(quote! { (#field_str, #collation) })
+        })
+        .collect()
+}
+
+/// Generate `(name, function)` entries for filterable fields with a `filter_with`
+/// function, which builds the condition for the field's filter value.
+pub fn generate_custom_filter_entries(fields: &[&syn::Field]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let filter = get_crudcrate_expr(field, "filter_with")?;
+            let field_str = ident_to_string(field.ident.as_ref().unwrap());
+            Some(quote! { (#field_str, #filter as crudcrate::filtering::FilterFn) })
+        })
+        .collect()
+}
+
+/// Generate string entries for filterable JSON columns, which accept dot-path filters
+/// like `metadata.color`.
+pub fn generate_json_filterable_entries(fields: &[&syn::Field]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter(|field| is_json_type(&field.ty))
+        .map(|field| {
+            let field_str = ident_to_string(field.ident.as_ref().unwrap());
+            quote! { #field_str }
+        })
+        .collect()
+}
+
+/// Generate string entries for filterable `Decimal` fields
+pub fn generate_decimal_filterable_entries(
+    fields: &[&syn::Field],
+) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter(|field| is_decimal_type(&field.ty))
+        .map(|field| {
+            let field_str = ident_to_string(field.ident.as_ref().unwrap());
+            quote! { #field_str }
+        })
+        .collect()
+}
+
+/// Generate `("field", DateKind::...)` entries for filterable date/time fields
+pub fn generate_date_filterable_entries(fields: &[&syn::Field]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let kind = format_ident!("{}", date_kind(&field.ty)?);
+            let field_str = ident_to_string(field.ident.as_ref()?);
+            Some(quote! { (#field_str, crudcrate::filtering::dates::DateKind::#kind) })
+        })
+        .collect()
+}
+
+/// Generate `("field", DateKind::...)` entries for date/time fields typed with the `time`
+/// crate
+pub fn generate_time_crate_entries(fields: &[&syn::Field]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let kind = format_ident!("{}", time_crate_kind(&field.ty)?);
+            let field_str = ident_to_string(field.ident.as_ref()?);
+            Some(quote! { (#field_str, crudcrate::filtering::dates::DateKind::#kind) })
+        })
+        .collect()
+}
+
+/// Generate `("field", "element_type")` entries for filterable Postgres array fields
+/// (`Vec<String>`, `Vec<i32>`, ...). The element type is used to cast bound values.
+pub fn generate_array_filterable_entries(fields: &[&syn::Field]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let element_type = postgres_array_element_type(&field.ty)?;
+            let field_str = ident_to_string(field.ident.as_ref()?);
+            Some(quote! { (#field_str, #element_type) })
+        })
+        .collect()
+}
+
 /// Generate string entries for columns excluded from scoped (public) requests.
 ///
 /// Collects field names that have `exclude(scoped)` — these are stripped from
@@ -374,6 +522,50 @@ pub fn generate_scoped_excluded_entries(fields: &[&syn::Field]) -> Vec<proc_macr
         .collect()
 }
 
+/// Generate field names for `sensitive` and `hash_with` fields, whose values are
+/// masked in `Debug` output and query plans
+pub fn generate_sensitive_entries(fields: &[&syn::Field]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter(|field| crate::attribute_parser::is_sensitive(field))
+        .filter_map(|field| {
+            let field_str = ident_to_string(field.ident.as_ref()?);
+            Some(quote! { #field_str })
+        })
+        .collect()
+}
+
+/// Generate `(external name, field name)` entries for `rename` and `alias` keys, and
+/// for names changed by the struct-level `rename_all` rule
+pub fn generate_field_alias_entries(
+    fields: &[&syn::Field],
+    rename_all: Option<&str>,
+) -> Vec<proc_macro2::TokenStream> {
+    let mut entries = Vec::new();
+    for field in fields {
+        let Some(field_name) = field.ident.as_ref() else {
+            continue;
+        };
+        let field_str = ident_to_string(field_name);
+        let mut renames = get_crudcrate_strings(field, "rename");
+        if renames.is_empty()
+            && let Some(rule) = rename_all
+        {
+            let external = apply_rename_all(rule, &field_str);
+            if external != field_str {
+                renames.push(external);
+            }
+        }
+        let externals = renames
+            .into_iter()
+            .chain(get_crudcrate_strings(field, "alias"));
+        for external in externals {
+            entries.push(quote! { (#external, #field_str) });
+        }
+    }
+    entries
+}
+
 /// Generate enum field checker using compile-time trait detection.
 /// Automatically detects fields whose type implements `sea_orm::ActiveEnum`
 /// using the inherent impl trick — no explicit annotation needed.
@@ -424,6 +616,47 @@ pub fn generate_enum_field_checker(all_fields: &[&syn::Field]) -> proc_macro2::T
     }
 }
 
+/// Generate the `enum_variants()` body. Uses the same inherent impl trick as
+/// `generate_enum_field_checker`: fields whose (Option-unwrapped) type implements
+/// `sea_orm::ActiveEnum` return their variants' database values, others `None`.
+pub fn generate_enum_variants(all_fields: &[&syn::Field]) -> proc_macro2::TokenStream {
+    let field_arms = all_fields.iter().filter_map(|field| {
+        let field_name_str = ident_to_string(field.ident.as_ref()?);
+        let inner_ty = extract_option_inner_type_ref(&field.ty);
+
+        Some(quote! {
+            #field_name_str => {
+                trait __Fallback {
+                    fn variants() -> Option<Vec<String>> { None }
+                }
+                impl<T> __Fallback for __Probe<T> {}
+                struct __Probe<T>(::core::marker::PhantomData<T>);
+                #[allow(dead_code)]
+                impl<T: ::sea_orm::ActiveEnum> __Probe<T> {
+                    fn variants() -> Option<Vec<String>> {
+                        Some(
+                            <T as ::sea_orm::ActiveEnum>::values()
+                                .into_iter()
+                                .filter_map(|value| {
+                                    crudcrate::filtering::enums::enum_value_to_string(value.into())
+                                })
+                                .collect(),
+                        )
+                    }
+                }
+                <__Probe<#inner_ty>>::variants()
+            },
+        })
+    });
+
+    quote! {
+        match field_name {
+            #(#field_arms)*
+            _ => None,
+        }
+    }
+}
+
 /// Helper function to handle raw identifiers properly by stripping the r# prefix
 pub fn ident_to_string(ident: &syn::Ident) -> String {
     let ident_str = ident.to_string();
@@ -434,6 +667,152 @@ pub fn ident_to_string(ident: &syn::Ident) -> String {
     }
 }
 
+/// Check if a type is a JSON type (`Json`, `JsonValue`, or `serde_json::Value`),
+/// handling Option<T> wrappers
+pub fn is_json_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(type_path) = ty else {
+        return false;
+    };
+    let segments = &type_path.path.segments;
+    let Some(last_seg) = segments.last() else {
+        return false;
+    };
+
+    // Handle Option<T> - check the inner type
+    if last_seg.ident == "Option"
+        && let syn::PathArguments::AngleBracketed(args) = &last_seg.arguments
+        && let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first()
+    {
+        return is_json_type(inner_ty);
+    }
+
+    // A bare `Value` is ambiguous, so only accept it qualified by `serde_json`
+    last_seg.ident == "Json"
+        || last_seg.ident == "JsonValue"
+        || (last_seg.ident == "Value"
+            && segments.len() >= 2
+            && segments[segments.len() - 2].ident == "serde_json")
+}
+
+/// `DateKind` variant name for a date/time type, handling Option<T> wrappers.
+///
+/// `DateTime` without generics is Sea-ORM's alias for `NaiveDateTime`; `DateTime<Tz>` is
+/// chrono's zoned timestamp.
+pub fn date_kind(ty: &syn::Type) -> Option<&'static str> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let last_seg = type_path.path.segments.last()?;
+
+    if last_seg.ident == "Option"
+        && let syn::PathArguments::AngleBracketed(args) = &last_seg.arguments
+        && let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first()
+    {
+        return date_kind(inner_ty);
+    }
+
+    match last_seg.ident.to_string().as_str() {
+        "NaiveDate" | "Date" | "TimeDate" => Some("Date"),
+        "NaiveDateTime" | "PrimitiveDateTime" | "DateTimeLocal" | "TimeDateTime" => {
+            Some("Timestamp")
+        }
+        "DateTime" if matches!(last_seg.arguments, syn::PathArguments::None) => Some("Timestamp"),
+        "DateTime"
+        | "DateTimeUtc"
+        | "DateTimeWithTimeZone"
+        | "OffsetDateTime"
+        | "TimeDateTimeWithTimeZone" => Some("TimestampTz"),
+        _ => None,
+    }
+}
+
+/// `DateKind` variant name for a `time` crate type (`OffsetDateTime`, `PrimitiveDateTime`,
+/// `time::Date` and `SeaORM`'s `Time*` aliases), handling Option<T> wrappers. A bare
+/// `Date` is taken to be `SeaORM`'s chrono alias.
+pub fn time_crate_kind(ty: &syn::Type) -> Option<&'static str> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segments = &type_path.path.segments;
+    let last_seg = segments.last()?;
+
+    if last_seg.ident == "Option"
+        && let syn::PathArguments::AngleBracketed(args) = &last_seg.arguments
+        && let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first()
+    {
+        return time_crate_kind(inner_ty);
+    }
+
+    match last_seg.ident.to_string().as_str() {
+        "TimeDate" => Some("Date"),
+        "Date" if segments.len() >= 2 && segments[segments.len() - 2].ident == "time" => {
+            Some("Date")
+        }
+        "PrimitiveDateTime" | "TimeDateTime" => Some("Timestamp"),
+        "OffsetDateTime" | "TimeDateTimeWithTimeZone" => Some("TimestampTz"),
+        _ => None,
+    }
+}
+
+/// Check if a type is `Decimal` (`rust_decimal::Decimal`, `sea_orm::prelude::Decimal`),
+/// handling Option<T> wrappers
+pub fn is_decimal_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(last_seg) = type_path.path.segments.last() else {
+        return false;
+    };
+
+    if last_seg.ident == "Option"
+        && let syn::PathArguments::AngleBracketed(args) = &last_seg.arguments
+        && let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first()
+    {
+        return is_decimal_type(inner_ty);
+    }
+
+    last_seg.ident == "Decimal"
+}
+
+/// Postgres element type of a `Vec<T>` array column, handling Option<T> wrappers.
+///
+/// Returns `None` for non-`Vec` types and for element types without a Postgres array
+/// mapping (notably `Vec<u8>`, which is `bytea`).
+pub fn postgres_array_element_type(ty: &syn::Type) -> Option<&'static str> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let last_seg = type_path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &last_seg.arguments else {
+        return None;
+    };
+    let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() else {
+        return None;
+    };
+
+    if last_seg.ident == "Option" {
+        return postgres_array_element_type(inner_ty);
+    }
+    if last_seg.ident != "Vec" {
+        return None;
+    }
+
+    let syn::Type::Path(inner_path) = inner_ty else {
+        return None;
+    };
+    match inner_path.path.segments.last()?.ident.to_string().as_str() {
+        "String" => Some("text"),
+        "i16" => Some("smallint"),
+        "i32" => Some("integer"),
+        "i64" => Some("bigint"),
+        "f32" => Some("real"),
+        "f64" => Some("double precision"),
+        "bool" => Some("boolean"),
+        "Uuid" => Some("uuid"),
+        _ => None,
+    }
+}
+
 /// Check if a type is a text type (String or &str), handling Option<T> wrappers
 pub fn is_text_type(ty: &syn::Type) -> bool {
     match ty {
@@ -5,8 +5,11 @@
 
 use crate::attribute_parser;
 use crate::codegen::joins::get_join_config;
-use crate::fields::extraction::has_sea_orm_ignore;
-use crate::traits::crudresource::structs::{EntityFieldAnalysis, JoinFilterSortConfig};
+use crate::codegen::joins::polymorphic::{check_polymorphic_columns, get_polymorphic_config};
+use crate::codegen::models::should_include_in_model;
+use crate::codegen::type_resolution::is_text_type;
+use crate::fields::extraction::{has_sea_orm_ignore, has_sea_orm_unique};
+use crate::traits::crudresource::structs::{EntityFieldAnalysis, JoinFilterSortConfig, SlugField};
 use proc_macro::TokenStream;
 
 /// Analyze entity fields and categorize them by attributes.
@@ -14,6 +17,7 @@ use proc_macro::TokenStream;
 /// that exist only on the generated API struct, not on the `SeaORM` Model.
 ///
 /// Returns an error if deprecated syntax (like `join_filterable`/`join_sortable`) is used.
+#[allow(clippy::too_many_lines)]
 pub fn analyze_entity_fields<'a>(
     fields: &'a syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
     struct_level_joins: &'a [syn::Field],
@@ -25,9 +29,17 @@ pub fn analyze_entity_fields<'a>(
         sortable_fields: Vec::new(),
         filterable_fields: Vec::new(),
         fulltext_fields: Vec::new(),
+        trigram_fields: Vec::new(),
+        boolean_fulltext_fields: Vec::new(),
+        geo_fields: Vec::new(),
+        large_text_fields: Vec::new(),
+        lookup_fields: Vec::new(),
+        slug_field: None,
+        file_upload_fields: Vec::new(),
         join_on_one_fields: Vec::new(),
         join_on_all_fields: Vec::new(),
         join_filter_sort_configs: Vec::new(),
+        polymorphic_fields: Vec::new(),
     };
 
     let mut deprecation_errors: Vec<syn::Error> = Vec::new();
@@ -69,26 +81,228 @@ pub fn analyze_entity_fields<'a>(
             }
         }
 
+        if attribute_parser::field_has_crudcrate_flag(field, "list_only") {
+            let message = if !is_non_db
+                || (attribute_parser::get_crudcrate_expr(field, "computed").is_none()
+                    && attribute_parser::get_crudcrate_strings(field, "counted_relation")
+                        .is_empty())
+            {
+                Some("`list_only` requires a `computed` or `counted_relation` field")
+            } else if !should_include_in_model(field, "list_model") {
+                Some("`list_only` conflicts with `exclude(list)`")
+            } else {
+                None
+            };
+            if let Some(message) = message {
+                deprecation_errors.push(syn::Error::new_spanned(field, message));
+            }
+        }
+
+        let slug_from = attribute_parser::get_crudcrate_strings(field, "slug_from").pop();
+        if slug_from.is_none()
+            && attribute_parser::field_has_crudcrate_flag(field, "slug_on_update")
+        {
+            deprecation_errors.push(syn::Error::new_spanned(
+                field,
+                "`slug_on_update` requires a `slug_from` field",
+            ));
+        }
+
         if is_non_db {
             analysis.non_db_fields.push(field);
+            if slug_from.is_some() {
+                deprecation_errors.push(syn::Error::new_spanned(
+                    field,
+                    "`slug_from` requires a database column",
+                ));
+            }
+            if attribute_parser::field_has_crudcrate_flag(field, "large_text") {
+                deprecation_errors.push(syn::Error::new_spanned(
+                    field,
+                    "`large_text` requires a database column",
+                ));
+            }
+            if attribute_parser::field_has_crudcrate_flag(field, "lookup") {
+                deprecation_errors.push(syn::Error::new_spanned(
+                    field,
+                    "`lookup` requires a database column",
+                ));
+            }
+            match get_polymorphic_config(field) {
+                Ok(Some(config)) => analysis.polymorphic_fields.push((field, config)),
+                Ok(None) => {}
+                Err(e) => deprecation_errors.push(e),
+            }
         } else {
             analysis.db_fields.push(field);
 
             if attribute_parser::field_has_crudcrate_flag(field, "primary_key") {
                 analysis.primary_key_field = Some(field);
             }
-            if attribute_parser::field_has_crudcrate_flag(field, "sortable") {
+            // Time-sortable keys are sortable, so `sort=id_desc` lists newest first
+            let time_sortable_id = attribute_parser::get_id_generator(field).is_some_and(|g| {
+                attribute_parser::TIME_SORTABLE_ID_GENERATORS.contains(&g.as_str())
+            });
+            if attribute_parser::field_has_crudcrate_flag(field, "sortable") || time_sortable_id {
                 analysis.sortable_fields.push(field);
             }
             if attribute_parser::field_has_crudcrate_flag(field, "filterable") {
                 analysis.filterable_fields.push(field);
             }
-            if attribute_parser::field_has_crudcrate_flag(field, "fulltext") {
-                analysis.fulltext_fields.push(field);
+            if attribute_parser::field_has_crudcrate_flag(field, "ci")
+                && !(attribute_parser::field_has_crudcrate_flag(field, "filterable")
+                    && is_text_type(&field.ty))
+            {
+                deprecation_errors.push(syn::Error::new_spanned(
+                    field,
+                    "`ci` requires a filterable String field",
+                ));
+            }
+            if let Some(collation) = attribute_parser::get_crudcrate_strings(field, "collate").pop()
+            {
+                let message = if !(attribute_parser::field_has_crudcrate_flag(field, "sortable")
+                    && is_text_type(&field.ty))
+                {
+                    Some("`collate` requires a sortable String field")
+                } else if collation.is_empty()
+                    || !collation
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "_-.@".contains(c))
+                {
+                    Some(
+                        "`collate` expects a collation name such as \"de-x-icu\" (letters, digits, `_`, `-`, `.`, `@`)",
+                    )
+                } else {
+                    None
+                };
+                if let Some(message) = message {
+                    deprecation_errors.push(syn::Error::new_spanned(field, message));
+                }
+            }
+            if let Some(function) = attribute_parser::get_crudcrate_expr(field, "hash_with") {
+                let queryable = ["filterable", "sortable", "fulltext"]
+                    .into_iter()
+                    .find(|flag| attribute_parser::field_has_crudcrate_flag(field, flag));
+                let message = if !matches!(function, syn::Expr::Path(_)) {
+                    Some(
+                        "`hash_with` expects a function path, e.g. `hash_with = argon2_hash`"
+                            .to_string(),
+                    )
+                } else if !is_text_type(&field.ty) {
+                    Some("`hash_with` requires a String field".to_string())
+                } else {
+                    queryable.map(|flag| {
+                        format!("`hash_with` fields are write-only and can't be `{flag}`")
+                    })
+                };
+                if let Some(message) = message {
+                    deprecation_errors.push(syn::Error::new_spanned(field, message));
+                }
+            }
+            if let Some(filter) = attribute_parser::get_crudcrate_expr(field, "filter_with") {
+                if !matches!(filter, syn::Expr::Path(_)) {
+                    deprecation_errors.push(syn::Error::new_spanned(
+                        filter,
+                        "`filter_with` expects a function path, e.g. `filter_with = status_filter`",
+                    ));
+                } else if !attribute_parser::field_has_crudcrate_flag(field, "filterable") {
+                    deprecation_errors.push(syn::Error::new_spanned(
+                        field,
+                        "`filter_with` requires a filterable field",
+                    ));
+                }
+            }
+            deprecation_errors.extend(check_id_generator(field));
+            if attribute_parser::field_has_crudcrate_flag(field, "geo") {
+                analysis.geo_fields.push(field);
+            }
+            if attribute_parser::field_has_crudcrate_flag(field, "large_text") {
+                if is_text_type(&field.ty) {
+                    analysis.large_text_fields.push(field);
+                } else {
+                    deprecation_errors.push(syn::Error::new_spanned(
+                        field,
+                        "`large_text` requires a String field",
+                    ));
+                }
+            }
+            if attribute_parser::field_has_crudcrate_flag(field, "lookup") {
+                // The lookup value is the key clients address the row by: it has to be
+                // readable, and `find_by_lookup` takes the first match
+                let message = if !is_text_type(&field.ty) {
+                    Some("`lookup` requires a String field")
+                } else if attribute_parser::is_sensitive(field)
+                    || attribute_parser::get_crudcrate_bool(field, "response_model") == Some(false)
+                {
+                    Some(
+                        "`lookup` fields can't be `sensitive`, `hash_with` or `exclude(response)`: \
+                         their values address rows in URLs",
+                    )
+                } else if !(has_sea_orm_unique(field)
+                    || attribute_parser::field_has_crudcrate_flag(field, "lookup_not_unique"))
+                {
+                    Some(
+                        "`lookup` requires a unique column: add `#[sea_orm(unique)]`, or \
+                         `#[crudcrate(lookup_not_unique)]` if uniqueness is enforced elsewhere",
+                    )
+                } else {
+                    None
+                };
+                match message {
+                    Some(message) => {
+                        deprecation_errors.push(syn::Error::new_spanned(field, message));
+                    }
+                    None => analysis.lookup_fields.push(field),
+                }
+            }
+            if let Some(source) = slug_from {
+                let message = if !is_text_type(&field.ty) {
+                    Some("`slug_from` requires a String field")
+                } else if analysis.slug_field.is_some() {
+                    Some("only one field can be `slug_from`")
+                } else {
+                    None
+                };
+                match message {
+                    Some(message) => {
+                        deprecation_errors.push(syn::Error::new_spanned(field, message));
+                    }
+                    None => {
+                        analysis.slug_field = Some(SlugField {
+                            field,
+                            source: quote::format_ident!("{}", source),
+                            on_update: attribute_parser::field_has_crudcrate_flag(
+                                field,
+                                "slug_on_update",
+                            ),
+                        });
+                    }
+                }
+            }
+            match attribute_parser::get_file_upload_storage(field) {
+                Ok(Some(storage)) => analysis.file_upload_fields.push((field, storage)),
+                Ok(None) => {}
+                Err(e) => deprecation_errors.push(e),
+            }
+            match attribute_parser::get_fulltext_config(field) {
+                Ok(Some(config)) => {
+                    analysis.fulltext_fields.push(field);
+                    if let Some(threshold) = config.trigram_threshold {
+                        analysis.trigram_fields.push((field, threshold));
+                    }
+                    if config.boolean_mode {
+                        analysis.boolean_fulltext_fields.push(field);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => deprecation_errors.push(e),
             }
         }
     }
 
+    deprecation_errors.extend(check_polymorphic_columns(&analysis));
+    deprecation_errors.extend(check_slug_source(&analysis));
+
     // If there are deprecation errors, return them immediately
     if !deprecation_errors.is_empty() {
         // Combine all errors into one
@@ -102,10 +316,56 @@ pub fn analyze_entity_fields<'a>(
     Ok(analysis)
 }
 
+/// An error if the `slug_from` source isn't another String column
+fn check_slug_source(analysis: &EntityFieldAnalysis) -> Option<syn::Error> {
+    let slug = analysis.slug_field.as_ref()?;
+    let source = analysis
+        .db_fields
+        .iter()
+        .find(|field| field.ident.as_ref() == Some(&slug.source));
+    let message = match source {
+        None => format!(
+            "`slug_from` field `{}` is not a column of this model",
+            slug.source
+        ),
+        Some(source) if std::ptr::eq(*source, slug.field) => {
+            "`slug_from` must name another field".to_string()
+        }
+        Some(source) if !is_text_type(&source.ty) => {
+            format!("`slug_from` field `{}` must be a String", slug.source)
+        }
+        Some(_) => return None,
+    };
+    Some(syn::Error::new_spanned(slug.field, message))
+}
+
+/// An error if the field's `id_generator` doesn't fit its type, or would make a `String`
+/// primary key (primary keys must convert to and from `Uuid`)
+fn check_id_generator(field: &syn::Field) -> Option<syn::Error> {
+    let generator = attribute_parser::get_id_generator(field)?;
+    let is_uuid = attribute_parser::is_uuid_type(&field.ty);
+    let is_string = matches!(&field.ty, syn::Type::Path(type_path)
+        if type_path.path.segments.last().is_some_and(|segment| segment.ident == "String"));
+    let is_primary_key = attribute_parser::field_has_crudcrate_flag(field, "primary_key");
+    let message = match generator.as_str() {
+        "uuid_v4" | "uuid_v7" if !is_uuid => "requires a Uuid field",
+        "ulid" if !is_uuid && !is_string => "requires a Uuid or String field",
+        "ksuid" if !is_string => "requires a String field",
+        "ulid" | "ksuid" if is_string && is_primary_key => {
+            "can't generate a String primary key; use `id_generator = \"ulid\"` on a Uuid field"
+        }
+        _ => return None,
+    };
+    Some(syn::Error::new_spanned(
+        field,
+        format!("`id_generator = \"{generator}\"` {message}"),
+    ))
+}
+
 /// Validate field analysis for consistency
 pub fn validate_field_analysis(analysis: &EntityFieldAnalysis) -> Result<(), TokenStream> {
     // Check for multiple primary keys
-    if analysis.primary_key_field.is_some()
+    if let Some(primary_key) = analysis.primary_key_field
         && analysis
             .db_fields
             .iter()
@@ -114,7 +374,7 @@ pub fn validate_field_analysis(analysis: &EntityFieldAnalysis) -> Result<(), Tok
             > 1
     {
         return Err(syn::Error::new_spanned(
-            analysis.primary_key_field.unwrap(),
+            primary_key,
             "Only one field can be marked with 'primary_key' attribute",
         )
         .to_compile_error()
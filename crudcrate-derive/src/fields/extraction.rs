@@ -97,6 +97,24 @@ pub fn parse_entity_attributes(
     (api_struct_name, active_model_path)
 }
 
+/// Check if a field has a unique index: #[`sea_orm(unique)`] or #[`sea_orm(unique_key = "...")`]
+pub fn has_sea_orm_unique(field: &syn::Field) -> bool {
+    for attr in &field.attrs {
+        if attr.path().is_ident("sea_orm")
+            && let Meta::List(meta_list) = &attr.meta
+            && let Ok(metas) =
+                Punctuated::<Meta, Comma>::parse_terminated.parse2(meta_list.tokens.clone())
+        {
+            for meta in metas {
+                if meta.path().is_ident("unique") || meta.path().is_ident("unique_key") {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
 /// Check if a field has the #[`sea_orm(ignore)`] attribute
 pub fn has_sea_orm_ignore(field: &syn::Field) -> bool {
     for attr in &field.attrs {
@@ -10,17 +10,52 @@
 //!
 //! | Attribute | Type | Description |
 //! |-----------|------|-------------|
-//! | `generate_router` | flag | Generate Axum router function |
+//! | `generate_router` | flag | Generate Axum routers, and a `<name>_handlers` module re-exporting their handlers |
 //! | `api_struct = "Name"` | string | Override generated struct name |
 //! | `name_singular = "item"` | string | Singular resource name for errors/headers |
 //! | `name_plural = "items"` | string | Plural resource name for routes |
 //! | `description = "..."` | string | `OpenAPI` description |
 //! | `fulltext_language = "english"` | string | `PostgreSQL` fulltext language |
+//! | `tsvector_column = "search_vector"` | string | `PostgreSQL` stored `tsvector` column for fulltext |
+//! | `fts5_table = "items_fts"` | string | `SQLite` FTS5 table for fulltext (`MATCH` + `bm25` ordering) |
+//! | `normalized_search = "search_text"` | string | Column kept with the accent- and case-folded `fulltext` fields; `?q=` matches it accent-insensitively (`unicode` feature) |
+//! | `view_sql = "SELECT ..."` | string | Read from a SQL query instead of the table; the router serves reads only |
 //! | `batch_limit = 100` | integer | Max items for batch create/update/delete |
 //! | `max_page_size = 1000` | integer | Max items per page for pagination |
+//! | `max_filter_length = 16384` | integer | Max `filter` length in bytes (400 when exceeded) |
+//! | `max_filter_depth = 4` | integer | Max `filter` JSON nesting depth (400 when exceeded) |
+//! | `max_body_size = 1048576` | integer | Request body limit for the generated router (413 when exceeded) |
+//! | `timeout_ms = 5000` | integer | Deadline for each generated handler (504 when exceeded) |
+//! | `singleflight_ms = 100` | integer | Identical concurrent `GET` requests share one response, reused for N ms |
+//! | `retry_attempts = 3` | integer | Runs of default writes that hit deadlocks or serialization failures (1 disables retries) |
+//! | `max_join_queries = 50` | integer | Max estimated relation queries per list page or item (400 when exceeded) |
+//! | `max_joined_rows = 5000` | integer | Max estimated joined rows per list page or item (400 when exceeded) |
 //! | `operations = MyOps` | path | Custom `CRUDOperations` implementation |
 //! | `derive_partial_eq` | flag | Derive `PartialEq` on generated structs |
 //! | `derive_eq` | flag | Derive `Eq` on generated structs |
+//! | `auto_index` | flag | Allow `crudcrate::database::ensure_indexes` to create indexes |
+//! | `enum_case_sensitive` | flag | Match enum filter values exactly (default: case-insensitive) |
+//! | `strict_query` | flag | Reject unknown filter keys and sort columns with 400 instead of ignoring them |
+//! | `server_timing` | flag | `Server-Timing` response header for requests sending `X-Server-Timing` (always with crudcrate's `debug` feature) |
+//! | `unstable_sort` | flag | Don't break ties in the list order by primary key |
+//! | `serialize_writes` | flag | Update and delete handlers lock each row they write, so writes to one row don't interleave |
+//! | `create_builder` | flag | Generate `<Name>CreateBuilder` and `<Name>Create::builder()` |
+//! | `restrict_delete` | flag | Deletes answer 409 listing dependent rows instead of failing on foreign keys |
+//! | `cascade_delete` | flag | Deletes also remove the rows of `Vec` join fields, in one transaction |
+//! | `guard_delete = "locked"` | string | Boolean column; deletes answer 423 while a row has it set |
+//! | `openapi = false` | bool | Plain `axum::Router` and models without `utoipa` derives or paths |
+//! | `security = "bearer"` | string | `OpenAPI` security scheme required by every generated operation |
+//! | `rename_all = "camelCase"` | string | serde `rename_all` on generated structs and filter/sort keys |
+//! | `layer = my_layer` | path | Tower layer (from `fn() -> L`) applied to the generated routers (repeatable) |
+//! | `geo(name = "location", lat = "latitude", lon = "longitude")` | config | Geo field from lat/lon columns |
+//! | `virtual_filter(name = "active", builder = fn_path, description = "...")` | config | Filter key expanded to a condition by a function instead of matching a column |
+//! | `tree(parent_field = "parent_id", max_depth = 10)` | config | `/{id}/children` and `/{id}/ancestors` endpoints for a self-referencing model |
+//! | `temporal` or `temporal(from = "starts_at", to = "ends_at")` | flag/config | Reads return the rows valid now, or at `?as_of=...`, by their `valid_from`/`valid_to` fields |
+//! | `state_machine(field = "status", transitions("todo -> doing"))` | config | Allowed moves of a status field, checked on update, and `POST /{id}/transition` |
+//! | `dedupe_on(name, email)` | list | `POST /check-duplicates` returning rows whose fields match a create payload |
+//! | `unique_together(email, org_id)` | list | 409 when another row has the same values, plus a unique index (`condition = "..."` for partial ones) (repeatable) |
+//! | `sync = "updated_at"` | string | `GET /changes?since=...` returning rows changed after a timestamp and tombstones of deleted ones |
+//! | `webhooks` | flag | Record signed `crudcrate::webhooks` deliveries after creates, updates and deletes |
 //!
 //! ### Hook Attributes
 //!
@@ -28,13 +63,17 @@
 //!
 //! | Operation | Cardinality | Phase | Description |
 //! |-----------|-------------|-------|-------------|
-//! | `create` | `one`, `many` | `pre`, `body`, `transform`, `post` | Create hooks |
+//! | `create` | `one`, `many` | `pre`, `body`, `transform`, `post`, `post_async` | Create hooks |
 //! | `read` | `one`, `many` | `pre`, `body`, `transform`, `post` | Read hooks |
-//! | `update` | `one`, `many` | `pre`, `body`, `transform`, `post` | Update hooks |
-//! | `delete` | `one`, `many` | `pre`, `body`, `transform`, `post` | Delete hooks |
+//! | `read` | `many` | `condition` | `fn() -> Condition` limiting every read, including `get_one` |
+//! | `update` | `one`, `many` | `pre`, `body`, `transform`, `post`, `post_async` | Update hooks |
+//! | `delete` | `one`, `many` | `pre`, `body`, `transform`, `post`, `post_async` | Delete hooks |
 //!
 //! Example: `#[crudcrate(create::one::pre = validate_input)]`
 //!
+//! `post_async` hooks take owned arguments and run as `crudcrate::jobs` background jobs
+//! after the operation, without delaying the response.
+//!
 //! ## Field-Level Attributes
 //!
 //! Use on fields with `#[crudcrate(...)]`:
@@ -43,19 +82,47 @@
 //! |-----------|------|-------------|
 //! | `primary_key` | flag | Mark as primary key field |
 //! | `filterable` | flag | Enable filtering on this field |
+//! | `ci` | flag | Exact, case-insensitive string filters through the database collation (index-friendly) |
+//! | `filter_with = fn_path` | path | Build the condition for this field's filter value with a custom function |
 //! | `sortable` | flag | Enable sorting on this field |
+//! | `collate = "de-x-icu"` | string | Sort through this collation on `PostgreSQL` (locale-aware ordering) |
 //! | `fulltext` | flag | Include in fulltext search |
+//! | `fulltext(strategy = "trigram", threshold = 0.3)` | config | Fuzzy `pg_trgm` similarity on `PostgreSQL` |
+//! | `fulltext(mode = "boolean")` | config | `MATCH ... AGAINST` boolean mode on `MySQL` |
+//! | `geo` | flag | Spatial point column with `_within` filter and distance sort |
 //! | `exclude(create)` | list | Exclude from create model |
 //! | `exclude(update)` | list | Exclude from update model |
 //! | `exclude(one)` | list | Exclude from `get_one` response |
 //! | `exclude(list)` | list | Exclude from `get_all` response |
-//! | `on_create = expr` | expr | Auto-generate value on create |
-//! | `on_update = expr` | expr | Auto-generate value on update |
+//! | `exclude(response)` | list | Never serialize, including nested joins and `OpenAPI` schemas |
+//! | `rename = "fullName"` | string | External name in bodies, `OpenAPI` and filter/sort keys |
+//! | `alias = "old_name"` | string | Extra accepted name in request bodies and filter/sort keys (repeatable) |
+//! | `description = "..."` | string | Field description in every generated model's `OpenAPI` schema and rustdoc |
+//! | `example = "\"Buy milk\""` | string | `OpenAPI` example, given as JSON text |
+//! | `sanitize(trim, lowercase)` | list | Normalise string input in create/update conversions (`trim`, `lowercase`, `uppercase`, `collapse_whitespace`) |
+//! | `sanitize_with = fn_path` | path | Normalise string input with a custom `fn(String) -> String` |
+//! | `hash_with = fn_path` | path | Store the awaited `async fn(String) -> Result<String, ApiError>` of the input (e.g. a password hash); implies `exclude(response)` |
+//! | `sensitive` | flag | Print `***` for the value in generated models' `Debug` output (not in your `Model`'s) |
+//! | `on_create = expr` | expr | Auto-generate value on create (a bare fn path is awaited as an async generator) |
+//! | `on_update = expr` | expr | Auto-generate value on update (a bare fn path is awaited as an async generator) |
+//! | `slug_from = "title"` | string | Fill with a unique URL slug of that field on create, and serve `GET /slug/{slug}` |
+//! | `slug_on_update` | flag | With `slug_from`, updates changing the source field get a new slug |
+//! | `lookup` | flag | Alternate key: serve the row at `GET /by/{field}/{value}` (unique String columns) |
+//! | `lookup_not_unique` | flag | With `lookup`, accept a column without `#[sea_orm(unique)]` |
+//! | `file_upload(storage = "s3")` | config | Accept the field as a multipart file part, stored via the named backend (`file-upload` feature) |
 //! | `non_db_attr` | flag | Mark as non-database field (for joins) |
+//! | `computed = fn` | path | Fill a non-db field in `get_one`/`get_all` with `fn(&Self, db).await` |
+//! | `computed_many = fn` | path | Batch variant of `computed` for `get_all`: `fn(&[Self], db)` returns one value per item |
+//! | `counted_relation = "Vehicles"` | string | Fill an `i64` non-db field with the child count over that `Relation` variant |
+//! | `counted_resource = "super::vehicle::Vehicle"` | string | API struct a `counted_relation` counts, whose read condition applies; defaults to the `Vec` join through the same variant |
+//! | `list_only` | flag | Serve a `computed`/`counted_relation` field in `<Name>List` only, skipping it in `get_one` |
+//! | `polymorphic_join(types = ["Customer", "Vehicle"])` | config | Load the target of a `{field}_type`/`{field}_id` pair into an `Option<serde_json::Value>` non-db field |
 //! | `join(one)` | config | Load in `get_one` only |
 //! | `join(all)` | config | Load in `get_all` only |
 //! | `join(one, all)` | config | Load in both endpoints |
 //! | `join(one, all, depth = N)` | config | With max recursion depth (1-5) |
+//! | `join(one, relation = "Name")` | config | `Relation` variant the join goes through (checked at compile time) |
+//! | `join(all, limit = 10, order_by = "created_at desc")` | config | At most N `Vec` children per parent in `get_all`, in this order |
 //! | `join_filterable("col1", "col2")` | list | Enable filtering on join columns |
 //! | `join_sortable("col1", "col2")` | list | Enable sorting on join columns |
 //!
@@ -89,6 +156,7 @@
 //! **Module guide**: `fields/` (field processing) | `codegen/` (models, handlers, joins, routes)
 
 mod attribute_parser;
+mod attribute_validator;
 mod codegen;
 mod fields;
 mod macro_implementation;
@@ -100,6 +168,19 @@ use quote::{format_ident, quote};
 use syn::{DeriveInput, parse_macro_input};
 use traits::crudresource::structs::CRUDResourceMeta;
 
+/// Named fields of a struct, for attribute validation (other shapes are rejected later)
+fn fields_of(input: &DeriveInput) -> impl Iterator<Item = &syn::Field> {
+    match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(named),
+            ..
+        }) => Some(named.named.iter()),
+        _ => None,
+    }
+    .into_iter()
+    .flatten()
+}
+
 fn extract_active_model_type(
     input: &DeriveInput,
     name: &syn::Ident,
@@ -124,13 +205,19 @@ fn extract_active_model_type(
 
 /// Generates `<Name>Create` struct with fields not excluded by `exclude(create)`.
 /// Fields with `on_create` become `Option<T>` to allow user override.
-/// Implements `From<NameCreate>` for `ActiveModel` with automatic value generation.
+/// Implements `From<NameCreate>` for `ActiveModel` with automatic value generation,
+/// `Default` when no field is required, and `<Name>CreateBuilder` with the
+/// `create_builder` struct attribute.
 #[proc_macro_derive(ToCreateModel, attributes(crudcrate, active_model))]
 pub fn to_create_model(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
     let create_name = format_ident!("{}Create", name);
 
+    if let Err(e) = attribute_validator::validate_attributes(&input.attrs, fields_of(&input)) {
+        return e.to_compile_error().into();
+    }
+
     let active_model_type = match extract_active_model_type(&input, name) {
         Ok(ty) => ty,
         Err(e) => return e.into(),
@@ -139,27 +226,61 @@ pub fn to_create_model(input: TokenStream) -> TokenStream {
         Ok(f) => f,
         Err(e) => return e,
     };
-    let create_struct_fields = codegen::models::create::generate_create_struct_fields(&fields);
+    let meta = attribute_parser::parse_crud_resource_meta(&input.attrs);
+    let create_struct_fields =
+        codegen::models::create::generate_create_struct_fields(&fields, meta.skip_openapi);
     let conv_lines = codegen::models::create::generate_create_conversion_lines(&fields);
+    let sanitize_lines = codegen::models::shared::generate_sanitize_lines(
+        fields
+            .iter()
+            .filter(|field| codegen::models::should_include_in_model(field, "create_model")),
+        &quote! { create },
+    );
+    let create_binding = if sanitize_lines.is_empty() {
+        quote! { create }
+    } else {
+        quote! { mut create }
+    };
+    let default_impl = codegen::models::create::generate_create_default_impl(&create_name, &fields);
+    let builder = if meta.create_builder {
+        codegen::models::create::generate_create_builder(&create_name, &fields)
+    } else {
+        quote! {}
+    };
+    let rename_all_attr =
+        codegen::models::shared::generate_rename_all_attr(meta.rename_all.as_deref());
 
-    // Always include ToSchema for Create models
+    // ToSchema unless `openapi = false`
     // Circular dependencies are handled by schema(no_recursion) on join fields in the main model
-    let create_derives =
-        quote! { Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema };
+    let schema_derive = codegen::models::shared::schema_derive(meta.skip_openapi);
+    let (debug_derive, debug_impl) = codegen::models::shared::generate_debug(
+        &create_name,
+        fields
+            .iter()
+            .filter(|field| codegen::models::should_include_in_model(field, "create_model")),
+    );
+    let create_derives = quote! { Clone, #debug_derive PartialEq, serde::Serialize, serde::Deserialize #schema_derive };
 
     let expanded = quote! {
         #[derive(#create_derives)]
+        #rename_all_attr
         pub struct #create_name {
             #(#create_struct_fields),*
         }
 
+        #debug_impl
+
         impl From<#create_name> for #active_model_type {
-            fn from(create: #create_name) -> Self {
+            fn from(#create_binding: #create_name) -> Self {
+                #(#sanitize_lines)*
                 #active_model_type {
                     #(#conv_lines),*
                 }
             }
         }
+
+        #default_impl
+        #builder
     };
 
     TokenStream::from(expanded)
@@ -174,6 +295,10 @@ pub fn to_update_model(input: TokenStream) -> TokenStream {
     let name = &input.ident;
     let update_name = format_ident!("{}Update", name);
 
+    if let Err(e) = attribute_validator::validate_attributes(&input.attrs, fields_of(&input)) {
+        return e.to_compile_error().into();
+    }
+
     let active_model_type = match extract_active_model_type(&input, name) {
         Ok(ty) => ty,
         Err(e) => return e.into(),
@@ -182,25 +307,45 @@ pub fn to_update_model(input: TokenStream) -> TokenStream {
         Ok(f) => f,
         Err(e) => return e,
     };
+    let meta = attribute_parser::parse_crud_resource_meta(&input.attrs);
     let included_fields = crate::codegen::models::update::filter_update_fields(&fields);
-    let update_struct_fields =
-        crate::codegen::models::update::generate_update_struct_fields(&included_fields);
+    let update_struct_fields = crate::codegen::models::update::generate_update_struct_fields(
+        &included_fields,
+        meta.skip_openapi,
+    );
     let included_merge = codegen::models::merge::generate_included_merge_code(&included_fields);
+    let sanitize_lines = codegen::models::shared::generate_sanitize_lines(
+        included_fields.iter().copied(),
+        &quote! { self },
+    );
+    let self_binding = if sanitize_lines.is_empty() {
+        quote! { self }
+    } else {
+        quote! { mut self }
+    };
     let excluded_merge = codegen::models::merge::generate_excluded_merge_code(&fields);
+    let rename_all_attr =
+        codegen::models::shared::generate_rename_all_attr(meta.rename_all.as_deref());
 
-    // Always include ToSchema for Update models
+    // ToSchema unless `openapi = false`
     // Circular dependencies are handled by schema(no_recursion) on join fields in the main model
-    let update_derives =
-        quote! { Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema };
+    let schema_derive = codegen::models::shared::schema_derive(meta.skip_openapi);
+    let (debug_derive, debug_impl) =
+        codegen::models::shared::generate_debug(&update_name, included_fields.iter().copied());
+    let update_derives = quote! { Clone, #debug_derive PartialEq, serde::Serialize, serde::Deserialize #schema_derive };
 
     let expanded = quote! {
         #[derive(#update_derives)]
+        #rename_all_attr
         pub struct #update_name {
             #(#update_struct_fields),*
         }
 
+        #debug_impl
+
         impl #update_name {
-            pub fn merge_fields(self, mut model: #active_model_type) -> Result<#active_model_type, crudcrate::ApiError> {
+            pub fn merge_fields(#self_binding, mut model: #active_model_type) -> Result<#active_model_type, crudcrate::ApiError> {
+                #(#sanitize_lines)*
                 #(#included_merge)*
                 #(#excluded_merge)*
                 Ok(model)
@@ -226,21 +371,30 @@ pub fn to_list_model(input: TokenStream) -> TokenStream {
     let name = &input.ident;
     let list_name = format_ident!("{}List", name);
 
+    if let Err(e) = attribute_validator::validate_attributes(&input.attrs, fields_of(&input)) {
+        return e.to_compile_error().into();
+    }
+
     let fields = match fields::extract_named_fields(&input) {
         Ok(f) => f,
         Err(e) => return e,
     };
+    let meta = attribute_parser::parse_crud_resource_meta(&input.attrs);
     let list_struct_fields =
-        crate::codegen::models::list::generate_list_struct_fields(&fields, name);
+        crate::codegen::models::list::generate_list_struct_fields(&fields, name, meta.skip_openapi);
     let list_from_assignments =
         crate::codegen::models::list::generate_list_from_assignments(&fields);
+    let rename_all_attr =
+        codegen::models::shared::generate_rename_all_attr(meta.rename_all.as_deref());
 
-    // Always include ToSchema for List models
+    // ToSchema unless `openapi = false`
     // Circular dependencies are handled by schema(no_recursion) on join fields in the main model
-    let list_derives = quote! { Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema };
+    let schema_derive = codegen::models::shared::schema_derive(meta.skip_openapi);
+    let list_derives = quote! { Clone, serde::Serialize, serde::Deserialize #schema_derive };
 
     let expanded = quote! {
         #[derive(#list_derives)]
+        #rename_all_attr
         pub struct #list_name {
             #(#list_struct_fields),*
         }
@@ -269,12 +423,18 @@ pub fn to_list_model(input: TokenStream) -> TokenStream {
 /// This function will panic in the following cases:
 /// - When deprecated syntax is used (e.g., `create_model = false` instead of `exclude(create)`)
 /// - When there are cyclic join dependencies without explicit depth specification
-/// - When required Sea-ORM relation enums are missing for join fields
+/// - When an `Option<T>`/`T` join has no `Related<Target>` impl, or `relation = "..."` names no `Relation` variant
 #[proc_macro_derive(EntityToModels, attributes(crudcrate))]
+#[allow(clippy::too_many_lines)]
 pub fn entity_to_models(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = &input.ident;
 
+    // Reject unknown `#[crudcrate(...)]` keys before anything reads them
+    if let Err(e) = attribute_validator::validate_attributes(&input.attrs, fields_of(&input)) {
+        return e.to_compile_error().into();
+    }
+
     // Parse and validate attributes
     let (api_struct_name, active_model_path) = fields::parse_entity_attributes(&input, struct_name);
     let table_name = attribute_parser::extract_table_name(&input.attrs)
@@ -350,6 +510,12 @@ pub fn entity_to_models(input: TokenStream) -> TokenStream {
         if let Some(ref fk) = j.fk_column {
             parts.push(format!("fk_column = \"{fk}\""));
         }
+        if let Some(limit) = j.limit {
+            parts.push(format!("limit = {limit}"));
+        }
+        if let Some(ref order_by) = j.order_by {
+            parts.push(format!("order_by = \"{order_by}\""));
+        }
         let join_attr = parts.join(", ");
         let struct_str = format!(
             "struct S {{ #[sea_orm(ignore)] #[crudcrate(non_db_attr, exclude(create, update), join({join_attr}))] pub {}: {} }}",
@@ -381,6 +547,281 @@ pub fn entity_to_models(input: TokenStream) -> TokenStream {
     if let Err(e) = fields::validate_field_analysis(&field_analysis) {
         return e;
     }
+    if let Some(tree) = &crud_meta.tree
+        && !field_analysis.db_fields.iter().any(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == &tree.parent_field)
+        })
+    {
+        return syn::Error::new_spanned(
+            &input,
+            format!(
+                "tree parent_field `{}` is not a field of this model",
+                tree.parent_field
+            ),
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    if let Some(sync) = &crud_meta.sync {
+        let field = field_analysis
+            .db_fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == sync));
+        let message = match field {
+            None => Some(format!("sync field `{sync}` is not a field of this model")),
+            Some(field)
+                if !matches!(
+                    codegen::type_resolution::date_kind(&field.ty),
+                    Some("Timestamp" | "TimestampTz")
+                ) =>
+            {
+                Some(format!("sync field `{sync}` must be a timestamp"))
+            }
+            Some(_) => None,
+        };
+        if let Some(message) = message {
+            return syn::Error::new_spanned(&input, message)
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    if let Some(temporal) = &crud_meta.temporal {
+        let message = [&temporal.from, &temporal.to].into_iter().find_map(|name| {
+            let field = field_analysis
+                .db_fields
+                .iter()
+                .find(|field| field.ident.as_ref().is_some_and(|ident| ident == name));
+            match field {
+                None => Some(format!(
+                    "temporal field `{name}` is not a field of this model"
+                )),
+                Some(field) if codegen::type_resolution::date_kind(&field.ty).is_none() => Some(
+                    format!("temporal field `{name}` must be a date or timestamp"),
+                ),
+                Some(_) => None,
+            }
+        });
+        if let Some(message) = message {
+            return syn::Error::new_spanned(&input, message)
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    if let Some(machine) = &crud_meta.state_machine {
+        let field = field_analysis.db_fields.iter().find(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == &machine.field)
+        });
+        let message = match field {
+            None => Some(format!(
+                "state_machine field `{}` is not a field of this model",
+                machine.field
+            )),
+            Some(field) if !codegen::models::should_include_in_model(field, "update_model") => {
+                Some(format!(
+                    "state_machine field `{}` is excluded from the update model, so transitions can't write it",
+                    machine.field
+                ))
+            }
+            Some(_) => None,
+        };
+        if let Some(message) = message {
+            return syn::Error::new_spanned(&input, message)
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    if let Some(column) = &crud_meta.normalized_search {
+        let field = field_analysis
+            .db_fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == column));
+        let message = match field {
+            None => Some(format!(
+                "normalized_search column `{column}` is not a field of this model"
+            )),
+            Some(field)
+                if !codegen::type_resolution::is_text_type(&field.ty)
+                    || codegen::type_resolution::is_option_type(&field.ty) =>
+            {
+                Some(format!(
+                    "normalized_search column `{column}` must be a `String`"
+                ))
+            }
+            Some(field)
+                if codegen::models::should_include_in_model(field, "create_model")
+                    || codegen::models::should_include_in_model(field, "update_model") =>
+            {
+                Some(format!(
+                    "normalized_search column `{column}` is written on every save; add `exclude(create, update)`"
+                ))
+            }
+            Some(_) if field_analysis.fulltext_fields.is_empty() => Some(
+                "`normalized_search` folds the `fulltext` fields, and this model has none"
+                    .to_string(),
+            ),
+            Some(_) if crud_meta.tsvector_column.is_some() || crud_meta.fts5_table.is_some() => {
+                Some(
+                    "`normalized_search` can't be combined with `tsvector_column` or `fts5_table`"
+                        .to_string(),
+                )
+            }
+            Some(_) => None,
+        };
+        if let Some(message) = message {
+            return syn::Error::new_spanned(&input, message)
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    if let Some(filter) = crud_meta.virtual_filters.iter().find(|filter| {
+        field_analysis.filterable_fields.iter().any(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == &filter.name)
+        })
+    }) {
+        return syn::Error::new_spanned(
+            &input,
+            format!(
+                "virtual_filter `{}` has the name of a filterable field; use \
+                 `filter_with` on the field instead",
+                filter.name
+            ),
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    if crud_meta.security.is_some() && crud_meta.skip_openapi {
+        return syn::Error::new_spanned(
+            &input,
+            "`security` declares an OpenAPI security requirement and needs `openapi`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    if crud_meta.cascade_delete {
+        let message = if crud_meta.restrict_delete {
+            Some("`cascade_delete` and `restrict_delete` can't be combined")
+        } else if codegen::joins::cascade::cascade_children(&field_analysis).is_empty() {
+            Some("`cascade_delete` deletes the rows of `Vec` join fields, and this model has none")
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            return syn::Error::new_spanned(&input, message)
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    if let Some(column) = &crud_meta.guard_delete {
+        let field = field_analysis
+            .db_fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == column));
+        let message = match field {
+            None => Some(format!(
+                "guard_delete column `{column}` is not a field of this model"
+            )),
+            Some(field)
+                if !matches!(
+                    codegen::type_resolution::extract_option_inner_type_ref(&field.ty),
+                    syn::Type::Path(type_path) if type_path.path.is_ident("bool")
+                ) =>
+            {
+                Some(format!(
+                    "guard_delete column `{column}` must be a `bool` or `Option<bool>`"
+                ))
+            }
+            Some(_) => None,
+        };
+        if let Some(message) = message {
+            return syn::Error::new_spanned(&input, message)
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    if crud_meta.view_sql.is_some() {
+        let conflict = [
+            ("auto_index", crud_meta.auto_index),
+            ("restrict_delete", crud_meta.restrict_delete),
+            ("cascade_delete", crud_meta.cascade_delete),
+            ("guard_delete", crud_meta.guard_delete.is_some()),
+            ("dedupe_on", !crud_meta.dedupe_on.is_empty()),
+            ("unique_together", !crud_meta.unique_together.is_empty()),
+            ("sync", crud_meta.sync.is_some()),
+            ("webhooks", crud_meta.webhooks),
+            ("state_machine", crud_meta.state_machine.is_some()),
+            ("slug_from", field_analysis.slug_field.is_some()),
+        ]
+        .into_iter()
+        .find_map(|(name, set)| set.then_some(name));
+        if let Some(name) = conflict {
+            return syn::Error::new_spanned(
+                &input,
+                format!("`{name}` can't be combined with `view_sql`, which is read-only"),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    for name in &crud_meta.dedupe_on {
+        let field = field_analysis
+            .db_fields
+            .iter()
+            .find(|field| field.ident.as_ref() == Some(name));
+        let message = match field {
+            None => Some(format!(
+                "dedupe_on field `{name}` is not a field of this model"
+            )),
+            Some(field) if !codegen::models::should_include_in_model(field, "create_model") => {
+                Some(format!(
+                    "dedupe_on field `{name}` is excluded from the create model, so a payload can't be compared on it"
+                ))
+            }
+            Some(_) => None,
+        };
+        if let Some(message) = message {
+            return syn::Error::new_spanned(name, message)
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    for name in crud_meta
+        .unique_together
+        .iter()
+        .flat_map(|unique| &unique.fields)
+    {
+        if !field_analysis
+            .db_fields
+            .iter()
+            .any(|field| field.ident.as_ref() == Some(name))
+        {
+            return syn::Error::new_spanned(
+                name,
+                format!("unique_together field `{name}` is not a column of this model"),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
 
     // Setup join validation - check for cyclic dependencies
     let cyclic_dependency_check = relation_validator::generate_cyclic_dependency_check(
@@ -393,7 +834,11 @@ pub fn entity_to_models(input: TokenStream) -> TokenStream {
 
     // Generate core API model components
     let (api_struct_fields, from_model_assignments) =
-        codegen::models::api_struct::generate_api_struct_content(&field_analysis, &api_struct_name);
+        codegen::models::api_struct::generate_api_struct_content(
+            &field_analysis,
+            &api_struct_name,
+            crud_meta.skip_openapi,
+        );
     let api_struct = codegen::models::api_struct::generate_api_struct(
         &api_struct_name,
         &api_struct_fields,
@@ -436,7 +881,18 @@ pub fn entity_to_models(input: TokenStream) -> TokenStream {
     });
 
     let router_impl = if crud_meta.generate_router && has_crud_resource_fields {
-        crate::codegen::router::axum::generate_router_impl(&api_struct_name, has_scoped_fields)
+        crate::codegen::router::axum::generate_router_impl(
+            &api_struct_name,
+            has_scoped_fields,
+            &crud_meta,
+            &field_analysis,
+        )
+    } else {
+        quote! {}
+    };
+
+    let factory_impl = if cfg!(feature = "testing") && has_crud_resource_fields {
+        codegen::factory::generate_factory(&api_struct_name, &field_analysis)
     } else {
         quote! {}
     };
@@ -444,6 +900,7 @@ pub fn entity_to_models(input: TokenStream) -> TokenStream {
     let crud_impl = quote! {
         #crud_impl_inner
         #router_impl
+        #factory_impl
     };
 
     // Generate list and response models
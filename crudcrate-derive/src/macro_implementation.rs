@@ -2,17 +2,24 @@ use crate::codegen::{
     handlers::{create, delete, get, update},
     joins::get_join_config,
     type_resolution::{
-        extract_api_struct_type_for_recursive_call, generate_crud_type_aliases,
-        generate_enum_field_checker, generate_field_entries, generate_id_column,
-        generate_like_filterable_entries, generate_scoped_excluded_entries,
-        get_path_from_field_type, is_vec_type,
+        extract_api_struct_type_for_recursive_call, generate_array_filterable_entries,
+        generate_case_insensitive_entries, generate_crud_type_aliases,
+        generate_custom_filter_entries, generate_date_filterable_entries,
+        generate_decimal_filterable_entries, generate_enum_field_checker, generate_enum_variants,
+        generate_field_alias_entries, generate_field_entries, generate_id_column,
+        generate_json_filterable_entries, generate_like_filterable_entries,
+        generate_scoped_excluded_entries, generate_sensitive_entries,
+        generate_sort_collation_entries, generate_time_crate_entries, get_path_from_field_type,
+        ident_to_string, is_vec_type,
     },
 };
 use crate::traits::crudresource::structs::{
-    CRUDResourceMeta, EntityFieldAnalysis, JoinFilterSortConfig,
+    CRUDResourceMeta, EntityFieldAnalysis, JoinFilterSortConfig, StructLevelGeo, VirtualFilter,
 };
+use heck::ToPascalCase;
 use quote::quote;
 
+#[allow(clippy::too_many_lines)]
 pub(crate) fn generate_crud_resource_impl(
     api_struct_name: &syn::Ident,
     crud_meta: &CRUDResourceMeta,
@@ -33,9 +40,33 @@ pub(crate) fn generate_crud_resource_impl(
     let sortable_entries = generate_field_entries(&analysis.sortable_fields);
     let filterable_entries = generate_field_entries(&analysis.filterable_fields);
     let like_filterable_entries = generate_like_filterable_entries(&analysis.filterable_fields);
+    let case_insensitive_entries = generate_case_insensitive_entries(&analysis.filterable_fields);
+    let sort_collation_entries = generate_sort_collation_entries(&analysis.sortable_fields);
+    let custom_filter_entries = generate_custom_filter_entries(&analysis.filterable_fields);
+    let json_filterable_entries = generate_json_filterable_entries(&analysis.filterable_fields);
+    let array_filterable_entries = generate_array_filterable_entries(&analysis.filterable_fields);
+    let decimal_filterable_entries =
+        generate_decimal_filterable_entries(&analysis.filterable_fields);
+    let date_filterable_entries = generate_date_filterable_entries(&analysis.filterable_fields);
+    let time_crate_entries = generate_time_crate_entries(&analysis.db_fields);
+    let large_text_entries = generate_field_entries(&analysis.large_text_fields);
+    let lookup_entries = generate_field_entries(&analysis.lookup_fields);
     let scoped_excluded_entries = generate_scoped_excluded_entries(&analysis.db_fields);
+    let sensitive_entries = generate_sensitive_entries(&analysis.db_fields);
+    let field_alias_entries =
+        generate_field_alias_entries(&analysis.db_fields, crud_meta.rename_all.as_deref());
     let fulltext_entries = generate_field_entries(&analysis.fulltext_fields);
+    let trigram_impl = generate_trigram_columns_impl(&analysis.trigram_fields);
+    let boolean_fulltext_impl =
+        generate_boolean_fulltext_columns_impl(&analysis.boolean_fulltext_fields);
+    let geo_impl = generate_geo_fields_impl(&analysis.geo_fields, &crud_meta.struct_level_geo);
+    let virtual_filters_impl = generate_virtual_filters_impl(&crud_meta.virtual_filters);
+    let file_upload_impl = generate_file_upload_impl(
+        &analysis.file_upload_fields,
+        crud_meta.rename_all.as_deref(),
+    );
     let enum_field_checker = generate_enum_field_checker(&analysis.db_fields);
+    let enum_variants = generate_enum_variants(&analysis.db_fields);
     let name_singular = crud_meta.name_singular.as_deref().unwrap_or("resource");
     let description = crud_meta.description.as_deref().unwrap_or("");
     let fulltext_language = crud_meta.fulltext_language.as_deref().unwrap_or("english");
@@ -83,6 +114,47 @@ pub(crate) fn generate_crud_resource_impl(
         }
     });
 
+    let max_filter_length_impl = crud_meta.max_filter_length.map(|length| {
+        quote! {
+            fn max_filter_length() -> usize { #length }
+        }
+    });
+
+    let max_filter_depth_impl = crud_meta.max_filter_depth.map(|depth| {
+        quote! {
+            fn max_filter_depth() -> usize { #depth }
+        }
+    });
+
+    let operation_timeout_impl = crud_meta.timeout_ms.map(|ms| {
+        quote! {
+            fn operation_timeout() -> Option<std::time::Duration> {
+                Some(std::time::Duration::from_millis(#ms))
+            }
+        }
+    });
+
+    let retry_policy_impl = crud_meta.retry_attempts.map(|attempts| {
+        quote! {
+            fn retry_policy() -> crudcrate::retry::RetryPolicy {
+                crudcrate::retry::RetryPolicy::new(#attempts)
+            }
+        }
+    });
+
+    let join_budget_impl =
+        (crud_meta.max_join_queries.is_some() || crud_meta.max_joined_rows.is_some()).then(|| {
+            let max_queries = crud_meta.max_join_queries.unwrap_or(u64::MAX);
+            let max_rows = crud_meta.max_joined_rows.unwrap_or(u64::MAX);
+            quote! {
+                fn join_budget() -> crudcrate::complexity::JoinBudget {
+                    crudcrate::complexity::JoinBudget::unlimited()
+                        .max_queries(#max_queries)
+                        .max_rows(#max_rows)
+                }
+            }
+        });
+
     // Generate require_scope constant (only when attribute is set, otherwise use trait default)
     let require_scope_impl = if crud_meta.require_scope {
         Some(quote! {
@@ -92,8 +164,310 @@ pub(crate) fn generate_crud_resource_impl(
         None
     };
 
+    let tsvector_column_impl = crud_meta.tsvector_column.as_ref().map(|column| {
+        quote! {
+            const TSVECTOR_COLUMN: Option<&'static str> = Some(#column);
+        }
+    });
+
+    let fts5_table_impl = crud_meta.fts5_table.as_ref().map(|table| {
+        quote! {
+            const FTS5_TABLE: Option<&'static str> = Some(#table);
+        }
+    });
+
+    let normalized_search_impl = crud_meta.normalized_search.as_ref().map(|column| {
+        quote! {
+            const NORMALIZED_SEARCH_COLUMN: Option<&'static str> = Some(#column);
+        }
+    });
+
+    let view_sql_impl = crud_meta.view_sql.as_ref().map(|sql| {
+        quote! {
+            const VIEW_SQL: Option<&'static str> = Some(#sql);
+        }
+    });
+
+    let auto_index_impl = if crud_meta.auto_index {
+        Some(quote! {
+            const AUTO_INDEX: bool = true;
+        })
+    } else {
+        None
+    };
+
+    let enum_case_sensitive_impl = if crud_meta.enum_case_sensitive {
+        Some(quote! {
+            const ENUM_CASE_SENSITIVE: bool = true;
+        })
+    } else {
+        None
+    };
+
+    let strict_query_impl = crud_meta.strict_query.then(|| {
+        quote! {
+            const STRICT_QUERY: bool = true;
+        }
+    });
+
+    let server_timing_impl = crud_meta.server_timing.then(|| {
+        quote! {
+            const SERVER_TIMING: bool = true;
+        }
+    });
+
+    let unstable_sort_impl = crud_meta.unstable_sort.then(|| {
+        quote! {
+            const STABLE_SORT: bool = false;
+        }
+    });
+
+    let serialize_writes_impl = crud_meta.serialize_writes.then(|| {
+        quote! {
+            const SERIALIZE_WRITES: bool = true;
+        }
+    });
+
+    let webhooks_impl = crud_meta.webhooks.then(|| {
+        quote! {
+            const WEBHOOKS: bool = true;
+        }
+    });
+
+    // Writes a batch transaction can't run: hooks, `operations` and nested creates
+    let write_hooks = [
+        &crud_meta.hooks.create.one,
+        &crud_meta.hooks.update.one,
+        &crud_meta.hooks.delete.one,
+    ];
+    let custom_writes = crud_meta.operations.is_some()
+        || write_hooks.iter().any(|hooks| {
+            hooks.pre.is_some()
+                || hooks.body.is_some()
+                || hooks.transform.is_some()
+                || hooks.post.is_some()
+                || hooks.post_async.is_some()
+        })
+        || crate::codegen::joins::nested_create::generate_nested_creates(analysis, api_struct_name)
+            .is_some();
+    let custom_writes_impl = custom_writes.then(|| {
+        quote! {
+            const CUSTOM_WRITES: bool = true;
+        }
+    });
+
+    let tree_impl = crud_meta.tree.as_ref().map(|tree| {
+        let column = quote::format_ident!("{}", tree.parent_field.to_pascal_case());
+        let max_depth = tree
+            .max_depth
+            .map(|depth| quote! { fn tree_max_depth() -> u32 { #depth } });
+        quote! {
+            fn tree_parent_column() -> Option<Self::ColumnType> {
+                Some(Self::ColumnType::#column)
+            }
+            #max_depth
+        }
+    });
+
+    let slug_impl = analysis.slug_field.as_ref().and_then(|slug| {
+        let column = quote::format_ident!(
+            "{}",
+            slug.field.ident.as_ref()?.to_string().to_pascal_case()
+        );
+        Some(quote! {
+            fn slug_column() -> Option<Self::ColumnType> {
+                Some(Self::ColumnType::#column)
+            }
+        })
+    });
+
+    let sync_impl = crud_meta.sync.as_ref().and_then(|sync| {
+        let field = analysis
+            .db_fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == sync))?;
+        let kind = quote::format_ident!("{}", crate::codegen::type_resolution::date_kind(&field.ty)?);
+        let column = quote::format_ident!("{}", sync.to_pascal_case());
+        Some(quote! {
+            fn sync_column() -> Option<(
+                &'static str,
+                Self::ColumnType,
+                crudcrate::filtering::dates::DateKind,
+            )> {
+                Some((#sync, Self::ColumnType::#column, crudcrate::filtering::dates::DateKind::#kind))
+            }
+        })
+    });
+
+    let temporal_impl = crud_meta.temporal.as_ref().and_then(|temporal| {
+        let bound = |name: &str| {
+            let field = analysis
+                .db_fields
+                .iter()
+                .find(|field| field.ident.as_ref().is_some_and(|ident| ident == name))?;
+            let kind =
+                quote::format_ident!("{}", crate::codegen::type_resolution::date_kind(&field.ty)?);
+            let column = quote::format_ident!("{}", name.to_pascal_case());
+            Some(quote! {
+                (#name, Self::ColumnType::#column, crudcrate::filtering::dates::DateKind::#kind)
+            })
+        };
+        let from = bound(&temporal.from)?;
+        let to = bound(&temporal.to)?;
+        Some(quote! {
+            fn temporal_columns() -> Option<crudcrate::temporal::Validity<Self::ColumnType>> {
+                Some(crudcrate::temporal::Validity { from: #from, to: #to })
+            }
+        })
+    });
+
+    let read_condition_impl = crud_meta.hooks.read.many.condition.as_ref().map(|fn_path| {
+        quote! {
+            fn read_condition() -> Option<sea_orm::Condition> {
+                Some(#fn_path())
+            }
+        }
+    });
+
+    let dedupe_impl = (!crud_meta.dedupe_on.is_empty()).then(|| {
+        let names: Vec<String> = crud_meta.dedupe_on.iter().map(ident_to_string).collect();
+        let columns = crud_meta
+            .dedupe_on
+            .iter()
+            .map(|ident| quote::format_ident!("{}", ident_to_string(ident).to_pascal_case()));
+        let idents = &crud_meta.dedupe_on;
+        quote! {
+            fn dedupe_fields() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+
+            fn dedupe_values(
+                create: &Self::CreateModel,
+            ) -> Vec<(&'static str, Self::ColumnType, Option<String>)> {
+                vec![#((
+                    #names,
+                    Self::ColumnType::#columns,
+                    crudcrate::core::duplicates::DedupeValue::dedupe_value(&create.#idents),
+                )),*]
+            }
+        }
+    });
+
+    // Transitions keyed by the field's serialized name, which the transition body uses
+    let state_machine_impl = crud_meta.state_machine.as_ref().and_then(|machine| {
+        let field = analysis.db_fields.iter().find(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == &machine.field)
+        })?;
+        let external = crate::attribute_parser::get_crudcrate_strings(field, "rename")
+            .into_iter()
+            .last()
+            .or_else(|| {
+                crud_meta.rename_all.as_deref().map(|rule| {
+                    crate::codegen::models::shared::apply_rename_all(rule, &machine.field)
+                })
+            })
+            .unwrap_or_else(|| machine.field.clone());
+        let transitions = machine
+            .transitions
+            .iter()
+            .map(|(from, to)| quote! { (#from, #to) });
+        Some(quote! {
+            fn state_machine() -> Option<crudcrate::state_machine::StateMachine> {
+                Some(crudcrate::state_machine::StateMachine {
+                    field: #external,
+                    transitions: &[#(#transitions),*],
+                })
+            }
+        })
+    });
+
+    let unique_together_impl = (!crud_meta.unique_together.is_empty()).then(|| {
+        let constraints = crud_meta.unique_together.iter().map(|unique| {
+            let names = unique.fields.iter().map(ident_to_string);
+            let columns = unique
+                .fields
+                .iter()
+                .map(|ident| quote::format_ident!("{}", ident_to_string(ident).to_pascal_case()));
+            let condition = unique
+                .condition
+                .as_ref()
+                .map_or_else(|| quote! { None }, |sql| quote! { Some(#sql) });
+            quote! {
+                crudcrate::core::unique::UniqueTogether {
+                    fields: vec![#((#names, Self::ColumnType::#columns)),*],
+                    condition: #condition,
+                }
+            }
+        });
+        quote! {
+            fn unique_together() -> Vec<crudcrate::core::unique::UniqueTogether<Self::ColumnType>> {
+                vec![#(#constraints),*]
+            }
+        }
+    });
+
+    let metadata_impl = crate::codegen::metadata::generate_metadata_impl(analysis);
+    let join_cost_impl =
+        crate::codegen::joins::cost::generate_join_cost_impl(analysis, api_struct_name);
+    let relation_filters_impl =
+        crate::codegen::joins::exists::generate_relation_filters_impl(analysis, api_struct_name);
+
+    let cascade_delete_impl = crud_meta.cascade_delete.then(|| {
+        crate::codegen::joins::cascade::generate_cascade_delete_impl(analysis, api_struct_name)
+    });
+    // Locked rows are reported before dependents: clearing the flag comes first
+    let ensure_deletable_impl = (crud_meta.restrict_delete || crud_meta.guard_delete.is_some())
+        .then(|| {
+            let guard = crud_meta.guard_delete.as_ref().map(|column| {
+                let column = quote::format_ident!("{}", column.to_pascal_case());
+                quote! {
+                    crudcrate::core::guard::ensure_unlocked::<Self>(db, ids, Self::ColumnType::#column)
+                        .await?;
+                }
+            });
+            let delete_condition = crud_meta.guard_delete.as_ref().map(|column| {
+                let column = quote::format_ident!("{}", column.to_pascal_case());
+                quote! {
+                    fn delete_condition() -> Option<sea_orm::Condition> {
+                        Some(crudcrate::core::guard::unlocked(Self::ColumnType::#column))
+                    }
+                }
+            });
+            let restrict = if crud_meta.restrict_delete {
+                quote! { crudcrate::relationships::ensure_no_dependents::<Self, Relation>(db, ids).await }
+            } else {
+                quote! { Ok(()) }
+            };
+            quote! {
+                async fn ensure_deletable<C: sea_orm::ConnectionTrait>(
+                    db: &C,
+                    ids: &[uuid::Uuid],
+                ) -> Result<(), crudcrate::ApiError> {
+                    #guard
+                    #restrict
+                }
+
+                #delete_condition
+            }
+        });
+    let state_field = crud_meta
+        .state_machine
+        .as_ref()
+        .map(|machine| quote::format_ident!("{}", machine.field));
+    let async_generator_impl = crate::codegen::generators::generate_async_generator_impls(
+        analysis,
+        !crud_meta.unique_together.is_empty(),
+        state_field.as_ref(),
+        crud_meta.normalized_search.as_deref(),
+    );
+
     // Generate #[cfg(test)] FK validation tests for Vec joins
     let fk_validation_tests = generate_fk_validation_tests(analysis, api_struct_name);
+    let join_loaders =
+        crate::codegen::joins::loading::generate_join_loaders(analysis, api_struct_name);
 
     quote! {
         #[async_trait::async_trait]
@@ -111,9 +485,27 @@ pub(crate) fn generate_crud_resource_impl(
             const TABLE_NAME: &'static str = #table_name;
             const RESOURCE_DESCRIPTION: &'static str = #description;
             const FULLTEXT_LANGUAGE: &'static str = #fulltext_language;
+            #tsvector_column_impl
+            #fts5_table_impl
+            #normalized_search_impl
+            #view_sql_impl
             #batch_limit_impl
             #require_scope_impl
+            #auto_index_impl
+            #enum_case_sensitive_impl
+            #strict_query_impl
+            #server_timing_impl
+            #unstable_sort_impl
+            #serialize_writes_impl
+            #webhooks_impl
+            #custom_writes_impl
             #max_page_size_impl
+            #max_filter_length_impl
+            #max_filter_depth_impl
+            #operation_timeout_impl
+            #retry_policy_impl
+            #join_budget_impl
+            #join_cost_impl
 
             fn sortable_columns() -> Vec<(&'static str, Self::ColumnType)> {
                 vec![#(#sortable_entries),*]
@@ -127,18 +519,80 @@ pub(crate) fn generate_crud_resource_impl(
                 #enum_field_checker
             }
 
+            fn enum_variants(field_name: &str) -> Option<Vec<String>> {
+                #enum_variants
+            }
+
             fn like_filterable_columns() -> Vec<&'static str> {
                 vec![#(#like_filterable_entries),*]
             }
 
+            fn case_insensitive_columns() -> Vec<&'static str> {
+                vec![#(#case_insensitive_entries),*]
+            }
+
+            fn sort_collations() -> Vec<(&'static str, &'static str)> {
+                vec![#(#sort_collation_entries),*]
+            }
+
+            fn custom_filters() -> Vec<(&'static str, crudcrate::filtering::FilterFn)> {
+                vec![#(#custom_filter_entries),*]
+            }
+
+            fn json_filterable_columns() -> Vec<&'static str> {
+                vec![#(#json_filterable_entries),*]
+            }
+
+            fn array_filterable_columns() -> Vec<(&'static str, &'static str)> {
+                vec![#(#array_filterable_entries),*]
+            }
+
+            fn decimal_filterable_columns() -> Vec<&'static str> {
+                vec![#(#decimal_filterable_entries),*]
+            }
+
+            fn date_filterable_columns() -> Vec<(&'static str, crudcrate::filtering::dates::DateKind)> {
+                vec![#(#date_filterable_entries),*]
+            }
+
+            fn time_crate_columns() -> Vec<(&'static str, crudcrate::filtering::dates::DateKind)> {
+                vec![#(#time_crate_entries),*]
+            }
+
+            fn large_text_columns() -> Vec<(&'static str, Self::ColumnType)> {
+                vec![#(#large_text_entries),*]
+            }
+
+            fn lookup_columns() -> Vec<(&'static str, Self::ColumnType)> {
+                vec![#(#lookup_entries),*]
+            }
+
             fn fulltext_searchable_columns() -> Vec<(&'static str, Self::ColumnType)> {
                 vec![#(#fulltext_entries),*]
             }
 
+            #trigram_impl
+            #boolean_fulltext_impl
+            #geo_impl
+            #virtual_filters_impl
+            #file_upload_impl
+            #tree_impl
+            #sync_impl
+            #slug_impl
+            #temporal_impl
+            #read_condition_impl
+            #dedupe_impl
+            #unique_together_impl
+            #state_machine_impl
+
             fn scoped_excluded_columns() -> &'static [&'static str] {
                 &[#(#scoped_excluded_entries),*]
             }
 
+            fn sensitive_fields() -> &'static [&'static str] {
+                &[#(#sensitive_entries),*]
+            }
+
             fn joined_filterable_columns() -> Vec<crudcrate::JoinedColumnDef> {
                 vec![#(#joined_filterable_entries),*]
             }
@@ -147,6 +601,13 @@ pub(crate) fn generate_crud_resource_impl(
                 vec![#(#joined_sortable_entries),*]
             }
 
+            fn field_aliases() -> Vec<(&'static str, &'static str)> {
+                vec![#(#field_alias_entries),*]
+            }
+
+            #metadata_impl
+            #async_generator_impl
+
             #get_one_impl
             #get_all_impl
             #create_impl
@@ -155,8 +616,13 @@ pub(crate) fn generate_crud_resource_impl(
             #update_many_impl
             #delete_impl
             #delete_many_impl
+            #cascade_delete_impl
+            #ensure_deletable_impl
+            #relation_filters_impl
         }
 
+        #join_loaders
+
         #fk_validation_tests
     }
 }
@@ -195,6 +661,159 @@ fn generate_joined_column_entries(
     entries
 }
 
+/// Generate `trigram_searchable_columns()` for fields declared with
+/// `fulltext(strategy = "trigram")`. Returns `None` (trait default) when there are none.
+fn generate_trigram_columns_impl(
+    trigram_fields: &[(&syn::Field, f64)],
+) -> Option<proc_macro2::TokenStream> {
+    if trigram_fields.is_empty() {
+        return None;
+    }
+
+    let entries = trigram_fields.iter().map(|(field, threshold)| {
+        let field_str = ident_to_string(field.ident.as_ref().unwrap());
+        let column_name = quote::format_ident!("{}", field_str.to_pascal_case());
+        quote! { (#field_str, Self::ColumnType::#column_name, #threshold) }
+    });
+
+    Some(quote! {
+        fn trigram_searchable_columns() -> Vec<(&'static str, Self::ColumnType, f64)> {
+            vec![#(#entries),*]
+        }
+    })
+}
+
+/// Generate `boolean_fulltext_columns()` for fields declared with
+/// `fulltext(mode = "boolean")`. Returns `None` (trait default) when there are none.
+fn generate_boolean_fulltext_columns_impl(
+    boolean_fields: &[&syn::Field],
+) -> Option<proc_macro2::TokenStream> {
+    if boolean_fields.is_empty() {
+        return None;
+    }
+
+    let entries = boolean_fields.iter().map(|field| {
+        let field_str = ident_to_string(field.ident.as_ref().unwrap());
+        let column_name = quote::format_ident!("{}", field_str.to_pascal_case());
+        quote! { (#field_str, Self::ColumnType::#column_name) }
+    });
+
+    Some(quote! {
+        fn boolean_fulltext_columns() -> Vec<(&'static str, Self::ColumnType)> {
+            vec![#(#entries),*]
+        }
+    })
+}
+
+/// Generate `geo_fields()` from field-level `geo` flags (spatial point columns) and
+/// struct-level `geo(name, lat, lon)` declarations. Returns `None` (trait default) when
+/// there are none.
+fn generate_geo_fields_impl(
+    point_fields: &[&syn::Field],
+    struct_level_geo: &[StructLevelGeo],
+) -> Option<proc_macro2::TokenStream> {
+    if point_fields.is_empty() && struct_level_geo.is_empty() {
+        return None;
+    }
+
+    let column_name = |field_name: &str| {
+        let column = quote::format_ident!("{}", field_name.to_pascal_case());
+        quote! { sea_orm::IdenStatic::as_str(&Self::ColumnType::#column) }
+    };
+
+    let points = point_fields.iter().map(|field| {
+        let field_str = ident_to_string(field.ident.as_ref().unwrap());
+        let column = column_name(&field_str);
+        quote! { (#field_str, crudcrate::filtering::GeoColumn::Point { column: #column }) }
+    });
+
+    let lat_lons = struct_level_geo.iter().map(|geo| {
+        let name = &geo.name;
+        let lat = column_name(&geo.lat);
+        let lon = column_name(&geo.lon);
+        quote! { (#name, crudcrate::filtering::GeoColumn::LatLon { lat: #lat, lon: #lon }) }
+    });
+
+    Some(quote! {
+        fn geo_fields() -> Vec<(&'static str, crudcrate::filtering::GeoColumn)> {
+            vec![#(#points,)* #(#lat_lons),*]
+        }
+    })
+}
+
+/// Generate `virtual_filters()` from struct-level `virtual_filter(...)` declarations.
+/// Returns `None` (trait default) when there are none.
+fn generate_virtual_filters_impl(filters: &[VirtualFilter]) -> Option<proc_macro2::TokenStream> {
+    if filters.is_empty() {
+        return None;
+    }
+
+    let entries = filters.iter().map(|filter| {
+        let name = &filter.name;
+        let description = filter.description.as_deref().unwrap_or("");
+        let builder = &filter.builder;
+        quote! {
+            crudcrate::filtering::VirtualFilter {
+                name: #name,
+                description: #description,
+                builder: #builder,
+            }
+        }
+    });
+
+    Some(quote! {
+        fn virtual_filters() -> Vec<crudcrate::filtering::VirtualFilter> {
+            vec![#(#entries),*]
+        }
+    })
+}
+
+/// Generate `file_fields()` and `stored_files()` from field-level `file_upload` attributes.
+/// Fields are listed under their serialized name, which is the multipart part name.
+/// Returns `None` (trait defaults) when there are none.
+fn generate_file_upload_impl(
+    fields: &[(&syn::Field, String)],
+    rename_all: Option<&str>,
+) -> Option<proc_macro2::TokenStream> {
+    if fields.is_empty() {
+        return None;
+    }
+
+    let file_fields = fields.iter().map(|(field, storage)| {
+        let field_str = ident_to_string(field.ident.as_ref().unwrap());
+        let external = crate::attribute_parser::get_crudcrate_strings(field, "rename")
+            .into_iter()
+            .last()
+            .or_else(|| {
+                rename_all
+                    .map(|rule| crate::codegen::models::shared::apply_rename_all(rule, &field_str))
+            })
+            .unwrap_or(field_str);
+        quote! { (#external, #storage) }
+    });
+
+    let stored_files = fields.iter().map(|(field, storage)| {
+        let ident = field.ident.as_ref().unwrap();
+        quote! {
+            if let Some(stored) = crudcrate::storage::StoredFile::stored_file(&self.#ident) {
+                files.push((#storage, stored.to_string()));
+            }
+        }
+    });
+
+    Some(quote! {
+        fn file_fields() -> Vec<(&'static str, &'static str)> {
+            vec![#(#file_fields),*]
+        }
+
+        fn stored_files(&self) -> Vec<(&'static str, String)> {
+            let mut files = Vec::new();
+            #(#stored_files)*
+            files
+        }
+    })
+}
+
 /// Generate `#[cfg(test)]` functions that validate FK column naming conventions
 /// against the actual `SeaORM` `RelationDef` at test time.
 ///
@@ -342,12 +961,13 @@ fn generate_method_impls(
 ) {
     let get_one_impl = get::generate_get_one_impl(crud_meta, analysis, api_struct_name);
     let get_all_impl = get::generate_get_all_impl(crud_meta, analysis, api_struct_name);
-    let create_impl = create::generate_create_impl(crud_meta);
-    let create_many_impl = create::generate_create_many_impl(crud_meta);
+    let create_impl = create::generate_create_impl(crud_meta, analysis, api_struct_name);
+    let create_many_impl = create::generate_create_many_impl(crud_meta, analysis, api_struct_name);
     let update_impl = update::generate_update_impl(crud_meta);
-    let update_many_impl = update::generate_update_many_impl(crud_meta);
-    let delete_impl = delete::generate_delete_impl(crud_meta);
-    let delete_many_impl = delete::generate_delete_many_impl(crud_meta);
+    let update_many_impl = update::generate_update_many_impl(crud_meta, analysis);
+    let has_file_uploads = !analysis.file_upload_fields.is_empty();
+    let delete_impl = delete::generate_delete_impl(crud_meta, has_file_uploads);
+    let delete_many_impl = delete::generate_delete_many_impl(crud_meta, has_file_uploads);
 
     (
         get_one_impl,
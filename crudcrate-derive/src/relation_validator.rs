@@ -4,10 +4,12 @@
 //! - Self-referencing joins without explicit depth (would default to `MAX_DEPTH`)
 //! - Depth exceeding `MAX_ALLOWED_DEPTH`
 //! - Bidirectional `SeaORM` relations that cause infinite recursion in `find_related()`
+//! - Missing `Related<Target>` impls and `relation = "..."` variants for join fields
 
 use crate::codegen::joins::get_join_config;
 use crate::codegen::type_resolution::{
-    extract_api_struct_type_for_recursive_call, get_path_from_field_type,
+    extract_api_struct_type_for_recursive_call, extract_option_inner_type_ref,
+    extract_vec_inner_type_ref, get_path_from_field_type, is_vec_type,
 };
 use crate::traits::crudresource::structs::EntityFieldAnalysis;
 use quote::quote;
@@ -119,6 +121,7 @@ pub fn generate_bidirectional_checks(
         if let Some(tokens) = check_bidirectional_relation(field, entity_name) {
             checks.push(tokens);
         }
+        checks.extend(check_relation_exists(field, entity_name));
     }
 
     quote! { #( #checks )* }
@@ -187,6 +190,104 @@ fn check_bidirectional_relation(
     }
 }
 
+/// Check that the `SeaORM` relation a join field loads through exists.
+///
+/// `Option<T>` and `T` joins load with `find_related()`, which needs
+/// `impl Related<Target> for Entity`. Without this check a missing impl only shows up as
+/// an unsatisfied trait bound deep inside the generated loader. An explicit
+/// `relation = "Name"` must name a variant of the `Relation` enum.
+fn check_relation_exists(field: &syn::Field, entity_name: &str) -> Vec<proc_macro2::TokenStream> {
+    let mut checks = Vec::new();
+    let Some(join_config) = get_join_config(field).config else {
+        return checks;
+    };
+
+    let field_name = field
+        .ident
+        .as_ref()
+        .map_or_else(|| "unknown".to_string(), std::string::ToString::to_string);
+    let span = field
+        .ident
+        .as_ref()
+        .map_or_else(proc_macro2::Span::call_site, syn::Ident::span);
+    let variant_name = join_config
+        .relation
+        .clone()
+        .unwrap_or_else(|| target_struct_name(&field.ty));
+
+    if let Some(relation) = &join_config.relation {
+        let Ok(variant) = syn::parse_str::<syn::Ident>(relation) else {
+            let msg = format!(
+                "crudcrate: join '{field_name}' has relation = \"{relation}\", which is not a valid Relation variant name"
+            );
+            checks.push(quote::quote_spanned! {span=> compile_error!(#msg); });
+            return checks;
+        };
+        // A missing variant fails here, pointing at the join field
+        let variant = syn::Ident::new(&variant.to_string(), span);
+        checks.push(quote::quote_spanned! {span=>
+            const _: fn() -> sea_orm::RelationDef =
+                || <Relation as sea_orm::RelationTrait>::def(&Relation::#variant);
+        });
+    }
+
+    // Vec<T> joins filter on the child's FK column and don't need `Related`
+    if is_vec_type(&field.ty) {
+        return checks;
+    }
+
+    let target_entity = match &join_config.path {
+        Some(path) => match path.parse::<proc_macro2::TokenStream>() {
+            Ok(path) => quote! { #path::Entity },
+            // Invalid paths are reported by the join loader
+            Err(_) => return checks,
+        },
+        None => get_path_from_field_type(&field.ty, "Entity"),
+    };
+    let target = target_entity.to_string().replace(' ', "");
+    let target_module = target.strip_suffix("::Entity").unwrap_or(&target);
+
+    let msg = format!(
+        "crudcrate: join '{field_name}' in '{entity_name}' needs a SeaORM relation to {target}, \
+         but Entity does not implement Related<{target}>.\n\
+         \n\
+         Option/single joins load with find_related(). Add a Relation variant and the Related impl:\n\
+         \n\
+         #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]\n\
+         pub enum Relation {{\n\
+         \x20   #[sea_orm(belongs_to = \"{target_module}::Entity\", from = \"Column::{variant_name}Id\", to = \"{target_module}::Column::Id\")]\n\
+         \x20   {variant_name},\n\
+         }}\n\
+         \n\
+         impl Related<{target}> for Entity {{\n\
+         \x20   fn to() -> RelationDef {{\n\
+         \x20       Relation::{variant_name}.def()\n\
+         \x20   }}\n\
+         }}"
+    );
+    // `assert!` reads the message as a format string
+    let msg = msg.replace('{', "{{").replace('}', "}}");
+    checks.push(quote::quote_spanned! {span=>
+        const _: () = assert!(
+            crudcrate::impls!(Entity: sea_orm::Related<#target_entity>),
+            #msg
+        );
+    });
+    checks
+}
+
+/// Name of the joined API struct, used as the suggested `Relation` variant
+fn target_struct_name(ty: &syn::Type) -> String {
+    let inner = extract_option_inner_type_ref(extract_vec_inner_type_ref(ty));
+    if let syn::Type::Path(type_path) = inner
+        && let Some(segment) = type_path.path.segments.last()
+    {
+        let name = segment.ident.to_string();
+        return name.strip_suffix("API").unwrap_or(&name).to_string();
+    }
+    "Target".to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,14 +326,82 @@ mod tests {
             sortable_fields: vec![],
             filterable_fields: vec![],
             fulltext_fields: vec![],
+            trigram_fields: vec![],
+            boolean_fulltext_fields: vec![],
+            geo_fields: vec![],
+            large_text_fields: vec![],
+            lookup_fields: vec![],
+            slug_field: None,
+            file_upload_fields: vec![],
             join_on_one_fields: vec![],
             join_on_all_fields: vec![],
             join_filter_sort_configs: vec![],
+            polymorphic_fields: vec![],
         };
         let result = generate_cyclic_dependency_check(&analysis, "TestEntity");
         assert!(result.is_empty());
     }
 
+    #[test]
+    fn test_option_join_requires_related_impl() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, join(one))]
+            pub owner: Option<User>
+        };
+        let checks = check_relation_exists(&field, "Post");
+        assert_eq!(checks.len(), 1);
+        let tokens = checks[0].to_string();
+        assert!(tokens.contains("sea_orm :: Related < super :: user :: Entity >"));
+        assert!(tokens.contains("Related<super::user::Entity>"));
+        assert!(tokens.contains("Relation::User.def()"));
+    }
+
+    #[test]
+    fn test_custom_path_used_for_related_check() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, join(one, path = "crate::accounts"))]
+            pub owner: Option<Account>
+        };
+        let tokens = check_relation_exists(&field, "Post")[0].to_string();
+        assert!(tokens.contains("Related < crate :: accounts :: Entity >"));
+    }
+
+    #[test]
+    fn test_vec_join_needs_no_related_impl() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, join(one, all, depth = 1))]
+            pub comments: Vec<Comment>
+        };
+        assert!(check_relation_exists(&field, "Post").is_empty());
+    }
+
+    #[test]
+    fn test_explicit_relation_variant_is_checked() {
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, join(one, relation = "Replies"))]
+            pub comments: Vec<Comment>
+        };
+        let checks = check_relation_exists(&field, "Post");
+        assert_eq!(checks.len(), 1);
+        assert!(checks[0].to_string().contains("Relation :: Replies"));
+
+        let field: syn::Field = parse_quote! {
+            #[crudcrate(non_db_attr, join(one, relation = "not valid"))]
+            pub comments: Vec<Comment>
+        };
+        let tokens = check_relation_exists(&field, "Post")[0].to_string();
+        assert!(tokens.contains("compile_error"));
+    }
+
+    #[test]
+    fn test_target_struct_name_strips_api_suffix() {
+        assert_eq!(target_struct_name(&parse_quote!(Option<UserAPI>)), "User");
+        assert_eq!(
+            target_struct_name(&parse_quote!(Vec<crate::tags::Tag>)),
+            "Tag"
+        );
+    }
+
     #[test]
     fn test_max_allowed_depth_constant() {
         assert_eq!(MAX_ALLOWED_DEPTH, 5);
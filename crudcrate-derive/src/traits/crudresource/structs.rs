@@ -1,12 +1,18 @@
+use crate::codegen::joins::polymorphic::PolymorphicConfig;
 use convert_case::{Case, Casing};
 
-/// Hook configuration for a single operation phase (pre, body, transform, post)
+/// Hook configuration for a single operation phase (pre, body, transform, post, `post_async`,
+/// condition)
 #[derive(Default, Clone)]
 pub(crate) struct OperationHooks {
     pub(crate) pre: Option<syn::Path>,
     pub(crate) body: Option<syn::Path>,
     pub(crate) transform: Option<syn::Path>,
     pub(crate) post: Option<syn::Path>,
+    /// Enqueued as a `crudcrate::jobs::Job` after `post`; write operations only
+    pub(crate) post_async: Option<syn::Path>,
+    /// `fn() -> Condition` added to every read; `read::many` only
+    pub(crate) condition: Option<syn::Path>,
 }
 
 /// Hooks for an operation with one/many variants
@@ -39,10 +45,73 @@ pub(crate) struct StructLevelJoin {
     pub(crate) filterable_columns: Vec<String>,
     pub(crate) sortable_columns: Vec<String>,
     pub(crate) fk_column: Option<String>,
+    pub(crate) limit: Option<u64>,
+    pub(crate) order_by: Option<String>,
+}
+
+/// A geo field built from two numeric columns, declared at the struct level with
+/// `geo(name = "location", lat = "latitude", lon = "longitude")`.
+#[derive(Clone)]
+pub(crate) struct StructLevelGeo {
+    pub(crate) name: String,
+    pub(crate) lat: String,
+    pub(crate) lon: String,
+}
+
+/// A filter key that isn't a column, declared with
+/// `virtual_filter(name = "active", builder = active_condition)`.
+#[derive(Clone)]
+pub(crate) struct VirtualFilter {
+    pub(crate) name: String,
+    pub(crate) builder: syn::Path,
+    pub(crate) description: Option<String>,
+}
+
+/// Fields that must be unique together, declared with
+/// `unique_together(email, org_id, condition = "deleted_at IS NULL")`
+#[derive(Clone)]
+pub(crate) struct UniqueTogether {
+    pub(crate) fields: Vec<syn::Ident>,
+    /// SQL condition of a partial constraint
+    pub(crate) condition: Option<String>,
+}
+
+/// A self-referencing resource, declared with `tree(parent_field = "parent_id", max_depth = 10)`
+#[derive(Clone)]
+pub(crate) struct StructLevelTree {
+    pub(crate) parent_field: String,
+    pub(crate) max_depth: Option<u32>,
+}
+
+/// Allowed transitions of a status field, declared with
+/// `state_machine(field = "status", transitions("todo -> doing", "doing -> done, todo"))`
+#[derive(Clone)]
+pub(crate) struct StructLevelStateMachine {
+    pub(crate) field: String,
+    /// `(from, to)` pairs, one per target state
+    pub(crate) transitions: Vec<(String, String)>,
+}
+
+/// Validity window of a `temporal` resource, `valid_from`/`valid_to` unless set with
+/// `temporal(from = "starts_at", to = "ends_at")`
+#[derive(Clone)]
+pub(crate) struct StructLevelTemporal {
+    pub(crate) from: String,
+    pub(crate) to: String,
+}
+
+impl Default for StructLevelTemporal {
+    fn default() -> Self {
+        Self {
+            from: "valid_from".to_string(),
+            to: "valid_to".to_string(),
+        }
+    }
 }
 
 /// Extracts `CRUDResource` metadata from struct-level crudcrate attributes
 #[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub(crate) struct CRUDResourceMeta {
     pub(crate) name_singular: Option<String>,
     pub(crate) name_plural: Option<String>,
@@ -53,15 +122,82 @@ pub(crate) struct CRUDResourceMeta {
     pub(crate) operations: Option<syn::Path>,
     pub(crate) generate_router: bool,
     pub(crate) fulltext_language: Option<String>,
+    // Stored tsvector generated column queried for fulltext search on Postgres
+    pub(crate) tsvector_column: Option<String>,
+    // FTS5 table queried for fulltext search on SQLite
+    pub(crate) fts5_table: Option<String>,
+    // Column filled with the folded text of the fulltext fields for accent-insensitive search
+    pub(crate) normalized_search: Option<String>,
+    // SQL query read from instead of the table; the router serves reads only
+    pub(crate) view_sql: Option<String>,
     pub(crate) derive_partial_eq: bool,
     pub(crate) derive_eq: bool,
     // Configurable limits
     pub(crate) batch_limit: Option<usize>,
     pub(crate) max_page_size: Option<u64>,
+    pub(crate) max_filter_length: Option<usize>,
+    pub(crate) max_filter_depth: Option<usize>,
+    // Request body limit applied to the generated router
+    pub(crate) max_body_size: Option<usize>,
+    // Deadline for each generated handler, in milliseconds
+    pub(crate) timeout_ms: Option<u64>,
+    // How long identical GET requests share one response, in milliseconds
+    pub(crate) singleflight_ms: Option<u64>,
+    // Attempts of the default write bodies after transient database errors
+    pub(crate) retry_attempts: Option<u32>,
+    // Join budget of the list and single-item handlers
+    pub(crate) max_join_queries: Option<u64>,
+    pub(crate) max_joined_rows: Option<u64>,
     // Struct-level join definitions (fields only on the API struct, not the Model)
     pub(crate) struct_level_joins: Vec<StructLevelJoin>,
+    // Lat/lon column pairs exposed as geo fields
+    pub(crate) struct_level_geo: Vec<StructLevelGeo>,
+    // Filter keys expanded by user functions instead of matching a column
+    pub(crate) virtual_filters: Vec<VirtualFilter>,
+    // Parent column served by the `/{id}/children` and `/{id}/ancestors` endpoints
+    pub(crate) tree: Option<StructLevelTree>,
+    // Status field whose updates must follow the declared transitions
+    pub(crate) state_machine: Option<StructLevelStateMachine>,
+    // Timestamp fields bounding the rows reads return at a point in time
+    pub(crate) temporal: Option<StructLevelTemporal>,
+    // Timestamp field the `/changes` endpoint reads changes by; deletes leave tombstones
+    pub(crate) sync: Option<String>,
+    // Record webhook deliveries after creates, updates and deletes
+    pub(crate) webhooks: bool,
+    // Fields compared by the `/check-duplicates` endpoint
+    pub(crate) dedupe_on: Vec<syn::Ident>,
+    // Field sets checked for duplicates before writes and indexed as unique
+    pub(crate) unique_together: Vec<UniqueTogether>,
     // Require scope middleware on read endpoints (returns 500 if missing)
     pub(crate) require_scope: bool,
+    // Allow `crudcrate::database::ensure_indexes` to create indexes for this resource
+    pub(crate) auto_index: bool,
+    // Match enum filter values exactly instead of case-insensitively
+    pub(crate) enum_case_sensitive: bool,
+    // Reject unknown filter keys and sort columns instead of ignoring them
+    pub(crate) strict_query: bool,
+    // Answer `X-Server-Timing` requests with a `Server-Timing` header
+    pub(crate) server_timing: bool,
+    // Leave ties in the list order unbroken instead of ordering them by ID
+    pub(crate) unstable_sort: bool,
+    // Lock rows in the update and delete handlers so writes to one row don't interleave
+    pub(crate) serialize_writes: bool,
+    // Generate a builder for the create model
+    pub(crate) create_builder: bool,
+    // Answer 409 instead of deleting rows that `has_many` relations still point at
+    pub(crate) restrict_delete: bool,
+    // Boolean column whose rows deletes refuse with 423
+    pub(crate) guard_delete: Option<String>,
+    // Delete the rows of `Vec` join fields with their parent, in one transaction
+    pub(crate) cascade_delete: bool,
+    // serde `rename_all` rule applied to every generated struct
+    pub(crate) rename_all: Option<String>,
+    // `openapi = false`: no `ToSchema` derives, `#[utoipa::path]` or `OpenApiRouter`
+    pub(crate) skip_openapi: bool,
+    // OpenAPI security scheme every generated operation requires
+    pub(crate) security: Option<String>,
+    // Functions returning tower layers applied to the generated routers, in order
+    pub(crate) layers: Vec<syn::Path>,
     // Deprecation errors to emit as compile errors
     pub(crate) deprecation_errors: Vec<syn::Error>,
 }
@@ -108,12 +244,30 @@ pub(crate) struct JoinFilterSortConfig {
     /// Reserved for future use (e.g., generating join queries)
     #[allow(dead_code)]
     pub(crate) entity_path: Option<String>,
-    /// Column names on the related entity that can be filtered (e.g., ["make", "year"])
+    /// Column names on the related entity that can be filtered (e.g., `["make", "year"]`)
     pub(crate) filterable_columns: Vec<String>,
-    /// Column names on the related entity that can be sorted (e.g., ["year"])
+    /// Column names on the related entity that can be sorted (e.g., `["year"]`)
     pub(crate) sortable_columns: Vec<String>,
 }
 
+/// Field-level fulltext configuration from `fulltext` or `fulltext(strategy = "trigram")`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FulltextFieldConfig {
+    /// `Some(threshold)` when the field uses `pg_trgm` similarity on Postgres
+    pub(crate) trigram_threshold: Option<f64>,
+    /// `fulltext(mode = "boolean")`: matched in boolean mode on `MySQL`
+    pub(crate) boolean_mode: bool,
+}
+
+/// A `#[crudcrate(slug_from = "title")]` field
+pub(crate) struct SlugField<'a> {
+    pub(crate) field: &'a syn::Field,
+    /// The text field the slug is made from
+    pub(crate) source: syn::Ident,
+    /// `slug_on_update`: updates changing `source` get a new slug
+    pub(crate) on_update: bool,
+}
+
 pub(crate) struct EntityFieldAnalysis<'a> {
     pub(crate) db_fields: Vec<&'a syn::Field>,
     pub(crate) non_db_fields: Vec<&'a syn::Field>,
@@ -121,8 +275,24 @@ pub(crate) struct EntityFieldAnalysis<'a> {
     pub(crate) sortable_fields: Vec<&'a syn::Field>,
     pub(crate) filterable_fields: Vec<&'a syn::Field>,
     pub(crate) fulltext_fields: Vec<&'a syn::Field>,
+    /// Fulltext fields using `pg_trgm` similarity on Postgres, with their thresholds
+    pub(crate) trigram_fields: Vec<(&'a syn::Field, f64)>,
+    /// Fulltext fields matched with `MATCH ... AGAINST` in boolean mode on `MySQL`
+    pub(crate) boolean_fulltext_fields: Vec<&'a syn::Field>,
+    /// Spatial point columns marked `geo`
+    pub(crate) geo_fields: Vec<&'a syn::Field>,
+    /// Text columns marked `large_text`, left out of lists and served from `/{id}/{field}`
+    pub(crate) large_text_fields: Vec<&'a syn::Field>,
+    /// Text columns marked `lookup`, alternate keys served from `/by/{field}/{value}`
+    pub(crate) lookup_fields: Vec<&'a syn::Field>,
+    /// The `slug_from` field, filled in from its source field on writes
+    pub(crate) slug_field: Option<SlugField<'a>>,
+    /// Fields marked `file_upload`, with their storage backend names
+    pub(crate) file_upload_fields: Vec<(&'a syn::Field, String)>,
     pub(crate) join_on_one_fields: Vec<&'a syn::Field>,
     pub(crate) join_on_all_fields: Vec<&'a syn::Field>,
     /// Join fields that have filter/sort configuration for related entity columns
     pub(crate) join_filter_sort_configs: Vec<JoinFilterSortConfig>,
+    /// Non-db fields with `polymorphic_join(...)`, with their configuration
+    pub(crate) polymorphic_fields: Vec<(&'a syn::Field, PolymorphicConfig)>,
 }
@@ -0,0 +1,24 @@
+//! Test that `lookup` is rejected on fields that aren't String columns
+
+use crudcrate::EntityToModels;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "orders")]
+#[crudcrate(api_struct = "Order", generate_router)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, exclude(create, update))]
+    pub id: Uuid,
+    #[crudcrate(lookup)]
+    pub number: i32,
+    #[crudcrate(lookup)]
+    pub customer_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+impl ActiveModelBehavior for ActiveModel {}
+
+fn main() {}
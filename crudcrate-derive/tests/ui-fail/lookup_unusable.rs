@@ -0,0 +1,38 @@
+//! Test that `lookup` is rejected on secret and response-excluded fields, and on
+//! columns without a unique index unless `lookup_not_unique` says so
+
+use crudcrate::EntityToModels;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+async fn hash(value: String) -> Result<String, crudcrate::ApiError> {
+    Ok(value)
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "accounts")]
+#[crudcrate(api_struct = "Account", generate_router)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, exclude(create, update))]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    #[crudcrate(lookup, sensitive)]
+    pub ssn: String,
+    #[sea_orm(unique)]
+    #[crudcrate(lookup, hash_with = hash)]
+    pub token: String,
+    #[sea_orm(unique)]
+    #[crudcrate(lookup, exclude(response))]
+    pub internal_code: String,
+    #[crudcrate(lookup)]
+    pub nickname: String,
+    #[crudcrate(lookup, lookup_not_unique)]
+    pub handle: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+impl ActiveModelBehavior for ActiveModel {}
+
+fn main() {}
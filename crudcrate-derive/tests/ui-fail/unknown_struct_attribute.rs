@@ -0,0 +1,25 @@
+//! Test that misspelled struct attributes and hooks are rejected with a suggestion
+
+use crudcrate::EntityToModels;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "todos")]
+#[crudcrate(
+    api_struct = "Todo",
+    generate_routes,
+    create::ones::pre = validate,
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key)]
+    pub id: Uuid,
+    pub title: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+impl ActiveModelBehavior for ActiveModel {}
+
+fn main() {}
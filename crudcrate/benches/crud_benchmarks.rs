@@ -68,7 +68,8 @@ pub struct Model {
     #[crudcrate(filterable)]
     pub published: bool,
 
-    #[crudcrate(filterable, sortable)]
+    // Exact case-insensitive matches through an index (see the collation indexes below)
+    #[crudcrate(filterable, sortable, ci)]
     pub category: String,
 
     #[crudcrate(filterable, sortable)]
@@ -208,10 +209,16 @@ impl MigrationTrait for CreateBenchmarkIndexes {
                 )
                 .await?;
 
+            // `ci` filters on category compare as CITEXT
+            manager
+                .get_connection()
+                .execute_unprepared("CREATE EXTENSION IF NOT EXISTS citext")
+                .await?;
+
             manager
                 .get_connection()
                 .execute_unprepared(
-                    "CREATE INDEX idx_benchmark_posts_category ON benchmark_posts (category)",
+                    "CREATE INDEX idx_benchmark_posts_category ON benchmark_posts ((CAST(category AS CITEXT)))",
                 )
                 .await?;
 
@@ -305,7 +312,7 @@ impl MigrationTrait for CreateBenchmarkIndexes {
             manager
                 .get_connection()
                 .execute_unprepared(
-                    "CREATE INDEX idx_benchmark_posts_category ON benchmark_posts (category)",
+                    "CREATE INDEX idx_benchmark_posts_category ON benchmark_posts (category COLLATE NOCASE)",
                 )
                 .await?;
 
@@ -679,6 +686,7 @@ fn bench_filtered_queries(
         r#"{"published":true}"#,
         r#"{"author":"Author1"}"#,
         r#"{"category":"Category2"}"#,
+        r#"{"category":"category2"}"#,
         r#"{"view_count_gte":500}"#,
     ];
 
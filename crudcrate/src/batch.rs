@@ -0,0 +1,491 @@
+//! Mixed batches of writes across resources, applied in one transaction.
+//!
+//! [`BatchRouter`] mounts a single `POST /batch` taking an ordered list of operations on
+//! any of the resources added to it. They run in one transaction: either every operation
+//! applies, or none does. This suits offline clients replaying a queue of edits:
+//!
+//! ```rust,ignore
+//! let app = Router::new()
+//!     .nest("/todos", Todo::router(&db).into())
+//!     .nest("/tags", Tag::router(&db).into())
+//!     .merge(crudcrate::batch::BatchRouter::new().resource::<Todo>()?.resource::<Tag>()?.router(&db));
+//! ```
+//!
+//! ```text
+//! POST /batch
+//! [
+//!   {"op": "create", "resource": "todos", "body": {"title": "Buy milk"}},
+//!   {"op": "update", "resource": "todos", "id": "…", "body": {"completed": true}},
+//!   {"op": "delete", "resource": "tags", "id": "…"}
+//! ]
+//!
+//! 200 OK
+//! [{"status": 201, "body": {…}}, {"status": 200, "body": {…}}, {"status": 204}]
+//! ```
+//!
+//! Resources are named by their plural name. Created and updated rows are returned as
+//! list items. If an operation fails, the transaction is rolled back and the response
+//! has that operation's status with a [`BatchFailure`] naming its index.
+//!
+//! Operations run the default create, update and delete logic on the transaction:
+//! `on_create`/`on_update` values and async generators apply, `restrict_delete` and
+//! `guard_delete` refuse deletes of referenced and locked rows, `cascade_delete` deletes
+//! their children and deletes of `sync` resources leave tombstones. The rows of `serialize_writes` resources stay locked
+//! until the transaction ends. Once it commits, webhook deliveries are recorded and the
+//! files of deleted rows, and those an update replaced, are deleted. Hooks,
+//! `operations` and nested `join(create)` inserts can't run inside the transaction, so
+//! [`BatchRouter::resource`] refuses resources that have them, as well as `view_sql`
+//! resources, which have no writes. Requests carrying a
+//! [`ScopeCondition`](crate::ScopeCondition) are refused, as for the generated write
+//! endpoints.
+//!
+//! `POST /batch` is its own route: the layers of each resource's `router()` (`layer`
+//! functions, `max_body_size` and `timeout_ms`) don't run for its operations. Mount it
+//! behind middleware that enforces the same authentication, body size limit and timeout
+//! as the strictest resource added to it.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Extension, Json, Router};
+use sea_orm::{
+    ActiveModelTrait, Condition, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    IntoActiveModel, Iterable, ModelTrait, PrimaryKeyToColumn, QueryFilter, TransactionTrait,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::relationships::{DeletedChild, report_cascade};
+use crate::webhooks::Event;
+use crate::{ApiError, BatchFailure, CRUDResource, ScopeCondition};
+
+/// Most operations in one batch unless set with [`BatchRouter::limit`]
+pub const DEFAULT_BATCH_LIMIT: usize = 100;
+
+/// One operation of a batch
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    /// Create a row from a create model body
+    Create { resource: String, body: Value },
+    /// Update the row `id` with an update model body
+    Update {
+        resource: String,
+        id: Uuid,
+        body: Value,
+    },
+    /// Delete the row `id`
+    Delete { resource: String, id: Uuid },
+}
+
+impl Operation {
+    fn resource(&self) -> &str {
+        match self {
+            Self::Create { resource, .. }
+            | Self::Update { resource, .. }
+            | Self::Delete { resource, .. } => resource,
+        }
+    }
+}
+
+/// Result of one operation of a successful batch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OperationResult {
+    /// HTTP status the single-item endpoint would have answered: 201, 200 or 204
+    pub status: u16,
+    /// The created or updated row as a list item; absent for deletes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// What an applied operation leaves to do once the batch commits
+#[derive(Default)]
+struct Deferred {
+    /// Webhook event and payload of the written row, for `webhooks` resources
+    webhook: Option<(Event, Uuid, Value)>,
+    /// Files the row no longer references: all of a deleted row's, or those an update
+    /// replaced
+    files: Vec<(&'static str, String)>,
+    /// Children deleted with the row by `cascade_delete`
+    cascaded: Vec<DeletedChild>,
+}
+
+/// Applies the operations of one resource
+#[async_trait]
+trait Executor: Send + Sync {
+    /// Lock the rows with `ids` for writing, for `serialize_writes` resources
+    async fn lock(&self, ids: Vec<Uuid>) -> crate::locks::RowLocks;
+
+    async fn run(
+        &self,
+        txn: &DatabaseTransaction,
+        operation: Operation,
+    ) -> Result<(OperationResult, Deferred), ApiError>;
+
+    /// Finish an operation of the committed batch
+    async fn finish(&self, db: &DatabaseConnection, deferred: Deferred);
+}
+
+struct ResourceExecutor<T>(PhantomData<fn() -> T>);
+
+/// The UUID primary key of `model`
+fn model_id<T: CRUDResource>(model: &<T::EntityType as EntityTrait>::Model) -> Option<Uuid> {
+    let key = <<T::EntityType as EntityTrait>::PrimaryKey as Iterable>::iter().next()?;
+    match model.get(key.into_column()) {
+        sea_orm::Value::Uuid(Some(id)) => Some(*id),
+        _ => None,
+    }
+}
+
+fn list_item<T>(item: T) -> Result<Value, ApiError>
+where
+    T: CRUDResource,
+    T::ListModel: Serialize,
+{
+    serde_json::to_value(T::ListModel::from(item))
+        .map_err(|e| ApiError::internal("Failed to serialize result", Some(e.to_string())))
+}
+
+fn parse_body<M: DeserializeOwned>(body: Value) -> Result<M, ApiError> {
+    serde_json::from_value(body).map_err(|e| ApiError::bad_request(format!("Invalid body: {e}")))
+}
+
+/// Webhook event and payload of `item`, for `webhooks` resources
+fn webhook<T: CRUDResource + Serialize>(
+    event: Event,
+    id: Uuid,
+    item: &T,
+) -> Option<(Event, Uuid, Value)> {
+    T::WEBHOOKS.then(|| (event, id, serde_json::to_value(item).unwrap_or_default()))
+}
+
+#[async_trait]
+impl<T> Executor for ResourceExecutor<T>
+where
+    T: CRUDResource + Serialize,
+    T::CreateModel: DeserializeOwned,
+    T::UpdateModel: DeserializeOwned,
+    T::ListModel: Serialize,
+    T::ActiveModelType: Send,
+{
+    async fn lock(&self, ids: Vec<Uuid>) -> crate::locks::RowLocks {
+        crate::locks::lock_rows::<T>(ids).await
+    }
+
+    async fn run(
+        &self,
+        txn: &DatabaseTransaction,
+        operation: Operation,
+    ) -> Result<(OperationResult, Deferred), ApiError> {
+        match operation {
+            Operation::Create { body, .. } => {
+                let create: T::CreateModel = parse_body(body)?;
+                let active_model = T::create_active_model(txn, create).await?;
+                let model = active_model.insert(txn).await.map_err(ApiError::database)?;
+                let id = model_id::<T>(&model);
+                let item = T::from(model);
+                let deferred = Deferred {
+                    webhook: id.and_then(|id| webhook(Event::Created, id, &item)),
+                    ..Deferred::default()
+                };
+                Ok((
+                    OperationResult {
+                        status: 201,
+                        body: Some(list_item(item)?),
+                    },
+                    deferred,
+                ))
+            }
+            Operation::Update { id, body, .. } => {
+                let update: T::UpdateModel = parse_body(body)?;
+                let existing = T::EntityType::find_by_id(id)
+                    .one(txn)
+                    .await
+                    .map_err(ApiError::database)?
+                    .ok_or_else(|| {
+                        ApiError::not_found(T::RESOURCE_NAME_SINGULAR, Some(id.to_string()))
+                    })?;
+                let previous = if T::file_fields().is_empty() {
+                    Vec::new()
+                } else {
+                    T::from(existing.clone()).stored_files()
+                };
+                let active_model =
+                    T::update_active_model(txn, update, existing.into_active_model()).await?;
+                let item = T::from(active_model.update(txn).await.map_err(ApiError::database)?);
+                let current = item.stored_files();
+                let deferred = Deferred {
+                    webhook: webhook(Event::Updated, id, &item),
+                    files: previous
+                        .into_iter()
+                        .filter(|file| !current.contains(file))
+                        .collect(),
+                    cascaded: Vec::new(),
+                };
+                Ok((
+                    OperationResult {
+                        status: 200,
+                        body: Some(list_item(item)?),
+                    },
+                    deferred,
+                ))
+            }
+            Operation::Delete { id, .. } => {
+                T::ensure_deletable(txn, &[id]).await?;
+                let files = if T::file_fields().is_empty() {
+                    Vec::new()
+                } else {
+                    T::EntityType::find_by_id(id)
+                        .one(txn)
+                        .await
+                        .map_err(ApiError::database)?
+                        .map(|model| T::from(model).stored_files())
+                        .unwrap_or_default()
+                };
+                let cascaded = T::cascade_delete(txn, &[id]).await?;
+                let deleted = T::EntityType::delete_by_id(id)
+                    .filter(Condition::all().add_option(T::delete_condition()))
+                    .exec(txn)
+                    .await
+                    .map_err(ApiError::database)?;
+                if deleted.rows_affected == 0 {
+                    // Locked since the check: the `DELETE` kept it
+                    T::ensure_deletable(txn, &[id]).await?;
+                    return Err(ApiError::not_found(
+                        T::RESOURCE_NAME_SINGULAR,
+                        Some(id.to_string()),
+                    ));
+                }
+                crate::sync::record_deletes::<T, _>(txn, &[id]).await?;
+                let deferred = Deferred {
+                    webhook: T::WEBHOOKS.then_some((Event::Deleted, id, Value::Null)),
+                    files,
+                    cascaded,
+                };
+                Ok((
+                    OperationResult {
+                        status: 204,
+                        body: None,
+                    },
+                    deferred,
+                ))
+            }
+        }
+    }
+
+    async fn finish(&self, db: &DatabaseConnection, deferred: Deferred) {
+        if let Some((event, id, payload)) = deferred.webhook
+            && let Err(error) = crate::webhooks::record::<T>(db, event, vec![(id, payload)]).await
+        {
+            tracing::warn!(
+                resource = T::RESOURCE_NAME_PLURAL,
+                error = %error,
+                "Failed to record webhook deliveries"
+            );
+        }
+        crate::storage::discard(&deferred.files).await;
+        report_cascade(T::RESOURCE_NAME_SINGULAR, &deferred.cascaded);
+    }
+}
+
+/// Why [`BatchRouter::resource`] refused a resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchResourceError {
+    /// The resource's writes run hooks, `operations` or nested creates
+    /// ([`CRUDResource::CUSTOM_WRITES`]), which the batch transaction would skip
+    CustomWrites { resource: &'static str },
+    /// The resource is backed by a `view_sql` query and has no writes
+    View { resource: &'static str },
+}
+
+impl fmt::Display for BatchResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CustomWrites { resource } => write!(
+                f,
+                "`{resource}` can't be batched: its writes run hooks, `operations` or nested \
+                 creates, which the batch transaction would skip"
+            ),
+            Self::View { resource } => {
+                write!(f, "`{resource}` can't be batched: it is a read-only view")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchResourceError {}
+
+/// Builder of the `POST /batch` router
+#[derive(Clone)]
+pub struct BatchRouter {
+    executors: BTreeMap<&'static str, Arc<dyn Executor>>,
+    limit: usize,
+}
+
+impl Default for BatchRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchRouter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            executors: BTreeMap::new(),
+            limit: DEFAULT_BATCH_LIMIT,
+        }
+    }
+
+    /// Accept operations on `T`, named by its plural name
+    ///
+    /// # Errors
+    /// Returns [`BatchResourceError::CustomWrites`] if `T`'s writes have hooks, an
+    /// `operations` object or nested `join(create)` inserts, which the batch transaction
+    /// can't run, and [`BatchResourceError::View`] if `T` is a `view_sql` resource.
+    pub fn resource<T>(mut self) -> Result<Self, BatchResourceError>
+    where
+        T: CRUDResource + Serialize + 'static,
+        T::CreateModel: DeserializeOwned,
+        T::UpdateModel: DeserializeOwned,
+        T::ListModel: Serialize,
+        T::ActiveModelType: Send,
+    {
+        let resource = T::RESOURCE_NAME_PLURAL;
+        if T::VIEW_SQL.is_some() {
+            return Err(BatchResourceError::View { resource });
+        }
+        if T::CUSTOM_WRITES {
+            return Err(BatchResourceError::CustomWrites { resource });
+        }
+        self.executors
+            .insert(resource, Arc::new(ResourceExecutor::<T>(PhantomData)));
+        Ok(self)
+    }
+
+    /// Most operations accepted in one batch
+    #[must_use]
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// `POST /batch`, to merge into the application router
+    pub fn router(self, db: &DatabaseConnection) -> Router {
+        let batch = Arc::new(self);
+        Router::new()
+            .route(
+                "/batch",
+                post(
+                    move |State(db): State<DatabaseConnection>,
+                          scope: Option<Extension<ScopeCondition>>,
+                          Json(operations): Json<Vec<Operation>>| {
+                        let batch = Arc::clone(&batch);
+                        async move {
+                            if scope.is_some() {
+                                return ApiError::forbidden(
+                                    "Write access denied in scoped context",
+                                )
+                                .into_response();
+                            }
+                            match batch.apply(&db, operations).await {
+                                Ok(results) => Json(results).into_response(),
+                                Err(response) => response,
+                            }
+                        }
+                    },
+                ),
+            )
+            .with_state(db.clone())
+    }
+
+    /// Apply `operations` in one transaction, returning their results in order.
+    ///
+    /// # Errors
+    /// Returns `ApiError::BadRequest` if there are more operations than the limit, and
+    /// for a failed operation an error response with its status and a [`BatchFailure`].
+    pub async fn apply(
+        &self,
+        db: &DatabaseConnection,
+        operations: Vec<Operation>,
+    ) -> Result<Vec<OperationResult>, Response> {
+        if operations.len() > self.limit {
+            return Err(ApiError::bad_request(format!(
+                "Batch limited to {} operations. Received {} operations.",
+                self.limit,
+                operations.len()
+            ))
+            .into_response());
+        }
+
+        // `serialize_writes`: written rows stay locked until the batch ends. Resources
+        // lock in name order, so batches sharing rows can't deadlock.
+        let mut written: BTreeMap<&str, Vec<Uuid>> = BTreeMap::new();
+        for operation in &operations {
+            if let Operation::Update { resource, id, .. } | Operation::Delete { resource, id } =
+                operation
+            {
+                written.entry(resource).or_default().push(*id);
+            }
+        }
+        let mut locks = Vec::with_capacity(written.len());
+        for (resource, ids) in written {
+            if let Some(executor) = self.executors.get(resource) {
+                locks.push(executor.lock(ids).await);
+            }
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| ApiError::database(e).into_response())?;
+        let mut results = Vec::with_capacity(operations.len());
+        let mut finished = Vec::with_capacity(operations.len());
+        for (index, operation) in operations.into_iter().enumerate() {
+            let result = match self.executors.get(operation.resource()) {
+                Some(executor) => executor
+                    .run(&txn, operation)
+                    .await
+                    .map(|(result, deferred)| {
+                        finished.push((executor, deferred));
+                        result
+                    }),
+                None => Err(ApiError::bad_request(format!(
+                    "Unknown resource '{}'",
+                    operation.resource()
+                ))),
+            };
+            match result {
+                Ok(result) => results.push(result),
+                // Dropping the transaction rolls it back
+                Err(error) => return Err(failure(index, &error)),
+            }
+        }
+        txn.commit()
+            .await
+            .map_err(|e| ApiError::database(e).into_response())?;
+
+        for (executor, deferred) in finished {
+            executor.finish(db, deferred).await;
+        }
+        Ok(results)
+    }
+}
+
+/// `error`'s response with a [`BatchFailure`] body naming the failed operation
+fn failure(index: usize, error: &ApiError) -> Response {
+    error.log_internal();
+    let failure = BatchFailure {
+        index,
+        error: error.to_string(),
+    };
+    (error.status_code(), Json(failure)).into_response()
+}
@@ -0,0 +1,547 @@
+//! Typed Rust clients generated from the [resource registry](crate::registry).
+//!
+//! [`rust_client`] writes the source of a module with, for every resource, the structs
+//! of its models and a `reqwest` client mirroring its endpoints, filters, sorts and
+//! pagination. Generate it from the binary that mounts the routers, e.g. behind a
+//! command-line flag, and commit it to the crate that calls the API:
+//!
+//! ```rust,ignore
+//! let app = Router::new().nest("/todos", Todo::router(&db).into());
+//! let source = crudcrate::client_gen::rust_client(&crudcrate::registry::resources());
+//! std::fs::write("../todo-client/src/api.rs", source)?;
+//! ```
+//!
+//! The calling crate then uses the typed client instead of redeclaring the models:
+//!
+//! ```rust,ignore
+//! let todos = TodoClient::new("http://localhost:3000");
+//! let open = todos
+//!     .list(&TodoQuery::new().completed_eq(false).sort_by(TodoSort::Priority, Order::Desc).page(1, 20))
+//!     .await?;
+//! ```
+//!
+//! The module needs `reqwest` (with its `json` feature), `serde` and `serde_json`.
+//!
+//! | Generated | From |
+//! |-----------|------|
+//! | `Todo`, `TodoList`, `TodoCreate`, `TodoUpdate` | Fields with `response`, `list`, `create`, `update` |
+//! | `TodoQuery` with `title_eq`, `priority_gte`, ... | Filterable fields; ordered types also get `_gt`/`_gte`/`_lt`/`_lte` |
+//! | `TodoSort` | Sortable fields |
+//! | `TodoClient` with `list`, `get`, `create`, `update`, `delete` | The resource's registered path |
+//!
+//! UUIDs, dates, times and decimals are `String`s (their JSON form), JSON columns and
+//! types the generator doesn't recognise are `serde_json::Value`, and relations are
+//! typed as the related resource when it is generated too. Fields are named as on the
+//! entity, so resources using `rename` or `rename_all` need the serde attributes added
+//! by hand.
+
+use std::fmt::Write;
+
+use crate::registry::{Cardinality, FieldMetadata, ResourceMetadata};
+
+/// Preamble shared by every resource
+const PRELUDE: &str = r#"// Generated by `crudcrate::client_gen::rust_client`. Do not edit.
+
+use serde::{Deserialize, Serialize};
+
+/// Error returned by the generated clients
+#[derive(Debug)]
+pub enum ClientError {
+    /// The request failed, or the response wasn't the expected JSON
+    Http(reqwest::Error),
+    /// The API answered with an error status and message
+    Api { status: u16, message: String },
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(error) => write!(f, "{error}"),
+            Self::Api { status, message } => write!(f, "{status}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error)
+    }
+}
+
+/// Sort direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl Order {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// Turn an error status into `ClientError::Api` with the API's `error` message
+async fn check(response: reqwest::Response) -> Result<reqwest::Response, ClientError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    let message = body["error"].as_str().unwrap_or_else(|| status.as_str()).to_string();
+    Err(ClientError::Api { status: status.as_u16(), message })
+}
+"#;
+
+/// How a field's type is written in the client
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ClientType {
+    /// Type of the value, without `Option`
+    inner: String,
+    /// Accepts `_gt`/`_gte`/`_lt`/`_lte` filters
+    ordered: bool,
+    /// No typed filter methods (JSON and unrecognised types)
+    opaque: bool,
+}
+
+/// Generate the source of a client module for `resources`.
+///
+/// Pass [`registry::resources()`](crate::registry::resources) to cover every mounted
+/// resource.
+#[must_use]
+pub fn rust_client(resources: &[ResourceMetadata]) -> String {
+    let names: Vec<String> = resources
+        .iter()
+        .map(|resource| resource.api_struct.to_string())
+        .collect();
+    let mut out = String::from(PRELUDE);
+    for (resource, name) in resources.iter().zip(&names) {
+        write_resource(&mut out, resource, name, &names);
+    }
+    out
+}
+
+fn write_resource(out: &mut String, resource: &ResourceMetadata, name: &str, known: &[String]) {
+    let fields = |include: fn(&FieldMetadata) -> bool| -> Vec<&FieldMetadata> {
+        resource
+            .fields
+            .iter()
+            .filter(|field| include(field))
+            .collect()
+    };
+
+    let response = fields(|field| field.response);
+    let list = fields(|field| field.list);
+    let relations_one: Vec<String> = resource
+        .relations
+        .iter()
+        .filter(|relation| relation.on_one)
+        .map(|relation| relation_field(relation, known))
+        .collect();
+    let relations_all: Vec<String> = resource
+        .relations
+        .iter()
+        .filter(|relation| relation.on_all)
+        .map(|relation| relation_field(relation, known))
+        .collect();
+
+    let _ = write!(
+        out,
+        "\n/// A `{name}` as returned by `GET {}/{{id}}`\n",
+        resource.path
+    );
+    write_struct(out, name, &response, &relations_one, false);
+    let _ = write!(
+        out,
+        "\n/// A `{name}` as returned by `GET {}`\n",
+        resource.path
+    );
+    write_struct(out, &format!("{name}List"), &list, &relations_all, false);
+    let _ = write!(out, "\n/// Body of `POST {}`\n", resource.path);
+    write_struct(
+        out,
+        &format!("{name}Create"),
+        &fields(|field| field.create),
+        &[],
+        false,
+    );
+    let _ = write!(
+        out,
+        "\n/// Body of `PUT {}/{{id}}`; unset fields are left unchanged\n",
+        resource.path
+    );
+    write_struct(
+        out,
+        &format!("{name}Update"),
+        &fields(|field| field.update),
+        &[],
+        true,
+    );
+
+    write_sort(out, name, &fields(|field| field.sortable));
+    write_query(out, resource, name);
+    write_client(out, resource, name);
+}
+
+/// `name: Type` for a relation, typed as the related resource when it is generated
+fn relation_field(relation: &crate::registry::RelationMetadata, known: &[String]) -> String {
+    let target = if known.iter().any(|name| name == relation.target) {
+        relation.target.to_string()
+    } else {
+        "serde_json::Value".to_string()
+    };
+    let ty = match relation.cardinality {
+        Cardinality::Many => format!("Vec<{target}>"),
+        Cardinality::One => format!("Option<Box<{target}>>"),
+    };
+    format!("    #[serde(default)]\n    pub {}: {ty},\n", relation.name)
+}
+
+fn write_struct(
+    out: &mut String,
+    name: &str,
+    fields: &[&FieldMetadata],
+    relations: &[String],
+    update: bool,
+) {
+    let default = if update { "Default, " } else { "" };
+    let _ = writeln!(
+        out,
+        "#[derive(Debug, Clone, {default}PartialEq, Serialize, Deserialize)]"
+    );
+    let _ = writeln!(out, "pub struct {name} {{");
+    for field in fields {
+        let ty = client_type(field);
+        if update {
+            // `Some(None)` sends `null`, clearing a nullable field
+            let value = if field.nullable {
+                format!("Option<{}>", ty.inner)
+            } else {
+                ty.inner.clone()
+            };
+            out.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+            let _ = writeln!(out, "    pub {}: Option<{value}>,", field.name);
+        } else if field.nullable {
+            let _ = writeln!(out, "    pub {}: Option<{}>,", field.name, ty.inner);
+        } else {
+            let _ = writeln!(out, "    pub {}: {},", field.name, ty.inner);
+        }
+    }
+    for relation in relations {
+        out.push_str(relation);
+    }
+    out.push_str("}\n");
+}
+
+fn write_sort(out: &mut String, name: &str, fields: &[&FieldMetadata]) {
+    let _ = write!(
+        out,
+        "\n/// Sortable fields of `{name}`\n#[derive(Debug, Clone, Copy, PartialEq, Eq)]\npub enum {name}Sort {{\n"
+    );
+    for field in fields {
+        let _ = writeln!(out, "    {},", pascal_case(field.name));
+    }
+    let _ = write!(
+        out,
+        "}}\n\nimpl {name}Sort {{\n    fn as_str(self) -> &'static str {{\n        match self {{\n"
+    );
+    for field in fields {
+        let _ = writeln!(
+            out,
+            "            Self::{} => \"{}\",",
+            pascal_case(field.name),
+            field.name
+        );
+    }
+    out.push_str("        }\n    }\n}\n");
+}
+
+fn write_query(out: &mut String, resource: &ResourceMetadata, name: &str) {
+    let _ = write!(
+        out,
+        r#"
+/// Filter, sort and page of a `{name}Client::list` request
+#[derive(Debug, Clone, Default)]
+pub struct {name}Query {{
+    filter: serde_json::Map<String, serde_json::Value>,
+    sort: Vec<({name}Sort, Order)>,
+    page: Option<(u64, u64)>,
+}}
+
+impl {name}Query {{
+    pub fn new() -> Self {{
+        Self::default()
+    }}
+
+    /// Add a raw filter key, e.g. `filter("priority_between", [1, 3])`
+    pub fn filter(mut self, key: impl Into<String>, value: impl Serialize) -> Self {{
+        let value = serde_json::to_value(value).unwrap_or_default();
+        self.filter.insert(key.into(), value);
+        self
+    }}
+
+    /// Sort by `field`; later calls add tie-breakers
+    pub fn sort_by(mut self, field: {name}Sort, order: Order) -> Self {{
+        self.sort.push((field, order));
+        self
+    }}
+
+    /// Request page `page` (1-based) of `per_page` items (at most {max_page_size})
+    pub fn page(mut self, page: u64, per_page: u64) -> Self {{
+        self.page = Some((page, per_page));
+        self
+    }}
+"#,
+        max_page_size = resource.max_page_size
+    );
+    if resource.fields.iter().any(|field| field.fulltext) {
+        out.push_str(
+            "\n    /// Fulltext search across the searchable fields\n    pub fn search(self, text: impl Into<String>) -> Self {\n        self.filter(\"q\", Into::<String>::into(text))\n    }\n",
+        );
+    }
+    for field in resource.fields.iter().filter(|field| field.filterable) {
+        let ty = client_type(field);
+        if ty.opaque {
+            continue;
+        }
+        let param = if ty.inner == "String" {
+            "impl Into<String>".to_string()
+        } else {
+            ty.inner.clone()
+        };
+        let mut operators = vec![("eq", ""), ("neq", "_neq")];
+        if ty.ordered {
+            operators.extend([
+                ("gt", "_gt"),
+                ("gte", "_gte"),
+                ("lt", "_lt"),
+                ("lte", "_lte"),
+            ]);
+        }
+        for (method, suffix) in operators {
+            let _ = write!(
+                out,
+                "\n    pub fn {field}_{method}(self, value: {param}) -> Self {{\n        self.filter(\"{field}{suffix}\", {value})\n    }}\n",
+                field = field.name,
+                value = if ty.inner == "String" {
+                    "Into::<String>::into(value)"
+                } else {
+                    "value"
+                },
+            );
+        }
+    }
+    out.push_str(
+        r#"
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if !self.filter.is_empty() {
+            params.push(("filter", serde_json::Value::Object(self.filter.clone()).to_string()));
+        }
+        if !self.sort.is_empty() {
+            let sort: Vec<[&str; 2]> = self
+                .sort
+                .iter()
+                .map(|(field, order)| [field.as_str(), order.as_str()])
+                .collect();
+            params.push(("sort", serde_json::to_string(&sort).unwrap_or_default()));
+        }
+        if let Some((page, per_page)) = self.page {
+            params.push(("page", page.to_string()));
+            params.push(("per_page", per_page.to_string()));
+        }
+        params
+    }
+}
+"#,
+    );
+}
+
+fn write_client(out: &mut String, resource: &ResourceMetadata, name: &str) {
+    let id = resource
+        .fields
+        .iter()
+        .find(|field| field.primary_key)
+        .map_or_else(|| "String".to_string(), |field| client_type(field).inner);
+    let id = if id == "String" {
+        "&str".to_string()
+    } else {
+        id
+    };
+    let _ = write!(
+        out,
+        r#"
+/// Client for `{path}`
+#[derive(Debug, Clone)]
+pub struct {name}Client {{
+    http: reqwest::Client,
+    url: String,
+}}
+
+impl {name}Client {{
+    /// Client for the API at `base_url`, e.g. `http://localhost:3000`
+    pub fn new(base_url: &str) -> Self {{
+        Self::with_client(reqwest::Client::new(), base_url)
+    }}
+
+    /// Client sending its requests through `http`
+    pub fn with_client(http: reqwest::Client, base_url: &str) -> Self {{
+        let url = format!("{{}}{path}", base_url.trim_end_matches('/'));
+        Self {{ http, url }}
+    }}
+
+    /// `GET {path}`
+    pub async fn list(&self, query: &{name}Query) -> Result<Vec<{name}List>, ClientError> {{
+        let request = self.http.get(&self.url).query(&query.params());
+        Ok(check(request.send().await?).await?.json().await?)
+    }}
+
+    /// `GET {path}/{{id}}`
+    pub async fn get(&self, id: {id}) -> Result<{name}, ClientError> {{
+        let request = self.http.get(format!("{{}}/{{id}}", self.url));
+        Ok(check(request.send().await?).await?.json().await?)
+    }}
+
+    /// `POST {path}`
+    pub async fn create(&self, body: &{name}Create) -> Result<{name}, ClientError> {{
+        let request = self.http.post(&self.url).json(body);
+        Ok(check(request.send().await?).await?.json().await?)
+    }}
+
+    /// `PUT {path}/{{id}}`
+    pub async fn update(&self, id: {id}, body: &{name}Update) -> Result<{name}, ClientError> {{
+        let request = self.http.put(format!("{{}}/{{id}}", self.url)).json(body);
+        Ok(check(request.send().await?).await?.json().await?)
+    }}
+
+    /// `DELETE {path}/{{id}}`
+    pub async fn delete(&self, id: {id}) -> Result<(), ClientError> {{
+        let request = self.http.delete(format!("{{}}/{{id}}", self.url));
+        check(request.send().await?).await?;
+        Ok(())
+    }}
+}}
+"#,
+        path = resource.path
+    );
+}
+
+/// The client type of a field, from the Rust type written on the model
+fn client_type(field: &FieldMetadata) -> ClientType {
+    if field.enum_variants.is_some() {
+        return ClientType {
+            inner: "String".to_string(),
+            ordered: false,
+            opaque: false,
+        };
+    }
+    let ty = field.rust_type.replace(' ', "");
+    let ty = ty
+        .strip_prefix("Option<")
+        .and_then(|inner| inner.strip_suffix('>'))
+        .unwrap_or(&ty);
+    map_type(ty)
+}
+
+fn map_type(ty: &str) -> ClientType {
+    let scalar = |inner: &str, ordered: bool| ClientType {
+        inner: inner.to_string(),
+        ordered,
+        opaque: false,
+    };
+    if let Some(item) = ty
+        .strip_prefix("Vec<")
+        .and_then(|inner| inner.strip_suffix('>'))
+    {
+        return ClientType {
+            inner: format!("Vec<{}>", map_type(item).inner),
+            ordered: false,
+            opaque: false,
+        };
+    }
+    let head = ty.split('<').next().unwrap_or(ty);
+    let last = head.rsplit("::").next().unwrap_or(head);
+    match last {
+        "String" | "str" | "Uuid" => scalar("String", false),
+        "bool" => scalar("bool", false),
+        "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "f32" | "f64" => {
+            scalar(last, true)
+        }
+        "Decimal"
+        | "DateTime"
+        | "DateTimeUtc"
+        | "DateTimeLocal"
+        | "DateTimeWithTimeZone"
+        | "NaiveDate"
+        | "NaiveDateTime"
+        | "NaiveTime"
+        | "Date"
+        | "Time"
+        | "TimeDate"
+        | "TimeTime"
+        | "TimeDateTime"
+        | "TimeDateTimeWithTimeZone"
+        | "OffsetDateTime"
+        | "PrimitiveDateTime" => scalar("String", true),
+        _ => ClientType {
+            inner: "serde_json::Value".to_string(),
+            ordered: false,
+            opaque: true,
+        },
+    }
+}
+
+/// `todo_item` → `TodoItem`
+fn pascal_case(name: &str) -> String {
+    name.split(['_', '-', ' '])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &'static str, rust_type: &'static str) -> FieldMetadata {
+        let mut field = FieldMetadata::new(name, rust_type);
+        field.nullable = rust_type.starts_with("Option<");
+        field
+    }
+
+    #[test]
+    fn test_map_types() {
+        assert_eq!(client_type(&field("title", "String")).inner, "String");
+        assert_eq!(
+            client_type(&field("due", "Option<DateTime<Utc>>")),
+            ClientType {
+                inner: "String".to_string(),
+                ordered: true,
+                opaque: false
+            }
+        );
+        assert!(client_type(&field("priority", "i32")).ordered);
+        assert_eq!(
+            client_type(&field("tags", "Vec<String>")).inner,
+            "Vec<String>"
+        );
+        assert!(client_type(&field("metadata", "Json")).opaque);
+        assert_eq!(client_type(&field("id", "uuid::Uuid")).inner, "String");
+    }
+
+    #[test]
+    fn test_pascal_case() {
+        assert_eq!(pascal_case("todo"), "Todo");
+        assert_eq!(pascal_case("todo_item"), "TodoItem");
+    }
+}
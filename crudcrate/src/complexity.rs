@@ -0,0 +1,219 @@
+//! Budgets for the relation loading of `join` fields.
+//!
+//! Each `join(...)` field a response loads costs at least one query, and joins with a
+//! depth above 1 load every related row again through its own `get_one`, joins
+//! included. A list page of 1000 items with a few deep `Vec` joins can run thousands of
+//! queries. The generated handlers estimate the queries and joined rows a request would
+//! cost before running it, and answer 400 when the estimate is over the resource's
+//! [`JoinBudget`]:
+//!
+//! ```rust,ignore
+//! #[crudcrate(generate_router, max_join_queries = 50, max_joined_rows = 5000)]
+//! pub struct Model { /* ... */ }
+//! ```
+//!
+//! Estimates assume [`ASSUMED_CHILDREN`] rows per parent for `Vec` joins, or the
+//! join's `limit` in list responses, and one row for `Option` joins. Both limits are
+//! off unless set.
+
+use crate::core::traits::CRUDResource;
+use crate::errors::ApiError;
+
+/// Related rows assumed per parent for `Vec` joins without a `limit`
+pub const ASSUMED_CHILDREN: u64 = 10;
+
+/// Depth at which cost estimates stop following nested joins, the cap of join depths
+pub const MAX_ESTIMATE_DEPTH: u8 = 5;
+
+/// Estimated queries and joined rows of loading relations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JoinCost {
+    /// Queries run to load relations, not counting the query of the items themselves
+    pub queries: u64,
+    /// Related rows loaded, at every depth
+    pub rows: u64,
+}
+
+impl JoinCost {
+    /// Add a relation loaded for `parents` items with one query, `children` related
+    /// rows each. `nested` is the cost of loading one related row again with its own
+    /// joins, for joins deeper than 1.
+    #[must_use]
+    pub fn relation(self, parents: u64, children: u64, nested: Option<Self>) -> Self {
+        let rows = parents.saturating_mul(children);
+        let mut cost = Self {
+            queries: self.queries.saturating_add(1),
+            rows: self.rows.saturating_add(rows),
+        };
+        if let Some(nested) = nested {
+            // One `get_one` per related row, then its own relations
+            cost.queries = cost
+                .queries
+                .saturating_add(rows.saturating_mul(nested.queries.saturating_add(1)));
+            cost.rows = cost.rows.saturating_add(rows.saturating_mul(nested.rows));
+        }
+        cost
+    }
+}
+
+/// Most relation queries and joined rows one request may cost
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JoinBudget {
+    max_queries: u64,
+    max_rows: u64,
+}
+
+impl Default for JoinBudget {
+    /// No limits
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl JoinBudget {
+    /// Allow any number of queries and rows
+    #[must_use]
+    pub const fn unlimited() -> Self {
+        Self {
+            max_queries: u64::MAX,
+            max_rows: u64::MAX,
+        }
+    }
+
+    /// Most queries loading relations per request
+    #[must_use]
+    pub const fn max_queries(mut self, max: u64) -> Self {
+        self.max_queries = max;
+        self
+    }
+
+    /// Most related rows loaded per request
+    #[must_use]
+    pub const fn max_rows(mut self, max: u64) -> Self {
+        self.max_rows = max;
+        self
+    }
+
+    /// Whether `cost` is within the budget
+    #[must_use]
+    pub const fn allows(&self, cost: JoinCost) -> bool {
+        cost.queries <= self.max_queries && cost.rows <= self.max_rows
+    }
+}
+
+/// Estimated cost of loading the relations of `items` items of `T`, for a list page
+/// when `list` is set and for `get_one` otherwise
+#[must_use]
+pub fn estimate<T: CRUDResource>(items: u64, list: bool) -> JoinCost {
+    T::join_cost(items, list, MAX_ESTIMATE_DEPTH)
+}
+
+/// Check that loading the relations of `items` items of `T` fits in
+/// [`CRUDResource::join_budget`]. The generated handlers call this before querying.
+///
+/// # Errors
+///
+/// Returns `ApiError::BadRequest` when the estimate is over budget, naming the largest
+/// page size that fits.
+pub fn check<T: CRUDResource>(items: u64, list: bool) -> Result<(), ApiError> {
+    let budget = T::join_budget();
+    if budget == JoinBudget::unlimited() {
+        return Ok(());
+    }
+    let cost = estimate::<T>(items, list);
+    if budget.allows(cost) {
+        return Ok(());
+    }
+
+    let summary = format!(
+        "Loading the relations of {items} {} would take about {} queries and {} rows, over the limit of {} queries and {} rows.",
+        if items == 1 {
+            T::RESOURCE_NAME_SINGULAR
+        } else {
+            T::RESOURCE_NAME_PLURAL
+        },
+        cost.queries,
+        cost.rows,
+        budget.max_queries,
+        budget.max_rows,
+    );
+    let fits = largest_fitting::<T>(items, list, budget);
+    let guidance = if list && fits > 0 {
+        format!("Request at most {fits} items per page with `per_page` or `range`.")
+    } else {
+        "The join fields load too much for a single item.".to_string()
+    };
+    Err(ApiError::bad_request(format!("{summary} {guidance}")))
+}
+
+/// Most items below `items` whose relations fit in `budget`, or 0
+fn largest_fitting<T: CRUDResource>(items: u64, list: bool, budget: JoinBudget) -> u64 {
+    // Costs grow with the number of items, so bisect
+    let (mut low, mut high) = (0, items);
+    while low < high {
+        let middle = low + (high - low).div_ceil(2);
+        if budget.allows(estimate::<T>(middle, list)) {
+            low = middle;
+        } else {
+            high = middle - 1;
+        }
+    }
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relation_cost() {
+        // Depth 1: one batch query for the page
+        let cost = JoinCost::default().relation(20, 10, None);
+        assert_eq!(
+            cost,
+            JoinCost {
+                queries: 1,
+                rows: 200
+            }
+        );
+
+        // Depth 2: each of the 200 children is loaded again with two relations of its own
+        let nested = JoinCost::default()
+            .relation(1, 10, None)
+            .relation(1, 1, None);
+        assert_eq!(
+            nested,
+            JoinCost {
+                queries: 2,
+                rows: 11
+            }
+        );
+        let cost = JoinCost::default().relation(20, 10, Some(nested));
+        assert_eq!(cost.queries, 1 + 200 * 3);
+        assert_eq!(cost.rows, 200 + 200 * 11);
+
+        let huge = JoinCost::default().relation(u64::MAX, u64::MAX, Some(nested));
+        assert_eq!(huge.rows, u64::MAX);
+    }
+
+    #[test]
+    fn test_budget_allows() {
+        let budget = JoinBudget::default().max_queries(10).max_rows(100);
+        assert!(budget.allows(JoinCost {
+            queries: 10,
+            rows: 100
+        }));
+        assert!(!budget.allows(JoinCost {
+            queries: 11,
+            rows: 1
+        }));
+        assert!(!budget.allows(JoinCost {
+            queries: 1,
+            rows: 101
+        }));
+        assert!(JoinBudget::unlimited().allows(JoinCost {
+            queries: u64::MAX,
+            rows: u64::MAX,
+        }));
+    }
+}
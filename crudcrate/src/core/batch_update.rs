@@ -0,0 +1,215 @@
+//! Per-item results for `PATCH /resource/batch?per_item=true`.
+//!
+//! Admin grids editing several rows inline need to know which edits took. With
+//! `per_item=true` the batch update applies every item it can in one transaction and
+//! answers one [`ItemResult`] per item, in request order:
+//!
+//! ```text
+//! PATCH /todos/batch?per_item=true
+//! [{"id": "…", "completed": true}, {"id": "<unknown>", "completed": true}, {"completed": 3}]
+//!
+//! 207 Multi-Status
+//! [
+//!   {"index": 0, "id": "…", "status": 200, "body": {…}},
+//!   {"index": 1, "id": "<unknown>", "status": 404, "error": "todo with ID '<unknown>' not found"},
+//!   {"index": 2, "status": 422, "error": "missing or invalid `id`"}
+//! ]
+//! ```
+//!
+//! Each item runs in a savepoint, so a failed item leaves no partial writes while the
+//! others commit together. As with `?partial=true`, the response is `200 OK` when every
+//! item succeeded, `400 Bad Request` when every item failed and `207 Multi-Status`
+//! otherwise. Items run the default update logic on the
+//! transaction, as [`BatchRouter`](crate::batch::BatchRouter) operations do, so
+//! resources whose updates run hooks or an `operations` object
+//! ([`CRUDResource::CUSTOM_WRITES`]) answer 400 and keep `?partial=true`.
+
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, TransactionTrait,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::core::traits::CRUDResource;
+use crate::errors::{ApiError, BatchResult};
+use crate::webhooks::Event;
+
+/// Body of `PATCH /batch` responses, for the `OpenAPI` document: the updated rows, the
+/// `?partial=true` [`BatchResult`] or the `?per_item=true` results
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum BatchUpdateResponse<R> {
+    /// The updated rows, in request order
+    Rows(Vec<R>),
+    /// The `?partial=true` result
+    Partial(BatchResult<R>),
+    /// One result per item, with `?per_item=true`
+    PerItem(Vec<ItemResult<R>>),
+}
+
+/// Result of one item of a per-item batch update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ItemResult<R> {
+    /// Position of the item in the request (0-based)
+    pub index: usize,
+    /// The item's `id`; absent when it had none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    /// HTTP status `PUT /{id}` would have answered: 200, 404, 422, ...
+    pub status: u16,
+    /// The updated row, for items that succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<R>,
+    /// Why the item failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<R> ItemResult<R> {
+    fn failed(index: usize, id: Option<Uuid>, error: &ApiError) -> Self {
+        Self {
+            index,
+            id,
+            status: error.status_code().as_u16(),
+            body: None,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// The `id` and update model of one request item, or the item's error with its `id`
+/// when it has one
+fn parse_item<M: DeserializeOwned>(item: Value) -> Result<(Uuid, M), (Option<Uuid>, ApiError)> {
+    let Value::Object(mut fields) = item else {
+        let error = ApiError::validation_failed(vec!["item must be a JSON object".into()]);
+        return Err((None, error));
+    };
+    let Some(id) = fields
+        .remove("id")
+        .and_then(|id| serde_json::from_value::<Uuid>(id).ok())
+    else {
+        let error = ApiError::validation_failed(vec!["missing or invalid `id`".into()]);
+        return Err((None, error));
+    };
+    serde_json::from_value(Value::Object(fields))
+        .map(|model| (id, model))
+        .map_err(|e| (Some(id), ApiError::validation_failed(vec![e.to_string()])))
+}
+
+/// Apply the update `items`, each a JSON object with an `id` and the fields to change,
+/// in one transaction, returning one [`ItemResult`] per item in request order.
+///
+/// Successful items commit together; each failed item is rolled back to its savepoint
+/// and reported with its status. Once the transaction commits, webhook deliveries are
+/// recorded and the files the updates replaced are deleted.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if there are more items than
+/// [`batch_limit`](CRUDResource::batch_limit) or `T`'s updates run hooks or
+/// `operations`, and `ApiError::Database` if the transaction can't begin or commit.
+pub async fn update_each<T, R>(
+    db: &DatabaseConnection,
+    items: Vec<Value>,
+) -> Result<Vec<ItemResult<R>>, ApiError>
+where
+    T: CRUDResource + Serialize,
+    T::UpdateModel: DeserializeOwned,
+    R: From<T>,
+{
+    if T::CUSTOM_WRITES {
+        return Err(ApiError::bad_request(format!(
+            "Per-item batch updates aren't available for {}, whose updates run hooks or \
+             operations; use ?partial=true",
+            T::RESOURCE_NAME_PLURAL
+        )));
+    }
+    if items.len() > T::batch_limit() {
+        return Err(ApiError::bad_request(format!(
+            "Batch update limited to {} items. Received {} items.",
+            T::batch_limit(),
+            items.len()
+        )));
+    }
+
+    let parsed: Vec<_> = items
+        .into_iter()
+        .map(parse_item::<T::UpdateModel>)
+        .collect();
+    let ids = parsed
+        .iter()
+        .filter_map(|item| item.as_ref().ok().map(|(id, _)| *id));
+    let _locks = crate::locks::lock_rows::<T>(ids).await;
+
+    let txn = db.begin().await.map_err(ApiError::database)?;
+    let mut results = Vec::with_capacity(parsed.len());
+    let mut updated = Vec::new();
+    let mut replaced = Vec::new();
+    for (index, item) in parsed.into_iter().enumerate() {
+        let (id, model) = match item {
+            Ok(item) => item,
+            Err((id, error)) => {
+                results.push(ItemResult::failed(index, id, &error));
+                continue;
+            }
+        };
+        let savepoint = txn.begin().await.map_err(ApiError::database)?;
+        let outcome = async {
+            let existing = T::EntityType::find_by_id(id)
+                .one(&savepoint)
+                .await
+                .map_err(ApiError::database)?
+                .ok_or_else(|| {
+                    ApiError::not_found(T::RESOURCE_NAME_SINGULAR, Some(id.to_string()))
+                })?;
+            let previous = if T::file_fields().is_empty() {
+                Vec::new()
+            } else {
+                T::from(existing.clone()).stored_files()
+            };
+            let active_model =
+                T::update_active_model(&savepoint, model, existing.into_active_model()).await?;
+            let item = active_model
+                .update(&savepoint)
+                .await
+                .map_err(ApiError::database)?;
+            Ok::<_, ApiError>((T::from(item), previous))
+        }
+        .await;
+        match outcome {
+            Ok((item, previous)) => {
+                savepoint.commit().await.map_err(ApiError::database)?;
+                let current = item.stored_files();
+                replaced.extend(previous.into_iter().filter(|file| !current.contains(file)));
+                if T::WEBHOOKS {
+                    updated.push((id, serde_json::to_value(&item).unwrap_or_default()));
+                }
+                results.push(ItemResult {
+                    index,
+                    id: Some(id),
+                    status: 200,
+                    body: Some(R::from(item)),
+                    error: None,
+                });
+            }
+            Err(error) => {
+                savepoint.rollback().await.map_err(ApiError::database)?;
+                results.push(ItemResult::failed(index, Some(id), &error));
+            }
+        }
+    }
+    txn.commit().await.map_err(ApiError::database)?;
+
+    if let Err(error) = crate::webhooks::record::<T>(db, Event::Updated, updated).await {
+        tracing::warn!(
+            resource = T::RESOURCE_NAME_PLURAL,
+            error = %error,
+            "Failed to record webhook deliveries"
+        );
+    }
+    crate::storage::discard(&replaced).await;
+    Ok(results)
+}
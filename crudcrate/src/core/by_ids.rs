@@ -0,0 +1,178 @@
+//! Batched reads by ID, for React Admin's `getMany` and `GraphQL` dataloaders.
+//!
+//! The generated routers answer both forms, the `POST` for lists too long for a URL:
+//!
+//! ```text
+//! GET /todos/by-ids?ids=3f0c…,9a1b…,77d2…
+//! POST /todos/by-ids
+//! ["3f0c…", "9a1b…", "77d2…"]
+//!
+//! 200 OK
+//! {"items": [{"id": "3f0c…", …}, {"id": "77d2…", …}], "missing": ["9a1b…"]}
+//! ```
+//!
+//! `items` are list models in the requested order, `missing` the IDs that don't exist or
+//! are outside the request's [`ScopeCondition`](crate::ScopeCondition). Repeated IDs are
+//! answered once. The rows are found with one `WHERE id IN (...)` query and loaded with
+//! another through [`get_all_ordered`](CRUDResource::get_all_ordered), so list joins and
+//! computed fields apply as on the list endpoint. At most
+//! [`max_page_size`](CRUDResource::max_page_size) IDs are accepted per request.
+
+use std::collections::HashSet;
+
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use sea_orm::sea_query::{CaseStatement, Expr, SimpleExpr};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, Order, QueryFilter, QuerySelect};
+use serde::Serialize;
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::core::crud_operations::check_required_scope;
+use crate::{ApiError, CRUDResource, UuidIdResult};
+
+/// Items found by ID, in the requested order, and the IDs that weren't
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct ByIds<I> {
+    /// The found items, in the order their IDs were requested
+    pub items: Vec<I>,
+    /// Requested IDs without an item, in the order requested
+    pub missing: Vec<Uuid>,
+}
+
+/// Parse the comma-separated `ids` query parameter
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` naming the first value that isn't a UUID.
+pub fn parse_ids(ids: &str) -> Result<Vec<Uuid>, ApiError> {
+    ids.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            Uuid::parse_str(id).map_err(|_| ApiError::bad_request(format!("Invalid id '{id}'")))
+        })
+        .collect()
+}
+
+/// The `T` rows with the given IDs as list models, in the requested order.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` for more than [`max_page_size`](CRUDResource::max_page_size)
+/// IDs or when loading their join fields is over the [join budget](crate::complexity),
+/// and `ApiError::Database` if a query fails.
+pub async fn find_by_ids<T: CRUDResource>(
+    db: &DatabaseConnection,
+    ids: &[Uuid],
+    scope: Option<&Condition>,
+) -> Result<ByIds<T::ListModel>, ApiError> {
+    let mut seen = HashSet::new();
+    let requested: Vec<Uuid> = ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+    let limit = T::max_page_size();
+    if requested.len() as u64 > limit {
+        return Err(ApiError::bad_request(format!(
+            "At most {limit} ids per request. Received {}.",
+            requested.len()
+        )));
+    }
+    crate::complexity::check::<T>(requested.len() as u64, true)?;
+    if requested.is_empty() {
+        return Ok(ByIds {
+            items: Vec::new(),
+            missing: Vec::new(),
+        });
+    }
+
+    let mut condition = Condition::all()
+        .add(T::ID_COLUMN.is_in(requested.iter().copied()))
+        .add_option(T::read_condition());
+    if let Some(scope) = scope {
+        condition = condition.add(scope.clone());
+    }
+    let existing: HashSet<Uuid> = crate::core::view::select::<T>()
+        .select_only()
+        .column_as(T::ID_COLUMN, "id")
+        .filter(condition.clone())
+        .into_model::<UuidIdResult>()
+        .all(db)
+        .await
+        .map_err(ApiError::database)?
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+    let (found, missing): (Vec<Uuid>, Vec<Uuid>) =
+        requested.into_iter().partition(|id| existing.contains(id));
+    if found.is_empty() {
+        return Ok(ByIds {
+            items: Vec::new(),
+            missing,
+        });
+    }
+
+    // `CASE WHEN id = <first> THEN 0 WHEN id = <second> THEN 1 ... END` keeps the requested
+    // order. The positions are literals: as parameters, `PostgreSQL` would type them as text.
+    let position = found
+        .iter()
+        .enumerate()
+        .fold(CaseStatement::new(), |case, (index, id)| {
+            case.case(
+                Expr::col((T::ID_COLUMN.entity_name(), T::ID_COLUMN)).eq(*id),
+                Expr::cust(index.to_string()),
+            )
+        });
+    let order: [(SimpleExpr, Order); 1] = [(position.into(), Order::Asc)];
+    let items = T::get_all_ordered(
+        db,
+        &condition,
+        &order,
+        0,
+        found.len() as u64,
+        scope.is_some(),
+    )
+    .await?;
+    Ok(ByIds { items, missing })
+}
+
+/// Body of the generated `get_by_ids_handler` and `post_by_ids_handler`. `S` is the
+/// scoped list model, returned when `scope` is set.
+///
+/// # Errors
+/// Returns any error from [`find_by_ids`].
+pub async fn by_ids_response<T, S>(
+    db: &DatabaseConnection,
+    ids: &[Uuid],
+    scope: Option<Condition>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    T::ListModel: Serialize,
+    S: From<T::ListModel> + Serialize,
+{
+    check_required_scope::<T>(scope.is_some())?;
+    let found = find_by_ids::<T>(db, ids, scope.as_ref()).await?;
+    if scope.is_some() {
+        Ok(Json(ByIds {
+            items: found.items.into_iter().map(S::from).collect::<Vec<_>>(),
+            missing: found.missing,
+        })
+        .into_response())
+    } else {
+        Ok(Json(found).into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ids() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        assert_eq!(parse_ids(&format!("{a}, {b},")).unwrap(), [a, b]);
+        assert!(parse_ids("").unwrap().is_empty());
+        let error = parse_ids(&format!("{a},nope")).unwrap_err();
+        assert!(matches!(error, ApiError::BadRequest { .. }), "{error:?}");
+    }
+}
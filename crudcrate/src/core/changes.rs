@@ -0,0 +1,121 @@
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, EntityTrait, IdenStatic, Iterable, ModelTrait, Value,
+};
+
+/// Stands in for the values of masked columns
+const MASK: &str = "***";
+
+/// One column modified by an update: its value before and after.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldChange {
+    /// Database column name
+    pub column: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// The columns an update actually modified, handed to `update::one::post` and
+/// `update::many::post` hooks so audit or notification code can report what changed
+/// without re-querying.
+///
+/// Built from the merged active model before it is saved: a column is included when the
+/// update sets it to a value different from the stored one, which covers `on_update`
+/// columns as well as fields from the request body.
+///
+/// Generated handlers mask `sensitive` and `hash_with` columns with [`masking`](Self::masking):
+/// the set still reports that they changed, but both values read `***`, so hooks that log
+/// it never see the secret or its hash.
+///
+/// ```rust,ignore
+/// async fn audit(db: &DatabaseConnection, task: &Task, changes: &ChangeSet) -> Result<(), ApiError> {
+///     for change in changes {
+///         log::info!("task {} {}: {:?} -> {:?}", task.id, change.column, change.old, change.new);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChangeSet {
+    changes: Vec<FieldChange>,
+}
+
+impl ChangeSet {
+    /// Compare the stored `original` row with the `merged` active model about to be saved
+    #[must_use]
+    pub fn between<E, A>(original: &E::Model, merged: &A) -> Self
+    where
+        E: EntityTrait,
+        A: ActiveModelTrait<Entity = E>,
+    {
+        let changes = E::Column::iter()
+            .filter_map(|column| match merged.get(column) {
+                ActiveValue::Set(new) => {
+                    let old = original.get(column);
+                    if old == new {
+                        None
+                    } else {
+                        Some(FieldChange {
+                            column: column.as_str().to_string(),
+                            old,
+                            new,
+                        })
+                    }
+                }
+                ActiveValue::Unchanged(_) | ActiveValue::NotSet => None,
+            })
+            .collect();
+        Self { changes }
+    }
+
+    /// Replace the old and new values of `columns` with `***`, keeping the fact that they
+    /// changed
+    #[must_use]
+    pub fn masking(mut self, columns: &[&str]) -> Self {
+        for change in &mut self.changes {
+            if columns.contains(&change.column.as_str()) {
+                change.old = Value::from(MASK);
+                change.new = Value::from(MASK);
+            }
+        }
+        self
+    }
+
+    /// The change to `column`, if the update modified it
+    #[must_use]
+    pub fn get(&self, column: &str) -> Option<&FieldChange> {
+        self.changes.iter().find(|change| change.column == column)
+    }
+
+    #[must_use]
+    pub fn contains(&self, column: &str) -> bool {
+        self.get(column).is_some()
+    }
+
+    /// Names of the modified columns, in column order
+    pub fn columns(&self) -> impl Iterator<Item = &str> {
+        self.changes.iter().map(|change| change.column.as_str())
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, FieldChange> {
+        self.changes.iter()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a ChangeSet {
+    type Item = &'a FieldChange;
+    type IntoIter = std::slice::Iter<'a, FieldChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
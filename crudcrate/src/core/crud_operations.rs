@@ -1,5 +1,47 @@
+//! Handler macros and the generic runtime functions they delegate to.
+//!
+//! `crud_handlers!` emits one thin axum handler per endpoint; the shared read logic
+//! (filtering, sorting, pagination, scoping) lives in [`get_all_response`] and
+//! [`get_one_response`], and the derived `get_all`/`get_one` bodies query through
+//! [`find_page`] and [`find_one`]. Keeping it here, generic over the resource, keeps the
+//! code each resource expands to small.
+
+use axum::{
+    Json,
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, IdenStatic, Iterable,
+    Order, QueryFilter, QueryOrder, QuerySelect,
+    sea_query::{Expr, SimpleExpr},
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::traits::CRUDResource;
+use crate::filtering::facets::FacetedPage;
+use crate::{ApiError, DistinctValue, FilterOptions, SharedOperations, SortConfig};
+
+type ModelOf<T> = <<T as CRUDResource>::EntityType as EntityTrait>::Model;
+
 #[macro_export]
 macro_rules! crud_handlers {
+    // Plain axum handlers without OpenAPI annotations (`#[crudcrate(openapi = false)]`)
+    (@no_openapi $resource:ty, $update_model:ty, $create_model:ty, $list_model:ty, $response_model:ty, $scoped_list:ty, $scoped_response:ty) => {
+        crudcrate::crud_handlers_impl!(
+            @openapi false;
+            $resource,
+            $update_model,
+            $create_model,
+            $list_model,
+            $response_model,
+            $scoped_list,
+            $scoped_response
+        );
+    };
+
     // Version with scoped models for auth-aware field visibility
     ($resource:ty, $update_model:ty, $create_model:ty, $list_model:ty, $response_model:ty, $scoped_list:ty, $scoped_response:ty) => {
         crudcrate::crud_handlers_impl!(
@@ -53,13 +95,31 @@ macro_rules! crud_handlers {
     };
 }
 
+/// Emits the item with its leading attribute if `$openapi` is `true`, without it if
+/// `false`. Used by `crud_handlers_impl!` for the utoipa attributes.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __openapi_attr {
+    (true, #[$attr:meta] $($item:tt)*) => {
+        #[$attr]
+        $($item)*
+    };
+    (false, #[$attr:meta] $($item:tt)*) => {
+        $($item)*
+    };
+}
+
 #[macro_export]
 macro_rules! crud_handlers_impl {
-    ($resource:ty, $update_model:ty, $create_model:ty, $list_model:ty, $response_model:ty, $scoped_list:ty, $scoped_response:ty) => {
+    // `$openapi` is `true` or `false`: whether handlers carry `#[utoipa::path]`
+    (@openapi $openapi:tt; $resource:ty, $update_model:ty, $create_model:ty, $list_model:ty, $response_model:ty, $scoped_list:ty, $scoped_response:ty) => {
         use crudcrate::filter::{apply_filters, parse_pagination};
         use crudcrate::models::FilterOptions;
         use crudcrate::pagination::calculate_content_range;
         use crudcrate::sort::parse_sorting;
+        // utoipa resolves generic response bodies by their unqualified name
+        use crudcrate::ByIds;
+        use crudcrate::core::batch_update::BatchUpdateResponse;
 
         use axum::{
             extract::{Path, Query, State},
@@ -71,6 +131,7 @@ macro_rules! crud_handlers_impl {
         use sea_orm::{DbErr, SqlErr};
 
 
+        crudcrate::__openapi_attr!($openapi,
         #[utoipa::path(
             get,
             path = "/{id}",
@@ -80,179 +141,233 @@ macro_rules! crud_handlers_impl {
                 (status = axum::http::StatusCode::BAD_REQUEST, description = "Bad request"),
                 (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
             ),
-            operation_id = format!("get_one_{}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR),
-            summary = format!("Get one {}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR),
-            description = format!("Retrieves one {} by its ID.\n\n{}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR, <$resource as CRUDResource>::RESOURCE_DESCRIPTION)
+            params(crudcrate::temporal::AsOfOptions),
+            operation_id = format!("get_one_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Get one {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!("Retrieves one {} by its ID.\n\n{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR, <$resource as crudcrate::CRUDResource>::RESOURCE_DESCRIPTION)
         )]
         pub async fn get_one_handler(
             axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
-            axum::extract::Path(id): axum::extract::Path<uuid::Uuid>,
+            crudcrate::PathId { id, .. }: crudcrate::PathId<$resource>,
+            axum::extract::Query(options): axum::extract::Query<crudcrate::temporal::AsOfOptions>,
             scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
         ) -> Result<axum::response::Response, crudcrate::ApiError> {
-            use axum::response::IntoResponse;
-
-            // require_scope: if scope middleware is required but not present, return 500
-            if <$resource as crudcrate::traits::CRUDResource>::REQUIRE_SCOPE && scope.is_none() {
-                return Err(crudcrate::ApiError::internal(
-                    "Scope middleware required for this resource but not configured",
-                    Some("require_scope check failed: ScopeCondition extension not found in request".into()),
-                ));
-            }
-
-            if let Some(axum::Extension(crudcrate::ScopeCondition { condition: extra })) = scope {
-                // Atomic scoped fetch: ID + scope condition in a single query.
-                // Prevents TOCTOU race where scope-relevant columns could change
-                // between a fetch and a separate verification query.
-                let result = <$resource as crudcrate::traits::CRUDResource>::get_one_scoped(&db, id, &extra)
-                    .await
-                    .map_err(|_| crudcrate::ApiError::not_found(
-                        <$resource as crudcrate::traits::CRUDResource>::RESOURCE_NAME_SINGULAR,
-                        Some(id.to_string()),
-                    ))?;
-
-                let response: $response_model = result.into();
-                let scoped: $scoped_response = response.into();
-                Ok(axum::Json(scoped).into_response())
-            } else {
-                let result = <$resource as crudcrate::traits::CRUDResource>::get_one(&db, id)
-                    .await
-                    .map_err(crudcrate::ApiError::from)?;
-                let response: $response_model = result.into();
-                Ok(axum::Json(response).into_response())
-            }
-        }
+            crudcrate::core::crud_operations::get_one_response::<$resource, $response_model, $scoped_response>(
+                &db,
+                id,
+                options.as_of.as_deref(),
+                scope.map(|axum::Extension(scope)| scope.condition),
+                ops.as_ref().map(|axum::Extension(ops)| ops),
+            )
+            .await
+        });
 
+        crudcrate::__openapi_attr!($openapi,
         #[utoipa::path(
             get,
             path = "/",
             responses(
-                (status = axum::http::StatusCode::OK, description = "List of resources", body = [$list_model]),
+                (status = axum::http::StatusCode::OK, description = "List of resources, as `{items, facets}` when `facets` is given", body = [$list_model]),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Invalid filter or facets, e.g. an unknown enum value"),
                 (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
             ),
             params(crudcrate::models::FilterOptions),
-            operation_id = format!("get_all_{}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL),
-            summary = format!("Get all {}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL),
+            operation_id = format!("get_all_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            summary = format!("Get all {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
             description = format!(
-                "Retrieves all {}.\n\n{}\n\nAdditional sortable columns: {}.\n\nAdditional filterable columns: {}.",
-                <$resource as CRUDResource>::RESOURCE_NAME_PLURAL,
-                <$resource as CRUDResource>::RESOURCE_DESCRIPTION,
-                <$resource as CRUDResource>::sortable_columns()
+                "Retrieves all {}.\n\n{}\n\nAdditional sortable columns: {}.\n\n{}\n\nAdditional filterable columns: {}{}.\n\nEach filter key can also be passed as a query parameter of its own, such as `?status=open&priority_gte=3`; a repeated parameter matches any of its values and `_between` takes `min,max`. Keys in `filter` take precedence over the same parameter.",
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL,
+                <$resource as crudcrate::CRUDResource>::RESOURCE_DESCRIPTION,
+                <$resource as crudcrate::CRUDResource>::sortable_columns()
                     .iter()
                     .map(|(name, _)| format!("\n- {}", name))
                     .collect::<Vec<String>>()
                     .join(""),
-                <$resource as CRUDResource>::filterable_columns()
+                crudcrate::core::crud_operations::tie_breaker_note::<$resource>(),
+                <$resource as crudcrate::CRUDResource>::filterable_columns()
                     .iter()
-                    .map(|(name, _)| format!("\n- {}", name))
+                    .map(|(name, _)| {
+                        if <$resource as crudcrate::CRUDResource>::json_filterable_columns().contains(name) {
+                            format!("\n- {name} (JSON: filter nested keys with `{name}.key.nested`, operators `_neq`, `_gt`, `_gte`, `_lt`, `_lte`)")
+                        } else if <$resource as crudcrate::CRUDResource>::array_filterable_columns().iter().any(|(array, _)| array == name) {
+                            format!("\n- {name} (array, PostgreSQL: `{name}_contains`, `{name}_overlaps`)")
+                        } else if <$resource as crudcrate::CRUDResource>::decimal_filterable_columns().contains(name) {
+                            format!("\n- {name} (decimal: pass a numeric string such as \"19.99\" for exact values)")
+                        } else if <$resource as crudcrate::CRUDResource>::date_filterable_columns().iter().any(|(date, _)| date == name) {
+                            format!("\n- {name} (date: also `{name}_date`, `{name}_year`, optional UTC offset like \"2024-05-01+02:00\")")
+                        } else if let Some(variants) = <$resource as crudcrate::CRUDResource>::enum_variants(name) {
+                            format!("\n- {name} (one of: {})", variants.join(", "))
+                        } else {
+                            format!("\n- {}", name)
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(""),
+                <$resource as crudcrate::CRUDResource>::virtual_filters()
+                    .iter()
+                    .map(|filter| if filter.description.is_empty() {
+                        format!("\n- {} (virtual)", filter.name)
+                    } else {
+                        format!("\n- {} (virtual: {})", filter.name, filter.description)
+                    })
                     .collect::<Vec<String>>()
                     .join("")
             )
         )]
         pub async fn get_all_handler(
             axum::extract::Query(params): axum::extract::Query<crudcrate::models::FilterOptions>,
+            axum::extract::Query(query): axum::extract::Query<Vec<(String, String)>>,
             axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
             scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
         ) -> Result<axum::response::Response, crudcrate::ApiError> {
-            use axum::response::IntoResponse;
-
-            // require_scope: if scope middleware is required but not present, return 500
-            if <$resource as crudcrate::traits::CRUDResource>::REQUIRE_SCOPE && scope.is_none() {
-                return Err(crudcrate::ApiError::internal(
-                    "Scope middleware required for this resource but not configured",
-                    Some("require_scope check failed: ScopeCondition extension not found in request".into()),
-                ));
-            }
-
-            let (offset, limit) = crudcrate::filter::parse_pagination(&params);
-            let limit = limit.min(<$resource as crudcrate::traits::CRUDResource>::max_page_size());
-
-            let is_scoped = scope.is_some();
-
-            // When scoped, strip excluded columns from filterable/sortable lists
-            // to prevent schema probing by unauthenticated users
-            let filterable_columns = <$resource as CRUDResource>::filterable_columns();
-            let sortable_columns = <$resource as crudcrate::traits::CRUDResource>::sortable_columns();
-            let (filterable_columns, sortable_columns) = if is_scoped {
-                let excluded = <$resource as crudcrate::traits::CRUDResource>::scoped_excluded_columns();
-                let f: Vec<_> = filterable_columns.into_iter().filter(|(name, _)| !excluded.contains(name)).collect();
-                let s: Vec<_> = sortable_columns.into_iter().filter(|(name, _)| !excluded.contains(name)).collect();
-                (f, s)
-            } else {
-                (filterable_columns, sortable_columns)
-            };
-
-            let parsed_filters = crudcrate::apply_filters_with_joins::<$resource>(
-                params.filter.clone(),
-                &filterable_columns,
-                db.get_database_backend()
-            )?;
-
-            let sort_config = crudcrate::parse_sorting_with_joins::<$resource, _>(
-                &params,
-                &sortable_columns,
-                <$resource as crudcrate::traits::CRUDResource>::default_index_column(),
-            );
-
-            let mut condition = parsed_filters.main_condition;
-
-            if let Some(axum::Extension(crudcrate::ScopeCondition { condition: extra })) = scope {
-                condition = condition.add(extra);
-            };
+            let params = crudcrate::filtering::merge_query_filters::<$resource>(params, &query)?;
+            crudcrate::core::crud_operations::get_all_response::<$resource, $scoped_list>(
+                params,
+                &db,
+                scope.map(|axum::Extension(scope)| scope.condition),
+                ops.as_ref().map(|axum::Extension(ops)| ops),
+            )
+            .await
+        });
 
-            let (order_column, order_direction) = match &sort_config {
-                crudcrate::SortConfig::Column { column, direction } => (*column, direction.clone()),
-                crudcrate::SortConfig::Joined { direction, .. } => {
-                    (<$resource as crudcrate::traits::CRUDResource>::default_index_column(), direction.clone())
-                }
-            };
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            get,
+            path = "/distinct/{field}",
+            responses(
+                (status = axum::http::StatusCode::OK, description = "Distinct values of the field, in ascending order", body = [crudcrate::DistinctValue]),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Field is not filterable or invalid filter"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            params(crudcrate::models::FilterOptions, crudcrate::DistinctOptions),
+            operation_id = format!("get_distinct_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            summary = format!("Get distinct values of a {} field", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!(
+                "Retrieves the distinct values of a filterable field among the {} matching `filter` (at most {}), with `counts=true` for the number of rows per value.\n\nFilterable fields: {}",
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL,
+                <$resource as crudcrate::CRUDResource>::max_page_size(),
+                <$resource as crudcrate::CRUDResource>::filterable_columns()
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        )]
+        pub async fn get_distinct_handler(
+            axum::extract::Path(field): axum::extract::Path<String>,
+            axum::extract::Query(params): axum::extract::Query<crudcrate::models::FilterOptions>,
+            axum::extract::Query(options): axum::extract::Query<crudcrate::DistinctOptions>,
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            crudcrate::filtering::distinct::distinct_response::<$resource>(
+                &db,
+                &field,
+                params,
+                options,
+                scope.map(|axum::Extension(scope)| scope.condition),
+            )
+            .await
+        });
 
-            let items = if is_scoped {
-                <$resource as crudcrate::traits::CRUDResource>::get_all_scoped(&db, &condition, order_column, order_direction, offset, limit)
-                    .await
-                    .map_err(crudcrate::ApiError::from)?
-            } else {
-                <$resource as crudcrate::traits::CRUDResource>::get_all(&db, &condition, order_column, order_direction, offset, limit)
-                    .await
-                    .map_err(crudcrate::ApiError::from)?
-            };
-            let total_count = <$resource as crudcrate::traits::CRUDResource>::total_count(&db, &condition).await;
-            let headers = crudcrate::pagination::calculate_content_range(offset, limit, total_count, <$resource as crudcrate::traits::CRUDResource>::RESOURCE_NAME_PLURAL);
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            get,
+            path = "/by-ids",
+            responses(
+                (status = axum::http::StatusCode::OK, description = "Found items in the requested order, and the IDs without one", body = crudcrate::ByIds<$list_model>),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Invalid ID or too many IDs"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            params(crudcrate::ByIdsOptions),
+            operation_id = format!("get_by_ids_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            summary = format!("Get {} by ID", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            description = format!(
+                "Retrieves up to {} {} by comma-separated ID, in the requested order. IDs without a {} are listed under `missing`.",
+                <$resource as crudcrate::CRUDResource>::max_page_size(),
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL,
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR
+            )
+        )]
+        pub async fn get_by_ids_handler(
+            axum::extract::Query(options): axum::extract::Query<crudcrate::ByIdsOptions>,
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            let ids = crudcrate::core::by_ids::parse_ids(&options.ids)?;
+            crudcrate::core::by_ids::by_ids_response::<$resource, $scoped_list>(
+                &db,
+                &ids,
+                scope.map(|axum::Extension(scope)| scope.condition),
+            )
+            .await
+        });
 
-            if is_scoped {
-                let scoped: Vec<$scoped_list> = items.into_iter().map(|item| { let converted: $scoped_list = item.into(); converted }).collect();
-                Ok((headers, axum::Json(scoped)).into_response())
-            } else {
-                Ok((headers, axum::Json(items)).into_response())
-            }
-        }
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            post,
+            path = "/by-ids",
+            request_body = Vec<uuid::Uuid>,
+            responses(
+                (status = axum::http::StatusCode::OK, description = "Found items in the requested order, and the IDs without one", body = crudcrate::ByIds<$list_model>),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Too many IDs"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            operation_id = format!("post_by_ids_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            summary = format!("Get {} by ID (JSON body)", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            description = "Same as `GET /by-ids`, with the IDs as a JSON array for lists too long for a URL. Nothing is modified."
+        )]
+        pub async fn post_by_ids_handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            axum::Json(ids): axum::Json<Vec<uuid::Uuid>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            crudcrate::core::by_ids::by_ids_response::<$resource, $scoped_list>(
+                &db,
+                &ids,
+                scope.map(|axum::Extension(scope)| scope.condition),
+            )
+            .await
+        });
 
 
+        crudcrate::__openapi_attr!($openapi,
         #[utoipa::path(
             delete,
             path = "/{id}",
             responses(
                 (status = axum::http::StatusCode::NO_CONTENT, description = "Resource deleted successfully"),
                 (status = axum::http::StatusCode::NOT_FOUND, description = "Resource not found"),
+                (status = axum::http::StatusCode::CONFLICT, description = "Dependent rows exist (`restrict_delete` resources)"),
+                (status = axum::http::StatusCode::LOCKED, description = "A row is locked (`guard_delete` resources)"),
                 (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
             ),
-            operation_id = format!("delete_one_{}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR),
-            summary = format!("Delete one {}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR),
-            description = format!("Deletes one {} by its ID.\n\n{}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR, <$resource as CRUDResource>::RESOURCE_DESCRIPTION)
+            operation_id = format!("delete_one_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Delete one {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!("Deletes one {} by its ID.\n\n{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR, <$resource as crudcrate::CRUDResource>::RESOURCE_DESCRIPTION)
         )]
         pub async fn delete_one_handler(
             state: axum::extract::State<sea_orm::DatabaseConnection>,
             scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
-            path: axum::extract::Path<uuid::Uuid>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
+            path: crudcrate::PathId<$resource>,
         ) -> Result<axum::http::StatusCode, crudcrate::ApiError> {
             if scope.is_some() {
                 return Err(crudcrate::ApiError::forbidden("Write access denied in scoped context"));
             }
-            <$resource as crudcrate::traits::CRUDResource>::delete(&state.0, path.0)
-                .await
+            let _locks = crudcrate::locks::lock_rows::<$resource>([path.id]).await;
+            let result = match &ops {
+                Some(axum::Extension(ops)) => crudcrate::CRUDOperations::delete(ops.operations(), &state.0, path.id).await,
+                None => <$resource as crudcrate::traits::CRUDResource>::delete(&state.0, path.id).await,
+            };
+            result
                 .map(|_| axum::http::StatusCode::NO_CONTENT)
                 .map_err(crudcrate::ApiError::from)
-        }
+        });
 
+        crudcrate::__openapi_attr!($openapi,
         #[utoipa::path(
             post,
             path = "/",
@@ -269,24 +384,35 @@ macro_rules! crud_handlers_impl {
                     body = String
                 )
             ),
-            operation_id = format!("create_one_{}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR),
-            summary = format!("Create one {}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR),
-            description = format!("Creates a new {}.\n\n{}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR, <$resource as CRUDResource>::RESOURCE_DESCRIPTION)
+            operation_id = format!("create_one_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Create one {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!("Creates a new {}.\n\n{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR, <$resource as crudcrate::CRUDResource>::RESOURCE_DESCRIPTION)
         )]
         pub async fn create_one_handler(
             state: axum::extract::State<sea_orm::DatabaseConnection>,
             scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
-            json: axum::Json<$create_model>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
+            payload: crudcrate::storage::Payload<$resource, $create_model>,
         ) -> Result<(axum::http::StatusCode, axum::Json<$response_model>), crudcrate::ApiError> {
+            let (data, stored) = payload.into_parts();
             if scope.is_some() {
+                crudcrate::storage::discard(&stored).await;
                 return Err(crudcrate::ApiError::forbidden("Write access denied in scoped context"));
             }
-            <$resource as crudcrate::traits::CRUDResource>::create(&state.0, json.0)
-                .await
+            let result = match &ops {
+                Some(axum::Extension(ops)) => crudcrate::CRUDOperations::create(ops.operations(), &state.0, data).await,
+                None => <$resource as crudcrate::traits::CRUDResource>::create(&state.0, data).await,
+            };
+            if result.is_err() {
+                // Uploaded files would otherwise be left without a record
+                crudcrate::storage::discard(&stored).await;
+            }
+            result
                 .map(|res| (axum::http::StatusCode::CREATED, axum::Json(res.into())))
                 .map_err(crudcrate::ApiError::from)
-        }
+        });
 
+        crudcrate::__openapi_attr!($openapi,
         #[utoipa::path(
             delete,
             path = "/batch",
@@ -295,15 +421,18 @@ macro_rules! crud_handlers_impl {
                 (status = axum::http::StatusCode::OK, description = "Resources deleted successfully", body = [uuid::Uuid]),
                 (status = 207, description = "Partial success - some items deleted, some failed"),
                 (status = axum::http::StatusCode::BAD_REQUEST, description = "Bad request - batch size exceeded", body = String),
+                (status = axum::http::StatusCode::CONFLICT, description = "Dependent rows exist (`restrict_delete` resources)"),
+                (status = axum::http::StatusCode::LOCKED, description = "A row is locked (`guard_delete` resources)"),
                 (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error", body = String)
             ),
-            operation_id = format!("delete_many_{}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL),
-            summary = format!("Delete many {}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL),
-            description = format!("Deletes many {} by their IDs and returns array of deleted UUIDs.\n\nUse `?partial=true` for partial success mode (deletes valid items even if some fail).\n\n{}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL, <$resource as CRUDResource>::RESOURCE_DESCRIPTION)
+            operation_id = format!("delete_many_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            summary = format!("Delete many {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            description = format!("Deletes many {} by their IDs and returns array of deleted UUIDs.\n\nUse `?partial=true` for partial success mode (deletes valid items even if some fail).\n\n{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL, <$resource as crudcrate::CRUDResource>::RESOURCE_DESCRIPTION)
         )]
         pub async fn delete_many_handler(
             state: axum::extract::State<sea_orm::DatabaseConnection>,
             scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
             axum::extract::Query(options): axum::extract::Query<crudcrate::BatchOptions>,
             json: axum::Json<Vec<uuid::Uuid>>,
         ) -> axum::response::Response {
@@ -322,13 +451,18 @@ macro_rules! crud_handlers_impl {
                         <$resource as crudcrate::traits::CRUDResource>::batch_limit(), ids.len())
                 ).into_response();
             }
+            let _locks = crudcrate::locks::lock_rows::<$resource>(ids.iter().copied()).await;
 
             if options.partial {
                 // Partial success mode: process each item individually
                 let mut result: crudcrate::BatchResult<uuid::Uuid> = crudcrate::BatchResult::new();
 
                 for (index, id) in ids.into_iter().enumerate() {
-                    match <$resource as crudcrate::traits::CRUDResource>::delete(&state.0, id).await {
+                    let outcome = match &ops {
+                        Some(axum::Extension(ops)) => crudcrate::CRUDOperations::delete(ops.operations(), &state.0, id).await,
+                        None => <$resource as crudcrate::traits::CRUDResource>::delete(&state.0, id).await,
+                    };
+                    match outcome {
                         Ok(_) => result.add_success(id),
                         Err(e) => result.add_failure(index, e.to_string()),
                     }
@@ -347,15 +481,89 @@ macro_rules! crud_handlers_impl {
                 }
             } else {
                 // All-or-nothing mode (default)
-                match <$resource as crudcrate::traits::CRUDResource>::delete_many(&state.0, ids).await {
+                let outcome = match &ops {
+                    Some(axum::Extension(ops)) => crudcrate::CRUDOperations::delete_many(ops.operations(), &state.0, ids).await,
+                    None => <$resource as crudcrate::traits::CRUDResource>::delete_many(&state.0, ids).await,
+                };
+                match outcome {
                     Ok(deleted_ids) => {
                         (axum::http::StatusCode::OK, axum::Json(deleted_ids)).into_response()
                     }
                     Err(e) => crudcrate::ApiError::from(e).into_response()
                 }
             }
-        }
+        });
+
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            delete,
+            path = "/",
+            params(crudcrate::models::FilterOptions, crudcrate::DeleteFilterOptions),
+            responses(
+                (status = axum::http::StatusCode::OK, description = "Number of matching and deleted resources", body = crudcrate::FilterDeleteResult),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Missing, empty or unknown filter, or more matches than the batch limit", body = String),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error", body = String)
+            ),
+            operation_id = format!("delete_by_filter_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            summary = format!("Delete {} matching a filter", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            description = format!("Deletes all {} matching `filter`, which is required and must only use filterable columns. Use `?count_only=true` to count the matches without deleting. Deletes go through the same path as batch delete and are limited to {} items.\n\n{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL, <$resource as crudcrate::CRUDResource>::batch_limit(), <$resource as crudcrate::CRUDResource>::RESOURCE_DESCRIPTION)
+        )]
+        pub async fn delete_by_filter_handler(
+            axum::extract::Query(params): axum::extract::Query<crudcrate::models::FilterOptions>,
+            axum::extract::Query(options): axum::extract::Query<crudcrate::DeleteFilterOptions>,
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
+        ) -> Result<axum::Json<crudcrate::FilterDeleteResult>, crudcrate::ApiError> {
+            use sea_orm::{EntityTrait, PaginatorTrait, QueryFilter, QuerySelect};
+
+            if scope.is_some() {
+                return Err(crudcrate::ApiError::forbidden("Write access denied in scoped context"));
+            }
+
+            let params = crudcrate::filtering::translate_field_names::<$resource>(params);
+            let condition = crudcrate::filtering::delete_filter_condition::<$resource>(
+                params.filter,
+                &<$resource as crudcrate::traits::CRUDResource>::filterable_columns(),
+                db.get_database_backend(),
+            )?;
+            let matching = <$resource as crudcrate::traits::CRUDResource>::EntityType::find().filter(condition);
 
+            if options.count_only {
+                let matched = matching.count(&db).await.map_err(crudcrate::ApiError::database)?;
+                return Ok(axum::Json(crudcrate::FilterDeleteResult { matched, deleted: 0 }));
+            }
+
+            // Delete through `delete_many` so hooks, custom operations and the batch
+            // limit apply as they do to `DELETE /batch`
+            let limit = <$resource as crudcrate::traits::CRUDResource>::batch_limit();
+            let ids: Vec<uuid::Uuid> = matching
+                .select_only()
+                .column_as(<$resource as crudcrate::traits::CRUDResource>::ID_COLUMN, "id")
+                .limit(limit as u64 + 1)
+                .into_model::<crudcrate::UuidIdResult>()
+                .all(&db)
+                .await
+                .map_err(crudcrate::ApiError::database)?
+                .into_iter()
+                .map(|row| row.id)
+                .collect();
+            if ids.len() > limit {
+                return Err(crudcrate::ApiError::bad_request(format!(
+                    "Filter matches more than {limit} items, the batch delete limit. Narrow the filter or use count_only=true to check the number of matches."
+                )));
+            }
+
+            let matched = ids.len() as u64;
+            let _locks = crudcrate::locks::lock_rows::<$resource>(ids.iter().copied()).await;
+            let deleted = match &ops {
+                Some(axum::Extension(ops)) => crudcrate::CRUDOperations::delete_many(ops.operations(), &db, ids).await,
+                None => <$resource as crudcrate::traits::CRUDResource>::delete_many(&db, ids).await,
+            }?;
+            Ok(axum::Json(crudcrate::FilterDeleteResult { matched, deleted: deleted.len() as u64 }))
+        });
+
+        crudcrate::__openapi_attr!($openapi,
         #[utoipa::path(
             put,
             path = "/{id}",
@@ -365,25 +573,60 @@ macro_rules! crud_handlers_impl {
             (status = axum::http::StatusCode::NOT_FOUND, description = "Resource not found"),
             (status =  axum::http::StatusCode::CONFLICT, description = "Duplicate record", body = String)
             ),
-            operation_id = format!("update_one_{}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR),
-            summary = format!("Update one {}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR),
-            description = format!("Updates one {} by its ID.\n\n{}", <$resource as CRUDResource>::RESOURCE_NAME_SINGULAR, <$resource as CRUDResource>::RESOURCE_DESCRIPTION)
+            operation_id = format!("update_one_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Update one {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!("Updates one {} by its ID.\n\n{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR, <$resource as crudcrate::CRUDResource>::RESOURCE_DESCRIPTION)
         )]
         pub async fn update_one_handler(
             state: axum::extract::State<sea_orm::DatabaseConnection>,
             scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
-            path: axum::extract::Path<uuid::Uuid>,
-            json: axum::Json<$update_model>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
+            path: crudcrate::PathId<$resource>,
+            payload: crudcrate::storage::Payload<$resource, $update_model>,
         ) -> Result<axum::Json<$response_model>, crudcrate::ApiError> {
+            let (data, stored) = payload.into_parts();
             if scope.is_some() {
+                crudcrate::storage::discard(&stored).await;
                 return Err(crudcrate::ApiError::forbidden("Write access denied in scoped context"));
             }
-            <$resource as crudcrate::traits::CRUDResource>::update(&state.0, path.0, json.0)
-                .await
+            // `serialize_writes`: held until the merged row is written and its files settled
+            let _locks = crudcrate::locks::lock_rows::<$resource>([path.id]).await;
+            // Files referenced before the update, deleted once they are replaced
+            let previous = if stored.is_empty() {
+                Ok(vec![])
+            } else {
+                let existing = match &ops {
+                    Some(axum::Extension(ops)) => crudcrate::CRUDOperations::get_one(ops.operations(), &state.0, path.id).await,
+                    None => <$resource as crudcrate::traits::CRUDResource>::get_one(&state.0, path.id).await,
+                };
+                existing.map(|existing| crudcrate::traits::CRUDResource::stored_files(&existing))
+            };
+            let result = match previous {
+                Ok(previous) => {
+                    let result = match &ops {
+                        Some(axum::Extension(ops)) => crudcrate::CRUDOperations::update(ops.operations(), &state.0, path.id, data).await,
+                        None => <$resource as crudcrate::traits::CRUDResource>::update(&state.0, path.id, data).await,
+                    };
+                    if let Ok(updated) = &result {
+                        crudcrate::storage::discard_replaced(
+                            &previous,
+                            &crudcrate::traits::CRUDResource::stored_files(updated),
+                        )
+                        .await;
+                    }
+                    result
+                }
+                Err(e) => Err(e),
+            };
+            if result.is_err() {
+                crudcrate::storage::discard(&stored).await;
+            }
+            result
                 .map(|res| axum::Json(res.into()))
                 .map_err(crudcrate::ApiError::from)
-        }
+        });
 
+        crudcrate::__openapi_attr!($openapi,
         #[utoipa::path(
             post,
             path = "/batch",
@@ -396,13 +639,14 @@ macro_rules! crud_handlers_impl {
                 (status = axum::http::StatusCode::CONFLICT, description = "Duplicate record", body = String),
                 (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error", body = String)
             ),
-            operation_id = format!("create_many_{}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL),
-            summary = format!("Create many {}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL),
-            description = format!("Creates multiple {} in a batch. Limited to {} items per request.\n\nUse `?partial=true` for partial success mode (commits successful items even if some fail).\n\n{}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL, <$resource as CRUDResource>::batch_limit(), <$resource as CRUDResource>::RESOURCE_DESCRIPTION)
+            operation_id = format!("create_many_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            summary = format!("Create many {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            description = format!("Creates multiple {} in a batch. Limited to {} items per request.\n\nUse `?partial=true` for partial success mode (commits successful items even if some fail).\n\n{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL, <$resource as crudcrate::CRUDResource>::batch_limit(), <$resource as crudcrate::CRUDResource>::RESOURCE_DESCRIPTION)
         )]
         pub async fn create_many_handler(
             state: axum::extract::State<sea_orm::DatabaseConnection>,
             scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
             axum::extract::Query(options): axum::extract::Query<crudcrate::BatchOptions>,
             json: axum::Json<Vec<$create_model>>,
         ) -> axum::response::Response {
@@ -427,7 +671,11 @@ macro_rules! crud_handlers_impl {
                 let mut result: crudcrate::BatchResult<$response_model> = crudcrate::BatchResult::new();
 
                 for (index, create_model) in data.into_iter().enumerate() {
-                    match <$resource as crudcrate::traits::CRUDResource>::create(&state.0, create_model).await {
+                    let outcome = match &ops {
+                        Some(axum::Extension(ops)) => crudcrate::CRUDOperations::create(ops.operations(), &state.0, create_model).await,
+                        None => <$resource as crudcrate::traits::CRUDResource>::create(&state.0, create_model).await,
+                    };
+                    match outcome {
                         Ok(created) => result.add_success(created.into()),
                         Err(e) => result.add_failure(index, e.to_string()),
                     }
@@ -446,7 +694,11 @@ macro_rules! crud_handlers_impl {
                 }
             } else {
                 // All-or-nothing mode (default)
-                match <$resource as crudcrate::traits::CRUDResource>::create_many(&state.0, data).await {
+                let outcome = match &ops {
+                    Some(axum::Extension(ops)) => crudcrate::CRUDOperations::create_many(ops.operations(), &state.0, data).await,
+                    None => <$resource as crudcrate::traits::CRUDResource>::create_many(&state.0, data).await,
+                };
+                match outcome {
                     Ok(results) => {
                         let response: Vec<$response_model> = results.into_iter().map(|r| r.into()).collect();
                         (axum::http::StatusCode::CREATED, axum::Json(response)).into_response()
@@ -454,11 +706,13 @@ macro_rules! crud_handlers_impl {
                     Err(e) => crudcrate::ApiError::from(e).into_response()
                 }
             }
-        }
+        });
 
+        crudcrate::__openapi_attr!($openapi,
+        #[derive(utoipa::ToSchema)]
         /// Wrapper type for batch update request items.
         /// Each item contains an `id` field and the update fields flattened into the same object.
-        #[derive(Debug, Clone, serde::Deserialize, utoipa::ToSchema)]
+        #[derive(Debug, Clone, serde::Deserialize)]
         #[allow(dead_code)]
         pub struct BatchUpdateRequest {
             /// The ID of the resource to update
@@ -466,30 +720,32 @@ macro_rules! crud_handlers_impl {
             /// Additional update fields (flattened)
             #[serde(flatten)]
             pub data: $update_model,
-        }
+        });
 
+        crudcrate::__openapi_attr!($openapi,
         #[utoipa::path(
             patch,
             path = "/batch",
             request_body = Vec<BatchUpdateRequest>,
             params(crudcrate::BatchOptions),
             responses(
-                (status = axum::http::StatusCode::OK, description = "Resources updated successfully", body = [$response_model]),
-                (status = 207, description = "Partial success - some items updated, some failed"),
-                (status = axum::http::StatusCode::BAD_REQUEST, description = "Bad request - batch size exceeded or validation failed", body = String),
+                (status = axum::http::StatusCode::OK, description = "Resources updated successfully: the rows, the `partial=true` result or the `per_item=true` results", body = crudcrate::core::batch_update::BatchUpdateResponse<$response_model>),
+                (status = 207, description = "Partial success - some items updated, some failed: the `partial=true` result or the `per_item=true` results", body = crudcrate::core::batch_update::BatchUpdateResponse<$response_model>),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Bad request - batch size exceeded or validation failed, or with `partial=true` or `per_item=true` every item failed", body = crudcrate::core::batch_update::BatchUpdateResponse<$response_model>),
                 (status = axum::http::StatusCode::NOT_FOUND, description = "One or more resources not found"),
                 (status = axum::http::StatusCode::CONFLICT, description = "Duplicate record", body = String),
                 (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error", body = String)
             ),
-            operation_id = format!("update_many_{}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL),
-            summary = format!("Update many {}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL),
-            description = format!("Updates multiple {} in a batch. Limited to {} items per request.\n\nUse `?partial=true` for partial success mode (commits successful items even if some fail).\n\n{}", <$resource as CRUDResource>::RESOURCE_NAME_PLURAL, <$resource as CRUDResource>::batch_limit(), <$resource as CRUDResource>::RESOURCE_DESCRIPTION)
+            operation_id = format!("update_many_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            summary = format!("Update many {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            description = format!("Updates multiple {} in a batch. Limited to {} items per request.\n\nUse `?partial=true` for partial success mode (commits successful items even if some fail), or `?per_item=true` to apply the items in one transaction and get each item's status and row.\n\n{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL, <$resource as crudcrate::CRUDResource>::batch_limit(), <$resource as crudcrate::CRUDResource>::RESOURCE_DESCRIPTION)
         )]
         pub async fn update_many_handler(
             state: axum::extract::State<sea_orm::DatabaseConnection>,
             scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
             axum::extract::Query(options): axum::extract::Query<crudcrate::BatchOptions>,
-            json: axum::Json<Vec<BatchUpdateRequest>>,
+            json: axum::Json<Vec<serde_json::Value>>,
         ) -> axum::response::Response {
             use axum::response::IntoResponse;
 
@@ -497,7 +753,30 @@ macro_rules! crud_handlers_impl {
                 return crudcrate::ApiError::forbidden("Write access denied in scoped context").into_response();
             }
 
-            let updates: Vec<(uuid::Uuid, $update_model)> = json.0
+            // Per-item mode parses each item itself, so one malformed item fails alone
+            if options.per_item {
+                return match crudcrate::core::batch_update::update_each::<$resource, $response_model>(&state.0, json.0).await {
+                    Ok(results) => {
+                        let succeeded = results.iter().filter(|result| result.status == 200).count();
+                        // Same statuses as partial mode: 400 if all failed, 207 if some did
+                        let status = if succeeded == results.len() {
+                            axum::http::StatusCode::OK
+                        } else if succeeded == 0 {
+                            axum::http::StatusCode::BAD_REQUEST
+                        } else {
+                            axum::http::StatusCode::MULTI_STATUS
+                        };
+                        (status, axum::Json(results)).into_response()
+                    }
+                    Err(e) => e.into_response(),
+                };
+            }
+
+            let items: Vec<BatchUpdateRequest> = match serde_json::from_value(serde_json::Value::Array(json.0)) {
+                Ok(items) => items,
+                Err(e) => return crudcrate::ApiError::validation_failed(vec![e.to_string()]).into_response(),
+            };
+            let updates: Vec<(uuid::Uuid, $update_model)> = items
                 .into_iter()
                 .map(|item| (item.id, item.data))
                 .collect();
@@ -509,13 +788,18 @@ macro_rules! crud_handlers_impl {
                         <$resource as crudcrate::traits::CRUDResource>::batch_limit(), updates.len())
                 ).into_response();
             }
+            let _locks = crudcrate::locks::lock_rows::<$resource>(updates.iter().map(|(id, _)| *id)).await;
 
             if options.partial {
                 // Partial success mode: process each item individually
                 let mut result: crudcrate::BatchResult<$response_model> = crudcrate::BatchResult::new();
 
                 for (index, (id, update_model)) in updates.into_iter().enumerate() {
-                    match <$resource as crudcrate::traits::CRUDResource>::update(&state.0, id, update_model).await {
+                    let outcome = match &ops {
+                        Some(axum::Extension(ops)) => crudcrate::CRUDOperations::update(ops.operations(), &state.0, id, update_model).await,
+                        None => <$resource as crudcrate::traits::CRUDResource>::update(&state.0, id, update_model).await,
+                    };
+                    match outcome {
                         Ok(updated) => result.add_success(updated.into()),
                         Err(e) => result.add_failure(index, e.to_string()),
                     }
@@ -534,7 +818,11 @@ macro_rules! crud_handlers_impl {
                 }
             } else {
                 // All-or-nothing mode (default)
-                match <$resource as crudcrate::traits::CRUDResource>::update_many(&state.0, updates).await {
+                let outcome = match &ops {
+                    Some(axum::Extension(ops)) => crudcrate::CRUDOperations::update_many(ops.operations(), &state.0, updates).await,
+                    None => <$resource as crudcrate::traits::CRUDResource>::update_many(&state.0, updates).await,
+                };
+                match outcome {
                     Ok(results) => {
                         let response: Vec<$response_model> = results.into_iter().map(|r| r.into()).collect();
                         (axum::http::StatusCode::OK, axum::Json(response)).into_response()
@@ -542,7 +830,20 @@ macro_rules! crud_handlers_impl {
                     Err(e) => crudcrate::ApiError::from(e).into_response()
                 }
             }
-        }
+        });
+    };
+
+    ($resource:ty, $update_model:ty, $create_model:ty, $list_model:ty, $response_model:ty, $scoped_list:ty, $scoped_response:ty) => {
+        crudcrate::crud_handlers_impl!(
+            @openapi true;
+            $resource,
+            $update_model,
+            $create_model,
+            $list_model,
+            $response_model,
+            $scoped_list,
+            $scoped_response
+        );
     };
 }
 
@@ -564,18 +865,31 @@ macro_rules! generate_crud_router {
                 max_page_size = <$api_struct as crudcrate::traits::CRUDResource>::max_page_size(),
                 "Mounting CRUD routes with security defaults: input_sanitization=enabled, sql_parameterization=enabled. See https://crudcrate.evanjt.com/latest/advanced/security.html"
             );
+            crudcrate::registry::register::<$api_struct>();
 
             OpenApiRouter::new()
                 .routes(routes!(get_one_handler))
                 .routes(routes!(get_all_handler))
+                .routes(routes!(get_distinct_handler))
+                .routes(routes!(get_by_ids_handler, post_by_ids_handler))
                 .routes(routes!(create_one_handler))
                 .routes(routes!(create_many_handler))
                 .routes(routes!(update_one_handler))
                 .routes(routes!(update_many_handler))
                 .routes(routes!(delete_one_handler))
                 .routes(routes!(delete_many_handler))
+                .routes(routes!(delete_by_filter_handler))
+                .layer(axum::middleware::from_fn(crudcrate::timeout::enforce::<$api_struct>))
                 .with_state(db.clone())
         }
+
+        pub fn router_with_ops<O>(db: &sea_orm::DatabaseConnection, ops: O) -> utoipa_axum::router::OpenApiRouter
+        where
+            $api_struct: crudcrate::traits::CRUDResource,
+            O: crudcrate::CRUDOperations<Resource = $api_struct> + 'static,
+        {
+            router(db).layer(axum::Extension(crudcrate::SharedOperations::<$api_struct>::new(ops)))
+        }
     };
     ($model:ty, $api_struct:ty, $create_model:ty, $update_model:ty, $($extra_routes:expr),* $(,)?) => {
         crudcrate::crud_handlers!($api_struct, $update_model, $create_model);
@@ -593,20 +907,450 @@ macro_rules! generate_crud_router {
                 max_page_size = <$api_struct as crudcrate::traits::CRUDResource>::max_page_size(),
                 "Mounting CRUD routes with security defaults: input_sanitization=enabled, sql_parameterization=enabled. See https://crudcrate.evanjt.com/latest/advanced/security.html"
             );
+            crudcrate::registry::register::<$api_struct>();
 
             OpenApiRouter::new()
                 .routes(routes!(get_one_handler))
                 .routes(routes!(get_all_handler))
+                .routes(routes!(get_distinct_handler))
+                .routes(routes!(get_by_ids_handler, post_by_ids_handler))
                 .routes(routes!(create_one_handler))
                 .routes(routes!(create_many_handler))
                 .routes(routes!(update_one_handler))
                 .routes(routes!(update_many_handler))
                 .routes(routes!(delete_one_handler))
                 .routes(routes!(delete_many_handler))
+                .routes(routes!(delete_by_filter_handler))
                 $(
                     .routes($extra_routes)
                 )*
+                .layer(axum::middleware::from_fn(crudcrate::timeout::enforce::<$api_struct>))
                 .with_state(db.clone())
         }
+
+        pub fn router_with_ops<O>(db: &sea_orm::DatabaseConnection, ops: O) -> utoipa_axum::router::OpenApiRouter
+        where
+            $api_struct: crudcrate::traits::CRUDResource,
+            O: crudcrate::CRUDOperations<Resource = $api_struct> + 'static,
+        {
+            router(db).layer(axum::Extension(crudcrate::SharedOperations::<$api_struct>::new(ops)))
+        }
     };
 }
+
+/// `ORDER BY column direction` in the expression form taken by [`find_page`].
+pub fn column_order<C: ColumnTrait>(column: C, direction: Order) -> (SimpleExpr, Order) {
+    (Expr::col((column.entity_name(), column)).into(), direction)
+}
+
+/// `order` followed by `T`'s ID in the direction of its last term, unless it already ends
+/// with the ID or `T` opts out with `unstable_sort`.
+///
+/// Rows with equal sort values then come back in the same order on every query, so
+/// offset pages neither repeat nor skip them, and continue where keyset pagination
+/// expects.
+pub fn with_tie_breaker<T: CRUDResource>(
+    order: &[(SimpleExpr, Order)],
+) -> Vec<(SimpleExpr, Order)> {
+    let mut order = order.to_vec();
+    if T::STABLE_SORT
+        && let Some((last, direction)) = order.last()
+    {
+        let (id_expr, direction) = column_order(T::ID_COLUMN, direction.clone());
+        if *last != id_expr {
+            order.push((id_expr, direction));
+        }
+    }
+    order
+}
+
+/// Sentence of the list endpoint's `OpenAPI` description on the order of tied rows
+#[must_use]
+pub fn tie_breaker_note<T: CRUDResource>() -> String {
+    if T::STABLE_SORT {
+        format!(
+            "Rows with equal sort values are ordered by `{}` in the same direction, so pages neither repeat nor skip rows.",
+            T::ID_COLUMN.as_str()
+        )
+    } else {
+        "Rows with equal sort values come back in no guaranteed order, so pages sorted on non-unique columns can repeat or skip rows.".to_string()
+    }
+}
+
+/// Fetch one page of `T`'s models, as used by the list endpoints, leaving out rows
+/// outside the [`read_condition`](CRUDResource::read_condition).
+///
+/// `null_columns` are selected as `NULL` instead of their value. The derive passes the
+/// `Option` columns left out of the list model, so heavy values are never transferred.
+///
+/// # Errors
+/// Returns `ApiError::Database` if the query fails.
+pub async fn find_page<T: CRUDResource>(
+    db: &DatabaseConnection,
+    condition: &Condition,
+    order: &[(SimpleExpr, Order)],
+    offset: u64,
+    limit: u64,
+    null_columns: &[T::ColumnType],
+) -> Result<Vec<ModelOf<T>>, ApiError> {
+    let mut query = super::view::select::<T>();
+    if !null_columns.is_empty() {
+        query = query.select_only();
+        for column in <T::EntityType as EntityTrait>::Column::iter() {
+            if null_columns
+                .iter()
+                .any(|null| null.as_str() == column.as_str())
+            {
+                query = query.column_as(Expr::cust("NULL"), column.as_str());
+            } else {
+                query = query.column(column);
+            }
+        }
+    }
+    let mut query = query.filter(condition.clone());
+    if let Some(read) = T::read_condition() {
+        query = query.filter(read);
+    }
+    for (expr, direction) in with_tie_breaker::<T>(order) {
+        query = query.order_by(expr, direction);
+    }
+    query
+        .offset(offset)
+        .limit(limit)
+        .all(db)
+        .await
+        .map_err(ApiError::database)
+}
+
+/// Fetch `T`'s model with the given ID, matching `scope` if given and the
+/// [`read_condition`](CRUDResource::read_condition), as used by the read endpoints.
+///
+/// # Errors
+/// Returns `ApiError::NotFound` if no row matches, or `ApiError::Database` if the query
+/// fails.
+pub async fn find_one<T: CRUDResource>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    scope: Option<&Condition>,
+) -> Result<ModelOf<T>, ApiError> {
+    match (scope, T::read_condition()) {
+        (Some(scope), Some(read)) => find_by_id::<T>(db, id, Some(&scope.clone().add(read))).await,
+        (scope, None) => find_by_id::<T>(db, id, scope).await,
+        (None, Some(read)) => find_by_id::<T>(db, id, Some(&read)).await,
+    }
+}
+
+/// Fetch `T`'s model with the given ID, and matching `scope` if given, whatever the
+/// [`read_condition`](CRUDResource::read_condition). The derived `get_one` reads through
+/// it, so writes can answer with rows the read endpoints hide.
+///
+/// ID and scope go into a single `WHERE id = ? AND <scope>` query, so a row cannot move
+/// out of scope between fetching and checking it.
+///
+/// # Errors
+/// Returns `ApiError::NotFound` if no row matches, or `ApiError::Database` if the query
+/// fails.
+pub async fn find_by_id<T: CRUDResource>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    scope: Option<&Condition>,
+) -> Result<ModelOf<T>, ApiError> {
+    let mut condition = Condition::all().add(T::ID_COLUMN.eq(id));
+    if let Some(scope) = scope {
+        condition = condition.add(scope.clone());
+    }
+    super::view::select::<T>()
+        .filter(condition)
+        .one(db)
+        .await
+        .map_err(ApiError::database)?
+        .ok_or_else(|| ApiError::not_found(T::RESOURCE_NAME_SINGULAR, Some(id.to_string())))
+}
+
+/// `require_scope` resources refuse to serve reads without `ScopeCondition` middleware
+pub(crate) fn check_required_scope<T: CRUDResource>(scoped: bool) -> Result<(), ApiError> {
+    if T::REQUIRE_SCOPE && !scoped {
+        return Err(ApiError::internal(
+            "Scope middleware required for this resource but not configured",
+            Some(
+                "require_scope check failed: ScopeCondition extension not found in request".into(),
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Body of the generated `get_one_handler`.
+///
+/// `R` is the response model and `S` its scoped variant, returned when `scope` is set.
+/// `temporal` resources only answer rows valid at `as_of`, or now, and rows outside the
+/// [`read_condition`](CRUDResource::read_condition) are not found.
+///
+/// # Errors
+/// Returns `ApiError::NotFound` for unknown, out-of-scope or invalid IDs,
+/// `ApiError::BadRequest` for a malformed `as_of` or when the joins are over the
+/// [`join_budget`](CRUDResource::join_budget), and any error from `get_one`.
+pub async fn get_one_response<T, R, S>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    as_of: Option<&str>,
+    scope: Option<Condition>,
+    ops: Option<&SharedOperations<T>>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    R: From<T> + Serialize,
+    S: From<R> + Serialize,
+{
+    check_required_scope::<T>(scope.is_some())?;
+    crate::complexity::check::<T>(1, false)?;
+
+    if let Some(scope) = scope {
+        let scope = match crate::temporal::condition::<T>(as_of)? {
+            Some(valid) => scope.add(valid),
+            None => scope,
+        };
+        let result = crate::timing::measure("db", T::get_one_scoped(db, id, &scope))
+            .await
+            .map_err(|_| ApiError::not_found(T::RESOURCE_NAME_SINGULAR, Some(id.to_string())))?;
+        Ok(item_response(S::from(R::from(result))))
+    } else {
+        let result = crate::timing::measure("db", async {
+            crate::temporal::ensure_valid::<T>(db, id, as_of).await?;
+            if T::read_condition().is_some() {
+                find_one::<T>(db, id, None).await?;
+            }
+            match ops {
+                Some(ops) => ops.operations().get_one(db, id).await,
+                None => T::get_one(db, id).await,
+            }
+        })
+        .await?;
+        Ok(item_response(R::from(result)))
+    }
+}
+
+/// The `GET /{id}` body, timed as `serialize`
+fn item_response<I: Serialize>(item: I) -> Response {
+    let started = std::time::Instant::now();
+    let response = Json(item).into_response();
+    crate::timing::record("serialize", started.elapsed());
+    response
+}
+
+/// Body of the generated `get_all_handler`: parses filters, sorting and pagination from
+/// `params`, fetches the page and sets the `Content-Range` header.
+///
+/// `S` is the scoped list model, returned when `scope` is set. Scoped requests cannot
+/// filter or sort on the resource's `scoped_excluded_columns`.
+///
+/// With `params.facets`, the body is a [`FacetedPage`] holding the page and the value
+/// counts of the requested fields over every matching row.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` for invalid filters, sorting or facets (including
+/// unknown fields on `strict_query` resources) or a page whose joins are over the
+/// [`join_budget`](CRUDResource::join_budget), and any error from `get_all`.
+pub async fn get_all_response<T, S>(
+    params: FilterOptions,
+    db: &DatabaseConnection,
+    scope: Option<Condition>,
+    ops: Option<&SharedOperations<T>>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    T::ListModel: Serialize,
+    S: From<T::ListModel> + Serialize,
+{
+    list_response::<T, S>(params, db, scope, None, ops).await
+}
+
+/// [`get_all_response`] over the rows matching `within`, which narrows the page, the
+/// total and the facets like `scope` without making the request scoped. Used by the
+/// relation endpoints of `lazy` joins.
+///
+/// # Errors
+/// Returns any error from [`get_all_response`].
+#[allow(clippy::too_many_lines)]
+pub(crate) async fn list_response<T, S>(
+    params: FilterOptions,
+    db: &DatabaseConnection,
+    scope: Option<Condition>,
+    within: Option<Condition>,
+    ops: Option<&SharedOperations<T>>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    T::ListModel: Serialize,
+    S: From<T::ListModel> + Serialize,
+{
+    check_required_scope::<T>(scope.is_some())?;
+    let started = std::time::Instant::now();
+
+    // Renamed fields are queried by their external names
+    let params = crate::filtering::translate_field_names::<T>(params);
+    let backend = db.get_database_backend();
+
+    let (offset, limit) = crate::parse_pagination(&params);
+    let limit = limit.min(T::max_page_size());
+    crate::complexity::check::<T>(limit, true)?;
+
+    let is_scoped = scope.is_some();
+
+    // When scoped, strip excluded columns from filterable/sortable lists
+    // to prevent schema probing by unauthenticated users
+    let mut filterable_columns = T::filterable_columns();
+    let mut sortable_columns = T::sortable_columns();
+    if is_scoped {
+        let excluded = T::scoped_excluded_columns();
+        filterable_columns.retain(|(name, _)| !excluded.contains(name));
+        sortable_columns.retain(|(name, _)| !excluded.contains(name));
+    }
+
+    if T::STRICT_QUERY {
+        crate::filtering::check_query_fields::<T>(
+            &params,
+            &filterable_columns,
+            &sortable_columns,
+            backend,
+        )?;
+    }
+
+    // `facets=status,priority` on fulltext resources: value counts next to the page
+    let facets = crate::filtering::facets::requested_facets::<T>(&params, &filterable_columns)?;
+
+    let parsed_filters =
+        crate::apply_filters_with_joins::<T>(params.filter.clone(), &filterable_columns, backend)?;
+
+    let sort_config = crate::parse_sorting_with_joins::<T, _>(
+        &params,
+        &sortable_columns,
+        T::default_index_column(),
+    );
+
+    let mut condition = parsed_filters.main_condition.add_option(
+        crate::filtering::exists::relation_condition::<T>(
+            &parsed_filters.relation_filters,
+            is_scoped,
+        ),
+    );
+    if let Some(extra) = scope {
+        condition = condition.add(extra);
+    }
+    if let Some(within) = within {
+        condition = condition.add(within);
+    }
+    condition = condition.add_option(T::read_condition());
+    if let Some(valid) = crate::temporal::condition::<T>(params.as_of.as_deref())? {
+        condition = condition.add(valid);
+    }
+
+    let (order_column, order_direction) = match sort_config {
+        SortConfig::Column { column, direction } => (column, direction),
+        SortConfig::Joined { direction, .. } => (T::default_index_column(), direction),
+    };
+
+    // Sorting by a geo field orders by distance from its `_within` point
+    let distance_order = crate::filtering::geo::distance_order::<T>(&params, backend)?;
+
+    // Unsorted `q` searches against an FTS5 table are ordered by relevance
+    let relevance_order = crate::filtering::search::relevance_order::<T>(&params, backend);
+
+    // `sort=[["a","DESC"],["b","ASC"]]` or `sort=a_desc,b_asc`
+    let multi_order = crate::parse_multi_sorting(&params, &sortable_columns)?;
+
+    // `after_id`/`after_value` continue after the previous page's last row. The
+    // count below keeps the unpaged condition, so the total still covers every match.
+    let keyset = crate::filtering::keyset_page::<T>(&params, &sortable_columns, backend)?;
+
+    // Orderings that are not a single plain column: (page condition, order, offset)
+    let ordered = if let Some(keyset) = keyset {
+        Some((condition.clone().add(keyset.condition), keyset.order, 0))
+    } else if let Some(order) = distance_order.or(relevance_order) {
+        Some((condition.clone(), vec![order], offset))
+    } else {
+        // Columns declared with `collate` are ordered through their collation
+        let order = |column, direction: Order| {
+            crate::filtering::collation::collated_order::<T>(column, direction.clone(), backend)
+                .unwrap_or_else(|| column_order(column, direction))
+        };
+        match multi_order {
+            Some(columns) => Some((
+                condition.clone(),
+                columns
+                    .into_iter()
+                    .map(|(column, direction)| order(column, direction))
+                    .collect(),
+                offset,
+            )),
+            None => crate::filtering::collation::collated_order::<T>(
+                order_column,
+                order_direction.clone(),
+                backend,
+            )
+            .map(|order| (condition.clone(), vec![order], offset)),
+        }
+    };
+
+    crate::timing::record("parse", started.elapsed());
+
+    // `?__explain=true` (debug feature): return the plan of the query below
+    #[cfg(feature = "debug")]
+    if params.explain == Some(true) {
+        let plan = if let Some((page_condition, order, page_offset)) = &ordered {
+            crate::debug::explain_get_all::<T>(db, page_condition, order, *page_offset, limit)
+                .await?
+        } else {
+            let order = [column_order(order_column, order_direction.clone())];
+            crate::debug::explain_get_all::<T>(db, &condition, &order, offset, limit).await?
+        };
+        return Ok(Json(plan).into_response());
+    }
+
+    let (items, total_count, facets) = crate::timing::measure("db", async {
+        let items = if let Some((page_condition, order, page_offset)) = ordered {
+            T::get_all_ordered(db, &page_condition, &order, page_offset, limit, is_scoped).await?
+        } else if is_scoped {
+            T::get_all_scoped(db, &condition, order_column, order_direction, offset, limit).await?
+        } else if let Some(ops) = ops {
+            ops.operations()
+                .get_all(db, &condition, order_column, order_direction, offset, limit)
+                .await?
+        } else {
+            T::get_all(db, &condition, order_column, order_direction, offset, limit).await?
+        };
+        let total_count = T::total_count(db, &condition).await;
+        let facets = match facets {
+            Some(facets) => {
+                Some(crate::filtering::facets::facet_counts::<T>(db, facets, &condition).await?)
+            }
+            None => None,
+        };
+        Ok::<_, ApiError>((items, total_count, facets))
+    })
+    .await?;
+    let headers: HeaderMap =
+        crate::calculate_content_range(offset, limit, total_count, T::RESOURCE_NAME_PLURAL);
+
+    let started = std::time::Instant::now();
+    let response = if is_scoped {
+        let scoped: Vec<S> = items.into_iter().map(S::from).collect();
+        page_response(headers, scoped, facets)
+    } else {
+        page_response(headers, items, facets)
+    };
+    crate::timing::record("serialize", started.elapsed());
+    Ok(response)
+}
+
+/// The list body: the bare array, or the array with facet counts when requested
+fn page_response<I: Serialize>(
+    headers: HeaderMap,
+    items: Vec<I>,
+    facets: Option<BTreeMap<String, Vec<DistinctValue>>>,
+) -> Response {
+    match facets {
+        Some(facets) => (headers, Json(FacetedPage { items, facets })).into_response(),
+        None => (headers, Json(items)).into_response(),
+    }
+}
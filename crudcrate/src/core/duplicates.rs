@@ -0,0 +1,168 @@
+//! Duplicate detection for resources declared with `#[crudcrate(dedupe_on(...))]`.
+//!
+//! The derive adds `POST /check-duplicates` to the generated router. It takes the same
+//! body as `POST /`, inserts nothing, and answers the existing rows that look like the
+//! payload, so a form can ask "did you mean this existing record?" before creating:
+//!
+//! ```text
+//! POST /customers/check-duplicates
+//! {"name": "ada lovelace", "email": "ADA@example.com"}
+//!
+//! 200 OK
+//! [{"id": "…", "name": "Ada Lovelace", "email": "ada@example.com"}]
+//! ```
+//!
+//! A row is a potential duplicate when any `dedupe_on` field equals the payload's value,
+//! ignoring case and surrounding whitespace. On `PostgreSQL`, `dedupe_on` fields that
+//! are also `fulltext(strategy = "trigram")` fields match by `pg_trgm` similarity above
+//! their threshold as well, which catches typos. Empty and `null` values don't match
+//! anything. At most [`MAX_DUPLICATES`] rows are returned, loaded like list rows, and
+//! rows outside the request's [`ScopeCondition`](crate::ScopeCondition) are never shown.
+
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use sea_orm::sea_query::{Expr, Func, SimpleExpr};
+use sea_orm::{Condition, ConnectionTrait, DatabaseBackend, DatabaseConnection, Order};
+use serde::Serialize;
+
+use crate::core::crud_operations::{check_required_scope, column_order};
+use crate::filtering::search::build_trigram_condition;
+use crate::{ApiError, CRUDResource};
+
+/// Most potential duplicates returned by [`find_duplicates`]
+pub const MAX_DUPLICATES: u64 = 10;
+
+/// A create model field that `dedupe_on` can compare: `String` and `Option<String>`
+pub trait DedupeValue {
+    /// The trimmed value, or `None` when it is missing or empty
+    fn dedupe_value(&self) -> Option<String>;
+}
+
+impl DedupeValue for String {
+    fn dedupe_value(&self) -> Option<String> {
+        let trimmed = self.trim();
+        (!trimmed.is_empty()).then(|| trimmed.to_string())
+    }
+}
+
+impl<T: DedupeValue> DedupeValue for Option<T> {
+    fn dedupe_value(&self) -> Option<String> {
+        self.as_ref().and_then(DedupeValue::dedupe_value)
+    }
+}
+
+/// The condition matching rows that look like `create`, or `None` when none of its
+/// `dedupe_on` fields has a value
+fn duplicate_condition<T: CRUDResource>(
+    create: &T::CreateModel,
+    backend: DatabaseBackend,
+) -> Option<Condition> {
+    let trigram_columns = T::trigram_searchable_columns();
+    let mut condition = Condition::any();
+    let mut matched_any = false;
+    for (name, column, value) in T::dedupe_values(create) {
+        let Some(value) = value else {
+            continue;
+        };
+        let column_upper = SimpleExpr::FunctionCall(Func::upper(Expr::col(column)));
+        condition = condition.add(column_upper.eq(value.to_uppercase()));
+        if backend == DatabaseBackend::Postgres
+            && let Some((_, _, threshold)) = trigram_columns.iter().find(|(t, _, _)| *t == name)
+            && let Some(similar) = build_trigram_condition(&value, name, *threshold)
+        {
+            condition = condition.add(similar);
+        }
+        matched_any = true;
+    }
+    matched_any.then_some(condition)
+}
+
+/// Existing rows that look like `create`, as list models in index order.
+///
+/// # Errors
+/// Returns `ApiError::Database` if the query fails.
+pub async fn find_duplicates<T: CRUDResource>(
+    db: &DatabaseConnection,
+    create: &T::CreateModel,
+    scope: Option<&Condition>,
+) -> Result<Vec<T::ListModel>, ApiError> {
+    let Some(mut condition) = duplicate_condition::<T>(create, db.get_database_backend()) else {
+        return Ok(Vec::new());
+    };
+    if let Some(scope) = scope {
+        condition = Condition::all().add(condition).add(scope.clone());
+    }
+    let order = [column_order(T::default_index_column(), Order::Asc)];
+    T::get_all_ordered(db, &condition, &order, 0, MAX_DUPLICATES, scope.is_some()).await
+}
+
+/// Body of the generated `check_duplicates_handler`. `S` is the scoped list model,
+/// returned when `scope` is set.
+///
+/// # Errors
+/// Returns any error from [`find_duplicates`].
+pub async fn duplicates_response<T, S>(
+    db: &DatabaseConnection,
+    create: &T::CreateModel,
+    scope: Option<Condition>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    T::ListModel: Serialize,
+    S: From<T::ListModel> + Serialize,
+{
+    check_required_scope::<T>(scope.is_some())?;
+    let rows = find_duplicates::<T>(db, create, scope.as_ref()).await?;
+    if scope.is_some() {
+        let rows: Vec<S> = rows.into_iter().map(S::from).collect();
+        Ok(Json(rows).into_response())
+    } else {
+        Ok(Json(rows).into_response())
+    }
+}
+
+/// Handler for `POST /check-duplicates`, invoked by the derive next to `crud_handlers!`
+/// for resources with `dedupe_on(...)`. `@no_openapi` leaves out `#[utoipa::path]`.
+#[macro_export]
+macro_rules! duplicates_handlers {
+    (@no_openapi $resource:ty, $create_model:ty, $list_model:ty, $scoped_list:ty) => {
+        crudcrate::duplicates_handlers!(@openapi false; $resource, $create_model, $list_model, $scoped_list);
+    };
+    ($resource:ty, $create_model:ty, $list_model:ty, $scoped_list:ty) => {
+        crudcrate::duplicates_handlers!(@openapi true; $resource, $create_model, $list_model, $scoped_list);
+    };
+    (@openapi $openapi:tt; $resource:ty, $create_model:ty, $list_model:ty, $scoped_list:ty) => {
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            post,
+            path = "/check-duplicates",
+            request_body = $create_model,
+            responses(
+                (status = axum::http::StatusCode::OK, description = "Existing resources that look like the payload", body = [$list_model]),
+                (status = axum::http::StatusCode::UNPROCESSABLE_ENTITY, description = "Invalid payload"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            operation_id = format!("check_duplicates_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Find {} that look like a new one", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            description = format!(
+                "Takes a {} create payload without inserting it, and returns up to {} existing {} with a matching {} (case-insensitive).",
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR,
+                crudcrate::core::duplicates::MAX_DUPLICATES,
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL,
+                <$resource as crudcrate::CRUDResource>::dedupe_fields().join(" or ")
+            )
+        )]
+        pub async fn check_duplicates_handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            axum::Json(create): axum::Json<$create_model>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            crudcrate::core::duplicates::duplicates_response::<$resource, $scoped_list>(
+                &db,
+                &create,
+                scope.map(|axum::Extension(scope)| scope.condition),
+            )
+            .await
+        });
+    };
+}
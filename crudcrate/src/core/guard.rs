@@ -0,0 +1,65 @@
+//! Delete protection for resources declared with `#[crudcrate(guard_delete = "locked")]`.
+//!
+//! The named boolean column marks protected rows. Before deleting, the generated
+//! `delete` and `delete_many` look for rows among the ones being deleted that have it set
+//! and answer `423 Locked` naming them, so the whole request is refused:
+//!
+//! ```rust,ignore
+//! #[crudcrate(generate_router, guard_delete = "locked")]
+//! pub struct Model {
+//!     pub locked: bool,
+//! }
+//! ```
+//!
+//! Clearing the flag with an update makes the row deletable again. The `DELETE`
+//! statement itself only matches unlocked rows, so a row locked by a concurrent update
+//! after the check is kept and the delete answers `423` as well.
+
+use sea_orm::{ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect};
+use uuid::Uuid;
+
+use crate::core::traits::{CRUDResource, UuidIdResult};
+use crate::errors::ApiError;
+
+/// The rows whose boolean `column` isn't set, as [`CRUDResource::delete_condition`] of
+/// `#[crudcrate(guard_delete = "...")]` resources. `NULL` counts as unlocked, as in
+/// [`ensure_unlocked`].
+#[must_use]
+pub fn unlocked(column: impl ColumnTrait) -> Condition {
+    Condition::any().add(column.ne(true)).add(column.is_null())
+}
+
+/// Refuse to delete the `T` rows with the given IDs while any of them has the boolean
+/// `column` set, as generated for `#[crudcrate(guard_delete = "...")]`.
+///
+/// Rows that don't exist are left to the delete itself. `db` may be a transaction.
+///
+/// # Errors
+/// Returns [`ApiError::locked`] naming the protected rows, or `ApiError::Database` if
+/// the query fails.
+pub async fn ensure_unlocked<T: CRUDResource>(
+    db: &impl ConnectionTrait,
+    ids: &[Uuid],
+    column: T::ColumnType,
+) -> Result<(), ApiError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let locked: Vec<Uuid> = T::EntityType::find()
+        .select_only()
+        .column_as(T::ID_COLUMN, "id")
+        .filter(T::ID_COLUMN.is_in(ids.iter().copied()))
+        .filter(column.eq(true))
+        .into_model::<UuidIdResult>()
+        .all(db)
+        .await
+        .map_err(ApiError::database)?
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+    if locked.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::locked(T::RESOURCE_NAME_SINGULAR, &locked))
+    }
+}
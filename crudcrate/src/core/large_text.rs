@@ -0,0 +1,150 @@
+//! Text columns marked `#[crudcrate(large_text)]`.
+//!
+//! Long content (article bodies, descriptions, notes) makes every list row heavy. A
+//! `large_text` field is left out of the list model, stays in the single-item response,
+//! and gets its own read endpoint on the generated routers:
+//!
+//! ```text
+//! GET /posts/{id}/body
+//! 200 OK
+//! Content-Type: text/plain; charset=utf-8
+//!
+//! # Release notes
+//! ...
+//! ```
+//!
+//! Only that column is selected. A `NULL` value answers `204 No Content`; unknown IDs
+//! and IDs outside the request's [`ScopeCondition`](crate::ScopeCondition) answer `404`.
+
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, QueryFilter, QuerySelect};
+use uuid::Uuid;
+
+use crate::core::crud_operations::check_required_scope;
+use crate::{ApiError, CRUDResource};
+
+/// The `large_text` column clients call `field`, by its external or field name.
+/// Scoped requests can't read the resource's `scoped_excluded_columns`.
+fn large_text_column<T: CRUDResource>(
+    field: &str,
+    scoped: bool,
+) -> Result<T::ColumnType, ApiError> {
+    let name = T::field_aliases()
+        .into_iter()
+        .find(|(external, _)| *external == field)
+        .map_or(field, |(_, aliased)| aliased);
+    if scoped && T::scoped_excluded_columns().contains(&name) {
+        return Err(ApiError::bad_request(format!(
+            "Field '{field}' is not a large text field"
+        )));
+    }
+    T::large_text_columns()
+        .into_iter()
+        .find(|(large_text, _)| *large_text == name)
+        .map(|(_, column)| column)
+        .ok_or_else(|| ApiError::bad_request(format!("Field '{field}' is not a large text field")))
+}
+
+/// The value of one `large_text` field, `None` when the column is `NULL`.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `field` is not a large text field,
+/// `ApiError::NotFound` for unknown or out-of-scope IDs, and `ApiError::Database` if the
+/// query fails.
+pub async fn large_text<T: CRUDResource>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    field: &str,
+    scope: Option<&Condition>,
+) -> Result<Option<String>, ApiError> {
+    let column = large_text_column::<T>(field, scope.is_some())?;
+    let mut condition = Condition::all()
+        .add(T::ID_COLUMN.eq(id))
+        .add_option(T::read_condition());
+    if let Some(scope) = scope {
+        condition = condition.add(scope.clone());
+    }
+    crate::core::view::select::<T>()
+        .select_only()
+        .column(column)
+        .filter(condition)
+        .into_tuple::<Option<String>>()
+        .one(db)
+        .await
+        .map_err(ApiError::database)?
+        .ok_or_else(|| ApiError::not_found(T::RESOURCE_NAME_SINGULAR, Some(id.to_string())))
+}
+
+/// Body of the generated `get_large_text_handler`: the field's text as
+/// `text/plain`, or `204 No Content` when it is `NULL`.
+///
+/// # Errors
+/// Returns any error from [`large_text`].
+pub async fn large_text_response<T: CRUDResource>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    field: &str,
+    scope: Option<Condition>,
+) -> Result<Response, ApiError> {
+    check_required_scope::<T>(scope.is_some())?;
+    let text = large_text::<T>(db, id, field, scope.as_ref()).await?;
+    Ok(text.map_or_else(
+        || StatusCode::NO_CONTENT.into_response(),
+        |text| ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], text).into_response(),
+    ))
+}
+
+/// Handler for `GET /{id}/{field}`, invoked by the derive next to `crud_handlers!` for
+/// resources with `large_text` fields. `@no_openapi` leaves out `#[utoipa::path]`.
+#[macro_export]
+macro_rules! large_text_handlers {
+    (@no_openapi $resource:ty) => {
+        crudcrate::large_text_handlers!(@openapi false; $resource);
+    };
+    ($resource:ty) => {
+        crudcrate::large_text_handlers!(@openapi true; $resource);
+    };
+    (@openapi $openapi:tt; $resource:ty) => {
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            get,
+            path = "/{id}/{field}",
+            responses(
+                (status = axum::http::StatusCode::OK, description = "The field's text", body = String, content_type = "text/plain"),
+                (status = axum::http::StatusCode::NO_CONTENT, description = "The field is null"),
+                (status = axum::http::StatusCode::NOT_FOUND, description = "Resource not found"),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Field is not a large text field"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            params(
+                ("id" = uuid::Uuid, Path, description = "ID of the resource"),
+                ("field" = String, Path, description = "Name of a large text field")
+            ),
+            operation_id = format!("get_large_text_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Get a large text field of one {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!(
+                "Retrieves one large text field of one {} as plain text. These fields are left out of list responses.\n\nLarge text fields: {}",
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR,
+                <$resource as crudcrate::CRUDResource>::large_text_columns()
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        )]
+        pub async fn get_large_text_handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            axum::extract::Path((id, field)): axum::extract::Path<(String, String)>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            crudcrate::core::large_text::large_text_response::<$resource>(
+                &db,
+                crudcrate::core::path_id::parse_id::<$resource>(&id)?,
+                &field,
+                scope.map(|axum::Extension(scope)| scope.condition),
+            )
+            .await
+        });
+    };
+}
@@ -0,0 +1,165 @@
+//! Alternate keys for fields declared with `#[crudcrate(lookup)]`.
+//!
+//! A unique text column such as an email or an order number can identify a row as well
+//! as its ID. Each `lookup` field can be used in place of the ID on the generated
+//! routers, without a filter round-trip:
+//!
+//! ```rust,ignore
+//! #[crudcrate(generate_router)]
+//! pub struct Model {
+//!     #[sea_orm(unique)]
+//!     #[crudcrate(lookup)]
+//!     pub email: String,
+//! }
+//! ```
+//!
+//! ```text
+//! GET /customers/by/email/ada@example.com
+//! ```
+//!
+//! The value is matched exactly and answered as `GET /{id}` answers it. Unknown values,
+//! and rows outside the request's [`ScopeCondition`](crate::ScopeCondition) or the
+//! resource's read condition, answer `404`. Versions of a
+//! [`temporal`](crate::temporal) resource can share a value, so only the version valid
+//! now is matched. The macro requires `#[sea_orm(unique)]` on the column, or
+//! `#[crudcrate(lookup_not_unique)]` where uniqueness is enforced elsewhere: the first of
+//! several matching rows is returned. `sensitive`, `hash_with` and `exclude(response)`
+//! fields can't be `lookup`.
+
+use axum::response::Response;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, QueryFilter, QuerySelect};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::core::crud_operations::check_required_scope;
+use crate::core::traits::{CRUDResource, UuidIdResult};
+use crate::errors::ApiError;
+use crate::operations::SharedOperations;
+
+/// The `lookup` column clients call `field`, by its external or field name.
+/// Scoped requests can't look rows up by the resource's `scoped_excluded_columns`.
+fn lookup_column<T: CRUDResource>(field: &str, scoped: bool) -> Result<T::ColumnType, ApiError> {
+    let name = T::field_aliases()
+        .into_iter()
+        .find(|(external, _)| *external == field)
+        .map_or(field, |(_, aliased)| aliased);
+    if scoped && T::scoped_excluded_columns().contains(&name) {
+        return Err(ApiError::bad_request(format!(
+            "Field '{field}' is not a lookup field"
+        )));
+    }
+    T::lookup_columns()
+        .into_iter()
+        .find(|(lookup, _)| *lookup == name)
+        .map(|(_, column)| column)
+        .ok_or_else(|| ApiError::bad_request(format!("Field '{field}' is not a lookup field")))
+}
+
+/// The ID of the `T` row whose `field` is `value`, among the rows the request can read.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `field` is not a lookup field, `ApiError::NotFound`
+/// for unknown or out-of-scope values and `ApiError::Database` if the query fails.
+pub async fn find_by_lookup<T: CRUDResource>(
+    db: &DatabaseConnection,
+    field: &str,
+    value: &str,
+    scope: Option<&Condition>,
+) -> Result<Uuid, ApiError> {
+    let column = lookup_column::<T>(field, scope.is_some())?;
+    let mut condition = Condition::all()
+        .add(column.eq(value))
+        .add_option(crate::temporal::condition::<T>(None)?)
+        .add_option(T::read_condition());
+    if let Some(scope) = scope {
+        condition = condition.add(scope.clone());
+    }
+    crate::core::view::select::<T>()
+        .select_only()
+        .column_as(T::ID_COLUMN, "id")
+        .filter(condition)
+        .into_model::<UuidIdResult>()
+        .one(db)
+        .await
+        .map_err(ApiError::database)?
+        .map(|row| row.id)
+        .ok_or_else(|| ApiError::not_found(T::RESOURCE_NAME_SINGULAR, Some(value.to_string())))
+}
+
+/// Body of the generated `get_by_lookup_handler`: the row whose `field` is `value`,
+/// answered as `GET /{id}` answers it.
+///
+/// # Errors
+/// Returns any error from [`find_by_lookup`] or
+/// [`get_one_response`](crate::core::crud_operations::get_one_response).
+pub async fn lookup_response<T, R, S>(
+    db: &DatabaseConnection,
+    field: &str,
+    value: &str,
+    scope: Option<Condition>,
+    ops: Option<&SharedOperations<T>>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    R: From<T> + Serialize,
+    S: From<R> + Serialize,
+{
+    check_required_scope::<T>(scope.is_some())?;
+    let id = find_by_lookup::<T>(db, field, value, scope.as_ref()).await?;
+    crate::core::crud_operations::get_one_response::<T, R, S>(db, id, None, scope, ops).await
+}
+
+/// Handler for `GET /by/{field}/{value}`, invoked by the derive next to `crud_handlers!`
+/// for resources with `lookup` fields. `@no_openapi` leaves out `#[utoipa::path]`.
+#[macro_export]
+macro_rules! lookup_handlers {
+    (@no_openapi $resource:ty, $response_model:ty, $scoped_response:ty) => {
+        crudcrate::lookup_handlers!(@openapi false; $resource, $response_model, $scoped_response);
+    };
+    ($resource:ty, $response_model:ty, $scoped_response:ty) => {
+        crudcrate::lookup_handlers!(@openapi true; $resource, $response_model, $scoped_response);
+    };
+    (@openapi $openapi:tt; $resource:ty, $response_model:ty, $scoped_response:ty) => {
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            get,
+            path = "/by/{field}/{value}",
+            responses(
+                (status = axum::http::StatusCode::OK, description = "The resource with the value", body = $response_model),
+                (status = axum::http::StatusCode::NOT_FOUND, description = "Resource not found"),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Field is not a lookup field"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            params(
+                ("field" = String, Path, description = "Name of a lookup field"),
+                ("value" = String, Path, description = "Value of the field")
+            ),
+            operation_id = format!("get_by_lookup_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Get one {} by an alternate key", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!(
+                "Retrieves one {} by the value of a lookup field, as `GET /{{id}}` does by its ID.\n\nLookup fields: {}",
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR,
+                <$resource as crudcrate::CRUDResource>::lookup_columns()
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        )]
+        pub async fn get_by_lookup_handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            axum::extract::Path((field, value)): axum::extract::Path<(String, String)>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            crudcrate::core::lookup::lookup_response::<$resource, $response_model, $scoped_response>(
+                &db,
+                &field,
+                &value,
+                scope.map(|axum::Extension(scope)| scope.condition),
+                ops.as_ref().map(|axum::Extension(ops)| ops),
+            )
+            .await
+        });
+    };
+}
@@ -6,6 +6,7 @@
 //!
 //! - **[`CRUDResource`](@/traits/trait.CRUDResource.html)**: Central trait that defines CRUD operations, filtering, and sorting capabilities
 //! - **[`MergeIntoActiveModel`](@/traits/trait.MergeIntoActiveModel.html)**: Helper trait for merging API structs into `ActiveModel`
+//! - **[`ChangeSet`]**: Columns modified by an update, passed to post-update hooks
 //!
 //! ## Generated Operations
 //!
@@ -14,12 +15,23 @@
 //! ### HTTP Endpoints
 //! - `GET /resource` - List all items with filtering and pagination
 //! - `GET /resource/{id}` - Get specific item by ID
+//! - `GET /resource/by-ids?ids=a,b` and `POST /resource/by-ids` - Several items by ID, in the requested order (see [`by_ids`])
 //! - `POST /resource` - Create new item
 //! - `POST /resource/batch` - Batch create items
 //! - `PUT /resource/{id}` - Update existing item
-//! - `PATCH /resource/batch` - Batch update items
+//! - `PATCH /resource/batch` - Batch update items, with `?per_item=true` a result per item (see [`batch_update`])
 //! - `DELETE /resource/{id}` - Delete specific item
 //! - `DELETE /resource/batch` - Bulk delete by IDs
+//! - `GET /resource/{id}/{field}` - One `large_text` field as plain text (see [`large_text`])
+//! - `GET /resource/slug/{slug}` - Item by its `slug_from` field (see [`slug`])
+//! - `GET /resource/by/{field}/{value}` - Item by a `lookup` field (see [`lookup`])
+//! - `POST /resource/check-duplicates` - Existing rows matching a create payload's `dedupe_on` fields (see [`duplicates`])
+//!
+//! Creates and updates of `unique_together` resources answer 409 when another row has
+//! the same values (see [`unique`]).
+//!
+//! Deletes of `guard_delete` resources answer 423 while a row has its lock flag set
+//! (see [`guard`]).
 //!
 //! ### Generated Structs
 //! - **API Struct** (e.g., `Todo`): For HTTP responses
@@ -44,8 +56,22 @@
 //! let deleted = Todo::delete(&db, id).await?;
 //! ```
 
+pub mod batch_update;
+pub mod by_ids;
+pub mod changes;
 pub mod crud_operations;
+pub mod duplicates;
+pub mod guard;
+pub mod large_text;
+pub mod lookup;
+pub mod path_id;
+pub mod slug;
 pub mod traits;
+pub mod unique;
+pub mod view;
 
 // Re-export commonly used items
+pub use by_ids::ByIds;
+pub use changes::{ChangeSet, FieldChange};
+pub use path_id::PathId;
 pub use traits::{CRUDResource, MergeIntoActiveModel, UuidIdResult};
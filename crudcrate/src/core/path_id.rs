@@ -0,0 +1,69 @@
+//! The `{id}` path segment of the generated item routes.
+//!
+//! The handlers take [`PathId<T>`] instead of axum's `Path<Uuid>`, whose rejection is a
+//! plain-text 400 that says nothing about the resource. An ID that isn't a UUID is
+//! answered before any query with the usual JSON error body:
+//!
+//! ```text
+//! GET /todos/42
+//!
+//! 400 Bad Request
+//! {"error": "Invalid todo id '42': expected a UUID such as 550e8400-e29b-41d4-a716-446655440000"}
+//! ```
+//!
+//! [`parse_id`] holds the parsing and message, so handlers for other frameworks answer
+//! the same way.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use axum::extract::{FromRequestParts, Path};
+use axum::http::request::Parts;
+use uuid::Uuid;
+
+use crate::{ApiError, CRUDResource};
+
+/// Example shown in the error for an invalid ID
+const EXAMPLE_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+
+/// Parse a `T` ID from a path segment
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` naming the resource and the expected format if `raw`
+/// isn't a UUID.
+pub fn parse_id<T: CRUDResource>(raw: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(raw).map_err(|_| {
+        ApiError::bad_request(format!(
+            "Invalid {} id '{raw}': expected a UUID such as {EXAMPLE_ID}",
+            T::RESOURCE_NAME_SINGULAR
+        ))
+    })
+}
+
+/// Extracts the `{id}` path parameter as a `T` ID, rejecting with [`parse_id`]'s error
+pub struct PathId<T> {
+    /// The parsed ID
+    pub id: Uuid,
+    resource: PhantomData<fn() -> T>,
+}
+
+impl<T, S> FromRequestParts<S> for PathId<T>
+where
+    T: CRUDResource,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(params) = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| ApiError::bad_request(rejection.body_text()))?;
+        let raw = params
+            .get("id")
+            .ok_or_else(|| ApiError::bad_request("Missing id path parameter"))?;
+        Ok(Self {
+            id: parse_id::<T>(raw)?,
+            resource: PhantomData,
+        })
+    }
+}
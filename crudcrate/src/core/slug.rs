@@ -0,0 +1,301 @@
+//! URL slugs for fields declared with `#[crudcrate(slug_from = "title")]`.
+//!
+//! The field holds a slug of the named source field, filled in on create when the
+//! client leaves it out or blank. It is made unique among the resource's rows by
+//! suffixing `-2`, `-3`, ...:
+//!
+//! ```rust,ignore
+//! #[crudcrate(generate_router)]
+//! pub struct Model {
+//!     pub title: String,
+//!     #[sea_orm(unique)]
+//!     #[crudcrate(slug_from = "title", exclude(create, update))]
+//!     pub slug: String,
+//! }
+//! ```
+//!
+//! ```text
+//! POST /posts {"title": "Hello, World!"}    # slug "hello-world"
+//! POST /posts {"title": "Hello world"}      # slug "hello-world-2"
+//! GET /posts/slug/hello-world-2
+//! ```
+//!
+//! A slug the client sends is normalised and suffixed the same way. Updates keep the
+//! slug unless they send one, or, with `slug_on_update`, change the source field.
+//!
+//! Two writes checked at the same time can pick the same slug; a unique index on the
+//! column makes the second one fail instead.
+
+use std::collections::HashSet;
+
+use axum::response::Response;
+use sea_orm::sea_query::ValueType;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection,
+    EntityTrait, QueryFilter, QuerySelect, Value,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::core::crud_operations::check_required_scope;
+use crate::core::traits::{CRUDResource, UuidIdResult};
+use crate::errors::ApiError;
+use crate::operations::SharedOperations;
+
+/// Longest slug [`slugify`] returns, before any uniqueness suffix
+pub const MAX_SLUG_LENGTH: usize = 80;
+
+/// `text` as a URL slug: lowercase ASCII letters and digits, with every other run of
+/// characters replaced by one `-`, so "Hello, World!" becomes `hello-world`. Empty when
+/// `text` has no letters or digits.
+///
+/// With the `unicode` feature, accents are dropped first, so "Crème Brûlée" becomes
+/// `creme-brulee` rather than `cr-me-br-l-e`.
+#[must_use]
+pub fn slugify(text: &str) -> String {
+    #[cfg(feature = "unicode")]
+    let text = crate::normalize::fold(text);
+
+    let mut slug = String::new();
+    for word in text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        if slug.len() + word.len() + 1 > MAX_SLUG_LENGTH && !slug.is_empty() {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug.truncate(MAX_SLUG_LENGTH);
+    slug
+}
+
+/// `base`, or `base-2`, `base-3`, ... for the first one no other `T` row has in `column`.
+/// `id` is the row being written, whose own slug doesn't count.
+///
+/// # Errors
+/// Returns `ApiError::Database` if the lookup fails.
+pub async fn unique_slug<T: CRUDResource>(
+    db: &impl ConnectionTrait,
+    column: T::ColumnType,
+    base: &str,
+    id: Option<Value>,
+) -> Result<String, ApiError> {
+    let mut condition = Condition::all().add(
+        Condition::any()
+            .add(column.eq(base))
+            .add(column.like(format!("{base}-%"))),
+    );
+    if let Some(id) = id {
+        condition = condition.add(T::ID_COLUMN.ne(id));
+    }
+    let taken: HashSet<String> = T::EntityType::find()
+        .select_only()
+        .column(column)
+        .filter(condition)
+        .into_tuple::<String>()
+        .all(db)
+        .await
+        .map_err(ApiError::database)?
+        .into_iter()
+        .collect();
+    // One of `taken.len() + 1` candidates is always free
+    Ok((1..=taken.len() + 1)
+        .map(|n| {
+            if n == 1 {
+                base.to_string()
+            } else {
+                format!("{base}-{n}")
+            }
+        })
+        .find(|slug| !taken.contains(slug))
+        .unwrap_or_default())
+}
+
+/// The text an active model column holds, `None` when unset, `NULL` or not a string
+fn text(value: ActiveValue<Value>) -> Option<String> {
+    value
+        .into_value()
+        .and_then(|value| <String as ValueType>::try_from(value).ok())
+}
+
+/// Fill in the `slug` column of a `T` row about to be written, as generated for
+/// `#[crudcrate(slug_from = "...")]` fields.
+///
+/// A create (`existing` is `None`) gets a slug of its `source` field unless it sends
+/// one. An update, whose `existing` row fills in the fields it didn't send, gets a new
+/// slug when it sends one or a blank one, and with `regenerate` when it changes
+/// `source`. Sent slugs are normalised with [`slugify`]. Every new slug is made unique
+/// with [`unique_slug`]; a source without letters or digits slugs to the resource's
+/// singular name.
+///
+/// # Errors
+/// Returns `ApiError::Database` if the uniqueness lookup fails.
+pub async fn assign_slug<T, C>(
+    db: &C,
+    model: &mut T::ActiveModelType,
+    existing: Option<&T::ActiveModelType>,
+    slug: T::ColumnType,
+    source: T::ColumnType,
+    regenerate: bool,
+) -> Result<(), ApiError>
+where
+    T: CRUDResource,
+    T::ColumnType: Into<<T::EntityType as EntityTrait>::Column>,
+    C: ConnectionTrait,
+{
+    let sent = model.get(slug.into());
+    let source_text = || {
+        text(model.get(source.into()))
+            .or_else(|| existing.and_then(|existing| text(existing.get(source.into()))))
+            .unwrap_or_default()
+    };
+    let base = match (text(sent.clone()).map(|sent| slugify(&sent)), existing) {
+        (Some(sent), _) if !sent.is_empty() => sent,
+        (_, None) => slugify(&source_text()),
+        (_, Some(_)) if sent.is_set() => slugify(&source_text()),
+        (_, Some(existing))
+            if regenerate
+                && model.get(source.into()).is_set()
+                && text(model.get(source.into())) != text(existing.get(source.into())) =>
+        {
+            slugify(&source_text())
+        }
+        _ => return Ok(()),
+    };
+    let base = if base.is_empty() {
+        slugify(T::RESOURCE_NAME_SINGULAR)
+    } else {
+        base
+    };
+    let id = existing
+        .map_or_else(
+            || model.get(T::ID_COLUMN.into()),
+            |existing| existing.get(T::ID_COLUMN.into()),
+        )
+        .into_value();
+    let value = unique_slug::<T>(db, slug, &base, id).await?;
+    model.set(slug.into(), value.into());
+    Ok(())
+}
+
+/// The ID of the `T` row whose slug is `slug`, among the rows the request can read.
+///
+/// # Errors
+/// Returns `ApiError::NotFound` for unknown or out-of-scope slugs, `ApiError::BadRequest`
+/// if `T` has no slug field and `ApiError::Database` if the query fails.
+pub async fn find_by_slug<T: CRUDResource>(
+    db: &DatabaseConnection,
+    slug: &str,
+    scope: Option<&Condition>,
+) -> Result<Uuid, ApiError> {
+    let column = T::slug_column().ok_or_else(|| {
+        ApiError::bad_request(format!("{} has no slug field", T::RESOURCE_NAME_PLURAL))
+    })?;
+    let mut condition = Condition::all()
+        .add(column.eq(slug))
+        .add_option(crate::temporal::condition::<T>(None)?)
+        .add_option(T::read_condition());
+    if let Some(scope) = scope {
+        condition = condition.add(scope.clone());
+    }
+    crate::core::view::select::<T>()
+        .select_only()
+        .column_as(T::ID_COLUMN, "id")
+        .filter(condition)
+        .into_model::<UuidIdResult>()
+        .one(db)
+        .await
+        .map_err(ApiError::database)?
+        .map(|row| row.id)
+        .ok_or_else(|| ApiError::not_found(T::RESOURCE_NAME_SINGULAR, Some(slug.to_string())))
+}
+
+/// Body of the generated `get_by_slug_handler`: the row with the slug, answered as
+/// `GET /{id}` answers it.
+///
+/// # Errors
+/// Returns any error from [`find_by_slug`] or
+/// [`get_one_response`](crate::core::crud_operations::get_one_response).
+pub async fn slug_response<T, R, S>(
+    db: &DatabaseConnection,
+    slug: &str,
+    scope: Option<Condition>,
+    ops: Option<&SharedOperations<T>>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    R: From<T> + Serialize,
+    S: From<R> + Serialize,
+{
+    check_required_scope::<T>(scope.is_some())?;
+    let id = find_by_slug::<T>(db, slug, scope.as_ref()).await?;
+    crate::core::crud_operations::get_one_response::<T, R, S>(db, id, None, scope, ops).await
+}
+
+/// Handler for `GET /slug/{slug}`, invoked by the derive next to `crud_handlers!` for
+/// resources with a `slug_from` field. `@no_openapi` leaves out `#[utoipa::path]`.
+#[macro_export]
+macro_rules! slug_handlers {
+    (@no_openapi $resource:ty, $response_model:ty, $scoped_response:ty) => {
+        crudcrate::slug_handlers!(@openapi false; $resource, $response_model, $scoped_response);
+    };
+    ($resource:ty, $response_model:ty, $scoped_response:ty) => {
+        crudcrate::slug_handlers!(@openapi true; $resource, $response_model, $scoped_response);
+    };
+    (@openapi $openapi:tt; $resource:ty, $response_model:ty, $scoped_response:ty) => {
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            get,
+            path = "/slug/{slug}",
+            responses(
+                (status = axum::http::StatusCode::OK, description = "The resource with the slug", body = $response_model),
+                (status = axum::http::StatusCode::NOT_FOUND, description = "Resource not found"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            params(("slug" = String, Path, description = "Slug of the resource")),
+            operation_id = format!("get_by_slug_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Get one {} by its slug", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!("Retrieves one {} by its slug, as `GET /{{id}}` does by its ID.", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR)
+        )]
+        pub async fn get_by_slug_handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            axum::extract::Path(slug): axum::extract::Path<String>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            crudcrate::core::slug::slug_response::<$resource, $response_model, $scoped_response>(
+                &db,
+                &slug,
+                scope.map(|axum::Extension(scope)| scope.condition),
+                ops.as_ref().map(|axum::Extension(ops)| ops),
+            )
+            .await
+        });
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Rust 2024 -- edition  "), "rust-2024-edition");
+        assert_eq!(slugify("¿?!"), "");
+        let long = "word ".repeat(40);
+        let slug = slugify(&long);
+        assert!(slug.len() <= MAX_SLUG_LENGTH);
+        assert!(!slug.ends_with('-'));
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_slugify_folds_accents() {
+        assert_eq!(slugify("Crème Brûlée"), "creme-brulee");
+    }
+}
@@ -1,10 +1,11 @@
 use async_trait::async_trait;
 use sea_orm::{
-    Condition, DatabaseConnection, EntityTrait, IntoActiveModel, Order, PaginatorTrait, QueryOrder,
-    QuerySelect, entity::prelude::*,
+    Condition, ConnectionTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Order,
+    PaginatorTrait, QuerySelect, entity::prelude::*,
 };
 use uuid::Uuid;
 
+use super::crud_operations::{column_order, find_by_id, find_one, find_page};
 use crate::ApiError;
 
 /// Helper for extracting UUID PKs in batch queries.
@@ -49,10 +50,72 @@ where
     const RESOURCE_DESCRIPTION: &'static str = "";
     const FULLTEXT_LANGUAGE: &'static str = "english";
 
+    /// Stored `tsvector` generated column used for fulltext search on `PostgreSQL`.
+    /// When set, `?q=` queries use `column @@ plainto_tsquery(FULLTEXT_LANGUAGE, q)` instead
+    /// of ILIKE. Set via `#[crudcrate(tsvector_column = "search_vector")]`; create the column
+    /// with [`tsvector_column_sql`](crate::database::tsvector_column_sql).
+    const TSVECTOR_COLUMN: Option<&'static str> = None;
+
+    /// `SQLite` FTS5 table used for fulltext search.
+    /// When set, `?q=` queries on `SQLite` use `MATCH` against this table instead of LIKE, and
+    /// unsorted results are ordered by `bm25` relevance. Set via
+    /// `#[crudcrate(fts5_table = "articles_fts")]`; create the table with
+    /// [`ensure_fts5`](crate::database::ensure_fts5).
+    const FTS5_TABLE: Option<&'static str> = None;
+
+    /// Column holding the accent- and case-folded text of the `fulltext` fields.
+    /// When set, `?q=` folds the query and matches it against this column with LIKE, so
+    /// "Muller" finds "Müller". Set via `#[crudcrate(normalized_search = "search_text")]`
+    /// with the `unicode` feature; see `crudcrate::normalize`.
+    const NORMALIZED_SEARCH_COLUMN: Option<&'static str> = None;
+
+    /// SQL query the resource reads from instead of its table, as a subquery aliased to
+    /// `TABLE_NAME`. Set via `#[crudcrate(view_sql = "SELECT ...")]`; see
+    /// [`view`](crate::core::view).
+    const VIEW_SQL: Option<&'static str> = None;
+
     /// When true, read handlers return HTTP 500 if no `ScopeCondition` middleware is present.
     /// Set via `#[crudcrate(require_scope)]` on the struct.
     const REQUIRE_SCOPE: bool = false;
 
+    /// When true, [`ensure_indexes`](crate::database::ensure_indexes) creates missing indexes
+    /// for filterable, sortable, and fulltext columns. Set via `#[crudcrate(auto_index)]`.
+    const AUTO_INDEX: bool = false;
+
+    /// When true, enum filters match variants exactly instead of case-insensitively.
+    /// Set via `#[crudcrate(enum_case_sensitive)]`.
+    const ENUM_CASE_SENSITIVE: bool = false;
+
+    /// When true, the list handler returns 400 for filter keys and sort columns that
+    /// don't match a filterable or sortable field, instead of ignoring them.
+    /// Set via `#[crudcrate(strict_query)]`; see [`check_query_fields`](crate::filtering::strict::check_query_fields).
+    const STRICT_QUERY: bool = false;
+
+    /// When true, requests sending an `X-Server-Timing` header get a `Server-Timing`
+    /// response header breaking down the handler's time (always on with the `debug`
+    /// feature). Set via `#[crudcrate(server_timing)]`; see [`timing`](crate::timing).
+    const SERVER_TIMING: bool = false;
+
+    /// When true, list queries break ties in the requested order by ID, so pages sorted on
+    /// non-unique columns neither repeat nor skip rows. Disable with
+    /// `#[crudcrate(unstable_sort)]`; see [`with_tie_breaker`](crate::core::crud_operations::with_tie_breaker).
+    const STABLE_SORT: bool = true;
+
+    /// When true, the generated update and delete handlers lock each row they write, so
+    /// concurrent writes to one row run one at a time. Set via
+    /// `#[crudcrate(serialize_writes)]`; see [`lock_rows`](crate::locks::lock_rows).
+    const SERIALIZE_WRITES: bool = false;
+
+    /// When true, creates, updates and deletes record deliveries for the resource's
+    /// webhooks. Set via `#[crudcrate(webhooks)]`; see [`webhooks`](crate::webhooks).
+    const WEBHOOKS: bool = false;
+
+    /// When true, `create`, `update` or `delete` run more than the default single-row
+    /// writes: hooks, an `operations` object or nested `join(create)` inserts.
+    /// [`BatchRouter`](crate::batch::BatchRouter) can't run those inside its transaction
+    /// and refuses the resource. Generated.
+    const CUSTOM_WRITES: bool = false;
+
     /// Maximum number of items allowed in batch create/update/delete operations.
     /// Override with `#[crudcrate(batch_limit = 500)]` on your struct, or implement
     /// manually for runtime logic (env vars, config, etc.).
@@ -69,6 +132,61 @@ where
         1000
     }
 
+    /// Maximum length in bytes of the `filter` query parameter. Longer filters are
+    /// rejected with 400 before they are parsed.
+    /// Override with `#[crudcrate(max_filter_length = 4096)]` on your struct.
+    #[must_use]
+    fn max_filter_length() -> usize {
+        16 * 1024
+    }
+
+    /// Maximum nesting depth of the `filter` JSON, counting the top-level object as 1.
+    /// Deeper filters are rejected with 400 before they are parsed.
+    /// Override with `#[crudcrate(max_filter_depth = 2)]` on your struct.
+    #[must_use]
+    fn max_filter_depth() -> usize {
+        4
+    }
+
+    /// Deadline for each request to the generated router's handlers, after which the
+    /// handler is cancelled and the request gets 504 Gateway Timeout. `None` (the
+    /// default) never times out.
+    /// Override with `#[crudcrate(timeout_ms = 5000)]` on your struct, or implement
+    /// manually for runtime logic (env vars, config, etc.).
+    #[must_use]
+    fn operation_timeout() -> Option<std::time::Duration> {
+        None
+    }
+
+    /// Retries of the default create, update and delete bodies after a deadlock,
+    /// serialization failure or busy database; see [`retry`](crate::retry). Three
+    /// attempts by default.
+    /// Override with `#[crudcrate(retry_attempts = 5)]` on your struct, or implement
+    /// manually for custom delays.
+    #[must_use]
+    fn retry_policy() -> crate::retry::RetryPolicy {
+        crate::retry::RetryPolicy::default()
+    }
+
+    /// Most relation queries and joined rows the list and single-item handlers may
+    /// spend on `join` fields; see [`complexity`](crate::complexity). Unlimited by
+    /// default.
+    /// Override with `#[crudcrate(max_join_queries = 50, max_joined_rows = 5000)]` on
+    /// your struct, or implement manually for runtime logic.
+    #[must_use]
+    fn join_budget() -> crate::complexity::JoinBudget {
+        crate::complexity::JoinBudget::default()
+    }
+
+    /// Estimated cost of loading the `join` fields of `items` items, for a list page
+    /// when `list` is set and for `get_one` otherwise, following nested joins `depth`
+    /// levels down. Generated from the `join(...)` fields; no cost by default.
+    #[must_use]
+    fn join_cost(items: u64, list: bool, depth: u8) -> crate::complexity::JoinCost {
+        let _ = (items, list, depth);
+        crate::complexity::JoinCost::default()
+    }
+
     async fn get_all(
         db: &DatabaseConnection,
         condition: &Condition,
@@ -77,14 +195,8 @@ where
         offset: u64,
         limit: u64,
     ) -> Result<Vec<Self::ListModel>, ApiError> {
-        let models = Self::EntityType::find()
-            .filter(condition.clone())
-            .order_by(order_column, order_direction)
-            .offset(offset)
-            .limit(limit)
-            .all(db)
-            .await
-            .map_err(ApiError::database)?;
+        let order = [column_order(order_column, order_direction)];
+        let models = find_page::<Self>(db, condition, &order, offset, limit, &[]).await?;
         Ok(models
             .into_iter()
             .map(|model| Self::ListModel::from(Self::from(model)))
@@ -112,14 +224,30 @@ where
         Self::get_all(db, condition, order_column, order_direction, offset, limit).await
     }
 
+    /// List variant ordered by arbitrary expressions rather than a single column.
+    ///
+    /// Used by `get_all_handler` for orderings that are not plain columns, such as
+    /// distance from a point on a `geo` field. `scoped` mirrors the choice between
+    /// `get_all` and `get_all_scoped`. The derive macro overrides this to keep
+    /// `join(all)` loading and hooks; the default impl runs a plain query.
+    async fn get_all_ordered(
+        db: &DatabaseConnection,
+        condition: &Condition,
+        order: &[(sea_orm::sea_query::SimpleExpr, Order)],
+        offset: u64,
+        limit: u64,
+        scoped: bool,
+    ) -> Result<Vec<Self::ListModel>, ApiError> {
+        let _ = scoped;
+        let models = find_page::<Self>(db, condition, order, offset, limit, &[]).await?;
+        Ok(models
+            .into_iter()
+            .map(|model| Self::ListModel::from(Self::from(model)))
+            .collect())
+    }
+
     async fn get_one(db: &DatabaseConnection, id: Uuid) -> Result<Self, ApiError> {
-        let model = Self::EntityType::find_by_id(id)
-            .one(db)
-            .await
-            .map_err(ApiError::database)?
-            .ok_or_else(|| {
-                ApiError::not_found(Self::RESOURCE_NAME_SINGULAR, Some(id.to_string()))
-            })?;
+        let model = find_by_id::<Self>(db, id, None).await?;
         Ok(Self::from(model))
     }
 
@@ -136,27 +264,51 @@ where
         id: Uuid,
         scope: &Condition,
     ) -> Result<Self, ApiError> {
-        use sea_orm::QueryFilter;
-        let condition = Condition::all()
-            .add(Self::ID_COLUMN.eq(id))
-            .add(scope.clone());
-        let model = Self::EntityType::find()
-            .filter(condition)
-            .one(db)
-            .await
-            .map_err(ApiError::database)?
-            .ok_or_else(|| {
-                ApiError::not_found(Self::RESOURCE_NAME_SINGULAR, Some(id.to_string()))
-            })?;
+        let model = find_one::<Self>(db, id, Some(scope)).await?;
         Ok(Self::from(model))
     }
 
+    /// Convert a create model into the active model to insert.
+    ///
+    /// Defaults to the model's `Into` conversion. The derive overrides it when a field's
+    /// `on_create` is an async generator function, awaiting it here, and to check
+    /// `polymorphic_join` targets and `unique_together` constraints. `db` is the
+    /// transaction during batch creates.
+    ///
+    /// # Errors
+    /// Returns the generator's `ApiError`, `ApiError::BadRequest` for an invalid
+    /// polymorphic target, or `ApiError::Conflict` for a duplicate.
+    async fn create_active_model<C: ConnectionTrait>(
+        _db: &C,
+        create_model: Self::CreateModel,
+    ) -> Result<Self::ActiveModelType, ApiError> {
+        Ok(create_model.into())
+    }
+
+    /// Merge an update model into the existing active model.
+    ///
+    /// Defaults to [`MergeIntoActiveModel`]. The derive overrides it when a field's
+    /// `on_update` is an async generator function, awaiting it here, and to check
+    /// `polymorphic_join` targets and `unique_together` constraints. `db` is the
+    /// transaction during batch updates.
+    ///
+    /// # Errors
+    /// Returns the merge or generator `ApiError`, `ApiError::BadRequest` for an
+    /// invalid polymorphic target, or `ApiError::Conflict` for a duplicate.
+    async fn update_active_model<C: ConnectionTrait>(
+        _db: &C,
+        update_model: Self::UpdateModel,
+        existing: Self::ActiveModelType,
+    ) -> Result<Self::ActiveModelType, ApiError> {
+        update_model.merge_into_activemodel(existing)
+    }
+
     async fn create(
         db: &DatabaseConnection,
         create_model: Self::CreateModel,
     ) -> Result<Self, ApiError> {
         use sea_orm::ActiveModelTrait;
-        let active_model: Self::ActiveModelType = create_model.into();
+        let active_model = Self::create_active_model(db, create_model).await?;
 
         // Use insert and return the model directly
         // This works across all databases unlike last_insert_id for UUIDs
@@ -180,22 +332,35 @@ where
                 ApiError::not_found(Self::RESOURCE_NAME_SINGULAR, Some(id.to_string()))
             })?;
         let existing: Self::ActiveModelType = model.into_active_model();
-        let updated_model = update_model.merge_into_activemodel(existing)?;
+        let updated_model = Self::update_active_model(db, update_model, existing).await?;
         let updated = updated_model.update(db).await.map_err(ApiError::database)?;
         Ok(Self::from(updated))
     }
 
     async fn delete(db: &DatabaseConnection, id: Uuid) -> Result<Uuid, ApiError> {
+        let stored_files = if Self::file_fields().is_empty() {
+            vec![]
+        } else {
+            Self::EntityType::find_by_id(id)
+                .one(db)
+                .await
+                .map_err(ApiError::database)?
+                .map(|model| Self::from(model).stored_files())
+                .unwrap_or_default()
+        };
         let res = Self::EntityType::delete_by_id(id)
             .exec(db)
             .await
             .map_err(ApiError::database)?;
-        match res.rows_affected {
-            0 => Err(ApiError::not_found(
+        if res.rows_affected == 0 {
+            Err(ApiError::not_found(
                 Self::RESOURCE_NAME_SINGULAR,
                 Some(id.to_string()),
-            )),
-            _ => Ok(id),
+            ))
+        } else {
+            crate::sync::record_deletes::<Self, _>(db, &[id]).await?;
+            crate::storage::discard(&stored_files).await;
+            Ok(id)
         }
     }
 
@@ -226,11 +391,13 @@ where
 
         // Delete only existing IDs
         if !existing_set.is_empty() {
+            let existing_ids: Vec<Uuid> = existing_set.iter().copied().collect();
             Self::EntityType::delete_many()
-                .filter(Self::ID_COLUMN.is_in(existing_set.iter().copied().collect::<Vec<_>>()))
+                .filter(Self::ID_COLUMN.is_in(existing_ids.clone()))
                 .exec(db)
                 .await
                 .map_err(ApiError::database)?;
+            crate::sync::record_deletes::<Self, _>(db, &existing_ids).await?;
         }
 
         // Return only IDs that actually existed (preserving input order)
@@ -240,6 +407,43 @@ where
             .collect())
     }
 
+    /// Delete the children of the rows with the given IDs, as generated for
+    /// `#[crudcrate(cascade_delete)]`: the rows of each `Vec` join field, after their own
+    /// children. `db` is the parent delete's transaction. Returns the deleted rows,
+    /// deepest first. Defaults to deleting nothing.
+    ///
+    /// # Errors
+    /// Returns `ApiError::Database` if a query fails.
+    async fn cascade_delete<C: ConnectionTrait>(
+        _db: &C,
+        _ids: &[Uuid],
+    ) -> Result<Vec<crate::relationships::DeletedChild>, ApiError> {
+        Ok(vec![])
+    }
+
+    /// Refuse to delete the rows with the given IDs while other rows reference them, as
+    /// generated for `#[crudcrate(restrict_delete)]` with
+    /// [`ensure_no_dependents`](crate::relationships::ensure_no_dependents), or while
+    /// one of them is locked, as generated for `#[crudcrate(guard_delete = "...")]` with
+    /// [`ensure_unlocked`](crate::core::guard::ensure_unlocked). `db` may be a
+    /// transaction. Defaults to allowing every delete.
+    ///
+    /// # Errors
+    /// Returns `ApiError::Restricted` listing the relations with rows, a 423
+    /// `ApiError::Custom` naming the locked rows, or `ApiError::Database` if a query fails.
+    async fn ensure_deletable<C: ConnectionTrait>(_db: &C, _ids: &[Uuid]) -> Result<(), ApiError> {
+        Ok(())
+    }
+
+    /// Condition the rows must still match when the `DELETE` statement runs, so a row
+    /// locked after [`ensure_deletable`](Self::ensure_deletable) checked it is kept, as
+    /// generated for `#[crudcrate(guard_delete = "...")]` with
+    /// [`unlocked`](crate::core::guard::unlocked). Defaults to `None`.
+    #[must_use]
+    fn delete_condition() -> Option<Condition> {
+        None
+    }
+
     /// Create multiple entities in a batch.
     ///
     /// Uses a transaction to ensure all-or-nothing semantics: if any insert fails,
@@ -274,7 +478,7 @@ where
 
         let mut results = Vec::with_capacity(create_models.len());
         for create_model in create_models {
-            let active_model: Self::ActiveModelType = create_model.into();
+            let active_model = Self::create_active_model(&txn, create_model).await?;
             let model = match active_model.insert(&txn).await {
                 Ok(m) => m,
                 Err(e) => {
@@ -331,7 +535,7 @@ where
                     ApiError::not_found(Self::RESOURCE_NAME_SINGULAR, Some(id.to_string()))
                 })?;
             let existing: Self::ActiveModelType = model.into_active_model();
-            let updated_model = update_model.merge_into_activemodel(existing)?;
+            let updated_model = Self::update_active_model(&txn, update_model, existing).await?;
             let updated = updated_model
                 .update(&txn)
                 .await
@@ -344,7 +548,7 @@ where
     }
 
     async fn total_count(db: &DatabaseConnection, condition: &Condition) -> u64 {
-        let query = Self::EntityType::find().filter(condition.clone());
+        let query = crate::core::view::select::<Self>().filter(condition.clone());
         match PaginatorTrait::count(query, db).await {
             Ok(count) => count,
             Err(e) => {
@@ -384,6 +588,15 @@ where
         false
     }
 
+    /// Returns the database values of an enum field's variants, or `None` if the field
+    /// is not an `ActiveEnum`. Filters with other values are rejected with a 400, and the
+    /// variants are listed in the `OpenAPI` description of the list endpoint.
+    #[must_use]
+    fn enum_variants(field_name: &str) -> Option<Vec<String>> {
+        let _ = field_name;
+        None
+    }
+
     /// Normalizes an enum value for case-insensitive matching.
     /// This is used for enum types that don't support case-insensitive operations.
     /// Default implementation returns None, indicating no enum normalization is available.
@@ -401,6 +614,85 @@ where
         vec![]
     }
 
+    /// Returns field names marked `#[crudcrate(ci)]`, whose filters compare the exact
+    /// value through a case-insensitive collation instead of `UPPER`/`LIKE`.
+    /// See [`crate::filtering::collation`].
+    #[must_use]
+    fn case_insensitive_columns() -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// Returns `(field, collation)` for sortable fields declared with `collate`, ordered
+    /// through that collation on `PostgreSQL`. See [`crate::filtering::collation`].
+    #[must_use]
+    fn sort_collations() -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    /// Returns filterable fields declared with `filter_with`, whose function builds the
+    /// condition for their filter value. See [`crate::filtering::custom`].
+    #[must_use]
+    fn custom_filters() -> Vec<(&'static str, crate::filtering::FilterFn)> {
+        vec![]
+    }
+
+    /// Returns the resource's virtual filters: filter keys that aren't columns, each
+    /// expanded to a condition by its builder. See [`crate::filtering::custom`].
+    #[must_use]
+    fn virtual_filters() -> Vec<crate::filtering::VirtualFilter> {
+        vec![]
+    }
+
+    /// Returns filterable fields typed `Json`/`serde_json::Value`. These accept dot-path
+    /// filters into the document, e.g. `{"metadata.color": "red"}`.
+    #[must_use]
+    fn json_filterable_columns() -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// Returns filterable Postgres array fields (`Vec<String>`, `Vec<i32>`, ...) with their
+    /// element type. These accept `{field}_contains` (`@>`) and `{field}_overlaps` (`&&`).
+    #[must_use]
+    fn array_filterable_columns() -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    /// Returns filterable fields typed `Decimal`. Their filter values are compared as exact
+    /// numerics, so `{"price_gte": "19.99"}` does not go through a float.
+    #[must_use]
+    fn decimal_filterable_columns() -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// Returns filterable date/time fields with their storage kind. These accept calendar
+    /// filters such as `{"created_at_date": "2024-05-01"}` and `{"created_at_year": 2024}`.
+    #[must_use]
+    fn date_filterable_columns() -> Vec<(&'static str, crate::filtering::dates::DateKind)> {
+        vec![]
+    }
+
+    /// Returns date/time fields typed with the `time` crate (`OffsetDateTime`,
+    /// `PrimitiveDateTime`, `time::Date`). With the `time` feature, filter and keyset
+    /// values for these are parsed from RFC 3339 and bound as typed values.
+    #[must_use]
+    fn time_crate_columns() -> Vec<(&'static str, crate::filtering::dates::DateKind)> {
+        vec![]
+    }
+
+    /// Returns text fields marked `large_text`. They are left out of list responses and
+    /// read one at a time from `GET /{id}/{field}`.
+    #[must_use]
+    fn large_text_columns() -> Vec<(&'static str, Self::ColumnType)> {
+        vec![]
+    }
+
+    /// Returns fields marked `lookup`, alternate keys rows can be read by from
+    /// `GET /by/{field}/{value}`.
+    #[must_use]
+    fn lookup_columns() -> Vec<(&'static str, Self::ColumnType)> {
+        vec![]
+    }
+
     /// Returns a list of field names and their column types that should be included in fulltext search.
     /// These fields will be concatenated and searched when the 'q' parameter is used.
     /// Default is empty - no fields are included in fulltext search by default.
@@ -409,6 +701,38 @@ where
         vec![]
     }
 
+    /// Returns fulltext fields that use `pg_trgm` similarity on `PostgreSQL`, with the
+    /// similarity threshold for each. Declared with `#[crudcrate(fulltext(strategy = "trigram"))]`.
+    ///
+    /// On `PostgreSQL` these fields match when `similarity(field, q) > threshold`, which
+    /// tolerates typos. Other backends treat them like regular `fulltext` fields.
+    /// Requires the `pg_trgm` extension.
+    #[must_use]
+    fn trigram_searchable_columns() -> Vec<(&'static str, Self::ColumnType, f64)> {
+        vec![]
+    }
+
+    /// Returns fulltext fields matched in boolean mode on `MySQL`. Declared with
+    /// `#[crudcrate(fulltext(mode = "boolean"))]`.
+    ///
+    /// On `MySQL` these fields match with `MATCH(...) AGAINST (q IN BOOLEAN MODE)`, so `q` may
+    /// use `+required`, `-excluded`, `"exact phrase"` and `prefix*`. Other backends treat
+    /// them like regular `fulltext` fields. Requires a FULLTEXT index over exactly these
+    /// columns, which [`ensure_indexes`](crate::database::ensure_indexes) creates.
+    #[must_use]
+    fn boolean_fulltext_columns() -> Vec<(&'static str, Self::ColumnType)> {
+        vec![]
+    }
+
+    /// Returns geo fields marked `#[crudcrate(geo)]` or declared with struct-level
+    /// `geo(name = ..., lat = ..., lon = ...)`, keyed by the name used in filters and sorts.
+    ///
+    /// Each field accepts a `{name}_within` filter and can be sorted by distance.
+    #[must_use]
+    fn geo_fields() -> Vec<(&'static str, crate::filtering::GeoColumn)> {
+        vec![]
+    }
+
     /// Returns column names excluded from filtering/sorting when a `ScopeCondition` is active.
     ///
     /// Fields marked with `#[crudcrate(exclude(scoped))]` are automatically included.
@@ -421,6 +745,16 @@ where
         &[]
     }
 
+    /// Returns field names marked `#[crudcrate(sensitive)]` or `hash_with`. Generated
+    /// models print `***` for them in `Debug` output, and `?__explain=true` keeps the
+    /// query's parameters out of the plan for resources that have any.
+    ///
+    /// Default: empty.
+    #[must_use]
+    fn sensitive_fields() -> &'static [&'static str] {
+        &[]
+    }
+
     /// Returns a list of filterable columns on joined/related entities.
     ///
     /// These columns can be filtered using dot-notation in query parameters:
@@ -438,6 +772,14 @@ where
         vec![]
     }
 
+    /// `Vec` join fields accepting `{join}__exists` and `{join}__count` filters, as in
+    /// `GET /customers?filter={"vehicles__count_gte":2}`. The derive macro lists every
+    /// `Vec` join field; see [`exists`](crate::filtering::exists).
+    #[must_use]
+    fn relation_filters() -> Vec<crate::filtering::RelationFilterDef> {
+        vec![]
+    }
+
     /// Returns a list of sortable columns on joined/related entities.
     ///
     /// These columns can be sorted using dot-notation in query parameters:
@@ -454,4 +796,145 @@ where
     fn joined_sortable_columns() -> Vec<crate::JoinedColumnDef> {
         vec![]
     }
+
+    /// Fields marked `#[crudcrate(file_upload(storage = "..."))]`, as
+    /// `(request field name, storage name)` pairs.
+    ///
+    /// Multipart create and update requests stream these parts to the named
+    /// [`StorageBackend`](crate::storage::StorageBackend).
+    #[must_use]
+    fn file_fields() -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    /// Objects this record references in its `file_upload` fields, as
+    /// `(storage name, stored value)` pairs. Used to delete them with the record.
+    #[must_use]
+    fn stored_files(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+
+    /// Parent column of a `#[crudcrate(tree(parent_field = "..."))]` resource, walked by
+    /// the [`tree`](crate::relationships::tree) endpoints. `None` for other resources.
+    #[must_use]
+    fn tree_parent_column() -> Option<Self::ColumnType> {
+        None
+    }
+
+    /// Most levels the tree endpoints walk up or down. Set with `tree(max_depth = N)`.
+    #[must_use]
+    fn tree_max_depth() -> u32 {
+        10
+    }
+
+    /// Field name, column and kind of the `#[crudcrate(sync = "...")]` timestamp the
+    /// [`sync`](crate::sync) endpoint reads changes by. `None` for other resources,
+    /// whose deletes leave no tombstones.
+    #[must_use]
+    fn sync_column() -> Option<(
+        &'static str,
+        Self::ColumnType,
+        crate::filtering::dates::DateKind,
+    )> {
+        None
+    }
+
+    /// Column of the `#[crudcrate(slug_from = "...")]` field the
+    /// [`slug`](crate::core::slug) endpoint looks rows up by. `None` for other resources.
+    #[must_use]
+    fn slug_column() -> Option<Self::ColumnType> {
+        None
+    }
+
+    /// Condition from the `read::many::condition` hook, limiting every read of the
+    /// generated handlers: the list with its count and facets, `GET /{id}`, `by-ids`,
+    /// distinct values, `large_text` fields and the tree, `changes` and lazy relation
+    /// endpoints. `None` for other resources.
+    #[must_use]
+    fn read_condition() -> Option<Condition> {
+        None
+    }
+
+    /// Fields bounding the rows a `#[crudcrate(temporal)]` resource's reads return, see
+    /// [`temporal`](crate::temporal). `None` for other resources.
+    #[must_use]
+    fn temporal_columns() -> Option<crate::temporal::Validity<Self::ColumnType>> {
+        None
+    }
+
+    /// Fields compared by [`check-duplicates`](crate::core::duplicates), declared with
+    /// `#[crudcrate(dedupe_on(name, email))]`. Empty for other resources.
+    #[must_use]
+    fn dedupe_fields() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// The `dedupe_on` fields of a create payload, as `(field, column, value)`. The value
+    /// is trimmed, and `None` when the payload leaves the field empty or `null`.
+    #[must_use]
+    fn dedupe_values(
+        _create: &Self::CreateModel,
+    ) -> Vec<(&'static str, Self::ColumnType, Option<String>)> {
+        vec![]
+    }
+
+    /// Allowed transitions of the field declared with `#[crudcrate(state_machine(...))]`,
+    /// checked by the default update bodies and the
+    /// [`transition`](crate::state_machine) endpoint. `None` for other resources.
+    #[must_use]
+    fn state_machine() -> Option<crate::state_machine::StateMachine> {
+        None
+    }
+
+    /// Field sets that must be unique together, declared with
+    /// `#[crudcrate(unique_together(email, org_id))]` and checked by
+    /// [`ensure_unique`](crate::core::unique::ensure_unique). Empty for other resources.
+    #[must_use]
+    fn unique_together() -> Vec<crate::core::unique::UniqueTogether<Self::ColumnType>> {
+        vec![]
+    }
+
+    /// Query parameter names that refer to a differently named field, as
+    /// `(external name, field name)` pairs.
+    ///
+    /// Generated from `#[crudcrate(rename = "...", alias = "...")]`. The list handler
+    /// translates filter and sort keys with
+    /// [`translate_field_names`](crate::filtering::translate_field_names).
+    #[must_use]
+    fn field_aliases() -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    /// Field descriptions published through the [`registry`](crate::registry).
+    ///
+    /// The derive macro lists every model field with its type and flags. The default
+    /// lists only filterable and sortable columns, without types.
+    #[must_use]
+    fn field_metadata() -> Vec<crate::registry::FieldMetadata> {
+        let filterable = Self::filterable_columns();
+        let sortable = Self::sortable_columns();
+        let mut names: Vec<&'static str> = filterable
+            .iter()
+            .chain(sortable.iter())
+            .map(|(name, _)| *name)
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|name| {
+                let mut field = crate::registry::FieldMetadata::new(name, "");
+                field.filterable = filterable.iter().any(|(n, _)| *n == name);
+                field.sortable = sortable.iter().any(|(n, _)| *n == name);
+                field.enum_variants = Self::enum_variants(name);
+                field
+            })
+            .collect()
+    }
+
+    /// `join(...)` fields published through the [`registry`](crate::registry).
+    #[must_use]
+    fn relation_metadata() -> Vec<crate::registry::RelationMetadata> {
+        vec![]
+    }
 }
@@ -0,0 +1,188 @@
+//! Uniqueness checks for resources declared with `#[crudcrate(unique_together(...))]`.
+//!
+//! A `unique_together(email, org_id)` constraint is checked twice. Before a create or
+//! update writes its row, the generated `create_active_model()`/`update_active_model()`
+//! look for another row with the same values and answer 409 Conflict naming the
+//! fields, instead of a database error. The database enforces it too, through the
+//! unique index [`unique_index_sql`](crate::database::unique_index_sql) builds, which
+//! closes the race between two writes checked at the same time. `ensure_indexes`
+//! creates that index for `auto_index` resources.
+//!
+//! `condition = "deleted_at IS NULL"` makes the constraint partial: only rows matching the
+//! SQL condition must be unique, so a soft-deleted row doesn't block a new one.
+//!
+//! ```rust,ignore
+//! #[crudcrate(generate_router, unique_together(email, org_id, condition = "deleted_at IS NULL"))]
+//! pub struct Model { /* ... */ }
+//! ```
+//!
+//! Like unique indexes, the check ignores rows where any of the fields is `NULL`.
+
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, ConnectionTrait, EntityTrait,
+    IdenStatic, PaginatorTrait, QueryFilter, Value,
+};
+
+use crate::core::traits::CRUDResource;
+use crate::errors::ApiError;
+
+/// Fields whose values must be unique together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniqueTogether<C> {
+    /// Field names with their columns, in declaration order
+    pub fields: Vec<(&'static str, C)>,
+    /// SQL condition selecting the rows the constraint applies to, for partial indexes
+    pub condition: Option<&'static str>,
+}
+
+impl<C: IdenStatic> UniqueTogether<C> {
+    /// Name of the unique index on `table`: `uq_{table}_{column}_{column}...`
+    #[must_use]
+    pub fn index_name(&self, table: &str) -> String {
+        let columns: Vec<&str> = self.fields.iter().map(|(_, c)| c.as_str()).collect();
+        format!("uq_{table}_{}", columns.join("_"))
+    }
+
+    /// The field names joined for messages, e.g. `email and org_id`
+    fn describe(&self) -> String {
+        let names: Vec<&str> = self.fields.iter().map(|(name, _)| *name).collect();
+        match names.split_last() {
+            Some((last, rest)) if !rest.is_empty() => format!("{} and {last}", rest.join(", ")),
+            _ => names.join(""),
+        }
+    }
+}
+
+/// The value of `column` in `model`, falling back to `existing` for columns `model`
+/// leaves unset; `None` when neither has a value or it is `NULL`
+fn column_value<T: CRUDResource>(
+    model: &T::ActiveModelType,
+    existing: Option<&T::ActiveModelType>,
+    column: T::ColumnType,
+) -> Option<Value>
+where
+    T::ColumnType: Into<<T::EntityType as EntityTrait>::Column>,
+{
+    let value = match model.get(column.into()) {
+        ActiveValue::NotSet => existing?.get(column.into()).into_value()?,
+        ActiveValue::Set(value) | ActiveValue::Unchanged(value) => value,
+    };
+    (value != value.as_null()).then_some(value)
+}
+
+/// Check that no other row of `T` has the values `model` is about to write for any of
+/// `T`'s [`unique_together`](CRUDResource::unique_together) constraints.
+///
+/// `existing` is the stored row of an update, whose merged `model` leaves the columns
+/// the request didn't send unset. Constraints none of whose fields is `Set` in `model`
+/// are skipped, so updates only check what they change. So are constraints with a
+/// `NULL` field.
+///
+/// # Errors
+///
+/// Returns `ApiError::Conflict` naming the fields when another row has the same
+/// values, or `ApiError::Database` if the lookup fails.
+pub async fn ensure_unique<T, C>(
+    db: &C,
+    model: &T::ActiveModelType,
+    existing: Option<&T::ActiveModelType>,
+) -> Result<(), ApiError>
+where
+    T: CRUDResource,
+    T::ColumnType: Into<<T::EntityType as EntityTrait>::Column>,
+    C: ConnectionTrait,
+{
+    for constraint in T::unique_together() {
+        let changed = constraint
+            .fields
+            .iter()
+            .any(|(_, column)| model.get((*column).into()).is_set());
+        if !changed {
+            continue;
+        }
+
+        let mut condition = Condition::all();
+        let mut complete = true;
+        for (_, column) in &constraint.fields {
+            match column_value::<T>(model, existing, *column) {
+                Some(value) => condition = condition.add(column.eq(value)),
+                None => complete = false,
+            }
+        }
+        if !complete {
+            continue;
+        }
+        if let Some(sql) = constraint.condition {
+            condition = condition.add(Expr::cust(sql));
+        }
+        // An update's row holds the values it already had
+        if let Some(id) = column_value::<T>(model, existing, T::ID_COLUMN) {
+            condition = condition.add(T::ID_COLUMN.ne(id));
+        }
+
+        let existing = T::EntityType::find().filter(condition).count(db).await?;
+        if existing > 0 {
+            return Err(ApiError::conflict(format!(
+                "A {} with this {} already exists",
+                T::RESOURCE_NAME_SINGULAR,
+                constraint.describe()
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Column {
+        Email,
+        OrgId,
+        Code,
+    }
+
+    impl sea_orm::Iden for Column {
+        fn unquoted(&self, s: &mut dyn std::fmt::Write) {
+            write!(s, "{}", self.as_str()).unwrap();
+        }
+    }
+
+    impl IdenStatic for Column {
+        fn as_str(&self) -> &'static str {
+            match self {
+                Self::Email => "email",
+                Self::OrgId => "org_id",
+                Self::Code => "code",
+            }
+        }
+    }
+
+    #[test]
+    fn test_index_name_and_description() {
+        let pair = UniqueTogether {
+            fields: vec![("email", Column::Email), ("orgId", Column::OrgId)],
+            condition: None,
+        };
+        assert_eq!(pair.index_name("users"), "uq_users_email_org_id");
+        assert_eq!(pair.describe(), "email and orgId");
+
+        let single = UniqueTogether {
+            fields: vec![("code", Column::Code)],
+            condition: Some("deleted_at IS NULL"),
+        };
+        assert_eq!(single.describe(), "code");
+
+        let triple = UniqueTogether {
+            fields: vec![
+                ("email", Column::Email),
+                ("org_id", Column::OrgId),
+                ("code", Column::Code),
+            ],
+            condition: None,
+        };
+        assert_eq!(triple.describe(), "email, org_id and code");
+    }
+}
@@ -0,0 +1,79 @@
+//! Resources backed by a SQL query instead of a table.
+//!
+//! `#[crudcrate(view_sql = "SELECT ...")]` makes every read select from the query as a
+//! subquery aliased to the entity's table name, so a reporting endpoint gets the same
+//! filter, sort, search and pagination grammar as a table:
+//!
+//! ```rust,ignore
+//! #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+//! #[sea_orm(table_name = "customer_totals")]
+//! #[crudcrate(
+//!     generate_router,
+//!     view_sql = "SELECT c.id, c.name, COUNT(o.id) AS orders FROM customers c LEFT JOIN orders o ON o.customer_id = c.id GROUP BY c.id, c.name"
+//! )]
+//! pub struct Model {
+//!     #[sea_orm(primary_key, auto_increment = false)]
+//!     #[crudcrate(primary_key)]
+//!     pub id: Uuid,
+//!     #[crudcrate(filterable, sortable)]
+//!     pub name: String,
+//!     #[crudcrate(filterable, sortable)]
+//!     pub orders: i64,
+//! }
+//! ```
+//!
+//! ```text
+//! GET /customer-totals?filter={"orders_gte":10}&sort=["orders","DESC"]
+//! SELECT ... FROM (SELECT c.id, ... GROUP BY c.id, c.name) AS "customer_totals" WHERE ...
+//! ```
+//!
+//! The query must return a column for every entity field. Its SQL is sent as written,
+//! so it must not contain user input. The generated `router()` serves the read endpoints
+//! only, like `read_only_router()`. For a view that exists in the database, point
+//! `table_name` at it instead; no attribute is needed.
+
+use std::fmt;
+
+use sea_orm::sea_query::{Iden, Quote};
+use sea_orm::{EntityTrait, QueryTrait, Select};
+
+use crate::CRUDResource;
+
+/// `(<sql>) AS "<alias>"` in a `FROM` clause
+struct ViewSource {
+    sql: &'static str,
+    alias: &'static str,
+}
+
+impl Iden for ViewSource {
+    fn prepare(&self, s: &mut dyn fmt::Write, q: Quote) {
+        write!(
+            s,
+            "({}) AS {}{}{}",
+            self.sql,
+            q.left(),
+            self.alias,
+            q.right()
+        )
+        .unwrap();
+    }
+
+    fn unquoted(&self, s: &mut dyn fmt::Write) {
+        write!(s, "{}", self.alias).unwrap();
+    }
+}
+
+/// `SELECT` of `T`'s rows: from its table, or from its
+/// [`VIEW_SQL`](CRUDResource::VIEW_SQL) query when set. The read paths start from this
+/// instead of `T::EntityType::find()`.
+#[must_use]
+pub fn select<T: CRUDResource>() -> Select<T::EntityType> {
+    let mut query = T::EntityType::find();
+    if let Some(sql) = T::VIEW_SQL {
+        QueryTrait::query(&mut query).from_clear().from(ViewSource {
+            sql,
+            alias: T::TABLE_NAME,
+        });
+    }
+    query
+}
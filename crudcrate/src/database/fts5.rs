@@ -0,0 +1,181 @@
+//! `SQLite` FTS5 tables for fulltext search.
+//!
+//! With `#[crudcrate(fts5_table = "articles_fts")]`, fulltext queries on `SQLite` match
+//! against an FTS5 index instead of a LIKE over every `fulltext` field, and lists with a
+//! `q` filter and no explicit sort are ordered by `bm25` relevance. The table is an
+//! external-content FTS5 table over the resource's table, kept in sync by triggers. Create
+//! it with [`ensure_fts5`] (or let [`ensure_indexes`](super::ensure_indexes) do it for
+//! `auto_index` resources), or run [`fts5_table_sql`] in a migration.
+//!
+//! The resource's table must have a `rowid` (no `WITHOUT ROWID`).
+
+use crate::core::CRUDResource;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbErr, IdenStatic, Statement};
+
+/// Statements creating the FTS5 table for `T` and the triggers that keep it in sync.
+///
+/// Returns an empty list if `T` has no `fts5_table` or no `fulltext` fields. Run
+/// [`fts5_rebuild_sql`] afterwards to index rows that already exist.
+#[must_use]
+pub fn fts5_table_sql<T: CRUDResource>() -> Vec<String> {
+    let Some(fts) = T::FTS5_TABLE else {
+        return vec![];
+    };
+    build_table_sql(T::TABLE_NAME, fts, &fulltext_columns::<T>())
+}
+
+/// Statement re-indexing every row of `T` into its FTS5 table.
+#[must_use]
+pub fn fts5_rebuild_sql<T: CRUDResource>() -> Option<String> {
+    let fts = T::FTS5_TABLE?;
+    if fulltext_columns::<T>().is_empty() {
+        return None;
+    }
+    Some(build_rebuild_sql(fts))
+}
+
+/// Create the FTS5 table and triggers for `T` if they are missing.
+///
+/// A newly created table is filled from the existing rows. Does nothing on backends
+/// other than `SQLite`. Returns whether the table was created.
+///
+/// # Errors
+///
+/// Returns a `DbErr` if a statement fails, for example when `SQLite` was built without FTS5.
+pub async fn ensure_fts5<T: CRUDResource>(db: &impl ConnectionTrait) -> Result<bool, DbErr> {
+    if db.get_database_backend() != DatabaseBackend::Sqlite {
+        return Ok(false);
+    }
+    let (Some(fts), Some(rebuild)) = (T::FTS5_TABLE, fts5_rebuild_sql::<T>()) else {
+        return Ok(false);
+    };
+
+    let exists = db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?",
+            [fts.into()],
+        ))
+        .await?
+        .is_some();
+
+    for sql in fts5_table_sql::<T>() {
+        db.execute_unprepared(&sql).await?;
+    }
+    if exists {
+        return Ok(false);
+    }
+
+    db.execute_unprepared(&rebuild).await?;
+    tracing::info!(
+        table = T::TABLE_NAME,
+        fts5_table = fts,
+        "Created FTS5 table"
+    );
+    Ok(true)
+}
+
+fn fulltext_columns<T: CRUDResource>() -> Vec<String> {
+    T::fulltext_searchable_columns()
+        .into_iter()
+        .map(|(_, c)| c.as_str().to_string())
+        .collect()
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn build_table_sql(table: &str, fts: &str, columns: &[String]) -> Vec<String> {
+    if columns.is_empty() {
+        return vec![];
+    }
+    let content = table.replace('\'', "''");
+    let table = quote(table);
+    let quoted_fts = quote(fts);
+    let names = columns
+        .iter()
+        .map(|c| quote(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = |row: &str| {
+        columns
+            .iter()
+            .map(|c| format!("{row}.{}", quote(c)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let (new, old) = (values("new"), values("old"));
+    let trigger = |suffix: &str| quote(&format!("{fts}_{suffix}"));
+    let (insert_trigger, delete_trigger, update_trigger) =
+        (trigger("ai"), trigger("ad"), trigger("au"));
+
+    vec![
+        format!(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS {quoted_fts} USING fts5({names}, content='{content}')"
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {insert_trigger} AFTER INSERT ON {table} BEGIN \
+             INSERT INTO {quoted_fts}(rowid, {names}) VALUES (new.rowid, {new}); END"
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {delete_trigger} AFTER DELETE ON {table} BEGIN \
+             INSERT INTO {quoted_fts}({quoted_fts}, rowid, {names}) VALUES ('delete', old.rowid, {old}); END"
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {update_trigger} AFTER UPDATE ON {table} BEGIN \
+             INSERT INTO {quoted_fts}({quoted_fts}, rowid, {names}) VALUES ('delete', old.rowid, {old}); \
+             INSERT INTO {quoted_fts}(rowid, {names}) VALUES (new.rowid, {new}); END"
+        ),
+    ]
+}
+
+fn build_rebuild_sql(fts: &str) -> String {
+    let fts = quote(fts);
+    format!("INSERT INTO {fts}({fts}) VALUES ('rebuild')")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_sql_creates_table_and_triggers() {
+        let sql = build_table_sql(
+            "articles",
+            "articles_fts",
+            &["title".to_string(), "content".to_string()],
+        );
+        assert_eq!(sql.len(), 4);
+        assert_eq!(
+            sql[0],
+            "CREATE VIRTUAL TABLE IF NOT EXISTS \"articles_fts\" USING fts5(\"title\", \"content\", content='articles')"
+        );
+        assert_eq!(
+            sql[1],
+            "CREATE TRIGGER IF NOT EXISTS \"articles_fts_ai\" AFTER INSERT ON \"articles\" BEGIN \
+             INSERT INTO \"articles_fts\"(rowid, \"title\", \"content\") \
+             VALUES (new.rowid, new.\"title\", new.\"content\"); END"
+        );
+        assert!(sql[2].contains("VALUES ('delete', old.rowid, old.\"title\", old.\"content\")"));
+        assert!(sql[3].contains("AFTER UPDATE ON \"articles\""));
+        assert!(sql[3].contains("'delete', old.rowid"));
+        assert!(sql[3].contains("VALUES (new.rowid, new.\"title\", new.\"content\")"));
+    }
+
+    #[test]
+    fn test_table_sql_requires_columns() {
+        assert!(build_table_sql("articles", "articles_fts", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_identifiers_are_quoted() {
+        let sql = build_table_sql("it's", "a\"b", &["c".to_string()]);
+        assert!(sql[0].starts_with("CREATE VIRTUAL TABLE IF NOT EXISTS \"a\"\"b\""));
+        assert!(sql[0].ends_with("content='it''s')"));
+        assert_eq!(
+            build_rebuild_sql("a\"b"),
+            "INSERT INTO \"a\"\"b\"(\"a\"\"b\") VALUES ('rebuild')"
+        );
+    }
+}
@@ -0,0 +1,571 @@
+//! Startup index creation for filterable, sortable, and fulltext columns.
+//!
+//! Resources opt in with `#[crudcrate(auto_index)]`, then the application calls
+//! [`ensure_indexes`] once at startup:
+//!
+//! ```rust,ignore
+//! crudcrate::database::ensure_indexes::<Todo>(&db).await?;
+//! ```
+//!
+//! Index types per backend:
+//!
+//! | Backend | Filterable / sortable | Fulltext |
+//! |---------|-----------------------|----------|
+//! | `PostgreSQL` | B-tree | GIN over `to_tsvector(FULLTEXT_LANGUAGE, ...)`, or over the `tsvector_column` |
+//! | `MySQL` | B-tree | `FULLTEXT` (over the `fulltext(mode = "boolean")` columns if any) |
+//! | `SQLite` | B-tree | B-tree per column, plus the `fts5_table` if set |
+//!
+//! `unique_together` constraints get a unique index over their columns, with their
+//! `condition` as a partial index on `PostgreSQL` and `SQLite`. `MySQL` has no
+//! partial indexes, so partial constraints are only checked by the handlers there.
+//!
+//! Columns marked `ci` are indexed the way their filters compare them (see
+//! [`collation`](crate::filtering::collation)): `(CAST(col AS CITEXT))` on `PostgreSQL`,
+//! which needs the `citext` extension created here, and `(col COLLATE NOCASE)` on
+//! `SQLite`. `MySQL` keeps the plain B-tree.
+
+use crate::core::CRUDResource;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DbErr, IdenStatic, Statement};
+
+/// Kind of index planned for a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexKind {
+    /// Plain single-column B-tree index
+    BTree,
+    /// Backend-specific fulltext index (GIN tsvector on Postgres, FULLTEXT on `MySQL`)
+    Fulltext,
+    /// Unique index of a `unique_together` constraint
+    Unique,
+}
+
+/// A single index that [`ensure_indexes`] will create if missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedIndex {
+    pub name: String,
+    pub kind: IndexKind,
+    pub columns: Vec<String>,
+    /// `CREATE INDEX` statement for the target backend
+    pub sql: String,
+}
+
+/// Plan the indexes for a resource without touching the database.
+///
+/// Filterable and sortable columns each get a B-tree index (deduplicated, primary key
+/// skipped), built on the case-insensitive collation for `ci` columns. Fulltext columns
+/// get a single combined index where the backend supports one. `unique_together`
+/// constraints get a unique index each.
+#[must_use]
+pub fn plan_indexes<T: CRUDResource>(backend: DatabaseBackend) -> Vec<PlannedIndex> {
+    let id_column = T::ID_COLUMN.as_str().to_string();
+
+    let btree_columns: Vec<String> = T::filterable_columns()
+        .into_iter()
+        .chain(T::sortable_columns())
+        .map(|(_, column)| column.as_str().to_string())
+        .collect();
+
+    // `MATCH ... AGAINST` needs a FULLTEXT index over exactly the boolean-mode columns
+    let boolean_columns = T::boolean_fulltext_columns();
+    let fulltext_columns: Vec<String> =
+        if backend == DatabaseBackend::MySql && !boolean_columns.is_empty() {
+            boolean_columns
+        } else {
+            T::fulltext_searchable_columns()
+        }
+        .into_iter()
+        .map(|(_, column)| column.as_str().to_string())
+        .collect();
+
+    let ci_fields = T::case_insensitive_columns();
+    let ci_columns: Vec<String> = T::filterable_columns()
+        .into_iter()
+        .filter(|(name, _)| ci_fields.contains(name))
+        .map(|(_, column)| column.as_str().to_string())
+        .collect();
+
+    let mut plan = build_index_plan(
+        T::TABLE_NAME,
+        &id_column,
+        &btree_columns,
+        &fulltext_columns,
+        T::FULLTEXT_LANGUAGE,
+        T::TSVECTOR_COLUMN,
+        backend,
+    );
+    use_ci_collation(&mut plan, T::TABLE_NAME, &ci_columns, backend);
+    plan.extend(unique_indexes::<T>(backend));
+    plan
+}
+
+/// `CREATE UNIQUE INDEX` statements of `T`'s `unique_together` constraints, for
+/// migrations. Partial constraints are left out on `MySQL`, which can't index them.
+#[must_use]
+pub fn unique_index_sql<T: CRUDResource>(backend: DatabaseBackend) -> Vec<String> {
+    unique_indexes::<T>(backend)
+        .into_iter()
+        .map(|index| index.sql)
+        .collect()
+}
+
+fn unique_indexes<T: CRUDResource>(backend: DatabaseBackend) -> Vec<PlannedIndex> {
+    T::unique_together()
+        .into_iter()
+        .filter_map(|constraint| {
+            let columns: Vec<String> = constraint
+                .fields
+                .iter()
+                .map(|(_, column)| column.as_str().to_string())
+                .collect();
+            let sql = unique_sql(
+                &constraint.index_name(T::TABLE_NAME),
+                T::TABLE_NAME,
+                &columns,
+                constraint.condition,
+                backend,
+            )?;
+            Some(PlannedIndex {
+                name: constraint.index_name(T::TABLE_NAME),
+                kind: IndexKind::Unique,
+                columns,
+                sql,
+            })
+        })
+        .collect()
+}
+
+/// Create any missing indexes for `T`.
+///
+/// Does nothing unless the resource was declared with `#[crudcrate(auto_index)]`.
+/// Returns the names of the indexes that were created (or already present on
+/// backends that support `IF NOT EXISTS`).
+///
+/// # Errors
+///
+/// Returns a `DbErr` if an index statement or the `MySQL` existence check fails.
+pub async fn ensure_indexes<T: CRUDResource>(
+    db: &impl ConnectionTrait,
+) -> Result<Vec<String>, DbErr> {
+    if !T::AUTO_INDEX {
+        tracing::debug!(
+            resource = T::RESOURCE_NAME_PLURAL,
+            "Skipping index creation: resource is not marked auto_index"
+        );
+        return Ok(vec![]);
+    }
+
+    let backend = db.get_database_backend();
+    let mut created = Vec::new();
+
+    // The GIN index on a tsvector column needs the generated column to exist first
+    if backend == DatabaseBackend::Postgres
+        && let Some(sql) = super::tsvector_column_sql::<T>()
+    {
+        db.execute_unprepared(&sql).await?;
+    }
+
+    // Indexes on `ci` columns cast to CITEXT, which ships as an extension
+    if backend == DatabaseBackend::Postgres && !T::case_insensitive_columns().is_empty() {
+        db.execute_unprepared("CREATE EXTENSION IF NOT EXISTS citext")
+            .await?;
+    }
+
+    // FTS5 tables replace LIKE scans for fulltext search on SQLite
+    super::ensure_fts5::<T>(db).await?;
+
+    for index in plan_indexes::<T>(backend) {
+        // MySQL has no `CREATE INDEX IF NOT EXISTS`, check information_schema first
+        if backend == DatabaseBackend::MySql
+            && mysql_index_exists(db, T::TABLE_NAME, &index.name).await?
+        {
+            continue;
+        }
+
+        db.execute_unprepared(&index.sql).await?;
+        tracing::info!(
+            table = T::TABLE_NAME,
+            index = %index.name,
+            "Ensured index"
+        );
+        created.push(index.name);
+    }
+
+    Ok(created)
+}
+
+async fn mysql_index_exists(
+    db: &impl ConnectionTrait,
+    table: &str,
+    index: &str,
+) -> Result<bool, DbErr> {
+    let stmt = Statement::from_sql_and_values(
+        DatabaseBackend::MySql,
+        "SELECT COUNT(*) FROM information_schema.statistics \
+         WHERE table_schema = DATABASE() AND table_name = ? AND index_name = ?",
+        [table.into(), index.into()],
+    );
+    let count: i64 = match db.query_one(stmt).await? {
+        Some(row) => row.try_get_by_index(0)?,
+        None => 0,
+    };
+    Ok(count > 0)
+}
+
+fn build_index_plan(
+    table: &str,
+    id_column: &str,
+    btree_columns: &[String],
+    fulltext_columns: &[String],
+    language: &str,
+    tsvector_column: Option<&str>,
+    backend: DatabaseBackend,
+) -> Vec<PlannedIndex> {
+    let mut plan: Vec<PlannedIndex> = Vec::new();
+
+    for column in btree_columns {
+        if column == id_column || plan.iter().any(|p| &p.columns[0] == column) {
+            continue;
+        }
+        let name = format!("idx_{table}_{column}");
+        let sql = btree_sql(&name, table, column, backend);
+        plan.push(PlannedIndex {
+            name,
+            kind: IndexKind::BTree,
+            columns: vec![column.clone()],
+            sql,
+        });
+    }
+
+    if fulltext_columns.is_empty() {
+        return plan;
+    }
+
+    match (backend, tsvector_column) {
+        (DatabaseBackend::Postgres, Some(column)) => {
+            plan.push(PlannedIndex {
+                name: super::tsvector::tsvector_index_name(table, column),
+                kind: IndexKind::Fulltext,
+                columns: vec![column.to_string()],
+                sql: super::tsvector::build_index_sql(table, column),
+            });
+        }
+        (DatabaseBackend::Postgres, None) => {
+            let name = format!("idx_{table}_fulltext");
+            let language = language.replace('\'', "''");
+            let document = fulltext_columns
+                .iter()
+                .map(|c| format!("COALESCE(\"{c}\"::text, '')"))
+                .collect::<Vec<_>>()
+                .join(" || ' ' || ");
+            let sql = format!(
+                "CREATE INDEX IF NOT EXISTS \"{name}\" ON \"{table}\" \
+                 USING GIN (to_tsvector('{language}', {document}))"
+            );
+            plan.push(PlannedIndex {
+                name,
+                kind: IndexKind::Fulltext,
+                columns: fulltext_columns.to_vec(),
+                sql,
+            });
+        }
+        (DatabaseBackend::MySql, _) => {
+            let name = format!("idx_{table}_fulltext");
+            let columns = fulltext_columns
+                .iter()
+                .map(|c| format!("`{c}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let sql = format!("CREATE FULLTEXT INDEX `{name}` ON `{table}` ({columns})");
+            plan.push(PlannedIndex {
+                name,
+                kind: IndexKind::Fulltext,
+                columns: fulltext_columns.to_vec(),
+                sql,
+            });
+        }
+        _ => {
+            // No native fulltext index without FTS virtual tables; index each column instead
+            for column in fulltext_columns {
+                if column == id_column || plan.iter().any(|p| &p.columns[0] == column) {
+                    continue;
+                }
+                let name = format!("idx_{table}_{column}");
+                let sql = btree_sql(&name, table, column, backend);
+                plan.push(PlannedIndex {
+                    name,
+                    kind: IndexKind::BTree,
+                    columns: vec![column.clone()],
+                    sql,
+                });
+            }
+        }
+    }
+
+    plan
+}
+
+/// The unique index over `columns`, partial when `condition` is set; `None` for
+/// partial indexes on `MySQL`
+fn unique_sql(
+    name: &str,
+    table: &str,
+    columns: &[String],
+    condition: Option<&str>,
+    backend: DatabaseBackend,
+) -> Option<String> {
+    match (backend, condition) {
+        (DatabaseBackend::MySql, Some(_)) => None,
+        (DatabaseBackend::MySql, None) => {
+            let columns: Vec<String> = columns.iter().map(|c| format!("`{c}`")).collect();
+            Some(format!(
+                "CREATE UNIQUE INDEX `{name}` ON `{table}` ({})",
+                columns.join(", ")
+            ))
+        }
+        (_, condition) => {
+            let columns: Vec<String> = columns.iter().map(|c| format!("\"{c}\"")).collect();
+            let filter = condition
+                .map(|sql| format!(" WHERE {sql}"))
+                .unwrap_or_default();
+            Some(format!(
+                "CREATE UNIQUE INDEX IF NOT EXISTS \"{name}\" ON \"{table}\" ({}){filter}",
+                columns.join(", ")
+            ))
+        }
+    }
+}
+
+fn btree_sql(name: &str, table: &str, column: &str, backend: DatabaseBackend) -> String {
+    match backend {
+        DatabaseBackend::MySql => format!("CREATE INDEX `{name}` ON `{table}` (`{column}`)"),
+        _ => format!("CREATE INDEX IF NOT EXISTS \"{name}\" ON \"{table}\" (\"{column}\")"),
+    }
+}
+
+/// Rebuild the B-tree indexes of `ci_columns` over the expression their filters compare
+/// (see [`crate::filtering::collation`])
+fn use_ci_collation(
+    plan: &mut [PlannedIndex],
+    table: &str,
+    ci_columns: &[String],
+    backend: DatabaseBackend,
+) {
+    for index in plan
+        .iter_mut()
+        .filter(|index| index.kind == IndexKind::BTree && ci_columns.contains(&index.columns[0]))
+    {
+        let (name, column) = (&index.name, &index.columns[0]);
+        index.sql = match backend {
+            DatabaseBackend::Postgres => format!(
+                "CREATE INDEX IF NOT EXISTS \"{name}\" ON \"{table}\" ((CAST(\"{column}\" AS CITEXT)))"
+            ),
+            DatabaseBackend::Sqlite => format!(
+                "CREATE INDEX IF NOT EXISTS \"{name}\" ON \"{table}\" (\"{column}\" COLLATE NOCASE)"
+            ),
+            DatabaseBackend::MySql => continue,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cols(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| (*s).to_string()).collect()
+    }
+
+    #[test]
+    fn test_btree_indexes_deduplicated_and_skip_primary_key() {
+        let plan = build_index_plan(
+            "todos",
+            "id",
+            &cols(&["id", "title", "priority", "title"]),
+            &[],
+            "english",
+            None,
+            DatabaseBackend::Sqlite,
+        );
+        let names: Vec<_> = plan.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["idx_todos_title", "idx_todos_priority"]);
+        assert!(plan.iter().all(|p| p.kind == IndexKind::BTree));
+        assert_eq!(
+            plan[0].sql,
+            "CREATE INDEX IF NOT EXISTS \"idx_todos_title\" ON \"todos\" (\"title\")"
+        );
+    }
+
+    #[test]
+    fn test_postgres_fulltext_uses_gin_tsvector() {
+        let plan = build_index_plan(
+            "articles",
+            "id",
+            &[],
+            &cols(&["title", "content"]),
+            "spanish",
+            None,
+            DatabaseBackend::Postgres,
+        );
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].kind, IndexKind::Fulltext);
+        assert!(plan[0].sql.contains("USING GIN (to_tsvector('spanish'"));
+        assert!(plan[0].sql.contains("COALESCE(\"title\"::text, '')"));
+        assert!(plan[0].sql.contains("COALESCE(\"content\"::text, '')"));
+    }
+
+    #[test]
+    fn test_mysql_fulltext_index() {
+        let plan = build_index_plan(
+            "articles",
+            "id",
+            &cols(&["status"]),
+            &cols(&["title", "content"]),
+            "english",
+            None,
+            DatabaseBackend::MySql,
+        );
+        assert_eq!(plan.len(), 2);
+        assert_eq!(
+            plan[0].sql,
+            "CREATE INDEX `idx_articles_status` ON `articles` (`status`)"
+        );
+        assert_eq!(
+            plan[1].sql,
+            "CREATE FULLTEXT INDEX `idx_articles_fulltext` ON `articles` (`title`, `content`)"
+        );
+    }
+
+    #[test]
+    fn test_sqlite_fulltext_falls_back_to_btree() {
+        let plan = build_index_plan(
+            "articles",
+            "id",
+            &cols(&["title"]),
+            &cols(&["title", "content"]),
+            "english",
+            None,
+            DatabaseBackend::Sqlite,
+        );
+        let names: Vec<_> = plan.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["idx_articles_title", "idx_articles_content"]);
+        assert!(plan.iter().all(|p| p.kind == IndexKind::BTree));
+    }
+
+    #[test]
+    fn test_postgres_fulltext_uses_tsvector_column() {
+        let plan = build_index_plan(
+            "articles",
+            "id",
+            &[],
+            &cols(&["title", "content"]),
+            "english",
+            Some("search_vector"),
+            DatabaseBackend::Postgres,
+        );
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].name, "idx_articles_search_vector");
+        assert_eq!(plan[0].columns, vec!["search_vector"]);
+        assert!(plan[0].sql.ends_with("USING GIN (\"search_vector\")"));
+    }
+
+    #[test]
+    fn test_language_is_quoted() {
+        let plan = build_index_plan(
+            "t",
+            "id",
+            &[],
+            &cols(&["a"]),
+            "eng'lish",
+            None,
+            DatabaseBackend::Postgres,
+        );
+        assert!(plan[0].sql.contains("'eng''lish'"));
+    }
+
+    #[test]
+    fn test_unique_indexes() {
+        let columns = cols(&["email", "org_id"]);
+        assert_eq!(
+            unique_sql(
+                "uq_users_email_org_id",
+                "users",
+                &columns,
+                None,
+                DatabaseBackend::Sqlite
+            )
+            .unwrap(),
+            "CREATE UNIQUE INDEX IF NOT EXISTS \"uq_users_email_org_id\" ON \"users\" (\"email\", \"org_id\")"
+        );
+        assert_eq!(
+            unique_sql(
+                "uq_users_email_org_id",
+                "users",
+                &columns,
+                Some("deleted_at IS NULL"),
+                DatabaseBackend::Postgres
+            )
+            .unwrap(),
+            "CREATE UNIQUE INDEX IF NOT EXISTS \"uq_users_email_org_id\" ON \"users\" (\"email\", \"org_id\") WHERE deleted_at IS NULL"
+        );
+        assert_eq!(
+            unique_sql(
+                "uq_users_email_org_id",
+                "users",
+                &columns,
+                None,
+                DatabaseBackend::MySql
+            )
+            .unwrap(),
+            "CREATE UNIQUE INDEX `uq_users_email_org_id` ON `users` (`email`, `org_id`)"
+        );
+        assert_eq!(
+            unique_sql(
+                "uq_users_email_org_id",
+                "users",
+                &columns,
+                Some("deleted_at IS NULL"),
+                DatabaseBackend::MySql
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ci_columns_use_collation_indexes() {
+        let ci = cols(&["email"]);
+        let plan_for = |backend| {
+            let mut plan = build_index_plan(
+                "users",
+                "id",
+                &cols(&["email", "status"]),
+                &[],
+                "english",
+                None,
+                backend,
+            );
+            use_ci_collation(&mut plan, "users", &ci, backend);
+            plan
+        };
+
+        let plan = plan_for(DatabaseBackend::Sqlite);
+        assert_eq!(
+            plan[0].sql,
+            "CREATE INDEX IF NOT EXISTS \"idx_users_email\" ON \"users\" (\"email\" COLLATE NOCASE)"
+        );
+        assert_eq!(
+            plan[1].sql,
+            "CREATE INDEX IF NOT EXISTS \"idx_users_status\" ON \"users\" (\"status\")"
+        );
+
+        let plan = plan_for(DatabaseBackend::Postgres);
+        assert_eq!(
+            plan[0].sql,
+            "CREATE INDEX IF NOT EXISTS \"idx_users_email\" ON \"users\" ((CAST(\"email\" AS CITEXT)))"
+        );
+
+        let plan = plan_for(DatabaseBackend::MySql);
+        assert_eq!(
+            plan[0].sql,
+            "CREATE INDEX `idx_users_email` ON `users` (`email`)"
+        );
+    }
+}
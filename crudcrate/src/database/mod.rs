@@ -1,2 +1,10 @@
 // Feature Group 5: Multi-Database Optimization
-// Database-specific features (reserved for future use)
+// Database-specific features
+
+pub mod fts5;
+pub mod indexes;
+pub mod tsvector;
+
+pub use fts5::{ensure_fts5, fts5_rebuild_sql, fts5_table_sql};
+pub use indexes::{IndexKind, PlannedIndex, ensure_indexes, plan_indexes, unique_index_sql};
+pub use tsvector::{tsvector_column_sql, tsvector_index_sql};
@@ -0,0 +1,101 @@
+//! `PostgreSQL` stored `tsvector` columns for fulltext search.
+//!
+//! With `#[crudcrate(tsvector_column = "search_vector")]`, fulltext queries match against a
+//! stored generated column instead of an ILIKE over every `fulltext` field. The column is
+//! not part of the Sea-ORM model; create it in a migration with [`tsvector_column_sql`] and
+//! index it with [`tsvector_index_sql`] (or let [`ensure_indexes`](super::ensure_indexes)
+//! do both for `auto_index` resources).
+//!
+//! Every `fulltext` field must be castable to `text` with an immutable cast (text, varchar,
+//! and enum columns are fine), since generated column expressions must be immutable.
+
+use crate::core::CRUDResource;
+use sea_orm::IdenStatic;
+
+/// `ALTER TABLE` statement adding the stored `tsvector` generated column for `T`.
+///
+/// Returns `None` if `T` has no `tsvector_column` or no `fulltext` fields.
+#[must_use]
+pub fn tsvector_column_sql<T: CRUDResource>() -> Option<String> {
+    let column = T::TSVECTOR_COLUMN?;
+    let sources: Vec<String> = T::fulltext_searchable_columns()
+        .into_iter()
+        .map(|(_, c)| c.as_str().to_string())
+        .collect();
+    build_column_sql(T::TABLE_NAME, column, &sources, T::FULLTEXT_LANGUAGE)
+}
+
+/// `CREATE INDEX` statement for a GIN index on the `tsvector` column of `T`.
+#[must_use]
+pub fn tsvector_index_sql<T: CRUDResource>() -> Option<String> {
+    let column = T::TSVECTOR_COLUMN?;
+    if T::fulltext_searchable_columns().is_empty() {
+        return None;
+    }
+    Some(build_index_sql(T::TABLE_NAME, column))
+}
+
+pub(crate) fn tsvector_index_name(table: &str, column: &str) -> String {
+    format!("idx_{table}_{column}")
+}
+
+pub(crate) fn build_index_sql(table: &str, column: &str) -> String {
+    let name = tsvector_index_name(table, column);
+    format!("CREATE INDEX IF NOT EXISTS \"{name}\" ON \"{table}\" USING GIN (\"{column}\")")
+}
+
+fn build_column_sql(
+    table: &str,
+    column: &str,
+    sources: &[String],
+    language: &str,
+) -> Option<String> {
+    if sources.is_empty() {
+        return None;
+    }
+    let language = language.replace('\'', "''");
+    let document = sources
+        .iter()
+        .map(|c| format!("COALESCE(\"{c}\"::text, '')"))
+        .collect::<Vec<_>>()
+        .join(" || ' ' || ");
+    Some(format!(
+        "ALTER TABLE \"{table}\" ADD COLUMN IF NOT EXISTS \"{column}\" tsvector \
+         GENERATED ALWAYS AS (to_tsvector('{language}'::regconfig, {document})) STORED"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_sql_combines_sources() {
+        let sql = build_column_sql(
+            "articles",
+            "search_vector",
+            &["title".to_string(), "content".to_string()],
+            "english",
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            "ALTER TABLE \"articles\" ADD COLUMN IF NOT EXISTS \"search_vector\" tsvector \
+             GENERATED ALWAYS AS (to_tsvector('english'::regconfig, \
+             COALESCE(\"title\"::text, '') || ' ' || COALESCE(\"content\"::text, ''))) STORED"
+        );
+    }
+
+    #[test]
+    fn test_column_sql_requires_sources() {
+        assert!(build_column_sql("articles", "search_vector", &[], "english").is_none());
+    }
+
+    #[test]
+    fn test_index_sql_uses_gin() {
+        assert_eq!(
+            build_index_sql("articles", "search_vector"),
+            "CREATE INDEX IF NOT EXISTS \"idx_articles_search_vector\" ON \"articles\" USING GIN (\"search_vector\")"
+        );
+    }
+}
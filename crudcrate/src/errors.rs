@@ -28,6 +28,7 @@ use axum::{
 use sea_orm::DbErr;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+#[cfg(feature = "openapi")]
 use utoipa::ToSchema;
 
 // ============================================================================
@@ -35,7 +36,8 @@ use utoipa::ToSchema;
 // ============================================================================
 
 /// A single failure in a batch operation
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
 pub struct BatchFailure {
     /// The index of the failed item in the original request (0-based)
     pub index: usize,
@@ -47,7 +49,8 @@ pub struct BatchFailure {
 ///
 /// Used when `?partial=true` is specified on batch endpoints.
 /// Returns HTTP 207 Multi-Status when some items succeed and some fail.
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
 pub struct BatchResult<T> {
     /// Items that were successfully processed
     pub succeeded: Vec<T>,
@@ -103,6 +106,28 @@ impl<T> Default for BatchResult<T> {
     }
 }
 
+/// Result of `DELETE /resource?filter=...`
+///
+/// With `?count_only=true`, `deleted` is 0 and nothing is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct FilterDeleteResult {
+    /// Rows matching the filter
+    pub matched: u64,
+    /// Rows deleted
+    pub deleted: u64,
+}
+
+/// Rows of a related table that keep a `restrict_delete` resource from being deleted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct Dependent {
+    /// Table holding the dependent rows
+    pub relation: String,
+    /// Number of rows referencing the resources being deleted
+    pub count: u64,
+}
+
 /// API error type with automatic logging and sanitized responses
 ///
 /// This enum provides different error types that map to appropriate HTTP status codes.
@@ -141,6 +166,14 @@ pub enum ApiError {
         message: String,
     },
 
+    /// 409 Conflict - Rows of related tables still reference the resource
+    Restricted {
+        /// User-facing error message
+        message: String,
+        /// Related tables and their referencing row counts, sent to the user
+        dependents: Vec<Dependent>,
+    },
+
     /// 422 Unprocessable Entity - Validation failed
     ValidationFailed {
         /// User-facing validation errors
@@ -240,6 +273,47 @@ impl ApiError {
         }
     }
 
+    /// Create a 409 Conflict error listing the rows that still reference `resource`
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// return Err(ApiError::restricted("customer", vec![Dependent { relation: "vehicles".into(), count: 2 }]));
+    /// ```
+    pub fn restricted(resource: impl Into<String>, dependents: Vec<Dependent>) -> Self {
+        let relations: Vec<&str> = dependents
+            .iter()
+            .map(|dependent| dependent.relation.as_str())
+            .collect();
+        Self::Restricted {
+            message: format!(
+                "Cannot delete {}: referenced by {}",
+                resource.into(),
+                relations.join(", ")
+            ),
+            dependents,
+        }
+    }
+
+    /// Create a 423 Locked error naming the protected rows of `resource` a delete was
+    /// refused for, as returned by `guard_delete` resources
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// return Err(ApiError::locked("invoice", &[invoice_id]));
+    /// ```
+    pub fn locked(resource: impl Into<String>, ids: &[uuid::Uuid]) -> Self {
+        let ids: Vec<String> = ids.iter().map(ToString::to_string).collect();
+        Self::custom(
+            StatusCode::LOCKED,
+            format!(
+                "Cannot delete locked {}: {}",
+                resource.into(),
+                ids.join(", ")
+            ),
+            None,
+        )
+    }
+
     /// Create a 422 Validation Failed error
     ///
     /// # Example
@@ -310,16 +384,15 @@ impl ApiError {
     // ============================================================================
 
     /// Get the HTTP status code for this error
-    fn status_code(&self) -> StatusCode {
+    pub(crate) fn status_code(&self) -> StatusCode {
         match self {
             Self::NotFound { .. } => StatusCode::NOT_FOUND,
             Self::BadRequest { .. } => StatusCode::BAD_REQUEST,
             Self::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
             Self::Forbidden { .. } => StatusCode::FORBIDDEN,
-            Self::Conflict { .. } => StatusCode::CONFLICT,
+            Self::Conflict { .. } | Self::Restricted { .. } => StatusCode::CONFLICT,
             Self::ValidationFailed { .. } => StatusCode::UNPROCESSABLE_ENTITY,
-            Self::Database { .. } => StatusCode::INTERNAL_SERVER_ERROR,
-            Self::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Database { .. } | Self::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             Self::Custom { status, .. } => *status,
         }
     }
@@ -334,10 +407,14 @@ impl ApiError {
                     format!("{resource} not found")
                 }
             }
-            Self::BadRequest { message } => message.clone(),
-            Self::Unauthorized { message } => message.clone(),
-            Self::Forbidden { message } => message.clone(),
-            Self::Conflict { message } => message.clone(),
+            Self::BadRequest { message }
+            | Self::Unauthorized { message }
+            | Self::Forbidden { message }
+            | Self::Conflict { message }
+            | Self::Restricted { message, .. }
+            | Self::Database { message, .. }
+            | Self::Internal { message, .. }
+            | Self::Custom { message, .. } => message.clone(),
             Self::ValidationFailed { errors } => {
                 if errors.len() == 1 {
                     errors[0].clone()
@@ -345,9 +422,6 @@ impl ApiError {
                     format!("Validation failed: {}", errors.join(", "))
                 }
             }
-            Self::Database { message, .. } => message.clone(),
-            Self::Internal { message, .. } => message.clone(),
-            Self::Custom { message, .. } => message.clone(),
         }
     }
 
@@ -355,7 +429,7 @@ impl ApiError {
     ///
     /// Uses the `tracing` crate - only logs if user has enabled tracing.
     /// No output if tracing is not configured.
-    fn log_internal(&self) {
+    pub(crate) fn log_internal(&self) {
         match self {
             Self::Database { internal, .. } => {
                 tracing::error!(
@@ -404,6 +478,12 @@ struct ErrorResponse {
     /// Optional list of validation errors
     #[serde(skip_serializing_if = "Option::is_none")]
     details: Option<Vec<String>>,
+    /// Rows blocking a delete
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependents: Option<Vec<Dependent>>,
+    /// ID of the request, for correlating the error with logs and other services
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
 }
 
 impl IntoResponse for ApiError {
@@ -418,10 +498,23 @@ impl IntoResponse for ApiError {
             Self::ValidationFailed { errors } => ErrorResponse {
                 error: "Validation failed".to_string(),
                 details: Some(errors.clone()),
+                dependents: None,
+                request_id: crate::telemetry::request_id(),
+            },
+            Self::Restricted {
+                message,
+                dependents,
+            } => ErrorResponse {
+                error: message.clone(),
+                details: None,
+                dependents: Some(dependents.clone()),
+                request_id: crate::telemetry::request_id(),
             },
             _ => ErrorResponse {
                 error: self.user_message(),
                 details: None,
+                dependents: None,
+                request_id: crate::telemetry::request_id(),
             },
         };
 
@@ -556,6 +649,28 @@ mod tests {
         assert_eq!(err.user_message(), "Email already exists");
     }
 
+    #[test]
+    fn test_restricted() {
+        let err = ApiError::restricted(
+            "customer",
+            vec![
+                Dependent {
+                    relation: "vehicles".to_string(),
+                    count: 2,
+                },
+                Dependent {
+                    relation: "invoices".to_string(),
+                    count: 1,
+                },
+            ],
+        );
+        assert_eq!(err.status_code(), StatusCode::CONFLICT);
+        assert_eq!(
+            err.user_message(),
+            "Cannot delete customer: referenced by vehicles, invoices"
+        );
+    }
+
     #[test]
     fn test_validation_failed_single_error() {
         let err = ApiError::validation_failed(vec!["Email is required".to_string()]);
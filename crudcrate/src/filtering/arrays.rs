@@ -0,0 +1,188 @@
+//! Filtering on Postgres array columns.
+//!
+//! Filterable fields typed `Vec<String>`, `Vec<i32>`, etc. map to Postgres array columns
+//! and accept two operators:
+//!
+//! ```text
+//! GET /posts?filter={"tags_contains": ["rust", "web"]}   # tags @> ARRAY['rust','web']
+//! GET /posts?filter={"tags_overlaps": ["rust", "go"]}    # tags && ARRAY['rust','go']
+//! GET /posts?filter={"tags": "rust"}                      # same as tags_contains
+//! ```
+//!
+//! A single value is treated as a one-element array. Values are bound as parameters and
+//! the array is cast to the column's element type, so GIN indexes on the column apply.
+//! Other comparison suffixes (`_gt`, `_neq`, ...) are rejected with 400, and so are array
+//! filters on backends other than `PostgreSQL`.
+
+use crate::errors::ApiError;
+use sea_orm::{
+    DatabaseBackend, Value,
+    sea_query::{Expr, SimpleExpr},
+};
+
+/// Build the condition for a filter on one of `T::array_filterable_columns()`.
+///
+/// Returns `Ok(None)` if `key` does not address an array column (present in
+/// `searchable_columns`), or if the value is `null` or an empty list, so the caller can
+/// apply its regular handling.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` for unsupported operators or value types, or if the
+/// database is not `PostgreSQL`.
+pub fn process_array_column_filter<T: crate::traits::CRUDResource>(
+    key: &str,
+    value: &serde_json::Value,
+    searchable_columns: &[(&str, impl sea_orm::ColumnTrait)],
+    backend: DatabaseBackend,
+) -> Result<Option<SimpleExpr>, ApiError> {
+    let array_columns = T::array_filterable_columns();
+    if array_columns.is_empty() {
+        return Ok(None);
+    }
+
+    let (field, operator) = if let Some(field) = key.strip_suffix("_contains") {
+        (field, "@>")
+    } else if let Some(field) = key.strip_suffix("_overlaps") {
+        (field, "&&")
+    } else if let Some((field, _)) = super::conditions::parse_comparison_operator(key) {
+        if array_columns.iter().any(|(name, _)| *name == field) {
+            return Err(ApiError::bad_request(format!(
+                "Array field '{field}' only supports '{field}_contains' and '{field}_overlaps' filters"
+            )));
+        }
+        return Ok(None);
+    } else {
+        (key, "@>")
+    };
+
+    let Some(element_type) = array_columns
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, element_type)| *element_type)
+    else {
+        return Ok(None);
+    };
+    let Some(column) = searchable_columns
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, column)| column.as_str())
+    else {
+        return Ok(None);
+    };
+
+    if value.is_null() {
+        return Ok(None);
+    }
+    if backend != DatabaseBackend::Postgres {
+        return Err(ApiError::bad_request(format!(
+            "Filtering on array field '{field}' requires PostgreSQL"
+        )));
+    }
+
+    let values = array_values(field, value)?;
+    if values.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(build_array_condition(
+        column,
+        operator,
+        element_type,
+        values,
+    )))
+}
+
+/// Convert a scalar or list filter value into bound array elements
+fn array_values(field: &str, value: &serde_json::Value) -> Result<Vec<Value>, ApiError> {
+    let items = match value {
+        serde_json::Value::Array(items) => items.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+
+    items
+        .iter()
+        .filter_map(|item| match item {
+            serde_json::Value::String(s) => {
+                let trimmed = s.trim();
+                if trimmed.is_empty() {
+                    None
+                } else if super::conditions::validate_field_value(trimmed) {
+                    Some(Ok(Value::from(trimmed.to_string())))
+                } else {
+                    Some(Err(ApiError::bad_request(format!(
+                        "Value for array field '{field}' is too long"
+                    ))))
+                }
+            }
+            serde_json::Value::Number(n) => n
+                .as_i64()
+                .map(Value::from)
+                .or_else(|| n.as_f64().map(Value::from))
+                .map(Ok),
+            serde_json::Value::Bool(b) => Some(Ok(Value::from(*b))),
+            _ => Some(Err(ApiError::bad_request(format!(
+                "Array field '{field}' filters take strings, numbers or booleans"
+            )))),
+        })
+        .collect()
+}
+
+/// `"col" @> ARRAY[?, ?]::element_type[]`
+fn build_array_condition(
+    column: &str,
+    operator: &str,
+    element_type: &str,
+    values: Vec<Value>,
+) -> SimpleExpr {
+    let placeholders = vec!["?"; values.len()].join(", ");
+    Expr::cust_with_values(
+        format!("\"{column}\" {operator} ARRAY[{placeholders}]::{element_type}[]"),
+        values,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_array_condition() {
+        let sql = format!(
+            "{:?}",
+            build_array_condition(
+                "tags",
+                "@>",
+                "text",
+                vec![Value::from("rust"), Value::from("web")]
+            )
+        );
+        assert!(sql.contains("\\\"tags\\\" @> ARRAY[?, ?]::text[]"), "{sql}");
+
+        let sql = format!(
+            "{:?}",
+            build_array_condition("scores", "&&", "integer", vec![Value::from(3_i64)])
+        );
+        assert!(
+            sql.contains("\\\"scores\\\" && ARRAY[?]::integer[]"),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_array_values() {
+        assert_eq!(
+            array_values("tags", &json!("rust")).unwrap(),
+            vec![Value::from("rust".to_string())]
+        );
+        assert_eq!(
+            array_values("tags", &json!([" a ", "", 2, true])).unwrap(),
+            vec![
+                Value::from("a".to_string()),
+                Value::from(2_i64),
+                Value::from(true)
+            ]
+        );
+        assert!(array_values("tags", &json!([{"nested": 1}])).is_err());
+        assert!(array_values("tags", &json!(["x".repeat(10_001)])).is_err());
+    }
+}
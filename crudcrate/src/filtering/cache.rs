@@ -0,0 +1,254 @@
+//! Cache of compiled filter shapes.
+//!
+//! List endpoints see the same few filter shapes over and over, such as
+//! `{"status": ..., "priority_gte": ...}` with different values. How each key resolves
+//! (operator suffix, searchable column, and whether the field is an enum, decimal,
+//! `time`, `ci` or `LIKE` field) depends only on the key, so
+//! [`apply_filters`](super::apply_filters) and
+//! [`apply_filters_with_joins`](super::apply_filters_with_joins) compile the set of
+//! keys once per resource and searchable columns, and only read the values per request.
+//!
+//! Keys that another filter kind may claim depending on the value (JSON paths, joined
+//! fields, array, date range and geo filters) are not compiled and go through the full
+//! lookup on every request. The cache holds up to [`MAX_CACHED_SHAPES`] shapes and
+//! starts over when full, so clients sending random keys can't grow it without bound.
+//!
+//! With the `debug` feature, `crudcrate::debug::filter_cache_stats` reports hits and misses.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use sea_orm::ColumnTrait;
+
+use super::conditions::{is_valid_field_name, parse_comparison_operator};
+use crate::traits::CRUDResource;
+
+/// Number of filter shapes kept before the cache is cleared
+pub const MAX_CACHED_SHAPES: usize = 1024;
+
+static SHAPES: RwLock<BTreeMap<String, Arc<CompiledFilter>>> = RwLock::new(BTreeMap::new());
+
+#[cfg(feature = "debug")]
+static HITS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+#[cfg(feature = "debug")]
+static MISSES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Routes of a filter's keys, in the order of the keys it was compiled from
+#[derive(Debug)]
+pub(crate) struct CompiledFilter {
+    pub(crate) routes: Vec<Route>,
+}
+
+/// How the value of one filter key becomes a condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Route {
+    /// Handled before the per-key loop (`q` is the fulltext search)
+    Skip,
+    /// A comparison on one of the searchable columns
+    Column(ColumnClause),
+    /// Resolved on every request by the full lookup
+    Dispatch,
+}
+
+/// A filter key resolved to a searchable column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ColumnClause {
+    /// Position of the column in the searchable columns
+    pub(crate) column: usize,
+    /// Field name without the operator suffix
+    pub(crate) base_field: String,
+    /// SQL operator from the suffix (`=` without one, `BETWEEN` for `_between`, `LIKE`
+    /// for `_like`)
+    pub(crate) operator: &'static str,
+    pub(crate) is_enum: bool,
+    pub(crate) is_decimal: bool,
+    pub(crate) matching: TextMatch,
+    /// Kind of a `time` crate date/time column
+    #[cfg(feature = "time")]
+    pub(crate) time_kind: Option<super::dates::DateKind>,
+}
+
+/// How string values compare against a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TextMatch {
+    /// `UPPER(col) op UPPER(value)`
+    Upper,
+    /// `UPPER(col) LIKE '%VALUE%'` for equality, `Upper` otherwise
+    Like,
+    /// Through the case-insensitive collation (`ci` fields)
+    Collation,
+}
+
+/// Hit and miss counts of the filter cache since startup (requires the `debug` feature)
+#[cfg(feature = "debug")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct FilterCacheStats {
+    /// Filters whose shape was already compiled
+    pub hits: u64,
+    /// Filters compiled and added to the cache
+    pub misses: u64,
+    /// Shapes currently cached
+    pub shapes: usize,
+}
+
+/// Current hit and miss counts of the filter cache
+#[cfg(feature = "debug")]
+#[must_use]
+pub fn filter_cache_stats() -> FilterCacheStats {
+    use std::sync::atomic::Ordering;
+    FilterCacheStats {
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        shapes: SHAPES.read().unwrap_or_else(PoisonError::into_inner).len(),
+    }
+}
+
+/// The routes of `keys` for `T`, compiled on the first request with this shape
+pub(crate) fn compile<T: CRUDResource>(
+    keys: &[&str],
+    searchable_columns: &[(&str, impl ColumnTrait)],
+) -> Arc<CompiledFilter> {
+    let shape = shape_key(
+        std::any::type_name::<T>(),
+        searchable_columns.iter().map(|(name, _)| *name),
+        keys,
+    );
+
+    let cached = SHAPES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&shape)
+        .cloned();
+    if let Some(compiled) = cached {
+        record(true);
+        return compiled;
+    }
+    record(false);
+
+    let compiled = Arc::new(CompiledFilter {
+        routes: keys
+            .iter()
+            .map(|key| route::<T>(key, searchable_columns))
+            .collect(),
+    });
+    let mut shapes = SHAPES.write().unwrap_or_else(PoisonError::into_inner);
+    if shapes.len() >= MAX_CACHED_SHAPES {
+        shapes.clear();
+    }
+    shapes.insert(shape, Arc::clone(&compiled));
+    compiled
+}
+
+#[cfg(feature = "debug")]
+fn record(hit: bool) {
+    let counter = if hit { &HITS } else { &MISSES };
+    counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[cfg(not(feature = "debug"))]
+const fn record(_hit: bool) {}
+
+/// Resource, searchable columns and keys, each length-prefixed so no two shapes collide
+fn shape_key<'a>(resource: &str, columns: impl Iterator<Item = &'a str>, keys: &[&str]) -> String {
+    let mut shape = format!("{}:{resource}|", resource.len());
+    for column in columns {
+        let _ = write!(shape, "{}:{column}", column.len());
+    }
+    shape.push('|');
+    for key in keys {
+        let _ = write!(shape, "{}:{key}", key.len());
+    }
+    shape
+}
+
+/// Route of one key. Keys a JSON, joined, array, date or geo filter might claim are
+/// left to `Dispatch`.
+fn route<T: CRUDResource>(key: &str, searchable_columns: &[(&str, impl ColumnTrait)]) -> Route {
+    if key == "q" {
+        return Route::Skip;
+    }
+    if ["_within", "_contains", "_overlaps"]
+        .iter()
+        .any(|suffix| key.ends_with(suffix))
+    {
+        return Route::Dispatch;
+    }
+    let Some(clause) = column_clause::<T>(key, searchable_columns) else {
+        return Route::Dispatch;
+    };
+
+    let field = clause.base_field.as_str();
+    let is_array = T::array_filterable_columns()
+        .iter()
+        .any(|(name, _)| *name == field);
+    // `_between` on a date column may be a whole-day range, depending on the values
+    let is_date_range = clause.operator == "BETWEEN"
+        && T::date_filterable_columns()
+            .iter()
+            .any(|(name, _)| *name == field);
+    if is_array || is_date_range {
+        return Route::Dispatch;
+    }
+    Route::Column(clause)
+}
+
+/// Resolve a `field` or `field_<op>` key to its searchable column
+pub(crate) fn column_clause<T: CRUDResource>(
+    key: &str,
+    searchable_columns: &[(&str, impl ColumnTrait)],
+) -> Option<ColumnClause> {
+    if !is_valid_field_name(key) {
+        return None;
+    }
+    // `_like` is only an operator after a column name, so it can't shadow other keys
+    let (base_field, operator) = match key.strip_suffix("_like") {
+        Some(base) if searchable_columns.iter().any(|(name, _)| *name == base) => (base, "LIKE"),
+        _ => parse_comparison_operator(key).unwrap_or((key, "=")),
+    };
+    let column = searchable_columns
+        .iter()
+        .position(|(name, _)| *name == base_field)?;
+
+    let matching = if T::case_insensitive_columns().contains(&base_field) {
+        TextMatch::Collation
+    } else if T::like_filterable_columns().contains(&base_field) {
+        TextMatch::Like
+    } else {
+        TextMatch::Upper
+    };
+    Some(ColumnClause {
+        column,
+        base_field: base_field.to_string(),
+        operator,
+        is_enum: T::is_enum_field(base_field),
+        is_decimal: T::decimal_filterable_columns().contains(&base_field),
+        matching,
+        #[cfg(feature = "time")]
+        time_kind: super::timestamps::time_kind::<T>(base_field),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape_keys_do_not_collide() {
+        let joined = shape_key("Todo", ["id"].into_iter(), &["a,b"]);
+        let split = shape_key("Todo", ["id"].into_iter(), &["a", "b"]);
+        assert_ne!(joined, split);
+
+        let moved = shape_key("Todo", ["id", "a"].into_iter(), &["b"]);
+        let kept = shape_key("Todo", ["id"].into_iter(), &["a", "b"]);
+        assert_ne!(moved, kept);
+    }
+
+    #[test]
+    fn test_shape_key_depends_on_resource() {
+        assert_ne!(
+            shape_key("Todo", ["id"].into_iter(), &["title"]),
+            shape_key("Note", ["id"].into_iter(), &["title"])
+        );
+    }
+}
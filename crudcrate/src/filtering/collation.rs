@@ -0,0 +1,177 @@
+//! Case-insensitive comparisons for fields marked `#[crudcrate(ci)]`.
+//!
+//! String filters normally compare `UPPER(column)` against the uppercased value, and
+//! plain `{"field": "value"}` filters use `UPPER(column) LIKE '%VALUE%'`. Neither can
+//! use an index on the column. `ci` fields instead compare the exact value through
+//! the database's case-insensitive collation:
+//!
+//! | Backend | Condition | Index ([`ensure_indexes`](crate::database::ensure_indexes)) |
+//! |---------|-----------|-------|
+//! | `PostgreSQL` | `CAST(col AS CITEXT) = CAST(? AS CITEXT)` | `(CAST(col AS CITEXT))`; none needed if the column is `CITEXT` |
+//! | `SQLite` | `col COLLATE NOCASE = ?` | `(col COLLATE NOCASE)` |
+//! | `MySQL` | `col = ?` | B-tree on `col`, compared with its `_ci` collation |
+//!
+//! On `PostgreSQL` the `citext` extension must be installed; `ensure_indexes` creates
+//! it for resources with `ci` fields. On `MySQL` the column's collation decides, and
+//! the default `utf8mb4` collations are case-insensitive. `_like` filters keep
+//! substring matching.
+//!
+//! Sortable fields with `#[crudcrate(collate = "de-x-icu")]` are ordered through that
+//! collation on `PostgreSQL`, `ORDER BY "name" COLLATE "de-x-icu"`, so names with
+//! accents and other scripts sort the way readers of the locale expect. Any collation in
+//! `pg_collation` works, ICU ones (`-x-icu`) included. Other backends order by the
+//! column's own collation. Keyset pagination compares without the collation, so it
+//! orders such fields by the column's collation too.
+
+use sea_orm::{
+    ColumnTrait, DatabaseBackend, IdenStatic, Order,
+    sea_query::{Alias, Expr, ExprTrait, SimpleExpr},
+};
+
+use crate::CRUDResource;
+
+/// `column <operator> value` compared case-insensitively, for `=`, `!=`, `>`, `>=`,
+/// `<` and `<=`
+#[must_use]
+pub fn ci_comparison(
+    column: impl ColumnTrait,
+    operator: &str,
+    value: &str,
+    backend: DatabaseBackend,
+) -> SimpleExpr {
+    compare(Expr::col(column).into(), operator, value, backend)
+}
+
+/// `column IN (values)` compared case-insensitively
+#[must_use]
+pub fn ci_in(column: impl ColumnTrait, values: &[String], backend: DatabaseBackend) -> SimpleExpr {
+    is_in(Expr::col(column).into(), values, backend)
+}
+
+/// `ORDER BY column COLLATE "<collation>"` for a sortable column declared with
+/// `collate`, on `PostgreSQL`. `None` for other columns and backends, which keep the
+/// plain column order.
+#[must_use]
+pub fn collated_order<T: CRUDResource>(
+    column: T::ColumnType,
+    direction: Order,
+    backend: DatabaseBackend,
+) -> Option<(SimpleExpr, Order)> {
+    if backend != DatabaseBackend::Postgres {
+        return None;
+    }
+    let (name, _) = T::sortable_columns()
+        .into_iter()
+        .find(|(_, sortable)| sortable.as_str() == column.as_str())?;
+    let (_, collation) = T::sort_collations()
+        .into_iter()
+        .find(|(field, _)| *field == name)?;
+    Some((
+        collate(Expr::col((column.entity_name(), column)).into(), collation),
+        direction,
+    ))
+}
+
+/// The derive only accepts collation names of letters, digits, `_`, `-`, `.` and `@`,
+/// so quoting them is enough
+fn collate(column: SimpleExpr, collation: &str) -> SimpleExpr {
+    Expr::cust_with_expr(format!("$1 COLLATE \"{collation}\""), column)
+}
+
+fn compare(
+    column: SimpleExpr,
+    operator: &str,
+    value: &str,
+    backend: DatabaseBackend,
+) -> SimpleExpr {
+    let column = ci_column(column, backend);
+    let value = ci_value(value, backend);
+    match operator {
+        "!=" => column.ne(value),
+        ">=" => column.gte(value),
+        "<=" => column.lte(value),
+        ">" => column.gt(value),
+        "<" => column.lt(value),
+        _ => column.eq(value),
+    }
+}
+
+fn is_in(column: SimpleExpr, values: &[String], backend: DatabaseBackend) -> SimpleExpr {
+    ci_column(column, backend).is_in(values.iter().map(|value| ci_value(value, backend)))
+}
+
+fn ci_column(column: SimpleExpr, backend: DatabaseBackend) -> SimpleExpr {
+    match backend {
+        DatabaseBackend::Postgres => column.cast_as(Alias::new("CITEXT")),
+        // The collation of the left operand decides, and matches a NOCASE index
+        DatabaseBackend::Sqlite => Expr::cust_with_expr("? COLLATE NOCASE", column),
+        DatabaseBackend::MySql => column,
+    }
+}
+
+fn ci_value(value: &str, backend: DatabaseBackend) -> SimpleExpr {
+    match backend {
+        DatabaseBackend::Postgres => Expr::val(value).cast_as(Alias::new("CITEXT")),
+        _ => value.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::sea_query::{PostgresQueryBuilder, Query, SqliteQueryBuilder};
+
+    fn where_sql(expr: SimpleExpr, backend: DatabaseBackend) -> String {
+        let query = Query::select()
+            .column(Alias::new("email"))
+            .from(Alias::new("users"))
+            .and_where(expr)
+            .to_owned();
+        match backend {
+            DatabaseBackend::Postgres => query.to_string(PostgresQueryBuilder),
+            _ => query.to_string(SqliteQueryBuilder),
+        }
+    }
+
+    fn email() -> SimpleExpr {
+        Expr::col(Alias::new("email")).into()
+    }
+
+    #[test]
+    fn test_postgres_compares_as_citext() {
+        let backend = DatabaseBackend::Postgres;
+        let sql = where_sql(compare(email(), "=", "Ana@Example.com", backend), backend);
+        assert!(sql.contains(r#"CAST("email" AS CITEXT) ="#), "{sql}");
+        assert!(sql.contains("CAST('Ana@Example.com' AS CITEXT)"), "{sql}");
+        assert!(!sql.contains("UPPER"), "{sql}");
+    }
+
+    #[test]
+    fn test_postgres_orders_through_collation() {
+        let query = Query::select()
+            .column(Alias::new("name"))
+            .from(Alias::new("users"))
+            .order_by_expr(
+                collate(Expr::col(Alias::new("name")).into(), "de-x-icu"),
+                Order::Asc,
+            )
+            .to_string(PostgresQueryBuilder);
+        assert!(
+            query.ends_with(r#"ORDER BY "name" COLLATE "de-x-icu" ASC"#),
+            "{query}"
+        );
+    }
+
+    #[test]
+    fn test_sqlite_uses_nocase_collation() {
+        let backend = DatabaseBackend::Sqlite;
+        let values = ["a@x.io".to_string(), "B@x.io".to_string()];
+        let sql = where_sql(is_in(email(), &values, backend), backend);
+        assert!(sql.contains(r#""email" COLLATE NOCASE"#), "{sql}");
+        assert!(sql.contains("IN ('a@x.io', 'B@x.io')"), "{sql}");
+        assert!(!sql.contains("UPPER"), "{sql}");
+
+        let sql = where_sql(compare(email(), "!=", "a@x.io", backend), backend);
+        assert!(sql.contains("<> 'a@x.io'"), "{sql}");
+    }
+}
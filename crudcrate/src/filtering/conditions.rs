@@ -5,6 +5,8 @@ use sea_orm::{
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use super::cache::{ColumnClause, Route, TextMatch, column_clause};
+use super::like;
 use super::search::{build_fulltext_condition, build_like_condition};
 
 // Basic safety limits
@@ -24,17 +26,8 @@ const MAX_OFFSET: u64 = 1_000_000;
 /// response is worse than a failed request.
 const MAX_FILTER_CLAUSES: usize = 100;
 
-/// Escape LIKE wildcards to prevent wildcard injection attacks
-/// Escapes: % (match any) and _ (match single char)
-fn escape_like_wildcards(input: &str) -> String {
-    input
-        .replace('\\', "\\\\") // Escape backslash first
-        .replace('%', "\\%") // Escape %
-        .replace('_', "\\_") // Escape _
-}
-
 /// Basic field name validation
-fn is_valid_field_name(field_name: &str) -> bool {
+pub(super) fn is_valid_field_name(field_name: &str) -> bool {
     // Strengthen validation to prevent injection attempts (defense-in-depth)
     // Note: Actual field names are validated against a whitelist, but this adds an extra layer
     !field_name.is_empty()
@@ -45,13 +38,16 @@ fn is_valid_field_name(field_name: &str) -> bool {
 }
 
 /// Basic value length check
-const fn validate_field_value(value: &str) -> bool {
+pub(super) const fn validate_field_value(value: &str) -> bool {
     value.len() <= MAX_FIELD_VALUE_LENGTH
 }
 
 /// Parse React Admin comparison operator suffixes
 /// Returns (`base_field_name`, `sql_operator`) if a suffix is found
-fn parse_comparison_operator(field_name: &str) -> Option<(&str, &str)> {
+pub(super) fn parse_comparison_operator(field_name: &str) -> Option<(&str, &'static str)> {
+    if let Some(base_field) = field_name.strip_suffix("_between") {
+        return Some((base_field, "BETWEEN"));
+    }
     field_name.strip_suffix("_gte").map_or_else(
         || {
             field_name.strip_suffix("_lte").map_or_else(
@@ -93,6 +89,62 @@ where
     }
 }
 
+/// Nesting depth of a JSON document, counting string contents as opaque.
+///
+/// Scans brackets without parsing so oversized input is rejected before serde
+/// allocates anything for it.
+fn json_depth(input: &str) -> usize {
+    let (mut depth, mut max_depth) = (0usize, 0usize);
+    let (mut in_string, mut escaped) = (false, false);
+    for byte in input.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max_depth
+}
+
+/// Reject filters longer than [`max_filter_length`] or nested deeper than
+/// [`max_filter_depth`].
+///
+/// [`max_filter_length`]: crate::traits::CRUDResource::max_filter_length
+/// [`max_filter_depth`]: crate::traits::CRUDResource::max_filter_depth
+fn check_filter_limits<T: crate::traits::CRUDResource>(
+    filter_str: Option<&str>,
+) -> Result<(), crate::errors::ApiError> {
+    let Some(filter) = filter_str else {
+        return Ok(());
+    };
+    if filter.len() > T::max_filter_length() {
+        return Err(crate::errors::ApiError::bad_request(format!(
+            "Filter is too long (max {} bytes)",
+            T::max_filter_length()
+        )));
+    }
+    if json_depth(filter) > T::max_filter_depth() {
+        return Err(crate::errors::ApiError::bad_request(format!(
+            "Filter is nested too deeply (max depth {})",
+            T::max_filter_depth()
+        )));
+    }
+    Ok(())
+}
+
 fn parse_filter_json(
     filter_str: Option<String>,
 ) -> Result<HashMap<String, serde_json::Value>, crate::errors::ApiError> {
@@ -145,8 +197,8 @@ fn handle_fulltext_search<T: crate::traits::CRUDResource>(
         }
 
         // Fallback to original LIKE search on regular searchable columns
-        // Escape LIKE wildcards to prevent wildcard injection
-        let escaped_query = escape_like_wildcards(trimmed_q);
+        // LIKE wildcards in the query are escaped and matched literally
+        let upper_query = trimmed_q.to_uppercase();
 
         let mut or_conditions = Condition::any();
         for (col_name, col) in searchable_columns {
@@ -158,7 +210,7 @@ fn handle_fulltext_search<T: crate::traits::CRUDResource>(
                             SimpleExpr::FunctionCall(sea_orm::sea_query::Func::upper(
                                 Expr::cast_as(Expr::col(*col), Alias::new("TEXT")),
                             ))
-                            .like(format!("%{}%", escaped_query.to_uppercase())),
+                            .like(like::contains(&upper_query)),
                         );
                     }
                     _ => {
@@ -167,7 +219,7 @@ fn handle_fulltext_search<T: crate::traits::CRUDResource>(
                             SimpleExpr::FunctionCall(sea_orm::sea_query::Func::upper(Expr::col(
                                 *col,
                             )))
-                            .like(format!("%{}%", escaped_query.to_uppercase())),
+                            .like(like::contains(&upper_query)),
                         );
                     }
                 }
@@ -175,7 +227,7 @@ fn handle_fulltext_search<T: crate::traits::CRUDResource>(
                 // Regular string columns
                 or_conditions = or_conditions.add(
                     SimpleExpr::FunctionCall(sea_orm::sea_query::Func::upper(Expr::col(*col)))
-                        .like(format!("%{}%", escaped_query.to_uppercase())),
+                        .like(like::contains(&upper_query)),
                 );
             }
         }
@@ -203,12 +255,12 @@ fn apply_string_comparison(
 }
 
 fn process_string_filter<T: crate::traits::CRUDResource>(
-    base_field: &str,
-    operator: &str,
+    clause: &ColumnClause,
     string_value: &str,
     column: impl sea_orm::ColumnTrait + Copy,
     backend: DatabaseBackend,
 ) -> Option<SimpleExpr> {
+    let (base_field, operator) = (clause.base_field.as_str(), clause.operator);
     if !validate_field_value(string_value) {
         return None;
     }
@@ -218,17 +270,37 @@ fn process_string_filter<T: crate::traits::CRUDResource>(
         return None;
     }
 
+    // `ci` fields compare the exact value through a case-insensitive collation
+    if clause.matching == TextMatch::Collation {
+        return Some(super::collation::ci_comparison(
+            column,
+            operator,
+            trimmed_value,
+            backend,
+        ));
+    }
+
     // Check if this field should use LIKE queries (only for equality, not comparison operators)
-    if operator == "=" && T::like_filterable_columns().contains(&base_field) {
+    if operator == "=" && clause.matching == TextMatch::Like {
         return Some(build_like_condition(base_field, trimmed_value));
     }
 
-    if T::is_enum_field(base_field) {
-        // Handle enum fields with case-insensitive matching
+    if clause.is_enum {
         let col_expr = match backend {
             DatabaseBackend::Postgres => Expr::cast_as(Expr::col(column), Alias::new("TEXT")),
             _ => Expr::col(column).into(),
         };
+        if T::ENUM_CASE_SENSITIVE {
+            return Some(match operator {
+                "!=" => col_expr.ne(trimmed_value),
+                ">=" => col_expr.gte(trimmed_value),
+                "<=" => col_expr.lte(trimmed_value),
+                ">" => col_expr.gt(trimmed_value),
+                "<" => col_expr.lt(trimmed_value),
+                _ => col_expr.eq(trimmed_value),
+            });
+        }
+        // Handle enum fields with case-insensitive matching
         let col_upper = SimpleExpr::FunctionCall(sea_orm::sea_query::Func::upper(col_expr));
         let val_upper = trimmed_value.to_uppercase();
         return Some(match operator {
@@ -282,10 +354,135 @@ fn process_number_filter(
     None
 }
 
+/// Cast a bound decimal literal to the backend's exact numeric type
+pub(super) fn decimal_literal(literal: String, backend: DatabaseBackend) -> SimpleExpr {
+    let sql = match backend {
+        // Plain DECIMAL is DECIMAL(10,0) in MySQL, which would drop the fraction
+        DatabaseBackend::MySql => "CAST(? AS DECIMAL(65,30))",
+        _ => "CAST(? AS NUMERIC)",
+    };
+    Expr::cust_with_values(sql, [literal])
+}
+
+/// Parse a JSON number or numeric string as a decimal literal, without going through `f64`
+pub(super) fn parse_decimal_value(
+    field: &str,
+    value: &serde_json::Value,
+) -> Result<String, crate::errors::ApiError> {
+    let literal = match value {
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => s.trim().to_string(),
+        _ => String::new(),
+    };
+
+    let digits = literal.strip_prefix(['-', '+']).unwrap_or(&literal);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let is_valid = !whole.is_empty()
+        && whole.chars().all(|c| c.is_ascii_digit())
+        && fraction.chars().all(|c| c.is_ascii_digit())
+        && literal.len() <= 100;
+
+    if is_valid {
+        Ok(literal)
+    } else {
+        Err(crate::errors::ApiError::bad_request(format!(
+            "Invalid decimal value for '{field}'"
+        )))
+    }
+}
+
+/// Build a condition on a `Decimal` column.
+///
+/// Values may be JSON numbers or numeric strings (`"19.99"`) and are compared as exact
+/// numerics. Arrays produce `IN`, or `BETWEEN` with the `_between` operator.
+fn process_decimal_filter(
+    field: &str,
+    operator: &str,
+    value: &serde_json::Value,
+    column: impl sea_orm::ColumnTrait + Copy,
+    backend: DatabaseBackend,
+) -> Result<Option<SimpleExpr>, crate::errors::ApiError> {
+    let col = Expr::col(column);
+    Ok(Some(match (operator, value) {
+        (_, serde_json::Value::Null) => col.is_null(),
+        (_, serde_json::Value::Object(_)) => return Ok(None),
+        ("BETWEEN", _) => {
+            let (min, max) = between_bounds(field, value)?;
+            col.between(
+                decimal_literal(parse_decimal_value(field, min)?, backend),
+                decimal_literal(parse_decimal_value(field, max)?, backend),
+            )
+        }
+        (_, serde_json::Value::Array(items)) => {
+            if items.is_empty() {
+                return Ok(None);
+            }
+            let literals = items
+                .iter()
+                .map(|item| Ok(decimal_literal(parse_decimal_value(field, item)?, backend)))
+                .collect::<Result<Vec<_>, crate::errors::ApiError>>()?;
+            col.is_in(literals)
+        }
+        _ => {
+            let literal = decimal_literal(parse_decimal_value(field, value)?, backend);
+            match operator {
+                "!=" => col.ne(literal),
+                ">=" => col.gte(literal),
+                "<=" => col.lte(literal),
+                ">" => col.gt(literal),
+                "<" => col.lt(literal),
+                _ => col.eq(literal),
+            }
+        }
+    }))
+}
+
+/// Split a `[min, max]` filter value
+pub(super) fn between_bounds<'a>(
+    field: &str,
+    value: &'a serde_json::Value,
+) -> Result<(&'a serde_json::Value, &'a serde_json::Value), crate::errors::ApiError> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([min, max]) => Ok((min, max)),
+        _ => Err(crate::errors::ApiError::bad_request(format!(
+            "'{field}_between' expects a [min, max] array"
+        ))),
+    }
+}
+
+/// Build `column BETWEEN min AND max` for numbers or strings (e.g. ISO dates)
+fn process_between_filter(
+    field: &str,
+    value: &serde_json::Value,
+    column: impl sea_orm::ColumnTrait + Copy,
+) -> Result<SimpleExpr, crate::errors::ApiError> {
+    let (min, max) = between_bounds(field, value)?;
+    let bound = |item: &serde_json::Value| -> Result<sea_orm::Value, crate::errors::ApiError> {
+        match item {
+            serde_json::Value::Number(n) => n
+                .as_i64()
+                .map(sea_orm::Value::from)
+                .or_else(|| n.as_f64().map(sea_orm::Value::from))
+                .ok_or_else(|| {
+                    crate::errors::ApiError::bad_request(format!("Invalid bound for '{field}'"))
+                }),
+            serde_json::Value::String(s) if validate_field_value(s.trim()) => {
+                Ok(sea_orm::Value::from(s.trim().to_string()))
+            }
+            _ => Err(crate::errors::ApiError::bad_request(format!(
+                "'{field}_between' bounds must be numbers or strings"
+            ))),
+        }
+    };
+    Ok(Expr::col(column).between(bound(min)?, bound(max)?))
+}
+
 fn process_array_filter(
     array_values: &[serde_json::Value],
     column: impl sea_orm::ColumnTrait + Copy,
     is_enum: bool,
+    enum_case_sensitive: bool,
+    case_insensitive: bool,
     backend: DatabaseBackend,
 ) -> Option<SimpleExpr> {
     if array_values.is_empty() {
@@ -306,7 +503,7 @@ fn process_array_filter(
         break;
     }
 
-    if all_uuids && !uuid_values.is_empty() {
+    if all_uuids && !uuid_values.is_empty() && !case_insensitive {
         return Some(Expr::col(column).is_in(uuid_values));
     }
 
@@ -329,25 +526,102 @@ fn process_array_filter(
                 DatabaseBackend::Postgres => Expr::cast_as(Expr::col(column), Alias::new("TEXT")),
                 _ => Expr::col(column).into(),
             };
+            if enum_case_sensitive {
+                return Some(col_expr.is_in(in_values));
+            }
             let col_upper = SimpleExpr::FunctionCall(sea_orm::sea_query::Func::upper(col_expr));
             let upper_values: Vec<String> = in_values.iter().map(|v| v.to_uppercase()).collect();
             return Some(col_upper.is_in(upper_values));
         }
+        if case_insensitive {
+            return Some(super::collation::ci_in(column, &in_values, backend));
+        }
         return Some(Expr::col(column).is_in(in_values));
     }
     None
 }
 
+/// Condition for the value of a key resolved to a searchable column
+fn column_condition<T: crate::traits::CRUDResource>(
+    clause: &ColumnClause,
+    key: &str,
+    value: &serde_json::Value,
+    searchable_columns: &[(&str, impl sea_orm::ColumnTrait)],
+    backend: DatabaseBackend,
+) -> Result<Option<SimpleExpr>, crate::errors::ApiError> {
+    let column = searchable_columns[clause.column].1;
+    let base_field = clause.base_field.as_str();
+
+    // `_like` matches a substring of any column, before the type-specific comparisons
+    if clause.operator == "LIKE" {
+        return Ok(value
+            .as_str()
+            .map(str::trim)
+            .filter(|text| !text.is_empty() && validate_field_value(text))
+            .map(|text| build_like_condition(base_field, text)));
+    }
+
+    // Reject unknown enum variants instead of silently matching nothing
+    if clause.is_enum {
+        super::enums::validate_enum_filter::<T>(base_field, value)?;
+    }
+
+    // Decimal columns compare as exact numerics, including their `_between` ranges
+    if clause.is_decimal {
+        return process_decimal_filter(base_field, clause.operator, value, column, backend);
+    }
+    // `time` crate columns bind their values as typed dates and timestamps
+    #[cfg(feature = "time")]
+    if let Some(kind) = clause.time_kind
+        && let Some(expr) =
+            super::timestamps::time_condition(base_field, kind, clause.operator, value, column)?
+    {
+        return Ok(Some(expr));
+    }
+    if clause.operator == "BETWEEN" {
+        return process_between_filter(base_field, value, column).map(Some);
+    }
+
+    // Handle different value types
+    Ok(match value {
+        serde_json::Value::String(string_value) => {
+            process_string_filter::<T>(clause, string_value, column, backend)
+        }
+        serde_json::Value::Number(number) => {
+            process_number_filter(key, number, column, searchable_columns)
+        }
+        serde_json::Value::Bool(bool_value) => Some(Expr::col(column).eq(*bool_value)),
+        serde_json::Value::Array(array_values) => process_array_filter(
+            array_values,
+            column,
+            clause.is_enum,
+            T::ENUM_CASE_SENSITIVE,
+            clause.matching == TextMatch::Collation,
+            backend,
+        ),
+        serde_json::Value::Null => Some(Expr::col(column).is_null()),
+        serde_json::Value::Object(_) => None, // Skip unsupported value types
+    })
+}
+
+/// Filter keys in a stable order, the shape the filter cache is keyed by
+fn sorted_keys(filters: &HashMap<String, serde_json::Value>) -> Vec<&str> {
+    let mut keys: Vec<&str> = filters.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    keys
+}
+
 /// Build a Sea-ORM `Condition` from a JSON filter string.
 ///
 /// # Errors
-/// Returns `ApiError::BadRequest` if the filter contains more than
-/// [`MAX_FILTER_CLAUSES`] keys.
+/// Returns `ApiError::BadRequest` if the filter exceeds the resource's length or
+/// depth limit, or contains more than [`MAX_FILTER_CLAUSES`] keys.
 pub fn apply_filters<T: crate::traits::CRUDResource>(
     filter_str: Option<String>,
     searchable_columns: &[(&str, impl sea_orm::ColumnTrait)],
     backend: DatabaseBackend,
 ) -> Result<Condition, crate::errors::ApiError> {
+    check_filter_limits::<T>(filter_str.as_deref())?;
     let filters = parse_filter_json(filter_str)?;
     let mut condition = Condition::all();
 
@@ -358,50 +632,67 @@ pub fn apply_filters<T: crate::traits::CRUDResource>(
         condition = condition.add(fulltext_condition);
     }
 
-    // Process other filters (excluding 'q')
-    for (key, value) in &filters {
-        if key == "q" {
-            continue; // Skip fulltext search, already handled
+    // Process other filters; keys that resolve to a column skip the lookup below
+    let keys = sorted_keys(&filters);
+    let compiled = super::cache::compile::<T>(&keys, searchable_columns);
+    for (key, route) in keys.iter().copied().zip(&compiled.routes) {
+        let value = &filters[key];
+        // `filter_with` functions see the value before the built-in filters
+        if let Some(custom) = super::custom::custom_condition::<T>(key, value)? {
+            condition = condition.add(custom);
+            continue;
+        }
+        match route {
+            Route::Skip => continue,
+            Route::Column(clause) => {
+                if let Some(filter_expr) =
+                    column_condition::<T>(clause, key, value, searchable_columns, backend)?
+                {
+                    condition = condition.add(filter_expr);
+                }
+                continue;
+            }
+            Route::Dispatch => {}
         }
 
-        // Validate field name
-        if !is_valid_field_name(key) {
+        // Dot-path filters into JSON columns ("metadata.color")
+        if let Some(json_expr) =
+            super::json::process_json_filter::<T>(key, value, searchable_columns, backend)?
+        {
+            condition = condition.add(json_expr);
             continue;
         }
 
-        // Parse comparison operator to get base field name
-        // For "year_neq", this extracts "year" and stores the operator
-        let (base_field, operator) = parse_comparison_operator(key).unwrap_or((key, "="));
+        // Postgres array columns ("tags_contains", "tags_overlaps")
+        if let Some(array_expr) = super::arrays::process_array_column_filter::<T>(
+            key,
+            value,
+            searchable_columns,
+            backend,
+        )? {
+            condition = condition.add(array_expr);
+            continue;
+        }
 
-        // Find the column in searchable columns using the BASE field name
-        let column_opt = searchable_columns
-            .iter()
-            .find(|(col_name, _)| *col_name == base_field)
-            .map(|(_, col)| col);
-
-        if let Some(column) = column_opt {
-            // Handle different value types
-            let filter_condition = match value {
-                serde_json::Value::String(string_value) => {
-                    process_string_filter::<T>(base_field, operator, string_value, *column, backend)
-                }
-                serde_json::Value::Number(number) => {
-                    process_number_filter(key, number, *column, searchable_columns)
-                }
-                serde_json::Value::Bool(bool_value) => Some(Expr::col(*column).eq(*bool_value)),
-                serde_json::Value::Array(array_values) => process_array_filter(
-                    array_values,
-                    *column,
-                    T::is_enum_field(base_field),
-                    backend,
-                ),
-                serde_json::Value::Null => Some(Expr::col(*column).is_null()),
-                serde_json::Value::Object(_) => None, // Skip unsupported value types
-            };
+        // Calendar filters on date columns ("created_at_date", "created_at_year")
+        if let Some(date_expr) =
+            super::dates::process_date_filter::<T>(key, value, searchable_columns, backend)?
+        {
+            condition = condition.add(date_expr);
+            continue;
+        }
 
-            if let Some(filter_expr) = filter_condition {
-                condition = condition.add(filter_expr);
-            }
+        // Geo radius filters ("location_within") map to a spatial expression
+        if let Some(geo_expr) = super::geo::process_geo_filter::<T>(key, value, backend)? {
+            condition = condition.add(geo_expr);
+            continue;
+        }
+
+        if let Some(clause) = column_clause::<T>(key, searchable_columns)
+            && let Some(filter_expr) =
+                column_condition::<T>(&clause, key, value, searchable_columns, backend)?
+        {
+            condition = condition.add(filter_expr);
         }
     }
 
@@ -411,9 +702,7 @@ pub fn apply_filters<T: crate::traits::CRUDResource>(
 #[must_use]
 pub fn parse_range(range_str: Option<String>) -> (u64, u64) {
     range_str.map_or((0, 9), |r| {
-        serde_json::from_str::<[u64; 2]>(&r)
-            .map(|range| (range[0], range[1]))
-            .unwrap_or((0, 9))
+        serde_json::from_str::<[u64; 2]>(&r).map_or((0, 9), |range| (range[0], range[1]))
     })
 }
 
@@ -434,7 +723,10 @@ pub fn parse_pagination(params: &crate::models::FilterOptions) -> (u64, u64) {
     } else if let Some(range) = &params.range {
         // React Admin pagination
         let (start, end) = parse_range(Some(range.clone()));
-        let limit = (end.saturating_sub(start) + 1).min(MAX_PAGE_SIZE);
+        let limit = end
+            .saturating_sub(start)
+            .saturating_add(1)
+            .min(MAX_PAGE_SIZE);
         let safe_start = start.min(MAX_OFFSET);
         (safe_start, limit)
     } else {
@@ -459,8 +751,8 @@ pub fn parse_pagination(params: &crate::models::FilterOptions) -> (u64, u64) {
 /// Parse filters with support for dot-notation joined-entity filters.
 ///
 /// # Errors
-/// Returns `ApiError::BadRequest` if the filter contains more than
-/// [`MAX_FILTER_CLAUSES`] keys.
+/// Returns `ApiError::BadRequest` if the filter exceeds the resource's length or
+/// depth limit, or contains more than [`MAX_FILTER_CLAUSES`] keys.
 pub fn apply_filters_with_joins<T: crate::traits::CRUDResource>(
     filter_str: Option<String>,
     searchable_columns: &[(&str, impl sea_orm::ColumnTrait)],
@@ -468,6 +760,7 @@ pub fn apply_filters_with_joins<T: crate::traits::CRUDResource>(
 ) -> Result<super::joined::ParsedFilters, crate::errors::ApiError> {
     use super::joined::{JoinedFilter, ParsedFilters, parse_dot_notation};
 
+    check_filter_limits::<T>(filter_str.as_deref())?;
     let filters = parse_filter_json(filter_str)?;
     let mut result = ParsedFilters::default();
 
@@ -481,10 +774,40 @@ pub fn apply_filters_with_joins<T: crate::traits::CRUDResource>(
         result.main_condition = result.main_condition.add(fulltext_condition);
     }
 
-    // Process other filters
-    for (key, value) in &filters {
-        if key == "q" {
-            continue; // Skip fulltext search, already handled
+    // Process other filters; keys that resolve to a column skip the lookup below
+    let keys = sorted_keys(&filters);
+    let compiled = super::cache::compile::<T>(&keys, searchable_columns);
+    for (key, route) in keys.iter().copied().zip(&compiled.routes) {
+        let value = &filters[key];
+        if let Some(custom) = super::custom::custom_condition::<T>(key, value)? {
+            result.main_condition = result.main_condition.add(custom);
+            continue;
+        }
+        match route {
+            Route::Skip => continue,
+            Route::Column(clause) => {
+                if let Some(filter_expr) =
+                    column_condition::<T>(clause, key, value, searchable_columns, backend)?
+                {
+                    result.main_condition = result.main_condition.add(filter_expr);
+                }
+                continue;
+            }
+            Route::Dispatch => {}
+        }
+
+        // `vehicles__exists` and `vehicles__count_gte` on `Vec` join fields
+        if let Some(relation) = super::exists::parse_relation_filter::<T>(key, value)? {
+            result.relation_filters.push(relation);
+            continue;
+        }
+
+        // JSON column paths share the dot syntax, so resolve them before joins
+        if let Some(json_expr) =
+            super::json::process_json_filter::<T>(key, value, searchable_columns, backend)?
+        {
+            result.main_condition = result.main_condition.add(json_expr);
+            continue;
         }
 
         // Check if this is a dot-notation filter (e.g., "vehicles.make")
@@ -508,51 +831,83 @@ pub fn apply_filters_with_joins<T: crate::traits::CRUDResource>(
             continue;
         }
 
-        // Regular filter - validate field name and apply to main condition
-        if !is_valid_field_name(key) {
+        if let Some(array_expr) = super::arrays::process_array_column_filter::<T>(
+            key,
+            value,
+            searchable_columns,
+            backend,
+        )? {
+            result.main_condition = result.main_condition.add(array_expr);
             continue;
         }
 
-        // Parse comparison operator to get base field name
-        let (base_field, operator) = parse_comparison_operator(key).unwrap_or((key, "="));
+        if let Some(date_expr) =
+            super::dates::process_date_filter::<T>(key, value, searchable_columns, backend)?
+        {
+            result.main_condition = result.main_condition.add(date_expr);
+            continue;
+        }
 
-        // Find the column in searchable columns using the BASE field name
-        let column_opt = searchable_columns
-            .iter()
-            .find(|(col_name, _)| *col_name == base_field)
-            .map(|(_, col)| col);
-
-        if let Some(column) = column_opt {
-            // Handle different value types (same as apply_filters)
-            let filter_condition = match value {
-                serde_json::Value::String(string_value) => {
-                    process_string_filter::<T>(base_field, operator, string_value, *column, backend)
-                }
-                serde_json::Value::Number(number) => {
-                    process_number_filter(key, number, *column, searchable_columns)
-                }
-                serde_json::Value::Bool(bool_value) => Some(Expr::col(*column).eq(*bool_value)),
-                serde_json::Value::Array(array_values) => process_array_filter(
-                    array_values,
-                    *column,
-                    T::is_enum_field(base_field),
-                    backend,
-                ),
-                serde_json::Value::Null => Some(Expr::col(*column).is_null()),
-                serde_json::Value::Object(_) => None,
-            };
+        if let Some(geo_expr) = super::geo::process_geo_filter::<T>(key, value, backend)? {
+            result.main_condition = result.main_condition.add(geo_expr);
+            continue;
+        }
 
-            if let Some(filter_expr) = filter_condition {
-                result.main_condition = result.main_condition.add(filter_expr);
-            }
+        // Regular filter on a column of the main entity
+        if let Some(clause) = column_clause::<T>(key, searchable_columns)
+            && let Some(filter_expr) =
+                column_condition::<T>(&clause, key, value, searchable_columns, backend)?
+        {
+            result.main_condition = result.main_condition.add(filter_expr);
         }
     }
 
     Ok(result)
 }
 
+/// Build the condition for deleting by filter (`DELETE /resource?filter=...`).
+///
+/// Unlike listing, every key must narrow the condition: a key that listing would
+/// silently ignore (unknown column, filter on a related resource) widens a delete
+/// instead, so it is rejected.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if the filter is missing or empty, or if any key is
+/// not a filterable column of the resource itself.
+pub fn delete_filter_condition<T: crate::traits::CRUDResource>(
+    filter_str: Option<String>,
+    searchable_columns: &[(&str, impl sea_orm::ColumnTrait)],
+    backend: DatabaseBackend,
+) -> Result<Condition, crate::errors::ApiError> {
+    check_filter_limits::<T>(filter_str.as_deref())?;
+    let filters = parse_filter_json(filter_str.clone())?;
+    if filters.is_empty() {
+        return Err(crate::errors::ApiError::bad_request(
+            "Deleting by filter requires a non-empty 'filter'",
+        ));
+    }
+
+    for (key, value) in filters {
+        let single = serde_json::Value::Object(serde_json::Map::from_iter([(key.clone(), value)]));
+        let parsed =
+            apply_filters_with_joins::<T>(Some(single.to_string()), searchable_columns, backend)?;
+        if parsed.has_joined_filters || !parsed.relation_filters.is_empty() {
+            return Err(crate::errors::ApiError::bad_request(format!(
+                "Deleting by filter on related resources is not supported: '{key}'"
+            )));
+        }
+        if parsed.main_condition.is_empty() {
+            return Err(crate::errors::ApiError::bad_request(format!(
+                "Unknown or empty filter '{key}'"
+            )));
+        }
+    }
+    Ok(apply_filters_with_joins::<T>(filter_str, searchable_columns, backend)?.main_condition)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::like::escape_like as escape_like_wildcards;
     use super::*;
 
     /// Test that malicious field names are rejected
@@ -687,9 +1042,54 @@ mod tests {
         assert_eq!(parse_comparison_operator("age_gt"), Some(("age", ">")));
         assert_eq!(parse_comparison_operator("age_lt"), Some(("age", "<")));
         assert_eq!(parse_comparison_operator("age_neq"), Some(("age", "!=")));
+        assert_eq!(
+            parse_comparison_operator("age_between"),
+            Some(("age", "BETWEEN"))
+        );
         assert_eq!(parse_comparison_operator("age"), None);
     }
 
+    /// Test decimal literal parsing keeps the exact text
+    #[test]
+    fn test_parse_decimal_value() {
+        let parse = |value: serde_json::Value| parse_decimal_value("price", &value).ok();
+        assert_eq!(parse(serde_json::json!("19.99")), Some("19.99".to_string()));
+        assert_eq!(
+            parse(serde_json::json!(" -0.000000000000000001 ")),
+            Some("-0.000000000000000001".to_string())
+        );
+        assert_eq!(parse(serde_json::json!(42)), Some("42".to_string()));
+        assert_eq!(parse(serde_json::json!(2.5)), Some("2.5".to_string()));
+        for invalid in ["", "abc", "1.2.3", "1e5", ".5", "1; DROP TABLE x"] {
+            assert_eq!(parse(serde_json::json!(invalid)), None, "{invalid:?}");
+        }
+        assert_eq!(parse(serde_json::json!(true)), None);
+    }
+
+    /// Test decimal conditions cast bound values per backend
+    #[test]
+    fn test_decimal_literal_per_backend() {
+        let sql = format!(
+            "{:?}",
+            decimal_literal("19.99".to_string(), DatabaseBackend::Postgres)
+        );
+        assert!(sql.contains("CAST(? AS NUMERIC)"), "{sql}");
+        let sql = format!(
+            "{:?}",
+            decimal_literal("19.99".to_string(), DatabaseBackend::MySql)
+        );
+        assert!(sql.contains("CAST(? AS DECIMAL(65,30))"), "{sql}");
+    }
+
+    /// Test `_between` bounds validation
+    #[test]
+    fn test_between_bounds() {
+        assert!(between_bounds("year", &serde_json::json!([2000, 2010])).is_ok());
+        assert!(between_bounds("year", &serde_json::json!([2000])).is_err());
+        assert!(between_bounds("year", &serde_json::json!([1, 2, 3])).is_err());
+        assert!(between_bounds("year", &serde_json::json!("2000,2010")).is_err());
+    }
+
     /// Test wildcard escaping for LIKE queries
     #[test]
     fn test_escape_like_wildcards() {
@@ -791,6 +1191,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_json_depth() {
+        assert_eq!(json_depth(""), 0);
+        assert_eq!(json_depth(r#"{"a": 1}"#), 1);
+        assert_eq!(json_depth(r#"{"a": [1, 2], "b": {"c": [3]}}"#), 3);
+        assert_eq!(
+            json_depth(&format!("{}{}", "[".repeat(200), "]".repeat(200))),
+            200
+        );
+        // Brackets inside strings, including after escaped quotes, don't count
+        assert_eq!(json_depth(r#"{"a": "[[{{"}"#), 1);
+        assert_eq!(json_depth(r#"{"a": "\"[[\\", "b": ["x"]}"#), 2);
+    }
+
     /// Test comparison operators with edge cases
     #[test]
     fn test_comparison_operator_edge_cases() {
@@ -0,0 +1,96 @@
+//! User-defined filter conditions.
+//!
+//! A filterable field declared with `#[crudcrate(filter_with = path::to::function)]`
+//! hands the raw value of its filter key to that function before the built-in parsing.
+//! Returning `Ok(Some(condition))` uses the condition instead; `Ok(None)` lets the value
+//! through to the regular column filter:
+//!
+//! ```rust,ignore
+//! fn status_filter(value: &serde_json::Value) -> Result<Option<Condition>, ApiError> {
+//!     Ok((value == "overdue").then(|| {
+//!         Condition::all()
+//!             .add(Column::Status.ne("done"))
+//!             .add(Column::DueAt.lt(chrono::Utc::now()))
+//!     }))
+//! }
+//!
+//! #[crudcrate(filterable, filter_with = status_filter)]
+//! pub status: String,
+//! ```
+//!
+//! ```text
+//! GET /tasks?filter={"status":"overdue"}   # status_filter's condition
+//! GET /tasks?filter={"status":"open"}      # status = 'open'
+//! ```
+//!
+//! Resource-level virtual filters name a key that isn't a field at all, so clients can
+//! ask for business concepts without knowing the columns behind them:
+//!
+//! ```rust,ignore
+//! #[crudcrate(virtual_filter(
+//!     name = "active",
+//!     builder = active_condition,
+//!     description = "true for subscriptions that are paid up and not cancelled"
+//! ))]
+//! ```
+//!
+//! ```text
+//! GET /subscriptions?filter={"active":true}
+//! ```
+//!
+//! Virtual filters are listed with their description in the list endpoint's `OpenAPI`
+//! description and accepted by `strict_query` resources.
+//!
+//! Only the bare key is passed to the function; operator keys such as `status_neq` use
+//! the built-in filters. Errors are returned to the client as they are, so use
+//! `ApiError::bad_request` for values the function rejects.
+
+use sea_orm::Condition;
+
+use crate::errors::ApiError;
+
+/// A `filter_with` function: the raw filter value to an optional condition
+pub type FilterFn = fn(&serde_json::Value) -> Result<Option<Condition>, ApiError>;
+
+/// A filter key that isn't a column, declared with
+/// `#[crudcrate(virtual_filter(name = "...", builder = ...))]`
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualFilter {
+    /// The filter key clients use
+    pub name: &'static str,
+    /// What the filter selects, shown in the list endpoint's description
+    pub description: &'static str,
+    /// Builds the condition for the key's value
+    pub builder: FilterFn,
+}
+
+/// Whether `key` is one of `T`'s virtual filters
+#[must_use]
+pub fn is_virtual_filter<T: crate::traits::CRUDResource>(key: &str) -> bool {
+    T::virtual_filters().iter().any(|filter| filter.name == key)
+}
+
+/// The condition from the `filter_with` function or virtual filter registered for
+/// `key`, if any.
+///
+/// # Errors
+/// Returns the function's error.
+pub fn custom_condition<T: crate::traits::CRUDResource>(
+    key: &str,
+    value: &serde_json::Value,
+) -> Result<Option<Condition>, ApiError> {
+    let builder = T::custom_filters()
+        .into_iter()
+        .find(|(field, _)| *field == key)
+        .map(|(_, filter)| filter)
+        .or_else(|| {
+            T::virtual_filters()
+                .into_iter()
+                .find(|filter| filter.name == key)
+                .map(|filter| filter.builder)
+        });
+    match builder {
+        Some(builder) => builder(value),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,461 @@
+//! Calendar filters on date and timestamp columns.
+//!
+//! Filterable date/time fields accept whole-day and whole-year matches, so clients don't
+//! have to compute exact UTC bounds themselves:
+//!
+//! ```text
+//! GET /events?filter={"created_at_date": "2024-05-01"}             # any time that day (UTC)
+//! GET /events?filter={"created_at_date": "2024-05-01+02:00"}       # that day in UTC+2
+//! GET /events?filter={"created_at_year": 2024}
+//! GET /events?filter={"created_at_date_gte": "2024-05-01"}
+//! GET /events?filter={"created_at_between": ["2024-05-01", "2024-05-07"]}  # whole days
+//! ```
+//!
+//! Values may carry a UTC offset (`Z`, `+02:00`, `-05:30`), as in XML Schema's `xs:date`.
+//! The column is shifted by that offset and truncated with backend-specific SQL:
+//!
+//! | Backend | Day | Year |
+//! |---------|-----|------|
+//! | `PostgreSQL` | `CAST(col + make_interval(mins => ?) AS DATE)` | `EXTRACT(YEAR FROM ...)` |
+//! | `MySQL` | `DATE(DATE_ADD(col, INTERVAL ? MINUTE))` | `YEAR(...)` |
+//! | `SQLite` | `date(col, '+120 minutes')` | `strftime('%Y', col, ...)` |
+//!
+//! `_between` with full timestamps keeps the regular `BETWEEN` comparison.
+
+use crate::errors::ApiError;
+use sea_orm::{
+    DatabaseBackend, Value,
+    sea_query::{Expr, ExprTrait, SimpleExpr},
+};
+
+/// Storage type of a date/time column, which decides how it is truncated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateKind {
+    /// Calendar date without a time (`NaiveDate`)
+    Date,
+    /// Timestamp without a time zone, assumed to be UTC (`NaiveDateTime`)
+    Timestamp,
+    /// Timestamp with a time zone (`DateTime<Utc>`, `DateTimeWithTimeZone`)
+    TimestampTz,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DatePart {
+    Day,
+    Year,
+}
+
+/// A parsed `_date`/`_year` value: the literal to compare against and the UTC offset
+/// (in minutes) to view the column in
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CalendarValue {
+    literal: String,
+    offset_minutes: i32,
+}
+
+const MAX_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// `time` as a value of a `kind` column, in UTC. `time_crate` columns (see
+/// [`timestamps`](super::timestamps)) are bound as `time` values, others as chrono
+/// values; date columns get the UTC date.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if a `time` value can't be built.
+#[cfg_attr(
+    not(feature = "time"),
+    allow(clippy::unnecessary_wraps, unused_variables)
+)]
+pub(crate) fn instant_value(
+    field: &str,
+    kind: DateKind,
+    time_crate: bool,
+    time: chrono::DateTime<chrono::Utc>,
+) -> Result<SimpleExpr, ApiError> {
+    #[cfg(feature = "time")]
+    if time_crate {
+        let raw = match kind {
+            DateKind::Date => time.date_naive().to_string(),
+            DateKind::Timestamp | DateKind::TimestampTz => {
+                time.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+            }
+        };
+        return super::timestamps::time_value(field, kind, &raw).map(Into::into);
+    }
+    Ok(match kind {
+        DateKind::Date => Value::from(time.date_naive()).into(),
+        DateKind::Timestamp => Value::from(time.naive_utc()).into(),
+        DateKind::TimestampTz => Value::from(time).into(),
+    })
+}
+
+/// Build the condition for a `_date`, `_year` or date-only `_between` filter on one of
+/// `T::date_filterable_columns()`.
+///
+/// Returns `Ok(None)` if `key` is not a calendar filter on a filterable date column, so
+/// the caller can apply its regular handling.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if a date, year or UTC offset is malformed.
+pub fn process_date_filter<T: crate::traits::CRUDResource>(
+    key: &str,
+    value: &serde_json::Value,
+    searchable_columns: &[(&str, impl sea_orm::ColumnTrait)],
+    backend: DatabaseBackend,
+) -> Result<Option<SimpleExpr>, ApiError> {
+    let date_columns = T::date_filterable_columns();
+    if date_columns.is_empty() {
+        return Ok(None);
+    }
+
+    let (base, operator) = super::conditions::parse_comparison_operator(key).unwrap_or((key, "="));
+    let base_is_column = searchable_columns.iter().any(|(name, _)| *name == base);
+    let (field, part, is_shorthand) = if base_is_column {
+        // A real column named e.g. `model_year` wins over the `_year` suffix
+        if operator != "BETWEEN" {
+            return Ok(None);
+        }
+        (base, DatePart::Day, true)
+    } else if let Some(field) = base.strip_suffix("_date") {
+        (field, DatePart::Day, false)
+    } else if let Some(field) = base.strip_suffix("_year") {
+        (field, DatePart::Year, false)
+    } else {
+        return Ok(None);
+    };
+
+    let Some(kind) = date_columns
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, kind)| *kind)
+    else {
+        return Ok(None);
+    };
+    let Some(column) = searchable_columns
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, column)| column.as_str())
+    else {
+        return Ok(None);
+    };
+
+    // `created_at_between` with full timestamps stays a plain BETWEEN
+    if is_shorthand && !is_date_only_range(value) {
+        return Ok(None);
+    }
+
+    let column = DateColumn {
+        name: column,
+        kind,
+        backend,
+    };
+    build_calendar_condition(&column, field, part, operator, value)
+}
+
+/// A date/time column as seen by the truncation SQL
+struct DateColumn<'a> {
+    name: &'a str,
+    kind: DateKind,
+    backend: DatabaseBackend,
+}
+
+fn build_calendar_condition(
+    column: &DateColumn,
+    field: &str,
+    part: DatePart,
+    operator: &str,
+    value: &serde_json::Value,
+) -> Result<Option<SimpleExpr>, ApiError> {
+    let condition = |operator: &str, item: &serde_json::Value| -> Result<SimpleExpr, ApiError> {
+        let parsed = parse_calendar_value(field, part, item)?;
+        Ok(compare(
+            truncate(column, part, parsed.offset_minutes),
+            operator,
+            literal(column.backend, part, &parsed.literal),
+        ))
+    };
+
+    match (operator, value) {
+        ("BETWEEN", serde_json::Value::Array(items)) if items.len() == 2 => Ok(Some(
+            condition(">=", &items[0])?.and(condition("<=", &items[1])?),
+        )),
+        ("BETWEEN", _) => Err(ApiError::bad_request(format!(
+            "'{field}_between' expects a [min, max] array"
+        ))),
+        // Several values match any of them
+        (_, serde_json::Value::Array(items)) => Ok(items
+            .iter()
+            .map(|item| condition("=", item))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .reduce(ExprTrait::or)),
+        _ => condition(operator, value).map(Some),
+    }
+}
+
+fn is_date_only_range(value: &serde_json::Value) -> bool {
+    value.as_array().is_some_and(|items| {
+        items.len() == 2
+            && items.iter().all(|item| {
+                item.as_str()
+                    .is_some_and(|s| parse_date(split_offset(s.trim()).0).is_some())
+            })
+    })
+}
+
+fn parse_calendar_value(
+    field: &str,
+    part: DatePart,
+    value: &serde_json::Value,
+) -> Result<CalendarValue, ApiError> {
+    let invalid = || {
+        let expected = match part {
+            DatePart::Day => "a date like \"2024-05-01\" or \"2024-05-01+02:00\"",
+            DatePart::Year => "a year like 2024 or \"2024+02:00\"",
+        };
+        ApiError::bad_request(format!("Invalid value for '{field}': expected {expected}"))
+    };
+
+    let text = match value {
+        serde_json::Value::String(s) => s.trim().to_string(),
+        serde_json::Value::Number(n) if part == DatePart::Year => n.to_string(),
+        _ => return Err(invalid()),
+    };
+    let (body, offset) = split_offset(&text);
+    let offset_minutes = parse_offset(offset).ok_or_else(invalid)?;
+    let literal = match part {
+        DatePart::Day => parse_date(body),
+        DatePart::Year => parse_year(body).map(|year| year.to_string()),
+    }
+    .ok_or_else(invalid)?;
+
+    Ok(CalendarValue {
+        literal,
+        offset_minutes,
+    })
+}
+
+/// Split a trailing `Z`/`+HH:MM`/`-HH:MM` offset from a date or year
+fn split_offset(text: &str) -> (&str, &str) {
+    if let Some(body) = text.strip_suffix('Z') {
+        return (body, "Z");
+    }
+    // Checking for the colon keeps a date's own "-MM-DD" from reading as an offset
+    let bytes = text.as_bytes();
+    match bytes.len().checked_sub(6) {
+        Some(split) if matches!(bytes[split], b'+' | b'-') && bytes[split + 3] == b':' => {
+            text.split_at(split)
+        }
+        _ => (text, ""),
+    }
+}
+
+/// Offset in minutes; empty and `Z` mean UTC
+fn parse_offset(offset: &str) -> Option<i32> {
+    if offset.is_empty() || offset == "Z" {
+        return Some(0);
+    }
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let (hours, minutes) = offset.get(1..)?.split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    let total = hours * 60 + minutes;
+    (minutes < 60 && total <= MAX_OFFSET_MINUTES).then_some(sign * total)
+}
+
+/// Validate `YYYY-MM-DD`; the database checks days per month
+fn parse_date(text: &str) -> Option<String> {
+    let mut parts = text.split('-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    parse_year(year)?;
+    let month: u32 = month.parse().ok()?;
+    let day: u32 = day.parse().ok()?;
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then(|| text.to_string())
+}
+
+fn parse_year(text: &str) -> Option<i32> {
+    if text.len() != 4 || !text.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// The column's day or year as seen at `offset_minutes` from UTC
+fn truncate(column: &DateColumn, part: DatePart, offset_minutes: i32) -> SimpleExpr {
+    let name = column.name;
+    let is_date = column.kind == DateKind::Date;
+    match column.backend {
+        DatabaseBackend::Postgres => {
+            let local = match column.kind {
+                DateKind::Date => format!("\"{name}\""),
+                DateKind::Timestamp => format!("(\"{name}\" + make_interval(mins => ?))"),
+                DateKind::TimestampTz => {
+                    format!("((\"{name}\" AT TIME ZONE 'UTC') + make_interval(mins => ?))")
+                }
+            };
+            let sql = match part {
+                DatePart::Day => format!("CAST({local} AS DATE)"),
+                DatePart::Year => format!("CAST(EXTRACT(YEAR FROM {local}) AS INTEGER)"),
+            };
+            with_offset(sql, is_date, Value::from(offset_minutes))
+        }
+        DatabaseBackend::MySql => {
+            let local = if is_date {
+                format!("`{name}`")
+            } else {
+                format!("DATE_ADD(`{name}`, INTERVAL ? MINUTE)")
+            };
+            let sql = match part {
+                DatePart::Day => format!("DATE({local})"),
+                DatePart::Year => format!("YEAR({local})"),
+            };
+            with_offset(sql, is_date, Value::from(offset_minutes))
+        }
+        DatabaseBackend::Sqlite => {
+            let modifier = if is_date { "" } else { ", ?" };
+            let sql = match part {
+                DatePart::Day => format!("date(\"{name}\"{modifier})"),
+                DatePart::Year => format!("CAST(strftime('%Y', \"{name}\"{modifier}) AS INTEGER)"),
+            };
+            with_offset(
+                sql,
+                is_date,
+                Value::from(format!("{offset_minutes:+} minutes")),
+            )
+        }
+    }
+}
+
+fn with_offset(sql: String, is_date: bool, offset: Value) -> SimpleExpr {
+    if is_date {
+        Expr::cust(sql)
+    } else {
+        Expr::cust_with_values(sql, [offset])
+    }
+}
+
+fn literal(backend: DatabaseBackend, part: DatePart, literal: &str) -> SimpleExpr {
+    match part {
+        DatePart::Year => Expr::val(literal.parse::<i32>().unwrap_or_default()).into(),
+        DatePart::Day => match backend {
+            DatabaseBackend::Sqlite => Expr::val(literal).into(),
+            _ => Expr::cust_with_values("CAST(? AS DATE)", [literal]),
+        },
+    }
+}
+
+fn compare(lhs: SimpleExpr, operator: &str, rhs: SimpleExpr) -> SimpleExpr {
+    match operator {
+        "!=" => lhs.ne(rhs),
+        ">=" => lhs.gte(rhs),
+        "<=" => lhs.lte(rhs),
+        ">" => lhs.gt(rhs),
+        "<" => lhs.lt(rhs),
+        _ => lhs.eq(rhs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn value(text: &str, offset_minutes: i32) -> CalendarValue {
+        CalendarValue {
+            literal: text.to_string(),
+            offset_minutes,
+        }
+    }
+
+    #[test]
+    fn test_parse_day_values() {
+        let parse = |v: serde_json::Value| parse_calendar_value("at", DatePart::Day, &v).ok();
+        assert_eq!(parse(json!("2024-05-01")), Some(value("2024-05-01", 0)));
+        assert_eq!(parse(json!("2024-05-01Z")), Some(value("2024-05-01", 0)));
+        assert_eq!(
+            parse(json!("2024-05-01+02:00")),
+            Some(value("2024-05-01", 120))
+        );
+        assert_eq!(
+            parse(json!("2024-05-01-05:30")),
+            Some(value("2024-05-01", -330))
+        );
+        for invalid in [
+            json!("May 1st"),
+            json!("2024-5-1"),
+            json!("2024-13-01"),
+            json!("2024-05-01+25:00"),
+            json!("2024-05-01T10:00:00Z"),
+            json!(20_240_501),
+        ] {
+            assert_eq!(parse(invalid.clone()), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_year_values() {
+        let parse = |v: serde_json::Value| parse_calendar_value("at", DatePart::Year, &v).ok();
+        assert_eq!(parse(json!(2024)), Some(value("2024", 0)));
+        assert_eq!(parse(json!("2024")), Some(value("2024", 0)));
+        assert_eq!(parse(json!("2024+02:00")), Some(value("2024", 120)));
+        assert_eq!(parse(json!("2024-02:00")), Some(value("2024", -120)));
+        assert_eq!(parse(json!(24)), None);
+        assert_eq!(parse(json!("twenty")), None);
+    }
+
+    #[test]
+    fn test_truncation_sql_per_backend() {
+        let sql = |kind, backend, part| {
+            let column = DateColumn {
+                name: "created_at",
+                kind,
+                backend,
+            };
+            format!("{:?}", truncate(&column, part, 120))
+        };
+
+        let pg = sql(
+            DateKind::TimestampTz,
+            DatabaseBackend::Postgres,
+            DatePart::Day,
+        );
+        assert!(pg.contains("AT TIME ZONE 'UTC'"), "{pg}");
+        assert!(pg.contains("make_interval(mins => ?)"), "{pg}");
+        assert!(pg.contains("Int(Some(120))"), "{pg}");
+
+        let mysql = sql(DateKind::Timestamp, DatabaseBackend::MySql, DatePart::Year);
+        assert!(
+            mysql.contains("YEAR(DATE_ADD(`created_at`, INTERVAL ? MINUTE))"),
+            "{mysql}"
+        );
+
+        let sqlite = sql(
+            DateKind::TimestampTz,
+            DatabaseBackend::Sqlite,
+            DatePart::Day,
+        );
+        assert!(sqlite.contains("+120 minutes"), "{sqlite}");
+
+        // Plain dates are not shifted
+        let date = sql(DateKind::Date, DatabaseBackend::Postgres, DatePart::Day);
+        assert!(!date.contains("make_interval"), "{date}");
+    }
+
+    #[test]
+    fn test_date_only_range_detection() {
+        assert!(is_date_only_range(&json!([
+            "2024-05-01",
+            "2024-05-07+02:00"
+        ])));
+        assert!(!is_date_only_range(&json!([
+            "2024-05-01T00:00:00Z",
+            "2024-05-07T00:00:00Z"
+        ])));
+        assert!(!is_date_only_range(&json!([1, 2])));
+    }
+}
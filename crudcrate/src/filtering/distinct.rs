@@ -0,0 +1,245 @@
+//! Distinct values of a filterable column.
+//!
+//! Every generated router serves `GET /distinct/{field}`, which lists the values a
+//! filterable field takes among the rows matching the request's `filter`, so a UI can
+//! fill a dropdown without custom SQL:
+//!
+//! ```text
+//! GET /tasks/distinct/status?filter={"archived":false}
+//! [{"value":"done"},{"value":"open"}]
+//!
+//! GET /tasks/distinct/status?counts=true
+//! [{"value":"done","count":12},{"value":"open","count":3}]
+//! ```
+//!
+//! Values are ordered ascending and capped at the resource's
+//! [`max_page_size`](crate::CRUDResource::max_page_size). Numbers, booleans, UUIDs and
+//! strings keep their JSON type; other column types (dates, decimals, JSON) are
+//! returned as the database's text form.
+
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use sea_orm::{
+    ColumnTrait, ColumnType, Condition, ConnectionTrait, DatabaseBackend, DatabaseConnection,
+    Order, QueryFilter, QueryOrder, QueryResult, QuerySelect, QueryTrait,
+    sea_query::{Alias, Expr, SimpleExpr},
+};
+use serde::Serialize;
+use serde_json::json;
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
+
+use crate::core::crud_operations::check_required_scope;
+use crate::errors::ApiError;
+use crate::filtering::{DistinctOptions, FilterOptions};
+
+/// One distinct value, with the number of matching rows when counts were requested
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct DistinctValue {
+    /// The column value, `null` for rows without one
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub value: serde_json::Value,
+    /// Number of matching rows with this value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+}
+
+/// The distinct values of `column` among the rows matching `condition`, ordered
+/// ascending and capped at [`max_page_size`](crate::CRUDResource::max_page_size).
+///
+/// # Errors
+/// Returns `ApiError::Database` if the query fails or a value can't be decoded.
+pub async fn distinct_values<T: crate::traits::CRUDResource>(
+    db: &DatabaseConnection,
+    column: T::ColumnType,
+    condition: &Condition,
+    counts: bool,
+) -> Result<Vec<DistinctValue>, ApiError> {
+    let backend = db.get_database_backend();
+    let column_type = column.def().get_column_type().clone();
+    let column_expr: SimpleExpr = Expr::col((column.entity_name(), column)).into();
+
+    let mut query = crate::core::view::select::<T>().select_only().expr_as(
+        value_expr(column_expr.clone(), &column_type, backend),
+        "value",
+    );
+    if counts {
+        query = query.expr_as(Expr::cust("COUNT(*)"), "count");
+    }
+    let statement = query
+        .filter(condition.clone())
+        .group_by(column_expr.clone())
+        .order_by(column_expr, Order::Asc)
+        .limit(T::max_page_size())
+        .build(backend);
+
+    let rows = db.query_all(statement).await.map_err(ApiError::database)?;
+    rows.iter()
+        .map(|row| {
+            Ok(DistinctValue {
+                value: decode_value(row, &column_type)?,
+                count: if counts {
+                    Some(row.try_get("", "count").map_err(ApiError::database)?)
+                } else {
+                    None
+                },
+            })
+        })
+        .collect()
+}
+
+/// Columns without a JSON counterpart are selected as text
+fn value_expr(
+    column: SimpleExpr,
+    column_type: &ColumnType,
+    backend: DatabaseBackend,
+) -> SimpleExpr {
+    if has_json_type(column_type) {
+        return column;
+    }
+    let text = match backend {
+        DatabaseBackend::MySql => "CHAR",
+        _ => "TEXT",
+    };
+    column.cast_as(Alias::new(text))
+}
+
+fn has_json_type(column_type: &ColumnType) -> bool {
+    matches!(
+        column_type,
+        ColumnType::TinyInteger
+            | ColumnType::SmallInteger
+            | ColumnType::Integer
+            | ColumnType::BigInteger
+            | ColumnType::TinyUnsigned
+            | ColumnType::SmallUnsigned
+            | ColumnType::Unsigned
+            | ColumnType::BigUnsigned
+            | ColumnType::Float
+            | ColumnType::Double
+            | ColumnType::Boolean
+            | ColumnType::Uuid
+            | ColumnType::Char(_)
+            | ColumnType::String(_)
+            | ColumnType::Text
+    )
+}
+
+fn decode_value(
+    row: &QueryResult,
+    column_type: &ColumnType,
+) -> Result<serde_json::Value, ApiError> {
+    fn get<V: sea_orm::TryGetable + Serialize>(
+        row: &QueryResult,
+    ) -> Result<serde_json::Value, ApiError> {
+        let value: Option<V> = row.try_get("", "value").map_err(ApiError::database)?;
+        Ok(json!(value))
+    }
+
+    match column_type {
+        ColumnType::TinyInteger => get::<i8>(row),
+        ColumnType::SmallInteger => get::<i16>(row),
+        ColumnType::Integer => get::<i32>(row),
+        ColumnType::BigInteger => get::<i64>(row),
+        ColumnType::TinyUnsigned => get::<u8>(row),
+        ColumnType::SmallUnsigned => get::<u16>(row),
+        ColumnType::Unsigned => get::<u32>(row),
+        ColumnType::BigUnsigned => get::<u64>(row),
+        ColumnType::Float => get::<f32>(row),
+        ColumnType::Double => get::<f64>(row),
+        ColumnType::Boolean => get::<bool>(row),
+        ColumnType::Uuid => get::<uuid::Uuid>(row),
+        _ => get::<String>(row),
+    }
+}
+
+/// The filterable column clients call `field`, by its external or field name
+pub(crate) fn filterable_column<T: crate::traits::CRUDResource>(
+    field: &str,
+    filterable_columns: &[(&str, T::ColumnType)],
+) -> Result<T::ColumnType, ApiError> {
+    let name = T::field_aliases()
+        .into_iter()
+        .find(|(external, _)| *external == field)
+        .map_or(field, |(_, aliased)| aliased);
+    filterable_columns
+        .iter()
+        .find(|(filterable, _)| *filterable == name)
+        .map(|(_, column)| *column)
+        .ok_or_else(|| ApiError::bad_request(format!("Field '{field}' is not filterable")))
+}
+
+/// Body of the generated `get_distinct_handler`.
+///
+/// `params.filter` narrows the rows the same way as on the list endpoint. Scoped
+/// requests cannot list the resource's `scoped_excluded_columns`, nor filter on them.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `field` is not filterable or the filter is invalid
+/// (including unknown fields on `strict_query` resources), and `ApiError::Database` if
+/// the query fails.
+pub async fn distinct_response<T: crate::traits::CRUDResource>(
+    db: &DatabaseConnection,
+    field: &str,
+    params: FilterOptions,
+    options: DistinctOptions,
+    scope: Option<Condition>,
+) -> Result<Response, ApiError> {
+    check_required_scope::<T>(scope.is_some())?;
+
+    let params = super::translate_field_names::<T>(params);
+    let backend = db.get_database_backend();
+
+    let mut filterable_columns = T::filterable_columns();
+    if scope.is_some() {
+        let excluded = T::scoped_excluded_columns();
+        filterable_columns.retain(|(name, _)| !excluded.contains(name));
+    }
+    let column = filterable_column::<T>(field, &filterable_columns)?;
+
+    if T::STRICT_QUERY {
+        super::check_query_fields::<T>(&params, &filterable_columns, &[], backend)?;
+    }
+
+    let parsed = super::apply_filters_with_joins::<T>(params.filter, &filterable_columns, backend)?;
+    let mut condition = parsed
+        .main_condition
+        .add_option(super::exists::relation_condition::<T>(
+            &parsed.relation_filters,
+            scope.is_some(),
+        ));
+    if let Some(scope) = scope {
+        condition = condition.add(scope);
+    }
+    let condition = condition.add_option(T::read_condition());
+
+    let values = distinct_values::<T>(db, column, &condition, options.counts).await?;
+    Ok(Json(values).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_is_left_out_unless_requested() {
+        let plain = DistinctValue {
+            value: json!("open"),
+            count: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&plain).unwrap(),
+            json!({"value": "open"})
+        );
+
+        let counted = DistinctValue {
+            value: json!(null),
+            count: Some(3),
+        };
+        assert_eq!(
+            serde_json::to_value(&counted).unwrap(),
+            json!({"value": null, "count": 3})
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Enum filter validation.
+//!
+//! Filters on enum fields are checked against the variants reported by
+//! [`CRUDResource::enum_variants`](crate::traits::CRUDResource::enum_variants), so an
+//! unknown value returns `400 Bad Request` listing the allowed values instead of an
+//! empty result. Matching is case-insensitive unless the resource sets
+//! `#[crudcrate(enum_case_sensitive)]`.
+
+use crate::errors::ApiError;
+
+/// Convert an `ActiveEnum` database value to the string used in filters.
+///
+/// Used by derived `enum_variants()` implementations. Returns `None` for value types
+/// that have no sensible string form.
+#[must_use]
+pub fn enum_value_to_string(value: sea_orm::Value) -> Option<String> {
+    use sea_orm::Value;
+    match value {
+        Value::String(Some(s)) => Some(*s),
+        Value::Char(Some(c)) => Some(c.to_string()),
+        Value::TinyInt(Some(i)) => Some(i.to_string()),
+        Value::SmallInt(Some(i)) => Some(i.to_string()),
+        Value::Int(Some(i)) => Some(i.to_string()),
+        Value::BigInt(Some(i)) => Some(i.to_string()),
+        Value::TinyUnsigned(Some(i)) => Some(i.to_string()),
+        Value::SmallUnsigned(Some(i)) => Some(i.to_string()),
+        Value::Unsigned(Some(i)) => Some(i.to_string()),
+        Value::BigUnsigned(Some(i)) => Some(i.to_string()),
+        _ => None,
+    }
+}
+
+/// Check a filter value on an enum field against the field's variants.
+///
+/// Strings and arrays of strings are validated; other value types (and fields
+/// without known variants) pass through unchanged.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` listing the allowed values if any value is unknown.
+pub fn validate_enum_filter<T: crate::traits::CRUDResource>(
+    field: &str,
+    value: &serde_json::Value,
+) -> Result<(), ApiError> {
+    let Some(variants) = T::enum_variants(field) else {
+        return Ok(());
+    };
+
+    let candidates: Vec<&str> = match value {
+        serde_json::Value::String(s) => vec![s.trim()],
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter_map(serde_json::Value::as_str)
+            .map(str::trim)
+            .collect(),
+        _ => return Ok(()),
+    };
+
+    let is_known = |candidate: &str| {
+        if T::ENUM_CASE_SENSITIVE {
+            variants.iter().any(|v| v == candidate)
+        } else {
+            let upper = candidate.to_uppercase();
+            variants.iter().any(|v| v.to_uppercase() == upper)
+        }
+    };
+
+    // Empty strings are ignored by the filter builders, so don't reject them here
+    if candidates.iter().all(|c| c.is_empty() || is_known(c)) {
+        Ok(())
+    } else {
+        Err(ApiError::bad_request(format!(
+            "Invalid value for '{field}'. Allowed values: {}",
+            variants.join(", ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enum_value_to_string() {
+        assert_eq!(
+            enum_value_to_string(sea_orm::Value::from("Diesel")),
+            Some("Diesel".to_string())
+        );
+        assert_eq!(
+            enum_value_to_string(sea_orm::Value::from(3_i32)),
+            Some("3".to_string())
+        );
+        assert_eq!(enum_value_to_string(sea_orm::Value::Bool(Some(true))), None);
+        assert_eq!(enum_value_to_string(sea_orm::Value::String(None)), None);
+    }
+}
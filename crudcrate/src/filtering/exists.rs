@@ -0,0 +1,216 @@
+//! Existence and count filters on `has_many` joins.
+//!
+//! Every `Vec` join field of a resource accepts two filter keys, so clients can ask for
+//! parents by how many children they have without loading them:
+//!
+//! ```text
+//! GET /customers?filter={"vehicles__exists":false}      customers without vehicles
+//! GET /customers?filter={"vehicles__count_gte":2}       customers with two or more
+//! ```
+//!
+//! `__count` takes the `_neq`, `_gt`, `_gte`, `_lt` and `_lte` suffixes. Both compile to
+//! a grouped subquery over the child's foreign key, never loading the children:
+//!
+//! ```sql
+//! id IN (SELECT customer_id FROM vehicles WHERE customer_id IS NOT NULL
+//!        GROUP BY customer_id HAVING COUNT(*) >= 2)
+//! ```
+//!
+//! Tests that parents without children pass (`__exists: false`, `__count_lt: 2`) use
+//! `NOT IN` with the negated test instead. The child's
+//! [`read_condition`](crate::CRUDResource::read_condition) applies inside the subquery,
+//! and in scoped requests its [`ScopeFilterable`](crate::ScopeFilterable) condition too,
+//! so hidden children are not counted.
+
+use sea_orm::sea_query::{Alias, Asterisk, Expr, Func, Query, SelectStatement, SimpleExpr};
+use sea_orm::{ColumnTrait, Condition};
+
+use super::joined::FilterOperator;
+use crate::errors::ApiError;
+use crate::traits::CRUDResource;
+
+/// A `Vec` join field that can be filtered by existence and count of its rows.
+///
+/// Generated for each `Vec` join field by the derive macro.
+#[derive(Debug, Clone, Copy)]
+pub struct RelationFilterDef {
+    /// The join field name on the parent entity (e.g., "vehicles")
+    pub join_field: &'static str,
+    /// Table of the child entity
+    pub table: &'static str,
+    /// Column of the child table holding the parent's ID
+    pub foreign_key: &'static str,
+    /// The child's scope condition, applied in scoped requests
+    pub scope: fn() -> Option<Condition>,
+    /// The child's read condition, always applied
+    pub read: fn() -> Option<Condition>,
+}
+
+/// What a relation filter asks of a parent's children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationTest {
+    /// `{join}__exists`: whether the parent has any
+    Exists(bool),
+    /// `{join}__count[_op]`: how many it has
+    Count(FilterOperator, i64),
+}
+
+/// A parsed `{join}__exists` or `{join}__count[_op]` filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationFilter {
+    /// The join field name (e.g., "vehicles")
+    pub join_field: String,
+    pub test: RelationTest,
+}
+
+/// Parse `key` as a relation filter on one of `T`'s `Vec` join fields.
+///
+/// Returns `None` for keys that name no join field or operator, which the caller
+/// resolves as other filters.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `__exists` isn't given a boolean, or `__count` a
+/// non-negative integer.
+pub fn parse_relation_filter<T: CRUDResource>(
+    key: &str,
+    value: &serde_json::Value,
+) -> Result<Option<RelationFilter>, ApiError> {
+    let Some((join_field, test)) = key.split_once("__") else {
+        return Ok(None);
+    };
+    if !T::relation_filters()
+        .iter()
+        .any(|def| def.join_field == join_field)
+    {
+        return Ok(None);
+    }
+    let test = if test == "exists" {
+        let exists = value
+            .as_bool()
+            .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+            .ok_or_else(|| ApiError::bad_request(format!("'{key}' takes true or false")))?;
+        RelationTest::Exists(exists)
+    } else if let Some(suffix) = test.strip_prefix("count") {
+        let operator = match suffix {
+            "" => FilterOperator::Eq,
+            "_neq" | "_gt" | "_gte" | "_lt" | "_lte" => {
+                FilterOperator::from_suffix(suffix).unwrap_or(FilterOperator::Eq)
+            }
+            _ => return Ok(None),
+        };
+        let count = value
+            .as_i64()
+            .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+            .filter(|count| *count >= 0)
+            .ok_or_else(|| {
+                ApiError::bad_request(format!("'{key}' takes a non-negative integer"))
+            })?;
+        RelationTest::Count(operator, count)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(RelationFilter {
+        join_field: join_field.to_string(),
+        test,
+    }))
+}
+
+/// The condition on `T`'s rows matching every filter of `filters`, `None` without any.
+#[must_use]
+pub fn relation_condition<T: CRUDResource>(
+    filters: &[RelationFilter],
+    scoped: bool,
+) -> Option<Condition> {
+    if filters.is_empty() {
+        return None;
+    }
+    let defs = T::relation_filters();
+    let id = Expr::col((T::ID_COLUMN.entity_name(), T::ID_COLUMN));
+    let mut condition = Condition::all();
+    for filter in filters {
+        let Some(def) = defs.iter().find(|def| def.join_field == filter.join_field) else {
+            continue;
+        };
+        let (mut children, foreign_key) = child_keys(def, scoped);
+        let (matching, includes_childless) = match filter.test {
+            RelationTest::Exists(exists) => (children, !exists),
+            RelationTest::Count(operator, count) => {
+                let having = compare(
+                    Expr::expr(Func::count(Expr::col(Asterisk))),
+                    operator,
+                    count,
+                );
+                let includes_childless = counts_match(0, operator, count);
+                children
+                    .group_by_col(foreign_key)
+                    .and_having(if includes_childless {
+                        having.not()
+                    } else {
+                        having
+                    });
+                (children, includes_childless)
+            }
+        };
+        condition = condition.add(if includes_childless {
+            id.clone().not_in_subquery(matching)
+        } else {
+            id.clone().in_subquery(matching)
+        });
+    }
+    Some(condition)
+}
+
+/// `SELECT fk FROM child WHERE fk IS NOT NULL`, with the child's read condition and its
+/// scope when `scoped`
+fn child_keys(def: &RelationFilterDef, scoped: bool) -> (SelectStatement, (Alias, Alias)) {
+    let foreign_key = (Alias::new(def.table), Alias::new(def.foreign_key));
+    let mut query = Query::select();
+    query
+        .column(foreign_key.clone())
+        .from(Alias::new(def.table))
+        .and_where(Expr::col(foreign_key.clone()).is_not_null());
+    if let Some(read) = (def.read)() {
+        query.cond_where(read);
+    }
+    if scoped && let Some(scope) = (def.scope)() {
+        query.cond_where(scope);
+    }
+    (query, foreign_key)
+}
+
+fn compare(count: Expr, operator: FilterOperator, value: i64) -> SimpleExpr {
+    match operator {
+        FilterOperator::Neq => count.ne(value),
+        FilterOperator::Gt => count.gt(value),
+        FilterOperator::Gte => count.gte(value),
+        FilterOperator::Lt => count.lt(value),
+        FilterOperator::Lte => count.lte(value),
+        _ => count.eq(value),
+    }
+}
+
+fn counts_match(count: i64, operator: FilterOperator, value: i64) -> bool {
+    match operator {
+        FilterOperator::Neq => count != value,
+        FilterOperator::Gt => count > value,
+        FilterOperator::Gte => count >= value,
+        FilterOperator::Lt => count < value,
+        FilterOperator::Lte => count <= value,
+        _ => count == value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_childless_parents_match_tests_passed_by_zero() {
+        assert!(counts_match(0, FilterOperator::Eq, 0));
+        assert!(counts_match(0, FilterOperator::Lt, 2));
+        assert!(counts_match(0, FilterOperator::Neq, 3));
+        assert!(!counts_match(0, FilterOperator::Gte, 1));
+        assert!(!counts_match(0, FilterOperator::Gt, 0));
+        assert!(!counts_match(0, FilterOperator::Neq, 0));
+    }
+}
@@ -0,0 +1,88 @@
+//! Facet counts on fulltext search results.
+//!
+//! A search page usually comes with counts per status, category and so on, so users can
+//! narrow it down. On resources searchable with `q`, the list endpoint takes a
+//! `facets` parameter naming filterable fields, and returns their value counts over
+//! every matching row next to the page:
+//!
+//! ```text
+//! GET /articles?filter={"q":"rust"}&facets=status,author&range=[0,9]
+//! {
+//!   "items": [...],
+//!   "facets": {
+//!     "author": [{"value": "ana", "count": 7}, {"value": "bo", "count": 2}],
+//!     "status": [{"value": "draft", "count": 1}, {"value": "published", "count": 8}]
+//!   }
+//! }
+//! ```
+//!
+//! Each facet is one grouped `COUNT(*)` query on the list condition (see
+//! [`distinct_values`]), so pagination doesn't change the counts.
+
+use std::collections::BTreeMap;
+
+use sea_orm::{Condition, DatabaseConnection};
+use serde::Serialize;
+
+use super::distinct::{DistinctValue, distinct_values, filterable_column};
+use super::search::has_fulltext;
+use crate::errors::ApiError;
+use crate::filtering::FilterOptions;
+
+/// A list page with facet counts, returned instead of the bare array when `facets` is
+/// requested
+#[derive(Debug, Serialize)]
+pub struct FacetedPage<I> {
+    /// The page of results, as without `facets`
+    pub items: Vec<I>,
+    /// Value counts of each requested field, by the name it was requested with
+    pub facets: BTreeMap<String, Vec<DistinctValue>>,
+}
+
+/// The fields named in `params.facets`, with their columns, or `None` if no facets were
+/// requested.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if the resource has no fulltext search or a field is
+/// not among `filterable_columns`.
+#[allow(clippy::type_complexity)]
+pub fn requested_facets<T: crate::traits::CRUDResource>(
+    params: &FilterOptions,
+    filterable_columns: &[(&str, T::ColumnType)],
+) -> Result<Option<Vec<(String, T::ColumnType)>>, ApiError> {
+    let Some(facets) = params.facets.as_deref() else {
+        return Ok(None);
+    };
+    if !has_fulltext::<T>() {
+        return Err(ApiError::bad_request(
+            "Facets are only available on resources with fulltext search",
+        ));
+    }
+
+    let mut requested: Vec<(String, T::ColumnType)> = Vec::new();
+    for field in facets.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        if requested.iter().any(|(name, _)| name == field) {
+            continue;
+        }
+        let column = filterable_column::<T>(field, filterable_columns)?;
+        requested.push((field.to_string(), column));
+    }
+    Ok(Some(requested))
+}
+
+/// Value counts of each of `facets` among the rows matching `condition`.
+///
+/// # Errors
+/// Returns `ApiError::Database` if a query fails.
+pub async fn facet_counts<T: crate::traits::CRUDResource>(
+    db: &DatabaseConnection,
+    facets: Vec<(String, T::ColumnType)>,
+    condition: &Condition,
+) -> Result<BTreeMap<String, Vec<DistinctValue>>, ApiError> {
+    let mut counts = BTreeMap::new();
+    for (name, column) in facets {
+        let values = distinct_values::<T>(db, column, condition, true).await?;
+        counts.insert(name, values);
+    }
+    Ok(counts)
+}
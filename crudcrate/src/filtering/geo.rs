@@ -0,0 +1,361 @@
+//! Geospatial radius filtering and distance sorting.
+//!
+//! Fields marked `#[crudcrate(geo)]` (a PostGIS/MySQL spatial column) or declared with
+//! struct-level `geo(name = "location", lat = "latitude", lon = "longitude")` (two numeric
+//! columns) accept a `_within` filter and can be sorted by distance:
+//!
+//! ```text
+//! GET /shops?filter={"location_within":"48.8566,2.3522,5"}               # lat,lon,radius_km
+//! GET /shops?filter={"location_within":[48.8566,2.3522,5]}&sort=["location","ASC"]
+//! ```
+//!
+//! Sorting by a geo field orders by distance from the centre of its `_within` filter;
+//! without one, the sort falls back to the default column.
+//!
+//! | Backend | Spatial column | Lat/lon columns |
+//! |---------|----------------|-----------------|
+//! | `PostgreSQL` | `ST_DWithin` / `ST_Distance` on `geography` (PostGIS) | same, via `ST_MakePoint` |
+//! | `MySQL` | `ST_Distance_Sphere` | haversine |
+//! | `SQLite` | not supported (400) | haversine (requires SQLite math functions) |
+
+use crate::errors::ApiError;
+use sea_orm::{
+    DatabaseBackend, Value,
+    sea_query::{Expr, ExprTrait, Order, SimpleExpr},
+};
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+const WITHIN_SUFFIX: &str = "_within";
+
+/// Storage layout of a geo field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoColumn {
+    /// A single spatial column (`PostGIS` `geometry`/`geography`, `MySQL` `POINT`)
+    Point { column: &'static str },
+    /// Two numeric columns holding latitude and longitude in degrees
+    LatLon {
+        lat: &'static str,
+        lon: &'static str,
+    },
+}
+
+/// Centre point and radius parsed from a `_within` filter value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoRadius {
+    pub lat: f64,
+    pub lon: f64,
+    pub radius_km: f64,
+}
+
+/// Parse a `_within` filter value: `"lat,lon,radius_km"` or `[lat, lon, radius_km]`.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if the value is malformed or out of range.
+pub fn parse_within(value: &serde_json::Value) -> Result<GeoRadius, ApiError> {
+    let parts: Option<Vec<f64>> = match value {
+        serde_json::Value::String(s) => {
+            s.split(',').map(|p| p.trim().parse::<f64>().ok()).collect()
+        }
+        serde_json::Value::Array(items) => items.iter().map(serde_json::Value::as_f64).collect(),
+        _ => None,
+    };
+
+    let Some([lat, lon, radius_km]) = parts.as_deref().and_then(|p| <[f64; 3]>::try_from(p).ok())
+    else {
+        return Err(ApiError::bad_request(
+            "Geo filter must be \"lat,lon,radius_km\" or [lat, lon, radius_km]",
+        ));
+    };
+
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(ApiError::bad_request(
+            "Geo filter latitude must be within [-90, 90] and longitude within [-180, 180]",
+        ));
+    }
+    if !radius_km.is_finite() || radius_km <= 0.0 {
+        return Err(ApiError::bad_request(
+            "Geo filter radius must be a positive number of kilometres",
+        ));
+    }
+
+    Ok(GeoRadius {
+        lat,
+        lon,
+        radius_km,
+    })
+}
+
+/// Build the condition for a `{geo_field}_within` filter key.
+///
+/// Returns `Ok(None)` if `key` is not a `_within` filter on one of `T::geo_fields()`.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` for malformed values or unsupported backends.
+pub fn process_geo_filter<T: crate::traits::CRUDResource>(
+    key: &str,
+    value: &serde_json::Value,
+    backend: DatabaseBackend,
+) -> Result<Option<SimpleExpr>, ApiError> {
+    let Some(base_field) = key.strip_suffix(WITHIN_SUFFIX) else {
+        return Ok(None);
+    };
+    let Some(geo) = find_geo_field::<T>(base_field) else {
+        return Ok(None);
+    };
+
+    let center = parse_within(value)?;
+    within_expr(geo, center, backend).map(Some)
+}
+
+/// Resolve a distance ordering if the requested sort column is a geo field.
+///
+/// The reference point is taken from the `{field}_within` filter. Returns `Ok(None)`
+/// when the sort is not on a geo field or no reference point is present.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` for malformed `_within` values or unsupported backends.
+pub fn distance_order<T: crate::traits::CRUDResource>(
+    params: &crate::models::FilterOptions,
+    backend: DatabaseBackend,
+) -> Result<Option<(SimpleExpr, Order)>, ApiError> {
+    let (sort_column, sort_order) = super::sort::requested_sort(params);
+    let Some(geo) = find_geo_field::<T>(&sort_column) else {
+        return Ok(None);
+    };
+
+    let within_key = format!("{sort_column}{WITHIN_SUFFIX}");
+    let center = params
+        .filter
+        .as_deref()
+        .and_then(|f| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(f).ok())
+        .and_then(|filters| filters.get(&within_key).cloned());
+    let Some(center) = center else {
+        return Ok(None);
+    };
+
+    let center = parse_within(&center)?;
+    let expr = distance_expr(geo, center, backend)?;
+    Ok(Some((expr, super::sort::parse_order(&sort_order))))
+}
+
+fn find_geo_field<T: crate::traits::CRUDResource>(name: &str) -> Option<GeoColumn> {
+    T::geo_fields()
+        .into_iter()
+        .find(|(field, _)| *field == name)
+        .map(|(_, geo)| geo)
+}
+
+fn quote_ident(name: &str, backend: DatabaseBackend) -> String {
+    match backend {
+        DatabaseBackend::MySql => format!("`{}`", name.replace('`', "``")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// Distance expression: metres on the `PostGIS` / `ST_Distance_Sphere` paths, kilometres on
+/// the haversine path. Only used for ordering, so units do not need to agree.
+fn distance_expr(
+    geo: GeoColumn,
+    center: GeoRadius,
+    backend: DatabaseBackend,
+) -> Result<SimpleExpr, ApiError> {
+    match (backend, geo) {
+        (DatabaseBackend::Postgres, _) => Ok(Expr::cust_with_values(
+            format!(
+                "ST_Distance({}, ST_SetSRID(ST_MakePoint(?, ?), 4326)::geography)",
+                postgis_point(geo)
+            ),
+            [center.lon, center.lat],
+        )),
+        (DatabaseBackend::MySql, GeoColumn::Point { column }) => Ok(Expr::cust_with_values(
+            format!(
+                "ST_Distance_Sphere({}, POINT(?, ?))",
+                quote_ident(column, backend)
+            ),
+            [center.lon, center.lat],
+        )),
+        (_, GeoColumn::LatLon { lat, lon }) => Ok(haversine_km(lat, lon, center, backend)),
+        (_, GeoColumn::Point { .. }) => Err(unsupported_backend()),
+    }
+}
+
+fn within_expr(
+    geo: GeoColumn,
+    center: GeoRadius,
+    backend: DatabaseBackend,
+) -> Result<SimpleExpr, ApiError> {
+    let radius_m = center.radius_km * 1000.0;
+    match (backend, geo) {
+        (DatabaseBackend::Postgres, _) => Ok(Expr::cust_with_values(
+            format!(
+                "ST_DWithin({}, ST_SetSRID(ST_MakePoint(?, ?), 4326)::geography, ?)",
+                postgis_point(geo)
+            ),
+            [center.lon, center.lat, radius_m],
+        )),
+        (DatabaseBackend::MySql, GeoColumn::Point { column }) => Ok(Expr::cust_with_values(
+            format!(
+                "ST_Distance_Sphere({}, POINT(?, ?)) <= ?",
+                quote_ident(column, backend)
+            ),
+            [center.lon, center.lat, radius_m],
+        )),
+        (_, GeoColumn::LatLon { lat, lon }) => {
+            Ok(haversine_km(lat, lon, center, backend).lte(Value::from(center.radius_km)))
+        }
+        (_, GeoColumn::Point { .. }) => Err(unsupported_backend()),
+    }
+}
+
+fn postgis_point(geo: GeoColumn) -> String {
+    let backend = DatabaseBackend::Postgres;
+    match geo {
+        GeoColumn::Point { column } => format!("{}::geography", quote_ident(column, backend)),
+        GeoColumn::LatLon { lat, lon } => format!(
+            "ST_SetSRID(ST_MakePoint({}, {}), 4326)::geography",
+            quote_ident(lon, backend),
+            quote_ident(lat, backend)
+        ),
+    }
+}
+
+/// Great-circle distance in kilometres using the haversine formula
+fn haversine_km(lat: &str, lon: &str, center: GeoRadius, backend: DatabaseBackend) -> SimpleExpr {
+    let lat = quote_ident(lat, backend);
+    let lon = quote_ident(lon, backend);
+    Expr::cust_with_values(
+        format!(
+            "(? * 2 * ASIN(SQRT(\
+             POWER(SIN(RADIANS({lat} - ?) / 2), 2) + \
+             COS(RADIANS(?)) * COS(RADIANS({lat})) * POWER(SIN(RADIANS({lon} - ?) / 2), 2))))"
+        ),
+        [EARTH_RADIUS_KM, center.lat, center.lat, center.lon],
+    )
+}
+
+fn unsupported_backend() -> ApiError {
+    ApiError::bad_request("Geo filtering on spatial columns requires PostgreSQL (PostGIS) or MySQL")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const PARIS: GeoRadius = GeoRadius {
+        lat: 48.8566,
+        lon: 2.3522,
+        radius_km: 5.0,
+    };
+
+    #[test]
+    fn test_parse_within_string() {
+        assert_eq!(parse_within(&json!("48.8566, 2.3522, 5")).unwrap(), PARIS);
+    }
+
+    #[test]
+    fn test_parse_within_array() {
+        assert_eq!(parse_within(&json!([48.8566, 2.3522, 5])).unwrap(), PARIS);
+    }
+
+    #[test]
+    fn test_parse_within_rejects_malformed() {
+        for value in [
+            json!("48.8566,2.3522"),
+            json!("a,b,c"),
+            json!([1, 2, 3, 4]),
+            json!(5),
+            json!(null),
+        ] {
+            assert!(parse_within(&value).is_err(), "should reject {value}");
+        }
+    }
+
+    #[test]
+    fn test_parse_within_rejects_out_of_range() {
+        assert!(parse_within(&json!("91,0,1")).is_err());
+        assert!(parse_within(&json!("0,181,1")).is_err());
+        assert!(parse_within(&json!("0,0,0")).is_err());
+        assert!(parse_within(&json!("0,0,-3")).is_err());
+    }
+
+    #[test]
+    fn test_postgres_within_uses_postgis() {
+        let expr = within_expr(
+            GeoColumn::Point { column: "location" },
+            PARIS,
+            DatabaseBackend::Postgres,
+        )
+        .unwrap();
+        let sql = format!("{expr:?}");
+        assert!(
+            sql.contains("ST_DWithin(\\\"location\\\"::geography"),
+            "{sql}"
+        );
+        assert!(
+            sql.contains("5000.0"),
+            "radius should be bound in metres: {sql}"
+        );
+    }
+
+    #[test]
+    fn test_postgres_latlon_builds_point() {
+        let expr = distance_expr(
+            GeoColumn::LatLon {
+                lat: "latitude",
+                lon: "longitude",
+            },
+            PARIS,
+            DatabaseBackend::Postgres,
+        )
+        .unwrap();
+        let sql = format!("{expr:?}");
+        assert!(
+            sql.contains("ST_MakePoint(\\\"longitude\\\", \\\"latitude\\\")"),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_mysql_point_uses_distance_sphere() {
+        let expr = within_expr(
+            GeoColumn::Point { column: "location" },
+            PARIS,
+            DatabaseBackend::MySql,
+        )
+        .unwrap();
+        let sql = format!("{expr:?}");
+        assert!(
+            sql.contains("ST_Distance_Sphere(`location`, POINT(?, ?)) <= ?"),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_sqlite_latlon_uses_haversine() {
+        let expr = within_expr(
+            GeoColumn::LatLon {
+                lat: "latitude",
+                lon: "longitude",
+            },
+            PARIS,
+            DatabaseBackend::Sqlite,
+        )
+        .unwrap();
+        let sql = format!("{expr:?}");
+        assert!(sql.contains("ASIN(SQRT("), "{sql}");
+        assert!(sql.contains("6371.0"), "{sql}");
+    }
+
+    #[test]
+    fn test_sqlite_point_is_unsupported() {
+        assert!(
+            within_expr(
+                GeoColumn::Point { column: "location" },
+                PARIS,
+                DatabaseBackend::Sqlite
+            )
+            .is_err()
+        );
+    }
+}
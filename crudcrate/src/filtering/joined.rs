@@ -74,15 +74,13 @@ impl FilterOperator {
     #[must_use]
     pub fn suffix(&self) -> &'static str {
         match self {
-            Self::Eq => "",
+            Self::Eq | Self::In | Self::IsNull => "",
             Self::Neq => "_neq",
             Self::Gt => "_gt",
             Self::Gte => "_gte",
             Self::Lt => "_lt",
             Self::Lte => "_lte",
             Self::Like => "_like",
-            Self::In => "",
-            Self::IsNull => "",
         }
     }
 }
@@ -96,6 +94,9 @@ pub struct ParsedFilters {
     pub joined_filters: Vec<JoinedFilter>,
     /// Whether any joined filters were found
     pub has_joined_filters: bool,
+    /// Existence and count filters on `Vec` join fields, applied with
+    /// [`relation_condition`](super::exists::relation_condition) once the scope is known
+    pub relation_filters: Vec<super::exists::RelationFilter>,
 }
 
 impl Default for ParsedFilters {
@@ -104,6 +105,7 @@ impl Default for ParsedFilters {
             main_condition: Condition::all(),
             joined_filters: Vec::new(),
             has_joined_filters: false,
+            relation_filters: Vec::new(),
         }
     }
 }
@@ -0,0 +1,378 @@
+//! Dot-path filtering into JSON columns.
+//!
+//! Filterable fields typed `Json`/`serde_json::Value` accept keys of the form
+//! `{column}.{path}`, combined with the usual operator suffixes:
+//!
+//! ```text
+//! GET /products?filter={"metadata.color": "red"}
+//! GET /products?filter={"metadata.dimensions.width_gte": 20}
+//! GET /products?filter={"metadata.tags.0": "sale"}            # array index
+//! ```
+//!
+//! | Backend | Text extraction | Typed extraction (numbers, booleans) |
+//! |---------|-----------------|--------------------------------------|
+//! | `PostgreSQL` | `"col" -> 'a' ->> 'b'` | `"col"::jsonb -> 'a' -> 'b'` compared with `to_jsonb(value)` |
+//! | `MySQL` | `JSON_UNQUOTE(JSON_EXTRACT(col, '$."a"."b"'))` | `JSON_EXTRACT(col, '$."a"."b"')` |
+//! | `SQLite` | `json_extract(col, '$."a"."b"')` | same |
+//!
+//! String comparisons are case-insensitive, like regular string filters. Path segments
+//! and values are always bound as parameters.
+
+use std::fmt::Write;
+
+use crate::errors::ApiError;
+use sea_orm::{
+    DatabaseBackend, Value,
+    sea_query::{Expr, ExprTrait, Func, SimpleExpr},
+};
+
+const MAX_JSON_PATH_DEPTH: usize = 8;
+
+/// A single step into a JSON document
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(u32),
+}
+
+/// Build the condition for a `{json_column}.{path}` filter key.
+///
+/// Returns `Ok(None)` if `key` does not address one of `T::json_filterable_columns()`
+/// (present in `searchable_columns`) or the value type is not filterable.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if the path is malformed or too deep.
+pub fn process_json_filter<T: crate::traits::CRUDResource>(
+    key: &str,
+    value: &serde_json::Value,
+    searchable_columns: &[(&str, impl sea_orm::ColumnTrait)],
+    backend: DatabaseBackend,
+) -> Result<Option<SimpleExpr>, ApiError> {
+    let (full_path, operator) =
+        super::conditions::parse_comparison_operator(key).unwrap_or((key, "="));
+    let Some((field, path)) = full_path.split_once('.') else {
+        return Ok(None);
+    };
+    if !T::json_filterable_columns().contains(&field) {
+        return Ok(None);
+    }
+    if operator == "BETWEEN" {
+        return Err(ApiError::bad_request(
+            "'_between' is not supported on JSON paths; combine '_gte' and '_lte' instead",
+        ));
+    }
+    let Some(column) = searchable_columns
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, column)| column.as_str())
+    else {
+        return Ok(None);
+    };
+
+    let path = parse_path(path)?;
+    Ok(build_json_condition(
+        column, &path, operator, value, backend,
+    ))
+}
+
+fn parse_path(path: &str) -> Result<Vec<PathSegment>, ApiError> {
+    let segments: Vec<&str> = path.split('.').collect();
+    if segments.len() > MAX_JSON_PATH_DEPTH {
+        return Err(ApiError::bad_request(format!(
+            "JSON filter path is too deep (max {MAX_JSON_PATH_DEPTH} segments)"
+        )));
+    }
+
+    segments
+        .into_iter()
+        .map(|segment| {
+            let valid = !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !valid {
+                return Err(ApiError::bad_request(format!(
+                    "Invalid JSON filter path segment '{segment}'"
+                )));
+            }
+            Ok(segment.parse::<u32>().map_or_else(
+                |_| PathSegment::Key(segment.to_string()),
+                PathSegment::Index,
+            ))
+        })
+        .collect()
+}
+
+fn build_json_condition(
+    column: &str,
+    path: &[PathSegment],
+    operator: &str,
+    value: &serde_json::Value,
+    backend: DatabaseBackend,
+) -> Option<SimpleExpr> {
+    match value {
+        serde_json::Value::String(s) => {
+            let trimmed = s.trim();
+            if trimmed.is_empty() || !super::conditions::validate_field_value(trimmed) {
+                return None;
+            }
+            let lhs = SimpleExpr::FunctionCall(Func::upper(text_extract(column, path, backend)));
+            Some(compare(
+                lhs,
+                operator,
+                Expr::val(trimmed.to_uppercase()).into(),
+            ))
+        }
+        serde_json::Value::Number(n) => {
+            let value = n
+                .as_i64()
+                .map(Value::from)
+                .or_else(|| n.as_f64().map(Value::from))?;
+            Some(compare(
+                typed_extract(column, path, backend),
+                operator,
+                json_literal(value, backend),
+            ))
+        }
+        serde_json::Value::Bool(b) => {
+            let operator = if operator == "!=" { "!=" } else { "=" };
+            let rhs = match backend {
+                DatabaseBackend::Postgres => json_literal(Value::from(*b), backend),
+                DatabaseBackend::MySql => {
+                    Expr::cust_with_values("CAST(? AS JSON)", [b.to_string()])
+                }
+                // SQLite's json_extract returns 1/0 for true/false
+                DatabaseBackend::Sqlite => Expr::val(i64::from(*b)).into(),
+            };
+            Some(compare(typed_extract(column, path, backend), operator, rhs))
+        }
+        serde_json::Value::Null => {
+            let extracted = text_extract(column, path, backend);
+            Some(if operator == "!=" {
+                extracted.is_not_null()
+            } else {
+                extracted.is_null()
+            })
+        }
+        serde_json::Value::Array(items) => {
+            let values: Vec<String> = items
+                .iter()
+                .filter_map(|v| match v {
+                    serde_json::Value::String(s) => Some(s.trim().to_uppercase()),
+                    serde_json::Value::Number(n) => Some(n.to_string()),
+                    serde_json::Value::Bool(b) => Some(b.to_string().to_uppercase()),
+                    _ => None,
+                })
+                .collect();
+            if values.is_empty() {
+                return None;
+            }
+            let lhs = SimpleExpr::FunctionCall(Func::upper(text_extract(column, path, backend)));
+            Some(lhs.is_in(values))
+        }
+        serde_json::Value::Object(_) => None,
+    }
+}
+
+fn compare(lhs: SimpleExpr, operator: &str, rhs: SimpleExpr) -> SimpleExpr {
+    match operator {
+        "!=" => lhs.ne(rhs),
+        ">=" => lhs.gte(rhs),
+        "<=" => lhs.lte(rhs),
+        ">" => lhs.gt(rhs),
+        "<" => lhs.lt(rhs),
+        _ => lhs.eq(rhs),
+    }
+}
+
+/// Right-hand side for comparisons against a typed extraction
+fn json_literal(value: Value, backend: DatabaseBackend) -> SimpleExpr {
+    match backend {
+        DatabaseBackend::Postgres => Expr::cust_with_values("to_jsonb(?)", [value]),
+        _ => Expr::val(value).into(),
+    }
+}
+
+/// Extract the value at `path` as text
+fn text_extract(column: &str, path: &[PathSegment], backend: DatabaseBackend) -> SimpleExpr {
+    match backend {
+        DatabaseBackend::Postgres => postgres_extract(&format!("\"{column}\""), path, "->>"),
+        DatabaseBackend::MySql => Expr::cust_with_values(
+            format!("JSON_UNQUOTE(JSON_EXTRACT(`{column}`, ?))"),
+            [json_path(path)],
+        ),
+        DatabaseBackend::Sqlite => {
+            Expr::cust_with_values(format!("json_extract(\"{column}\", ?)"), [json_path(path)])
+        }
+    }
+}
+
+/// Extract the value at `path` keeping its JSON type, for numeric and boolean comparisons
+fn typed_extract(column: &str, path: &[PathSegment], backend: DatabaseBackend) -> SimpleExpr {
+    match backend {
+        DatabaseBackend::Postgres => postgres_extract(&format!("\"{column}\"::jsonb"), path, "->"),
+        DatabaseBackend::MySql => {
+            Expr::cust_with_values(format!("JSON_EXTRACT(`{column}`, ?)"), [json_path(path)])
+        }
+        DatabaseBackend::Sqlite => {
+            Expr::cust_with_values(format!("json_extract(\"{column}\", ?)"), [json_path(path)])
+        }
+    }
+}
+
+/// `("col" -> ? -> ? ->> ?)`, binding keys as text and array indexes as integers
+fn postgres_extract(column: &str, path: &[PathSegment], last_operator: &str) -> SimpleExpr {
+    let mut sql = format!("({column}");
+    for i in 0..path.len() {
+        let operator = if i + 1 == path.len() {
+            last_operator
+        } else {
+            "->"
+        };
+        let _ = write!(sql, " {operator} ?");
+    }
+    sql.push(')');
+
+    let values = path.iter().map(|segment| match segment {
+        PathSegment::Key(key) => Value::from(key.clone()),
+        PathSegment::Index(index) => Value::from(i32::try_from(*index).unwrap_or(i32::MAX)),
+    });
+    Expr::cust_with_values(sql, values)
+}
+
+/// `MySQL`/`SQLite` JSON path: `$."key"[0]."nested"`
+fn json_path(path: &[PathSegment]) -> String {
+    let mut json_path = String::from("$");
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) => {
+                let _ = write!(json_path, ".\"{key}\"");
+            }
+            PathSegment::Index(index) => {
+                let _ = write!(json_path, "[{index}]");
+            }
+        }
+    }
+    json_path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(k: &str) -> PathSegment {
+        PathSegment::Key(k.to_string())
+    }
+
+    fn sql(expr: Option<SimpleExpr>) -> String {
+        format!("{:?}", expr.expect("condition should be built"))
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("dimensions.width").unwrap(),
+            vec![key("dimensions"), key("width")]
+        );
+        assert_eq!(
+            parse_path("tags.0").unwrap(),
+            vec![key("tags"), PathSegment::Index(0)]
+        );
+    }
+
+    #[test]
+    fn test_parse_path_rejects_invalid_segments() {
+        for path in ["", "a..b", "a.b'c", "a.b c", "a.$", "a.b)--"] {
+            assert!(parse_path(path).is_err(), "should reject {path:?}");
+        }
+        assert!(parse_path(&["a"; MAX_JSON_PATH_DEPTH + 1].join(".")).is_err());
+    }
+
+    #[test]
+    fn test_json_path() {
+        assert_eq!(
+            json_path(&[key("tags"), PathSegment::Index(2), key("name")]),
+            "$.\"tags\"[2].\"name\""
+        );
+    }
+
+    #[test]
+    fn test_postgres_string_uses_arrow_operators() {
+        let path = [key("dimensions"), key("unit")];
+        let sql = sql(build_json_condition(
+            "metadata",
+            &path,
+            "=",
+            &json!("cm"),
+            DatabaseBackend::Postgres,
+        ));
+        assert!(sql.contains("(\\\"metadata\\\" -> ? ->> ?)"), "{sql}");
+        assert!(sql.contains("CM"), "{sql}");
+    }
+
+    #[test]
+    fn test_postgres_number_compares_jsonb() {
+        let sql = sql(build_json_condition(
+            "metadata",
+            &[key("width")],
+            ">=",
+            &json!(20),
+            DatabaseBackend::Postgres,
+        ));
+        assert!(sql.contains("::jsonb ->"), "{sql}");
+        assert!(sql.contains("to_jsonb(?)"), "{sql}");
+        assert!(sql.contains("GreaterThanOrEqual"), "{sql}");
+    }
+
+    #[test]
+    fn test_mysql_uses_json_extract() {
+        let sql = sql(build_json_condition(
+            "metadata",
+            &[key("color")],
+            "=",
+            &json!("red"),
+            DatabaseBackend::MySql,
+        ));
+        assert!(
+            sql.contains("JSON_UNQUOTE(JSON_EXTRACT(`metadata`, ?))"),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_sqlite_uses_json_extract() {
+        let sql = sql(build_json_condition(
+            "metadata",
+            &[key("in_stock")],
+            "=",
+            &json!(true),
+            DatabaseBackend::Sqlite,
+        ));
+        assert!(sql.contains("json_extract(\\\"metadata\\\", ?)"), "{sql}");
+        assert!(sql.contains("BigInt(Some(1))"), "{sql}");
+    }
+
+    #[test]
+    fn test_null_and_object_values() {
+        let sql = sql(build_json_condition(
+            "metadata",
+            &[key("color")],
+            "!=",
+            &json!(null),
+            DatabaseBackend::Sqlite,
+        ));
+        assert!(sql.contains("IsNot"), "{sql}");
+
+        assert!(
+            build_json_condition(
+                "metadata",
+                &[key("color")],
+                "=",
+                &json!({"nested": true}),
+                DatabaseBackend::Sqlite,
+            )
+            .is_none()
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Keyset pagination on the active sort column.
+//!
+//! Offset pagination skips or repeats rows when others are inserted between pages.
+//! Keyset pagination instead continues after the last row the client saw, identified
+//! by its sort value and ID:
+//!
+//! ```text
+//! GET /tasks?sort=["created_at","DESC"]&per_page=20
+//! GET /tasks?sort=["created_at","DESC"]&per_page=20&after_value=2024-05-01T10:00:00Z&after_id=<uuid>
+//! ```
+//!
+//! The ID breaks ties between equal sort values, so the page is ordered by
+//! `(column, id)` in the sort direction and filtered with a tuple comparison:
+//!
+//! | Backend | Condition (ascending) |
+//! |---------|-----------------------|
+//! | `PostgreSQL`, `SQLite` | `(col, id) > (?, ?)` |
+//! | `MySQL` | `col > ? OR (col = ? AND id > ?)` (row constructors don't use indexes well) |
+//!
+//! When sorting by the ID itself, `after_value` is not needed.
+
+use crate::errors::ApiError;
+use crate::filtering::FilterOptions;
+use crate::filtering::sort::{parse_order, requested_multi_sort, requested_sort};
+use sea_orm::{
+    ColumnTrait, ColumnType, DatabaseBackend, IdenStatic, Order,
+    sea_query::{Alias, Expr, ExprTrait, SimpleExpr},
+};
+use uuid::Uuid;
+
+/// The extra condition and ordering for a keyset page
+pub struct KeysetPage {
+    /// Rows after the cursor, to be added to the list condition
+    pub condition: SimpleExpr,
+    /// Sort column then ID, both in the requested direction
+    pub order: Vec<(SimpleExpr, Order)>,
+}
+
+/// Build the keyset condition and ordering from `after_id`/`after_value`, or `None` if
+/// the request doesn't use keyset pagination.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `after_id` is missing or not a UUID, `after_value`
+/// is missing or doesn't parse as the sort column's type, or the sort is not a single
+/// sortable column (multi-column, joined and distance sorts are rejected).
+pub fn keyset_page<T: crate::traits::CRUDResource>(
+    params: &FilterOptions,
+    sortable_columns: &[(&str, T::ColumnType)],
+    backend: DatabaseBackend,
+) -> Result<Option<KeysetPage>, ApiError> {
+    if params.after_id.is_none() && params.after_value.is_none() {
+        return Ok(None);
+    }
+    let after_id = params
+        .after_id
+        .as_deref()
+        .map(str::trim)
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or_else(|| ApiError::bad_request("Keyset pagination requires a UUID 'after_id'"))?;
+
+    if requested_multi_sort(params).is_some() {
+        return Err(ApiError::bad_request(
+            "Keyset pagination requires a single sort column",
+        ));
+    }
+    let (sort_column, sort_order) = requested_sort(params);
+    if T::geo_fields().iter().any(|(name, _)| *name == sort_column) {
+        return Err(ApiError::bad_request(
+            "Keyset pagination is not supported when sorting by distance",
+        ));
+    }
+
+    let id_column = T::ID_COLUMN;
+    let column = sortable_columns
+        .iter()
+        .find(|(name, _)| *name == sort_column)
+        .map(|&(_, column)| column)
+        .or_else(|| (sort_column == id_column.as_str()).then_some(id_column))
+        .ok_or_else(|| {
+            ApiError::bad_request(format!(
+                "Keyset pagination requires a sortable column, got '{sort_column}'"
+            ))
+        })?;
+    let direction = parse_order(&sort_order);
+
+    let id_expr = column_expr(id_column);
+    let after_id_expr = SimpleExpr::from(after_id);
+    let after = |left: SimpleExpr, right: SimpleExpr| match direction {
+        Order::Asc => left.gt(right),
+        _ => left.lt(right),
+    };
+
+    if column.as_str() == id_column.as_str() {
+        return Ok(Some(KeysetPage {
+            condition: after(id_expr.clone(), after_id_expr),
+            order: vec![(id_expr, direction)],
+        }));
+    }
+
+    let raw = params.after_value.as_deref().ok_or_else(|| {
+        ApiError::bad_request(format!(
+            "Keyset pagination on '{sort_column}' requires 'after_value'"
+        ))
+    })?;
+    // `time` crate columns bind a typed value, encoded like the stored ones
+    #[cfg(feature = "time")]
+    let typed = super::timestamps::time_kind::<T>(&sort_column)
+        .map(|kind| super::timestamps::time_value(&sort_column, kind, raw))
+        .transpose()?;
+    #[cfg(not(feature = "time"))]
+    let typed: Option<sea_orm::Value> = None;
+    let value = match typed {
+        Some(value) => value.into(),
+        None => keyset_value(&sort_column, column.def().get_column_type(), raw, backend)?,
+    };
+    let column_expr = column_expr(column);
+
+    let condition = match backend {
+        DatabaseBackend::MySql => after(column_expr.clone(), value.clone()).or(column_expr
+            .clone()
+            .eq(value)
+            .and(after(id_expr.clone(), after_id_expr))),
+        _ => after(
+            Expr::tuple([column_expr.clone(), id_expr.clone()]).into(),
+            Expr::tuple([value, after_id_expr]).into(),
+        ),
+    };
+
+    Ok(Some(KeysetPage {
+        condition,
+        order: vec![(column_expr, direction.clone()), (id_expr, direction)],
+    }))
+}
+
+fn column_expr<C: ColumnTrait>(column: C) -> SimpleExpr {
+    Expr::col((column.entity_name(), column)).into()
+}
+
+/// Parse `after_value` as a literal of the sort column's type
+fn keyset_value(
+    field: &str,
+    column_type: &ColumnType,
+    raw: &str,
+    backend: DatabaseBackend,
+) -> Result<SimpleExpr, ApiError> {
+    let raw = raw.trim();
+    let invalid = |expected: &str| {
+        ApiError::bad_request(format!(
+            "Invalid 'after_value' for '{field}': expected {expected}"
+        ))
+    };
+
+    let value = match column_type {
+        ColumnType::TinyInteger
+        | ColumnType::SmallInteger
+        | ColumnType::Integer
+        | ColumnType::BigInteger
+        | ColumnType::TinyUnsigned
+        | ColumnType::SmallUnsigned
+        | ColumnType::Unsigned
+        | ColumnType::BigUnsigned
+        | ColumnType::Year => raw
+            .parse::<i64>()
+            .map_err(|_| invalid("an integer"))?
+            .into(),
+        ColumnType::Float | ColumnType::Double => {
+            raw.parse::<f64>().map_err(|_| invalid("a number"))?.into()
+        }
+        ColumnType::Decimal(_) | ColumnType::Money(_) => super::conditions::decimal_literal(
+            super::conditions::parse_decimal_value(field, &raw.into())?,
+            backend,
+        ),
+        ColumnType::Boolean => raw
+            .parse::<bool>()
+            .map_err(|_| invalid("true or false"))?
+            .into(),
+        ColumnType::Uuid => Uuid::parse_str(raw).map_err(|_| invalid("a UUID"))?.into(),
+        ColumnType::Char(_) | ColumnType::String(_) | ColumnType::Text => raw.into(),
+        ColumnType::Date
+        | ColumnType::Time
+        | ColumnType::DateTime
+        | ColumnType::Timestamp
+        | ColumnType::TimestampWithTimeZone => {
+            if raw.is_empty() {
+                return Err(invalid("a date or timestamp"));
+            }
+            // PostgreSQL won't compare a timestamp with a text parameter
+            match (backend, column_type) {
+                (DatabaseBackend::Postgres, ColumnType::Date) => {
+                    Expr::val(raw).cast_as(Alias::new("DATE"))
+                }
+                (DatabaseBackend::Postgres, ColumnType::Time) => {
+                    Expr::val(raw).cast_as(Alias::new("TIME"))
+                }
+                (DatabaseBackend::Postgres, ColumnType::TimestampWithTimeZone) => {
+                    Expr::val(raw).cast_as(Alias::new("TIMESTAMPTZ"))
+                }
+                (DatabaseBackend::Postgres, _) => Expr::val(raw).cast_as(Alias::new("TIMESTAMP")),
+                _ => raw.into(),
+            }
+        }
+        _ => {
+            return Err(ApiError::bad_request(format!(
+                "Keyset pagination is not supported on '{field}'"
+            )));
+        }
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::sea_query::{PostgresQueryBuilder, Query, SqliteQueryBuilder};
+
+    fn sql(expr: SimpleExpr, postgres: bool) -> String {
+        let query = Query::select().expr(expr).to_owned();
+        if postgres {
+            query.to_string(PostgresQueryBuilder)
+        } else {
+            query.to_string(SqliteQueryBuilder)
+        }
+    }
+
+    #[test]
+    fn test_keyset_value_types() {
+        let backend = DatabaseBackend::Sqlite;
+        assert_eq!(
+            sql(
+                keyset_value("n", &ColumnType::Integer, "42", backend).unwrap(),
+                false
+            ),
+            "SELECT 42"
+        );
+        assert!(keyset_value("b", &ColumnType::Boolean, "true", backend).is_ok());
+        assert!(keyset_value("b", &ColumnType::Boolean, "yes", backend).is_err());
+        assert_eq!(
+            sql(
+                keyset_value("s", &ColumnType::Text, "it's", backend).unwrap(),
+                false
+            ),
+            "SELECT 'it''s'"
+        );
+        assert!(keyset_value("n", &ColumnType::Integer, "4.2", backend).is_err());
+        assert!(keyset_value("u", &ColumnType::Uuid, "nope", backend).is_err());
+        assert!(keyset_value("j", &ColumnType::Json, "{}", backend).is_err());
+    }
+
+    #[test]
+    fn test_keyset_value_casts_timestamps_on_postgres() {
+        let value = keyset_value(
+            "created_at",
+            &ColumnType::TimestampWithTimeZone,
+            "2024-05-01T10:00:00Z",
+            DatabaseBackend::Postgres,
+        )
+        .unwrap();
+        let cast = sql(value, true);
+        assert!(
+            cast.starts_with("SELECT CAST('2024-05-01T10:00:00Z' AS"),
+            "{cast}"
+        );
+        assert!(cast.contains("TIMESTAMPTZ"), "{cast}");
+
+        let value = keyset_value(
+            "created_at",
+            &ColumnType::TimestampWithTimeZone,
+            "2024-05-01T10:00:00Z",
+            DatabaseBackend::Sqlite,
+        )
+        .unwrap();
+        assert_eq!(sql(value, false), "SELECT '2024-05-01T10:00:00Z'");
+    }
+}
@@ -0,0 +1,184 @@
+//! Escaping of user text matched with `LIKE`.
+//!
+//! `%` and `_` in a search term would otherwise act as wildcards, so `?q=%` matched
+//! every row. [`escape_like`] puts a backslash before `%`, `_` and the backslash itself,
+//! and every pattern crudcrate builds declares that escape character with
+//! `ESCAPE '\'`. The clause matters: `SQLite` has no default escape character, so
+//! without it `\%` is still a wildcard. Patterns are bound as parameters, never
+//! interpolated into the SQL text.
+//!
+//! Use the same helpers for `LIKE` conditions in hooks and custom handlers:
+//!
+//! ```rust
+//! use crudcrate::filtering::like;
+//! use sea_orm::sea_query::{Alias, Expr};
+//!
+//! let condition = Expr::col(Alias::new("title")).like(like::contains("100%"));
+//! ```
+
+use sea_orm::sea_query::LikeExpr;
+
+/// Escape character declared by the patterns built here
+pub const LIKE_ESCAPE: char = '\\';
+
+/// Escape `input` so that a `LIKE` pattern declaring `ESCAPE '\'` matches it literally:
+/// `\` becomes `\\`, `%` becomes `\%` and `_` becomes `\_`.
+///
+/// ```rust
+/// use crudcrate::filtering::escape_like;
+///
+/// assert_eq!(escape_like("100%_off\\"), "100\\%\\_off\\\\");
+/// ```
+#[must_use]
+pub fn escape_like(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push(LIKE_ESCAPE);
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Pattern matching values that contain `term`, with `ESCAPE '\'`
+#[must_use]
+pub fn contains(term: &str) -> LikeExpr {
+    LikeExpr::new(contains_pattern(term)).escape(LIKE_ESCAPE)
+}
+
+/// Pattern text matching values that contain `term`, for SQL written by hand; declare
+/// `ESCAPE '\'` next to it
+#[must_use]
+pub fn contains_pattern(term: &str) -> String {
+    format!("%{}%", escape_like(term))
+}
+
+/// The longest prefix of `input` that fits in `max` bytes without splitting a character
+pub(crate) fn truncate(input: &str, max: usize) -> &str {
+    if input.len() <= max {
+        return input;
+    }
+    let mut end = max;
+    while !input.is_char_boundary(end) {
+        end -= 1;
+    }
+    &input[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::sea_query::{
+        Alias, Expr, MysqlQueryBuilder, PostgresQueryBuilder, Query, SqliteQueryBuilder,
+    };
+
+    /// Splitmix64, so failures replay from the seed
+    struct Rng(u64);
+
+    impl Rng {
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        /// Random text leaning on `LIKE` and SQL metacharacters
+        fn text(&mut self) -> String {
+            const ALPHABET: &[char] = &[
+                '%', '_', '\\', '\'', '"', '[', ']', '^', '*', '?', ';', '-', 'a', 'B', ' ', 'é',
+                '\0', '\n',
+            ];
+            let len = usize::try_from(self.next_u64() % 12).unwrap_or(0);
+            (0..len)
+                .map(|_| {
+                    let index = self.next_u64() % ALPHABET.len() as u64;
+                    ALPHABET[usize::try_from(index).unwrap_or(0)]
+                })
+                .collect()
+        }
+    }
+
+    /// Split an escaped pattern into its characters, `true` marking the ones that act as
+    /// wildcards; `None` when a backslash escapes nothing
+    fn parse(pattern: &str) -> Option<Vec<(char, bool)>> {
+        let mut parsed = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                LIKE_ESCAPE => parsed.push((chars.next()?, false)),
+                '%' | '_' => parsed.push((c, true)),
+                c => parsed.push((c, false)),
+            }
+        }
+        Some(parsed)
+    }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("normal text"), "normal text");
+        assert_eq!(escape_like("100% complete"), "100\\% complete");
+        assert_eq!(escape_like("test_value"), "test\\_value");
+        assert_eq!(escape_like("%_"), "\\%\\_");
+        assert_eq!(escape_like("\\"), "\\\\");
+        assert_eq!(escape_like("\\%"), "\\\\\\%");
+        assert_eq!(escape_like(""), "");
+        assert_eq!(escape_like("ünï_cødé"), "ünï\\_cødé");
+    }
+
+    /// No wildcard of the term survives escaping, and the term comes back unchanged
+    #[test]
+    fn test_escape_like_fuzz() {
+        let mut rng = Rng(0x11CE_5EED);
+        for iteration in 0..5_000 {
+            let term = rng.text();
+            let pattern = contains_pattern(&term);
+            let parsed = parse(&pattern)
+                .unwrap_or_else(|| panic!("dangling escape ({iteration}): {pattern:?}"));
+            let wildcards: Vec<usize> = (0..parsed.len()).filter(|&i| parsed[i].1).collect();
+            assert_eq!(
+                wildcards,
+                [0, parsed.len() - 1],
+                "iteration {iteration}: {term:?} leaked a wildcard in {pattern:?}"
+            );
+            let literal: String = parsed[1..parsed.len() - 1].iter().map(|(c, _)| c).collect();
+            assert_eq!(literal, term, "iteration {iteration}");
+        }
+    }
+
+    /// Every backend gets the pattern as a bound value, with the escape clause
+    #[test]
+    fn test_contains_binds_pattern_with_escape() {
+        let query = Query::select()
+            .column(Alias::new("title"))
+            .from(Alias::new("items"))
+            .and_where(Expr::col(Alias::new("title")).like(contains("50%_' OR 1=1 --")))
+            .to_owned();
+        let expected = "%50\\%\\_' OR 1=1 --%";
+
+        // An `E''` string, so the backslash is escaped whatever `standard_conforming_strings` says
+        let (sql, values) = query.build(PostgresQueryBuilder);
+        assert!(sql.ends_with(r#""title" LIKE $1 ESCAPE E'\\'"#), "{sql}");
+        assert_eq!(values.0, [expected.into()]);
+
+        let (sql, values) = query.build(SqliteQueryBuilder);
+        assert!(sql.ends_with(r#""title" LIKE ? ESCAPE '\'"#), "{sql}");
+        assert_eq!(values.0, [expected.into()]);
+
+        // MySQL reads backslashes in string literals as escapes, so the clause doubles it
+        let (sql, values) = query.build(MysqlQueryBuilder);
+        assert!(sql.ends_with(r"`title` LIKE ? ESCAPE '\\'"), "{sql}");
+        assert_eq!(values.0, [expected.into()]);
+    }
+
+    #[test]
+    fn test_truncate_keeps_char_boundaries() {
+        assert_eq!(truncate("abc", 10), "abc");
+        assert_eq!(truncate("abc", 2), "ab");
+        assert_eq!(truncate("aé", 2), "a");
+        assert_eq!(truncate("éé", 3), "é");
+        assert_eq!(truncate("", 0), "");
+    }
+}
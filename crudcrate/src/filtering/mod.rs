@@ -20,8 +20,26 @@
 //! | `_lt` | `< value` | `{"priority_lt": 10}` |
 //! | `_lte` | `<= value` | `{"priority_lte": 10}` |
 //! | `_like` | `LIKE %value%` | `{"name_like": "john"}` |
+//! | `_between` | `BETWEEN min AND max` | `{"priority_between": [3, 10]}` |
 //!
 //! Multiple values for the same field (comma-separated) produce an `IN` clause.
+//! String fields match case-insensitively; fields marked `ci` compare the exact value
+//! through the database collation so indexes apply (see [`collation`]).
+//! Values for enum fields must be one of the enum's variants (see [`enums`]).
+//!
+//! Geo fields (see [`geo`]) accept `{"location_within": "lat,lon,radius_km"}`.
+//! JSON fields (see [`json`]) accept dot paths such as `{"metadata.color": "red"}`.
+//! Date/time fields (see [`dates`]) accept `{"created_at_date": "2024-05-01"}` and `{"created_at_year": 2024}`.
+//! With the `time` feature, fields of `time` crate types parse RFC 3339 values into their type (see `timestamps`).
+//! Postgres array fields (see [`arrays`]) accept `{"tags_contains": ["a"]}` and `{"tags_overlaps": ["a", "b"]}`.
+//! Fields with `filter_with` pass their value through a user-defined function first (see [`custom`]),
+//! and resource-level `virtual_filter`s add keys that expand to arbitrary conditions.
+//! `Vec` join fields accept `{"vehicles__exists": false}` and `{"vehicles__count_gte": 2}` (see [`exists`]).
+//! Fields with `rename`/`alias` are filtered and sorted by their external names (see [`rename`]).
+//! Unknown keys are ignored unless the resource uses `strict_query` (see [`strict`]).
+//! Every key can also be passed as a query parameter of its own, such as
+//! `?title_like=urgent&priority_gte=3`, with `filter` taking precedence (see [`params`]).
+//! How each key resolves is compiled once per filter shape and cached (see [`cache`]).
 //!
 //! ## Fulltext search
 //!
@@ -33,6 +51,8 @@
 //!
 //! On `PostgreSQL` this uses `to_tsvector`/`to_tsquery` with GIN indexes.
 //! On `MySQL` it uses `MATCH ... AGAINST`. On `SQLite` it falls back to `LIKE`.
+//! `LIKE` patterns escape `%`, `_` and `\` in the query, so they match literally
+//! (see [`like`]).
 //!
 //! ## Sorting
 //!
@@ -50,27 +70,68 @@
 //!
 //! Responses include `Content-Range` and `X-Total-Count` headers.
 //!
+//! ## Distinct values and facets
+//!
+//! ```text
+//! GET /todos/distinct/status?counts=true   # values of one filterable field (see [`distinct`])
+//! GET /todos?filter={"q":"urgent"}&facets=status   # page plus value counts (see [`facets`])
+//! ```
+//!
 //! # Key types
 //!
 //! - [`FilterOptions`] — parsed query parameters for a list request
+//! - [`DistinctOptions`] — options for the distinct values endpoint (`?counts=true`)
 //! - [`BatchOptions`] — options for batch endpoints (e.g. `?partial=true`)
 //! - [`apply_filters`] — builds a Sea-ORM `Condition` from filter params
 //! - [`parse_sorting`] — resolves sort parameters to column + direction
+//! - [`parse_multi_sorting`] — resolves a multi-column sort to `(column, direction)` pairs
 //! - [`parse_pagination`] — extracts offset/limit from query params
 
+pub mod arrays;
+pub mod cache;
+pub mod collation;
 pub mod conditions;
+pub mod custom;
+pub mod dates;
+pub mod distinct;
+pub mod enums;
+pub mod exists;
+pub mod facets;
+pub mod geo;
 pub mod joined;
+pub mod json;
+pub mod keyset;
+pub mod like;
 pub mod pagination;
+pub mod params;
 pub mod query_parser;
+pub mod rename;
 pub mod search;
 pub mod sort;
+pub mod strict;
+#[cfg(feature = "time")]
+pub mod timestamps;
 
 // Re-export commonly used items
-pub use conditions::{apply_filters, apply_filters_with_joins, parse_pagination, parse_range};
+pub use conditions::{
+    apply_filters, apply_filters_with_joins, delete_filter_condition, parse_pagination, parse_range,
+};
+pub use custom::{FilterFn, VirtualFilter};
+pub use distinct::{DistinctValue, distinct_values};
+pub use exists::{RelationFilter, RelationFilterDef, RelationTest};
+pub use facets::FacetedPage;
+pub use geo::GeoColumn;
 pub use joined::{
     FilterOperator, JoinedColumnDef, JoinedFilter, ParsedFilters, SortConfig, parse_dot_notation,
 };
+pub use keyset::{KeysetPage, keyset_page};
+pub use like::escape_like;
 pub use pagination::calculate_content_range;
-pub use query_parser::{BatchOptions, FilterOptions};
+pub use params::merge_query_filters;
+pub use query_parser::{
+    BatchOptions, ByIdsOptions, DeleteFilterOptions, DistinctOptions, FilterOptions, TreeOptions,
+};
+pub use rename::translate_field_names;
 pub use search::build_fulltext_condition;
-pub use sort::{parse_sorting, parse_sorting_with_joins};
+pub use sort::{parse_multi_sorting, parse_sorting, parse_sorting_with_joins};
+pub use strict::check_query_fields;
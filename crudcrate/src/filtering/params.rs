@@ -0,0 +1,186 @@
+//! Filters passed as plain query parameters.
+//!
+//! Besides the JSON `filter` parameter, the list endpoints accept every filter key as a
+//! query parameter of its own, which is easier to type and what HTML forms send. These
+//! two requests build the same condition:
+//!
+//! ```text
+//! GET /todos?title_like=urgent&priority_gte=3&status=open&status=review
+//! GET /todos?filter={"title_like":"urgent","priority_gte":3,"status":["open","review"]}
+//! ```
+//!
+//! The generated handlers pass the query through [`merge_query_filters`], which folds
+//! the parameters into `filter` before it is parsed, so every key the JSON form accepts
+//! works: operator suffixes, `q`, dot paths into joins and JSON columns, and virtual
+//! filters. Values are typed by the column they filter (numbers for numeric columns,
+//! `true`/`false` for boolean columns, strings otherwise), a repeated parameter becomes a
+//! list (`IN`), and `_between` takes `min,max`.
+//!
+//! When both forms are used, a key in `filter` takes precedence over the same query
+//! parameter and the remaining keys are combined. Parameters the list endpoint reads
+//! itself (`sort`, `page`, `range`, ...) are never filters. Values that need JSON, such as
+//! `null`, still go through `filter`. Unknown parameters are ignored like unknown filter
+//! keys, and rejected on resources with `strict_query`.
+
+use std::collections::BTreeMap;
+
+use sea_orm::{ColumnTrait, ColumnType};
+
+use super::cache::column_clause;
+use super::rename::translate_key;
+use crate::core::CRUDResource;
+use crate::errors::ApiError;
+use crate::filtering::FilterOptions;
+
+/// Query parameters of a list request that are not filters
+const RESERVED: &[&str] = &[
+    "filter",
+    "range",
+    "page",
+    "per_page",
+    "sort",
+    "sort_by",
+    "order",
+    "after_id",
+    "after_value",
+    "facets",
+    "as_of",
+    "__explain",
+];
+
+/// JSON type a query parameter value is parsed as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Number,
+    Bool,
+    Text,
+}
+
+impl ValueKind {
+    fn of(column_type: &ColumnType) -> Self {
+        match column_type {
+            ColumnType::TinyInteger
+            | ColumnType::SmallInteger
+            | ColumnType::Integer
+            | ColumnType::BigInteger
+            | ColumnType::TinyUnsigned
+            | ColumnType::SmallUnsigned
+            | ColumnType::Unsigned
+            | ColumnType::BigUnsigned
+            | ColumnType::Float
+            | ColumnType::Double => Self::Number,
+            ColumnType::Boolean => Self::Bool,
+            _ => Self::Text,
+        }
+    }
+
+    fn parse(self, key: &str, text: &str) -> Result<serde_json::Value, ApiError> {
+        match self {
+            Self::Number => text
+                .trim()
+                .parse::<serde_json::Number>()
+                .map(serde_json::Value::Number)
+                .map_err(|_| ApiError::bad_request(format!("Invalid number '{text}' for '{key}'"))),
+            Self::Bool => match text.trim() {
+                "true" => Ok(serde_json::Value::Bool(true)),
+                "false" => Ok(serde_json::Value::Bool(false)),
+                _ => Err(ApiError::bad_request(format!(
+                    "Invalid boolean '{text}' for '{key}', expected true or false"
+                ))),
+            },
+            Self::Text => Ok(serde_json::Value::String(text.to_string())),
+        }
+    }
+}
+
+/// Fold the filter keys among the query parameters `query` into `params.filter`.
+///
+/// Keys already in `filter` keep their JSON value. A malformed `filter` is ignored as it
+/// would be without query parameters.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if a numeric or boolean column gets a value of another
+/// type.
+pub fn merge_query_filters<T: CRUDResource>(
+    mut params: FilterOptions,
+    query: &[(String, String)],
+) -> Result<FilterOptions, ApiError> {
+    let mut grouped: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (key, value) in query {
+        if !RESERVED.contains(&key.as_str()) {
+            grouped.entry(key).or_default().push(value);
+        }
+    }
+    if grouped.is_empty() {
+        return Ok(params);
+    }
+
+    let mut filters = match params.filter.as_deref().map(serde_json::from_str) {
+        Some(Ok(serde_json::Value::Object(filters))) => filters,
+        _ => serde_json::Map::new(),
+    };
+    let columns = T::filterable_columns();
+    let aliases = T::field_aliases();
+    for (key, values) in grouped {
+        if filters.contains_key(key) {
+            continue;
+        }
+        // Renamed fields are typed by the column behind their external name
+        let field = translate_key(key, &aliases).unwrap_or_else(|| key.to_string());
+        let clause = column_clause::<T>(&field, &columns);
+        let kind = clause
+            .as_ref()
+            .filter(|clause| clause.operator != "LIKE" && !clause.is_decimal)
+            .map_or(ValueKind::Text, |clause| {
+                ValueKind::of(columns[clause.column].1.def().get_column_type())
+            });
+        let between = clause
+            .as_ref()
+            .is_some_and(|clause| clause.operator == "BETWEEN");
+        filters.insert(key.to_string(), query_value(key, &values, kind, between)?);
+    }
+    params.filter = Some(serde_json::Value::Object(filters).to_string());
+    Ok(params)
+}
+
+/// JSON value of the query parameter `key` given `values` times
+fn query_value(
+    key: &str,
+    values: &[&str],
+    kind: ValueKind,
+    between: bool,
+) -> Result<serde_json::Value, ApiError> {
+    let parts: Vec<&str> = match values {
+        [range] if between => range.split(',').collect(),
+        _ => values.to_vec(),
+    };
+    let mut typed = parts
+        .into_iter()
+        .map(|part| kind.parse(key, part))
+        .collect::<Result<Vec<_>, _>>()?;
+    if typed.len() == 1 && !between {
+        return Ok(typed.remove(0));
+    }
+    Ok(serde_json::Value::Array(typed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_query_values() {
+        let number = query_value("priority_gte", &["3"], ValueKind::Number, false).unwrap();
+        assert_eq!(number, json!(3));
+        let list = query_value("status", &["open", "review"], ValueKind::Text, false).unwrap();
+        assert_eq!(list, json!(["open", "review"]));
+        let range = query_value("price_between", &["1.5,3"], ValueKind::Number, true).unwrap();
+        assert_eq!(range, json!([1.5, 3]));
+        let flag = query_value("done", &["true"], ValueKind::Bool, false).unwrap();
+        assert_eq!(flag, json!(true));
+
+        assert!(query_value("priority", &["high"], ValueKind::Number, false).is_err());
+        assert!(query_value("done", &["yes"], ValueKind::Bool, false).is_err());
+    }
+}
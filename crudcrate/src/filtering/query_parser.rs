@@ -1,4 +1,5 @@
 use serde::Deserialize;
+#[cfg(feature = "openapi")]
 use utoipa::{IntoParams, ToSchema};
 
 /// Query parameters for filtering, pagination, and sorting resources.
@@ -21,6 +22,10 @@ use utoipa::{IntoParams, ToSchema};
 ///   ```json
 ///   {"name": "example"}
 ///   ```
+/// - **Filter into JSON columns:** Use a dot path, optionally with an operator suffix, for example:
+///   ```json
+///   {"metadata.color": "red", "metadata.width_gte": 20}
+///   ```
 ///
 /// # Pagination
 /// Two pagination formats are supported:
@@ -32,8 +37,22 @@ use utoipa::{IntoParams, ToSchema};
 /// ```json
 /// ["id", "ASC"]
 /// ```
-#[derive(Deserialize, IntoParams, ToSchema, Default)]
-#[into_params(parameter_in = Query)]
+/// To sort by several columns, pass an array of pairs, for example:
+/// ```json
+/// [["priority", "DESC"], ["created_at", "ASC"]]
+/// ```
+/// or a comma-separated list with `_asc`/`_desc` suffixes: `priority_desc,created_at_asc`.
+///
+/// # Keyset Pagination
+/// Pass the sort value and ID of the last row seen as `after_value` and `after_id` to get
+/// the rows after it, which stays stable when rows are inserted between pages.
+///
+/// # Facets
+/// On fulltext resources, `facets=status,priority` adds the value counts of those fields
+/// among all matching rows to the response.
+#[derive(Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(IntoParams, ToSchema))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
 pub struct FilterOptions {
     /// JSON-encoded filter for querying resources.
     ///
@@ -42,42 +61,84 @@ pub struct FilterOptions {
     /// - Filtering by a single ID: `{"id": "550e8400-e29b-41d4-a716-446655440000"}`
     /// - Filtering by multiple IDs: `{"id": ["550e8400-e29b-41d4-a716-446655440000", "550e8400-e29b-41d4-a716-446655440001"]}`
     /// - Filtering on other columns: `{"name": "example"}`
-    #[param(example = json!({
+    /// - Filtering into JSON columns: `{"metadata.color": "red"}` (supports `_neq`, `_gt`, `_gte`, `_lt`, `_lte`)
+    /// - Ranges: `{"year_between": [2000, 2010]}`; decimal columns also accept numeric strings: `{"price_gte": "19.99"}`
+    ///
+    /// Each key can also be passed as a query parameter of its own: `?name_like=exa&year_gte=2000`.
+    /// Keys in `filter` take precedence over the same parameter.
+    #[cfg_attr(feature = "openapi", param(example = json!({
         "q": "search text",
         "id": "550e8400-e29b-41d4-a716-446655440000",
         "name": "example"
-    }))]
+    })))]
     pub filter: Option<String>,
     /// Range for pagination in the format "[start, end]".
     ///
     /// Example: `[0,9]`
-    #[param(example = "[0,9]")]
+    #[cfg_attr(feature = "openapi", param(example = "[0,9]"))]
     pub range: Option<String>,
     /// Page number for standard REST pagination (1-based).
     ///
     /// Example: `1`
-    #[param(example = 1)]
+    #[cfg_attr(feature = "openapi", param(example = 1))]
     pub page: Option<u64>,
     /// Number of items per page for standard REST pagination.
     ///
     /// Example: `10`
-    #[param(example = 10)]
+    #[cfg_attr(feature = "openapi", param(example = 10))]
     pub per_page: Option<u64>,
     /// Sort order for the results in the format `["column", "order"]`.
     ///
+    /// Multiple columns: `[["priority", "DESC"], ["created_at", "ASC"]]` or
+    /// `priority_desc,created_at_asc`. Only sortable columns are accepted.
+    ///
     /// Example: `["id", "ASC"]`
-    #[param(example = r#"["id", "ASC"]"#)]
+    #[cfg_attr(feature = "openapi", param(example = r#"["id", "ASC"]"#))]
     pub sort: Option<String>,
     /// Sort column for standard REST format.
     ///
     /// Example: `title`
-    #[param(example = "title")]
+    #[cfg_attr(feature = "openapi", param(example = "title"))]
     pub sort_by: Option<String>,
     /// Sort order for standard REST format (ASC or DESC).
     ///
     /// Example: `ASC`
-    #[param(example = "ASC")]
+    #[cfg_attr(feature = "openapi", param(example = "ASC"))]
     pub order: Option<String>,
+    /// Keyset pagination: ID of the last row of the previous page.
+    ///
+    /// Returns the rows after it in the current sort order, ignoring `range`/`page`
+    /// offsets. Requires a single sort column.
+    #[cfg_attr(
+        feature = "openapi",
+        param(example = "550e8400-e29b-41d4-a716-446655440000")
+    )]
+    pub after_id: Option<String>,
+    /// Keyset pagination: sort column value of the last row of the previous page.
+    ///
+    /// Required with `after_id` unless sorting by ID.
+    ///
+    /// Example: `2024-05-01T10:00:00Z`
+    #[cfg_attr(feature = "openapi", param(example = "2024-05-01T10:00:00Z"))]
+    pub after_value: Option<String>,
+    /// Comma-separated filterable fields to count values of (fulltext resources only).
+    ///
+    /// The response becomes `{"items": [...], "facets": {"status": [{"value": "open",
+    /// "count": 3}]}}`, with counts over every row matching `filter`.
+    ///
+    /// Example: `status,priority`
+    #[cfg_attr(feature = "openapi", param(example = "status,priority"))]
+    pub facets: Option<String>,
+    /// Instant to list a `temporal` resource at, as an RFC 3339 timestamp or a date
+    /// (midnight UTC). Defaults to now; ignored by other resources.
+    ///
+    /// Example: `2024-05-01T10:00:00Z`
+    #[cfg_attr(feature = "openapi", param(example = "2024-05-01T10:00:00Z"))]
+    pub as_of: Option<String>,
+    /// Return the query plan instead of the rows (`debug` feature only).
+    #[cfg(feature = "debug")]
+    #[serde(rename = "__explain")]
+    pub explain: Option<bool>,
 }
 
 /// Query parameters for batch operations.
@@ -129,8 +190,9 @@ pub struct FilterOptions {
 ///   ]
 /// }
 /// ```
-#[derive(Deserialize, IntoParams, ToSchema, Default, Clone)]
-#[into_params(parameter_in = Query)]
+#[derive(Deserialize, Default, Clone)]
+#[cfg_attr(feature = "openapi", derive(IntoParams, ToSchema))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
 pub struct BatchOptions {
     /// Enable partial success mode for batch operations.
     ///
@@ -139,7 +201,99 @@ pub struct BatchOptions {
     /// other items fail.
     ///
     /// Default: `false` (all-or-nothing)
-    #[param(example = false)]
+    #[cfg_attr(feature = "openapi", param(example = false))]
     #[serde(default)]
     pub partial: bool,
+
+    /// Batch updates only: apply the items in one transaction and answer a result per
+    /// item, with its status (200, 404, 422, ...). See
+    /// [`batch_update`](crate::core::batch_update).
+    ///
+    /// Default: `false`
+    #[cfg_attr(feature = "openapi", param(example = false))]
+    #[serde(default)]
+    pub per_item: bool,
+}
+
+/// Query parameters for deleting by filter
+///
+/// ```bash
+/// # How many rows would be deleted?
+/// DELETE /resources?filter={"status":"archived"}&count_only=true
+///
+/// # Delete them
+/// DELETE /resources?filter={"status":"archived"}
+/// ```
+#[derive(Deserialize, Default, Clone)]
+#[cfg_attr(feature = "openapi", derive(IntoParams, ToSchema))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct DeleteFilterOptions {
+    /// Return the number of matching rows without deleting them.
+    ///
+    /// Default: `false`
+    #[cfg_attr(feature = "openapi", param(example = false))]
+    #[serde(default)]
+    pub count_only: bool,
+}
+
+/// Query parameters for the `tree(...)` children endpoint
+///
+/// ```bash
+/// # Direct children
+/// GET /categories/{id}/children
+///
+/// # Children, grandchildren and great-grandchildren
+/// GET /categories/{id}/children?depth=3
+/// ```
+#[derive(Deserialize, Default, Clone)]
+#[cfg_attr(feature = "openapi", derive(IntoParams, ToSchema))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct TreeOptions {
+    /// Number of levels to return, up to the resource's `max_depth`.
+    ///
+    /// Default: `1` (direct children)
+    #[cfg_attr(feature = "openapi", param(example = 1))]
+    pub depth: Option<u32>,
+}
+
+/// Query parameters for the distinct values of a filterable field
+///
+/// ```bash
+/// # Values of `status` among the matching rows
+/// GET /tasks/distinct/status?filter={"archived":false}
+///
+/// # With the number of rows for each value
+/// GET /tasks/distinct/status?counts=true
+/// ```
+#[derive(Deserialize, Default, Clone)]
+#[cfg_attr(feature = "openapi", derive(IntoParams, ToSchema))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct DistinctOptions {
+    /// Add the number of matching rows to each value (`{"value": ..., "count": n}`).
+    ///
+    /// Default: `false`
+    #[cfg_attr(feature = "openapi", param(example = false))]
+    #[serde(default)]
+    pub counts: bool,
+}
+
+/// Query parameters for fetching several items by ID
+///
+/// ```bash
+/// GET /tasks/by-ids?ids=3f0c…,9a1b…,77d2…
+/// ```
+#[derive(Deserialize, Default, Clone)]
+#[cfg_attr(feature = "openapi", derive(IntoParams, ToSchema))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct ByIdsOptions {
+    /// Comma-separated IDs. Items come back in this order; IDs without an item are
+    /// listed under `missing`.
+    #[cfg_attr(
+        feature = "openapi",
+        param(
+            example = "3f0c5b8e-1d2a-4c3b-9e8f-7a6b5c4d3e2f,9a1b2c3d-4e5f-4a6b-8c7d-0e1f2a3b4c5d"
+        )
+    )]
+    #[serde(default)]
+    pub ids: String,
 }
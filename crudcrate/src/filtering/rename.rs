@@ -0,0 +1,222 @@
+//! Renamed and aliased field names in query parameters.
+//!
+//! Fields declared with `#[crudcrate(rename = "externalName", alias = "legacy_name")]`
+//! serialize under their external name, so clients filter and sort by it too. The list
+//! handler passes its [`FilterOptions`] through [`translate_field_names`] first, which
+//! maps external names (with any operator suffix or dot path) back to field names. The
+//! rest of the filter and sort pipeline, and every SQL identifier, keeps using the
+//! field names.
+
+use crate::core::CRUDResource;
+use crate::filtering::FilterOptions;
+
+/// Operator suffixes that may follow a field name in a filter key
+const OPERATORS: &[&str] = &[
+    "neq", "gt", "gte", "lt", "lte", "like", "between", "contains", "overlaps", "within", "date",
+    "year",
+];
+
+/// Rewrite external field names in `filter`, `sort` and `sort_by` to field names.
+///
+/// Parameters that don't parse are left as they are for the regular parsers to reject.
+#[must_use]
+pub fn translate_field_names<T: CRUDResource>(mut params: FilterOptions) -> FilterOptions {
+    let aliases = T::field_aliases();
+    if aliases.is_empty() {
+        return params;
+    }
+
+    if let Some(filter) = params.filter.as_deref()
+        && let Some(translated) = translate_filter(filter, &aliases)
+    {
+        params.filter = Some(translated);
+    }
+    if let Some(sort) = params.sort.as_deref()
+        && let Some(translated) = translate_sort(sort, &aliases)
+    {
+        params.sort = Some(translated);
+    }
+    if let Some(sort_by) = params.sort_by.as_deref()
+        && let Some(field) = translate_key(sort_by, &aliases)
+    {
+        params.sort_by = Some(field);
+    }
+    params
+}
+
+/// The filter JSON with translated keys, or `None` if nothing changed
+fn translate_filter(filter: &str, aliases: &[(&str, &str)]) -> Option<String> {
+    let serde_json::Value::Object(map) = serde_json::from_str(filter).ok()? else {
+        return None;
+    };
+    let mut changed = false;
+    let translated: serde_json::Map<String, serde_json::Value> = map
+        .into_iter()
+        .map(|(key, value)| match translate_key(&key, aliases) {
+            Some(field) => {
+                changed = true;
+                (field, value)
+            }
+            None => (key, value),
+        })
+        .collect();
+    changed.then(|| serde_json::Value::Object(translated).to_string())
+}
+
+/// The `sort` parameter with translated column names, or `None` if nothing changed.
+///
+/// Handles `["col","ASC"]`, `[["a","DESC"],["b","ASC"]]` and `a_desc,b`.
+fn translate_sort(sort: &str, aliases: &[(&str, &str)]) -> Option<String> {
+    if !sort.trim_start().starts_with('[') {
+        let mut changed = false;
+        let keys: Vec<String> = sort
+            .split(',')
+            .map(|key| {
+                let key = key.trim();
+                let (column, suffix) = ["_asc", "_desc"]
+                    .iter()
+                    .find_map(|suffix| key.strip_suffix(suffix).map(|column| (column, *suffix)))
+                    .unwrap_or((key, ""));
+                match translate_key(key, aliases).or_else(|| {
+                    translate_key(column, aliases).map(|field| format!("{field}{suffix}"))
+                }) {
+                    Some(field) => {
+                        changed = true;
+                        field
+                    }
+                    None => key.to_string(),
+                }
+            })
+            .collect();
+        return changed.then(|| keys.join(","));
+    }
+
+    let mut sort_vec: Vec<serde_json::Value> = serde_json::from_str(sort).ok()?;
+    let mut changed = false;
+    let mut translate = |column: &mut serde_json::Value| {
+        if let serde_json::Value::String(name) = column
+            && let Some(field) = translate_key(name, aliases)
+        {
+            *name = field;
+            changed = true;
+        }
+    };
+    if sort_vec.first().is_some_and(serde_json::Value::is_array) {
+        for pair in &mut sort_vec {
+            if let Some(column) = pair.as_array_mut().and_then(|pair| pair.first_mut()) {
+                translate(column);
+            }
+        }
+    } else if let Some(column) = sort_vec.first_mut() {
+        translate(column);
+    }
+    changed.then(|| serde_json::Value::Array(sort_vec).to_string())
+}
+
+/// Map `external`, `external_<op>` or `external.path` to the field name.
+///
+/// The longest matching external name wins, so `fullName` and `fullNameSort` can coexist.
+pub(super) fn translate_key(key: &str, aliases: &[(&str, &str)]) -> Option<String> {
+    let mut best: Option<(usize, String)> = None;
+    for (external, field) in aliases {
+        let Some(rest) = key.strip_prefix(external) else {
+            continue;
+        };
+        if !is_key_suffix(rest) || best.as_ref().is_some_and(|(len, _)| *len >= external.len()) {
+            continue;
+        }
+        best = Some((external.len(), format!("{field}{rest}")));
+    }
+    best.map(|(_, field)| field)
+}
+
+fn is_key_suffix(rest: &str) -> bool {
+    rest.is_empty()
+        || rest.starts_with('.')
+        || rest
+            .strip_prefix('_')
+            .is_some_and(|ops| ops.split('_').all(|op| OPERATORS.contains(&op)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALIASES: &[(&str, &str)] = &[
+        ("fullName", "full_name"),
+        ("legacy_name", "full_name"),
+        ("fullNameSort", "sort_key"),
+        ("meta", "metadata"),
+    ];
+
+    #[test]
+    fn test_translate_key() {
+        assert_eq!(
+            translate_key("fullName", ALIASES).as_deref(),
+            Some("full_name")
+        );
+        assert_eq!(
+            translate_key("legacy_name", ALIASES).as_deref(),
+            Some("full_name")
+        );
+        assert_eq!(
+            translate_key("fullName_neq", ALIASES).as_deref(),
+            Some("full_name_neq")
+        );
+        assert_eq!(
+            translate_key("fullNameSort_gte", ALIASES).as_deref(),
+            Some("sort_key_gte")
+        );
+        assert_eq!(
+            translate_key("meta.color_lte", ALIASES).as_deref(),
+            Some("metadata.color_lte")
+        );
+        assert_eq!(
+            translate_key("fullName_date_gte", ALIASES).as_deref(),
+            Some("full_name_date_gte")
+        );
+    }
+
+    #[test]
+    fn test_translate_key_ignores_other_fields() {
+        // `metadata_extra` is a different field, not `meta` with a suffix
+        assert_eq!(translate_key("metadata_extra", ALIASES), None);
+        assert_eq!(translate_key("full_name", ALIASES), None);
+        assert_eq!(translate_key("q", ALIASES), None);
+    }
+
+    #[test]
+    fn test_translate_sort() {
+        assert_eq!(
+            translate_sort(r#"["fullName","DESC"]"#, ALIASES).as_deref(),
+            Some(r#"["full_name","DESC"]"#)
+        );
+        assert_eq!(
+            translate_sort(r#"[["fullName","DESC"],["age","ASC"]]"#, ALIASES).as_deref(),
+            Some(r#"[["full_name","DESC"],["age","ASC"]]"#)
+        );
+        assert_eq!(
+            translate_sort("fullName_desc,age", ALIASES).as_deref(),
+            Some("full_name_desc,age")
+        );
+        assert_eq!(
+            translate_sort("fullName", ALIASES).as_deref(),
+            Some("full_name")
+        );
+        assert_eq!(translate_sort("age_desc,name", ALIASES), None);
+        assert_eq!(translate_sort(r#"[["age","ASC"]]"#, ALIASES), None);
+    }
+
+    #[test]
+    fn test_translate_filter() {
+        let translated =
+            translate_filter(r#"{"fullName_neq":"Ada","q":"x","age":3}"#, ALIASES).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&translated).unwrap();
+        assert_eq!(value["full_name_neq"], "Ada");
+        assert_eq!(value["q"], "x");
+        assert_eq!(value["age"], 3);
+
+        assert_eq!(translate_filter(r#"{"age":3}"#, ALIASES), None);
+        assert_eq!(translate_filter("not json", ALIASES), None);
+    }
+}
@@ -1,15 +1,21 @@
 use sea_orm::{DatabaseBackend, sea_query::SimpleExpr};
 
+use super::like;
+
 // Basic safety limits
 const MAX_SEARCH_QUERY_LENGTH: usize = 10_000;
 
-/// Escape LIKE wildcards to prevent wildcard injection attacks
-/// Escapes: % (match any) and _ (match single char)
-fn escape_like_wildcards(input: &str) -> String {
-    input
-        .replace('\\', "\\\\") // Escape backslash first
-        .replace('%', "\\%") // Escape %
-        .replace('_', "\\_") // Escape _
+/// `query` cut to [`MAX_SEARCH_QUERY_LENGTH`] bytes on a character boundary, trimmed
+fn sanitize(query: &str) -> &str {
+    like::truncate(query, MAX_SEARCH_QUERY_LENGTH).trim()
+}
+
+/// Whether `T` can be searched with `q`: it has `fulltext` fields, a `tsvector_column`
+/// or an `fts5_table`
+pub(crate) fn has_fulltext<T: crate::traits::CRUDResource>() -> bool {
+    !T::fulltext_searchable_columns().is_empty()
+        || T::TSVECTOR_COLUMN.is_some()
+        || T::FTS5_TABLE.is_some()
 }
 
 /// Build fulltext search condition with database-specific optimizations
@@ -22,19 +28,164 @@ pub fn build_fulltext_condition<T: crate::traits::CRUDResource>(
     if fulltext_columns.is_empty() {
         return None;
     }
+    #[cfg(feature = "unicode")]
+    if let Some(column) = T::NORMALIZED_SEARCH_COLUMN {
+        return build_normalized_condition(query, column);
+    }
 
     match backend {
-        DatabaseBackend::Postgres => build_postgres_fulltext_condition(query, &fulltext_columns),
-        DatabaseBackend::MySql => build_mysql_fulltext_condition(query, &fulltext_columns),
-        _ => build_fallback_fulltext_condition(query, &fulltext_columns),
+        DatabaseBackend::Postgres => build_postgres_condition::<T>(query, &fulltext_columns),
+        DatabaseBackend::MySql => build_mysql_condition::<T>(query, &fulltext_columns),
+        DatabaseBackend::Sqlite => match T::FTS5_TABLE {
+            Some(fts) => build_sqlite_fts5_condition(query, T::TABLE_NAME, fts),
+            None => build_fallback_fulltext_condition(query, &fulltext_columns),
+        },
+    }
+}
+
+/// Build accent-insensitive search against a `normalized_search` column: the column
+/// holds folded text, so the folded query is matched with a plain LIKE on every backend
+#[cfg(feature = "unicode")]
+fn build_normalized_condition(query: &str, column: &str) -> Option<SimpleExpr> {
+    use sea_orm::sea_query::{Alias, Expr};
+
+    let folded = crate::normalize::fold(sanitize(query));
+    let folded = folded.trim();
+    if folded.is_empty() {
+        return None;
+    }
+    Some(Expr::col(Alias::new(column)).like(like::contains(folded)))
+}
+
+/// Order by FTS5 `bm25` relevance when a resource with an `fts5_table` is searched on
+/// `SQLite` with `q` and no explicit sort. Best matches come first.
+#[must_use]
+pub fn relevance_order<T: crate::traits::CRUDResource>(
+    params: &crate::models::FilterOptions,
+    backend: DatabaseBackend,
+) -> Option<(SimpleExpr, sea_orm::Order)> {
+    use sea_orm::sea_query::Expr;
+
+    if backend != DatabaseBackend::Sqlite || params.sort.is_some() || params.sort_by.is_some() {
+        return None;
+    }
+    let fts = T::FTS5_TABLE?;
+    if T::fulltext_searchable_columns().is_empty() {
+        return None;
+    }
+    let query = params
+        .filter
+        .as_deref()
+        .and_then(|f| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(f).ok())
+        .and_then(|filters| filters.get("q")?.as_str().and_then(fts5_query))?;
+
+    let (table, fts) = (quote_ident(T::TABLE_NAME), quote_ident(fts));
+    let expr = Expr::cust_with_values(
+        format!(
+            "(SELECT bm25({fts}) FROM {fts} WHERE {fts} MATCH ? AND {fts}.rowid = {table}.rowid)"
+        ),
+        [query],
+    );
+    Some((expr, sea_orm::Order::Asc))
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Turn free text into an FTS5 query: every word becomes a quoted prefix term, so FTS5
+/// operators and syntax characters in user input are matched literally. Words without
+/// any letter or digit are dropped, since they contain no token to match.
+fn fts5_query(query: &str) -> Option<String> {
+    let sanitized_query = sanitize(query);
+    let terms: Vec<String> = sanitized_query
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
     }
 }
 
+/// Build `SQLite` FTS5 search against the resource's external-content FTS5 table
+/// The query is bound as a parameter, not interpolated
+fn build_sqlite_fts5_condition(query: &str, table: &str, fts: &str) -> Option<SimpleExpr> {
+    use sea_orm::sea_query::Expr;
+
+    if query.trim().is_empty() {
+        return None;
+    }
+    // Punctuation-only searches can't match any token
+    let Some(query) = fts5_query(query) else {
+        return Some(Expr::cust("1 = 0"));
+    };
+    let (table, fts) = (quote_ident(table), quote_ident(fts));
+    Some(Expr::cust_with_values(
+        format!("{table}.rowid IN (SELECT rowid FROM {fts} WHERE {fts} MATCH ?)"),
+        [query],
+    ))
+}
+
+/// Combine the `PostgreSQL` strategies: `tsvector` column or ILIKE for regular fulltext
+/// fields, OR'd with `pg_trgm` similarity for `fulltext(strategy = "trigram")` fields
+fn build_postgres_condition<T: crate::traits::CRUDResource>(
+    query: &str,
+    fulltext_columns: &[(&'static str, T::ColumnType)],
+) -> Option<SimpleExpr> {
+    let trigram_columns = T::trigram_searchable_columns();
+
+    let base = if let Some(column) = T::TSVECTOR_COLUMN {
+        build_postgres_tsvector_condition(query, column, T::FULLTEXT_LANGUAGE)
+    } else {
+        let regular: Vec<_> = fulltext_columns
+            .iter()
+            .filter(|(name, _)| !trigram_columns.iter().any(|(t, _, _)| t == name))
+            .map(|(name, column)| (*name, *column))
+            .collect();
+        build_postgres_fulltext_condition(query, &regular)
+    };
+
+    let trigram = trigram_columns
+        .iter()
+        .filter_map(|(name, _, threshold)| build_trigram_condition(query, name, *threshold));
+
+    base.into_iter().chain(trigram).reduce(SimpleExpr::or)
+}
+
+/// Build `pg_trgm` similarity condition for a single column
+/// Query text and threshold are bound as parameters
+pub(crate) fn build_trigram_condition(
+    query: &str,
+    column: &str,
+    threshold: f64,
+) -> Option<SimpleExpr> {
+    use sea_orm::{Value, sea_query::Expr};
+
+    let sanitized_query = sanitize(query);
+    if sanitized_query.is_empty() {
+        return None;
+    }
+
+    let column = column.replace('"', "\"\"");
+    Some(Expr::cust_with_values(
+        format!("similarity(COALESCE(\"{column}\"::text, ''), ?) > ?"),
+        [
+            Value::from(sanitized_query.to_string()),
+            Value::from(threshold),
+        ],
+    ))
+}
+
 /// Build PostgreSQL-specific fulltext search using ILIKE for case-insensitive matching
 fn build_postgres_fulltext_condition(
     query: &str,
     columns: &[(&'static str, impl sea_orm::ColumnTrait)],
 ) -> Option<SimpleExpr> {
+    use sea_orm::sea_query::Expr;
+
     if columns.is_empty() || query.is_empty() {
         return None;
     }
@@ -47,17 +198,129 @@ fn build_postgres_fulltext_condition(
     }
 
     let concat_sql = concat_parts.join(" || ' ' || ");
-    let sanitized_query = query[..query.len().min(MAX_SEARCH_QUERY_LENGTH)].trim();
-
-    // Escape both SQL quotes and LIKE wildcards
-    let escaped_query = escape_like_wildcards(sanitized_query).replace('\'', "''");
+    let sanitized_query = sanitize(query);
 
     // Use ILIKE for case-insensitive substring matching (no pg_trgm extension required)
-    // Note: LIKE wildcards are escaped, ESCAPE '\' tells PostgreSQL to respect our escaping
-    let search_sql = format!("({concat_sql}) ILIKE '%{escaped_query}%' ESCAPE '\\'");
+    // The pattern is bound; ESCAPE '\' makes PostgreSQL honour its escaped wildcards
+    Some(Expr::cust_with_values(
+        format!("({concat_sql}) ILIKE ? ESCAPE '\\'"),
+        [like::contains_pattern(sanitized_query)],
+    ))
+}
+
+/// Build `PostgreSQL` `tsvector` search against a stored generated column
+/// Query text and language are bound as parameters, not interpolated
+fn build_postgres_tsvector_condition(
+    query: &str,
+    column: &str,
+    language: &str,
+) -> Option<SimpleExpr> {
+    use sea_orm::sea_query::Expr;
 
-    // Use custom SQL expression
-    Some(SimpleExpr::Custom(search_sql))
+    let sanitized_query = sanitize(query);
+    if sanitized_query.is_empty() {
+        return None;
+    }
+
+    let column = column.replace('"', "\"\"");
+    Some(Expr::cust_with_values(
+        format!("\"{column}\" @@ plainto_tsquery(CAST(? AS regconfig), ?)"),
+        [language.to_string(), sanitized_query.to_string()],
+    ))
+}
+
+/// Combine the `MySQL` strategies: boolean-mode `MATCH ... AGAINST` for
+/// `fulltext(mode = "boolean")` fields, OR'd with LIKE over the other fulltext fields
+fn build_mysql_condition<T: crate::traits::CRUDResource>(
+    query: &str,
+    fulltext_columns: &[(&'static str, T::ColumnType)],
+) -> Option<SimpleExpr> {
+    use sea_orm::IdenStatic;
+
+    let boolean_columns = T::boolean_fulltext_columns();
+    if boolean_columns.is_empty() {
+        return build_mysql_fulltext_condition(query, fulltext_columns);
+    }
+
+    let regular: Vec<_> = fulltext_columns
+        .iter()
+        .filter(|(name, _)| !boolean_columns.iter().any(|(b, _)| b == name))
+        .map(|(name, column)| (*name, *column))
+        .collect();
+    let boolean_names: Vec<&str> = boolean_columns.iter().map(|(_, c)| c.as_str()).collect();
+
+    build_mysql_boolean_condition(query, &boolean_names)
+        .into_iter()
+        .chain(build_mysql_fulltext_condition(query, &regular))
+        .reduce(SimpleExpr::or)
+}
+
+/// Build `MySQL` boolean-mode fulltext search over columns sharing one FULLTEXT index
+/// The query is rewritten by [`mysql_boolean_query`] and bound as a parameter
+fn build_mysql_boolean_condition(query: &str, columns: &[&str]) -> Option<SimpleExpr> {
+    use sea_orm::sea_query::Expr;
+
+    if columns.is_empty() {
+        return None;
+    }
+    let query = mysql_boolean_query(query)?;
+    let columns = columns
+        .iter()
+        .map(|c| format!("`{}`", c.replace('`', "``")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(Expr::cust_with_values(
+        format!("MATCH({columns}) AGAINST (? IN BOOLEAN MODE)"),
+        [query],
+    ))
+}
+
+/// Rewrite free text into a safe `MySQL` boolean-mode query.
+///
+/// Keeps `+term`, `-term`, `"phrase"` and a trailing `*` on single words. Every other
+/// operator character (`<`, `>`, `(`, `)`, `~`, `@`, stray quotes) is dropped, and words
+/// joined by punctuation (`web-dev`) become phrases so the punctuation can't act as an
+/// operator. An unbalanced quote runs to the end of the query.
+fn mysql_boolean_query(query: &str) -> Option<String> {
+    fn words(text: &str) -> Vec<&str> {
+        text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|w| !w.is_empty())
+            .collect()
+    }
+
+    let mut rest = sanitize(query);
+    let mut terms = Vec::new();
+    while !rest.is_empty() {
+        let (operator, after) = match rest.as_bytes()[0] {
+            b'+' => ("+", &rest[1..]),
+            b'-' => ("-", &rest[1..]),
+            _ => ("", rest),
+        };
+        let (text, next, phrase) = if let Some(inner) = after.strip_prefix('"') {
+            let (text, next) = inner.split_once('"').unwrap_or((inner, ""));
+            (text, next, true)
+        } else {
+            let end = after.find(char::is_whitespace).unwrap_or(after.len());
+            let (text, next) = after.split_at(end);
+            (text, next, false)
+        };
+        let text_words = words(text);
+        match text_words.as_slice() {
+            [] => {}
+            [word] if !phrase => {
+                let prefix = if text.ends_with('*') { "*" } else { "" };
+                terms.push(format!("{operator}{word}{prefix}"));
+            }
+            _ => terms.push(format!("{operator}\"{}\"", text_words.join(" "))),
+        }
+        rest = next.trim_start();
+    }
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
 }
 
 /// Build MySQL-specific fulltext search using CONCAT and LIKE
@@ -65,6 +328,8 @@ fn build_mysql_fulltext_condition(
     query: &str,
     columns: &[(&'static str, impl sea_orm::ColumnTrait)],
 ) -> Option<SimpleExpr> {
+    use sea_orm::sea_query::Expr;
+
     if columns.is_empty() || query.is_empty() {
         return None;
     }
@@ -83,16 +348,14 @@ fn build_mysql_fulltext_condition(
         format!("CONCAT({})", concat_parts.join(", ' ', "))
     };
 
-    let sanitized_query = query[..query.len().min(MAX_SEARCH_QUERY_LENGTH)].trim();
-
-    // Escape both SQL quotes and LIKE wildcards
-    let escaped_query = escape_like_wildcards(sanitized_query).replace('\'', "''");
+    let sanitized_query = sanitize(query);
 
     // MySQL LIKE is case-insensitive by default for non-binary columns
-    // Use ESCAPE '\\' for wildcard escaping (MySQL uses double backslash in string literals)
-    let search_sql = format!("UPPER({concat_sql}) LIKE UPPER('%{escaped_query}%') ESCAPE '\\\\'");
-
-    Some(SimpleExpr::Custom(search_sql))
+    // The pattern is bound; ESCAPE '\\' is one backslash in a MySQL string literal
+    Some(Expr::cust_with_values(
+        format!("UPPER({concat_sql}) LIKE UPPER(?) ESCAPE '\\\\'"),
+        [like::contains_pattern(sanitized_query)],
+    ))
 }
 
 /// Build fallback fulltext search for `SQLite` and other standard SQL databases
@@ -101,6 +364,8 @@ fn build_fallback_fulltext_condition(
     query: &str,
     columns: &[(&'static str, impl sea_orm::ColumnTrait)],
 ) -> Option<SimpleExpr> {
+    use sea_orm::sea_query::Expr;
+
     if columns.is_empty() || query.is_empty() {
         return None;
     }
@@ -114,15 +379,14 @@ fn build_fallback_fulltext_condition(
 
     let concat_sql = concat_parts.join(" || ' ' || ");
     // Additional security: validate and sanitize query
-    let sanitized_query = query[..query.len().min(MAX_SEARCH_QUERY_LENGTH)].trim();
-
-    // Escape both LIKE wildcards and SQL quotes
-    let escaped_query = escape_like_wildcards(sanitized_query).replace('\'', "''");
-
-    let like_sql = format!("UPPER({concat_sql}) LIKE UPPER('%{escaped_query}%') ESCAPE '\\'",);
-
-    // Use custom SQL expression
-    Some(SimpleExpr::Custom(like_sql))
+    let sanitized_query = sanitize(query);
+
+    // The pattern is bound; SQLite has no default escape character, so ESCAPE '\' is
+    // what keeps escaped wildcards literal
+    Some(Expr::cust_with_values(
+        format!("UPPER({concat_sql}) LIKE UPPER(?) ESCAPE '\\'"),
+        [like::contains_pattern(sanitized_query)],
+    ))
 }
 
 /// Build condition for string field with LIKE queries (case-insensitive)
@@ -133,14 +397,9 @@ pub fn build_like_condition(key: &str, trimmed_value: &str) -> SimpleExpr {
     // Use Expr::col() to properly quote column names instead of string interpolation
     let column = Expr::col(Alias::new(key));
 
-    // Escape LIKE wildcards to prevent injection attacks
-    let escaped_value = escape_like_wildcards(trimmed_value);
-
-    // Build UPPER(column) LIKE UPPER('%value%') ESCAPE '\'
-    // Case-insensitive pattern matching with wildcard escaping
-    let pattern = format!("%{}%", escaped_value.to_uppercase());
-
-    Func::upper(column).like(pattern)
+    // Build UPPER(column) LIKE '%VALUE%' ESCAPE '\'
+    // Case-insensitive pattern matching; wildcards in the value are escaped
+    Func::upper(column).like(like::contains(&trimmed_value.to_uppercase()))
 }
 
 #[cfg(test)]
@@ -211,37 +470,33 @@ mod tests {
     #[test]
     fn test_wildcard_escaping() {
         assert_eq!(
-            escape_like_wildcards("test"),
+            like::escape_like("test"),
             "test",
             "Normal text should pass through"
         );
+        assert_eq!(like::escape_like("test%"), "test\\%", "% should be escaped");
         assert_eq!(
-            escape_like_wildcards("test%"),
-            "test\\%",
-            "% should be escaped"
-        );
-        assert_eq!(
-            escape_like_wildcards("test_value"),
+            like::escape_like("test_value"),
             "test\\_value",
             "_ should be escaped"
         );
         assert_eq!(
-            escape_like_wildcards("100%"),
+            like::escape_like("100%"),
             "100\\%",
             "% in middle should be escaped"
         );
         assert_eq!(
-            escape_like_wildcards("%_"),
+            like::escape_like("%_"),
             "\\%\\_",
             "Both wildcards should be escaped"
         );
         assert_eq!(
-            escape_like_wildcards("\\"),
+            like::escape_like("\\"),
             "\\\\",
             "Backslash should be escaped"
         );
         assert_eq!(
-            escape_like_wildcards("\\%"),
+            like::escape_like("\\%"),
             "\\\\\\%",
             "Backslash and % should both be escaped"
         );
@@ -355,11 +610,136 @@ mod tests {
         );
     }
 
+    /// Tsvector search binds the query instead of interpolating it
+    #[test]
+    fn test_tsvector_condition_parameterized() {
+        let result =
+            build_postgres_tsvector_condition("o'reilly books", "search_vector", "english")
+                .expect("Non-empty query should produce a condition");
+        let sql = format!("{result:?}");
+        assert!(
+            sql.contains("\\\"search_vector\\\" @@ plainto_tsquery(CAST(? AS regconfig), ?)"),
+            "Should query the tsvector column: {sql}"
+        );
+        assert!(
+            sql.contains("o'reilly books"),
+            "Query should be passed as a bound value: {sql}"
+        );
+        assert!(
+            sql.contains("english"),
+            "Language should be passed as a bound value: {sql}"
+        );
+    }
+
+    /// Tsvector search ignores whitespace-only queries
+    #[test]
+    fn test_tsvector_condition_empty_query() {
+        assert!(build_postgres_tsvector_condition("   ", "search_vector", "english").is_none());
+    }
+
+    #[test]
+    fn test_fts5_query_quotes_terms() {
+        assert_eq!(
+            fts5_query("  rust  OR web-dev ").as_deref(),
+            Some("\"rust\"* \"OR\"* \"web-dev\"*")
+        );
+        assert_eq!(
+            fts5_query("say \"hi\"").as_deref(),
+            Some("\"say\"* \"\"\"hi\"\"\"*")
+        );
+        assert!(fts5_query("   ").is_none());
+        assert_eq!(fts5_query("* - c++").as_deref(), Some("\"c++\"*"));
+        assert!(fts5_query("* -").is_none());
+    }
+
+    #[test]
+    fn test_fts5_condition_parameterized() {
+        let result = build_sqlite_fts5_condition("o'reilly", "books", "books_fts")
+            .expect("Non-empty query should produce a condition");
+        let sql = format!("{result:?}");
+        assert!(
+            sql.contains(
+                "\\\"books\\\".rowid IN (SELECT rowid FROM \\\"books_fts\\\" WHERE \\\"books_fts\\\" MATCH ?)"
+            ),
+            "Should match against the FTS5 table: {sql}"
+        );
+        assert!(
+            sql.contains("o'reilly"),
+            "Query should be passed as a bound value: {sql}"
+        );
+    }
+
+    #[test]
+    fn test_mysql_boolean_query_keeps_supported_operators() {
+        assert_eq!(
+            mysql_boolean_query("+rust -java \"memory safety\" async*").as_deref(),
+            Some("+rust -java \"memory safety\" async*")
+        );
+        assert_eq!(
+            mysql_boolean_query("-\"garbage collector\"").as_deref(),
+            Some("-\"garbage collector\"")
+        );
+    }
+
+    #[test]
+    fn test_mysql_boolean_query_strips_other_syntax() {
+        assert_eq!(
+            mysql_boolean_query(">rust <(java) ~go @3 web-dev").as_deref(),
+            Some("rust java go 3 \"web dev\"")
+        );
+        assert_eq!(
+            mysql_boolean_query("+-rust ++ \"unbalanced phrase").as_deref(),
+            Some("+rust \"unbalanced phrase\"")
+        );
+        assert_eq!(
+            mysql_boolean_query("o'reilly").as_deref(),
+            Some("\"o reilly\"")
+        );
+        assert!(mysql_boolean_query("+ - \"\" ()").is_none());
+        assert!(mysql_boolean_query("   ").is_none());
+    }
+
+    #[test]
+    fn test_mysql_boolean_condition_parameterized() {
+        let result = build_mysql_boolean_condition("+rust 'x'", &["title", "body"])
+            .expect("Non-empty query should produce a condition");
+        let sql = format!("{result:?}");
+        assert!(
+            sql.contains("MATCH(`title`, `body`) AGAINST (? IN BOOLEAN MODE)"),
+            "Should use boolean mode: {sql}"
+        );
+        assert!(
+            sql.contains("+rust x"),
+            "Query should be passed as a bound value: {sql}"
+        );
+        assert!(build_mysql_boolean_condition("rust", &[]).is_none());
+    }
+
+    /// Trigram search binds both the query and the threshold
+    #[test]
+    fn test_trigram_condition_parameterized() {
+        let result = build_trigram_condition("jon smith", "name", 0.4)
+            .expect("Non-empty query should produce a condition");
+        let sql = format!("{result:?}");
+        assert!(
+            sql.contains("similarity(COALESCE(\\\"name\\\"::text, ''), ?) > ?"),
+            "Should use pg_trgm similarity: {sql}"
+        );
+        assert!(sql.contains("jon smith"), "Query should be bound: {sql}");
+        assert!(sql.contains("0.4"), "Threshold should be bound: {sql}");
+    }
+
+    /// Trigram search ignores whitespace-only queries
+    #[test]
+    fn test_trigram_condition_empty_query() {
+        assert!(build_trigram_condition("  ", "name", 0.3).is_none());
+    }
+
     /// Test escape function handles empty string
     #[test]
     fn test_escape_like_wildcards_empty() {
         assert_eq!(
-            escape_like_wildcards(""),
+            like::escape_like(""),
             "",
             "Empty string should pass through"
         );
@@ -1,9 +1,16 @@
+use crate::errors::ApiError;
 use sea_orm::{ColumnTrait, sea_query::Order};
 
 // Shared default values
 const DEFAULT_SORT_COLUMN: &str = "id";
 const DEFAULT_SORT_ORDER: &str = "ASC";
 
+/// Most columns a single request may sort by
+pub const MAX_SORT_COLUMNS: usize = 8;
+
+/// Suffixes selecting the direction in `sort=priority_desc,created_at_asc`
+const ORDER_SUFFIXES: [(&str, &str); 2] = [("_asc", "ASC"), ("_desc", "DESC")];
+
 /// Parse sort column and order from JSON array format
 fn parse_json_sort(json: &str) -> (String, String) {
     let sort_vec: Vec<String> = serde_json::from_str(json).unwrap_or(vec![
@@ -23,7 +30,7 @@ fn parse_json_sort(json: &str) -> (String, String) {
 }
 
 /// Convert sort order string to Order enum
-fn parse_order(sort_order: &str) -> Order {
+pub(crate) fn parse_order(sort_order: &str) -> Order {
     if sort_order.to_uppercase() == "ASC" {
         Order::Asc
     } else {
@@ -64,16 +71,11 @@ where
     (order_column, order_direction)
 }
 
-/// Parse sorting from `FilterOptions`, supporting both React Admin and standard REST formats
-pub fn parse_sorting<C>(
-    params: &crate::models::FilterOptions,
-    order_column_logic: &[(&str, C)],
-    default_column: C,
-) -> (C, Order)
-where
-    C: ColumnTrait + Copy,
-{
-    let (sort_column, sort_order) = if let Some(sort_by) = &params.sort_by {
+/// Extract the requested sort column and order strings from `FilterOptions`.
+/// `sort_by` takes priority over `sort`; `sort` may be a JSON array (React Admin)
+/// or a plain column name (REST).
+pub(crate) fn requested_sort(params: &crate::models::FilterOptions) -> (String, String) {
+    if let Some(sort_by) = &params.sort_by {
         // Standard REST format: sort_by=column&order=ASC/DESC
         (
             sort_by.clone(),
@@ -104,7 +106,19 @@ where
             DEFAULT_SORT_COLUMN.to_string(),
             DEFAULT_SORT_ORDER.to_string(),
         )
-    };
+    }
+}
+
+/// Parse sorting from `FilterOptions`, supporting both React Admin and standard REST formats
+pub fn parse_sorting<C>(
+    params: &crate::models::FilterOptions,
+    order_column_logic: &[(&str, C)],
+    default_column: C,
+) -> (C, Order)
+where
+    C: ColumnTrait + Copy,
+{
+    let (sort_column, sort_order) = requested_sort(params);
 
     let order_direction = parse_order(&sort_order);
     let order_column = find_column(&sort_column, order_column_logic, default_column);
@@ -112,6 +126,124 @@ where
     (order_column, order_direction)
 }
 
+/// One requested sort key from a multi-column `sort`
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SortKey {
+    pub column: String,
+    /// `None` for `sort=a,b_desc` entries: the direction may be a suffix of `column`
+    pub order: Option<String>,
+}
+
+/// Extract the keys of a multi-column sort, or `None` for the single-column formats
+/// handled by [`requested_sort`].
+///
+/// Two forms are recognised:
+/// - JSON array of pairs: `sort=[["priority","DESC"],["created_at","ASC"]]`
+/// - Comma-separated keys with optional `_asc`/`_desc` suffix: `sort=priority_desc,created_at`
+pub(crate) fn requested_multi_sort(params: &crate::models::FilterOptions) -> Option<Vec<SortKey>> {
+    if params.sort_by.is_some() {
+        return None;
+    }
+    let sort = params.sort.as_deref()?.trim();
+
+    let keys: Vec<SortKey> = if sort.starts_with('[') {
+        serde_json::from_str::<Vec<Vec<String>>>(sort)
+            .ok()?
+            .into_iter()
+            .filter_map(|pair| {
+                let mut pair = pair.into_iter();
+                Some(SortKey {
+                    column: pair.next()?,
+                    order: Some(pair.next().unwrap_or(DEFAULT_SORT_ORDER.to_string())),
+                })
+            })
+            .collect()
+    } else if sort.contains(',')
+        || ORDER_SUFFIXES
+            .iter()
+            .any(|(suffix, _)| sort.ends_with(suffix))
+    {
+        sort.split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| SortKey {
+                column: key.to_string(),
+                order: None,
+            })
+            .collect()
+    } else {
+        return None;
+    };
+
+    (!keys.is_empty()).then_some(keys)
+}
+
+/// Resolve a comma-form key to a column name and direction. An exact column name wins
+/// over stripping a suffix, so a column called `created_desc` still sorts by itself.
+fn resolve_suffixed_key<'a, C>(
+    key: &'a str,
+    columns: &[(&str, C)],
+    default_order: &'a str,
+) -> (&'a str, &'a str) {
+    if columns.iter().any(|(name, _)| *name == key) {
+        return (key, default_order);
+    }
+    ORDER_SUFFIXES
+        .iter()
+        .find_map(|(suffix, order)| key.strip_suffix(suffix).map(|column| (column, *order)))
+        .unwrap_or((key, default_order))
+}
+
+/// Parse a multi-column sort into `(column, direction)` pairs, in priority order.
+///
+/// Returns `Ok(None)` when the request uses a single-column format, which
+/// [`parse_sorting`] and [`parse_sorting_with_joins`] handle.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if a column is not sortable, a column is a joined
+/// (dot-notation) column, or more than [`MAX_SORT_COLUMNS`] columns are requested.
+pub fn parse_multi_sorting<C>(
+    params: &crate::models::FilterOptions,
+    order_column_logic: &[(&str, C)],
+) -> Result<Option<Vec<(C, Order)>>, ApiError>
+where
+    C: ColumnTrait + Copy,
+{
+    let Some(keys) = requested_multi_sort(params) else {
+        return Ok(None);
+    };
+    if keys.len() > MAX_SORT_COLUMNS {
+        return Err(ApiError::bad_request(format!(
+            "Cannot sort by more than {MAX_SORT_COLUMNS} columns (got {})",
+            keys.len()
+        )));
+    }
+
+    let default_order = params.order.as_deref().unwrap_or(DEFAULT_SORT_ORDER);
+    keys.iter()
+        .map(|key| {
+            let (column_name, order) = match &key.order {
+                Some(order) => (key.column.as_str(), order.as_str()),
+                None => resolve_suffixed_key(&key.column, order_column_logic, default_order),
+            };
+            if column_name.contains('.') {
+                return Err(ApiError::bad_request(format!(
+                    "Joined column '{column_name}' can only be sorted on its own"
+                )));
+            }
+            let column = order_column_logic
+                .iter()
+                .find(|(name, _)| *name == column_name)
+                .map(|&(_, column)| column)
+                .ok_or_else(|| {
+                    ApiError::bad_request(format!("Column '{column_name}' is not sortable"))
+                })?;
+            Ok((column, parse_order(order)))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
 /// Parse sorting with support for dot-notation (joined column) sorting.
 ///
 /// Returns a `SortConfig` which can be either:
@@ -139,34 +271,7 @@ where
 {
     use super::joined::SortConfig;
 
-    let (sort_column, sort_order) = if let Some(sort_by) = &params.sort_by {
-        (
-            sort_by.clone(),
-            params
-                .order
-                .as_deref()
-                .unwrap_or(DEFAULT_SORT_ORDER)
-                .to_string(),
-        )
-    } else if let Some(sort) = &params.sort {
-        if sort.starts_with('[') {
-            parse_json_sort(sort)
-        } else {
-            (
-                sort.clone(),
-                params
-                    .order
-                    .as_deref()
-                    .unwrap_or(DEFAULT_SORT_ORDER)
-                    .to_string(),
-            )
-        }
-    } else {
-        (
-            DEFAULT_SORT_COLUMN.to_string(),
-            DEFAULT_SORT_ORDER.to_string(),
-        )
-    };
+    let (sort_column, sort_order) = requested_sort(params);
 
     let order_direction = parse_order(&sort_order);
 
@@ -311,6 +416,130 @@ mod tests {
         );
     }
 
+    /// `requested_sort`: `sort_by` wins over sort, JSON and plain sort both parse
+    #[test]
+    fn test_requested_sort_formats() {
+        let params = crate::models::FilterOptions {
+            sort_by: Some("email".to_string()),
+            order: Some("DESC".to_string()),
+            sort: Some(r#"["name", "ASC"]"#.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            requested_sort(&params),
+            ("email".to_string(), "DESC".to_string())
+        );
+
+        let params = crate::models::FilterOptions {
+            sort: Some(r#"["name", "DESC"]"#.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            requested_sort(&params),
+            ("name".to_string(), "DESC".to_string())
+        );
+
+        let params = crate::models::FilterOptions {
+            sort: Some("name".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            requested_sort(&params),
+            ("name".to_string(), DEFAULT_SORT_ORDER.to_string())
+        );
+
+        assert_eq!(
+            requested_sort(&crate::models::FilterOptions::default()),
+            (
+                DEFAULT_SORT_COLUMN.to_string(),
+                DEFAULT_SORT_ORDER.to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_requested_multi_sort_json() {
+        let params = crate::models::FilterOptions {
+            sort: Some(r#"[["priority","DESC"],["created_at"]]"#.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            requested_multi_sort(&params),
+            Some(vec![
+                SortKey {
+                    column: "priority".to_string(),
+                    order: Some("DESC".to_string()),
+                },
+                SortKey {
+                    column: "created_at".to_string(),
+                    order: Some(DEFAULT_SORT_ORDER.to_string()),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_requested_multi_sort_comma() {
+        let params = crate::models::FilterOptions {
+            sort: Some("priority_desc, created_at_asc".to_string()),
+            ..Default::default()
+        };
+        let keys = requested_multi_sort(&params).unwrap();
+        let columns: Vec<_> = keys.iter().map(|key| key.column.as_str()).collect();
+        assert_eq!(columns, ["priority_desc", "created_at_asc"]);
+        assert!(keys.iter().all(|key| key.order.is_none()));
+
+        // A lone suffixed key is also a multi-column sort
+        let params = crate::models::FilterOptions {
+            sort: Some("priority_desc".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            requested_multi_sort(&params).map(|keys| keys.len()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_requested_multi_sort_leaves_single_formats() {
+        for sort in [r#"["name","DESC"]"#, "name", "[]", "[[]]"] {
+            let params = crate::models::FilterOptions {
+                sort: Some(sort.to_string()),
+                ..Default::default()
+            };
+            assert_eq!(requested_multi_sort(&params), None, "{sort}");
+        }
+
+        // sort_by always means a single column
+        let params = crate::models::FilterOptions {
+            sort: Some("a,b".to_string()),
+            sort_by: Some("name".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(requested_multi_sort(&params), None);
+    }
+
+    #[test]
+    fn test_resolve_suffixed_key() {
+        let columns: [(&str, ()); 2] = [("priority", ()), ("created_desc", ())];
+        assert_eq!(
+            resolve_suffixed_key("priority_desc", &columns, "ASC"),
+            ("priority", "DESC")
+        );
+        assert_eq!(
+            resolve_suffixed_key("priority_asc", &columns, "DESC"),
+            ("priority", "ASC")
+        );
+        assert_eq!(
+            resolve_suffixed_key("priority", &columns, "DESC"),
+            ("priority", "DESC")
+        );
+        assert_eq!(
+            resolve_suffixed_key("created_desc", &columns, "ASC"),
+            ("created_desc", "ASC")
+        );
+    }
+
     /// Test default order constant
     #[test]
     fn test_default_order_is_asc() {
@@ -0,0 +1,218 @@
+//! Rejecting unknown filter and sort fields.
+//!
+//! By default the list endpoint ignores filter keys and sort columns it doesn't know,
+//! so a typo such as `{"staus": "open"}` silently returns every row. Resources declared
+//! with `#[crudcrate(strict_query)]` pass their list parameters through
+//! [`check_query_fields`] instead, which answers 400 with the unrecognised keys and the
+//! fields that can be used:
+//!
+//! ```text
+//! GET /tasks?filter={"staus":"open"}
+//! 400 Unknown filter field 'staus'. Filterable fields: id, status, title
+//! ```
+//!
+//! Multi-column sorts already reject unknown columns on every resource.
+
+use sea_orm::DatabaseBackend;
+
+use super::conditions::{apply_filters_with_joins, parse_comparison_operator};
+use super::search::has_fulltext;
+use super::sort::{requested_multi_sort, requested_sort};
+use crate::errors::ApiError;
+use crate::filtering::FilterOptions;
+
+/// Check that every filter key and the sort column of `params` refer to a field the
+/// list endpoint understands.
+///
+/// `filterable_columns` and `sortable_columns` are the lists the request is parsed
+/// against, so columns hidden from scoped requests are reported as unknown too. Call
+/// after [`translate_field_names`](super::translate_field_names).
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` listing the unknown keys and the valid field names, or
+/// any error from parsing the filter.
+pub fn check_query_fields<T: crate::traits::CRUDResource>(
+    params: &FilterOptions,
+    filterable_columns: &[(&str, T::ColumnType)],
+    sortable_columns: &[(&str, T::ColumnType)],
+    backend: DatabaseBackend,
+) -> Result<(), ApiError> {
+    let mut problems = Vec::new();
+
+    let unknown_filters = unknown_filter_keys::<T>(params, filterable_columns, backend)?;
+    if !unknown_filters.is_empty() {
+        let mut valid: Vec<String> = filterable_columns
+            .iter()
+            .map(|(name, _)| external_name::<T>(name))
+            .collect();
+        valid.extend(
+            T::joined_filterable_columns()
+                .into_iter()
+                .map(|column| column.full_path.to_string()),
+        );
+        valid.extend(T::relation_filters().into_iter().flat_map(|relation| {
+            [
+                format!("{}__exists", relation.join_field),
+                format!("{}__count", relation.join_field),
+            ]
+        }));
+        valid.extend(
+            T::virtual_filters()
+                .into_iter()
+                .map(|filter| filter.name.to_string()),
+        );
+        if has_fulltext::<T>() {
+            valid.push("q".to_string());
+        }
+        problems.push(describe("filter", &unknown_filters, "Filterable", valid));
+    }
+
+    if let Some(column) = unknown_sort_column::<T>(params, sortable_columns) {
+        let mut valid: Vec<String> = sortable_columns
+            .iter()
+            .map(|(name, _)| external_name::<T>(name))
+            .collect();
+        valid.extend(
+            T::joined_sortable_columns()
+                .into_iter()
+                .map(|column| column.full_path.to_string()),
+        );
+        valid.extend(
+            T::geo_fields()
+                .into_iter()
+                .map(|(name, _)| name.to_string()),
+        );
+        problems.push(describe("sort", &[column], "Sortable", valid));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::bad_request(problems.join(". ")))
+    }
+}
+
+/// Filter keys that would not narrow the query. Each key is parsed on its own, the way
+/// [`delete_filter_condition`](super::delete_filter_condition) does, so operator
+/// suffixes, JSON paths, date parts, geo radii, joined columns, relation filters and
+/// virtual filters count as known.
+fn unknown_filter_keys<T: crate::traits::CRUDResource>(
+    params: &FilterOptions,
+    filterable_columns: &[(&str, T::ColumnType)],
+    backend: DatabaseBackend,
+) -> Result<Vec<String>, ApiError> {
+    let Some(filter) = params.filter.as_deref() else {
+        return Ok(Vec::new());
+    };
+    // Malformed JSON is reported by the regular filter parser
+    let Ok(serde_json::Value::Object(filters)) = serde_json::from_str(filter) else {
+        return Ok(Vec::new());
+    };
+
+    let mut unknown = Vec::new();
+    for (key, value) in filters {
+        if key == "q" {
+            if !has_fulltext::<T>() {
+                unknown.push(key);
+            }
+            continue;
+        }
+        if super::custom::is_virtual_filter::<T>(&key) {
+            continue;
+        }
+        // A known field with a value that produces no condition is not a typo
+        let (base_field, _) = parse_comparison_operator(&key).unwrap_or((key.as_str(), "="));
+        if filterable_columns
+            .iter()
+            .any(|(name, _)| *name == base_field)
+        {
+            continue;
+        }
+        let single = serde_json::Value::Object(serde_json::Map::from_iter([(key.clone(), value)]));
+        let parsed =
+            apply_filters_with_joins::<T>(Some(single.to_string()), filterable_columns, backend)?;
+        if !parsed.has_joined_filters
+            && parsed.relation_filters.is_empty()
+            && parsed.main_condition.is_empty()
+        {
+            unknown.push(key);
+        }
+    }
+    Ok(unknown)
+}
+
+/// The single sort column, if it isn't sortable. Multi-column sorts are checked by
+/// [`parse_multi_sorting`](super::parse_multi_sorting) on every resource.
+fn unknown_sort_column<T: crate::traits::CRUDResource>(
+    params: &FilterOptions,
+    sortable_columns: &[(&str, T::ColumnType)],
+) -> Option<String> {
+    if params.sort.is_none() && params.sort_by.is_none() {
+        return None;
+    }
+    if requested_multi_sort(params).is_some() {
+        return None;
+    }
+    let (column, _) = requested_sort(params);
+    let known = sortable_columns.iter().any(|(name, _)| *name == column)
+        || T::joined_sortable_columns()
+            .iter()
+            .any(|joined| joined.full_path == column)
+        || T::geo_fields().iter().any(|(name, _)| *name == column);
+    (!known).then_some(column)
+}
+
+/// The name clients use for `field`: its `rename` (listed first in `field_aliases`) or
+/// the field name itself
+fn external_name<T: crate::traits::CRUDResource>(field: &str) -> String {
+    T::field_aliases()
+        .into_iter()
+        .find(|(_, aliased)| *aliased == field)
+        .map_or(field, |(external, _)| external)
+        .to_string()
+}
+
+/// `Unknown filter fields 'a', 'b'. Filterable fields: x, y`
+fn describe(kind: &str, unknown: &[String], label: &str, mut valid: Vec<String>) -> String {
+    let plural = if unknown.len() == 1 { "" } else { "s" };
+    let unknown = unknown
+        .iter()
+        .map(|key| format!("'{key}'"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    valid.sort_unstable();
+    valid.dedup();
+    let valid = if valid.is_empty() {
+        "none".to_string()
+    } else {
+        valid.join(", ")
+    };
+    format!("Unknown {kind} field{plural} {unknown}. {label} fields: {valid}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_single_and_plural() {
+        assert_eq!(
+            describe(
+                "filter",
+                &["staus".to_string()],
+                "Filterable",
+                vec!["title".to_string(), "status".to_string()]
+            ),
+            "Unknown filter field 'staus'. Filterable fields: status, title"
+        );
+        assert_eq!(
+            describe(
+                "filter",
+                &["a".to_string(), "b".to_string()],
+                "Filterable",
+                vec![]
+            ),
+            "Unknown filter fields 'a', 'b'. Filterable fields: none"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Filter and keyset values for `time` crate columns (requires the `time` feature).
+//!
+//! Filter values arrive as strings. Compared as text, they only match values stored in
+//! the same text form: `SQLite` stores a `PrimitiveDateTime` space-separated
+//! (`2024-05-01 10:00:00.0`) while clients send RFC 3339 (`2024-05-01T10:00:00Z`), the
+//! same instant written with another offset never matches, and `PostgreSQL` won't
+//! compare a timestamp with text at all. Fields of `time` types
+//! (`T::time_crate_columns()`) instead parse the value into the field's type and bind
+//! it, so the driver encodes it the same way as the stored values:
+//!
+//! | Field type | Accepted values |
+//! |------------|-----------------|
+//! | `OffsetDateTime` | RFC 3339; without an offset (`2024-05-01T10:00:00`) or as a date, UTC |
+//! | `PrimitiveDateTime` | The same |
+//! | `Date` | `YYYY-MM-DD` |
+//!
+//! Timestamps are converted to UTC, so `SQLite` columns should hold UTC values.
+//!
+//! This applies to comparisons, `IN` lists, `_between` with full timestamps and keyset
+//! `after_value`. Calendar filters (`_date`, `_year`, date-only `_between`) go through
+//! [`dates`](super::dates) as for chrono fields.
+
+use sea_orm::{
+    ColumnTrait, Value,
+    sea_query::{Expr, SimpleExpr},
+};
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+use super::dates::DateKind;
+use crate::errors::ApiError;
+
+/// The field's kind if it is one of `T::time_crate_columns()`
+pub(crate) fn time_kind<T: crate::traits::CRUDResource>(field: &str) -> Option<DateKind> {
+    T::time_crate_columns()
+        .into_iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, kind)| kind)
+}
+
+/// Parse `raw` as a value of a `time` column of the given kind.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `raw` is not a date or timestamp in a form listed in
+/// the [module docs](self).
+pub fn time_value(field: &str, kind: DateKind, raw: &str) -> Result<Value, ApiError> {
+    let raw = raw.trim();
+    let invalid = || {
+        ApiError::bad_request(format!(
+            "'{field}' must be {}",
+            match kind {
+                DateKind::Date => "a date (YYYY-MM-DD)",
+                _ => "an RFC 3339 timestamp",
+            }
+        ))
+    };
+    match kind {
+        DateKind::Date => parse_date(raw).map(Value::from).ok_or_else(invalid),
+        // UTC, like `OffsetDateTime::now_utc()`, so `SQLite`'s text comparison lines up
+        DateKind::TimestampTz => parse_timestamp(raw)
+            .map(|timestamp| Value::from(timestamp.to_offset(UtcOffset::UTC)))
+            .ok_or_else(invalid),
+        DateKind::Timestamp => parse_timestamp(raw)
+            .map(|timestamp| {
+                let utc = timestamp.to_offset(UtcOffset::UTC);
+                Value::from(PrimitiveDateTime::new(utc.date(), utc.time()))
+            })
+            .ok_or_else(invalid),
+    }
+}
+
+/// Condition for a filter on a `time` column, or `None` for values left to the regular
+/// handling (`null`, numbers, booleans)
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if a value doesn't parse (see [`time_value`]).
+pub(crate) fn time_condition(
+    field: &str,
+    kind: DateKind,
+    operator: &str,
+    value: &serde_json::Value,
+    column: impl ColumnTrait,
+) -> Result<Option<SimpleExpr>, ApiError> {
+    let bound = |item: &serde_json::Value| -> Result<Value, ApiError> {
+        let raw = item.as_str().ok_or_else(|| {
+            ApiError::bad_request(format!("'{field}' values must be date/time strings"))
+        })?;
+        time_value(field, kind, raw)
+    };
+    let col = Expr::col(column);
+
+    if operator == "BETWEEN" {
+        let (min, max) = super::conditions::between_bounds(field, value)?;
+        return Ok(Some(col.between(bound(min)?, bound(max)?)));
+    }
+    Ok(match value {
+        serde_json::Value::String(_) => {
+            let value = bound(value)?;
+            Some(match operator {
+                "!=" => col.ne(value),
+                ">=" => col.gte(value),
+                "<=" => col.lte(value),
+                ">" => col.gt(value),
+                "<" => col.lt(value),
+                _ => col.eq(value),
+            })
+        }
+        serde_json::Value::Array(items) if !items.is_empty() => Some(
+            col.is_in(
+                items
+                    .iter()
+                    .map(bound)
+                    .collect::<Result<Vec<_>, ApiError>>()?,
+            ),
+        ),
+        _ => None,
+    })
+}
+
+fn parse_date(raw: &str) -> Option<Date> {
+    Date::parse(raw, format_description!("[year]-[month]-[day]")).ok()
+}
+
+/// RFC 3339, or a timestamp or date without an offset taken as UTC
+fn parse_timestamp(raw: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(raw, &Rfc3339)
+        .ok()
+        .or_else(|| {
+            PrimitiveDateTime::parse(
+                raw,
+                format_description!(
+                    "[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]"
+                ),
+            )
+            .ok()
+            .map(PrimitiveDateTime::assume_utc)
+        })
+        .or_else(|| parse_date(raw).map(|date| date.midnight().assume_utc()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_forms() {
+        let expected = Value::from(time::macros::datetime!(2024-05-01 10:00 UTC));
+        for raw in [
+            "2024-05-01T10:00:00Z",
+            "2024-05-01T12:00:00+02:00",
+            "2024-05-01T10:00:00",
+            " 2024-05-01T10:00:00.000Z ",
+        ] {
+            assert_eq!(
+                time_value("at", DateKind::TimestampTz, raw).unwrap(),
+                expected,
+                "{raw}"
+            );
+        }
+        assert_eq!(
+            time_value("at", DateKind::TimestampTz, "2024-05-01").unwrap(),
+            Value::from(time::macros::datetime!(2024-05-01 0:00 UTC))
+        );
+    }
+
+    #[test]
+    fn test_primitive_timestamps_are_utc() {
+        assert_eq!(
+            time_value("at", DateKind::Timestamp, "2024-05-01T12:30:00+02:00").unwrap(),
+            Value::from(time::macros::datetime!(2024-05-01 10:30))
+        );
+    }
+
+    #[test]
+    fn test_invalid_values_rejected() {
+        assert!(time_value("at", DateKind::TimestampTz, "yesterday").is_err());
+        assert!(time_value("day", DateKind::Date, "2024-05-01T10:00:00Z").is_err());
+        assert!(time_value("day", DateKind::Date, "2024-02-30").is_err());
+        assert_eq!(
+            time_value("day", DateKind::Date, "2024-02-29").unwrap(),
+            Value::from(time::macros::date!(2024 - 02 - 29))
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Liveness and readiness endpoints for services built from generated routers.
+//!
+//! ```rust,ignore
+//! let app = Router::new()
+//!     .nest("/todos", Todo::router(&db).into())
+//!     .merge(crudcrate::health::router(&db));
+//! ```
+//!
+//! - `GET /healthz` always answers `200 {"status": "ok"}` while the process serves requests.
+//! - `GET /readyz` runs `SELECT 1` and answers 200 with a [`Readiness`] body, or 503
+//!   when the check fails.
+//!
+//! [`router_with_table_checks`] also makes `/readyz` check that the table of every
+//! resource in the [`registry`](crate::registry) exists, which catches a deployment
+//! whose migrations haven't run. Resources register when their router is built, so
+//! merge the health router after the resource routers.
+
+use std::collections::BTreeSet;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, DbErr, Statement};
+use serde::Serialize;
+
+/// Result of a readiness check, the body of `/readyz`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct Readiness {
+    /// `"ready"` or `"unavailable"`
+    pub status: &'static str,
+    /// Whether `SELECT 1` succeeded
+    pub database: bool,
+    /// Tables of registered resources that don't exist, when tables are checked
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_tables: Vec<String>,
+}
+
+impl Readiness {
+    /// Whether the service can take traffic
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.status == "ready"
+    }
+}
+
+impl IntoResponse for Readiness {
+    fn into_response(self) -> Response {
+        let status = if self.is_ready() {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+        (status, Json(self)).into_response()
+    }
+}
+
+/// `/healthz` and `/readyz`, with `/readyz` checking only the database connection
+pub fn router(db: &DatabaseConnection) -> Router {
+    build_router(db.clone(), false)
+}
+
+/// `/healthz` and `/readyz`, with `/readyz` also checking that every registered
+/// resource's table exists
+pub fn router_with_table_checks(db: &DatabaseConnection) -> Router {
+    build_router(db.clone(), true)
+}
+
+fn build_router(db: DatabaseConnection, check_tables: bool) -> Router {
+    Router::new()
+        .route(
+            "/healthz",
+            get(|| async { Json(serde_json::json!({ "status": "ok" })) }),
+        )
+        .route(
+            "/readyz",
+            get(move || {
+                let db = db.clone();
+                async move { readiness(&db, check_tables).await }
+            }),
+        )
+}
+
+/// Check the database connection and, if `check_tables`, the tables of the registered
+/// resources. Failures are logged; the result only says what failed.
+pub async fn readiness(db: &DatabaseConnection, check_tables: bool) -> Readiness {
+    let unavailable = || Readiness {
+        status: "unavailable",
+        database: false,
+        missing_tables: vec![],
+    };
+
+    if let Err(error) = db.execute_unprepared("SELECT 1").await {
+        tracing::warn!(error = ?error, "Readiness check failed: database unreachable");
+        return unavailable();
+    }
+
+    let mut missing_tables = Vec::new();
+    if check_tables {
+        let tables: BTreeSet<&str> = crate::registry::resources()
+            .iter()
+            .map(|resource| resource.table)
+            .collect();
+        for table in tables {
+            match table_exists(db, table).await {
+                Ok(true) => {}
+                Ok(false) => missing_tables.push(table.to_string()),
+                Err(error) => {
+                    tracing::warn!(error = ?error, table, "Readiness check failed: table lookup");
+                    return unavailable();
+                }
+            }
+        }
+        if !missing_tables.is_empty() {
+            tracing::warn!(?missing_tables, "Readiness check failed: missing tables");
+        }
+    }
+
+    Readiness {
+        status: if missing_tables.is_empty() {
+            "ready"
+        } else {
+            "unavailable"
+        },
+        database: true,
+        missing_tables,
+    }
+}
+
+async fn table_exists(db: &impl ConnectionTrait, table: &str) -> Result<bool, DbErr> {
+    let backend = db.get_database_backend();
+    let sql = match backend {
+        DatabaseBackend::Sqlite => {
+            "SELECT 1 FROM sqlite_master WHERE type IN ('table', 'view') AND name = ?"
+        }
+        DatabaseBackend::Postgres => {
+            "SELECT 1 FROM information_schema.tables \
+             WHERE table_schema = ANY(current_schemas(false)) AND table_name = $1"
+        }
+        DatabaseBackend::MySql => {
+            "SELECT 1 FROM information_schema.tables \
+             WHERE table_schema = DATABASE() AND table_name = ?"
+        }
+    };
+    let row = db
+        .query_one(Statement::from_sql_and_values(backend, sql, [table.into()]))
+        .await?;
+    Ok(row.is_some())
+}
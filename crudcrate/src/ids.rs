@@ -0,0 +1,177 @@
+//! Primary key generators for `#[crudcrate(id_generator = "...")]`.
+//!
+//! `on_create = Uuid::new_v4()` gives random keys, so the default `id` ordering of list
+//! endpoints is random too, and inserts land all over the primary key index. The
+//! time-sortable generators start with a timestamp, so rows sort by creation time and
+//! new keys append to the end of the index:
+//!
+//! | `id_generator` | Field type | Form | Sorts by creation time |
+//! |----------------|------------|------|------------------------|
+//! | `"uuid_v4"` | `Uuid` | random UUID | no |
+//! | `"uuid_v7"` | `Uuid` | UUID with a millisecond timestamp prefix | yes |
+//! | `"ulid"` | `Uuid` | [ULID](https://github.com/ulid/spec) bits, written as a UUID | yes |
+//! | `"ulid"` | `String` | 26-character Crockford base32 ULID | yes |
+//! | `"ksuid"` | `String` | 27-character base62 [KSUID](https://github.com/segmentio/ksuid) | yes, with a binary collation |
+//!
+//! ```rust,ignore
+//! #[crudcrate(primary_key, exclude(create, update), id_generator = "uuid_v7")]
+//! pub id: Uuid,
+//!
+//! #[crudcrate(exclude(create, update), id_generator = "ksuid")]
+//! pub public_id: String,
+//! ```
+//!
+//! Primary keys must be `Uuid`s, so `String` ULIDs and KSUIDs are for secondary keys
+//! such as public identifiers.
+//!
+//! Keys created within the same millisecond (second for KSUIDs) are ordered randomly.
+//! KSUIDs mix upper and lower case, so they only sort correctly under a binary
+//! collation (`COLLATE "C"` on `PostgreSQL`, `utf8mb4_bin` on `MySQL`, the `SQLite`
+//! default); ULIDs are uppercase and sort correctly everywhere.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use uuid::Uuid;
+
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// KSUID timestamps count seconds from 2014-05-13
+const KSUID_EPOCH: u64 = 1_400_000_000;
+
+/// Random UUID (`id_generator = "uuid_v4"`)
+#[must_use]
+pub fn uuid_v4() -> Uuid {
+    Uuid::new_v4()
+}
+
+/// UUID starting with the current Unix time in milliseconds (`id_generator = "uuid_v7"`)
+#[must_use]
+pub fn uuid_v7() -> Uuid {
+    Uuid::now_v7()
+}
+
+/// ULID: 48-bit millisecond timestamp and 80 random bits (`id_generator = "ulid"` on a
+/// `String` field)
+#[must_use]
+pub fn ulid() -> String {
+    encode_ulid(ulid_bits())
+}
+
+/// ULID stored in a `Uuid` (`id_generator = "ulid"` on a `Uuid` field). The bytes are
+/// the same as the text form, so it sorts the same way.
+#[must_use]
+pub fn ulid_uuid() -> Uuid {
+    Uuid::from_u128(ulid_bits())
+}
+
+/// KSUID: 32-bit timestamp in seconds and 128 random bits (`id_generator = "ksuid"`)
+#[must_use]
+pub fn ksuid() -> String {
+    let seconds = unix_time().as_secs().saturating_sub(KSUID_EPOCH);
+    let mut bytes = [0u8; 20];
+    bytes[..4].copy_from_slice(&u32::try_from(seconds).unwrap_or(u32::MAX).to_be_bytes());
+    bytes[4..].copy_from_slice(&ksuid_payload());
+    encode_ksuid(bytes)
+}
+
+/// 128 random bits, from two v4 UUIDs since one only has 122
+fn ksuid_payload() -> [u8; 16] {
+    ((random_bits(64) << 64) | random_bits(64)).to_be_bytes()
+}
+
+fn ulid_bits() -> u128 {
+    let millis = unix_time().as_millis() & ((1 << 48) - 1);
+    (millis << 80) | random_bits(80)
+}
+
+fn unix_time() -> std::time::Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// `bits` (at most 104) random bits, taken from the bytes of a v4 UUID that don't hold
+/// its version and variant
+fn random_bits(bits: u32) -> u128 {
+    let bytes = Uuid::new_v4().into_bytes();
+    let random = bytes[..6]
+        .iter()
+        .chain(&bytes[9..])
+        .fold(0u128, |acc, byte| (acc << 8) | u128::from(*byte));
+    random & ((1 << bits) - 1)
+}
+
+fn encode_ulid(value: u128) -> String {
+    (0..26)
+        .map(|i| {
+            let shift = 125 - 5 * i;
+            let index = usize::try_from((value >> shift) & 0x1f).unwrap_or_default();
+            char::from(CROCKFORD[index])
+        })
+        .collect()
+}
+
+fn encode_ksuid(mut bytes: [u8; 20]) -> String {
+    let mut digits = [b'0'; 27];
+    for digit in digits.iter_mut().rev() {
+        // Divide the big-endian number by 62 in place, keeping the remainder
+        let mut remainder = 0usize;
+        for byte in &mut bytes {
+            let value = (remainder << 8) | usize::from(*byte);
+            *byte = u8::try_from(value / 62).unwrap_or(u8::MAX);
+            remainder = value % 62;
+        }
+        *digit = BASE62[remainder];
+    }
+    String::from_utf8_lossy(&digits).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ulid_encoding() {
+        assert_eq!(encode_ulid(0), "00000000000000000000000000");
+        assert_eq!(encode_ulid(u128::MAX), "7ZZZZZZZZZZZZZZZZZZZZZZZZZ");
+        // Spec example: timestamp 1469918176385 with zero randomness
+        assert_eq!(
+            encode_ulid(1_469_918_176_385 << 80),
+            "01ARYZ6S410000000000000000"
+        );
+    }
+
+    #[test]
+    fn test_ksuid_encoding() {
+        assert_eq!(encode_ksuid([0; 20]), "000000000000000000000000000");
+        assert_eq!(encode_ksuid([0xff; 20]), "aWgEPTl1tmebfsQzFP4bxwgy80V");
+    }
+
+    #[test]
+    fn test_ksuid_payload_is_fully_random() {
+        // A v4 UUID would fix the version nibble of byte 6 and the variant bits of byte 8
+        let payloads: Vec<[u8; 16]> = (0..64).map(|_| ksuid_payload()).collect();
+        assert!(payloads.iter().any(|bytes| bytes[6] >> 4 != 4));
+        assert!(payloads.iter().any(|bytes| bytes[8] >> 6 != 0b10));
+    }
+
+    #[test]
+    fn test_time_sortable_ids_sort_by_creation() {
+        let first = ulid();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let second = ulid();
+        assert_eq!(first.len(), 26);
+        assert!(first < second, "{first} {second}");
+
+        let first = uuid_v7();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        assert!(first < uuid_v7());
+        assert_eq!(first.get_version_num(), 7);
+
+        let first = ulid_uuid();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        assert!(first < ulid_uuid());
+
+        assert_eq!(ksuid().len(), 27);
+    }
+}
@@ -0,0 +1,175 @@
+//! Background jobs for `post_async` hooks.
+//!
+//! `::post` hooks run before the response is sent, so a slow side effect (an email, a
+//! webhook call) adds to the request's latency. A `::post_async` hook runs after the
+//! operation has succeeded as a [`Job`] handed to the installed [`JobQueue`], and the
+//! handler answers without waiting for it:
+//!
+//! ```rust,ignore
+//! #[crudcrate(create::one::post_async = send_welcome_email)]
+//!
+//! async fn send_welcome_email(db: DatabaseConnection, created: User) -> Result<(), ApiError> {
+//!     mailer::welcome(&created.email).await
+//! }
+//! ```
+//!
+//! Hooks take owned copies of the connection and of what the `::post` hook would
+//! borrow. By default jobs are spawned on the Tokio runtime ([`TokioQueue`]); install
+//! another queue with [`set_queue`] to bound concurrency or hand jobs to an external
+//! worker. A failed job is logged, not reported to the client: its request already
+//! succeeded.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use tracing::Instrument;
+
+use crate::errors::ApiError;
+use crate::telemetry;
+
+type JobFuture = Pin<Box<dyn Future<Output = Result<(), ApiError>> + Send>>;
+
+static QUEUE: RwLock<Option<Arc<dyn JobQueue>>> = RwLock::new(None);
+
+/// A `post_async` hook call waiting to run
+pub struct Job {
+    resource: &'static str,
+    operation: &'static str,
+    future: JobFuture,
+}
+
+impl Job {
+    /// A job for `resource`'s `operation` (such as `"create"`) running `future`.
+    ///
+    /// The job runs in the span it was created in and with its
+    /// [`trace_context`](crate::telemetry::trace_context), so a hook's spans and the
+    /// services it calls stay in the request's trace.
+    pub fn new<F>(resource: &'static str, operation: &'static str, future: F) -> Self
+    where
+        F: Future<Output = Result<(), ApiError>> + Send + 'static,
+    {
+        let future = telemetry::with_trace_context(telemetry::trace_context(), future)
+            .instrument(tracing::Span::current());
+        Self {
+            resource,
+            operation,
+            future: Box::pin(future),
+        }
+    }
+
+    /// Singular name of the resource whose operation enqueued the job
+    #[must_use]
+    pub fn resource(&self) -> &'static str {
+        self.resource
+    }
+
+    /// The operation that enqueued the job: `create`, `update` or `delete`
+    #[must_use]
+    pub fn operation(&self) -> &'static str {
+        self.operation
+    }
+
+    /// Run the hook, logging its error if it fails
+    pub async fn run(self) {
+        if let Err(error) = self.future.await {
+            tracing::warn!(
+                resource = self.resource,
+                operation = self.operation,
+                error = %error,
+                "Background post hook failed"
+            );
+        }
+    }
+}
+
+impl fmt::Debug for Job {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Job")
+            .field("resource", &self.resource)
+            .field("operation", &self.operation)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Where `post_async` jobs run
+///
+/// ```rust,ignore
+/// /// Runs at most `n` jobs at a time
+/// struct Bounded(Arc<tokio::sync::Semaphore>);
+///
+/// impl JobQueue for Bounded {
+///     fn enqueue(&self, job: Job) {
+///         let permits = Arc::clone(&self.0);
+///         tokio::spawn(async move {
+///             let _permit = permits.acquire_owned().await;
+///             job.run().await;
+///         });
+///     }
+/// }
+///
+/// crudcrate::jobs::set_queue(Bounded(Arc::new(Semaphore::new(4))));
+/// ```
+pub trait JobQueue: Send + Sync + 'static {
+    /// Take `job` and run it later with [`Job::run`]. Must not block.
+    fn enqueue(&self, job: Job);
+}
+
+/// The default queue: every job is spawned as its own Tokio task
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioQueue;
+
+impl JobQueue for TokioQueue {
+    fn enqueue(&self, job: Job) {
+        tokio::spawn(job.run());
+    }
+}
+
+/// Run `post_async` jobs on `queue` from now on, instead of [`TokioQueue`]
+///
+/// # Panics
+///
+/// Panics if the queue lock is poisoned.
+pub fn set_queue(queue: impl JobQueue) {
+    *QUEUE.write().expect("job queue lock poisoned") = Some(Arc::new(queue));
+}
+
+/// Hand `job` to the installed queue. Called by the generated operations after their
+/// `::post` hook.
+///
+/// # Panics
+///
+/// Panics if the queue lock is poisoned.
+pub fn enqueue(job: Job) {
+    let queue = QUEUE.read().expect("job queue lock poisoned").clone();
+    match queue {
+        Some(queue) => queue.enqueue(job),
+        None => TokioQueue.enqueue(job),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tokio_queue_runs_job() {
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        TokioQueue.enqueue(Job::new("todo", "create", async move {
+            sender.send(()).ok();
+            Ok(())
+        }));
+        receiver.await.expect("job ran");
+    }
+
+    #[tokio::test]
+    async fn test_failed_job_is_logged_not_raised() {
+        let job = Job::new("todo", "delete", async {
+            Err(ApiError::internal("Mailer unavailable", None))
+        });
+        assert_eq!(job.resource(), "todo");
+        assert_eq!(job.operation(), "delete");
+        job.run().await;
+    }
+}
@@ -3,7 +3,8 @@
 //! `crudcrate` generates CRUD endpoints, request/response models, filtering, sorting,
 //! pagination, batch operations, relationship loading, and `OpenAPI` schemas from a single
 //! `#[derive(EntityToModels)]` on your Sea-ORM model. It targets [Axum](https://github.com/tokio-rs/axum)
-//! and uses [utoipa](https://docs.rs/utoipa) for schema generation.
+//! and uses [utoipa](https://docs.rs/utoipa) for schema generation, behind the default
+//! `openapi` feature.
 //!
 //! For tutorials, walkthroughs, and guides see **<https://crudcrate.evanjt.com>**.
 //!
@@ -96,7 +97,9 @@
 //! )]
 //! ```
 //!
-//! Hook phases run in order: **pre** → **body** → **transform** → **post**.
+//! Hook phases run in order: **pre** → **body** → **transform** → **post**. Write
+//! operations also take `::post_async` hooks, run as background [`jobs`] after the
+//! response instead of before it.
 //!
 //! See the [`EntityToModels`] derive macro docs for the full attribute reference,
 //! and the [`operations`] module for the [`CRUDOperations`] trait (an alternative
@@ -104,12 +107,38 @@
 //!
 //! # Modules
 //!
-//! - [`core`] — [`CRUDResource`] trait, default CRUD implementations
+//! - [`batch`] — Mixed create/update/delete batches across resources in one transaction ([`batch::BatchRouter`])
+//! - [`core`] — [`CRUDResource`] trait, default CRUD implementations, [`ChangeSet`] for post-update hooks
 //! - [`filtering`] — Query parameter parsing, filter conditions, pagination, sorting, fulltext search
 //! - [`operations`] — [`CRUDOperations`] trait for struct-based customization
 //! - [`errors`] — [`ApiError`] type with automatic HTTP status codes and internal logging
-//! - [`database`] — Index analysis utilities
+//! - [`complexity`] — Query and row budgets for `join` loading ([`complexity::JoinBudget`])
+//! - [`database`] — Startup index creation ([`database::ensure_indexes`])
+//! - [`health`] — `/healthz` and `/readyz` endpoints ([`health::router`])
+//! - [`ids`] — Primary key generators for `id_generator` (UUID v4/v7, ULID, KSUID)
+//! - [`jobs`] — Background queue for `::post_async` hooks ([`jobs::JobQueue`])
+//! - [`locks`] — Per-row write locks for `serialize_writes` resources ([`locks::lock_rows`])
+//! - `normalize` — Accent- and case-insensitive text for `normalized_search` resources (`normalize::fold`; requires the `unicode` feature)
+//! - [`openapi`] — Complete `OpenAPI` documents and JSON Schema export ([`openapi::document`], [`openapi::json_schema`]; requires the `openapi` feature)
+//! - [`registry`] — Runtime metadata of mounted resources ([`registry::resources`])
+//! - [`relationships`] — Grouped child counts for `counted_relation` fields ([`relationships::count_related`]), polymorphic targets, `tree` endpoints ([`relationships::tree`]) and the relation endpoints of `lazy` joins ([`relationships::lazy`])
+//! - [`retry`] — Retries of default writes after deadlocks and serialization failures ([`retry::RetryPolicy`])
+//! - [`router`] — Mounting several resources with route conflict checks ([`router::merge_resources`]; requires the `openapi` feature)
+//! - [`schema`] — Startup checks that tables match their entities ([`schema::verify`])
+//! - [`seed`] — Seed files loaded through the create models ([`seed::load_json`], [`seed::SeedManifest`])
+//! - [`singleflight`] — One shared response for identical `GET` bursts on `singleflight_ms` resources ([`singleflight::share`])
+//! - [`state`] — Application state for hooks on routers built with `router_with_state` ([`app_state`])
+//! - [`state_machine`] — Allowed transitions of `state_machine` status fields ([`state_machine::StateMachine`])
+//! - [`storage`] — File uploads for `file_upload` fields ([`storage::StorageBackend`])
+//! - [`sync`] — `GET /changes` and delete tombstones for `sync` resources ([`sync::ensure_tombstones`])
+//! - [`telemetry`] — Request and query spans, `traceparent` propagation to hooks ([`telemetry::trace_context`])
+//! - [`temporal`] — Validity windows and `?as_of=` reads of `temporal` resources ([`temporal::condition`])
+//! - [`timeout`] — Handler deadlines for `timeout_ms` resources ([`timeout::enforce`])
+//! - [`timing`] — `Server-Timing` breakdowns of handlers into parse, query, join and serialization time ([`timing::measure`])
+//! - `debug` — `?__explain=true` query plans and filter cache stats (requires the `debug` feature)
 //! - [`validation`] — Input validation helpers
+//! - [`webhooks`] — Signed deliveries of changes to `webhooks` resources, with retries (`webhooks::Worker` requires the `webhooks` feature)
+//! - `testing` — In-memory `MockResource` for unit testing handlers (requires the `testing` feature)
 //!
 //! # Feature flags
 //!
@@ -117,18 +146,71 @@
 //! |------|---------|-------------|
 //! | `derive` | yes | Enables procedural macros (`EntityToModels`, etc.) |
 //! | `sqlite` | yes | SQLite support via sqlx |
+//! | `openapi` | yes | utoipa `ToSchema` models, documented handlers and `OpenApiRouter`s, [`openapi`] and [`router`]. Without it, `utoipa` and `utoipa-axum` aren't compiled and every resource is generated as with `openapi = false` |
 //! | `postgresql` | no | PostgreSQL support (enables GIN/tsvector fulltext) |
 //! | `mysql` | no | MySQL support (enables FULLTEXT indexes) |
 //! | `spring-rs` | no | [Spring-RS](https://spring-rs.github.io/docs/introduction) framework integration |
+//! | `testing` | no | In-memory [`MockResource`](testing::MockResource) and request helpers for unit tests |
+//! | `file-upload` | no | Multipart create/update requests and [`LocalStorage`](storage::LocalStorage) |
+//! | `debug` | no | `?__explain=true` on list endpoints returns the query plan, plus filter cache stats and `Server-Timing` headers on every response (not for production) |
+//! | `webhooks` | no | Delivery worker and HMAC signatures for [`webhooks`] |
+//! | `otel` | no | OpenTelemetry parents from `traceparent` and exported query spans for [`telemetry`] |
+//! | `unicode` | no | Accent-insensitive `normalized_search` and the `normalize` helpers |
+//! | `time` | no | Entities with `time` crate fields (`OffsetDateTime`, `PrimitiveDateTime`, `Date`): typed filter values and OpenAPI date/time formats |
 
+// The unit tests are written without the pedantic style lints the library follows
+#![cfg_attr(
+    test,
+    allow(
+        clippy::doc_markdown,
+        clippy::redundant_closure_for_method_calls,
+        clippy::similar_names,
+        clippy::single_char_pattern,
+        clippy::uninlined_format_args
+    )
+)]
+
+pub mod batch;
+pub mod client_gen;
+pub mod complexity;
 pub mod core;
 pub mod database;
+#[cfg(feature = "debug")]
+pub mod debug;
 pub mod errors;
 pub mod filtering;
+pub mod health;
+pub mod ids;
+pub mod jobs;
+pub mod locks;
+#[cfg(feature = "unicode")]
+pub mod normalize;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod operations;
+pub mod registry;
 pub mod relationships;
+pub mod retry;
+#[cfg(feature = "openapi")]
+pub mod router;
+pub mod sanitize;
+pub mod schema;
 pub mod scope;
+pub(crate) mod scoped;
+pub mod seed;
+pub mod singleflight;
+pub mod state;
+pub mod state_machine;
+pub mod storage;
+pub mod sync;
+pub mod telemetry;
+pub mod temporal;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod timeout;
+pub mod timing;
 pub mod validation;
+pub mod webhooks;
 
 // Deprecated module aliases — use the canonical paths above instead.
 #[doc(hidden)]
@@ -156,15 +238,22 @@ pub mod traits {
 
 pub use crudcrate_derive::*;
 
-pub use core::{CRUDResource, MergeIntoActiveModel, UuidIdResult};
-pub use errors::{ApiError, BatchFailure, BatchResult};
+pub use core::{
+    ByIds, CRUDResource, ChangeSet, FieldChange, MergeIntoActiveModel, PathId, UuidIdResult,
+};
+pub use errors::{ApiError, BatchFailure, BatchResult, Dependent, FilterDeleteResult};
 pub use filtering::{
-    BatchOptions, FilterOperator, FilterOptions, JoinedColumnDef, JoinedFilter, ParsedFilters,
-    SortConfig, apply_filters, apply_filters_with_joins, calculate_content_range,
-    parse_dot_notation, parse_pagination, parse_range, parse_sorting, parse_sorting_with_joins,
+    BatchOptions, ByIdsOptions, DeleteFilterOptions, DistinctOptions, DistinctValue,
+    FilterOperator, FilterOptions, JoinedColumnDef, JoinedFilter, ParsedFilters, SortConfig,
+    TreeOptions, apply_filters, apply_filters_with_joins, calculate_content_range,
+    parse_dot_notation, parse_multi_sorting, parse_pagination, parse_range, parse_sorting,
+    parse_sorting_with_joins,
 };
-pub use operations::{CRUDOperations, DefaultCRUDOperations};
+pub use operations::{CRUDOperations, DefaultCRUDOperations, SharedOperations};
 pub use scope::{ScopeCondition, ScopeFilterable};
+pub use state::app_state;
 
 pub use impls::impls;
 pub use serde_with;
+#[cfg(feature = "time")]
+pub use time;
@@ -0,0 +1,117 @@
+//! Per-row write locks for `serialize_writes` resources.
+//!
+//! An update reads the row, merges the request's `Option<Option<T>>` fields into it and
+//! writes it back. On backends with weak isolation two updates of the same row can
+//! interleave between the read and the write, so the second write is computed from a
+//! stale row and clobbers the first. With `#[crudcrate(serialize_writes)]` the
+//! generated update and delete handlers hold a lock on each row they write, so writes
+//! to one row run one at a time while writes to different rows stay concurrent:
+//!
+//! ```rust,ignore
+//! #[crudcrate(generate_router, serialize_writes)]
+//! pub struct Model { /* ... */ }
+//! ```
+//!
+//! The locks live in the process: they serialize the requests one server handles, not
+//! requests spread over several replicas. Use database row locks or optimistic
+//! versioning for those. Direct calls to the `CRUDResource` methods and
+//! [`BatchRouter`](crate::batch::BatchRouter) operations don't take them; call
+//! [`lock_rows`] around such writes.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, Weak};
+
+use tokio::sync::{Mutex as RowMutex, OwnedMutexGuard};
+use uuid::Uuid;
+
+use crate::core::traits::CRUDResource;
+
+type Key = (&'static str, Uuid);
+
+/// Row locks by table and ID. Entries are weak so idle rows don't keep their lock.
+static LOCKS: Mutex<BTreeMap<Key, Weak<RowMutex<()>>>> = Mutex::new(BTreeMap::new());
+
+/// Locks held on rows of one table, released when dropped
+#[must_use = "the rows are unlocked as soon as the guard is dropped"]
+pub struct RowLocks {
+    table: &'static str,
+    guards: Vec<(Uuid, OwnedMutexGuard<()>)>,
+}
+
+impl RowLocks {
+    /// IDs of the locked rows, in ascending order
+    pub fn ids(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.guards.iter().map(|(id, _)| *id)
+    }
+}
+
+impl std::fmt::Debug for RowLocks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RowLocks")
+            .field("table", &self.table)
+            .field("ids", &self.ids().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for RowLocks {
+    fn drop(&mut self) {
+        let ids: Vec<Uuid> = self.guards.drain(..).map(|(id, _)| id).collect();
+        // Forget the locks nobody else holds or waits for
+        let mut locks = LOCKS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        for id in ids {
+            let key = (self.table, id);
+            if locks.get(&key).is_some_and(|lock| lock.strong_count() == 0) {
+                locks.remove(&key);
+            }
+        }
+    }
+}
+
+/// Lock the rows of `T` with `ids` for writing, waiting for other holders. Returns
+/// without locking anything unless `T` is `serialize_writes`.
+///
+/// Rows are locked in ascending ID order, so batches sharing rows can't deadlock.
+/// Repeated IDs are locked once.
+pub async fn lock_rows<T: CRUDResource>(ids: impl IntoIterator<Item = Uuid>) -> RowLocks {
+    let table = T::TABLE_NAME;
+    if !T::SERIALIZE_WRITES {
+        return RowLocks {
+            table,
+            guards: Vec::new(),
+        };
+    }
+    let mut ids: Vec<Uuid> = ids.into_iter().collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let mut guards = Vec::with_capacity(ids.len());
+    for id in ids {
+        let lock = {
+            let mut locks = LOCKS
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let slot = locks.entry((table, id)).or_default();
+            slot.upgrade().unwrap_or_else(|| {
+                let lock = Arc::new(RowMutex::new(()));
+                *slot = Arc::downgrade(&lock);
+                lock
+            })
+        };
+        guards.push((id, lock.lock_owned().await));
+    }
+    RowLocks { table, guards }
+}
+
+/// Number of rows with a lock held or awaited, across all tables
+#[must_use]
+pub fn locked_rows() -> usize {
+    LOCKS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .values()
+        .filter(|lock| lock.strong_count() > 0)
+        .count()
+}
@@ -0,0 +1,88 @@
+//! Accent- and case-insensitive text for `normalized_search` resources.
+//!
+//! [`fold`] decomposes text to Unicode NFKD, drops the combining marks and lowercases
+//! the rest, so "Müller", "MULLER" and "muller" all fold to `muller`. A resource declared
+//! with `#[crudcrate(normalized_search = "search_text")]` stores the folded text of its
+//! `fulltext` fields in that column on every create and update, and the `q` filter folds
+//! the query the same way before matching it with LIKE:
+//!
+//! ```text
+//! GET /people?filter={"q":"Muller"}     # finds "Müller"
+//! GET /people?filter={"q":"müller"}     # finds "Muller" and "MÜLLER"
+//! ```
+//!
+//! Rows written before the column existed, or by other means than the generated
+//! endpoints, need their column filled with [`search_text`]. Hooks can call [`fold`] to
+//! compare or deduplicate names the same way.
+//!
+//! Requires the `unicode` feature.
+
+use sea_orm::{ActiveValue, Value};
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// `text` without accents and in lowercase: "Crème Brûlée" folds to `creme brulee`.
+/// Compatibility characters are decomposed too, so "ﬁ" folds to `fi` and "①" to `1`.
+#[must_use]
+pub fn fold(text: &str) -> String {
+    text.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The folded `values` joined by spaces: the contents of a `normalized_search` column.
+/// `None` values are skipped.
+#[must_use]
+pub fn search_text<I, S>(values: I) -> String
+where
+    I: IntoIterator<Item = Option<S>>,
+    S: AsRef<str>,
+{
+    values
+        .into_iter()
+        .flatten()
+        .map(|value| fold(value.as_ref()))
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The text an active model field holds: a string's contents, any other value as JSON,
+/// or `None` when it is unset or `null`. Used by the generated writes of
+/// `normalized_search` resources to collect their `fulltext` fields.
+#[must_use]
+pub fn active_text<V: Into<Value> + Serialize>(value: &ActiveValue<V>) -> Option<String> {
+    match serde_json::to_value(value.try_as_ref()?).ok()? {
+        serde_json::Value::String(text) => Some(text),
+        serde_json::Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("Müller"), "muller");
+        assert_eq!(fold("Müller"), fold("MULLER"));
+        assert_eq!(fold("Crème Brûlée"), "creme brulee");
+        assert_eq!(fold("ﬁle №1"), "file no1");
+        assert_eq!(fold("Ångström"), "angstrom");
+    }
+
+    #[test]
+    fn test_search_text() {
+        let text = search_text([Some("Zoë"), None, Some(""), Some("Ünal")]);
+        assert_eq!(text, "zoe unal");
+        assert_eq!(
+            active_text(&ActiveValue::Set(Some(3_i32))).as_deref(),
+            Some("3")
+        );
+        assert_eq!(active_text(&ActiveValue::<Option<String>>::Set(None)), None);
+        assert_eq!(active_text(&ActiveValue::<String>::NotSet), None);
+    }
+}
@@ -0,0 +1,282 @@
+//! Complete `OpenAPI` documents and JSON Schemas from generated routers.
+//!
+//! [`document`] nests each router under its prefix as [`merge_resources`] does, tags
+//! every operation with the resource it belongs to, and fills in the document's info,
+//! servers and security from an [`ApiInfo`]:
+//!
+//! ```rust,ignore
+//! let info = ApiInfo::new("Todo API", "1.0.0")
+//!     .description("Todos and their tags")
+//!     .server("https://api.example.com")
+//!     .security("bearer");
+//! let (router, openapi) = crudcrate::openapi::document(
+//!     &info,
+//!     &[("/todos", Todo::router(&db)), ("/tags", Tag::router(&db))],
+//! )?;
+//! let app = router.merge(Scalar::with_url("/docs", openapi));
+//! ```
+//!
+//! Tags are named after the registered resource mounted at the prefix (its plural name,
+//! with its `description`), or after the prefix itself.
+//!
+//! [`json_schema`] exports one model as a standalone JSON Schema (draft 2020-12), and
+//! [`json_schemas`] every schema of a document, for contract tests in other services:
+//!
+//! ```rust,ignore
+//! let schema = crudcrate::openapi::json_schema::<TodoCreate>();
+//! std::fs::write("contracts/todo-create.json", schema.to_string())?;
+//! ```
+//!
+//! Schemas a model refers to are included under `$defs`, with their `$ref`s rewritten to
+//! point there.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+use utoipa::openapi::{Info, OpenApi, Server, Tag};
+use utoipa_axum::router::OpenApiRouter;
+
+use crate::router::{RouteConflict, merge_resources, operations_mut, with_security};
+
+/// JSON Schema dialect of the exported schemas, the one `OpenAPI` 3.1 uses
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Title, version, servers and security of a [`document`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiInfo {
+    title: String,
+    version: String,
+    description: Option<String>,
+    servers: Vec<String>,
+    security: Option<String>,
+}
+
+impl ApiInfo {
+    #[must_use]
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            version: version.into(),
+            description: None,
+            servers: Vec::new(),
+            security: None,
+        }
+    }
+
+    /// Description of the whole API
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Add a server URL; the first is the default in API explorers
+    #[must_use]
+    pub fn server(mut self, url: impl Into<String>) -> Self {
+        self.servers.push(url.into());
+        self
+    }
+
+    /// Require the security scheme named `scheme` on every operation, as
+    /// [`with_security`] does
+    #[must_use]
+    pub fn security(mut self, scheme: impl Into<String>) -> Self {
+        self.security = Some(scheme.into());
+        self
+    }
+}
+
+/// Nest each router under its prefix and return the combined router with a complete
+/// `OpenAPI` 3.1 document.
+///
+/// # Errors
+/// Returns a [`RouteConflict`] if two resources register the same route, as
+/// [`merge_resources`] does.
+pub fn document(
+    info: &ApiInfo,
+    resources: &[(&str, OpenApiRouter)],
+) -> Result<(axum::Router, OpenApi), RouteConflict> {
+    let registered = crate::registry::resources();
+    let mut tags: Vec<Tag> = Vec::new();
+    let mut tagged = Vec::with_capacity(resources.len());
+    for (prefix, router) in resources {
+        let mut router = router.clone();
+        let mounted = prefix.trim_end_matches('/');
+        let resource = registered.iter().find(|resource| resource.path == mounted);
+        let name = resource.map_or_else(
+            || mounted.trim_start_matches('/').to_string(),
+            |resource| resource.name.to_string(),
+        );
+        if !name.is_empty() {
+            for item in router.get_openapi_mut().paths.paths.values_mut() {
+                for operation in operations_mut(item) {
+                    operation.tags = Some(vec![name.clone()]);
+                }
+            }
+            if !tags.iter().any(|tag| tag.name == name) {
+                let mut tag = Tag::new(&name);
+                tag.description = resource
+                    .map(|resource| resource.description)
+                    .filter(|description| !description.is_empty())
+                    .map(str::to_string);
+                tags.push(tag);
+            }
+        }
+        tagged.push((*prefix, router));
+    }
+
+    let mut merged = merge_resources(&tagged)?;
+    if let Some(scheme) = &info.security {
+        merged = with_security(merged, scheme);
+    }
+    let (router, mut openapi) = merged.split_for_parts();
+    openapi.info = Info::new(info.title.as_str(), info.version.as_str());
+    openapi.info.description.clone_from(&info.description);
+    openapi.servers =
+        (!info.servers.is_empty()).then(|| info.servers.iter().map(Server::new).collect());
+    openapi.tags = (!tags.is_empty()).then_some(tags);
+    Ok((router, openapi))
+}
+
+/// `T`'s schema as a standalone JSON Schema, with the schemas it refers to under `$defs`
+#[must_use]
+pub fn json_schema<T: ToSchema>() -> Value {
+    let mut schemas = Vec::new();
+    T::schemas(&mut schemas);
+    let components = schemas
+        .into_iter()
+        .map(|(name, schema)| (name, to_json(&schema)))
+        .collect();
+    standalone(&T::name(), to_json(&T::schema()), &components)
+}
+
+/// Every component schema of `openapi` as a standalone JSON Schema, keyed by name
+#[must_use]
+pub fn json_schemas(openapi: &OpenApi) -> BTreeMap<String, Value> {
+    let components: BTreeMap<String, Value> = openapi
+        .components
+        .iter()
+        .flat_map(|components| &components.schemas)
+        .map(|(name, schema)| (name.clone(), to_json(schema)))
+        .collect();
+    components
+        .iter()
+        .map(|(name, schema)| (name.clone(), standalone(name, schema.clone(), &components)))
+        .collect()
+}
+
+fn to_json(schema: &impl serde::Serialize) -> Value {
+    serde_json::to_value(schema).unwrap_or_default()
+}
+
+/// `schema` with the dialect, a title and the `$defs` it refers to
+fn standalone(title: &str, schema: Value, components: &BTreeMap<String, Value>) -> Value {
+    let mut defs = BTreeMap::new();
+    collect_refs(&schema, components, &mut defs);
+
+    let mut root = match rewrite_refs(schema) {
+        Value::Object(map) => map,
+        other => Map::from_iter([("allOf".to_string(), Value::Array(vec![other]))]),
+    };
+    root.insert("$schema".to_string(), Value::from(DIALECT));
+    root.entry("title").or_insert_with(|| Value::from(title));
+    if !defs.is_empty() {
+        let defs = defs
+            .into_iter()
+            .map(|(name, schema)| (name, rewrite_refs(schema)))
+            .collect();
+        root.insert("$defs".to_string(), Value::Object(defs));
+    }
+    Value::Object(root)
+}
+
+/// Add the components `value` refers to, directly or through each other, to `defs`
+fn collect_refs(
+    value: &Value,
+    components: &BTreeMap<String, Value>,
+    defs: &mut BTreeMap<String, Value>,
+) {
+    match value {
+        Value::Object(map) => {
+            if let Some(name) = map
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|reference| reference.strip_prefix("#/components/schemas/"))
+                && !defs.contains_key(name)
+                && let Some(schema) = components.get(name)
+            {
+                defs.insert(name.to_string(), schema.clone());
+                collect_refs(schema, components, defs);
+            }
+            for nested in map.values() {
+                collect_refs(nested, components, defs);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_refs(item, components, defs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `#/components/schemas/X` → `#/$defs/X`
+fn rewrite_refs(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, nested)| {
+                    let nested = match nested {
+                        Value::String(reference) if key == "$ref" => {
+                            Value::String(reference.replace("#/components/schemas/", "#/$defs/"))
+                        }
+                        other => rewrite_refs(other),
+                    };
+                    (key, nested)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(rewrite_refs).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_standalone_collects_nested_refs() {
+        let components = BTreeMap::from([
+            (
+                "Owner".to_string(),
+                json!({"type": "object", "properties": {"pet": {"$ref": "#/components/schemas/Pet"}}}),
+            ),
+            ("Pet".to_string(), json!({"type": "string"})),
+            ("Unused".to_string(), json!({"type": "integer"})),
+        ]);
+        let schema = standalone(
+            "Shop",
+            json!({"type": "array", "items": {"$ref": "#/components/schemas/Owner"}}),
+            &components,
+        );
+        assert_eq!(schema["$schema"], DIALECT);
+        assert_eq!(schema["title"], "Shop");
+        assert_eq!(schema["items"]["$ref"], "#/$defs/Owner");
+        assert_eq!(
+            schema["$defs"]["Owner"]["properties"]["pet"]["$ref"],
+            "#/$defs/Pet"
+        );
+        assert!(schema["$defs"].get("Unused").is_none());
+    }
+
+    #[test]
+    fn test_standalone_without_refs() {
+        let schema = standalone("Flag", json!({"type": "boolean"}), &BTreeMap::new());
+        assert!(schema.get("$defs").is_none());
+        assert_eq!(schema["type"], "boolean");
+    }
+}
@@ -24,6 +24,8 @@
 //! pub struct Model { /* ... */ }
 //! ```
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use sea_orm::{Condition, DatabaseConnection, Order};
 use uuid::Uuid;
@@ -182,11 +184,16 @@ pub trait CRUDOperations: Send + Sync {
         offset: u64,
         limit: u64,
     ) -> Result<Vec<<Self::Resource as CRUDResource>::ListModel>, ApiError> {
-        use sea_orm::{EntityTrait, QueryFilter, QueryOrder, QuerySelect};
-
-        let models = <Self::Resource as CRUDResource>::EntityType::find()
-            .filter(condition.clone())
-            .order_by(order_column, order_direction)
+        use sea_orm::{QueryFilter, QueryOrder, QuerySelect};
+
+        let order = crate::core::crud_operations::with_tie_breaker::<Self::Resource>(&[
+            crate::core::crud_operations::column_order(order_column, order_direction),
+        ]);
+        let mut query = crate::core::view::select::<Self::Resource>().filter(condition.clone());
+        for (expr, direction) in order {
+            query = query.order_by(expr, direction);
+        }
+        let models = query
             .offset(offset)
             .limit(limit)
             .all(db)
@@ -244,7 +251,7 @@ pub trait CRUDOperations: Send + Sync {
     ) -> Result<Self::Resource, ApiError> {
         use sea_orm::ActiveModelTrait;
 
-        let active_model: <Self::Resource as CRUDResource>::ActiveModelType = data.into();
+        let active_model = Self::Resource::create_active_model(db, data).await?;
         let model = active_model.insert(db).await.map_err(ApiError::database)?;
         Ok(Self::Resource::from(model))
     }
@@ -279,7 +286,6 @@ pub trait CRUDOperations: Send + Sync {
         id: Uuid,
         data: <Self::Resource as CRUDResource>::UpdateModel,
     ) -> Result<Self::Resource, ApiError> {
-        use crate::core::MergeIntoActiveModel;
         use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel};
 
         let model = <Self::Resource as CRUDResource>::EntityType::find_by_id(id)
@@ -293,7 +299,7 @@ pub trait CRUDOperations: Send + Sync {
                 )
             })?;
         let existing: <Self::Resource as CRUDResource>::ActiveModelType = model.into_active_model();
-        let updated_model = data.merge_into_activemodel(existing)?;
+        let updated_model = Self::Resource::update_active_model(db, data, existing).await?;
         let updated = updated_model.update(db).await.map_err(ApiError::database)?;
         Ok(Self::Resource::from(updated))
     }
@@ -689,3 +695,49 @@ impl<T: CRUDResource> CRUDOperations for DefaultCRUDOperations<T> {
     // All methods use default implementations from the trait
     // No overrides needed - delegates to T::method() automatically
 }
+
+/// An operations object injected into a generated router at runtime.
+///
+/// `Resource::router_with_ops(&db, ops)` attaches this as a request extension. The
+/// generated handlers look for it and dispatch through the trait object instead of the
+/// static [`CRUDResource`] methods, so behavior can be swapped per router (tests,
+/// feature flags) without recompiling the resource:
+///
+/// ```rust,ignore
+/// let ops: Arc<dyn CRUDOperations<Resource = Todo>> = if config.audit {
+///     Arc::new(AuditedOps::new(audit_log))
+/// } else {
+///     Arc::new(DefaultCRUDOperations::new())
+/// };
+/// let app = Todo::router(&db).layer(Extension(SharedOperations::from_arc(ops)));
+/// ```
+///
+/// Scoped requests (those carrying a [`ScopeCondition`](crate::ScopeCondition)) and
+/// geo distance sorting keep using the static methods, since the trait has no scoped
+/// or custom-order variants.
+pub struct SharedOperations<T: CRUDResource>(Arc<dyn CRUDOperations<Resource = T>>);
+
+impl<T: CRUDResource> SharedOperations<T> {
+    /// Wrap an operations object
+    pub fn new(ops: impl CRUDOperations<Resource = T> + 'static) -> Self {
+        Self(Arc::new(ops))
+    }
+
+    /// Wrap an operations object that is already shared
+    #[must_use]
+    pub fn from_arc(ops: Arc<dyn CRUDOperations<Resource = T>>) -> Self {
+        Self(ops)
+    }
+
+    /// The wrapped operations object
+    #[must_use]
+    pub fn operations(&self) -> &dyn CRUDOperations<Resource = T> {
+        self.0.as_ref()
+    }
+}
+
+impl<T: CRUDResource> Clone for SharedOperations<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
@@ -0,0 +1,208 @@
+//! Runtime registry of mounted resources.
+//!
+//! Generated routers register their resource here when they are built, so the running
+//! binary can describe its own API: names, paths, fields, types, filter/sort flags and
+//! relations. Use it to drive admin UIs, permission matrices or generated docs.
+//!
+//! ```rust,ignore
+//! let app = Router::new()
+//!     .nest("/todos", Todo::router(&db).into())
+//!     .route("/_meta", get(|| async { Json(crudcrate::registry::resources()) }));
+//! ```
+//!
+//! Resources are keyed by their plural name and registered at `/{plural}`. Call
+//! [`register_at`] when a router is mounted elsewhere. Resources whose router is never
+//! built can be added with [`register`].
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+use crate::core::CRUDResource;
+
+static REGISTRY: RwLock<BTreeMap<&'static str, ResourceMetadata>> = RwLock::new(BTreeMap::new());
+
+/// Description of one resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct ResourceMetadata {
+    /// Plural resource name, also the registry key (e.g. `"todos"`)
+    pub name: &'static str,
+    /// Singular resource name (e.g. `"todo"`)
+    pub singular: &'static str,
+    /// Name of the API struct (e.g. `"Todo"`)
+    pub api_struct: &'static str,
+    /// Path the router is mounted at
+    pub path: String,
+    /// Database table
+    pub table: &'static str,
+    /// `description` from the struct attribute
+    pub description: &'static str,
+    /// Whether list requests need a `ScopeCondition`
+    pub require_scope: bool,
+    pub batch_limit: usize,
+    pub max_page_size: u64,
+    pub fields: Vec<FieldMetadata>,
+    pub relations: Vec<RelationMetadata>,
+}
+
+impl ResourceMetadata {
+    /// Build the metadata of `T`, mounted at `/{plural}`
+    #[must_use]
+    pub fn of<T: CRUDResource>() -> Self {
+        Self {
+            name: T::RESOURCE_NAME_PLURAL,
+            singular: T::RESOURCE_NAME_SINGULAR,
+            api_struct: std::any::type_name::<T>()
+                .rsplit("::")
+                .next()
+                .unwrap_or_default(),
+            path: format!("/{}", T::RESOURCE_NAME_PLURAL),
+            table: T::TABLE_NAME,
+            description: T::RESOURCE_DESCRIPTION,
+            require_scope: T::REQUIRE_SCOPE,
+            batch_limit: T::batch_limit(),
+            max_page_size: T::max_page_size(),
+            fields: T::field_metadata(),
+            relations: T::relation_metadata(),
+        }
+    }
+
+    /// Look up a field by name
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&FieldMetadata> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+/// Description of one field of a resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+#[allow(clippy::struct_excessive_bools)]
+pub struct FieldMetadata {
+    pub name: &'static str,
+    /// Rust type as written on the model (e.g. `"Option<DateTime<Utc>>"`)
+    pub rust_type: &'static str,
+    pub nullable: bool,
+    pub primary_key: bool,
+    pub filterable: bool,
+    pub sortable: bool,
+    pub fulltext: bool,
+    /// Accepted in create requests
+    pub create: bool,
+    /// Accepted in update requests
+    pub update: bool,
+    /// Returned by single-item responses
+    pub response: bool,
+    /// Returned by list responses
+    pub list: bool,
+    /// Allowed values for enum fields
+    pub enum_variants: Option<Vec<String>>,
+}
+
+impl FieldMetadata {
+    /// A field with only its name and type set
+    #[must_use]
+    pub fn new(name: &'static str, rust_type: &'static str) -> Self {
+        Self {
+            name,
+            rust_type,
+            nullable: false,
+            primary_key: false,
+            filterable: false,
+            sortable: false,
+            fulltext: false,
+            create: false,
+            update: false,
+            response: false,
+            list: false,
+            enum_variants: None,
+        }
+    }
+}
+
+/// Whether a relation loads one related item or many
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Cardinality {
+    One,
+    Many,
+}
+
+/// Description of a `join(...)` field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct RelationMetadata {
+    /// Field name on the parent resource
+    pub name: &'static str,
+    /// API struct of the related resource
+    pub target: &'static str,
+    pub cardinality: Cardinality,
+    /// Loaded by the single-item endpoint
+    pub on_one: bool,
+    /// Loaded by the list endpoint
+    pub on_all: bool,
+    /// Maximum recursion depth
+    pub depth: u8,
+}
+
+impl RelationMetadata {
+    /// A depth-1 relation that is not loaded by either endpoint
+    #[must_use]
+    pub fn new(name: &'static str, target: &'static str, cardinality: Cardinality) -> Self {
+        Self {
+            name,
+            target,
+            cardinality,
+            on_one: false,
+            on_all: false,
+            depth: 1,
+        }
+    }
+}
+
+fn write_registry() -> std::sync::RwLockWriteGuard<'static, BTreeMap<&'static str, ResourceMetadata>>
+{
+    REGISTRY
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Register `T` at `/{plural}` unless it is already registered.
+///
+/// Generated `router()` and `read_only_router()` call this, so it rarely needs calling
+/// directly.
+pub fn register<T: CRUDResource>() {
+    write_registry()
+        .entry(T::RESOURCE_NAME_PLURAL)
+        .or_insert_with(ResourceMetadata::of::<T>);
+}
+
+/// Register `T` at `path`, replacing any earlier entry
+pub fn register_at<T: CRUDResource>(path: impl Into<String>) {
+    let mut metadata = ResourceMetadata::of::<T>();
+    metadata.path = path.into();
+    write_registry().insert(T::RESOURCE_NAME_PLURAL, metadata);
+}
+
+/// Every registered resource, ordered by name
+#[must_use]
+pub fn resources() -> Vec<ResourceMetadata> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .values()
+        .cloned()
+        .collect()
+}
+
+/// The registered resource with plural name `name`
+#[must_use]
+pub fn resource(name: &str) -> Option<ResourceMetadata> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(name)
+        .cloned()
+}
@@ -0,0 +1,105 @@
+//! Relation endpoints of `Vec` join fields declared `join(one, lazy)`.
+//!
+//! A join over a large child set makes every single-item response as big as the set.
+//! `lazy` keeps the field out of list responses, like any `join(one)` field, and adds
+//! `GET /{id}/{field}` to the generated routers, which lists the children on their own
+//! with the child resource's filters, sorting and pagination:
+//!
+//! ```text
+//! GET /authors/{id}/books?filter={"published":true}&sort=["title","ASC"]&page=2&per_page=50
+//! ```
+//!
+//! The body is the child's list model with a `Content-Range` header, as on the child's
+//! own list endpoint, and the child's join budget applies to the page. The parent must
+//! exist and be inside the request's `ScopeCondition`; scoped requests also apply the
+//! child's [`scope_condition`](crate::ScopeFilterable::scope_condition), as scoped join
+//! loading does, and answer with the child's scoped list model.
+
+use axum::response::Response;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::core::crud_operations::{check_required_scope, find_one, list_response};
+use crate::models::FilterOptions;
+use crate::{ApiError, CRUDResource, ScopeFilterable};
+
+/// Body of a generated relation handler: one page of the `T` rows whose `fk_column` is
+/// `parent_id`, a `P` row. Scoped pages are serialized as `S`.
+///
+/// # Errors
+/// Returns `ApiError::NotFound` if the parent doesn't exist or is outside `scope`, and
+/// any error of the child's list endpoint for its query parameters.
+pub async fn relation_response<P, T, S>(
+    db: &DatabaseConnection,
+    parent_id: Uuid,
+    fk_column: T::ColumnType,
+    params: FilterOptions,
+    scope: Option<Condition>,
+) -> Result<Response, ApiError>
+where
+    P: CRUDResource,
+    T: CRUDResource,
+    T::ListModel: Serialize + ScopeFilterable,
+    S: From<T::ListModel> + Serialize,
+{
+    check_required_scope::<P>(scope.is_some())?;
+    find_one::<P>(db, parent_id, scope.as_ref()).await?;
+
+    let child_scope = scope.map(|_| {
+        <T::ListModel as ScopeFilterable>::scope_condition().unwrap_or_else(Condition::all)
+    });
+    let children = Condition::all().add(fk_column.eq(parent_id));
+    list_response::<T, S>(params, db, child_scope, Some(children), None).await
+}
+
+/// Handler of one `lazy` join field's relation endpoint, invoked by the derive next to
+/// `crud_handlers!`. `@no_openapi` leaves out `#[utoipa::path]`.
+#[macro_export]
+macro_rules! relation_handler {
+    (@no_openapi $resource:ty, $child:ty, $child_list:ty, $child_scoped_list:ty, $handler:ident, $field:tt, $path:tt, $fk_column:expr) => {
+        crudcrate::relation_handler!(@openapi false; $resource, $child, $child_list, $child_scoped_list, $handler, $field, $path, $fk_column);
+    };
+    ($resource:ty, $child:ty, $child_list:ty, $child_scoped_list:ty, $handler:ident, $field:tt, $path:tt, $fk_column:expr) => {
+        crudcrate::relation_handler!(@openapi true; $resource, $child, $child_list, $child_scoped_list, $handler, $field, $path, $fk_column);
+    };
+    (@openapi $openapi:tt; $resource:ty, $child:ty, $child_list:ty, $child_scoped_list:ty, $handler:ident, $field:tt, $path:tt, $fk_column:expr) => {
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            get,
+            path = $path,
+            responses(
+                (status = axum::http::StatusCode::OK, description = "One page of the related resources", body = [$child_list]),
+                (status = axum::http::StatusCode::NOT_FOUND, description = "Resource not found"),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Invalid filter"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            params(crudcrate::models::FilterOptions),
+            operation_id = format!("get_{}_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR, $field),
+            summary = format!("Get the {} of one {}", $field, <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!(
+                "Retrieves the {} of one {}, filtered, sorted and paginated like the list of all {}.",
+                $field,
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR,
+                <$child as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL
+            )
+        )]
+        pub async fn $handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            crudcrate::PathId { id, .. }: crudcrate::PathId<$resource>,
+            axum::extract::Query(params): axum::extract::Query<crudcrate::models::FilterOptions>,
+            axum::extract::Query(query): axum::extract::Query<Vec<(String, String)>>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            let params = crudcrate::filtering::merge_query_filters::<$child>(params, &query)?;
+            crudcrate::relationships::lazy::relation_response::<$resource, $child, $child_scoped_list>(
+                &db,
+                id,
+                $fk_column,
+                params,
+                scope.map(|axum::Extension(scope)| scope.condition),
+            )
+            .await
+        });
+    };
+}
@@ -1,2 +1,312 @@
-// Relationship loading is handled by generated code from crudcrate-derive.
-// This module exists as a namespace for future runtime relationship utilities.
+//! Runtime helpers for relationship fields.
+//!
+//! Join loading itself is generated by `crudcrate-derive`. This module holds the parts
+//! that are generic over the entity, like the grouped `COUNT` behind
+//! `#[crudcrate(counted_relation = "...")]` fields, the target lookups behind
+//! `#[crudcrate(polymorphic_join(...))]` fields and the dependent row check of
+//! `#[crudcrate(restrict_delete)]` resources. [`DeletedChild`] and
+//! [`report_cascade`] describe the rows removed by `#[crudcrate(cascade_delete)]`. [`tree`] serves the children and
+//! ancestors endpoints of `#[crudcrate(tree(...))]` resources, [`lazy`] the relation
+//! endpoints of `join(one, lazy)` fields, and [`nested_create_model`] reads the related
+//! row of `join(create)` fields.
+
+pub mod lazy;
+pub mod tree;
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use sea_orm::sea_query::{Alias, Expr, Query, TableRef, ValueType};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, Identity, Iterable,
+    ModelTrait, PaginatorTrait, PrimaryKeyToColumn, QueryFilter, RelationDef, RelationTrait,
+    TryGetable, Value,
+};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::ApiError;
+use crate::errors::Dependent;
+use crate::traits::CRUDResource;
+
+/// Count the children of each parent over a `has_many` relation, in one grouped query:
+///
+/// ```sql
+/// SELECT fk AS parent_id, COUNT(*) AS count FROM child WHERE fk IN (...) GROUP BY fk
+/// ```
+///
+/// `relation` goes from the parent to the child (e.g. `Relation::Vehicles.def()`), and
+/// `parent_ids` are values of the parent column it joins on, normally the primary key.
+/// Only children matching `condition` are counted; the generated fields pass the child's
+/// [`read_condition`](CRUDResource::read_condition). Parents without children are absent
+/// from the map.
+///
+/// # Errors
+/// Returns `ApiError::Internal` for a relation over a composite key, and
+/// `ApiError::Database` if the query fails.
+pub async fn count_related<K>(
+    db: &DatabaseConnection,
+    relation: RelationDef,
+    condition: Option<Condition>,
+    parent_ids: Vec<K>,
+) -> Result<HashMap<K, i64>, ApiError>
+where
+    K: Into<Value> + TryGetable + Eq + Hash,
+{
+    if parent_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let Identity::Unary(foreign_key) = relation.to_col else {
+        return Err(ApiError::internal(
+            "Failed to count related rows",
+            Some("counted_relation does not support composite keys".to_string()),
+        ));
+    };
+
+    let mut query = Query::select()
+        .expr_as(Expr::col(foreign_key.clone()), Alias::new("parent_id"))
+        .expr_as(Expr::col(foreign_key.clone()).count(), Alias::new("count"))
+        .from(relation.to_tbl)
+        .and_where(Expr::col(foreign_key.clone()).is_in(parent_ids.into_iter().map(Into::into)))
+        .group_by_col(foreign_key)
+        .to_owned();
+    if let Some(condition) = condition {
+        query.cond_where(condition);
+    }
+    let rows = db
+        .query_all(db.get_database_backend().build(&query))
+        .await
+        .map_err(ApiError::database)?;
+
+    let mut counts = HashMap::with_capacity(rows.len());
+    for row in rows {
+        let parent_id: K = row.try_get("", "parent_id").map_err(ApiError::database)?;
+        let count: i64 = row.try_get("", "count").map_err(ApiError::database)?;
+        counts.insert(parent_id, count);
+    }
+    Ok(counts)
+}
+
+/// Refuse to delete the `T` rows with the given IDs while other tables still reference
+/// them, as generated for `#[crudcrate(restrict_delete)]`.
+///
+/// Every `has_many` and `has_one` relation of `R` (the entity's `Relation` enum) is
+/// counted with one query:
+///
+/// ```sql
+/// SELECT COUNT(fk) AS count FROM child WHERE fk IN (SELECT pk_col FROM parent WHERE id IN (...))
+/// ```
+///
+/// Relations over composite keys are skipped. The check runs before the delete, so a
+/// row inserted in between still reaches the database's foreign key.
+///
+/// # Errors
+/// Returns [`ApiError::Restricted`] listing the relations with rows, or
+/// `ApiError::Database` if a query fails.
+pub async fn ensure_no_dependents<T, R>(
+    db: &impl ConnectionTrait,
+    ids: &[Uuid],
+) -> Result<(), ApiError>
+where
+    T: CRUDResource,
+    R: RelationTrait + Iterable,
+{
+    let dependents = dependents::<T, R>(db, ids).await?;
+    if dependents.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::restricted(T::RESOURCE_NAME_SINGULAR, dependents))
+    }
+}
+
+/// Rows referencing the `T` rows with the given IDs, per `has_many`/`has_one` relation
+/// of `R`. Relations without rows are left out.
+///
+/// # Errors
+/// Returns `ApiError::Database` if a query fails.
+pub async fn dependents<T, R>(
+    db: &impl ConnectionTrait,
+    ids: &[Uuid],
+) -> Result<Vec<Dependent>, ApiError>
+where
+    T: CRUDResource,
+    R: RelationTrait + Iterable,
+{
+    let mut dependents = Vec::new();
+    if ids.is_empty() {
+        return Ok(dependents);
+    }
+    for relation in R::iter() {
+        let relation = relation.def();
+        // `belongs_to` relations point at a parent, which doesn't block this delete
+        if !relation.is_owner {
+            continue;
+        }
+        let (Identity::Unary(foreign_key), Identity::Unary(parent_column)) =
+            (relation.to_col, relation.from_col)
+        else {
+            continue;
+        };
+
+        let parents = Query::select()
+            .column(parent_column)
+            .from(relation.from_tbl)
+            .and_where(Expr::col(T::ID_COLUMN).is_in(ids.iter().copied()))
+            .to_owned();
+        let query = Query::select()
+            .expr_as(Expr::col(foreign_key.clone()).count(), Alias::new("count"))
+            .from(relation.to_tbl.clone())
+            .and_where(Expr::col(foreign_key).in_subquery(parents))
+            .to_owned();
+        let row = db
+            .query_one(db.get_database_backend().build(&query))
+            .await
+            .map_err(ApiError::database)?;
+        let count: i64 = match row {
+            Some(row) => row.try_get("", "count").map_err(ApiError::database)?,
+            None => 0,
+        };
+        if count > 0 {
+            dependents.push(Dependent {
+                relation: table_name(&relation.to_tbl),
+                count: count.unsigned_abs(),
+            });
+        }
+    }
+    Ok(dependents)
+}
+
+/// The unqualified table name of `table`
+fn table_name(table: &TableRef) -> String {
+    match table {
+        TableRef::Table(table)
+        | TableRef::SchemaTable(_, table)
+        | TableRef::DatabaseSchemaTable(_, _, table) => table.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// A row deleted along with its parent by `#[crudcrate(cascade_delete)]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeletedChild {
+    /// Singular name of the child resource
+    pub resource: &'static str,
+    pub id: Uuid,
+}
+
+/// Emit one `tracing` event per child removed by a committed cascade delete of the
+/// `parent` resource, at `info` level with the `crudcrate::cascade` target.
+pub fn report_cascade(parent: &'static str, children: &[DeletedChild]) {
+    for child in children {
+        tracing::info!(
+            target: "crudcrate::cascade",
+            parent,
+            resource = child.resource,
+            id = %child.id,
+            "Deleted with its parent"
+        );
+    }
+}
+
+/// Load the `T` rows with the given IDs for a `polymorphic_join` field, serialized as JSON
+/// and keyed by ID. IDs without a row, or whose row `T`'s
+/// [`read_condition`](CRUDResource::read_condition) hides, are absent from the map.
+///
+/// # Errors
+/// Returns `ApiError::Database` if the query fails, and `ApiError::Internal` if a row
+/// can't be serialized.
+pub async fn load_polymorphic<T>(
+    db: &DatabaseConnection,
+    ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, serde_json::Value>, ApiError>
+where
+    T: CRUDResource + Serialize,
+{
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let Some(primary_key) = <T::EntityType as EntityTrait>::PrimaryKey::iter().next() else {
+        return Ok(HashMap::new());
+    };
+    let id_column = primary_key.into_column();
+
+    let models = T::EntityType::find()
+        .filter(
+            Condition::all()
+                .add(T::ID_COLUMN.is_in(ids))
+                .add_option(T::read_condition()),
+        )
+        .all(db)
+        .await
+        .map_err(ApiError::database)?;
+
+    let mut loaded = HashMap::with_capacity(models.len());
+    for model in models {
+        let Ok(id) = <Uuid as ValueType>::try_from(model.get(id_column)) else {
+            continue;
+        };
+        let value = serde_json::to_value(T::from(model)).map_err(|e| {
+            ApiError::internal(
+                "Failed to load polymorphic relation",
+                Some(format!("serializing {}: {e}", T::RESOURCE_NAME_SINGULAR)),
+            )
+        })?;
+        loaded.insert(id, value);
+    }
+    Ok(loaded)
+}
+
+/// Check that a `polymorphic_join` field's ID points at an existing `T` row before it is
+/// written, counting rows hidden by `T`'s
+/// [`read_condition`](CRUDResource::read_condition) as missing. `field` is the field name
+/// used in the error message.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if no `T` row has the ID, and `ApiError::Database` if
+/// the query fails.
+pub async fn ensure_polymorphic_target<T, C>(db: &C, field: &str, id: Uuid) -> Result<(), ApiError>
+where
+    T: CRUDResource,
+    C: ConnectionTrait,
+{
+    let count = T::EntityType::find()
+        .filter(
+            Condition::all()
+                .add(T::ID_COLUMN.eq(id))
+                .add_option(T::read_condition()),
+        )
+        .count(db)
+        .await
+        .map_err(ApiError::database)?;
+    if count == 0 {
+        return Err(ApiError::bad_request(format!(
+            "Invalid {field}: no {} with id {id}",
+            T::RESOURCE_NAME_SINGULAR
+        )));
+    }
+    Ok(())
+}
+
+/// The create model of a `join(create)` row sent nested in its parent's create request,
+/// with `fk_field` set to the new parent's key. `field` is the parent's field name used in
+/// error messages.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `value` is not a JSON object or doesn't deserialize
+/// into `C`.
+pub fn nested_create_model<C: DeserializeOwned>(
+    field: &str,
+    value: serde_json::Value,
+    fk_field: &str,
+    parent_id: serde_json::Value,
+) -> Result<C, ApiError> {
+    let serde_json::Value::Object(mut object) = value else {
+        return Err(ApiError::bad_request(format!(
+            "Field '{field}' must be an object"
+        )));
+    };
+    object.insert(fk_field.to_string(), parent_id);
+    serde_json::from_value(serde_json::Value::Object(object))
+        .map_err(|e| ApiError::bad_request(format!("Invalid '{field}': {e}")))
+}
@@ -0,0 +1,332 @@
+//! Self-referencing resources declared with `#[crudcrate(tree(parent_field = "parent_id"))]`.
+//!
+//! The derive adds two read endpoints to the generated routers:
+//!
+//! - `GET /{id}/children` — direct children, or `?depth=N` levels of descendants
+//! - `GET /{id}/ancestors` — the parent, its parent and so on up to the root
+//!
+//! Walking more than one level uses a `WITH RECURSIVE` query (`PostgreSQL`, `MySQL` 8+,
+//! `SQLite`), capped at the resource's [`tree_max_depth`](crate::CRUDResource::tree_max_depth)
+//! so a cycle in the data cannot run away. The nodes found are then loaded through
+//! [`get_all_ordered`](crate::CRUDResource::get_all_ordered), so list joins, computed
+//! fields and scope conditions apply as on the list endpoint.
+
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DatabaseBackend, DatabaseConnection, IdenStatic,
+    Order, Statement, Value,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::complexity::JoinBudget;
+use crate::core::crud_operations::{check_required_scope, column_order, find_one};
+use crate::{ApiError, CRUDResource, TreeOptions};
+
+/// Which way [`tree_nodes`] walks from the starting node
+#[derive(Clone, Copy)]
+enum Direction {
+    Up,
+    Down,
+}
+
+/// IDs of the nodes up to `depth` levels above or below `id`, nearest first
+async fn tree_nodes<T: CRUDResource>(
+    db: &DatabaseConnection,
+    parent_column: T::ColumnType,
+    id: Uuid,
+    depth: u32,
+    direction: Direction,
+) -> Result<Vec<Uuid>, ApiError> {
+    let backend = db.get_database_backend();
+    let table = quote_ident(T::TABLE_NAME, backend);
+    let id_col = quote_ident(T::ID_COLUMN.as_str(), backend);
+    let parent_col = quote_ident(parent_column.as_str(), backend);
+    let (first, second) = match backend {
+        DatabaseBackend::Postgres => ("$1", "$2"),
+        _ => ("?", "?"),
+    };
+    // Up: start from the node's parent and follow parent links.
+    // Down: start from the node's children and follow child links.
+    let (select_col, match_col) = match direction {
+        Direction::Up => (&parent_col, &id_col),
+        Direction::Down => (&id_col, &parent_col),
+    };
+    let sql = format!(
+        "WITH RECURSIVE tree_nodes (node_id, depth) AS ( \
+             SELECT {select_col}, 1 FROM {table} WHERE {match_col} = {first} \
+             UNION ALL \
+             SELECT t.{select_col}, n.depth + 1 FROM {table} t \
+             JOIN tree_nodes n ON t.{match_col} = n.node_id \
+             WHERE n.depth < {second} \
+         ) \
+         SELECT node_id FROM tree_nodes WHERE node_id IS NOT NULL ORDER BY depth"
+    );
+    let max_depth = i32::try_from(depth).unwrap_or(i32::MAX);
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            backend,
+            sql,
+            [Value::from(id), Value::from(max_depth)],
+        ))
+        .await
+        .map_err(ApiError::database)?;
+
+    let mut ids = Vec::with_capacity(rows.len());
+    for row in rows {
+        let node: Uuid = row.try_get("", "node_id").map_err(ApiError::database)?;
+        // A cycle revisits nodes; keep the nearest occurrence
+        if !ids.contains(&node) {
+            ids.push(node);
+        }
+    }
+    Ok(ids)
+}
+
+fn quote_ident(name: &str, backend: DatabaseBackend) -> String {
+    match backend {
+        DatabaseBackend::MySql => format!("`{}`", name.replace('`', "``")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// Load `ids` as list models in the given order, leaving out rows outside `scope`
+async fn load_nodes<T: CRUDResource>(
+    db: &DatabaseConnection,
+    ids: &[Uuid],
+    scope: Option<&Condition>,
+) -> Result<Vec<T::ListModel>, ApiError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    crate::complexity::check::<T>(ids.len() as u64, true)?;
+    let mut condition = Condition::all().add(T::ID_COLUMN.is_in(ids.iter().copied()));
+    if let Some(scope) = scope {
+        condition = condition.add(scope.clone());
+    }
+    // CASE WHEN id = <nearest> THEN 0 WHEN ... END keeps the walk order
+    let mut position = Expr::case(T::ID_COLUMN.eq(ids[0]), 0);
+    for (index, id) in (0_i64..).zip(ids).skip(1) {
+        position = position.case(T::ID_COLUMN.eq(*id), index);
+    }
+    let order = [
+        (position.into(), Order::Asc),
+        column_order(T::default_index_column(), Order::Asc),
+    ];
+    T::get_all_ordered(db, &condition, &order, 0, ids.len() as u64, scope.is_some()).await
+}
+
+fn parent_column<T: CRUDResource>() -> Result<T::ColumnType, ApiError> {
+    T::tree_parent_column().ok_or_else(|| {
+        ApiError::internal(
+            "Tree endpoints are not available for this resource",
+            Some(format!(
+                "{} has no #[crudcrate(tree(parent_field = \"...\"))]",
+                T::RESOURCE_NAME_SINGULAR
+            )),
+        )
+    })
+}
+
+/// Descendants of the `T` node `id`, up to `depth` levels down, as list models ordered
+/// by level. With `depth = 1` these are the direct children, fetched without a recursive
+/// query.
+///
+/// # Errors
+/// Returns `ApiError::NotFound` if `id` doesn't exist or is outside `scope`,
+/// `ApiError::BadRequest` if `depth` is 0 or above
+/// [`tree_max_depth`](CRUDResource::tree_max_depth) or loading the descendants' join
+/// fields is over the [join budget](crate::complexity), and `ApiError::Database` if a
+/// query fails.
+pub async fn children<T: CRUDResource>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    depth: u32,
+    scope: Option<&Condition>,
+) -> Result<Vec<T::ListModel>, ApiError> {
+    let parent_column = parent_column::<T>()?;
+    let max_depth = T::tree_max_depth();
+    if depth == 0 || depth > max_depth {
+        return Err(ApiError::bad_request(format!(
+            "depth must be between 1 and {max_depth}"
+        )));
+    }
+    find_one::<T>(db, id, scope).await?;
+
+    if depth == 1 {
+        let mut condition = Condition::all().add(parent_column.eq(id));
+        if let Some(scope) = scope {
+            condition = condition.add(scope.clone());
+        }
+        // Join budgets need the number of children before their joins load
+        if T::join_budget() != JoinBudget::unlimited() {
+            let count = T::total_count(db, &condition).await.min(T::max_page_size());
+            crate::complexity::check::<T>(count, true)?;
+        }
+        let order = [column_order(T::default_index_column(), Order::Asc)];
+        return T::get_all_ordered(
+            db,
+            &condition,
+            &order,
+            0,
+            T::max_page_size(),
+            scope.is_some(),
+        )
+        .await;
+    }
+
+    let ids = tree_nodes::<T>(db, parent_column, id, depth, Direction::Down).await?;
+    load_nodes::<T>(db, &ids, scope).await
+}
+
+/// Ancestors of the `T` node `id` as list models, nearest first, up to
+/// [`tree_max_depth`](CRUDResource::tree_max_depth) levels.
+///
+/// # Errors
+/// Returns `ApiError::NotFound` if `id` doesn't exist or is outside `scope`,
+/// `ApiError::BadRequest` if loading the ancestors' join fields is over the
+/// [join budget](crate::complexity), and `ApiError::Database` if a query fails.
+pub async fn ancestors<T: CRUDResource>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    scope: Option<&Condition>,
+) -> Result<Vec<T::ListModel>, ApiError> {
+    let parent_column = parent_column::<T>()?;
+    find_one::<T>(db, id, scope).await?;
+    let ids = tree_nodes::<T>(db, parent_column, id, T::tree_max_depth(), Direction::Up).await?;
+    load_nodes::<T>(db, &ids, scope).await
+}
+
+/// Body of the generated `get_children_handler`. `S` is the scoped list model, returned
+/// when `scope` is set.
+///
+/// # Errors
+/// Returns any error from [`children`].
+pub async fn children_response<T, S>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    options: TreeOptions,
+    scope: Option<Condition>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    T::ListModel: Serialize,
+    S: From<T::ListModel> + Serialize,
+{
+    check_required_scope::<T>(scope.is_some())?;
+    let nodes = children::<T>(db, id, options.depth.unwrap_or(1), scope.as_ref()).await?;
+    Ok(nodes_response::<T, S>(nodes, scope.is_some()))
+}
+
+/// Body of the generated `get_ancestors_handler`. `S` is the scoped list model, returned
+/// when `scope` is set.
+///
+/// # Errors
+/// Returns any error from [`ancestors`].
+pub async fn ancestors_response<T, S>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    scope: Option<Condition>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    T::ListModel: Serialize,
+    S: From<T::ListModel> + Serialize,
+{
+    check_required_scope::<T>(scope.is_some())?;
+    let nodes = ancestors::<T>(db, id, scope.as_ref()).await?;
+    Ok(nodes_response::<T, S>(nodes, scope.is_some()))
+}
+
+fn nodes_response<T, S>(nodes: Vec<T::ListModel>, scoped: bool) -> Response
+where
+    T: CRUDResource,
+    T::ListModel: Serialize,
+    S: From<T::ListModel> + Serialize,
+{
+    if scoped {
+        let nodes: Vec<S> = nodes.into_iter().map(S::from).collect();
+        Json(nodes).into_response()
+    } else {
+        Json(nodes).into_response()
+    }
+}
+
+/// Handlers for the tree endpoints, invoked by the derive next to `crud_handlers!` for
+/// resources with `#[crudcrate(tree(...))]`. `@no_openapi` leaves out `#[utoipa::path]`.
+#[macro_export]
+macro_rules! tree_handlers {
+    (@no_openapi $resource:ty, $list_model:ty, $scoped_list:ty) => {
+        crudcrate::tree_handlers!(@openapi false; $resource, $list_model, $scoped_list);
+    };
+    ($resource:ty, $list_model:ty, $scoped_list:ty) => {
+        crudcrate::tree_handlers!(@openapi true; $resource, $list_model, $scoped_list);
+    };
+    (@openapi $openapi:tt; $resource:ty, $list_model:ty, $scoped_list:ty) => {
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            get,
+            path = "/{id}/children",
+            responses(
+                (status = axum::http::StatusCode::OK, description = "Children of the resource, ordered by level", body = [$list_model]),
+                (status = axum::http::StatusCode::NOT_FOUND, description = "Resource not found"),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Invalid depth"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            params(crudcrate::TreeOptions),
+            operation_id = format!("get_children_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Get the children of one {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!(
+                "Retrieves the children of one {}, or `depth` levels of descendants (at most {}).",
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR,
+                <$resource as crudcrate::CRUDResource>::tree_max_depth()
+            )
+        )]
+        pub async fn get_children_handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            crudcrate::PathId { id, .. }: crudcrate::PathId<$resource>,
+            axum::extract::Query(options): axum::extract::Query<crudcrate::TreeOptions>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            crudcrate::relationships::tree::children_response::<$resource, $scoped_list>(
+                &db,
+                id,
+                options,
+                scope.map(|axum::Extension(scope)| scope.condition),
+            )
+            .await
+        });
+
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            get,
+            path = "/{id}/ancestors",
+            responses(
+                (status = axum::http::StatusCode::OK, description = "Ancestors of the resource, nearest first", body = [$list_model]),
+                (status = axum::http::StatusCode::NOT_FOUND, description = "Resource not found"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            operation_id = format!("get_ancestors_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Get the ancestors of one {}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!(
+                "Retrieves the parent of one {}, its parent and so on up to the root (at most {} levels).",
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR,
+                <$resource as crudcrate::CRUDResource>::tree_max_depth()
+            )
+        )]
+        pub async fn get_ancestors_handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            crudcrate::PathId { id, .. }: crudcrate::PathId<$resource>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            crudcrate::relationships::tree::ancestors_response::<$resource, $scoped_list>(
+                &db,
+                id,
+                scope.map(|axum::Extension(scope)| scope.condition),
+            )
+            .await
+        });
+    };
+}
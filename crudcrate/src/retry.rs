@@ -0,0 +1,299 @@
+//! Retries of writes that lost a deadlock or serialization conflict.
+//!
+//! Under concurrent writes the database may abort one transaction to let another
+//! finish: `PostgreSQL` reports a deadlock or serialization failure, `MySQL` a deadlock,
+//! and `SQLite` a busy or locked database. Running the same write again usually
+//! succeeds. The generated create, update and delete methods run their default bodies
+//! under [`CRUDResource::retry_policy`](crate::CRUDResource::retry_policy), which
+//! retries those errors with jittered exponential backoff:
+//!
+//! ```rust,ignore
+//! #[crudcrate(generate_router, retry_attempts = 5)]
+//! pub struct Model { /* ... */ }
+//! ```
+//!
+//! Only the write is retried, up to its commit: a created row is read back afterwards,
+//! so an error reading it can't insert it twice. Hooks run once, outside the retries,
+//! and custom `body` hooks and `operations` are not retried; wrap their writes in
+//! [`RetryPolicy::run`] yourself.
+
+use std::future::Future;
+use std::time::Duration;
+
+use sea_orm::DbErr;
+
+use crate::errors::ApiError;
+
+/// How many times a write runs before its transient error is returned, and how long to
+/// wait between runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, waiting 5-10ms and then 10-20ms
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+impl RetryPolicy {
+    /// Run writes up to `attempts` times in all, waiting up to 10ms before the first
+    /// retry, doubling up to 500ms. `0` is treated as `1`.
+    #[must_use]
+    pub const fn new(attempts: u32) -> Self {
+        Self {
+            attempts: if attempts == 0 { 1 } else { attempts },
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(500),
+        }
+    }
+
+    /// Run writes once, returning transient errors as they are
+    #[must_use]
+    pub const fn never() -> Self {
+        Self::new(1)
+    }
+
+    /// Wait before the first retry, doubled for each further retry
+    #[must_use]
+    pub const fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Longest wait between two attempts
+    #[must_use]
+    pub const fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Most times a write runs, first attempt included
+    #[must_use]
+    pub const fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Wait before retry number `retry` (from 1): half of the capped exponential delay
+    /// plus a random part of the other half, so writes that collided don't retry in step
+    #[must_use]
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)));
+        let cap = exponential.min(self.max_delay);
+        let half = cap / 2;
+        let nanos = u64::try_from(cap.saturating_sub(half).as_nanos()).unwrap_or(u64::MAX);
+        half + Duration::from_nanos(jitter() % nanos.saturating_add(1))
+    }
+
+    /// Run the write `attempt` makes, running it again after a wait while it fails with
+    /// a [transient](is_transient) database error and attempts remain. `resource` and
+    /// `operation` label the retry log lines.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt, or the first error that isn't transient.
+    pub async fn run<T, F, Fut>(
+        &self,
+        resource: &str,
+        operation: &str,
+        attempt: F,
+    ) -> Result<T, ApiError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        self.run_with(resource, operation, attempt, |error| match error {
+            ApiError::Database { internal, .. } => is_transient(internal),
+            _ => false,
+        })
+        .await
+    }
+
+    async fn run_with<T, F, Fut>(
+        &self,
+        resource: &str,
+        operation: &str,
+        mut attempt: F,
+        transient: impl Fn(&ApiError) -> bool,
+    ) -> Result<T, ApiError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        let mut retry = 0;
+        loop {
+            match attempt().await {
+                Err(error) if retry + 1 < self.attempts && transient(&error) => {
+                    retry += 1;
+                    let delay = self.delay(retry);
+                    tracing::debug!(
+                        resource,
+                        operation,
+                        retry,
+                        delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+                        error = %error,
+                        "Retrying write after transient database error"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Whether `error` is a deadlock, serialization failure or lock conflict that running
+/// the write again may get past: SQLSTATE `40001` or `40P01` (`PostgreSQL`, `MySQL`) or
+/// `SQLITE_BUSY`/`SQLITE_LOCKED` and their extended codes
+#[must_use]
+pub fn is_transient(error: &DbErr) -> bool {
+    match error {
+        DbErr::Conn(runtime) | DbErr::Exec(runtime) | DbErr::Query(runtime) => {
+            is_transient_runtime(runtime)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgresql", feature = "mysql"))]
+fn is_transient_runtime(error: &sea_orm::RuntimeErr) -> bool {
+    match error {
+        sea_orm::RuntimeErr::SqlxError(error) => error
+            .as_database_error()
+            .and_then(sea_orm::sqlx::error::DatabaseError::code)
+            .is_some_and(|code| is_transient_code(&code)),
+        sea_orm::RuntimeErr::Internal(_) => false,
+    }
+}
+
+#[cfg(not(any(feature = "sqlite", feature = "postgresql", feature = "mysql")))]
+fn is_transient_runtime(_error: &sea_orm::RuntimeErr) -> bool {
+    false
+}
+
+/// SQLSTATEs of serialization failures and deadlocks, or `SQLite` result codes whose
+/// primary code is `SQLITE_BUSY` (5) or `SQLITE_LOCKED` (6)
+fn is_transient_code(code: &str) -> bool {
+    match code {
+        "40001" | "40P01" => true,
+        // SQLSTATEs have five characters, `SQLite` codes at most four digits
+        code => {
+            code.len() < 5
+                && code
+                    .parse::<u32>()
+                    .is_ok_and(|code| matches!(code & 0xff, 5 | 6))
+        }
+    }
+}
+
+/// Random bits for backoff jitter
+fn jitter() -> u64 {
+    uuid::Uuid::new_v4().as_u64_pair().1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn conflict() -> ApiError {
+        ApiError::Database {
+            message: "A database error occurred".to_string(),
+            internal: DbErr::Custom("deadlock".to_string()),
+        }
+    }
+
+    fn is_conflict(error: &ApiError) -> bool {
+        matches!(error, ApiError::Database { internal: DbErr::Custom(message), .. } if message == "deadlock")
+    }
+
+    #[test]
+    fn test_transient_codes() {
+        for code in ["40001", "40P01", "5", "6", "261", "517", "773", "262"] {
+            assert!(is_transient_code(code), "{code}");
+        }
+        for code in ["23505", "19", "2067", "1", "40000", "42P01", "00005", ""] {
+            assert!(!is_transient_code(code), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_other_errors_are_not_transient() {
+        assert!(!is_transient(&DbErr::RecordNotFound("row".to_string())));
+        assert!(!is_transient(&DbErr::Custom("40001".to_string())));
+        assert!(!is_transient(&DbErr::Exec(sea_orm::RuntimeErr::Internal(
+            "40001".to_string()
+        ))));
+    }
+
+    #[test]
+    fn test_delay_is_jittered_exponential_and_capped() {
+        let policy = RetryPolicy::new(10)
+            .base_delay(Duration::from_millis(10))
+            .max_delay(Duration::from_millis(50));
+        for (retry, cap) in [(1, 10), (2, 20), (3, 40), (4, 50), (9, 50), (40, 50)] {
+            let cap = Duration::from_millis(cap);
+            for _ in 0..20 {
+                let delay = policy.delay(retry);
+                assert!(delay >= cap / 2 && delay <= cap, "{retry}: {delay:?}");
+            }
+        }
+        assert_eq!(RetryPolicy::new(0).attempts(), 1);
+        assert_eq!(RetryPolicy::never(), RetryPolicy::new(1));
+    }
+
+    #[tokio::test]
+    async fn test_run_retries_transient_errors() {
+        let policy = RetryPolicy::new(3).base_delay(Duration::from_millis(1));
+        let calls = AtomicU32::new(0);
+        let result = policy
+            .run_with(
+                "item",
+                "update",
+                || async {
+                    match calls.fetch_add(1, Ordering::SeqCst) {
+                        0 | 1 => Err(conflict()),
+                        n => Ok(n),
+                    }
+                },
+                is_conflict,
+            )
+            .await;
+        assert_eq!(result.unwrap(), 2);
+
+        // Attempts run out
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = policy
+            .run_with(
+                "item",
+                "update",
+                || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Err(conflict())
+                },
+                is_conflict,
+            )
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_run_returns_other_errors_at_once() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = RetryPolicy::default()
+            .run("item", "create", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(conflict())
+            })
+            .await;
+        assert!(matches!(result, Err(ApiError::Database { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
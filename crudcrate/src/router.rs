@@ -0,0 +1,230 @@
+//! Mounting several generated routers under one `OpenApiRouter`.
+//!
+//! Axum panics when two nested routers register the same route, for example when a
+//! resource is mounted twice or two resources share a prefix. [`merge_resources`]
+//! checks every documented route first and returns a [`RouteConflict`] naming the
+//! clashing path instead:
+//!
+//! ```rust,ignore
+//! let api = crudcrate::router::merge_resources(&[
+//!     ("/users", User::router(&db)),
+//!     ("/posts", Post::router(&db)),
+//! ])?;
+//! let (router, openapi) = api.split_for_parts();
+//! ```
+//!
+//! [`with_security`] marks a router's operations as requiring a security scheme, as
+//! generated for `#[crudcrate(security = "bearer")]`.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt;
+
+use utoipa::openapi::PathItem;
+use utoipa::openapi::path::Operation;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme};
+use utoipa_axum::router::OpenApiRouter;
+
+/// Two mounted resources register the same route
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteConflict {
+    /// The route as mounted, e.g. `/users/{id}`
+    pub path: String,
+    /// The method both resources handle, or `None` when the paths only differ in
+    /// parameter names (`/{id}` and `/{name}`), which axum can't tell apart
+    pub method: Option<&'static str>,
+    /// Prefix of the resource that registered the route first
+    pub first: String,
+    /// Prefix of the resource that registered it again
+    pub second: String,
+}
+
+impl fmt::Display for RouteConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let route = match self.method {
+            Some(method) => format!("{method} {}", self.path),
+            None => self.path.clone(),
+        };
+        write!(
+            f,
+            "Route conflict: `{route}` is registered by the resources mounted at `{}` and `{}`",
+            self.first, self.second
+        )
+    }
+}
+
+impl std::error::Error for RouteConflict {}
+
+/// A route registered so far: its path as written, who registered it, and its methods
+struct Registered {
+    path: String,
+    prefix: String,
+    methods: Vec<&'static str>,
+}
+
+/// Nest each router under its prefix (`"/"` merges at the root) and return the combined
+/// router with its combined `OpenAPI` document.
+///
+/// # Errors
+/// Returns a [`RouteConflict`] if two resources register the same method on the same
+/// path, or paths that differ only in parameter names. Routes without `OpenAPI`
+/// documentation are not checked.
+pub fn merge_resources(
+    resources: &[(&str, OpenApiRouter)],
+) -> Result<OpenApiRouter, RouteConflict> {
+    let mut registered: HashMap<String, Registered> = HashMap::new();
+    for (prefix, router) in resources {
+        for (path, item) in &router.get_openapi().paths.paths {
+            let path = mounted_path(prefix, path);
+            let methods = item_methods(item);
+            match registered.entry(route_shape(&path)) {
+                Entry::Vacant(entry) => {
+                    entry.insert(Registered {
+                        path,
+                        prefix: (*prefix).to_string(),
+                        methods,
+                    });
+                }
+                Entry::Occupied(mut entry) => {
+                    let existing = entry.get_mut();
+                    let overlap = methods
+                        .iter()
+                        .copied()
+                        .find(|method| existing.methods.contains(method));
+                    if existing.path != path || overlap.is_some() {
+                        return Err(RouteConflict {
+                            method: overlap,
+                            path,
+                            first: existing.prefix.clone(),
+                            second: (*prefix).to_string(),
+                        });
+                    }
+                    existing.methods.extend(methods);
+                }
+            }
+        }
+    }
+
+    Ok(resources
+        .iter()
+        .fold(OpenApiRouter::new(), |merged, (prefix, router)| {
+            if prefix.trim_end_matches('/').is_empty() {
+                merged.merge(router.clone())
+            } else {
+                merged.nest(prefix, router.clone())
+            }
+        }))
+}
+
+/// Require the security scheme named `scheme` on every documented operation of `router`.
+///
+/// `bearer` (an HTTP bearer token) and `basic` (HTTP basic credentials) are also added to
+/// the document's security schemes, so the "Authorize" button of Scalar or Swagger UI
+/// works without further setup. Other names must be registered by the application, for
+/// example with a utoipa `Modify`. Only the documentation changes; authentication itself
+/// is left to middleware.
+#[must_use]
+pub fn with_security<S>(mut router: OpenApiRouter<S>, scheme: &str) -> OpenApiRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let openapi = router.get_openapi_mut();
+    for item in openapi.paths.paths.values_mut() {
+        for operation in operations_mut(item) {
+            operation
+                .security
+                .get_or_insert_with(Vec::new)
+                .push(SecurityRequirement::new(scheme, Vec::<String>::new()));
+        }
+    }
+    let known = match scheme {
+        "bearer" => Some(HttpAuthScheme::Bearer),
+        "basic" => Some(HttpAuthScheme::Basic),
+        _ => None,
+    };
+    if let Some(http_scheme) = known {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                scheme,
+                SecurityScheme::Http(HttpBuilder::new().scheme(http_scheme).build()),
+            );
+    }
+    router
+}
+
+/// `path` of a router nested at `prefix`, as axum serves it
+fn mounted_path(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    match path {
+        "/" if !prefix.is_empty() => prefix.to_string(),
+        _ => format!("{prefix}{path}"),
+    }
+}
+
+/// `path` with parameter names erased, so `/{id}` and `/{name}` compare equal
+fn route_shape(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if segment.starts_with('{') && segment.ends_with('}') {
+                "{}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Every operation documented on `item`
+pub(crate) fn operations_mut(item: &mut PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        &mut item.get,
+        &mut item.put,
+        &mut item.post,
+        &mut item.delete,
+        &mut item.options,
+        &mut item.head,
+        &mut item.patch,
+        &mut item.trace,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+fn item_methods(item: &PathItem) -> Vec<&'static str> {
+    [
+        ("GET", &item.get),
+        ("PUT", &item.put),
+        ("POST", &item.post),
+        ("DELETE", &item.delete),
+        ("OPTIONS", &item.options),
+        ("HEAD", &item.head),
+        ("PATCH", &item.patch),
+        ("TRACE", &item.trace),
+    ]
+    .into_iter()
+    .filter_map(|(method, operation)| operation.is_some().then_some(method))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mounted_path() {
+        assert_eq!(mounted_path("/users", "/"), "/users");
+        assert_eq!(mounted_path("/users/", "/{id}"), "/users/{id}");
+        assert_eq!(mounted_path("/", "/"), "/");
+        assert_eq!(mounted_path("", "/{id}"), "/{id}");
+    }
+
+    #[test]
+    fn test_route_shape() {
+        assert_eq!(route_shape("/users/{id}"), route_shape("/users/{name}"));
+        assert_ne!(route_shape("/users/{id}"), route_shape("/users/batch"));
+        assert_eq!(route_shape("/"), "/");
+    }
+}
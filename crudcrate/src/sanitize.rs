@@ -0,0 +1,101 @@
+//! String sanitizers for `#[crudcrate(sanitize(...))]` and `sanitize_with = fn`.
+//!
+//! Sanitizers run in the generated `From<Create>` and `MergeIntoActiveModel` conversions,
+//! before the value reaches the `ActiveModel`, so normalisation doesn't need a pre hook
+//! on every resource:
+//!
+//! ```rust,ignore
+//! #[crudcrate(filterable, sanitize(trim, lowercase))]
+//! pub email: String,
+//!
+//! #[crudcrate(sanitize(trim), sanitize_with = strip_tags)]
+//! pub bio: Option<String>,
+//! ```
+//!
+//! Named sanitizers are the functions of this module; `sanitize_with` takes any
+//! `fn(String) -> String`. They are applied in the order they are written, and only to
+//! values the request provides: `null` and omitted fields are left alone.
+
+/// A create or update model field that sanitizers can be applied to: `String` and
+/// `Option`s of it, such as the `Option<Option<String>>` fields of update models
+pub trait Sanitize {
+    /// Apply `sanitizer` to the string, if there is one
+    #[must_use]
+    fn sanitize<F: Fn(String) -> String>(self, sanitizer: &F) -> Self;
+}
+
+impl Sanitize for String {
+    fn sanitize<F: Fn(String) -> String>(self, sanitizer: &F) -> Self {
+        sanitizer(self)
+    }
+}
+
+impl<T: Sanitize> Sanitize for Option<T> {
+    fn sanitize<F: Fn(String) -> String>(self, sanitizer: &F) -> Self {
+        self.map(|value| value.sanitize(sanitizer))
+    }
+}
+
+/// Remove leading and trailing whitespace
+#[must_use]
+pub fn trim(value: String) -> String {
+    let trimmed = value.trim();
+    if trimmed.len() == value.len() {
+        value
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Lowercase the string (Unicode aware)
+#[must_use]
+pub fn lowercase(value: String) -> String {
+    if value.chars().any(char::is_uppercase) {
+        value.to_lowercase()
+    } else {
+        value
+    }
+}
+
+/// Uppercase the string (Unicode aware)
+#[must_use]
+pub fn uppercase(value: String) -> String {
+    if value.chars().any(char::is_lowercase) {
+        value.to_uppercase()
+    } else {
+        value
+    }
+}
+
+/// Replace every run of whitespace with a single space, and trim the ends
+#[must_use]
+pub fn collapse_whitespace(value: String) -> String {
+    let collapsed = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed == value { value } else { collapsed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitizers() {
+        assert_eq!(trim("  Ada \n".to_string()), "Ada");
+        assert_eq!(lowercase("Ada@Example.COM".to_string()), "ada@example.com");
+        assert_eq!(uppercase("straße".to_string()), "STRASSE");
+        assert_eq!(
+            collapse_whitespace(" Ada \t Lovelace\n".to_string()),
+            "Ada Lovelace"
+        );
+    }
+
+    #[test]
+    fn test_options_are_sanitized_inside() {
+        assert_eq!(
+            Some(Some(" a ".to_string())).sanitize(&trim),
+            Some(Some("a".to_string()))
+        );
+        assert_eq!(Some(None::<String>).sanitize(&trim), Some(None));
+        assert_eq!(None::<String>.sanitize(&trim), None);
+    }
+}
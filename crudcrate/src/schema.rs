@@ -0,0 +1,412 @@
+//! Startup checks that a resource's table matches its entity.
+//!
+//! Run [`verify`] before serving traffic to catch drift between migrations and the
+//! annotated entity, for example while blue/green deployments share a database:
+//!
+//! ```rust,ignore
+//! crudcrate::schema::verify::<Todo>(&db).await?;
+//! ```
+//!
+//! Every entity column must exist with a compatible type and the same nullability, and
+//! the table may not have extra `NOT NULL` columns without a default, which would make
+//! every create fail. Types are compared by kind (text, integer, timestamp, ...), not
+//! width, and allow each backend's storage: `SQLite` keeps UUIDs, dates and JSON as
+//! text, `MySQL` keeps UUIDs as `BINARY(16)` and booleans as `TINYINT`. Columns of
+//! custom types are only checked for presence and nullability.
+
+use std::fmt;
+
+use sea_orm::sea_query::ColumnType;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseBackend, DbErr, EntityTrait, IdenStatic, Iterable,
+    Statement,
+};
+
+use crate::core::traits::CRUDResource;
+
+/// One difference between an entity and its table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The table doesn't exist
+    MissingTable,
+    /// An entity column is missing from the table
+    MissingColumn { column: String },
+    /// The column's type can't hold the entity's values
+    Type {
+        column: String,
+        /// Kind of type the entity declares, e.g. `"integer"`
+        expected: &'static str,
+        /// Type reported by the database, e.g. `"text"`
+        found: String,
+    },
+    /// The entity field is `Option<_>` and the column `NOT NULL`, or the other way round
+    Nullability {
+        column: String,
+        expected_nullable: bool,
+    },
+    /// A `NOT NULL` column without a default that the entity doesn't know about
+    UnexpectedRequiredColumn { column: String },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTable => write!(f, "the table doesn't exist"),
+            Self::MissingColumn { column } => write!(f, "column `{column}` is missing"),
+            Self::Type {
+                column,
+                expected,
+                found,
+            } => write!(f, "column `{column}` is `{found}`, expected {expected}"),
+            Self::Nullability {
+                column,
+                expected_nullable: true,
+            } => write!(f, "column `{column}` is NOT NULL but the field is optional"),
+            Self::Nullability { column, .. } => {
+                write!(f, "column `{column}` is nullable but the field is required")
+            }
+            Self::UnexpectedRequiredColumn { column } => write!(
+                f,
+                "column `{column}` is NOT NULL without a default and not on the entity"
+            ),
+        }
+    }
+}
+
+/// Why [`verify`] failed
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The table's columns could not be read
+    Database(DbErr),
+    /// The table doesn't match the entity
+    Drift {
+        table: &'static str,
+        mismatches: Vec<Mismatch>,
+    },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Database(error) => write!(f, "Cannot read the table schema: {error}"),
+            Self::Drift { table, mismatches } => {
+                write!(f, "Table `{table}` doesn't match its entity: ")?;
+                for (index, mismatch) in mismatches.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{mismatch}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Database(error) => Some(error),
+            Self::Drift { .. } => None,
+        }
+    }
+}
+
+impl From<DbErr> for SchemaError {
+    fn from(error: DbErr) -> Self {
+        Self::Database(error)
+    }
+}
+
+/// Check that `T`'s table matches its entity.
+///
+/// # Errors
+/// Returns [`SchemaError::Drift`] listing every [`Mismatch`], or
+/// [`SchemaError::Database`] if the columns can't be read.
+pub async fn verify<T: CRUDResource>(db: &impl ConnectionTrait) -> Result<(), SchemaError> {
+    let mismatches = compare::<T>(db).await?;
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    for mismatch in &mismatches {
+        tracing::error!(table = T::TABLE_NAME, %mismatch, "Schema drift");
+    }
+    Err(SchemaError::Drift {
+        table: T::TABLE_NAME,
+        mismatches,
+    })
+}
+
+/// Every difference between `T`'s entity and its table, empty when they match.
+///
+/// # Errors
+/// Returns a `DbErr` if the table's columns can't be read.
+pub async fn compare<T: CRUDResource>(db: &impl ConnectionTrait) -> Result<Vec<Mismatch>, DbErr> {
+    let backend = db.get_database_backend();
+    let live = live_columns(db, T::TABLE_NAME).await?;
+    if live.is_empty() {
+        return Ok(vec![Mismatch::MissingTable]);
+    }
+
+    let mut mismatches = Vec::new();
+    let mut declared = Vec::new();
+    for column in <T::EntityType as EntityTrait>::Column::iter() {
+        let name = column.as_str().to_string();
+        let def = column.def();
+        let Some(found) = live.iter().find(|live| live.name == name) else {
+            mismatches.push(Mismatch::MissingColumn { column: name });
+            continue;
+        };
+        let expected = TypeKind::of(def.get_column_type());
+        let found_kind = TypeKind::parse(&found.data_type);
+        if !compatible(expected, found_kind, backend) {
+            mismatches.push(Mismatch::Type {
+                column: name.clone(),
+                expected: expected.name(),
+                found: found.data_type.clone(),
+            });
+        }
+        if def.is_null() != found.nullable {
+            mismatches.push(Mismatch::Nullability {
+                column: name.clone(),
+                expected_nullable: def.is_null(),
+            });
+        }
+        declared.push(name);
+    }
+    for column in &live {
+        if !column.nullable && !column.has_default && !declared.contains(&column.name) {
+            mismatches.push(Mismatch::UnexpectedRequiredColumn {
+                column: column.name.clone(),
+            });
+        }
+    }
+    Ok(mismatches)
+}
+
+/// A column as the database describes it
+struct LiveColumn {
+    name: String,
+    data_type: String,
+    nullable: bool,
+    has_default: bool,
+}
+
+async fn live_columns(db: &impl ConnectionTrait, table: &str) -> Result<Vec<LiveColumn>, DbErr> {
+    let backend = db.get_database_backend();
+    // Columns: name, type, nullable (0/1), has_default (0/1)
+    let sql = match backend {
+        // `pk` columns are NOT NULL even when not declared so
+        DatabaseBackend::Sqlite => {
+            "SELECT name, type, CAST(\"notnull\" = 0 AND pk = 0 AS INTEGER), \
+             CAST(dflt_value IS NOT NULL AS INTEGER) FROM pragma_table_info(?)"
+        }
+        DatabaseBackend::Postgres => {
+            "SELECT column_name::text, \
+             CASE WHEN data_type = 'USER-DEFINED' THEN udt_name::text ELSE data_type::text END, \
+             (is_nullable = 'YES')::int::bigint, \
+             (column_default IS NOT NULL OR is_identity = 'YES' OR is_generated = 'ALWAYS')::int::bigint \
+             FROM information_schema.columns \
+             WHERE table_schema = ANY(current_schemas(false)) AND table_name = $1"
+        }
+        DatabaseBackend::MySql => {
+            "SELECT CAST(column_name AS CHAR), CAST(data_type AS CHAR), \
+             CAST(is_nullable = 'YES' AS SIGNED), \
+             CAST(column_default IS NOT NULL OR extra LIKE '%auto_increment%' \
+             OR extra LIKE '%GENERATED%' AS SIGNED) \
+             FROM information_schema.columns \
+             WHERE table_schema = DATABASE() AND table_name = ?"
+        }
+    };
+    let rows = db
+        .query_all(Statement::from_sql_and_values(backend, sql, [table.into()]))
+        .await?;
+    rows.iter()
+        .map(|row| {
+            Ok(LiveColumn {
+                name: row.try_get_by_index(0)?,
+                data_type: row.try_get_by_index::<String>(1)?.to_lowercase(),
+                nullable: row.try_get_by_index::<i64>(2)? != 0,
+                has_default: row.try_get_by_index::<i64>(3)? != 0,
+            })
+        })
+        .collect()
+}
+
+/// What kind of values a column type holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeKind {
+    Text,
+    Integer,
+    Float,
+    Decimal,
+    Boolean,
+    Timestamp,
+    Date,
+    Time,
+    Uuid,
+    Json,
+    Binary,
+    Enum,
+    Array,
+    /// Custom or unrecognized types, never reported
+    Other,
+}
+
+impl TypeKind {
+    fn of(column_type: &ColumnType) -> Self {
+        match column_type {
+            ColumnType::Char(_) | ColumnType::String(_) | ColumnType::Text => Self::Text,
+            ColumnType::TinyInteger
+            | ColumnType::SmallInteger
+            | ColumnType::Integer
+            | ColumnType::BigInteger
+            | ColumnType::TinyUnsigned
+            | ColumnType::SmallUnsigned
+            | ColumnType::Unsigned
+            | ColumnType::BigUnsigned => Self::Integer,
+            ColumnType::Float | ColumnType::Double => Self::Float,
+            ColumnType::Decimal(_) | ColumnType::Money(_) => Self::Decimal,
+            ColumnType::Boolean => Self::Boolean,
+            ColumnType::DateTime | ColumnType::Timestamp | ColumnType::TimestampWithTimeZone => {
+                Self::Timestamp
+            }
+            ColumnType::Date => Self::Date,
+            ColumnType::Time => Self::Time,
+            ColumnType::Uuid => Self::Uuid,
+            ColumnType::Json | ColumnType::JsonBinary => Self::Json,
+            ColumnType::Binary(_) | ColumnType::VarBinary(_) | ColumnType::Blob => Self::Binary,
+            ColumnType::Enum { .. } => Self::Enum,
+            ColumnType::Array(_) => Self::Array,
+            _ => Self::Other,
+        }
+    }
+
+    /// Kind of a type name reported by the database (lowercase)
+    fn parse(data_type: &str) -> Self {
+        let has = |part: &str| data_type.contains(part);
+        if has("uuid") {
+            Self::Uuid
+        } else if data_type == "array" || data_type.ends_with("[]") {
+            Self::Array
+        } else if has("json") {
+            Self::Json
+        } else if has("timestamp") || has("datetime") {
+            Self::Timestamp
+        } else if has("date") {
+            Self::Date
+        } else if has("time") {
+            Self::Time
+        } else if has("bool") {
+            Self::Boolean
+        } else if data_type == "enum" {
+            Self::Enum
+        } else if has("int") {
+            Self::Integer
+        } else if has("decimal") || has("numeric") || has("money") {
+            Self::Decimal
+        } else if has("real") || has("floa") || has("doub") {
+            Self::Float
+        } else if has("char") || has("text") || has("clob") || has("string") {
+            Self::Text
+        } else if has("blob") || has("binary") || has("bytea") {
+            Self::Binary
+        } else {
+            Self::Other
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Decimal => "decimal",
+            Self::Boolean => "boolean",
+            Self::Timestamp => "timestamp",
+            Self::Date => "date",
+            Self::Time => "time",
+            Self::Uuid => "uuid",
+            Self::Json => "json",
+            Self::Binary => "binary",
+            Self::Enum => "enum",
+            Self::Array => "array",
+            Self::Other => "any type",
+        }
+    }
+}
+
+/// Whether a column of kind `found` holds the values of an entity column of kind `expected`
+fn compatible(expected: TypeKind, found: TypeKind, backend: DatabaseBackend) -> bool {
+    use TypeKind::{
+        Binary, Boolean, Date, Decimal, Enum, Float, Integer, Json, Other, Text, Time, Timestamp,
+        Uuid,
+    };
+    if expected == found || expected == Other || found == Other {
+        return true;
+    }
+    matches!(
+        (backend, expected, found),
+        // Enums are text columns, or native enum types (reported by name on PostgreSQL)
+        (_, Enum, Text)
+            | (DatabaseBackend::Postgres, Enum, _)
+            | (
+                DatabaseBackend::Sqlite,
+                Uuid | Timestamp | Date | Time | Json | Decimal,
+                Text
+            )
+            | (DatabaseBackend::Sqlite, Decimal, Float)
+            | (
+                DatabaseBackend::Sqlite | DatabaseBackend::MySql,
+                Boolean,
+                Integer
+            )
+            | (
+                DatabaseBackend::Sqlite | DatabaseBackend::MySql,
+                Uuid,
+                Binary
+            )
+            | (DatabaseBackend::MySql, Uuid | Json, Text)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_type_names() {
+        assert_eq!(TypeKind::parse("uuid_text"), TypeKind::Uuid);
+        assert_eq!(
+            TypeKind::parse("timestamp_with_timezone_text"),
+            TypeKind::Timestamp
+        );
+        assert_eq!(
+            TypeKind::parse("timestamp with time zone"),
+            TypeKind::Timestamp
+        );
+        assert_eq!(TypeKind::parse("date_text"), TypeKind::Date);
+        assert_eq!(TypeKind::parse("time without time zone"), TypeKind::Time);
+        assert_eq!(TypeKind::parse("character varying"), TypeKind::Text);
+        assert_eq!(TypeKind::parse("bigint"), TypeKind::Integer);
+        assert_eq!(TypeKind::parse("double precision"), TypeKind::Float);
+        assert_eq!(TypeKind::parse("numeric"), TypeKind::Decimal);
+        assert_eq!(TypeKind::parse("jsonb"), TypeKind::Json);
+        assert_eq!(TypeKind::parse("array"), TypeKind::Array);
+        assert_eq!(TypeKind::parse("geometry"), TypeKind::Other);
+    }
+
+    #[test]
+    fn test_backend_storage_is_compatible() {
+        let sqlite = DatabaseBackend::Sqlite;
+        let mysql = DatabaseBackend::MySql;
+        let postgres = DatabaseBackend::Postgres;
+        assert!(compatible(TypeKind::Uuid, TypeKind::Text, sqlite));
+        assert!(compatible(TypeKind::Boolean, TypeKind::Integer, mysql));
+        assert!(compatible(TypeKind::Uuid, TypeKind::Binary, mysql));
+        assert!(compatible(TypeKind::Enum, TypeKind::Other, postgres));
+        assert!(!compatible(TypeKind::Uuid, TypeKind::Text, postgres));
+        assert!(!compatible(TypeKind::Integer, TypeKind::Text, sqlite));
+        assert!(!compatible(TypeKind::Boolean, TypeKind::Integer, postgres));
+    }
+}
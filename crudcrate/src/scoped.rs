@@ -0,0 +1,107 @@
+//! Values scoped to a future, read by the code it calls without being passed down.
+//!
+//! [`Scoped`] sets a thread-local slot to its value while the future it wraps is polled
+//! and puts the previous value back after every poll, so scopes nest and never leak
+//! into other futures polled on the same thread. [`telemetry`](crate::telemetry) keeps
+//! the request's trace context and ID in one, [`state`](crate::state) the application
+//! state of `router_with_state`.
+//!
+//! The value only exists inside the polls of the wrapped future. Work that runs outside
+//! them sees the slot's default (`None` from the public readers):
+//!
+//! - tasks started with `tokio::spawn`, which are polled by the runtime on their own
+//! - closures run with `spawn_blocking` or on other threads
+//! - futures stored and polled later, such as jobs pushed to a queue
+//!
+//! Read the value before handing the work off and scope it again there, with the
+//! `with_*` function it came from:
+//!
+//! ```rust,ignore
+//! let state = crudcrate::app_state::<AppState>();
+//! let request_id = crudcrate::telemetry::request_id();
+//! tokio::spawn(crudcrate::telemetry::with_request_id(request_id, async move {
+//!     notify(state).await
+//! }));
+//! ```
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread::LocalKey;
+
+/// Thread-local slot holding the current scope's value
+pub(crate) type Slot<T> = LocalKey<RefCell<T>>;
+
+/// `future`, polled with `slot` set to `value`
+pub(crate) struct Scoped<T: 'static, F> {
+    slot: &'static Slot<T>,
+    value: T,
+    future: Pin<Box<F>>,
+}
+
+impl<T: 'static, F> Scoped<T, F> {
+    pub(crate) fn new(slot: &'static Slot<T>, value: T, future: F) -> Self {
+        Self {
+            slot,
+            value,
+            future: Box::pin(future),
+        }
+    }
+}
+
+// The value is never pinned, only cloned into the slot
+impl<T: 'static, F> Unpin for Scoped<T, F> {}
+
+impl<T: Clone + 'static, F: Future> Future for Scoped<T, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let this = self.get_mut();
+        let previous = this.slot.with(|slot| slot.replace(this.value.clone()));
+        // Restores the outer value even if the future panics
+        let _restore = Restore {
+            slot: this.slot,
+            previous: Some(previous),
+        };
+        this.future.as_mut().poll(cx)
+    }
+}
+
+struct Restore<T: 'static> {
+    slot: &'static Slot<T>,
+    previous: Option<T>,
+}
+
+impl<T: 'static> Drop for Restore<T> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            self.slot.with(|slot| *slot.borrow_mut() = previous);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    thread_local! {
+        static VALUE: RefCell<Option<u32>> = const { RefCell::new(None) };
+    }
+
+    fn current() -> Option<u32> {
+        VALUE.with(|value| *value.borrow())
+    }
+
+    #[tokio::test]
+    async fn test_spawned_tasks_are_outside_the_scope() {
+        let (inside, spawned) = Scoped::new(&VALUE, Some(1), async {
+            let spawned = tokio::spawn(async { current() }).await.unwrap();
+            (current(), spawned)
+        })
+        .await;
+        assert_eq!(inside, Some(1));
+        assert_eq!(spawned, None);
+        assert_eq!(current(), None);
+    }
+}
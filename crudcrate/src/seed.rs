@@ -0,0 +1,366 @@
+//! Seed data loaded through the generated create models.
+//!
+//! A seed file holds an array of `{Resource}Create` objects, exactly as a `POST` to
+//! the batch endpoint would take them. Each row goes through
+//! [`CRUDResource::create`], so `on_create` defaults, `id_generator`s and create hooks
+//! apply:
+//!
+//! ```rust,ignore
+//! let todos: Vec<Todo> = crudcrate::seed::load_json::<Todo>(&db, "seeds/todos.json").await?;
+//! ```
+//!
+//! [`SeedManifest`] loads several resources in dependency order. A resource is loaded
+//! after the resources holding it in a `Vec` join (see
+//! [`RelationMetadata`](crate::registry::RelationMetadata)) and after the ones named
+//! with [`SeedManifest::resource_after`]:
+//!
+//! ```rust,ignore
+//! SeedManifest::new()
+//!     .resource::<Vehicle>("seeds/vehicles.json")
+//!     .resource::<Customer>("seeds/customers.json")
+//!     .load(&db)
+//!     .await?;
+//! ```
+//!
+//! To reference seeded rows from other seed files, accept the primary key on create
+//! (`#[crudcrate(primary_key, exclude(update), on_create = Uuid::new_v4())]`) and give
+//! the referenced rows fixed ids; rows without one still get a generated id.
+//!
+//! Rows are created one at a time. A failing row stops the load and leaves the rows
+//! created before it in place. Files ending in `.yaml` or `.yml` are read as YAML with
+//! the `yaml` feature.
+
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+use sea_orm::DatabaseConnection;
+use serde::de::DeserializeOwned;
+
+use crate::ApiError;
+use crate::core::traits::CRUDResource;
+use crate::registry::Cardinality;
+
+/// Why seed data could not be loaded
+#[derive(Debug)]
+pub enum SeedError {
+    /// The seed file could not be read
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The seed file is not an array of the resource's create model
+    Parse { path: PathBuf, message: String },
+    /// Creating a row failed; `index` is its position in the file
+    Create {
+        resource: &'static str,
+        index: usize,
+        error: ApiError,
+    },
+    /// A manifest dependency names a resource the manifest doesn't load
+    UnknownDependency {
+        resource: &'static str,
+        dependency: String,
+    },
+    /// The manifest's dependencies form a cycle through these resources
+    Cycle { resources: Vec<&'static str> },
+}
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read { path, source } => {
+                write!(f, "Cannot read seed file {}: {source}", path.display())
+            }
+            Self::Parse { path, message } => {
+                write!(f, "Invalid seed file {}: {message}", path.display())
+            }
+            Self::Create {
+                resource,
+                index,
+                error,
+            } => write!(f, "Seeding {resource} failed at row {index}: {error}"),
+            Self::UnknownDependency {
+                resource,
+                dependency,
+            } => write!(
+                f,
+                "Seed manifest: {resource} depends on {dependency}, which it doesn't load"
+            ),
+            Self::Cycle { resources } => write!(
+                f,
+                "Seed manifest: dependency cycle between {}",
+                resources.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SeedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read { source, .. } => Some(source),
+            Self::Create { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Create every `T` in the JSON array at `path`, returning them in file order.
+///
+/// # Errors
+/// Returns a [`SeedError`] if the file can't be read or parsed, or a row can't be created.
+pub async fn load_json<T>(
+    db: &DatabaseConnection,
+    path: impl AsRef<Path>,
+) -> Result<Vec<T>, SeedError>
+where
+    T: CRUDResource,
+    T::CreateModel: DeserializeOwned,
+{
+    let path = path.as_ref();
+    let rows = serde_json::from_str(&read(path)?).map_err(|error| SeedError::Parse {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    })?;
+    create_all::<T>(db, rows).await
+}
+
+/// Create every `T` in the YAML sequence at `path`, returning them in file order.
+///
+/// # Errors
+/// Returns a [`SeedError`] if the file can't be read or parsed, or a row can't be created.
+#[cfg(feature = "yaml")]
+pub async fn load_yaml<T>(
+    db: &DatabaseConnection,
+    path: impl AsRef<Path>,
+) -> Result<Vec<T>, SeedError>
+where
+    T: CRUDResource,
+    T::CreateModel: DeserializeOwned,
+{
+    let path = path.as_ref();
+    let rows = serde_yaml::from_str(&read(path)?).map_err(|error| SeedError::Parse {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    })?;
+    create_all::<T>(db, rows).await
+}
+
+/// [`load_yaml`] for `.yaml` and `.yml` files, [`load_json`] otherwise.
+///
+/// # Errors
+/// Returns a [`SeedError`] if the file can't be read or parsed, or a row can't be
+/// created. YAML files are a parse error without the `yaml` feature.
+pub async fn load<T>(db: &DatabaseConnection, path: impl AsRef<Path>) -> Result<Vec<T>, SeedError>
+where
+    T: CRUDResource,
+    T::CreateModel: DeserializeOwned,
+{
+    let path = path.as_ref();
+    let yaml = path
+        .extension()
+        .is_some_and(|extension| extension == "yaml" || extension == "yml");
+    if !yaml {
+        return load_json::<T>(db, path).await;
+    }
+    #[cfg(feature = "yaml")]
+    {
+        load_yaml::<T>(db, path).await
+    }
+    #[cfg(not(feature = "yaml"))]
+    {
+        Err(SeedError::Parse {
+            path: path.to_path_buf(),
+            message: "YAML seed files need the `yaml` feature".to_string(),
+        })
+    }
+}
+
+fn read(path: &Path) -> Result<String, SeedError> {
+    std::fs::read_to_string(path).map_err(|source| SeedError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+async fn create_all<T: CRUDResource>(
+    db: &DatabaseConnection,
+    rows: Vec<T::CreateModel>,
+) -> Result<Vec<T>, SeedError> {
+    let mut created = Vec::with_capacity(rows.len());
+    for (index, row) in rows.into_iter().enumerate() {
+        let item = T::create(db, row)
+            .await
+            .map_err(|error| SeedError::Create {
+                resource: T::RESOURCE_NAME_PLURAL,
+                index,
+                error,
+            })?;
+        created.push(item);
+    }
+    tracing::info!(
+        resource = T::RESOURCE_NAME_PLURAL,
+        rows = created.len(),
+        "Seeded"
+    );
+    Ok(created)
+}
+
+type LoadFuture<'a> = Pin<Box<dyn Future<Output = Result<usize, SeedError>> + Send + 'a>>;
+
+/// One resource of a [`SeedManifest`]
+struct SeedEntry {
+    resource: &'static str,
+    /// API struct name, the `target` of relations pointing at this resource
+    api_struct: &'static str,
+    path: PathBuf,
+    /// Plural names of the resources loaded first
+    after: Vec<String>,
+    /// Targets of this resource's `Vec` joins, loaded after it
+    children: Vec<&'static str>,
+    load: for<'a> fn(&'a DatabaseConnection, &'a Path) -> LoadFuture<'a>,
+}
+
+fn load_count<'a, T>(db: &'a DatabaseConnection, path: &'a Path) -> LoadFuture<'a>
+where
+    T: CRUDResource + 'static,
+    T::CreateModel: DeserializeOwned,
+{
+    Box::pin(async move { Ok(load::<T>(db, path).await?.len()) })
+}
+
+/// Seed files for several resources, loaded in dependency order
+#[derive(Default)]
+pub struct SeedManifest {
+    entries: Vec<SeedEntry>,
+}
+
+impl SeedManifest {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load `T` from `path`, after the resources that hold `T` in a `Vec` join
+    #[must_use]
+    pub fn resource<T>(self, path: impl Into<PathBuf>) -> Self
+    where
+        T: CRUDResource + 'static,
+        T::CreateModel: DeserializeOwned,
+    {
+        self.resource_after::<T>(path, &[])
+    }
+
+    /// [`resource`](Self::resource), also after the resources with the plural names in
+    /// `after`, for dependencies no join describes
+    #[must_use]
+    pub fn resource_after<T>(mut self, path: impl Into<PathBuf>, after: &[&str]) -> Self
+    where
+        T: CRUDResource + 'static,
+        T::CreateModel: DeserializeOwned,
+    {
+        let type_name = std::any::type_name::<T>();
+        self.entries.push(SeedEntry {
+            resource: T::RESOURCE_NAME_PLURAL,
+            api_struct: type_name.rsplit("::").next().unwrap_or(type_name),
+            path: path.into(),
+            after: after.iter().map(ToString::to_string).collect(),
+            children: T::relation_metadata()
+                .into_iter()
+                .filter(|relation| relation.cardinality == Cardinality::Many)
+                .map(|relation| relation.target)
+                .collect(),
+            load: load_count::<T>,
+        });
+        self
+    }
+
+    /// Plural names of the resources in the order [`load`](Self::load) seeds them.
+    /// Independent resources keep the order they were added in.
+    ///
+    /// # Errors
+    /// Returns [`SeedError::UnknownDependency`] or [`SeedError::Cycle`].
+    pub fn order(&self) -> Result<Vec<&'static str>, SeedError> {
+        Ok(self
+            .ordered_entries()?
+            .into_iter()
+            .map(|entry| entry.resource)
+            .collect())
+    }
+
+    /// Load every file, returning the number of rows created per resource in load order.
+    ///
+    /// # Errors
+    /// Returns the first [`SeedError`]; resources loaded before it stay seeded.
+    pub async fn load(
+        &self,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<(&'static str, usize)>, SeedError> {
+        let mut counts = Vec::with_capacity(self.entries.len());
+        for entry in self.ordered_entries()? {
+            counts.push((entry.resource, (entry.load)(db, &entry.path).await?));
+        }
+        Ok(counts)
+    }
+
+    fn ordered_entries(&self) -> Result<Vec<&SeedEntry>, SeedError> {
+        let dependencies = self.dependencies()?;
+        let mut loaded = vec![false; self.entries.len()];
+        let mut order = Vec::with_capacity(self.entries.len());
+        while order.len() < self.entries.len() {
+            let next = (0..self.entries.len()).find(|&index| {
+                !loaded[index]
+                    && dependencies[index]
+                        .iter()
+                        .all(|&dependency| loaded[dependency])
+            });
+            let Some(index) = next else {
+                return Err(SeedError::Cycle {
+                    resources: (0..self.entries.len())
+                        .filter(|&index| !loaded[index])
+                        .map(|index| self.entries[index].resource)
+                        .collect(),
+                });
+            };
+            loaded[index] = true;
+            order.push(&self.entries[index]);
+        }
+        Ok(order)
+    }
+
+    /// Indexes of the entries each entry is loaded after
+    fn dependencies(&self) -> Result<Vec<Vec<usize>>, SeedError> {
+        let position = |resource: &str| {
+            self.entries
+                .iter()
+                .position(|entry| entry.resource == resource)
+        };
+        let mut dependencies = Vec::with_capacity(self.entries.len());
+        for entry in &self.entries {
+            let mut after = Vec::new();
+            for dependency in &entry.after {
+                after.push(
+                    position(dependency).ok_or_else(|| SeedError::UnknownDependency {
+                        resource: entry.resource,
+                        dependency: dependency.clone(),
+                    })?,
+                );
+            }
+            dependencies.push(after);
+        }
+        // A parent holding `Vec<Child>` is seeded before the child
+        for (parent, entry) in self.entries.iter().enumerate() {
+            for (child, candidate) in self.entries.iter().enumerate() {
+                if child != parent
+                    && entry.children.contains(&candidate.api_struct)
+                    && !dependencies[child].contains(&parent)
+                {
+                    dependencies[child].push(parent);
+                }
+            }
+        }
+        Ok(dependencies)
+    }
+}
@@ -0,0 +1,264 @@
+//! Sharing one response between identical `GET` requests, set with
+//! `#[crudcrate(singleflight_ms = 100)]`.
+//!
+//! When clients retry in a burst, every retry of `GET /orders?filter=...` would run the
+//! same query again. The generated routers of a `singleflight_ms` resource run list and
+//! get-one requests (`GET /` and `GET /{id}`) through [`share`]: the first request for
+//! a key runs the handler, identical requests arriving while it runs wait for its
+//! response, and successful responses are reused for `singleflight_ms` afterwards.
+//!
+//! Requests are identical when they have the same path and query, the same
+//! `Authorization` and `Cookie` headers, and the same [`ScopeCondition`], so responses
+//! are never shared between callers who could see different rows. Each router has its
+//! own flight, so routers over different databases never share responses either.
+//!
+//! A shared response is read fully into memory before it is handed out. Error responses
+//! are shared with the requests that waited for them but not kept afterwards. If the
+//! first request is cancelled, the requests waiting for it run their own handler.
+//!
+//! [`ScopeCondition`]: crate::ScopeCondition
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use axum::body::{Body, Bytes};
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, Method, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use tokio::sync::watch;
+
+use crate::ApiError;
+use crate::scope::ScopeCondition;
+
+/// Identical requests in flight and recent responses of one router, for [`share`]
+#[derive(Clone)]
+pub struct SingleFlight {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+/// A buffered response handed to every request with its key
+#[derive(Debug)]
+struct SharedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+type Flight = watch::Receiver<Option<Arc<SharedResponse>>>;
+
+enum Entry {
+    /// The first request is running; waiting requests receive its response
+    InFlight(Flight),
+    /// A successful response reused until `expires`
+    Done {
+        response: Arc<SharedResponse>,
+        expires: Instant,
+    },
+}
+
+/// What a request does with its key
+enum Join {
+    Ready(Arc<SharedResponse>),
+    Wait(Flight),
+    Lead(Leader),
+}
+
+/// The request running the handler for a key. Dropping it before
+/// [`finish`](Leader::finish) (when the request is cancelled) removes the key, so
+/// waiting and later requests run their own handler.
+struct Leader {
+    flight: SingleFlight,
+    key: String,
+    sender: watch::Sender<Option<Arc<SharedResponse>>>,
+    finished: bool,
+}
+
+impl SingleFlight {
+    /// A flight keeping successful responses for `ttl` after they complete
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::default(),
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn join(&self, key: &str) -> Join {
+        let mut entries = self.entries();
+        let now = Instant::now();
+        match entries.get(key) {
+            Some(Entry::InFlight(flight)) => return Join::Wait(flight.clone()),
+            Some(Entry::Done { response, expires }) if *expires > now => {
+                return Join::Ready(Arc::clone(response));
+            }
+            _ => {}
+        }
+        entries.retain(|_, entry| !matches!(entry, Entry::Done { expires, .. } if *expires <= now));
+
+        let (sender, flight) = watch::channel(None);
+        entries.insert(key.to_string(), Entry::InFlight(flight));
+        Join::Lead(Leader {
+            flight: self.clone(),
+            key: key.to_string(),
+            sender,
+            finished: false,
+        })
+    }
+}
+
+impl Leader {
+    /// Hand `response` to the waiting requests, keeping it for the TTL if successful
+    fn finish(mut self, response: &Arc<SharedResponse>) {
+        self.finished = true;
+        {
+            let mut entries = self.flight.entries();
+            if response.status.is_success() && !self.flight.ttl.is_zero() {
+                entries.insert(
+                    self.key.clone(),
+                    Entry::Done {
+                        response: Arc::clone(response),
+                        expires: Instant::now() + self.flight.ttl,
+                    },
+                );
+            } else {
+                entries.remove(&self.key);
+            }
+        }
+        self.sender.send_replace(Some(Arc::clone(response)));
+    }
+}
+
+impl Drop for Leader {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.flight.entries().remove(&self.key);
+        }
+    }
+}
+
+impl SharedResponse {
+    async fn buffer(response: Response) -> Result<Self, Response> {
+        let (parts, body) = response.into_parts();
+        match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(body) => Ok(Self {
+                status: parts.status,
+                headers: parts.headers,
+                body,
+            }),
+            Err(error) => {
+                tracing::error!(%error, "Failed to buffer a shared response");
+                Err(ApiError::internal("Failed to read the response", None).into_response())
+            }
+        }
+    }
+
+    fn to_response(&self) -> Response {
+        let mut response = Response::new(Body::from(self.body.clone()));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+/// Share the response of identical list and get-one requests through `flight`. Added
+/// to the generated routers with `axum::middleware::from_fn_with_state`.
+pub async fn share(State(flight): State<SingleFlight>, request: Request, next: Next) -> Response {
+    if request.method() != Method::GET || !is_shared_route(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let key = request_key(&request);
+    let mut waiting = match flight.join(&key) {
+        Join::Ready(response) => return response.to_response(),
+        Join::Lead(leader) => {
+            let response = match SharedResponse::buffer(next.run(request).await).await {
+                Ok(response) => Arc::new(response),
+                Err(response) => return response,
+            };
+            leader.finish(&response);
+            return response.to_response();
+        }
+        Join::Wait(waiting) => waiting,
+    };
+
+    let shared = waiting
+        .wait_for(Option::is_some)
+        .await
+        .ok()
+        .and_then(|response| response.clone());
+    match shared {
+        Some(response) => response.to_response(),
+        // The first request was cancelled
+        None => next.run(request).await,
+    }
+}
+
+/// `/` (list) or `/{id}` (get one), relative to the resource
+fn is_shared_route(path: &str) -> bool {
+    !path.trim_matches('/').contains('/')
+}
+
+/// Path, query, credentials and scope: everything that can change the response
+fn request_key(request: &Request) -> String {
+    let header = |name: header::HeaderName| {
+        request
+            .headers()
+            .get_all(name)
+            .iter()
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let scope = request
+        .extensions()
+        .get::<ScopeCondition>()
+        .map(|scope| format!("{:?}", scope.condition));
+    format!(
+        "{}\n{}\n{}\n{scope:?}",
+        request.uri(),
+        header(header::AUTHORIZATION),
+        header(header::COOKIE),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_routes() {
+        assert!(is_shared_route("/"));
+        assert!(is_shared_route(""));
+        assert!(is_shared_route("/6f0d3c4e-94d1-4c39-9d3e-6c1b5f1e2a01"));
+        assert!(!is_shared_route("/distinct/status"));
+        assert!(!is_shared_route(
+            "/6f0d3c4e-94d1-4c39-9d3e-6c1b5f1e2a01/children"
+        ));
+    }
+
+    #[test]
+    fn test_request_key_includes_credentials() {
+        let request = |authorization: &str| {
+            axum::http::Request::builder()
+                .uri("/?filter=%7B%7D")
+                .header(header::AUTHORIZATION, authorization)
+                .body(Body::empty())
+                .unwrap()
+        };
+        assert_eq!(
+            request_key(&request("Bearer a")),
+            request_key(&request("Bearer a"))
+        );
+        assert_ne!(
+            request_key(&request("Bearer a")),
+            request_key(&request("Bearer b"))
+        );
+    }
+}
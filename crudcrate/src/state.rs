@@ -0,0 +1,115 @@
+//! Application state for hooks and operations, set by `router_with_state`.
+//!
+//! `router(&db)` gives handlers only the `DatabaseConnection`. With
+//! `router_with_state(state)` every request on the router runs inside
+//! [`with_app_state`], so hooks, `CRUDOperations` and custom bodies called from the
+//! handlers can read the whole application state with [`app_state`]:
+//!
+//! ```rust,ignore
+//! #[derive(Clone)]
+//! struct AppState {
+//!     db: DatabaseConnection,
+//!     max_title_length: usize,
+//! }
+//!
+//! impl AsRef<DatabaseConnection> for AppState {
+//!     fn as_ref(&self) -> &DatabaseConnection {
+//!         &self.db
+//!     }
+//! }
+//!
+//! async fn check_title(_db: &DatabaseConnection, data: &PostCreate) -> Result<(), ApiError> {
+//!     // Fail closed: a check that can't read the state mustn't let the write through
+//!     let state = crudcrate::app_state::<AppState>()
+//!         .ok_or_else(|| ApiError::internal("Application state is not available", None))?;
+//!     if data.title.len() > state.max_title_length {
+//!         return Err(ApiError::bad_request("Title is too long"));
+//!     }
+//!     Ok(())
+//! }
+//!
+//! let app = Router::new().nest("/posts", Post::router_with_state(state).into());
+//! ```
+//!
+//! The state is scoped to the request's future like the request ID of
+//! [`telemetry`](crate::telemetry), with the same limits: [`app_state`] answers `None`
+//! in tasks a hook spawns, so read it before spawning and move the clone into the task,
+//! or wrap the task in [`with_app_state`]. The same holds for `spawn_blocking` and
+//! futures queued to run after the request.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::scoped::Scoped;
+
+type SharedState = Arc<dyn Any + Send + Sync>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<SharedState>> = const { RefCell::new(None) };
+}
+
+/// The application state of the request being handled, if the router was built with
+/// `router_with_state` and its state is an `S`. `None` in tasks spawned from the request.
+#[must_use]
+pub fn app_state<S: Clone + 'static>() -> Option<S> {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .and_then(|state| state.downcast_ref::<S>().cloned())
+    })
+}
+
+/// Run `future` with `state` visible to [`app_state`]. Scopes nest: the inner state
+/// applies until the inner future completes.
+pub fn with_app_state<S, F>(state: S, future: F) -> WithAppState<F>
+where
+    S: Send + Sync + 'static,
+    F: Future,
+{
+    let state: SharedState = Arc::new(state);
+    WithAppState(Scoped::new(&CURRENT, Some(state), future))
+}
+
+/// Future returned by [`with_app_state`]
+#[must_use = "futures do nothing unless polled"]
+pub struct WithAppState<F>(Scoped<Option<SharedState>, F>);
+
+impl<F: Future> Future for WithAppState<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        Pin::new(&mut self.get_mut().0).poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_state_is_scoped_to_future() {
+        assert_eq!(app_state::<String>(), None);
+
+        let outer = with_app_state("outer".to_string(), async {
+            assert_eq!(app_state::<String>().as_deref(), Some("outer"));
+            assert_eq!(app_state::<u32>(), None);
+
+            let inner = with_app_state("inner".to_string(), async {
+                tokio::task::yield_now().await;
+                app_state::<String>()
+            })
+            .await;
+            assert_eq!(inner.as_deref(), Some("inner"));
+
+            app_state::<String>()
+        })
+        .await;
+        assert_eq!(outer.as_deref(), Some("outer"));
+        assert_eq!(app_state::<String>(), None);
+    }
+}
@@ -0,0 +1,283 @@
+//! Allowed transitions of a status field, declared with
+//! `#[crudcrate(state_machine(field = "status", transitions(...)))]`.
+//!
+//! ```rust,ignore
+//! #[crudcrate(
+//!     generate_router,
+//!     state_machine(field = "status", transitions("todo -> doing", "doing -> done, todo"))
+//! )]
+//! pub struct Model { /* ... */ }
+//! ```
+//!
+//! Each transition names a state and the states it may move to. The default `update`
+//! and `update_many` bodies compare the stored state with the merged one and answer 400
+//! listing the allowed next states when the move isn't declared, so `PUT /{id}` with
+//! `{"status": "todo"}` on a done item fails. Writing the same state again is always
+//! allowed. The derive also adds `POST /{id}/transition` to the full router, taking
+//! `{"to": "done"}` and answering the updated item like `PUT /{id}`.
+//!
+//! States are compared by their JSON form, so a `String` field uses its value and an
+//! enum field the name serde gives the variant. Custom `update::*::body` hooks and
+//! `operations` bypass the default bodies and check transitions themselves with
+//! [`StateMachine::check`].
+
+use axum::Json;
+use sea_orm::{ActiveValue, DatabaseConnection, Value};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+#[cfg(feature = "openapi")]
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{ApiError, CRUDResource, SharedOperations};
+
+/// The declared transitions of a resource's state field
+#[derive(Debug, Clone, Copy)]
+pub struct StateMachine {
+    /// Serialized name of the field holding the state
+    pub field: &'static str,
+    /// Allowed moves, as `(from, to)` pairs in declaration order
+    pub transitions: &'static [(&'static str, &'static str)],
+}
+
+impl StateMachine {
+    /// Every state named by a transition, in declaration order
+    #[must_use]
+    pub fn states(&self) -> Vec<&'static str> {
+        let mut states = Vec::new();
+        for (from, to) in self.transitions {
+            for state in [*from, *to] {
+                if !states.contains(&state) {
+                    states.push(state);
+                }
+            }
+        }
+        states
+    }
+
+    /// States `from` may move to
+    #[must_use]
+    pub fn next_states(&self, from: &str) -> Vec<&'static str> {
+        self.transitions
+            .iter()
+            .filter(|(source, _)| *source == from)
+            .map(|(_, to)| *to)
+            .collect()
+    }
+
+    /// Check a move from `from` to `to`. Staying in the same state is always allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ApiError::BadRequest` naming the allowed next states when the move
+    /// isn't declared.
+    pub fn check(&self, from: &str, to: &str) -> Result<(), ApiError> {
+        if from == to || self.next_states(from).contains(&to) {
+            return Ok(());
+        }
+        let next = self.next_states(from);
+        let allowed = if next.is_empty() {
+            format!("\"{from}\" is a final state")
+        } else {
+            format!("Allowed next states: {}", quoted(&next))
+        };
+        Err(ApiError::bad_request(format!(
+            "Can't move `{}` from \"{from}\" to \"{to}\". {allowed}.",
+            self.field
+        )))
+    }
+
+    /// The transitions as Markdown, one line per source state, for `OpenAPI` descriptions
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let mut lines = Vec::new();
+        for state in self.states() {
+            let next = self.next_states(state);
+            if !next.is_empty() {
+                lines.push(format!("- `{state}` → {}", quoted(&next)));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+fn quoted(states: &[&str]) -> String {
+    states
+        .iter()
+        .map(|state| format!("\"{state}\""))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The state a field value stands for: a JSON string's contents, or any other JSON
+/// value as written
+fn state_name<V: Serialize>(value: &V) -> Option<String> {
+    match serde_json::to_value(value).ok()? {
+        serde_json::Value::String(state) => Some(state),
+        serde_json::Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+/// Check the move between the stored and merged values of `T`'s state field. The
+/// generated `update_active_model` calls this; unsent or `null` values pass.
+///
+/// # Errors
+///
+/// Returns the error of [`StateMachine::check`].
+pub fn check_update<T, V>(
+    original: &ActiveValue<V>,
+    updated: &ActiveValue<V>,
+) -> Result<(), ApiError>
+where
+    T: CRUDResource,
+    V: Into<Value> + Serialize,
+{
+    let Some(machine) = T::state_machine() else {
+        return Ok(());
+    };
+    match (
+        original.try_as_ref().and_then(state_name),
+        updated.try_as_ref().and_then(state_name),
+    ) {
+        (Some(from), Some(to)) => machine.check(&from, &to),
+        _ => Ok(()),
+    }
+}
+
+/// Body of `POST /{id}/transition`
+///
+/// ```json
+/// {"to": "done"}
+/// ```
+#[derive(Debug, Clone, serde::Deserialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct Transition {
+    /// State to move to
+    pub to: String,
+}
+
+/// Body of the generated `transition_handler`: updates the state field through
+/// `update`, which checks the move, and answers the item as `R`.
+///
+/// # Errors
+///
+/// Returns `ApiError::Forbidden` in a scoped context, `ApiError::BadRequest` for a
+/// state the resource doesn't declare, and any error of `update`.
+pub async fn transition_response<T, R>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    transition: Transition,
+    scoped: bool,
+    ops: Option<&SharedOperations<T>>,
+) -> Result<Json<R>, ApiError>
+where
+    T: CRUDResource,
+    T::UpdateModel: DeserializeOwned,
+    R: From<T>,
+{
+    if scoped {
+        return Err(ApiError::forbidden("Write access denied in scoped context"));
+    }
+    let Some(machine) = T::state_machine() else {
+        return Err(ApiError::not_found(T::RESOURCE_NAME_SINGULAR, None));
+    };
+    let states = machine.states();
+    if !states.contains(&transition.to.as_str()) {
+        return Err(ApiError::bad_request(format!(
+            "\"{}\" is not a state of `{}`. States: {}.",
+            transition.to,
+            machine.field,
+            quoted(&states)
+        )));
+    }
+
+    let data: T::UpdateModel = serde_json::from_value(serde_json::json!({
+        machine.field: transition.to,
+    }))
+    .map_err(|e| ApiError::bad_request(format!("Invalid state for `{}`: {e}", machine.field)))?;
+    let _locks = crate::locks::lock_rows::<T>([id]).await;
+    let updated = match ops {
+        Some(ops) => crate::CRUDOperations::update(ops.operations(), db, id, data).await,
+        None => T::update(db, id, data).await,
+    }?;
+    Ok(Json(R::from(updated)))
+}
+
+/// Handler for `POST /{id}/transition`, invoked by the derive next to `crud_handlers!`
+/// for resources with `#[crudcrate(state_machine(...))]`. `@no_openapi` leaves out
+/// `#[utoipa::path]`.
+#[macro_export]
+macro_rules! transition_handlers {
+    (@no_openapi $resource:ty, $response_model:ty) => {
+        crudcrate::transition_handlers!(@openapi false; $resource, $response_model);
+    };
+    ($resource:ty, $response_model:ty) => {
+        crudcrate::transition_handlers!(@openapi true; $resource, $response_model);
+    };
+    (@openapi $openapi:tt; $resource:ty, $response_model:ty) => {
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            post,
+            path = "/{id}/transition",
+            request_body = crudcrate::state_machine::Transition,
+            responses(
+                (status = axum::http::StatusCode::OK, description = "Resource moved to the new state", body = $response_model),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Unknown state, or a move the state machine doesn't allow"),
+                (status = axum::http::StatusCode::NOT_FOUND, description = "Resource not found"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            operation_id = format!("transition_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            summary = format!("Move one {} to another state", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR),
+            description = format!(
+                "Sets the `{}` of one {} to `to`. Allowed moves:\n\n{}",
+                <$resource as crudcrate::CRUDResource>::state_machine().map_or("", |machine| machine.field),
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_SINGULAR,
+                <$resource as crudcrate::CRUDResource>::state_machine().map(|machine| machine.describe()).unwrap_or_default()
+            )
+        )]
+        pub async fn transition_handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            crudcrate::PathId { id, .. }: crudcrate::PathId<$resource>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+            ops: Option<axum::Extension<crudcrate::SharedOperations<$resource>>>,
+            axum::Json(transition): axum::Json<crudcrate::state_machine::Transition>,
+        ) -> Result<axum::Json<$response_model>, crudcrate::ApiError> {
+            crudcrate::state_machine::transition_response::<$resource, $response_model>(
+                &db,
+                id,
+                transition,
+                scope.is_some(),
+                ops.as_ref().map(|axum::Extension(ops)| ops),
+            )
+            .await
+        });
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MACHINE: StateMachine = StateMachine {
+        field: "status",
+        transitions: &[("todo", "doing"), ("doing", "done"), ("doing", "todo")],
+    };
+
+    #[test]
+    fn test_check() {
+        assert!(MACHINE.check("todo", "doing").is_ok());
+        assert!(MACHINE.check("done", "done").is_ok());
+        assert_eq!(MACHINE.states(), ["todo", "doing", "done"]);
+
+        let err = MACHINE.check("todo", "done").unwrap_err().to_string();
+        assert!(err.contains("Allowed next states: \"doing\""), "{err}");
+        let err = MACHINE.check("done", "todo").unwrap_err().to_string();
+        assert!(err.contains("\"done\" is a final state"), "{err}");
+
+        assert_eq!(
+            MACHINE.describe(),
+            "- `todo` → \"doing\"\n- `doing` → \"done\", \"todo\""
+        );
+    }
+}
@@ -0,0 +1,616 @@
+//! File uploads for `#[crudcrate(file_upload(storage = "..."))]` fields.
+//!
+//! Create and update handlers accept `multipart/form-data` as well as JSON. File parts
+//! whose name matches a `file_upload` field are streamed to the named
+//! [`StorageBackend`], and the value it returns (an object key or URL) is stored in the
+//! column. Deleting the record deletes the object, and replacing the file on update
+//! deletes the old one.
+//!
+//! ```rust,ignore
+//! #[crudcrate(file_upload(storage = "images"))]
+//! pub image: Option<String>,
+//!
+//! crudcrate::storage::register_storage("images", LocalStorage::new("./uploads"));
+//! ```
+//!
+//! Other parts of the form fill the remaining fields: a part named `data` holds a JSON
+//! object, and any other text part is taken as a string value. `file_upload` columns
+//! only take values stored by the request itself: JSON bodies and `data` parts may
+//! leave them out or send `null`, so a client can't point a record at another object
+//! and have it deleted with the record.
+//!
+//! ```text
+//! curl -F 'data={"title":"Cat","year":2024}' -F 'image=@cat.png' http://localhost:3000/photos
+//! ```
+//!
+//! Backends are registered by name at startup. [`LocalStorage`] writes to disk; for S3
+//! or another object store, implement [`StorageBackend`] over your client. Multipart
+//! requests and [`LocalStorage`] require the `file-upload` feature.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde::de::{DeserializeOwned, Deserializer, Error as _};
+
+use crate::core::CRUDResource;
+use crate::errors::ApiError;
+
+static BACKENDS: RwLock<BTreeMap<String, Arc<dyn StorageBackend>>> = RwLock::new(BTreeMap::new());
+
+thread_local! {
+    /// Values stored by the multipart request being deserialized on this thread
+    static ISSUED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Where uploaded files are stored
+///
+/// ```rust,ignore
+/// struct S3Storage { client: aws_sdk_s3::Client, bucket: String }
+///
+/// #[async_trait]
+/// impl StorageBackend for S3Storage {
+///     async fn put(&self, key: &str, upload: &mut Upload<'_>) -> Result<String, ApiError> {
+///         let body = upload.bytes().await?;
+///         self.client.put_object().bucket(&self.bucket).key(key)
+///             .set_content_type(upload.content_type().map(str::to_string))
+///             .body(body.into()).send().await
+///             .map_err(|e| ApiError::internal("Failed to store file", Some(e.to_string())))?;
+///         Ok(key.to_string())
+///     }
+///
+///     async fn delete(&self, stored: &str) -> Result<(), ApiError> {
+///         self.client.delete_object().bucket(&self.bucket).key(stored).send().await
+///             .map_err(|e| ApiError::internal("Failed to delete file", Some(e.to_string())))?;
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait StorageBackend: Send + Sync + 'static {
+    /// Store `upload` under `key` and return the value to persist in the column
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ApiError` if reading the upload or writing the object fails.
+    async fn put(&self, key: &str, upload: &mut Upload<'_>) -> Result<String, ApiError>;
+
+    /// Delete an object by the value returned from [`put`](Self::put). Deleting a missing
+    /// object should succeed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ApiError` if the object could not be deleted.
+    async fn delete(&self, stored: &str) -> Result<(), ApiError>;
+}
+
+/// Register `backend` under the name used in `file_upload(storage = "...")`,
+/// replacing any backend already registered with that name
+///
+/// # Panics
+///
+/// Panics if the backend registry lock is poisoned.
+pub fn register_storage(name: impl Into<String>, backend: impl StorageBackend) {
+    BACKENDS
+        .write()
+        .expect("storage registry poisoned")
+        .insert(name.into(), Arc::new(backend));
+}
+
+/// Look up a registered backend
+///
+/// # Panics
+///
+/// Panics if the backend registry lock is poisoned.
+#[must_use]
+pub fn storage(name: &str) -> Option<Arc<dyn StorageBackend>> {
+    BACKENDS
+        .read()
+        .expect("storage registry poisoned")
+        .get(name)
+        .cloned()
+}
+
+/// An uploaded file, read in chunks as it arrives
+pub struct Upload<'a> {
+    file_name: Option<String>,
+    content_type: Option<String>,
+    source: Source<'a>,
+}
+
+#[allow(clippy::large_enum_variant)]
+enum Source<'a> {
+    Bytes(Option<Bytes>, PhantomData<&'a mut ()>),
+    #[cfg(feature = "file-upload")]
+    Field(axum::extract::multipart::Field<'a>),
+}
+
+impl Upload<'_> {
+    /// An upload with the whole file in memory, for tests and programmatic use
+    #[must_use]
+    pub fn from_bytes(
+        file_name: Option<&str>,
+        content_type: Option<&str>,
+        data: impl Into<Bytes>,
+    ) -> Upload<'static> {
+        Upload {
+            file_name: file_name.map(str::to_string),
+            content_type: content_type.map(str::to_string),
+            source: Source::Bytes(Some(data.into()), PhantomData),
+        }
+    }
+
+    /// File name sent by the client
+    #[must_use]
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Content type sent by the client
+    #[must_use]
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// The next chunk of the file, or `None` once it has been read
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ApiError` if the request body can't be read.
+    #[cfg_attr(not(feature = "file-upload"), allow(clippy::unused_async))]
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, ApiError> {
+        match &mut self.source {
+            Source::Bytes(data, _) => Ok(data.take()),
+            #[cfg(feature = "file-upload")]
+            Source::Field(field) => field.chunk().await.map_err(multipart_error),
+        }
+    }
+
+    /// Read the rest of the file into memory
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ApiError` if the request body can't be read.
+    pub async fn bytes(&mut self) -> Result<Vec<u8>, ApiError> {
+        let mut data = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
+    }
+}
+
+/// Values that may hold a stored object, implemented for the column types accepted by
+/// `file_upload` fields
+pub trait StoredFile {
+    /// The stored value, or `None` if no file is set
+    fn stored_file(&self) -> Option<&str>;
+}
+
+impl StoredFile for String {
+    fn stored_file(&self) -> Option<&str> {
+        (!self.is_empty()).then_some(self.as_str())
+    }
+}
+
+impl StoredFile for Option<String> {
+    fn stored_file(&self) -> Option<&str> {
+        self.as_ref().and_then(StoredFile::stored_file)
+    }
+}
+
+/// Create and update model field types of `file_upload` columns
+#[doc(hidden)]
+pub trait UploadedValue: Sized {
+    /// The field value for a present JSON value, or `None` if it can't be `null`
+    fn from_uploaded(value: Option<String>) -> Option<Self>;
+}
+
+impl UploadedValue for String {
+    fn from_uploaded(value: Option<String>) -> Option<Self> {
+        value
+    }
+}
+
+impl UploadedValue for Option<String> {
+    fn from_uploaded(value: Option<String>) -> Option<Self> {
+        Some(value)
+    }
+}
+
+impl UploadedValue for Option<Option<String>> {
+    fn from_uploaded(value: Option<String>) -> Option<Self> {
+        Some(Some(value))
+    }
+}
+
+/// Deserializer of `file_upload` fields in the generated create and update models:
+/// accepts `null`, and strings only when the multipart request being read stored them
+///
+/// # Errors
+///
+/// Returns an error for any other value.
+#[doc(hidden)]
+pub fn deserialize_uploaded<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: UploadedValue,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    if let Some(value) = &value
+        && !ISSUED.with(|issued| issued.borrow().contains(value))
+    {
+        return Err(D::Error::custom("can only be set by uploading a file"));
+    }
+    T::from_uploaded(value).ok_or_else(|| D::Error::custom("a file is required"))
+}
+
+/// Delete stored objects, as `(storage name, stored value)` pairs.
+///
+/// Failures are logged rather than returned: this is cleanup after the outcome of the
+/// request is already decided.
+pub async fn discard(stored: &[(&'static str, String)]) {
+    for (name, value) in stored {
+        let Some(backend) = storage(name) else {
+            tracing::warn!(storage = *name, object = %value, "No storage backend registered; file not deleted");
+            continue;
+        };
+        if let Err(error) = backend.delete(value).await {
+            tracing::warn!(storage = *name, object = %value, %error, "Failed to delete stored file");
+        }
+    }
+}
+
+/// Delete the objects in `before` that are no longer referenced in `after`
+pub async fn discard_replaced(before: &[(&'static str, String)], after: &[(&'static str, String)]) {
+    let replaced: Vec<_> = before
+        .iter()
+        .filter(|file| !after.contains(file))
+        .cloned()
+        .collect();
+    discard(&replaced).await;
+}
+
+/// Request body of the create and update handlers: JSON, or `multipart/form-data` with
+/// file parts for `R`'s `file_upload` fields
+pub struct Payload<R, T> {
+    data: T,
+    stored: Vec<(&'static str, String)>,
+    resource: PhantomData<fn() -> R>,
+}
+
+impl<R, T> Payload<R, T> {
+    /// The deserialized body and the objects stored while reading it
+    #[must_use]
+    pub fn into_parts(self) -> (T, Vec<(&'static str, String)>) {
+        (self.data, self.stored)
+    }
+}
+
+impl<S, R, T> FromRequest<S> for Payload<R, T>
+where
+    S: Send + Sync,
+    R: CRUDResource,
+    T: DeserializeOwned + Send,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if is_multipart(&req) {
+            #[cfg(feature = "file-upload")]
+            return multipart::read::<R, T, S>(req, state)
+                .await
+                .map_err(IntoResponse::into_response);
+            #[cfg(not(feature = "file-upload"))]
+            return Err(ApiError::custom(
+                axum::http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Multipart requests are not supported",
+                Some("enable the `file-upload` feature of crudcrate".to_string()),
+            )
+            .into_response());
+        }
+
+        let axum::Json(data) = axum::Json::<T>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        Ok(Self {
+            data,
+            stored: Vec::new(),
+            resource: PhantomData,
+        })
+    }
+}
+
+fn is_multipart(req: &Request) -> bool {
+    req.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .to_ascii_lowercase()
+                .starts_with("multipart/form-data")
+        })
+}
+
+/// Object key for an upload: `{prefix}/{uuid}-{file name}`, keeping only file name
+/// characters that are safe in paths and URLs
+#[cfg(feature = "file-upload")]
+fn object_key(prefix: &str, file_name: Option<&str>) -> String {
+    const MAX_NAME_LEN: usize = 100;
+
+    let name: String = file_name
+        .and_then(|name| name.rsplit(['/', '\\']).next())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_NAME_LEN)
+        .collect();
+    let name = name.trim_start_matches('.');
+    let name = if name.is_empty() { "upload" } else { name };
+    format!("{prefix}/{}-{name}", uuid::Uuid::new_v4())
+}
+
+#[cfg(feature = "file-upload")]
+#[allow(clippy::needless_pass_by_value)]
+fn multipart_error(err: axum::extract::multipart::MultipartError) -> ApiError {
+    ApiError::custom(err.status(), err.body_text(), None)
+}
+
+#[cfg(feature = "file-upload")]
+mod multipart {
+    use super::{
+        ApiError, CRUDResource, DeserializeOwned, FromRequest, ISSUED, Payload, PhantomData,
+        Request, Source, Upload, discard, multipart_error, object_key, storage,
+    };
+    use serde_json::{Map, Value};
+
+    pub(super) async fn read<R, T, S>(req: Request, state: &S) -> Result<Payload<R, T>, ApiError>
+    where
+        S: Send + Sync,
+        R: CRUDResource,
+        T: DeserializeOwned,
+    {
+        let mut multipart = axum::extract::Multipart::from_request(req, state)
+            .await
+            .map_err(|err| ApiError::bad_request(err.body_text()))?;
+
+        let mut stored = Vec::new();
+        match read_fields::<R, T>(&mut multipart, &mut stored).await {
+            Ok(data) => Ok(Payload {
+                data,
+                stored,
+                resource: PhantomData,
+            }),
+            Err(err) => {
+                discard(&stored).await;
+                Err(err)
+            }
+        }
+    }
+
+    async fn read_fields<R: CRUDResource, T: DeserializeOwned>(
+        multipart: &mut axum::extract::Multipart,
+        stored: &mut Vec<(&'static str, String)>,
+    ) -> Result<T, ApiError> {
+        let file_fields = R::file_fields();
+        let mut object = Map::new();
+
+        while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+            let name = field.name().unwrap_or_default().to_string();
+            let is_file = field.file_name().is_some();
+
+            if let Some(&(_, storage_name)) = file_fields
+                .iter()
+                .find(|(file_field, _)| *file_field == name)
+            {
+                if !is_file {
+                    return Err(ApiError::bad_request(format!(
+                        "Field '{name}' must be a file"
+                    )));
+                }
+                let backend = storage(storage_name).ok_or_else(|| {
+                    ApiError::internal(
+                        "File storage is not configured",
+                        Some(format!("no storage backend registered as '{storage_name}'")),
+                    )
+                })?;
+                let key = object_key(R::RESOURCE_NAME_PLURAL, field.file_name());
+                let mut upload = Upload {
+                    file_name: field.file_name().map(str::to_string),
+                    content_type: field.content_type().map(str::to_string),
+                    source: Source::Field(field),
+                };
+                let value = backend.put(&key, &mut upload).await?;
+                stored.push((storage_name, value.clone()));
+                object.insert(name, Value::String(value));
+            } else if is_file {
+                return Err(ApiError::bad_request(format!(
+                    "Unexpected file field '{name}'"
+                )));
+            } else if name == "data" {
+                let text = field.text().await.map_err(multipart_error)?;
+                let Ok(Value::Object(data)) = serde_json::from_str(&text) else {
+                    return Err(ApiError::bad_request(
+                        "The 'data' part must be a JSON object",
+                    ));
+                };
+                // Uploaded files win over values given in `data`
+                for (key, value) in data {
+                    object.entry(key).or_insert(value);
+                }
+            } else {
+                let text = field.text().await.map_err(multipart_error)?;
+                object.insert(name, Value::String(text));
+            }
+        }
+
+        // Only the values stored above pass `deserialize_uploaded`
+        let issued = stored.iter().map(|(_, value)| value.clone()).collect();
+        let previous = ISSUED.with(|cell| cell.replace(issued));
+        let data = serde_json::from_value(Value::Object(object));
+        ISSUED.with(|cell| *cell.borrow_mut() = previous);
+        data.map_err(|err| ApiError::validation_failed(vec![err.to_string()]))
+    }
+}
+
+/// Stores files on the local disk under a root directory
+#[cfg(feature = "file-upload")]
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: std::path::PathBuf,
+    base_url: Option<String>,
+}
+
+#[cfg(feature = "file-upload")]
+impl LocalStorage {
+    /// Store files under `root`, persisting the object key
+    #[must_use]
+    pub fn new(root: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            base_url: None,
+        }
+    }
+
+    /// Persist `{base_url}/{key}` instead of the bare key, for files served over HTTP
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    fn path(&self, key: &str) -> Result<std::path::PathBuf, ApiError> {
+        use std::path::{Component, Path};
+
+        let key_path = Path::new(key);
+        if key.is_empty()
+            || !key_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(ApiError::bad_request(format!("Invalid object key '{key}'")));
+        }
+        Ok(self.root.join(key_path))
+    }
+}
+
+#[cfg(feature = "file-upload")]
+fn io_error(err: &std::io::Error) -> ApiError {
+    ApiError::internal("Failed to store file", Some(err.to_string()))
+}
+
+#[cfg(feature = "file-upload")]
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    async fn put(&self, key: &str, upload: &mut Upload<'_>) -> Result<String, ApiError> {
+        use tokio::io::AsyncWriteExt;
+
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|err| io_error(&err))?;
+        }
+        let mut file = tokio::fs::File::create(&path)
+            .await
+            .map_err(|err| io_error(&err))?;
+
+        let written = async {
+            while let Some(chunk) = upload.chunk().await? {
+                file.write_all(&chunk).await.map_err(|err| io_error(&err))?;
+            }
+            file.flush().await.map_err(|err| io_error(&err))
+        }
+        .await;
+        if let Err(err) = written {
+            // Don't leave a partial file behind
+            let _ = tokio::fs::remove_file(&path).await;
+            return Err(err);
+        }
+
+        Ok(match &self.base_url {
+            Some(base_url) => format!("{base_url}/{key}"),
+            None => key.to_string(),
+        })
+    }
+
+    async fn delete(&self, stored: &str) -> Result<(), ApiError> {
+        let key = match &self.base_url {
+            Some(base_url) => stored
+                .strip_prefix(base_url.as_str())
+                .map_or(stored, |key| key.trim_start_matches('/')),
+            None => stored,
+        };
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(io_error(&err)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "file-upload")]
+    #[test]
+    fn test_object_key_sanitizes_file_names() {
+        let key = object_key("photos", Some("../my cat (1).png"));
+        assert!(key.starts_with("photos/"), "{key}");
+        assert!(key.ends_with("-my_cat__1_.png"), "{key}");
+
+        assert!(object_key("photos", Some("C:\\tmp\\a.txt")).ends_with("-a.txt"));
+        assert!(object_key("photos", Some("..")).ends_with("-upload"));
+        assert!(object_key("photos", None).ends_with("-upload"));
+    }
+
+    #[test]
+    fn test_stored_file_skips_empty_values() {
+        assert_eq!("a/b.png".to_string().stored_file(), Some("a/b.png"));
+        assert_eq!(String::new().stored_file(), None);
+        assert_eq!(None::<String>.stored_file(), None);
+        assert_eq!(Some(String::new()).stored_file(), None);
+    }
+
+    #[tokio::test]
+    async fn test_upload_from_bytes_yields_one_chunk() {
+        let mut upload = Upload::from_bytes(Some("a.txt"), Some("text/plain"), "hello");
+        assert_eq!(upload.file_name(), Some("a.txt"));
+        assert_eq!(upload.bytes().await.unwrap(), b"hello");
+        assert!(upload.chunk().await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "file-upload")]
+    #[tokio::test]
+    async fn test_local_storage_round_trip() {
+        let root = std::env::temp_dir().join(format!("crudcrate-{}", uuid::Uuid::new_v4()));
+        let backend = LocalStorage::new(&root).with_base_url("/files/");
+
+        let mut upload = Upload::from_bytes(Some("a.txt"), None, "hello");
+        let stored = backend.put("notes/a.txt", &mut upload).await.unwrap();
+        assert_eq!(stored, "/files/notes/a.txt");
+        assert_eq!(std::fs::read(root.join("notes/a.txt")).unwrap(), b"hello");
+
+        backend.delete(&stored).await.unwrap();
+        assert!(!root.join("notes/a.txt").exists());
+        // Deleting again is not an error
+        backend.delete(&stored).await.unwrap();
+
+        let mut upload = Upload::from_bytes(None, None, "x");
+        assert!(backend.put("../escape.txt", &mut upload).await.is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
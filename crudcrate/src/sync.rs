@@ -0,0 +1,571 @@
+//! Incremental sync for resources declared with `#[crudcrate(sync = "updated_at")]`.
+//!
+//! The derive adds `GET /changes` to both generated routers. It answers the rows created
+//! or updated after a cursor, and tombstones for the rows deleted after it, so an
+//! offline-first client can catch up without downloading the whole collection:
+//!
+//! ```text
+//! GET /notes/changes?since=2024-05-01T10:00:00Z
+//!
+//! 200 OK
+//! {
+//!   "changed": [{"id": "…", "title": "Groceries", "updated_at": "2024-05-01T10:02:00Z"}],
+//!   "deleted": [{"id": "…", "deleted_at": "2024-05-01T10:05:00Z"}],
+//!   "since": "2024-05-01T10:05:00Z",
+//!   "after_id": "…",
+//!   "has_more": false
+//! }
+//! ```
+//!
+//! The `sync` field is the watermark: it must be a timestamp set on every create and
+//! update, e.g. with `on_create = Utc::now(), on_update = Utc::now()`. Changes and
+//! deletions are returned as one stream ordered by time then ID, at most `limit` entries
+//! at a time. Pass the returned `since` and `after_id` to the next request; while
+//! `has_more` is true there are further changes waiting. Without `since`, the stream
+//! starts at the beginning, which is how a client does its first full sync.
+//!
+//! Deletes are recorded in the [`TOMBSTONE_TABLE`], created at startup with
+//! [`ensure_tombstones`]. The default `delete` and `delete_many` bodies (and so
+//! `DELETE /`, the batch and filter deletes) write the tombstones in the transaction
+//! that deletes the rows. Custom `delete::*::body` hooks and `operations` call
+//! [`record_deletes`] themselves.
+//!
+//! Changed rows are loaded through [`get_all_ordered`](crate::CRUDResource::get_all_ordered),
+//! so list joins and scope conditions apply as on the list endpoint. Tombstones carry
+//! only the ID, and are not filtered by scope.
+
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, SecondsFormat, Utc};
+use sea_orm::sea_query::{Alias, ColumnDef, Expr, ExprTrait, Index, Query, SimpleExpr, Table};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DatabaseBackend, DatabaseConnection, DbErr,
+    EntityTrait, Order, QueryFilter, QueryOrder, QueryResult, QuerySelect, QueryTrait, Value,
+};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "openapi")]
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::core::crud_operations::{check_required_scope, column_order};
+use crate::filtering::dates::DateKind;
+use crate::{ApiError, CRUDResource};
+
+/// Table holding the tombstones of deleted rows of every `sync` resource
+pub const TOMBSTONE_TABLE: &str = "crudcrate_tombstones";
+
+/// Query parameters of `GET /changes`
+///
+/// ```bash
+/// # Everything, for the first sync
+/// GET /notes/changes
+///
+/// # What changed since the last response
+/// GET /notes/changes?since=2024-05-01T10:05:00Z&after_id=<uuid>
+/// ```
+#[derive(Deserialize, Default, Clone)]
+#[cfg_attr(feature = "openapi", derive(IntoParams, ToSchema))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct SyncOptions {
+    /// RFC 3339 timestamp; only changes after it are returned
+    #[cfg_attr(feature = "openapi", param(example = "2024-05-01T10:00:00Z"))]
+    pub since: Option<String>,
+    /// ID of the last change seen at `since`, as returned with it. Without it, every
+    /// change at exactly `since` is skipped.
+    pub after_id: Option<String>,
+    /// Most changes and deletions returned, up to the resource's `max_page_size`.
+    ///
+    /// Default: `max_page_size`
+    pub limit: Option<u64>,
+}
+
+/// A deleted row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct Tombstone {
+    pub id: Uuid,
+    /// RFC 3339 timestamp of the delete
+    pub deleted_at: String,
+}
+
+/// Body of `GET /changes`
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(ToSchema))]
+pub struct Changes<L> {
+    /// Rows created or updated after the cursor, oldest change first
+    pub changed: Vec<L>,
+    /// Rows deleted after the cursor, oldest first
+    pub deleted: Vec<Tombstone>,
+    /// `since` for the next request: the time of the last change returned, or the
+    /// request's own `since` when nothing changed
+    pub since: Option<String>,
+    /// `after_id` for the next request
+    pub after_id: Option<Uuid>,
+    /// Whether more changes are waiting after this response
+    pub has_more: bool,
+}
+
+/// Create the [`TOMBSTONE_TABLE`] if it doesn't exist. Call it at startup, before
+/// serving `sync` resources.
+///
+/// # Errors
+/// Returns the database error if the table can't be created.
+pub async fn ensure_tombstones(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    let table = Table::create()
+        .table(Alias::new(TOMBSTONE_TABLE))
+        .if_not_exists()
+        .col(
+            ColumnDef::new(Alias::new("resource"))
+                .string_len(255)
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(Alias::new("deleted_at"))
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(ColumnDef::new(Alias::new("id")).uuid().not_null())
+        // Also the index `changes` reads the tombstones in order through
+        .primary_key(
+            Index::create()
+                .col(Alias::new("resource"))
+                .col(Alias::new("deleted_at"))
+                .col(Alias::new("id")),
+        )
+        .to_owned();
+    db.execute(backend.build(&table)).await?;
+    Ok(())
+}
+
+/// Record tombstones for the deleted `T` rows `ids`. Does nothing unless `T` has a
+/// [`sync_column`](CRUDResource::sync_column).
+///
+/// # Errors
+/// Returns `ApiError::Database` if the insert fails, e.g. because
+/// [`ensure_tombstones`] wasn't called.
+pub async fn record_deletes<T: CRUDResource, C: ConnectionTrait>(
+    db: &C,
+    ids: &[Uuid],
+) -> Result<(), ApiError> {
+    if T::sync_column().is_none() || ids.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now();
+    let mut insert = Query::insert();
+    insert.into_table(Alias::new(TOMBSTONE_TABLE)).columns([
+        Alias::new("resource"),
+        Alias::new("deleted_at"),
+        Alias::new("id"),
+    ]);
+    for id in ids {
+        insert.values_panic([T::TABLE_NAME.into(), now.into(), (*id).into()]);
+    }
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&insert))
+        .await
+        .map_err(ApiError::database)?;
+    Ok(())
+}
+
+/// Position in the change stream: changes at `time`, then `id`
+#[derive(Clone, Copy)]
+struct Cursor {
+    time: DateTime<Utc>,
+    id: Option<Uuid>,
+}
+
+/// One entry of the change stream
+enum Entry {
+    Changed(DateTime<Utc>, Uuid),
+    Deleted(DateTime<Utc>, Uuid),
+}
+
+impl Entry {
+    fn key(&self) -> (DateTime<Utc>, Uuid) {
+        match self {
+            Self::Changed(time, id) | Self::Deleted(time, id) => (*time, *id),
+        }
+    }
+}
+
+fn sync_column<T: CRUDResource>() -> Result<(&'static str, T::ColumnType, DateKind), ApiError> {
+    T::sync_column().ok_or_else(|| {
+        ApiError::internal(
+            "Changes are not available for this resource",
+            Some(format!(
+                "{} has no #[crudcrate(sync = \"...\")]",
+                T::RESOURCE_NAME_SINGULAR
+            )),
+        )
+    })
+}
+
+fn parse_cursor(options: &SyncOptions) -> Result<Option<Cursor>, ApiError> {
+    let id = options
+        .after_id
+        .as_deref()
+        .map(|id| {
+            Uuid::parse_str(id.trim())
+                .map_err(|_| ApiError::bad_request("'after_id' must be a UUID"))
+        })
+        .transpose()?;
+    let Some(since) = options.since.as_deref() else {
+        return match id {
+            Some(_) => Err(ApiError::bad_request("'after_id' requires 'since'")),
+            None => Ok(None),
+        };
+    };
+    let time = DateTime::parse_from_rfc3339(since.trim())
+        .map_err(|_| ApiError::bad_request("'since' must be an RFC 3339 timestamp"))?
+        .with_timezone(&Utc);
+    Ok(Some(Cursor { time, id }))
+}
+
+/// `(time, id) > cursor`, spelled out on `MySQL` where row constructors don't use indexes
+fn after_cursor(
+    time_expr: SimpleExpr,
+    id_expr: SimpleExpr,
+    time: SimpleExpr,
+    id: Option<Uuid>,
+    backend: DatabaseBackend,
+) -> SimpleExpr {
+    match (id, backend) {
+        (None, _) => time_expr.gt(time),
+        (Some(id), DatabaseBackend::MySql) => time_expr
+            .clone()
+            .gt(time.clone())
+            .or(time_expr.eq(time).and(id_expr.gt(id))),
+        (Some(id), DatabaseBackend::Postgres | DatabaseBackend::Sqlite) => {
+            Expr::tuple([time_expr, id_expr]).gt(Expr::tuple([time, SimpleExpr::from(id)]))
+        }
+    }
+}
+
+/// The `sync` field of a resource, read and compared in UTC
+struct Watermark {
+    field: &'static str,
+    kind: DateKind,
+    /// Typed with the `time` crate rather than chrono
+    time_crate: bool,
+}
+
+impl Watermark {
+    #[cfg_attr(not(feature = "time"), allow(clippy::extra_unused_type_parameters))]
+    fn of<T: CRUDResource>(field: &'static str, kind: DateKind) -> Self {
+        #[cfg(feature = "time")]
+        let time_crate = crate::filtering::timestamps::time_kind::<T>(field).is_some();
+        #[cfg(not(feature = "time"))]
+        let time_crate = false;
+        Self {
+            field,
+            kind,
+            time_crate,
+        }
+    }
+
+    /// `time` as a value of the column's type
+    fn value(&self, time: DateTime<Utc>) -> Result<SimpleExpr, ApiError> {
+        crate::filtering::dates::instant_value(self.field, self.kind, self.time_crate, time)
+    }
+
+    /// The column of `row`, selected as `watermark`
+    fn read(&self, row: &QueryResult) -> Result<DateTime<Utc>, ApiError> {
+        #[cfg(feature = "time")]
+        if self.time_crate {
+            let time = if self.kind == DateKind::Timestamp {
+                row.try_get::<time::PrimitiveDateTime>("", "watermark")
+                    .map_err(ApiError::database)?
+                    .assume_utc()
+            } else {
+                row.try_get::<time::OffsetDateTime>("", "watermark")
+                    .map_err(ApiError::database)?
+            };
+            return DateTime::from_timestamp(time.unix_timestamp(), time.nanosecond()).ok_or_else(
+                || {
+                    ApiError::internal(
+                        "Invalid sync timestamp",
+                        Some(format!("{}: {time}", self.field)),
+                    )
+                },
+            );
+        }
+        match self.kind {
+            DateKind::Timestamp => Ok(row
+                .try_get::<chrono::NaiveDateTime>("", "watermark")
+                .map_err(ApiError::database)?
+                .and_utc()),
+            DateKind::TimestampTz | DateKind::Date => row
+                .try_get::<DateTime<Utc>>("", "watermark")
+                .map_err(ApiError::database),
+        }
+    }
+}
+
+/// Up to `limit` rows changed after `cursor`, as `(time, id)`
+async fn changed_keys<T: CRUDResource>(
+    db: &DatabaseConnection,
+    cursor: Option<Cursor>,
+    limit: u64,
+    scope: Option<&Condition>,
+) -> Result<Vec<Entry>, ApiError> {
+    let (field, column, kind) = sync_column::<T>()?;
+    let watermark = Watermark::of::<T>(field, kind);
+    let backend = db.get_database_backend();
+    let (time_expr, _) = column_order(column, Order::Asc);
+    let (id_expr, _) = column_order(T::ID_COLUMN, Order::Asc);
+
+    let mut condition = Condition::all();
+    if let Some(cursor) = cursor {
+        let time = watermark.value(cursor.time)?;
+        condition = condition.add(after_cursor(
+            time_expr.clone(),
+            id_expr.clone(),
+            time,
+            cursor.id,
+            backend,
+        ));
+    }
+    if let Some(scope) = scope {
+        condition = condition.add(scope.clone());
+    }
+    let statement = T::EntityType::find()
+        .select_only()
+        .column_as(T::ID_COLUMN, "id")
+        .column_as(column, "watermark")
+        .filter(condition)
+        .order_by(time_expr, Order::Asc)
+        .order_by(id_expr, Order::Asc)
+        .limit(limit)
+        .build(backend);
+    let rows = db.query_all(statement).await.map_err(ApiError::database)?;
+    rows.iter()
+        .map(|row| {
+            let id: Uuid = row.try_get("", "id").map_err(ApiError::database)?;
+            Ok(Entry::Changed(watermark.read(row)?, id))
+        })
+        .collect()
+}
+
+/// Up to `limit` tombstones of `T` after `cursor`
+async fn deleted_keys<T: CRUDResource>(
+    db: &DatabaseConnection,
+    cursor: Option<Cursor>,
+    limit: u64,
+) -> Result<Vec<Entry>, ApiError> {
+    let backend = db.get_database_backend();
+    let mut select = Query::select();
+    select
+        .columns([Alias::new("deleted_at"), Alias::new("id")])
+        .from(Alias::new(TOMBSTONE_TABLE))
+        .and_where(Expr::col(Alias::new("resource")).eq(T::TABLE_NAME))
+        .order_by(Alias::new("deleted_at"), Order::Asc)
+        .order_by(Alias::new("id"), Order::Asc)
+        .limit(limit);
+    if let Some(cursor) = cursor {
+        select.and_where(after_cursor(
+            Expr::col(Alias::new("deleted_at")).into(),
+            Expr::col(Alias::new("id")).into(),
+            Value::from(cursor.time).into(),
+            cursor.id,
+            backend,
+        ));
+    }
+    let rows = db
+        .query_all(backend.build(&select))
+        .await
+        .map_err(ApiError::database)?;
+    rows.iter()
+        .map(|row| {
+            let time: DateTime<Utc> = row.try_get("", "deleted_at").map_err(ApiError::database)?;
+            let id: Uuid = row.try_get("", "id").map_err(ApiError::database)?;
+            Ok(Entry::Deleted(time, id))
+        })
+        .collect()
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// `T` rows changed and deleted after the cursor in `options`, at most `limit` of them
+/// together, as list models and tombstones.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `since` or `after_id` don't parse, `limit` is 0 or
+/// loading the join fields of `limit` rows is over the [join budget](crate::complexity),
+/// and `ApiError::Database` if a query fails (including a missing [`TOMBSTONE_TABLE`]).
+pub async fn changes<T: CRUDResource>(
+    db: &DatabaseConnection,
+    options: &SyncOptions,
+    scope: Option<&Condition>,
+) -> Result<Changes<T::ListModel>, ApiError> {
+    let (_, column, _) = sync_column::<T>()?;
+    let cursor = parse_cursor(options)?;
+    let limit = options
+        .limit
+        .unwrap_or_else(T::max_page_size)
+        .min(T::max_page_size());
+    if limit == 0 {
+        return Err(ApiError::bad_request("'limit' must be at least 1"));
+    }
+    crate::complexity::check::<T>(limit, true)?;
+
+    // One more than the page from each stream tells whether anything is left
+    let mut entries = changed_keys::<T>(db, cursor, limit + 1, scope).await?;
+    entries.extend(deleted_keys::<T>(db, cursor, limit + 1).await?);
+    entries.sort_by_key(Entry::key);
+    let has_more = entries.len() as u64 > limit;
+    entries.truncate(usize::try_from(limit).unwrap_or(usize::MAX));
+
+    let (since, after_id) = match entries.last().map(Entry::key) {
+        Some((time, id)) => (Some(format_time(time)), Some(id)),
+        None => (
+            cursor.map(|cursor| format_time(cursor.time)),
+            cursor.and_then(|cursor| cursor.id),
+        ),
+    };
+    let mut changed_ids = Vec::new();
+    let mut deleted = Vec::new();
+    for entry in entries {
+        match entry {
+            Entry::Changed(_, id) => changed_ids.push(id),
+            Entry::Deleted(time, id) => deleted.push(Tombstone {
+                id,
+                deleted_at: format_time(time),
+            }),
+        }
+    }
+
+    let changed = if changed_ids.is_empty() {
+        Vec::new()
+    } else {
+        let count = changed_ids.len() as u64;
+        let mut condition = Condition::all().add(T::ID_COLUMN.is_in(changed_ids));
+        if let Some(scope) = scope {
+            condition = condition.add(scope.clone());
+        }
+        let order = [
+            column_order(column, Order::Asc),
+            column_order(T::ID_COLUMN, Order::Asc),
+        ];
+        T::get_all_ordered(db, &condition, &order, 0, count, scope.is_some()).await?
+    };
+
+    Ok(Changes {
+        changed,
+        deleted,
+        since,
+        after_id,
+        has_more,
+    })
+}
+
+/// Body of the generated `get_changes_handler`. `S` is the scoped list model, returned
+/// when `scope` is set.
+///
+/// # Errors
+/// Returns any error from [`changes`].
+pub async fn changes_response<T, S>(
+    db: &DatabaseConnection,
+    options: SyncOptions,
+    scope: Option<Condition>,
+) -> Result<Response, ApiError>
+where
+    T: CRUDResource,
+    T::ListModel: Serialize,
+    S: From<T::ListModel> + Serialize,
+{
+    check_required_scope::<T>(scope.is_some())?;
+    let changes = changes::<T>(db, &options, scope.as_ref()).await?;
+    if scope.is_some() {
+        let changes = Changes {
+            changed: changes.changed.into_iter().map(S::from).collect::<Vec<S>>(),
+            deleted: changes.deleted,
+            since: changes.since,
+            after_id: changes.after_id,
+            has_more: changes.has_more,
+        };
+        Ok(Json(changes).into_response())
+    } else {
+        Ok(Json(changes).into_response())
+    }
+}
+
+/// Handler for `GET /changes`, invoked by the derive next to `crud_handlers!` for
+/// resources with `#[crudcrate(sync = "...")]`. `@no_openapi` leaves out
+/// `#[utoipa::path]`.
+#[macro_export]
+macro_rules! changes_handlers {
+    (@no_openapi $resource:ty, $list_model:ident, $scoped_list:ty) => {
+        crudcrate::changes_handlers!(@openapi false; $resource, $list_model, $scoped_list);
+    };
+    ($resource:ty, $list_model:ident, $scoped_list:ty) => {
+        crudcrate::changes_handlers!(@openapi true; $resource, $list_model, $scoped_list);
+    };
+    (@openapi $openapi:tt; $resource:ty, $list_model:ident, $scoped_list:ty) => {
+        crudcrate::__openapi_attr!($openapi,
+        #[utoipa::path(
+            get,
+            path = "/changes",
+            responses(
+                (status = axum::http::StatusCode::OK, description = "Resources changed and deleted after the cursor", body = crudcrate::sync::Changes<$list_model>),
+                (status = axum::http::StatusCode::BAD_REQUEST, description = "Invalid cursor or limit"),
+                (status = axum::http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error")
+            ),
+            params(crudcrate::sync::SyncOptions),
+            operation_id = format!("get_changes_{}", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            summary = format!("Get the {} changed since a timestamp", <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL),
+            description = format!(
+                "Retrieves the {} created or updated after `since`, and tombstones for those deleted after it, oldest first (at most {} at a time).",
+                <$resource as crudcrate::CRUDResource>::RESOURCE_NAME_PLURAL,
+                <$resource as crudcrate::CRUDResource>::max_page_size()
+            )
+        )]
+        pub async fn get_changes_handler(
+            axum::extract::State(db): axum::extract::State<sea_orm::DatabaseConnection>,
+            axum::extract::Query(options): axum::extract::Query<crudcrate::sync::SyncOptions>,
+            scope: Option<axum::Extension<crudcrate::ScopeCondition>>,
+        ) -> Result<axum::response::Response, crudcrate::ApiError> {
+            crudcrate::sync::changes_response::<$resource, $scoped_list>(
+                &db,
+                options,
+                scope.map(|axum::Extension(scope)| scope.condition),
+            )
+            .await
+        });
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cursor() {
+        assert!(parse_cursor(&SyncOptions::default()).unwrap().is_none());
+
+        let cursor = parse_cursor(&SyncOptions {
+            since: Some("2024-05-01T12:00:00+02:00".into()),
+            after_id: Some(Uuid::nil().to_string()),
+            limit: None,
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(format_time(cursor.time), "2024-05-01T10:00:00Z");
+        assert_eq!(cursor.id, Some(Uuid::nil()));
+
+        for (since, after_id) in [
+            (Some("yesterday"), None),
+            (Some("2024-05-01T10:00:00Z"), Some("42")),
+            (None, Some("00000000-0000-0000-0000-000000000000")),
+        ] {
+            let options = SyncOptions {
+                since: since.map(Into::into),
+                after_id: after_id.map(Into::into),
+                limit: None,
+            };
+            assert!(parse_cursor(&options).is_err(), "{since:?} {after_id:?}");
+        }
+    }
+}
@@ -0,0 +1,511 @@
+//! Request spans, database query spans and W3C trace context propagation.
+//!
+//! The generated routers run every request inside a `crudcrate.request` span (see
+//! [`trace`]) carrying the method, route, resource and response status. An incoming
+//! [`traceparent`](https://www.w3.org/TR/trace-context/) header is parsed into a
+//! [`TraceContext`] that hooks, operations and custom bodies read with
+//! [`trace_context`], for example to forward it to the services they call:
+//!
+//! ```rust,ignore
+//! async fn notify_billing(_db: &DatabaseConnection, created: &Invoice) -> Result<(), ApiError> {
+//!     let mut request = billing.post("/invoices").json(created);
+//!     if let Some(context) = crudcrate::telemetry::trace_context() {
+//!         request = request.header("traceparent", context.traceparent());
+//!     }
+//!     request.send().await.map_err(ApiError::internal_error)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`instrument`] adds a span per query run on a connection, named after its statement
+//! kind and table (`SELECT todos`), as a child of the span it runs in:
+//!
+//! ```rust,ignore
+//! let mut db = Database::connect(url).await?;
+//! crudcrate::telemetry::instrument(&mut db);
+//! let app = Router::new().nest("/todos", Todo::router(&db).into());
+//! ```
+//!
+//! Every request also gets a request ID: the incoming `X-Request-Id` header, or a new
+//! UUID when it has none or an invalid one. It is recorded on the request span, echoed
+//! in the response's `X-Request-Id` header, added as `request_id` to error bodies and
+//! webhook payloads, and readable with [`request_id`], so a failed mutation can be
+//! followed across services:
+//!
+//! ```text
+//! PUT /todos/5f0c…
+//! X-Request-Id: checkout-7f3a
+//!
+//! 409 Conflict
+//! X-Request-Id: checkout-7f3a
+//! {"error": "...", "request_id": "checkout-7f3a"}
+//! ```
+//!
+//! The trace context and request ID belong to the request's future: tasks a hook
+//! spawns see neither. Read them first and wrap the task in [`with_request_id`] or
+//! [`with_trace_context`] to carry them over.
+//!
+//! With the `otel` feature, and a `tracing-opentelemetry` layer installed on the
+//! subscriber, the request span continues the trace of the incoming `traceparent` and
+//! query spans are exported through the global OpenTelemetry tracer with their real
+//! start and end times. Without it the spans are plain `tracing` spans: the request span
+//! records the incoming `trace_id` for log correlation, and query spans record their
+//! duration in `elapsed_ms`.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use sea_orm::{DatabaseConnection, DbBackend};
+use tracing::Instrument;
+
+use crate::core::traits::CRUDResource;
+use crate::scoped::Scoped;
+
+/// Header carrying the request ID, read from requests and set on responses
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest incoming request ID kept; longer ones are replaced
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+thread_local! {
+    static CURRENT: RefCell<RequestScope> = const {
+        RefCell::new(RequestScope {
+            context: None,
+            request_id: None,
+        })
+    };
+}
+
+/// Trace context and request ID of the request being handled
+#[derive(Clone)]
+struct RequestScope {
+    context: Option<TraceContext>,
+    request_id: Option<String>,
+}
+
+/// Trace context of an incoming request, from its `traceparent` and `tracestate` headers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    trace_id: String,
+    parent_id: String,
+    flags: u8,
+    tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Parse a version `00` `traceparent` value (`00-<trace-id>-<parent-id>-<flags>`).
+    /// Returns `None` when it is malformed or its IDs are all zeros.
+    #[must_use]
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let mut parts = traceparent.trim().split('-');
+        let (Some(version), Some(trace_id), Some(parent_id), Some(flags), None) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return None;
+        };
+        let is_hex = |value: &str, len: usize| {
+            value.len() == len
+                && value
+                    .bytes()
+                    .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte))
+        };
+        let all_zeros = |value: &str| value.bytes().all(|byte| byte == b'0');
+        if version != "00"
+            || !is_hex(trace_id, 32)
+            || !is_hex(parent_id, 16)
+            || !is_hex(flags, 2)
+            || all_zeros(trace_id)
+            || all_zeros(parent_id)
+        {
+            return None;
+        }
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            parent_id: parent_id.to_string(),
+            flags: u8::from_str_radix(flags, 16).ok()?,
+            tracestate: None,
+        })
+    }
+
+    /// The context of a request's `traceparent` header, with its `tracestate`
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let mut context = Self::parse(header("traceparent")?)?;
+        context.tracestate = header("tracestate")
+            .filter(|state| !state.is_empty())
+            .map(str::to_string);
+        Some(context)
+    }
+
+    /// 32 lowercase hex digits identifying the trace
+    #[must_use]
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// 16 lowercase hex digits identifying the caller's span
+    #[must_use]
+    pub fn parent_id(&self) -> &str {
+        &self.parent_id
+    }
+
+    /// Whether the caller sampled the trace
+    #[must_use]
+    pub fn sampled(&self) -> bool {
+        self.flags & 1 == 1
+    }
+
+    /// The vendor-specific `tracestate` header, passed on unchanged
+    #[must_use]
+    pub fn tracestate(&self) -> Option<&str> {
+        self.tracestate.as_deref()
+    }
+
+    /// The `traceparent` header value to forward to downstream services
+    #[must_use]
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-{:02x}", self.trace_id, self.parent_id, self.flags)
+    }
+}
+
+/// The trace context of the request being handled, if it came with a valid
+/// `traceparent` header.
+#[must_use]
+pub fn trace_context() -> Option<TraceContext> {
+    CURRENT.with(|current| current.borrow().context.clone())
+}
+
+/// The ID of the request being handled, from its `X-Request-Id` header or generated
+/// by [`trace`]. `None` outside the generated routers.
+#[must_use]
+pub fn request_id() -> Option<String> {
+    CURRENT.with(|current| current.borrow().request_id.clone())
+}
+
+/// The request ID to use for an incoming `X-Request-Id` value: the value itself when
+/// it is 1 to 128 visible ASCII characters, otherwise a new UUID
+#[must_use]
+pub fn resolve_request_id(header: Option<&str>) -> String {
+    header
+        .map(str::trim)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LENGTH
+                && id.bytes().all(|byte| byte.is_ascii_graphic())
+        })
+        .map_or_else(|| uuid::Uuid::new_v4().to_string(), str::to_string)
+}
+
+/// Run `future` with `context` visible to [`trace_context`]. Scopes nest: the inner
+/// context applies until the inner future completes. The [`request_id`] current when
+/// it is called carries over, so background work keeps the request's ID.
+pub fn with_trace_context<F: Future>(
+    context: Option<TraceContext>,
+    future: F,
+) -> WithTraceContext<F> {
+    scope(context, request_id(), future)
+}
+
+/// Run `future` with `request_id` visible to [`request_id`], keeping the current trace
+/// context. For work outside the generated routers that continues a request, such as a
+/// queue consumer or a task spawned by a hook.
+pub fn with_request_id<F: Future>(request_id: Option<String>, future: F) -> WithTraceContext<F> {
+    scope(trace_context(), request_id, future)
+}
+
+fn scope<F: Future>(
+    context: Option<TraceContext>,
+    request_id: Option<String>,
+    future: F,
+) -> WithTraceContext<F> {
+    let scope = RequestScope {
+        context,
+        request_id,
+    };
+    WithTraceContext(Scoped::new(&CURRENT, scope, future))
+}
+
+/// Future returned by [`with_trace_context`] and [`with_request_id`]
+#[must_use = "futures do nothing unless polled"]
+pub struct WithTraceContext<F>(Scoped<RequestScope, F>);
+
+impl<F: Future> Future for WithTraceContext<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        Pin::new(&mut self.get_mut().0).poll(cx)
+    }
+}
+
+/// Run the rest of the request in a `crudcrate.request` span, with its `traceparent`
+/// visible to [`trace_context`] and its ID to [`request_id`]. The ID is echoed in the
+/// response's `X-Request-Id` header. Added to the generated routers with
+/// `axum::middleware::from_fn`.
+pub async fn trace<T: CRUDResource>(request: Request, next: Next) -> Response {
+    let context = TraceContext::from_headers(request.headers());
+    let request_id = resolve_request_id(
+        request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path(), MatchedPath::as_str)
+        .to_string();
+    let method = request.method().clone();
+    let span = tracing::info_span!(
+        "crudcrate.request",
+        otel.name = %format_args!("{method} {route}"),
+        otel.kind = "server",
+        http.request.method = %method,
+        http.route = %route,
+        resource = T::RESOURCE_NAME_PLURAL,
+        request_id = %request_id,
+        trace_id = tracing::field::Empty,
+        http.response.status_code = tracing::field::Empty,
+    );
+    if let Some(context) = &context {
+        span.record("trace_id", context.trace_id());
+        #[cfg(feature = "otel")]
+        otel::set_remote_parent(&span, context);
+    }
+    let scoped = scope(context, Some(request_id.clone()), next.run(request));
+    let mut response = scoped.instrument(span.clone()).await;
+    span.record("http.response.status_code", response.status().as_u16());
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Record a span for every query run on `db` and on its transactions, as a child of
+/// the span the query runs in. Replaces any metric callback already set on `db`.
+pub fn instrument(db: &mut DatabaseConnection) {
+    db.set_metric_callback(|info| {
+        let (kind, table) = statement_summary(&info.statement.sql);
+        let system = match info.statement.db_backend {
+            DbBackend::Postgres => "postgresql",
+            DbBackend::MySql => "mysql",
+            DbBackend::Sqlite => "sqlite",
+        };
+        #[cfg(feature = "otel")]
+        otel::record_query(system, kind, table, info.elapsed, info.failed);
+        #[cfg(not(feature = "otel"))]
+        {
+            let span = tracing::debug_span!(
+                "crudcrate.db",
+                otel.name = %format_args!("{kind} {}", table.unwrap_or_default()),
+                otel.kind = "client",
+                db.system.name = system,
+                db.operation.name = kind,
+                db.collection.name = table,
+                elapsed_ms = info.elapsed.as_secs_f64() * 1000.0,
+                error = info.failed,
+            );
+            drop(span);
+        }
+    });
+}
+
+/// The statement kind (`SELECT`, `INSERT`, ...) and, when it reads or writes a single
+/// named table, that table. Subqueries and unknown statements have no table.
+pub(crate) fn statement_summary(sql: &str) -> (&'static str, Option<&str>) {
+    let mut words = sql.split_whitespace();
+    let kind = match words.next().map(str::to_ascii_uppercase).as_deref() {
+        Some("SELECT") => "SELECT",
+        Some("INSERT") => "INSERT",
+        Some("UPDATE") => "UPDATE",
+        Some("DELETE") => "DELETE",
+        Some("WITH") => "WITH",
+        Some("CREATE") => "CREATE",
+        Some("ALTER") => "ALTER",
+        Some("DROP") => "DROP",
+        _ => return ("OTHER", None),
+    };
+    let table = match kind {
+        "SELECT" | "DELETE" => words.find(|word| word.eq_ignore_ascii_case("FROM")),
+        "INSERT" => words.find(|word| word.eq_ignore_ascii_case("INTO")),
+        "UPDATE" => Some("UPDATE"),
+        _ => None,
+    }
+    .and_then(|_| words.next())
+    .filter(|name| !name.starts_with('('))
+    .map(|name| {
+        name.trim_end_matches([',', ';', '('])
+            .trim_matches(['"', '`'])
+    })
+    .filter(|name| !name.is_empty());
+    (kind, table)
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::borrow::Cow;
+    use std::str::FromStr;
+    use std::time::{Duration, SystemTime};
+
+    use opentelemetry::trace::{
+        Span as _, SpanContext, SpanId, SpanKind, Status, TraceContextExt, TraceFlags, TraceId,
+        TraceState, Tracer as _,
+    };
+    use opentelemetry::{Context, KeyValue, global};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    use super::TraceContext;
+
+    /// Make `span` a child of the caller's span, continuing its trace
+    pub(super) fn set_remote_parent(span: &tracing::Span, context: &TraceContext) {
+        let (Ok(trace_id), Ok(span_id)) = (
+            TraceId::from_hex(context.trace_id()),
+            SpanId::from_hex(context.parent_id()),
+        ) else {
+            return;
+        };
+        let state = context
+            .tracestate()
+            .and_then(|state| TraceState::from_str(state).ok())
+            .unwrap_or_default();
+        let flags = if context.sampled() {
+            TraceFlags::SAMPLED
+        } else {
+            TraceFlags::default()
+        };
+        let parent = SpanContext::new(trace_id, span_id, flags, true, state);
+        // Fails only when no `tracing-opentelemetry` layer is installed
+        let _ = span.set_parent(Context::new().with_remote_span_context(parent));
+    }
+
+    /// Export a query span that ended now, under the current `tracing` span
+    pub(super) fn record_query(
+        system: &'static str,
+        kind: &'static str,
+        table: Option<&str>,
+        elapsed: Duration,
+        failed: bool,
+    ) {
+        let end = SystemTime::now();
+        let name = match table {
+            Some(table) => Cow::Owned(format!("{kind} {table}")),
+            None => Cow::Borrowed(kind),
+        };
+        let mut attributes = vec![
+            KeyValue::new("db.system.name", system),
+            KeyValue::new("db.operation.name", kind),
+        ];
+        if let Some(table) = table {
+            attributes.push(KeyValue::new("db.collection.name", table.to_string()));
+        }
+        let tracer = global::tracer("crudcrate");
+        let mut span = tracer
+            .span_builder(name)
+            .with_kind(SpanKind::Client)
+            .with_start_time(end.checked_sub(elapsed).unwrap_or(end))
+            .with_attributes(attributes)
+            .start_with_context(&tracer, &tracing::Span::current().context());
+        if failed {
+            span.set_status(Status::error("query failed"));
+        }
+        span.end_with_timestamp(end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_parse_traceparent() {
+        let context = TraceContext::parse(TRACEPARENT).unwrap();
+        assert_eq!(context.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.parent_id(), "00f067aa0ba902b7");
+        assert!(context.sampled());
+        assert_eq!(context.traceparent(), TRACEPARENT);
+
+        for invalid in [
+            "",
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ] {
+            assert_eq!(TraceContext::parse(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_from_headers_keeps_tracestate() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(TraceContext::from_headers(&headers), None);
+        headers.insert("traceparent", TRACEPARENT.parse().unwrap());
+        headers.insert("tracestate", "vendor=abc".parse().unwrap());
+        let context = TraceContext::from_headers(&headers).unwrap();
+        assert_eq!(context.tracestate(), Some("vendor=abc"));
+    }
+
+    #[test]
+    fn test_resolve_request_id() {
+        assert_eq!(resolve_request_id(Some("checkout-7f3a")), "checkout-7f3a");
+        assert_eq!(resolve_request_id(Some(" padded ")), "padded");
+        for invalid in [None, Some(""), Some("two words"), Some("é")] {
+            let generated = resolve_request_id(invalid);
+            assert!(uuid::Uuid::parse_str(&generated).is_ok(), "{invalid:?}");
+        }
+        let long = "a".repeat(MAX_REQUEST_ID_LENGTH + 1);
+        assert_ne!(resolve_request_id(Some(&long)), long);
+    }
+
+    #[test]
+    fn test_statement_summary() {
+        assert_eq!(
+            statement_summary(r#"SELECT "todos"."id" FROM "todos" WHERE "todos"."id" = $1"#),
+            ("SELECT", Some("todos"))
+        );
+        assert_eq!(
+            statement_summary("INSERT INTO `items` (`id`) VALUES (?)"),
+            ("INSERT", Some("items"))
+        );
+        assert_eq!(
+            statement_summary(r#"UPDATE "items" SET "name" = $1"#),
+            ("UPDATE", Some("items"))
+        );
+        assert_eq!(
+            statement_summary(r#"delete from "items""#),
+            ("DELETE", Some("items"))
+        );
+        assert_eq!(
+            statement_summary("SELECT COUNT(*) FROM (SELECT 1) AS sub"),
+            ("SELECT", None)
+        );
+        assert_eq!(statement_summary("BEGIN"), ("OTHER", None));
+    }
+
+    #[tokio::test]
+    async fn test_trace_context_is_scoped_to_future() {
+        let context = TraceContext::parse(TRACEPARENT);
+        assert_eq!(trace_context(), None);
+        let seen = with_trace_context(context.clone(), async {
+            tokio::task::yield_now().await;
+            trace_context()
+        })
+        .await;
+        assert_eq!(seen, context);
+        assert_eq!(trace_context(), None);
+    }
+}
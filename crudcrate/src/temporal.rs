@@ -0,0 +1,130 @@
+//! Validity windows for resources declared with `#[crudcrate(temporal)]`.
+//!
+//! Rows of a temporal resource are valid from their `valid_from` field until their
+//! `valid_to` field, as with prices or configuration that change on a schedule. The
+//! list endpoint and `GET /{id}` only return the rows valid now; `?as_of=` reads them
+//! at another instant:
+//!
+//! ```text
+//! GET /prices                                   # the prices in force now
+//! GET /prices?as_of=2025-01-01T00:00:00Z        # the prices on New Year's Day
+//! GET /prices/{id}?as_of=2024-12-31             # 404 unless valid at that date's midnight UTC
+//! ```
+//!
+//! A row is valid at `as_of` when `valid_from <= as_of < valid_to`. A `NULL` bound is
+//! open, so `valid_to = NULL` rows never expire. Date fields compare with the UTC date
+//! of `as_of`.
+//!
+//! Writes ignore the window, so expired and scheduled rows can still be updated and
+//! deleted by ID, and the other read endpoints (`by_ids`, `distinct`, `changes`, `join`
+//! fields pointing at the resource) return them too.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sea_orm::sea_query::ExprTrait;
+use sea_orm::{Condition, DatabaseConnection, Order};
+use serde::Deserialize;
+#[cfg(feature = "openapi")]
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::core::crud_operations::{column_order, find_one};
+use crate::filtering::dates::DateKind;
+use crate::{ApiError, CRUDResource};
+
+/// The fields bounding the rows of a `temporal` resource, as `(field, column, kind)`
+#[derive(Debug, Clone, Copy)]
+pub struct Validity<C> {
+    /// First instant a row is valid, `valid_from` by default
+    pub from: (&'static str, C, DateKind),
+    /// First instant a row is no longer valid, `valid_to` by default
+    pub to: (&'static str, C, DateKind),
+}
+
+/// Query parameters of `GET /{id}`
+#[derive(Deserialize, Default, Clone)]
+#[cfg_attr(feature = "openapi", derive(IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct AsOfOptions {
+    /// Instant to read a `temporal` resource at, as an RFC 3339 timestamp or a date
+    /// (midnight UTC). Defaults to now; ignored by other resources.
+    #[cfg_attr(feature = "openapi", param(example = "2024-05-01T10:00:00Z"))]
+    pub as_of: Option<String>,
+}
+
+/// Parse an `as_of` parameter, or now without one.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` unless `raw` is an RFC 3339 timestamp or `YYYY-MM-DD`.
+pub fn parse_as_of(raw: Option<&str>) -> Result<DateTime<Utc>, ApiError> {
+    let Some(raw) = raw.map(str::trim) else {
+        return Ok(Utc::now());
+    };
+    DateTime::parse_from_rfc3339(raw)
+        .map(|time| time.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+        })
+        .map_err(|_| {
+            ApiError::bad_request("'as_of' must be an RFC 3339 timestamp or a date (YYYY-MM-DD)")
+        })
+}
+
+/// The rows of `T` valid at `as_of` (now without it), or `None` when `T` isn't temporal.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `as_of` doesn't parse.
+pub fn condition<T: CRUDResource>(as_of: Option<&str>) -> Result<Option<Condition>, ApiError> {
+    let Some(validity) = T::temporal_columns() else {
+        return Ok(None);
+    };
+    let time = parse_as_of(as_of)?;
+    let bound = |(field, column, kind): (&'static str, T::ColumnType, DateKind)| {
+        #[cfg(feature = "time")]
+        let time_crate = crate::filtering::timestamps::time_kind::<T>(field).is_some();
+        #[cfg(not(feature = "time"))]
+        let time_crate = false;
+        let value = crate::filtering::dates::instant_value(field, kind, time_crate, time)?;
+        Ok::<_, ApiError>((column_order(column, Order::Asc).0, value))
+    };
+    let (from, start) = bound(validity.from)?;
+    let (to, end) = bound(validity.to)?;
+    Ok(Some(
+        Condition::all()
+            .add(from.clone().is_null().or(from.lte(start)))
+            .add(to.clone().is_null().or(to.gt(end))),
+    ))
+}
+
+/// `ApiError::NotFound` unless the row `id` of `T` is valid at `as_of`. Used by
+/// `GET /{id}` before loading the item through `get_one`; does nothing for other
+/// resources.
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if `as_of` doesn't parse, `ApiError::NotFound` if the
+/// row doesn't exist or isn't valid, or `ApiError::Database` if the query fails.
+pub async fn ensure_valid<T: CRUDResource>(
+    db: &DatabaseConnection,
+    id: Uuid,
+    as_of: Option<&str>,
+) -> Result<(), ApiError> {
+    match condition::<T>(as_of)? {
+        Some(valid) => find_one::<T>(db, id, Some(&valid)).await.map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_as_of() {
+        let time = parse_as_of(Some("2024-05-01T12:00:00+02:00")).unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-05-01T10:00:00+00:00");
+        let date = parse_as_of(Some(" 2024-05-01 ")).unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-05-01T00:00:00+00:00");
+        assert!(parse_as_of(Some("yesterday")).is_err());
+        assert!(parse_as_of(None).is_ok());
+    }
+}
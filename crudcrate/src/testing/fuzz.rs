@@ -0,0 +1,432 @@
+//! Randomised filter, sort and pagination requests against a list endpoint.
+//!
+//! [`filter_fuzz`] builds query strings from a resource's filterable and sortable
+//! columns, mixing valid operators and values with malformed JSON, unknown fields,
+//! extreme numbers and hostile strings, and asserts that the list handler neither
+//! panics nor answers with a 5xx status. Runs are deterministic for a given
+//! [`FuzzConfig::seed`], so a failure can be replayed.
+//!
+//! ```rust,ignore
+//! #[tokio::test]
+//! async fn list_endpoint_survives_fuzzing() {
+//!     let db = setup_sqlite().await;
+//!     let app = Router::new().nest("/todos", Todo::router(&db).into());
+//!     crudcrate::testing::filter_fuzz::<Todo>(&app, "/todos").await;
+//! }
+//! ```
+//!
+//! Run it against a real database: requests that reach the mock connection of
+//! [`mock_connection`](super::mock_connection) fail with 500s by design.
+
+use std::fmt::Write as _;
+
+use crate::core::CRUDResource;
+
+/// Settings for [`filter_fuzz_with`]
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzConfig {
+    /// Number of requests to send
+    pub iterations: usize,
+    /// Seed for the request generator
+    pub seed: u64,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 200,
+            seed: 0x5EED_CAFE,
+        }
+    }
+}
+
+/// Fuzz `GET {base_path}` with the default [`FuzzConfig`].
+///
+/// # Panics
+/// Panics if a request returns a 5xx status or the handler panics.
+pub async fn filter_fuzz<T: CRUDResource>(app: &axum::Router, base_path: &str) {
+    filter_fuzz_with::<T>(app, base_path, FuzzConfig::default()).await;
+}
+
+/// Fuzz `GET {base_path}` with random `filter`, `sort`, `range` and paging parameters.
+///
+/// # Panics
+/// Panics if a request returns a 5xx status or the handler panics. The message names
+/// the seed, the iteration and the request URI.
+pub async fn filter_fuzz_with<T: CRUDResource>(
+    app: &axum::Router,
+    base_path: &str,
+    config: FuzzConfig,
+) {
+    let generator = QueryGenerator::for_resource::<T>();
+    let mut rng = Rng(config.seed);
+
+    for iteration in 0..config.iterations {
+        let uri = format!("{base_path}?{}", generator.query(&mut rng));
+        let guard = ReportOnPanic {
+            uri: &uri,
+            seed: config.seed,
+            iteration,
+        };
+        let (status, body) = super::send(app, "GET", &uri, None).await;
+        drop(guard);
+        assert!(
+            !status.is_server_error(),
+            "filter_fuzz (seed {}, iteration {iteration}): GET {uri} returned {status}: {body}",
+            config.seed
+        );
+    }
+}
+
+/// Names the failing request if a handler panics mid-request
+struct ReportOnPanic<'a> {
+    uri: &'a str,
+    seed: u64,
+    iteration: usize,
+}
+
+impl Drop for ReportOnPanic<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!(
+                "filter_fuzz (seed {}, iteration {}): handler panicked on GET {}",
+                self.seed, self.iteration, self.uri
+            );
+        }
+    }
+}
+
+/// `SplitMix64`: small, fast and good enough to spread test inputs
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n` (`n` must be non-zero)
+    fn below(&mut self, n: usize) -> usize {
+        let n = u64::try_from(n).unwrap_or(u64::MAX);
+        usize::try_from(self.next_u64() % n).unwrap_or(0)
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+
+    fn pick<'a, V>(&mut self, items: &'a [V]) -> &'a V {
+        &items[self.below(items.len())]
+    }
+}
+
+const SUFFIXES: &[&str] = &[
+    "",
+    "",
+    "",
+    "_neq",
+    "_gt",
+    "_gte",
+    "_lt",
+    "_lte",
+    "_between",
+    "_contains",
+    "_overlaps",
+    "_date",
+    "_year",
+    "_date_gte",
+    "_year_lt",
+    "_within",
+];
+
+const BOGUS_FIELDS: &[&str] = &[
+    "q",
+    "id",
+    "does_not_exist",
+    "name; DROP TABLE users",
+    "a.b.c",
+    "",
+    "_gt",
+    "x\"y",
+    "__proto__",
+];
+
+const STRINGS: &[&str] = &[
+    "",
+    " ",
+    "a",
+    "%",
+    "_",
+    "\\",
+    "'",
+    "\"",
+    "'; DROP TABLE items; --",
+    "%' OR '1'='1",
+    "ñandú",
+    "日本語",
+    "\u{0}",
+    "null",
+    "true",
+    "[1,2]",
+    "{\"a\":1}",
+    "2024-05-01",
+    "2024-05-01T10:00:00Z",
+    "2024-05-01+02:00",
+    "2024-02-30",
+    "9999-99-99",
+    "2024",
+    "550e8400-e29b-41d4-a716-446655440000",
+    "not-a-uuid",
+    "12.50",
+    "-0.0001",
+    "1e400",
+    "NaN",
+    "Infinity",
+];
+
+const MALFORMED_FILTERS: &[&str] = &[
+    "{",
+    "}",
+    "[]",
+    "null",
+    "\"x\"",
+    "42",
+    "{\"a\":}",
+    "{\"a\" 1}",
+    "",
+    "{{}}",
+];
+
+const SORT_DIRECTIONS: &[&str] = &["ASC", "DESC", "asc", "desc", "sideways", ""];
+
+const MALFORMED_SORTS: &[&str] = &["[", "[\"a\"]", "[1,2,3]", "\"name\"", "[null,null]", "{}"];
+
+const RANGES: &[&str] = &[
+    "[0,9]",
+    "[5,2]",
+    "[-1,10]",
+    "[0,18446744073709551615]",
+    "[18446744073709551615,0]",
+    "[\"a\",\"b\"]",
+    "[",
+    "[1]",
+    "",
+];
+
+const PAGE_VALUES: &[&str] = &[
+    "0",
+    "1",
+    "2",
+    "1000000",
+    "18446744073709551615",
+    "-1",
+    "x",
+    "",
+];
+
+/// Builds query strings for one resource
+struct QueryGenerator {
+    filter_fields: Vec<String>,
+    sort_fields: Vec<String>,
+}
+
+impl QueryGenerator {
+    fn for_resource<T: CRUDResource>() -> Self {
+        let mut filter_fields: Vec<String> = T::filterable_columns()
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        filter_fields.extend(
+            T::json_filterable_columns()
+                .into_iter()
+                .flat_map(|name| [format!("{name}.key"), format!("{name}.nested.0")]),
+        );
+        filter_fields.extend(
+            T::joined_filterable_columns()
+                .into_iter()
+                .map(|column| column.full_path.to_string()),
+        );
+
+        let mut sort_fields: Vec<String> = T::sortable_columns()
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        sort_fields.extend(
+            T::joined_sortable_columns()
+                .into_iter()
+                .map(|column| column.full_path.to_string()),
+        );
+
+        Self {
+            filter_fields,
+            sort_fields,
+        }
+    }
+
+    fn query(&self, rng: &mut Rng) -> String {
+        let mut params = Vec::new();
+        if rng.chance(80) {
+            params.push(("filter", self.filter(rng)));
+        }
+        if rng.chance(40) {
+            params.push(("sort", self.sort(rng)));
+        } else if rng.chance(20) {
+            params.push(("sort_by", field(rng, &self.sort_fields)));
+            params.push(("order", (*rng.pick(SORT_DIRECTIONS)).to_string()));
+        }
+        if rng.chance(30) {
+            params.push(("range", (*rng.pick(RANGES)).to_string()));
+        } else if rng.chance(20) {
+            params.push(("page", (*rng.pick(PAGE_VALUES)).to_string()));
+            params.push(("per_page", (*rng.pick(PAGE_VALUES)).to_string()));
+        }
+
+        params
+            .into_iter()
+            .map(|(key, value)| format!("{key}={}", encode(&value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    fn filter(&self, rng: &mut Rng) -> String {
+        if rng.chance(10) {
+            return (*rng.pick(MALFORMED_FILTERS)).to_string();
+        }
+        if rng.chance(2) {
+            // Deeper than serde_json's recursion limit
+            return format!("{}{}", "[".repeat(200), "]".repeat(200));
+        }
+
+        let mut filter = serde_json::Map::new();
+        for _ in 0..=rng.below(3) {
+            let key = format!("{}{}", field(rng, &self.filter_fields), rng.pick(SUFFIXES));
+            filter.insert(key, value(rng, 0));
+        }
+        if rng.chance(20) {
+            filter.insert("q".to_string(), value(rng, 1));
+        }
+        serde_json::Value::Object(filter).to_string()
+    }
+
+    fn sort(&self, rng: &mut Rng) -> String {
+        if rng.chance(15) {
+            return (*rng.pick(MALFORMED_SORTS)).to_string();
+        }
+        serde_json::json!([field(rng, &self.sort_fields), rng.pick(SORT_DIRECTIONS)]).to_string()
+    }
+}
+
+/// A real field most of the time, otherwise something that isn't one
+fn field(rng: &mut Rng, fields: &[String]) -> String {
+    if fields.is_empty() || rng.chance(15) {
+        (*rng.pick(BOGUS_FIELDS)).to_string()
+    } else {
+        rng.pick(fields).clone()
+    }
+}
+
+/// A random JSON value; arrays and objects nest at most two levels
+fn value(rng: &mut Rng, depth: usize) -> serde_json::Value {
+    use serde_json::{Value, json};
+
+    let kinds = if depth >= 2 { 8 } else { 10 };
+    match rng.below(kinds) {
+        0 => Value::Null,
+        1 => Value::Bool(rng.chance(50)),
+        2 => json!(rng.below(100)),
+        3 => rng
+            .pick(&[
+                json!(i64::MIN),
+                json!(i64::MAX),
+                json!(u64::MAX),
+                json!(-1),
+                json!(0),
+            ])
+            .clone(),
+        4 => rng
+            .pick(&[
+                json!(0.5),
+                json!(-0.0),
+                json!(1e308),
+                json!(-1e-308),
+                json!(3.75),
+            ])
+            .clone(),
+        5 | 6 => Value::String((*rng.pick(STRINGS)).to_string()),
+        7 => Value::String(random_string(rng)),
+        8 => Value::Array((0..rng.below(4)).map(|_| value(rng, depth + 1)).collect()),
+        _ => json!({
+            "lat": value(rng, depth + 1),
+            "lon": value(rng, depth + 1),
+            "radius": value(rng, depth + 1),
+        }),
+    }
+}
+
+/// Printable ASCII, occasionally long enough to hit input length limits
+fn random_string(rng: &mut Rng) -> String {
+    let len = if rng.chance(5) { 10_001 } else { rng.below(24) };
+    (0..len)
+        .map(|_| {
+            let offset = u8::try_from(rng.below(95)).unwrap_or(0);
+            char::from(b' ' + offset)
+        })
+        .collect()
+}
+
+/// Percent-encode a query parameter value
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = Rng(7);
+        let mut b = Rng(7);
+        let first: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        let second: Vec<u64> = (0..5).map(|_| b.next_u64()).collect();
+        assert_eq!(first, second);
+        assert!((0..1000).all(|_| a.below(3) < 3));
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("a-b_c.d~"), "a-b_c.d~");
+        assert_eq!(encode("{\"a\": 1}"), "%7B%22a%22%3A%201%7D");
+        assert_eq!(encode("ñ"), "%C3%B1");
+    }
+
+    #[test]
+    fn test_generated_filters_mix_valid_and_invalid_input() {
+        let generator = QueryGenerator {
+            filter_fields: vec!["name".to_string()],
+            sort_fields: vec!["name".to_string()],
+        };
+        let mut rng = Rng(1);
+        let filters: Vec<String> = (0..500).map(|_| generator.filter(&mut rng)).collect();
+
+        let parsed = filters
+            .iter()
+            .filter(|f| serde_json::from_str::<serde_json::Value>(f).is_ok_and(|v| v.is_object()))
+            .count();
+        assert!(parsed > 300, "{parsed}");
+        assert!(parsed < 500, "{parsed}");
+        assert!(filters.iter().any(|f| f.contains("\"name")));
+        assert!(filters.iter().any(|f| f.contains("does_not_exist")));
+    }
+}
@@ -0,0 +1,458 @@
+//! In-memory resources for unit testing generated handlers.
+//!
+//! [`MockResource`] stores a resource's models in a `HashMap` and implements
+//! [`CRUDOperations`], so a generated router can be mounted against it with
+//! `router_with_ops` and exercised without a real database:
+//!
+//! ```rust,ignore
+//! use crudcrate::testing::{MockResource, mock_connection, send};
+//!
+//! let store = MockResource::<Todo>::new().with_operations(TodoOps);
+//! let app = Router::new().nest(
+//!     "/todos",
+//!     Todo::router_with_ops(&mock_connection(), store.clone()).into(),
+//! );
+//!
+//! let (status, body) = send(&app, "POST", "/todos", Some(json!({"title": "Write tests"}))).await;
+//! assert_eq!(status, StatusCode::CREATED);
+//! assert_eq!(store.len(), 1);
+//! ```
+//!
+//! The `before_*`/`after_*` hooks of the wrapped operations (see
+//! [`MockResource::with_operations`]) run as usual, which makes authorization checks,
+//! validation and response serialization testable in isolation. Storage is simplified:
+//! filter conditions are parsed and validated by the handlers but not evaluated, so list
+//! requests return every stored item in insertion order (reversed for descending sorts),
+//! and the total count in `Content-Range` is always 0. Attribute hooks such as
+//! `create::one::pre` are part of the static `CRUDResource` methods and do not run.
+//!
+//! [`filter_fuzz`] sends randomised filter, sort and pagination queries to a list
+//! endpoint and fails if any of them panics or returns a 5xx status.
+//!
+//! Enable with the `testing` feature, usually as a dev-dependency.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use sea_orm::{
+    Condition, DatabaseConnection, EntityTrait, IntoActiveModel, Iterable, ModelTrait, Order,
+    PrimaryKeyToColumn, TryIntoModel,
+};
+use uuid::Uuid;
+
+use crate::core::CRUDResource;
+use crate::errors::ApiError;
+use crate::operations::{CRUDOperations, DefaultCRUDOperations};
+
+mod fuzz;
+
+pub use fuzz::{FuzzConfig, filter_fuzz, filter_fuzz_with};
+
+type ModelOf<T> = <<T as CRUDResource>::EntityType as EntityTrait>::Model;
+
+/// A database connection for mounting routers against a [`MockResource`].
+///
+/// Handlers use it to detect the backend while parsing filters. It is an in-memory
+/// `SQLite` pool that connects on first use and has no tables, so anything that still
+/// reaches the database gets an error instead of data.
+///
+/// # Panics
+/// Never in practice: the connection URL is a constant.
+#[must_use]
+pub fn mock_connection() -> DatabaseConnection {
+    // Without lifetimes and idle timeouts the pool spawns no maintenance task, so this
+    // works outside a runtime too
+    let pool = sea_orm::sqlx::sqlite::SqlitePoolOptions::new()
+        .max_lifetime(None)
+        .idle_timeout(None)
+        .connect_lazy("sqlite::memory:")
+        .expect("valid SQLite URL");
+    sea_orm::SqlxSqliteConnector::from_sqlx_sqlite_pool(pool)
+}
+
+/// Send a request to `app` and return the status and the JSON body
+/// (`Value::Null` for empty or non-JSON bodies).
+///
+/// # Panics
+/// Panics if the request cannot be built or the router fails to respond.
+pub async fn send(
+    app: &axum::Router,
+    method: &str,
+    uri: &str,
+    body: Option<serde_json::Value>,
+) -> (StatusCode, serde_json::Value) {
+    use tower::ServiceExt;
+
+    let mut request = axum::http::Request::builder().method(method).uri(uri);
+    let body = match body {
+        Some(json) => {
+            request = request.header(axum::http::header::CONTENT_TYPE, "application/json");
+            axum::body::Body::from(json.to_string())
+        }
+        None => axum::body::Body::empty(),
+    };
+    let response = app
+        .clone()
+        .oneshot(request.body(body).expect("valid request"))
+        .await
+        .expect("router responds");
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("readable response body");
+    let json = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+    (status, json)
+}
+
+/// In-memory storage for a resource, usable wherever [`CRUDOperations`] is accepted.
+///
+/// Clones share the same storage, so a test can keep one handle for assertions and
+/// pass another to `router_with_ops`. Models are keyed by their UUID primary key.
+pub struct MockResource<T: CRUDResource, O = DefaultCRUDOperations<T>> {
+    items: Arc<Mutex<MockItems<ModelOf<T>>>>,
+    hooks: Arc<O>,
+}
+
+struct MockItems<M> {
+    next_seq: u64,
+    by_id: HashMap<Uuid, (u64, M)>,
+}
+
+impl<T: CRUDResource> MockResource<T> {
+    /// Create an empty store that runs no hooks
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            items: Arc::new(Mutex::new(MockItems {
+                next_seq: 0,
+                by_id: HashMap::new(),
+            })),
+            hooks: Arc::new(DefaultCRUDOperations::new()),
+        }
+    }
+}
+
+impl<T: CRUDResource> Default for MockResource<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: CRUDResource, O> Clone for MockResource<T, O> {
+    fn clone(&self) -> Self {
+        Self {
+            items: Arc::clone(&self.items),
+            hooks: Arc::clone(&self.hooks),
+        }
+    }
+}
+
+impl<T: CRUDResource, O> MockResource<T, O> {
+    /// Run the `before_*`/`after_*` hooks of `ops` around the in-memory storage.
+    ///
+    /// Only the hooks are used; `ops`'s `fetch_*`/`perform_*` methods are replaced by
+    /// the store.
+    #[must_use]
+    pub fn with_operations<P>(self, ops: P) -> MockResource<T, P>
+    where
+        P: CRUDOperations<Resource = T>,
+    {
+        MockResource {
+            items: self.items,
+            hooks: Arc::new(ops),
+        }
+    }
+
+    /// Add a model to the store, replacing any model with the same primary key
+    ///
+    /// # Errors
+    /// Returns `ApiError::Internal` if the model's primary key is not a UUID.
+    pub fn insert(&self, model: ModelOf<T>) -> Result<(), ApiError> {
+        let id = model_id::<T>(&model)?;
+        self.store(id, model);
+        Ok(())
+    }
+
+    /// Add several models to the store
+    ///
+    /// # Errors
+    /// Returns `ApiError::Internal` if a model's primary key is not a UUID.
+    pub fn with_items(
+        self,
+        models: impl IntoIterator<Item = ModelOf<T>>,
+    ) -> Result<Self, ApiError> {
+        for model in models {
+            self.insert(model)?;
+        }
+        Ok(self)
+    }
+
+    /// The stored item with this ID, if any
+    #[must_use]
+    pub fn get(&self, id: Uuid) -> Option<T> {
+        self.lock()
+            .by_id
+            .get(&id)
+            .map(|(_, model)| T::from(model.clone()))
+    }
+
+    /// All stored items in insertion order
+    #[must_use]
+    pub fn items(&self) -> Vec<T> {
+        self.sorted_models().into_iter().map(T::from).collect()
+    }
+
+    /// Number of stored items
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().by_id.len()
+    }
+
+    /// Whether the store is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().by_id.is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockItems<ModelOf<T>>> {
+        // A panicking test must not poison the store for the assertions that follow
+        self.items
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn store(&self, id: Uuid, model: ModelOf<T>) {
+        let mut items = self.lock();
+        let seq = if let Some((seq, _)) = items.by_id.get(&id) {
+            *seq
+        } else {
+            items.next_seq += 1;
+            items.next_seq
+        };
+        items.by_id.insert(id, (seq, model));
+    }
+
+    fn find(&self, id: Uuid) -> Result<ModelOf<T>, ApiError> {
+        self.lock()
+            .by_id
+            .get(&id)
+            .map(|(_, model)| model.clone())
+            .ok_or_else(|| ApiError::not_found(T::RESOURCE_NAME_SINGULAR, Some(id.to_string())))
+    }
+
+    fn sorted_models(&self) -> Vec<ModelOf<T>> {
+        let items = self.lock();
+        let mut models: Vec<_> = items.by_id.values().collect();
+        models.sort_by_key(|(seq, _)| *seq);
+        models.into_iter().map(|(_, model)| model.clone()).collect()
+    }
+}
+
+/// Read the UUID primary key of a model
+fn model_id<T: CRUDResource>(model: &ModelOf<T>) -> Result<Uuid, ApiError> {
+    let key = <<T::EntityType as EntityTrait>::PrimaryKey as Iterable>::iter().next();
+    match key.map(|key| model.get(key.into_column())) {
+        Some(sea_orm::Value::Uuid(Some(id))) => Ok(*id),
+        _ => Err(ApiError::internal(
+            format!(
+                "MockResource for '{}' requires a UUID primary key",
+                T::TABLE_NAME
+            ),
+            None,
+        )),
+    }
+}
+
+/// Build a model from an active model, as an insert or update would
+fn into_model<T: CRUDResource>(active_model: T::ActiveModelType) -> Result<ModelOf<T>, ApiError>
+where
+    T::ActiveModelType: TryIntoModel<ModelOf<T>>,
+{
+    active_model.try_into_model().map_err(ApiError::database)
+}
+
+#[async_trait]
+impl<T, O> CRUDOperations for MockResource<T, O>
+where
+    T: CRUDResource + 'static,
+    T::ActiveModelType: TryIntoModel<ModelOf<T>>,
+    T::CreateModel: Sync,
+    O: CRUDOperations<Resource = T> + 'static,
+{
+    type Resource = T;
+
+    async fn before_get_one(&self, db: &DatabaseConnection, id: Uuid) -> Result<(), ApiError> {
+        self.hooks.before_get_one(db, id).await
+    }
+
+    async fn after_get_one(&self, db: &DatabaseConnection, entity: &mut T) -> Result<(), ApiError> {
+        self.hooks.after_get_one(db, entity).await
+    }
+
+    async fn fetch_one(&self, _db: &DatabaseConnection, id: Uuid) -> Result<T, ApiError> {
+        self.find(id).map(T::from)
+    }
+
+    async fn before_get_all(
+        &self,
+        db: &DatabaseConnection,
+        condition: &Condition,
+        order_column: T::ColumnType,
+        order_direction: &Order,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(), ApiError> {
+        self.hooks
+            .before_get_all(db, condition, order_column, order_direction, offset, limit)
+            .await
+    }
+
+    async fn after_get_all(
+        &self,
+        db: &DatabaseConnection,
+        entities: &mut Vec<T::ListModel>,
+    ) -> Result<(), ApiError> {
+        self.hooks.after_get_all(db, entities).await
+    }
+
+    async fn fetch_all(
+        &self,
+        _db: &DatabaseConnection,
+        _condition: &Condition,
+        _order_column: T::ColumnType,
+        order_direction: Order,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<T::ListModel>, ApiError> {
+        let mut models = self.sorted_models();
+        if matches!(order_direction, Order::Desc) {
+            models.reverse();
+        }
+        Ok(models
+            .into_iter()
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(usize::try_from(limit).unwrap_or(usize::MAX))
+            .map(|model| T::ListModel::from(T::from(model)))
+            .collect())
+    }
+
+    async fn before_create(
+        &self,
+        db: &DatabaseConnection,
+        data: &T::CreateModel,
+    ) -> Result<(), ApiError> {
+        self.hooks.before_create(db, data).await
+    }
+
+    async fn after_create(&self, db: &DatabaseConnection, entity: &mut T) -> Result<(), ApiError> {
+        self.hooks.after_create(db, entity).await
+    }
+
+    async fn perform_create(
+        &self,
+        db: &DatabaseConnection,
+        data: T::CreateModel,
+    ) -> Result<T, ApiError> {
+        let model = into_model::<T>(T::create_active_model(db, data).await?)?;
+        let id = model_id::<T>(&model)?;
+        self.store(id, model.clone());
+        Ok(T::from(model))
+    }
+
+    async fn before_update(
+        &self,
+        db: &DatabaseConnection,
+        id: Uuid,
+        data: &T::UpdateModel,
+    ) -> Result<(), ApiError> {
+        self.hooks.before_update(db, id, data).await
+    }
+
+    async fn after_update(&self, db: &DatabaseConnection, entity: &mut T) -> Result<(), ApiError> {
+        self.hooks.after_update(db, entity).await
+    }
+
+    async fn perform_update(
+        &self,
+        db: &DatabaseConnection,
+        id: Uuid,
+        data: T::UpdateModel,
+    ) -> Result<T, ApiError> {
+        let existing = self.find(id)?.into_active_model();
+        let model = into_model::<T>(T::update_active_model(db, data, existing).await?)?;
+        self.store(id, model.clone());
+        Ok(T::from(model))
+    }
+
+    async fn before_delete(&self, db: &DatabaseConnection, id: Uuid) -> Result<(), ApiError> {
+        self.hooks.before_delete(db, id).await
+    }
+
+    async fn after_delete(&self, db: &DatabaseConnection, id: Uuid) -> Result<(), ApiError> {
+        self.hooks.after_delete(db, id).await
+    }
+
+    async fn perform_delete(&self, _db: &DatabaseConnection, id: Uuid) -> Result<Uuid, ApiError> {
+        match self.lock().by_id.remove(&id) {
+            Some(_) => Ok(id),
+            None => Err(ApiError::not_found(
+                T::RESOURCE_NAME_SINGULAR,
+                Some(id.to_string()),
+            )),
+        }
+    }
+
+    async fn before_delete_many(
+        &self,
+        db: &DatabaseConnection,
+        ids: &[Uuid],
+    ) -> Result<(), ApiError> {
+        self.hooks.before_delete_many(db, ids).await
+    }
+
+    async fn after_delete_many(
+        &self,
+        db: &DatabaseConnection,
+        ids: &[Uuid],
+    ) -> Result<(), ApiError> {
+        self.hooks.after_delete_many(db, ids).await
+    }
+
+    async fn perform_delete_many(
+        &self,
+        _db: &DatabaseConnection,
+        ids: Vec<Uuid>,
+    ) -> Result<Vec<Uuid>, ApiError> {
+        let mut items = self.lock();
+        Ok(ids
+            .into_iter()
+            .filter(|id| items.by_id.remove(id).is_some())
+            .collect())
+    }
+
+    async fn create_many(
+        &self,
+        db: &DatabaseConnection,
+        data: Vec<T::CreateModel>,
+    ) -> Result<Vec<T>, ApiError> {
+        let mut created = Vec::with_capacity(data.len());
+        for item in data {
+            created.push(self.create(db, item).await?);
+        }
+        Ok(created)
+    }
+
+    async fn update_many(
+        &self,
+        db: &DatabaseConnection,
+        updates: Vec<(Uuid, T::UpdateModel)>,
+    ) -> Result<Vec<T>, ApiError> {
+        let mut results = Vec::with_capacity(updates.len());
+        for (id, data) in updates {
+            results.push(self.update(db, id, data).await?);
+        }
+        Ok(results)
+    }
+}
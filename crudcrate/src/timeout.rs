@@ -0,0 +1,51 @@
+//! Per-resource handler deadlines, set with `#[crudcrate(timeout_ms = 5000)]`.
+//!
+//! The generated routers run every handler through [`enforce`]. When
+//! [`CRUDResource::operation_timeout`] elapses first, the handler's future is dropped
+//! and the request gets `504 Gateway Timeout`, so a slow query can't hold a worker
+//! task indefinitely.
+//!
+//! Dropping the handler is cancellation-safe for the database: a Sea-ORM
+//! `DatabaseTransaction` that hasn't been committed is rolled back when dropped, so
+//! batch operations, nested creates and custom bodies using transactions leave no
+//! partial writes. Statements outside a transaction that already completed stay
+//! applied, as with any aborted request.
+
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::ApiError;
+use crate::core::traits::CRUDResource;
+
+/// Run the rest of the request under `T`'s [`operation_timeout`](CRUDResource::operation_timeout),
+/// answering 504 if it elapses. Added to the generated routers with
+/// `axum::middleware::from_fn`.
+pub async fn enforce<T: CRUDResource>(request: Request, next: Next) -> Response {
+    let Some(limit) = T::operation_timeout() else {
+        return next.run(request).await;
+    };
+    let method = request.method().clone();
+    let uri = request.uri().clone();
+    if let Ok(response) = tokio::time::timeout(limit, next.run(request)).await {
+        response
+    } else {
+        tracing::warn!(
+            resource = T::RESOURCE_NAME_PLURAL,
+            %method,
+            %uri,
+            timeout_ms = limit.as_millis(),
+            "Request cancelled after exceeding its timeout"
+        );
+        ApiError::custom(
+            StatusCode::GATEWAY_TIMEOUT,
+            format!(
+                "The request did not complete within {} ms",
+                limit.as_millis()
+            ),
+            None,
+        )
+        .into_response()
+    }
+}
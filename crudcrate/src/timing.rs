@@ -0,0 +1,224 @@
+//! `Server-Timing` breakdowns of the generated handlers, for tuning joins and indexes.
+//!
+//! The generated routers run every handler through [`server_timing`]. With the `debug`
+//! feature every response carries a
+//! [`Server-Timing`](https://www.w3.org/TR/server-timing/) header; without it, resources
+//! declared with `#[crudcrate(server_timing)]` add one to requests that send an
+//! `X-Server-Timing` header. Browsers show the header in their network tools:
+//!
+//! ```text
+//! GET /todos?filter={"done":false}
+//! X-Server-Timing: 1
+//!
+//! Server-Timing: parse;dur=0.21, db;dur=3.84, joins;dur=1.10, serialize;dur=0.35, total;dur=5.62
+//! ```
+//!
+//! Durations are in milliseconds. The list endpoint and `GET /{id}` report:
+//!
+//! - `parse`: reading the filters, sorting and pagination of a list request
+//! - `db`: queries for the rows, the total count and facets, without join loading
+//! - `joins`: loading `join` fields
+//! - `serialize`: encoding the JSON body
+//!
+//! Every response reports `total`, the time spent in the handler including hooks. Hooks
+//! and custom bodies add their own phases with [`measure`] or [`record`]:
+//!
+//! ```rust,ignore
+//! async fn enrich(db: &DatabaseConnection, items: Vec<TodoList>) -> Result<Vec<TodoList>, ApiError> {
+//!     crudcrate::timing::measure("pricing", pricing::load(db, items)).await
+//! }
+//! ```
+//!
+//! Measurements outside a request with timings enabled are ignored.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use axum::extract::Request;
+use axum::http::{HeaderMap, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::core::traits::CRUDResource;
+
+/// Request header asking `server_timing` resources for a `Server-Timing` response header
+pub const REQUEST_HEADER: &str = "x-server-timing";
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Mutex<Timings>>>> = const { RefCell::new(None) };
+}
+
+/// Durations recorded while handling one request, by phase
+#[derive(Debug, Default, Clone)]
+pub struct Timings {
+    phases: Vec<(&'static str, Duration)>,
+    recorded: Duration,
+}
+
+impl Timings {
+    /// The recorded phases in the order they were first recorded, with their total
+    /// durations
+    #[must_use]
+    pub fn phases(&self) -> &[(&'static str, Duration)] {
+        &self.phases
+    }
+
+    fn add(&mut self, phase: &'static str, elapsed: Duration) {
+        self.recorded += elapsed;
+        match self.phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => self.phases.push((phase, elapsed)),
+        }
+    }
+
+    /// `Server-Timing` header value of the phases followed by `total`
+    #[must_use]
+    pub fn header_value(&self, total: Duration) -> String {
+        self.phases
+            .iter()
+            .chain([&("total", total)])
+            .map(|(name, elapsed)| format!("{name};dur={:.2}", elapsed.as_secs_f64() * 1000.0))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Add `elapsed` to `phase` of the request being handled, if it collects timings
+pub fn record(phase: &'static str, elapsed: Duration) {
+    CURRENT.with(|current| {
+        if let Some(timings) = current.borrow().as_ref() {
+            timings
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .add(phase, elapsed);
+        }
+    });
+}
+
+/// Time recorded so far in the request being handled, or `None` when it doesn't
+/// collect timings
+fn recorded() -> Option<Duration> {
+    CURRENT.with(|current| {
+        current.borrow().as_ref().map(|timings| {
+            timings
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .recorded
+        })
+    })
+}
+
+/// Await `future`, recording its duration as `phase`. Phases recorded while it runs
+/// are subtracted, so nested measurements don't count twice.
+pub async fn measure<F: Future>(phase: &'static str, future: F) -> F::Output {
+    let Some(before) = recorded() else {
+        return future.await;
+    };
+    let started = Instant::now();
+    let output = future.await;
+    let nested = recorded().unwrap_or_default().saturating_sub(before);
+    record(phase, started.elapsed().saturating_sub(nested));
+    output
+}
+
+/// Run `future` collecting the phases recorded while it is polled
+pub fn collect<F: Future>(future: F) -> Collect<F> {
+    Collect {
+        timings: Arc::default(),
+        future: Box::pin(future),
+    }
+}
+
+/// Future returned by [`collect`], resolving to the output and its [`Timings`]
+#[must_use = "futures do nothing unless polled"]
+pub struct Collect<F> {
+    timings: Arc<Mutex<Timings>>,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Collect<F> {
+    type Output = (F::Output, Timings);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let previous = CURRENT.with(|current| current.replace(Some(this.timings.clone())));
+        // Restores the outer collector even if the future panics
+        let _restore = Restore(previous);
+        this.future.as_mut().poll(cx).map(|output| {
+            let timings = this
+                .timings
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone();
+            (output, timings)
+        })
+    }
+}
+
+struct Restore(Option<Arc<Mutex<Timings>>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Whether a request to `T` gets a `Server-Timing` header
+fn enabled<T: CRUDResource>(headers: &HeaderMap) -> bool {
+    cfg!(feature = "debug") || (T::SERVER_TIMING && headers.contains_key(REQUEST_HEADER))
+}
+
+/// Add a `Server-Timing` header to the response when timings are enabled for the
+/// request. Added to the generated routers with `axum::middleware::from_fn`.
+pub async fn server_timing<T: CRUDResource>(request: Request, next: Next) -> Response {
+    if !enabled::<T>(request.headers()) {
+        return next.run(request).await;
+    }
+    let started = Instant::now();
+    let (mut response, timings) = collect(next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&timings.header_value(started.elapsed())) {
+        response.headers_mut().append("server-timing", value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_nested_measurements() {
+        let (value, timings) = collect(measure("db", async {
+            record("joins", Duration::from_millis(2));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            7
+        }))
+        .await;
+        assert_eq!(value, 7);
+        let phases: Vec<_> = timings.phases().iter().map(|(name, _)| *name).collect();
+        assert_eq!(phases, ["joins", "db"]);
+        let db = timings.phases()[1].1;
+        assert!(db >= Duration::from_millis(3), "{db:?}");
+
+        // Without a collector nothing is recorded
+        record("db", Duration::from_millis(1));
+        assert!(recorded().is_none());
+    }
+
+    #[test]
+    fn test_header_value() {
+        let mut timings = Timings::default();
+        timings.add("db", Duration::from_micros(1500));
+        timings.add("db", Duration::from_micros(500));
+        timings.add("serialize", Duration::from_micros(250));
+        assert_eq!(
+            timings.header_value(Duration::from_millis(3)),
+            "db;dur=2.00, serialize;dur=0.25, total;dur=3.00"
+        );
+    }
+}
@@ -98,6 +98,10 @@ impl ValidationErrors {
     }
 
     /// Convert to Result
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` if any errors were collected.
     pub fn result(self) -> Result<(), Self> {
         if self.is_empty() { Ok(()) } else { Err(self) }
     }
@@ -131,6 +135,10 @@ pub trait Validatable {
     ///
     /// Return `Ok(())` if valid, or `Err(ValidationError)` if invalid.
     /// For multiple validation errors, use `ValidationErrors`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first `ValidationError` found.
     fn validate(&self) -> Result<(), ValidationError>;
 }
 
@@ -139,6 +147,10 @@ pub mod validators {
     use super::ValidationError;
 
     /// Validate string length is within range
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` if the length is outside `min..=max`.
     pub fn validate_length(
         field: &str,
         value: &str,
@@ -169,6 +181,11 @@ pub mod validators {
     }
 
     /// Validate number is within range
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` if the value is outside `min..=max`.
+    #[allow(clippy::needless_pass_by_value)]
     pub fn validate_range<T: PartialOrd + fmt::Display>(
         field: &str,
         value: T,
@@ -197,6 +214,10 @@ pub mod validators {
     }
 
     /// Basic email validation
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` if the value isn't an email address.
     pub fn validate_email(field: &str, value: &str) -> Result<(), ValidationError> {
         if !value.contains('@') || !value.contains('.') {
             return Err(ValidationError::new(field, "Invalid email format"));
@@ -213,6 +234,10 @@ pub mod validators {
     }
 
     /// Validate value is not empty
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` if the value is blank.
     pub fn validate_required(field: &str, value: &str) -> Result<(), ValidationError> {
         if value.trim().is_empty() {
             return Err(ValidationError::new(field, "This field is required"));
@@ -0,0 +1,743 @@
+//! Signed webhook deliveries for resources declared with `#[crudcrate(webhooks)]`.
+//!
+//! External systems register an endpoint for a resource with [`register`]. After the
+//! generated `create`, `update` and `delete` operations (one and many) succeed, a
+//! delivery is recorded for every endpoint of the resource whose events include the
+//! change, and a [`Worker`] posts them in the background:
+//!
+//! ```rust,ignore
+//! crudcrate::webhooks::ensure_tables(&db).await?;
+//! crudcrate::webhooks::register(&db, NewWebhook {
+//!     resource: "todos".into(),
+//!     url: "https://example.com/hooks/todos".into(),
+//!     secret: "s3cret".into(),
+//!     events: vec![Event::Created, Event::Deleted],
+//! }).await?;
+//! crudcrate::webhooks::Worker::new(db.clone()).spawn();
+//! ```
+//!
+//! Each delivery is a `POST` of a JSON [`Payload`] with these headers:
+//!
+//! ```text
+//! X-Crudcrate-Event: todos.created
+//! X-Crudcrate-Delivery: 5f0c…
+//! X-Crudcrate-Signature: sha256=<hex HMAC-SHA256 of the body, keyed by the secret>
+//! ```
+//!
+//! A delivery answered with anything but a 2xx status is retried with exponential
+//! backoff until it has been attempted [`Worker::max_attempts`] times, and then marked
+//! failed. Deliveries are at-least-once: a receiver should ignore a delivery ID it has
+//! already handled.
+//!
+//! Several workers, in one process or across replicas, can share the table: a worker
+//! claims each due delivery before posting it by moving its next attempt a
+//! [`Worker::lease`] ahead, so the others skip it. A worker stopped mid-delivery leaves
+//! it to be retried once the lease runs out.
+//!
+//! Deliveries are recorded in the [`DELIVERY_TABLE`] after the operation commits, so a
+//! crash between the two loses the delivery. The [`Worker`] and [`sign`] require the
+//! `webhooks` feature; registration and recording don't.
+
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{Alias, ColumnDef, Expr, Index, Query, Table};
+use sea_orm::{ConnectionTrait, DbErr, QueryResult};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{ApiError, CRUDResource};
+
+/// Table holding the registered webhook endpoints
+pub const WEBHOOK_TABLE: &str = "crudcrate_webhooks";
+
+/// Table holding the deliveries waiting for, or done by, the [`Worker`]
+pub const DELIVERY_TABLE: &str = "crudcrate_webhook_deliveries";
+
+/// A change a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Event {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl Event {
+    /// `created`, `updated` or `deleted`
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Deleted => "deleted",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "created" => Some(Self::Created),
+            "updated" => Some(Self::Updated),
+            "deleted" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+}
+
+/// A webhook endpoint to register
+#[derive(Clone, Deserialize)]
+pub struct NewWebhook {
+    /// Plural resource name, as in `RESOURCE_NAME_PLURAL`
+    pub resource: String,
+    /// `http` or `https` URL the deliveries are posted to
+    pub url: String,
+    /// Key of the deliveries' HMAC signatures
+    pub secret: String,
+    /// Events delivered; every event when empty
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+/// A registered webhook endpoint. Its secret is never read back.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub resource: String,
+    pub url: String,
+    /// Events delivered; every event when empty
+    pub events: Vec<Event>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    fn wants(&self, event: Event) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// Body of a delivery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Payload {
+    /// Same as the `X-Crudcrate-Delivery` header
+    pub delivery_id: Uuid,
+    pub event: Event,
+    /// Plural resource name
+    pub resource: String,
+    /// ID of the changed row
+    pub id: Uuid,
+    /// The row as returned by the operation; `null` for deletes
+    pub data: serde_json::Value,
+    pub occurred_at: DateTime<Utc>,
+    /// ID of the request that made the change, see
+    /// [`request_id`](crate::telemetry::request_id)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// Create the [`WEBHOOK_TABLE`] and [`DELIVERY_TABLE`] if they don't exist. Call it at
+/// startup, before serving `webhooks` resources.
+///
+/// # Errors
+/// Returns the database error if a table can't be created.
+pub async fn ensure_tables(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    let webhooks = Table::create()
+        .table(Alias::new(WEBHOOK_TABLE))
+        .if_not_exists()
+        .col(
+            ColumnDef::new(Alias::new("id"))
+                .uuid()
+                .not_null()
+                .primary_key(),
+        )
+        .col(
+            ColumnDef::new(Alias::new("resource"))
+                .string_len(255)
+                .not_null(),
+        )
+        .col(ColumnDef::new(Alias::new("url")).text().not_null())
+        .col(ColumnDef::new(Alias::new("secret")).text().not_null())
+        .col(
+            ColumnDef::new(Alias::new("events"))
+                .string_len(255)
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(Alias::new("created_at"))
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .to_owned();
+    db.execute(backend.build(&webhooks)).await?;
+
+    let deliveries = Table::create()
+        .table(Alias::new(DELIVERY_TABLE))
+        .if_not_exists()
+        .col(
+            ColumnDef::new(Alias::new("id"))
+                .uuid()
+                .not_null()
+                .primary_key(),
+        )
+        .col(ColumnDef::new(Alias::new("webhook_id")).uuid().not_null())
+        .col(
+            ColumnDef::new(Alias::new("event"))
+                .string_len(255)
+                .not_null(),
+        )
+        .col(ColumnDef::new(Alias::new("payload")).text().not_null())
+        .col(
+            ColumnDef::new(Alias::new("status"))
+                .string_len(16)
+                .not_null(),
+        )
+        .col(ColumnDef::new(Alias::new("attempts")).integer().not_null())
+        .col(
+            ColumnDef::new(Alias::new("next_attempt_at"))
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(ColumnDef::new(Alias::new("last_error")).text())
+        .to_owned();
+    db.execute(backend.build(&deliveries)).await?;
+
+    // The worker reads due deliveries in order through it
+    let due = Index::create()
+        .if_not_exists()
+        .name(format!("idx_{DELIVERY_TABLE}_due"))
+        .table(Alias::new(DELIVERY_TABLE))
+        .col(Alias::new("status"))
+        .col(Alias::new("next_attempt_at"))
+        .to_owned();
+    db.execute(backend.build(&due)).await?;
+    Ok(())
+}
+
+/// Register a webhook endpoint
+///
+/// # Errors
+/// Returns `ApiError::BadRequest` if the URL isn't `http` or `https` or the secret is
+/// empty, and `ApiError::Database` if the insert fails.
+pub async fn register(db: &impl ConnectionTrait, webhook: NewWebhook) -> Result<Webhook, ApiError> {
+    let url = webhook.url.trim();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(ApiError::bad_request(
+            "Webhook URL must start with http:// or https://",
+        ));
+    }
+    if webhook.secret.is_empty() {
+        return Err(ApiError::bad_request("Webhook secret must not be empty"));
+    }
+    let registered = Webhook {
+        id: Uuid::new_v4(),
+        resource: webhook.resource,
+        url: url.to_string(),
+        events: webhook.events,
+        created_at: Utc::now(),
+    };
+    let events = registered
+        .events
+        .iter()
+        .map(|event| event.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut insert = Query::insert();
+    insert
+        .into_table(Alias::new(WEBHOOK_TABLE))
+        .columns([
+            Alias::new("id"),
+            Alias::new("resource"),
+            Alias::new("url"),
+            Alias::new("secret"),
+            Alias::new("events"),
+            Alias::new("created_at"),
+        ])
+        .values_panic([
+            registered.id.into(),
+            registered.resource.clone().into(),
+            registered.url.clone().into(),
+            webhook.secret.into(),
+            events.into(),
+            registered.created_at.into(),
+        ]);
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&insert))
+        .await
+        .map_err(ApiError::database)?;
+    Ok(registered)
+}
+
+/// The webhooks registered for `resource`, or for every resource
+///
+/// # Errors
+/// Returns `ApiError::Database` if the query fails.
+pub async fn list(
+    db: &impl ConnectionTrait,
+    resource: Option<&str>,
+) -> Result<Vec<Webhook>, ApiError> {
+    let mut select = Query::select();
+    select
+        .columns([
+            Alias::new("id"),
+            Alias::new("resource"),
+            Alias::new("url"),
+            Alias::new("events"),
+            Alias::new("created_at"),
+        ])
+        .from(Alias::new(WEBHOOK_TABLE))
+        .order_by(Alias::new("created_at"), sea_orm::Order::Asc);
+    if let Some(resource) = resource {
+        select.and_where(Expr::col(Alias::new("resource")).eq(resource));
+    }
+    let backend = db.get_database_backend();
+    let rows = db
+        .query_all(backend.build(&select))
+        .await
+        .map_err(ApiError::database)?;
+    rows.iter().map(read_webhook).collect()
+}
+
+fn read_webhook(row: &QueryResult) -> Result<Webhook, ApiError> {
+    let events: String = row.try_get("", "events").map_err(ApiError::database)?;
+    Ok(Webhook {
+        id: row.try_get("", "id").map_err(ApiError::database)?,
+        resource: row.try_get("", "resource").map_err(ApiError::database)?,
+        url: row.try_get("", "url").map_err(ApiError::database)?,
+        events: events.split(',').filter_map(Event::parse).collect(),
+        created_at: row.try_get("", "created_at").map_err(ApiError::database)?,
+    })
+}
+
+/// Remove a webhook and its undelivered deliveries. Returns whether it existed.
+///
+/// # Errors
+/// Returns `ApiError::Database` if a delete fails.
+pub async fn unregister(db: &impl ConnectionTrait, id: Uuid) -> Result<bool, ApiError> {
+    let backend = db.get_database_backend();
+    let deliveries = Query::delete()
+        .from_table(Alias::new(DELIVERY_TABLE))
+        .and_where(Expr::col(Alias::new("webhook_id")).eq(id))
+        .and_where(Expr::col(Alias::new("status")).eq(PENDING))
+        .to_owned();
+    db.execute(backend.build(&deliveries))
+        .await
+        .map_err(ApiError::database)?;
+    let webhook = Query::delete()
+        .from_table(Alias::new(WEBHOOK_TABLE))
+        .and_where(Expr::col(Alias::new("id")).eq(id))
+        .to_owned();
+    let result = db
+        .execute(backend.build(&webhook))
+        .await
+        .map_err(ApiError::database)?;
+    Ok(result.rows_affected() > 0)
+}
+
+const PENDING: &str = "pending";
+#[cfg(feature = "webhooks")]
+const DELIVERED: &str = "delivered";
+#[cfg(feature = "webhooks")]
+const FAILED: &str = "failed";
+
+/// Record deliveries of `event` for the changed `T` rows, as `(id, row)` pairs, to every
+/// webhook of `T` subscribed to it. Called by the generated operations of `webhooks`
+/// resources after they succeed, including those with `body` hooks; `operations`
+/// implementations call it themselves.
+///
+/// # Errors
+/// Returns `ApiError::Database` if a query fails, e.g. because [`ensure_tables`] wasn't
+/// called.
+pub async fn record<T: CRUDResource>(
+    db: &impl ConnectionTrait,
+    event: Event,
+    changes: Vec<(Uuid, serde_json::Value)>,
+) -> Result<(), ApiError> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let webhooks: Vec<Webhook> = list(db, Some(T::RESOURCE_NAME_PLURAL))
+        .await?
+        .into_iter()
+        .filter(|webhook| webhook.wants(event))
+        .collect();
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let request_id = crate::telemetry::request_id();
+    let mut insert = Query::insert();
+    insert.into_table(Alias::new(DELIVERY_TABLE)).columns([
+        Alias::new("id"),
+        Alias::new("webhook_id"),
+        Alias::new("event"),
+        Alias::new("payload"),
+        Alias::new("status"),
+        Alias::new("attempts"),
+        Alias::new("next_attempt_at"),
+    ]);
+    for webhook in &webhooks {
+        for (id, data) in &changes {
+            let payload = Payload {
+                delivery_id: Uuid::new_v4(),
+                event,
+                resource: T::RESOURCE_NAME_PLURAL.to_string(),
+                id: *id,
+                data: data.clone(),
+                occurred_at: now,
+                request_id: request_id.clone(),
+            };
+            let body = serde_json::to_string(&payload).map_err(|e| {
+                ApiError::internal("Failed to encode webhook payload", Some(e.to_string()))
+            })?;
+            insert.values_panic([
+                payload.delivery_id.into(),
+                webhook.id.into(),
+                event.as_str().into(),
+                body.into(),
+                PENDING.into(),
+                0.into(),
+                now.into(),
+            ]);
+        }
+    }
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&insert))
+        .await
+        .map_err(ApiError::database)?;
+    Ok(())
+}
+
+#[cfg(feature = "webhooks")]
+pub use worker::{Worker, sign};
+
+#[cfg(feature = "webhooks")]
+mod worker {
+    use std::time::Duration;
+
+    use chrono::{DateTime, Utc};
+    use hmac::{Hmac, Mac};
+    use sea_orm::sea_query::{Alias, Expr, Query};
+    use sea_orm::{ConnectionTrait, DatabaseConnection, Order};
+    use sha2::Sha256;
+    use uuid::Uuid;
+
+    use super::{DELIVERED, DELIVERY_TABLE, FAILED, PENDING, WEBHOOK_TABLE};
+    use crate::ApiError;
+
+    /// Longest wait between two attempts of a delivery
+    const MAX_BACKOFF: Duration = Duration::from_hours(1);
+
+    /// `sha256=<hex>`: the `X-Crudcrate-Signature` of `body` under `secret`
+    ///
+    /// Receivers recompute it over the raw request body and compare.
+    ///
+    /// # Panics
+    ///
+    /// Never: HMAC-SHA256 accepts keys of any length.
+    #[must_use]
+    pub fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    /// Posts due deliveries, retrying failed ones with exponential backoff
+    ///
+    /// ```rust,ignore
+    /// Worker::new(db.clone())
+    ///     .poll_interval(Duration::from_secs(5))
+    ///     .max_attempts(10)
+    ///     .spawn();
+    /// ```
+    #[derive(Clone)]
+    pub struct Worker {
+        db: DatabaseConnection,
+        client: reqwest::Client,
+        poll_interval: Duration,
+        backoff: Duration,
+        max_attempts: u32,
+        lease: Duration,
+        batch_size: u64,
+    }
+
+    /// A due delivery with its endpoint
+    struct Due {
+        id: Uuid,
+        event: String,
+        payload: String,
+        attempts: i32,
+        url: String,
+        secret: String,
+        resource: String,
+    }
+
+    impl Worker {
+        /// A worker polling every second, retrying after 10 s, 20 s, 40 s, … up to an
+        /// hour, giving up after 8 attempts, and claiming deliveries for 5 minutes
+        #[must_use]
+        pub fn new(db: DatabaseConnection) -> Self {
+            Self {
+                db,
+                client: reqwest::Client::builder()
+                    .timeout(Duration::from_secs(10))
+                    .build()
+                    .unwrap_or_default(),
+                poll_interval: Duration::from_secs(1),
+                backoff: Duration::from_secs(10),
+                max_attempts: 8,
+                lease: Duration::from_mins(5),
+                batch_size: 100,
+            }
+        }
+
+        /// How long [`spawn`](Self::spawn) waits between polls when nothing is due
+        #[must_use]
+        pub fn poll_interval(mut self, interval: Duration) -> Self {
+            self.poll_interval = interval;
+            self
+        }
+
+        /// Wait before the first retry; each further retry waits twice as long
+        #[must_use]
+        pub fn backoff(mut self, backoff: Duration) -> Self {
+            self.backoff = backoff;
+            self
+        }
+
+        /// Attempts after which a delivery is marked failed (at least 1)
+        #[must_use]
+        pub fn max_attempts(mut self, attempts: u32) -> Self {
+            self.max_attempts = attempts.max(1);
+            self
+        }
+
+        /// How long a claimed delivery is left to this worker before another may attempt
+        /// it. Keep it longer than the client's timeout.
+        #[must_use]
+        pub fn lease(mut self, lease: Duration) -> Self {
+            self.lease = lease;
+            self
+        }
+
+        /// HTTP client the deliveries are posted with
+        #[must_use]
+        pub fn client(mut self, client: reqwest::Client) -> Self {
+            self.client = client;
+            self
+        }
+
+        /// Claim and attempt every due delivery once, up to 100 of them, and return how
+        /// many were attempted. Deliveries another worker claimed first are skipped.
+        ///
+        /// # Errors
+        /// Returns `ApiError::Database` if reading or updating the deliveries fails.
+        pub async fn run_once(&self) -> Result<usize, ApiError> {
+            let due = self.due().await?;
+            for delivery in &due {
+                let outcome = self.post(delivery).await;
+                self.settle(delivery, outcome).await?;
+            }
+            Ok(due.len())
+        }
+
+        /// Run the worker on a Tokio task until it's aborted
+        #[must_use = "dropping the handle detaches the worker; keep it to abort it"]
+        pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+            tokio::spawn(async move {
+                loop {
+                    match self.run_once().await {
+                        Ok(attempted) if attempted > 0 => continue,
+                        Ok(_) => {}
+                        Err(error) => {
+                            tracing::warn!(error = %error, "Webhook worker failed to poll deliveries");
+                        }
+                    }
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            })
+        }
+
+        /// The due deliveries this worker claimed
+        async fn due(&self) -> Result<Vec<Due>, ApiError> {
+            let now = Utc::now();
+            let delivery = |column: &str| (Alias::new(DELIVERY_TABLE), Alias::new(column));
+            let webhook = |column: &str| (Alias::new(WEBHOOK_TABLE), Alias::new(column));
+            let select = Query::select()
+                .columns([
+                    delivery("id"),
+                    delivery("event"),
+                    delivery("payload"),
+                    delivery("attempts"),
+                ])
+                .columns([webhook("url"), webhook("secret"), webhook("resource")])
+                .from(Alias::new(DELIVERY_TABLE))
+                .inner_join(
+                    Alias::new(WEBHOOK_TABLE),
+                    Expr::col(delivery("webhook_id")).equals(webhook("id")),
+                )
+                .and_where(Expr::col(delivery("status")).eq(PENDING))
+                .and_where(Expr::col(delivery("next_attempt_at")).lte(now))
+                .order_by(delivery("next_attempt_at"), Order::Asc)
+                .limit(self.batch_size)
+                .to_owned();
+            let backend = self.db.get_database_backend();
+            let rows = self
+                .db
+                .query_all(backend.build(&select))
+                .await
+                .map_err(ApiError::database)?;
+            let mut claimed = Vec::with_capacity(rows.len());
+            for row in &rows {
+                let due = Due {
+                    id: row.try_get("", "id").map_err(ApiError::database)?,
+                    event: row.try_get("", "event").map_err(ApiError::database)?,
+                    payload: row.try_get("", "payload").map_err(ApiError::database)?,
+                    attempts: row.try_get("", "attempts").map_err(ApiError::database)?,
+                    url: row.try_get("", "url").map_err(ApiError::database)?,
+                    secret: row.try_get("", "secret").map_err(ApiError::database)?,
+                    resource: row.try_get("", "resource").map_err(ApiError::database)?,
+                };
+                if self.claim(due.id, now).await? {
+                    claimed.push(due);
+                }
+            }
+            Ok(claimed)
+        }
+
+        /// Move a delivery still pending and due at `now` a lease ahead; false if
+        /// another worker claimed it first
+        async fn claim(&self, id: Uuid, now: DateTime<Utc>) -> Result<bool, ApiError> {
+            let until = chrono::Duration::from_std(self.lease)
+                .ok()
+                .and_then(|lease| now.checked_add_signed(lease))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC);
+            let claim = Query::update()
+                .table(Alias::new(DELIVERY_TABLE))
+                .value(Alias::new("next_attempt_at"), until)
+                .and_where(Expr::col(Alias::new("id")).eq(id))
+                .and_where(Expr::col(Alias::new("status")).eq(PENDING))
+                .and_where(Expr::col(Alias::new("next_attempt_at")).lte(now))
+                .to_owned();
+            let backend = self.db.get_database_backend();
+            let result = self
+                .db
+                .execute(backend.build(&claim))
+                .await
+                .map_err(ApiError::database)?;
+            Ok(result.rows_affected() == 1)
+        }
+
+        /// Post one delivery; `Err` holds why it didn't succeed
+        async fn post(&self, delivery: &Due) -> Result<(), String> {
+            let response = self
+                .client
+                .post(&delivery.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(
+                    "X-Crudcrate-Event",
+                    format!("{}.{}", delivery.resource, delivery.event),
+                )
+                .header("X-Crudcrate-Delivery", delivery.id.to_string())
+                .header(
+                    "X-Crudcrate-Signature",
+                    sign(&delivery.secret, delivery.payload.as_bytes()),
+                )
+                .body(delivery.payload.clone())
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            let status = response.status();
+            if status.is_success() {
+                Ok(())
+            } else {
+                Err(format!("HTTP {status}"))
+            }
+        }
+
+        /// Mark a delivery delivered, or schedule its retry or give up on it
+        async fn settle(
+            &self,
+            delivery: &Due,
+            outcome: Result<(), String>,
+        ) -> Result<(), ApiError> {
+            let attempts = delivery.attempts.saturating_add(1);
+            let mut update = Query::update();
+            update
+                .table(Alias::new(DELIVERY_TABLE))
+                .value(Alias::new("attempts"), attempts)
+                .and_where(Expr::col(Alias::new("id")).eq(delivery.id));
+            match outcome {
+                Ok(()) => {
+                    update.value(Alias::new("status"), DELIVERED);
+                }
+                Err(error) => {
+                    let exhausted =
+                        u32::try_from(attempts).unwrap_or(u32::MAX) >= self.max_attempts;
+                    tracing::warn!(
+                        delivery = %delivery.id,
+                        url = %delivery.url,
+                        attempts,
+                        error = %error,
+                        "Webhook delivery failed"
+                    );
+                    if exhausted {
+                        update.value(Alias::new("status"), FAILED);
+                    } else {
+                        let next = Utc::now() + self.retry_delay(attempts);
+                        update.value(Alias::new("next_attempt_at"), next);
+                    }
+                    update.value(Alias::new("last_error"), error);
+                }
+            }
+            let backend = self.db.get_database_backend();
+            self.db
+                .execute(backend.build(&update))
+                .await
+                .map_err(ApiError::database)?;
+            Ok(())
+        }
+
+        /// `backoff`, doubled for each attempt after the first, up to an hour
+        fn retry_delay(&self, attempts: i32) -> chrono::Duration {
+            let doublings = u32::try_from(attempts.saturating_sub(1))
+                .unwrap_or(0)
+                .min(20);
+            let delay = self.backoff.saturating_mul(1 << doublings).min(MAX_BACKOFF);
+            chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::MAX)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_sign_is_hmac_sha256() {
+            // RFC 4231 test case 2
+            assert_eq!(
+                sign("Jefe", b"what do ya want for nothing?"),
+                "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_events_means_every_event() {
+        let mut webhook = Webhook {
+            id: Uuid::nil(),
+            resource: "todos".to_string(),
+            url: "https://example.com".to_string(),
+            events: vec![],
+            created_at: Utc::now(),
+        };
+        assert!(webhook.wants(Event::Deleted));
+        webhook.events = vec![Event::Created];
+        assert!(webhook.wants(Event::Created));
+        assert!(!webhook.wants(Event::Updated));
+    }
+}
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use crudcrate::{CRUDResource, EntityToModels};
+use crudcrate::EntityToModels;
 use sea_orm::{Database, DatabaseConnection, entity::prelude::*};
 use uuid::Uuid;
 
@@ -51,8 +51,8 @@ async fn setup_database() -> DatabaseConnection {
         .min_connections(1)
         .connect_timeout(Duration::from_secs(30))
         .acquire_timeout(Duration::from_secs(30))
-        .idle_timeout(Duration::from_secs(300)) // Keep connection alive longer
-        .max_lifetime(Duration::from_secs(3600)) // 1 hour
+        .idle_timeout(Duration::from_mins(5)) // Keep connection alive longer
+        .max_lifetime(Duration::from_hours(1))
         .sqlx_logging(false);
 
     let db = Database::connect(opt).await.unwrap();
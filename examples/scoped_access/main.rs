@@ -12,13 +12,18 @@
 //! - **Admin API** (full CRUD, all records): <http://localhost:3000/admin/articles>
 //! - **Docs**: <http://localhost:3000/docs>
 
+// `#[derive(OpenApi)]` generates a `for_each` that clippy flags
+#![allow(clippy::needless_for_each)]
+
 mod article;
 
 use article::{Article, setup_article_database};
 use axum::{Extension, Router};
 use crudcrate::ScopeCondition;
+use crudcrate::seed::SeedError;
 use sea_orm::{ColumnTrait, Condition};
 use std::env;
+use std::path::Path;
 use utoipa::OpenApi;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_scalar::{Scalar, Servable};
@@ -33,7 +38,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let db = setup_article_database(&database_url).await?;
 
     // Seed some test data
-    seed_articles(&db).await;
+    seed_articles(&db).await?;
 
     // Admin router: full CRUD, all records
     let admin_router = Article::router(&db);
@@ -57,25 +62,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-async fn seed_articles(db: &sea_orm::DatabaseConnection) {
-    use article::ActiveModel;
-    use sea_orm::{ActiveModelTrait, Set};
-
-    let articles = vec![
-        ("Public article 1", "This is visible to everyone", false),
-        ("Public article 2", "Also visible to everyone", false),
-        ("Private draft", "Only visible to admins", true),
-        ("Internal notes", "Admin eyes only", true),
-    ];
-
-    for (title, body, is_private) in articles {
-        let article = ActiveModel {
-            id: Set(uuid::Uuid::new_v4()),
-            title: Set(title.to_string()),
-            body: Set(body.to_string()),
-            is_private: Set(is_private),
-        };
-        article.insert(db).await.ok();
-    }
-    println!("Seeded {} articles (2 public, 2 private)", 4);
+async fn seed_articles(db: &sea_orm::DatabaseConnection) -> Result<(), SeedError> {
+    // `is_private` defaults to false through its `on_create`
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/scoped_access/articles.json");
+    let articles = crudcrate::seed::load_json::<Article>(db, path).await?;
+    println!("Seeded {} articles (2 public, 2 private)", articles.len());
+    Ok(())
 }
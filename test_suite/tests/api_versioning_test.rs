@@ -0,0 +1,169 @@
+// Tests for the versioned API of the FAQ
+// Verifies that two entities over one table serve v1 (without `priority`, built from
+// the `legacy_todo_handlers` module without deletes) and v2 (the full router) side by side,
+// and that each version's operations are tagged and named by version in the `OpenAPI`
+// document.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use utoipa_axum::{router::OpenApiRouter, routes};
+use uuid::Uuid;
+
+pub mod todo {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "todos")]
+    #[crudcrate(generate_router, api_struct = "Todo")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        pub completed: bool,
+
+        /// Added in v2
+        pub priority: Option<i32>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// The v1 view of the `todos` table, without `priority`
+pub mod todo_v1 {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "todos")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "LegacyTodo",
+        name_singular = "todo",
+        name_plural = "todos"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        pub completed: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use todo::Todo;
+use todo_v1::legacy_todo_handlers;
+
+/// Tag `router`'s operations with `version` and prefix their operation IDs with it, so
+/// versions sharing handlers' names stay distinct in the merged document
+fn versioned(mut router: OpenApiRouter, version: &str) -> OpenApiRouter {
+    for item in router.get_openapi_mut().paths.paths.values_mut() {
+        let operations = [
+            &mut item.get,
+            &mut item.put,
+            &mut item.post,
+            &mut item.patch,
+            &mut item.delete,
+        ];
+        for operation in operations.into_iter().flatten() {
+            operation.tags = Some(vec![version.to_string()]);
+            operation.operation_id = operation
+                .operation_id
+                .take()
+                .map(|id| format!("{version}_{id}"));
+        }
+    }
+    router
+}
+
+/// v1 serves reads, creates and updates without `priority`; v2 is the full router
+fn api(db: &DatabaseConnection) -> OpenApiRouter {
+    let v1 = OpenApiRouter::new()
+        .routes(routes!(
+            legacy_todo_handlers::get_all_handler,
+            legacy_todo_handlers::create_one_handler
+        ))
+        .routes(routes!(
+            legacy_todo_handlers::get_one_handler,
+            legacy_todo_handlers::update_one_handler
+        ))
+        .with_state(db.clone());
+    OpenApiRouter::new()
+        .nest("/v1/todos", versioned(v1, "v1"))
+        .nest("/v2/todos", versioned(Todo::router(db), "v2"))
+}
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(todo::Entity)))
+        .await
+        .unwrap();
+    db
+}
+
+#[tokio::test]
+async fn test_versions_share_the_table() {
+    let db = setup().await;
+    let app: Router = api(&db).into();
+
+    let body = json!({"title": "v1 todo", "completed": false});
+    let (status, created) = send(&app, "POST", "/v1/todos", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{created}");
+    assert_eq!(created.get("priority"), None, "{created}");
+    let id = created["id"].as_str().unwrap();
+
+    let (status, todo) = send(&app, "GET", &format!("/v2/todos/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{todo}");
+    assert_eq!(todo["priority"], json!(null));
+
+    let body = json!({"priority": 2});
+    let (status, todo) = send(&app, "PUT", &format!("/v2/todos/{id}"), Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{todo}");
+    let (status, todo) = send(&app, "GET", &format!("/v1/todos/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{todo}");
+    assert_eq!(todo.get("priority"), None, "{todo}");
+
+    // v1 doesn't expose deletes
+    let (status, _) = send(&app, "DELETE", &format!("/v1/todos/{id}"), None).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    let (status, _) = send(&app, "DELETE", &format!("/v2/todos/{id}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_operations_are_tagged_by_version() {
+    let db = setup().await;
+    let (_, api) = api(&db).split_for_parts();
+    let api = serde_json::to_value(api).unwrap();
+    let paths = &api["paths"];
+
+    let list = &paths["/v1/todos"]["get"];
+    assert_eq!(list["tags"], json!(["v1"]), "{list}");
+    assert_eq!(list["operationId"], "v1_get_all_todos", "{list}");
+    assert_eq!(paths["/v1/todos/{id}"].get("delete"), None);
+
+    let delete = &paths["/v2/todos/{id}"]["delete"];
+    assert_eq!(delete["tags"], json!(["v2"]), "{delete}");
+    let schemas = &api["components"]["schemas"];
+    let legacy = &schemas["LegacyTodoResponse"]["properties"];
+    assert!(legacy.get("priority").is_none(), "{legacy}");
+    let current = &schemas["TodoResponse"]["properties"];
+    assert!(current.get("priority").is_some(), "{current}");
+}
@@ -0,0 +1,125 @@
+// Tests for Postgres array columns
+// Verifies that filterable `Vec<String>`/`Vec<i32>` fields are reported by
+// array_filterable_columns(), pass through the generated models, and that
+// `_contains`/`_overlaps` filters become `@>`/`&&` conditions on PostgreSQL.
+
+use crudcrate::{CRUDResource, EntityToModels, apply_filters};
+use sea_orm::DatabaseBackend;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+pub mod post {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "posts")]
+    #[crudcrate(generate_router, api_struct = "Post")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub tags: Vec<String>,
+
+        #[crudcrate(filterable)]
+        pub scores: Option<Vec<i32>>,
+
+        pub labels: Vec<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use post::{Post, PostCreate, PostUpdate};
+
+fn condition_sql(filter: &str, backend: DatabaseBackend) -> Result<String, crudcrate::ApiError> {
+    apply_filters::<Post>(
+        Some(filter.to_string()),
+        &Post::filterable_columns(),
+        backend,
+    )
+    .map(|condition| format!("{condition:?}"))
+}
+
+#[test]
+fn test_array_filterable_columns() {
+    // Only filterable Vec fields, with their Postgres element type
+    assert_eq!(
+        Post::array_filterable_columns(),
+        vec![("tags", "text"), ("scores", "integer")]
+    );
+}
+
+#[test]
+fn test_models_carry_array_fields() {
+    let create = PostCreate {
+        title: "Hello".into(),
+        tags: vec!["rust".into(), "web".into()],
+        scores: Some(vec![1, 2]),
+        labels: vec![],
+    };
+    let json = serde_json::to_value(&create).unwrap();
+    assert_eq!(json["tags"], serde_json::json!(["rust", "web"]));
+
+    let update: PostUpdate =
+        serde_json::from_value(serde_json::json!({"tags": ["go"]})).expect("partial update");
+    let json = serde_json::to_value(&update).unwrap();
+    assert_eq!(json["tags"], serde_json::json!(["go"]));
+}
+
+#[test]
+fn test_contains_and_overlaps_operators() {
+    let sql = condition_sql(
+        r#"{"tags_contains": ["rust", "web"]}"#,
+        DatabaseBackend::Postgres,
+    )
+    .unwrap();
+    assert!(sql.contains("@> ARRAY[?, ?]::text[]"), "got: {sql}");
+
+    let sql = condition_sql(r#"{"scores_overlaps": [1, 5]}"#, DatabaseBackend::Postgres).unwrap();
+    assert!(sql.contains("&& ARRAY[?, ?]::integer[]"), "got: {sql}");
+
+    // A bare key with a single value means "contains"
+    let sql = condition_sql(r#"{"tags": "rust"}"#, DatabaseBackend::Postgres).unwrap();
+    assert!(sql.contains("@> ARRAY[?]::text[]"), "got: {sql}");
+}
+
+#[test]
+fn test_array_filters_combine_with_regular_filters() {
+    let sql = condition_sql(
+        r#"{"title": "Hello", "tags_contains": "rust"}"#,
+        DatabaseBackend::Postgres,
+    )
+    .unwrap();
+    assert!(sql.contains("@> ARRAY[?]::text[]"), "got: {sql}");
+    assert!(sql.contains("HELLO"), "got: {sql}");
+}
+
+#[test]
+fn test_invalid_array_filters_are_rejected() {
+    // Comparison operators don't apply to arrays
+    assert!(condition_sql(r#"{"tags_gte": "a"}"#, DatabaseBackend::Postgres).is_err());
+    // Nested objects are not array elements
+    assert!(
+        condition_sql(
+            r#"{"tags_contains": [{"a": 1}]}"#,
+            DatabaseBackend::Postgres
+        )
+        .is_err()
+    );
+    // Array columns only exist on Postgres
+    assert!(condition_sql(r#"{"tags_contains": ["rust"]}"#, DatabaseBackend::Sqlite).is_err());
+}
+
+#[test]
+fn test_non_filterable_array_field_is_ignored() {
+    let sql = condition_sql(r#"{"labels_contains": ["x"]}"#, DatabaseBackend::Postgres).unwrap();
+    assert!(!sql.contains("ARRAY"), "got: {sql}");
+}
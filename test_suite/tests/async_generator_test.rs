@@ -0,0 +1,170 @@
+// Tests for async `on_create`/`on_update` generator functions
+// Verifies that a function path is awaited with the connection and the request model,
+// that client-provided values skip the generator, and that plain expressions still work.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, QueryOrder, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod invoice {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "invoices")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Invoice",
+        name_singular = "invoice",
+        name_plural = "invoices",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub customer: String,
+
+        /// Next number in sequence, read from the table
+        #[crudcrate(sortable, exclude(create, update), on_create = super::next_invoice_number)]
+        pub number: i32,
+
+        /// Derived from the customer unless the client sends one
+        #[crudcrate(on_create = super::reference_for)]
+        pub reference: String,
+
+        #[crudcrate(exclude(create, update), on_update = super::revision_note)]
+        pub note: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use invoice::{Invoice, InvoiceCreate, InvoiceUpdate};
+
+async fn next_invoice_number(
+    db: &impl ConnectionTrait,
+    _data: &InvoiceCreate,
+) -> Result<i32, ApiError> {
+    let last = invoice::Entity::find()
+        .order_by_desc(invoice::Column::Number)
+        .one(db)
+        .await?;
+    Ok(last.map_or(1000, |invoice| invoice.number + 1))
+}
+
+#[allow(clippy::unused_async)]
+async fn reference_for(
+    _db: &impl ConnectionTrait,
+    data: &InvoiceCreate,
+) -> Result<String, ApiError> {
+    Ok(format!("INV-{}", data.customer.to_uppercase()))
+}
+
+#[allow(clippy::unused_async)]
+async fn revision_note(
+    _db: &impl ConnectionTrait,
+    data: &InvoiceUpdate,
+) -> Result<String, ApiError> {
+    Ok(match &data.customer {
+        Some(Some(customer)) => format!("customer changed to {customer}"),
+        _ => "edited".to_string(),
+    })
+}
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(invoice::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/invoices", Invoice::router(&db).into());
+    (db, app)
+}
+
+#[tokio::test]
+async fn test_generators_run_on_create() {
+    let (_db, app) = setup().await;
+
+    let (status, first) = send(&app, "POST", "/invoices", Some(json!({"customer": "acme"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{first}");
+    assert_eq!(first["number"], 1000);
+    assert_eq!(first["reference"], "INV-ACME");
+    assert_eq!(first["note"], serde_json::Value::Null);
+
+    let (_, second) = send(
+        &app,
+        "POST",
+        "/invoices",
+        Some(json!({"customer": "globex", "reference": "PO-77"})),
+    )
+    .await;
+    assert_eq!(second["number"], 1001);
+    assert_eq!(second["reference"], "PO-77");
+}
+
+#[tokio::test]
+async fn test_generators_run_in_batches() {
+    let (db, _app) = setup().await;
+    let created = Invoice::create_many(
+        &db,
+        vec![
+            InvoiceCreate {
+                customer: "a".into(),
+                reference: None,
+            },
+            InvoiceCreate {
+                customer: "b".into(),
+                reference: None,
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(created[0].reference, "INV-A");
+    assert_eq!(created[1].reference, "INV-B");
+    // The generator runs inside the batch transaction, so it sees the earlier insert
+    assert_eq!(created[0].number, 1000);
+    assert_eq!(created[1].number, 1001);
+}
+
+#[tokio::test]
+async fn test_generator_runs_on_update() {
+    let (_db, app) = setup().await;
+    let (_, created) = send(&app, "POST", "/invoices", Some(json!({"customer": "acme"}))).await;
+    let id = created["id"].as_str().unwrap();
+
+    let (status, updated) = send(
+        &app,
+        "PUT",
+        &format!("/invoices/{id}"),
+        Some(json!({"customer": "initech"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{updated}");
+    assert_eq!(updated["note"], "customer changed to initech");
+    assert_eq!(updated["number"], 1000);
+    assert_eq!(updated["reference"], "INV-ACME");
+}
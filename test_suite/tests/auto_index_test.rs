@@ -0,0 +1,160 @@
+// Tests for the auto_index attribute and crudcrate::database::ensure_indexes
+// Verifies that indexes are only created for opted-in resources, and that
+// running ensure_indexes twice is idempotent on SQLite.
+
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{
+    ConnectionTrait, Database, DatabaseBackend, DatabaseConnection, DbErr, Schema, Statement,
+};
+use uuid::Uuid;
+
+pub mod indexed_item {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "indexed_items")]
+    #[crudcrate(generate_router, api_struct = "IndexedItem", auto_index)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, sortable, filterable, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable, fulltext)]
+        pub name: String,
+
+        #[crudcrate(filterable)]
+        pub priority: i32,
+
+        #[crudcrate(fulltext)]
+        pub notes: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod plain_item {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "plain_items")]
+    #[crudcrate(generate_router, api_struct = "PlainItem")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+async fn setup_test_db() -> Result<DatabaseConnection, DbErr> {
+    let db = Database::connect("sqlite::memory:").await?;
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+
+    db.execute(backend.build(&schema.create_table_from_entity(indexed_item::Entity)))
+        .await?;
+    db.execute(backend.build(&schema.create_table_from_entity(plain_item::Entity)))
+        .await?;
+
+    Ok(db)
+}
+
+async fn sqlite_index_names(db: &DatabaseConnection, table: &str) -> Vec<String> {
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = ? AND name LIKE 'idx_%' ORDER BY name",
+            [table.into()],
+        ))
+        .await
+        .unwrap();
+    rows.iter()
+        .map(|row| row.try_get_by_index::<String>(0).unwrap())
+        .collect()
+}
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn test_auto_index_constant() {
+    assert!(indexed_item::IndexedItem::AUTO_INDEX);
+    assert!(!plain_item::PlainItem::AUTO_INDEX);
+}
+
+#[test]
+fn test_plan_skips_primary_key_and_deduplicates() {
+    let plan =
+        crudcrate::database::plan_indexes::<indexed_item::IndexedItem>(DatabaseBackend::Sqlite);
+    let names: Vec<_> = plan.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "idx_indexed_items_name",
+            "idx_indexed_items_priority",
+            "idx_indexed_items_notes"
+        ]
+    );
+}
+
+#[test]
+fn test_plan_postgres_uses_gin_for_fulltext() {
+    let plan =
+        crudcrate::database::plan_indexes::<indexed_item::IndexedItem>(DatabaseBackend::Postgres);
+    let fulltext = plan
+        .iter()
+        .find(|p| p.kind == crudcrate::database::IndexKind::Fulltext)
+        .expect("Postgres plan should contain a fulltext index");
+    assert_eq!(fulltext.name, "idx_indexed_items_fulltext");
+    assert_eq!(fulltext.columns, vec!["name", "notes"]);
+    assert!(fulltext.sql.contains("USING GIN (to_tsvector('english'"));
+}
+
+#[tokio::test]
+async fn test_ensure_indexes_creates_missing_indexes() {
+    let db = setup_test_db()
+        .await
+        .expect("Failed to setup test database");
+
+    let created = crudcrate::database::ensure_indexes::<indexed_item::IndexedItem>(&db)
+        .await
+        .expect("ensure_indexes should succeed");
+    assert_eq!(created.len(), 3);
+
+    assert_eq!(
+        sqlite_index_names(&db, "indexed_items").await,
+        vec![
+            "idx_indexed_items_name",
+            "idx_indexed_items_notes",
+            "idx_indexed_items_priority"
+        ]
+    );
+
+    // Second run is a no-op thanks to IF NOT EXISTS
+    crudcrate::database::ensure_indexes::<indexed_item::IndexedItem>(&db)
+        .await
+        .expect("ensure_indexes should be idempotent");
+    assert_eq!(sqlite_index_names(&db, "indexed_items").await.len(), 3);
+}
+
+#[tokio::test]
+async fn test_ensure_indexes_skips_resources_without_auto_index() {
+    let db = setup_test_db()
+        .await
+        .expect("Failed to setup test database");
+
+    let created = crudcrate::database::ensure_indexes::<plain_item::PlainItem>(&db)
+        .await
+        .expect("ensure_indexes should succeed");
+    assert!(created.is_empty());
+    assert!(sqlite_index_names(&db, "plain_items").await.is_empty());
+}
@@ -0,0 +1,131 @@
+// Tests for `PATCH /batch?per_item=true`
+// Verifies that per-item batch updates answer one result per item in request order
+// (200, 404, 422), commit the items that succeeded, and answer 200, 207 Multi-Status or,
+// when every item failed, 400 as `?partial=true` does, and that the `OpenAPI` document
+// describes the per-item results.
+
+use axum::Router;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod todo {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "todos")]
+    #[crudcrate(generate_router, api_struct = "Todo")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        pub completed: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use todo::Todo;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(todo::Entity)))
+        .await
+        .unwrap();
+    Router::new().nest("/todos", Todo::router(&db).into())
+}
+
+async fn create(app: &Router, title: &str) -> String {
+    let body = json!({"title": title, "completed": false});
+    let (status, todo) = send(app, "POST", "/todos", Some(body)).await;
+    assert_eq!(status, 201, "{todo}");
+    todo["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_per_item_results() {
+    let app = setup().await;
+    let first = create(&app, "first").await;
+    let second = create(&app, "second").await;
+    let unknown = Uuid::new_v4().to_string();
+
+    let items = json!([
+        {"id": first, "completed": true},
+        {"id": unknown, "completed": true},
+        {"completed": true},
+        {"id": second, "completed": "yes"},
+        {"id": second, "title": "second, renamed"}
+    ]);
+    let (status, results) = send(&app, "PATCH", "/todos/batch?per_item=true", Some(items)).await;
+    assert_eq!(status, 207, "{results}");
+
+    let statuses: Vec<_> = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| (result["index"].clone(), result["status"].clone()))
+        .collect();
+    let expected = [(0, 200), (1, 404), (2, 422), (3, 422), (4, 200)];
+    assert_eq!(
+        statuses,
+        expected.map(|(index, status)| (json!(index), json!(status))),
+        "{results}"
+    );
+    assert_eq!(results[0]["body"]["completed"], true, "{results}");
+    assert_eq!(results[1]["id"], unknown.as_str(), "{results}");
+    assert!(results[1]["error"].as_str().unwrap().contains("not found"));
+    assert_eq!(results[2].get("id"), None, "{results}");
+    assert_eq!(results[3].get("body"), None, "{results}");
+
+    // The successful items were committed, the failed ones left no writes
+    let (_, todo) = send(&app, "GET", &format!("/todos/{first}"), None).await;
+    assert_eq!(todo["completed"], true);
+    let (_, todo) = send(&app, "GET", &format!("/todos/{second}"), None).await;
+    assert_eq!(todo["title"], "second, renamed");
+    assert_eq!(todo["completed"], false);
+
+    let items = json!([{"id": first, "title": "done"}, {"id": second, "completed": true}]);
+    let (status, results) = send(&app, "PATCH", "/todos/batch?per_item=true", Some(items)).await;
+    assert_eq!(status, 200, "{results}");
+    assert_eq!(results[1]["body"]["completed"], true, "{results}");
+
+    let items = json!([{"id": unknown, "completed": true}, {"completed": true}]);
+    let (status, results) = send(&app, "PATCH", "/todos/batch?per_item=true", Some(items)).await;
+    assert_eq!(status, 400, "{results}");
+    assert_eq!(results[0]["status"], 404, "{results}");
+    assert_eq!(results[1]["status"], 422, "{results}");
+    let items = json!([{"id": unknown, "completed": true}]);
+    let (status, _) = send(&app, "PATCH", "/todos/batch?partial=true", Some(items)).await;
+    assert_eq!(status, 400, "partial mode answers all failures the same");
+
+    // Without `per_item` one bad item still fails the whole batch
+    let items = json!([{"id": first, "completed": false}, {"completed": true}]);
+    let (status, _) = send(&app, "PATCH", "/todos/batch", Some(items)).await;
+    assert_eq!(status, 422);
+    let (_, todo) = send(&app, "GET", &format!("/todos/{first}"), None).await;
+    assert_eq!(todo["completed"], true);
+}
+
+#[tokio::test]
+async fn test_per_item_results_are_documented() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let (_, api) = Todo::router(&db).split_for_parts();
+    let api = serde_json::to_value(api).unwrap();
+    let responses = &api["paths"]["/batch"]["patch"]["responses"];
+    for status in ["200", "207", "400"] {
+        let schema = &responses[status]["content"]["application/json"]["schema"]["$ref"];
+        let name = schema.as_str().unwrap().rsplit('/').next().unwrap();
+        let schema = api["components"]["schemas"][name].to_string();
+        assert!(schema.contains("ItemResult"), "{status}: {schema}");
+    }
+}
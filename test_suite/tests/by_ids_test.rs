@@ -0,0 +1,143 @@
+// Tests for the generated `GET /by-ids` and `POST /by-ids` endpoints
+// Verifies that items come back in the requested order with missing IDs reported,
+// repeated IDs answered once, invalid IDs and too many IDs rejected, the scope condition
+// applied, and that the read-only router serves both forms.
+
+use axum::http::StatusCode;
+use axum::{Extension, Router};
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod book {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "books")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Book",
+        name_singular = "book",
+        name_plural = "books",
+        max_page_size = 3
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub title: String,
+
+        pub published: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use book::Book;
+
+async fn setup() -> (DatabaseConnection, Router, Vec<Uuid>) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(book::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/books", Book::router(&db).into());
+    let mut ids = Vec::new();
+    for (title, published) in [("Dune", true), ("Emma", true), ("Ulysses", false)] {
+        let body = json!({"title": title, "published": published});
+        let (status, body) = send(&app, "POST", "/books", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        ids.push(Uuid::parse_str(body["id"].as_str().unwrap()).unwrap());
+    }
+    (db, app, ids)
+}
+
+fn titles(body: &Value) -> Vec<&str> {
+    body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["title"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_get_returns_requested_order_and_missing() {
+    let (_, app, ids) = setup().await;
+    let unknown = Uuid::new_v4();
+    let uri = format!("/books/by-ids?ids={},{unknown},{}", ids[2], ids[0]);
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["Ulysses", "Dune"]);
+    assert_eq!(body["missing"], json!([unknown]));
+
+    let (status, body) = send(&app, "GET", "/books/by-ids?ids=", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!({"items": [], "missing": []}));
+}
+
+#[tokio::test]
+async fn test_post_answers_repeated_ids_once() {
+    let (_, app, ids) = setup().await;
+    let body = json!([ids[1], ids[0], ids[1]]);
+    let (status, body) = send(&app, "POST", "/books/by-ids", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["Emma", "Dune"]);
+    assert_eq!(body["missing"], json!([]));
+}
+
+#[tokio::test]
+async fn test_invalid_and_too_many_ids_are_rejected() {
+    let (_, app, ids) = setup().await;
+    let uri = format!("/books/by-ids?ids={},not-a-uuid", ids[0]);
+    let (status, _) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let body = json!([ids[0], ids[1], ids[2], Uuid::new_v4()]);
+    let (status, _) = send(&app, "POST", "/books/by-ids", Some(body)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_scope_reports_hidden_rows_as_missing() {
+    let (db, _, ids) = setup().await;
+    let scoped: Router = Book::router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all().add(book::Column::Published.eq(true)),
+        }))
+        .into();
+    let app = Router::new().nest("/books", scoped);
+    let (status, body) = send(&app, "POST", "/books/by-ids", Some(json!(ids))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["Dune", "Emma"]);
+    assert_eq!(body["missing"], json!([ids[2]]));
+}
+
+#[tokio::test]
+async fn test_find_by_ids_and_read_only_router() {
+    let (db, _, ids) = setup().await;
+    let found = crudcrate::core::by_ids::find_by_ids::<Book>(&db, &[ids[2], ids[1]], None)
+        .await
+        .unwrap();
+    let found: Vec<&str> = found.items.iter().map(|book| book.title.as_str()).collect();
+    assert_eq!(found, ["Ulysses", "Emma"]);
+
+    let app = Router::new().nest("/books", Book::read_only_router(&db).into());
+    let uri = format!("/books/by-ids?ids={}", ids[0]);
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["Dune"]);
+    let (status, body) = send(&app, "POST", "/books/by-ids", Some(json!([ids[1]]))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["Emma"]);
+    assert_eq!(Book::total_count(&db, &Condition::all()).await, 3);
+}
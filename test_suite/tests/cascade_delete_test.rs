@@ -0,0 +1,271 @@
+// Tests for `#[crudcrate(cascade_delete)]`
+// Verifies that deleting a customer also deletes its vehicles and their parts, that
+// batch deletes cascade the same way, that other customers' rows are kept, and that
+// `cascade_delete` returns the removed children deepest first.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::relationships::DeletedChild;
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod customer {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "customers")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Customer",
+        name_singular = "customer",
+        name_plural = "customers",
+        cascade_delete,
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, depth = 1))]
+        pub vehicles: Vec<super::vehicle::Vehicle>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::vehicle::Entity")]
+        Vehicles,
+    }
+
+    impl Related<super::vehicle::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Vehicles.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod vehicle {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "vehicles")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Vehicle",
+        name_singular = "vehicle",
+        name_plural = "vehicles",
+        cascade_delete,
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub customer_id: Uuid,
+
+        pub make: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, depth = 1))]
+        pub parts: Vec<super::part::Part>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::customer::Entity",
+            from = "Column::CustomerId",
+            to = "super::customer::Column::Id"
+        )]
+        Customer,
+        #[sea_orm(has_many = "super::part::Entity")]
+        Parts,
+    }
+
+    impl Related<super::customer::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Customer.def()
+        }
+    }
+
+    impl Related<super::part::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Parts.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod part {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "parts")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Part",
+        name_singular = "part",
+        name_plural = "parts",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub vehicle_id: Uuid,
+
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::vehicle::Entity",
+            from = "Column::VehicleId",
+            to = "super::vehicle::Column::Id"
+        )]
+        Vehicle,
+    }
+
+    impl Related<super::vehicle::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Vehicle.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use customer::Customer;
+use part::Part;
+use vehicle::Vehicle;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(customer::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(vehicle::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(part::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/customers", Customer::router(&db).into());
+    (db, app)
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
+
+/// A customer with one vehicle that has two parts; returns the customer, vehicle and part ids
+async fn customer_with_vehicle(db: &DatabaseConnection, name: &str) -> (Uuid, Uuid, Vec<Uuid>) {
+    let customer = Customer::create(db, from_json(json!({"name": name})))
+        .await
+        .unwrap();
+    let vehicle = Vehicle::create(
+        db,
+        from_json(json!({"customer_id": customer.id, "make": "Volvo"})),
+    )
+    .await
+    .unwrap();
+    let mut parts = Vec::new();
+    for name in ["wheel", "door"] {
+        let part = Part::create(
+            db,
+            from_json(json!({"vehicle_id": vehicle.id, "name": name})),
+        )
+        .await
+        .unwrap();
+        parts.push(part.id);
+    }
+    (customer.id, vehicle.id, parts)
+}
+
+async fn counts(db: &DatabaseConnection) -> (u64, u64, u64) {
+    (
+        Customer::total_count(db, &Condition::all()).await,
+        Vehicle::total_count(db, &Condition::all()).await,
+        Part::total_count(db, &Condition::all()).await,
+    )
+}
+
+#[tokio::test]
+async fn test_delete_removes_children_and_grandchildren() {
+    let (db, app) = setup().await;
+    let (ada, _, _) = customer_with_vehicle(&db, "Ada").await;
+    customer_with_vehicle(&db, "Grace").await;
+    assert_eq!(counts(&db).await, (2, 2, 4));
+
+    let (status, body) = send(&app, "DELETE", &format!("/customers/{ada}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT, "{body}");
+    assert_eq!(counts(&db).await, (1, 1, 2), "Grace's rows are kept");
+
+    let (status, _) = send(&app, "DELETE", &format!("/customers/{ada}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_batch_delete_cascades() {
+    let (db, app) = setup().await;
+    let (ada, _, _) = customer_with_vehicle(&db, "Ada").await;
+    let (grace, _, _) = customer_with_vehicle(&db, "Grace").await;
+
+    let (status, body) = send(
+        &app,
+        "DELETE",
+        "/customers/batch",
+        Some(json!([ada, grace])),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(counts(&db).await, (0, 0, 0));
+}
+
+#[tokio::test]
+async fn test_cascade_delete_lists_children_deepest_first() {
+    let (db, _) = setup().await;
+    let (ada, vehicle, parts) = customer_with_vehicle(&db, "Ada").await;
+
+    let mut deleted = Customer::cascade_delete(&db, &[ada]).await.unwrap();
+    let vehicle_row = deleted.pop().unwrap();
+    assert_eq!(
+        vehicle_row,
+        DeletedChild {
+            resource: "vehicle",
+            id: vehicle
+        }
+    );
+    let mut part_ids: Vec<Uuid> = deleted
+        .iter()
+        .map(|child| {
+            assert_eq!(child.resource, "part");
+            child.id
+        })
+        .collect();
+    let mut expected = parts.clone();
+    part_ids.sort();
+    expected.sort();
+    assert_eq!(part_ids, expected);
+
+    // Only the children are removed; the parent is left to the caller
+    assert_eq!(counts(&db).await, (1, 0, 0));
+    assert!(Part::cascade_delete(&db, &parts).await.unwrap().is_empty());
+}
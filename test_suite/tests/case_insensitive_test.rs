@@ -0,0 +1,177 @@
+// Tests for `#[crudcrate(ci)]` string fields
+// Verifies that ci fields match exact values regardless of case (where plain string
+// fields match substrings), that IN lists and `_neq` follow the same collation, and
+// that on SQLite the generated condition is answered from the NOCASE index that
+// ensure_indexes creates.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::filtering::apply_filters;
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, QueryFilter, QueryTrait, Schema, Statement};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod user {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "users")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "User",
+        name_singular = "user",
+        name_plural = "users",
+        derive_partial_eq,
+        derive_eq,
+        auto_index
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, ci)]
+        pub email: String,
+
+        #[crudcrate(filterable)]
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use user::User;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(user::Entity)))
+        .await
+        .unwrap();
+    crudcrate::database::ensure_indexes::<User>(&db)
+        .await
+        .unwrap();
+    let app = Router::new().nest("/users", User::router(&db).into());
+
+    for (email, name) in [
+        ("Ana@Example.com", "Ana"),
+        ("ana@example.com.au", "Anabel"),
+        ("BO@example.com", "Bo"),
+    ] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/users",
+            Some(json!({"email": email, "name": name})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    (db, app)
+}
+
+async fn emails(app: &Router, filter: &Value) -> Vec<String> {
+    let filter = url_escape::encode_component(&filter.to_string()).to_string();
+    let (status, body) = send(app, "GET", &format!("/users?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut emails: Vec<String> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| user["email"].as_str().unwrap().to_string())
+        .collect();
+    emails.sort();
+    emails
+}
+
+#[test]
+fn test_ci_fields_are_listed() {
+    assert_eq!(User::case_insensitive_columns(), vec!["email"]);
+}
+
+#[tokio::test]
+async fn test_ci_field_matches_exact_value_in_any_case() {
+    let (_, app) = setup().await;
+
+    assert_eq!(
+        emails(&app, &json!({"email": "ana@example.com"})).await,
+        ["Ana@Example.com"]
+    );
+
+    // Plain string fields keep matching substrings
+    assert_eq!(
+        emails(&app, &json!({"name": "ana"})).await,
+        ["Ana@Example.com", "ana@example.com.au"]
+    );
+}
+
+#[tokio::test]
+async fn test_ci_in_list_and_neq() {
+    let (_, app) = setup().await;
+
+    assert_eq!(
+        emails(
+            &app,
+            &json!({"email": ["ANA@EXAMPLE.COM", "bo@example.com"]})
+        )
+        .await,
+        ["Ana@Example.com", "BO@example.com"]
+    );
+    assert_eq!(
+        emails(&app, &json!({"email_neq": "bo@EXAMPLE.com"})).await,
+        ["Ana@Example.com", "ana@example.com.au"]
+    );
+}
+
+#[tokio::test]
+async fn test_sqlite_uses_nocase_index() {
+    let (db, _) = setup().await;
+
+    let condition = apply_filters::<User>(
+        Some(r#"{"email":"ana@example.com"}"#.to_string()),
+        &User::filterable_columns(),
+        db.get_database_backend(),
+    )
+    .unwrap();
+    let query = user::Entity::find()
+        .filter(condition)
+        .build(db.get_database_backend());
+
+    let plan = db
+        .query_all(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            format!("EXPLAIN QUERY PLAN {}", query.sql),
+            query.values.map(|values| values.0).unwrap_or_default(),
+        ))
+        .await
+        .unwrap();
+    let details: Vec<String> = plan
+        .iter()
+        .map(|row| row.try_get::<String>("", "detail").unwrap())
+        .collect();
+    assert!(
+        details
+            .iter()
+            .any(|detail| detail.contains("USING INDEX idx_users_email")),
+        "{details:?}"
+    );
+}
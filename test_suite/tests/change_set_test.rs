@@ -0,0 +1,234 @@
+// Tests for the `ChangeSet` passed to `update::one::post` and `update::many::post` hooks
+// Verifies that only columns whose value actually changed are reported, with their old
+// and new values, that `on_update` columns are included, that batch updates get one
+// set per item, and that `sensitive` and `hash_with` values are masked.
+
+use axum::Router;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use crudcrate::testing::send;
+use crudcrate::{ApiError, ChangeSet, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema, Value};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Change sets seen by the post hooks, by task ID
+static SEEN: LazyLock<Mutex<HashMap<Uuid, ChangeSet>>> = LazyLock::new(Mutex::default);
+
+#[allow(clippy::unused_async)]
+async fn record_change(
+    _db: &DatabaseConnection,
+    task: &task::Task,
+    changes: &ChangeSet,
+) -> Result<(), ApiError> {
+    SEEN.lock().unwrap().insert(task.id, changes.clone());
+    Ok(())
+}
+
+#[allow(clippy::unused_async)]
+async fn record_changes(
+    _db: &DatabaseConnection,
+    tasks: &[task::Task],
+    changes: &[ChangeSet],
+) -> Result<(), ApiError> {
+    assert_eq!(tasks.len(), changes.len());
+    let mut seen = SEEN.lock().unwrap();
+    for (task, changes) in tasks.iter().zip(changes) {
+        seen.insert(task.id, changes.clone());
+    }
+    Ok(())
+}
+
+/// Stand-in for a password hash, reversible so leaks are easy to spot
+#[allow(clippy::needless_pass_by_value, clippy::unused_async)]
+async fn fake_hash(pin: String) -> Result<String, ApiError> {
+    Ok(format!("hashed:{}", pin.chars().rev().collect::<String>()))
+}
+
+pub mod task {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "tasks")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Task",
+        name_singular = "task",
+        name_plural = "tasks",
+        update::one::post = super::record_change,
+        update::many::post = super::record_changes,
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        pub done: bool,
+
+        #[crudcrate(sensitive)]
+        pub secret: Option<String>,
+
+        #[crudcrate(hash_with = super::fake_hash)]
+        pub pin: Option<String>,
+
+        #[crudcrate(exclude(create, update), on_create = Utc::now(), on_update = Utc::now())]
+        pub updated_at: DateTime<Utc>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use task::Task;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(task::Entity)))
+        .await
+        .unwrap();
+    Router::new().nest("/tasks", Task::router(&db).into())
+}
+
+async fn create_task(app: &Router, title: &str) -> Uuid {
+    let (status, body) = send(
+        app,
+        "POST",
+        "/tasks",
+        Some(json!({"title": title, "done": false})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    body["id"].as_str().unwrap().parse().unwrap()
+}
+
+fn seen(id: Uuid) -> ChangeSet {
+    SEEN.lock()
+        .unwrap()
+        .get(&id)
+        .cloned()
+        .expect("post hook ran")
+}
+
+#[tokio::test]
+async fn test_update_reports_changed_columns() {
+    let app = setup().await;
+    let id = create_task(&app, "write docs").await;
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/tasks/{id}"),
+        Some(json!({"title": "write more docs", "done": false})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let changes = seen(id);
+    // `done` was sent but kept its value
+    assert_eq!(
+        changes.columns().collect::<Vec<_>>(),
+        ["title", "updated_at"]
+    );
+    let title = changes.get("title").unwrap();
+    assert_eq!(title.old, Value::from("write docs"));
+    assert_eq!(title.new, Value::from("write more docs"));
+    assert!(!changes.contains("done"));
+}
+
+#[tokio::test]
+async fn test_unchanged_values_are_left_out() {
+    let app = setup().await;
+    let id = create_task(&app, "same").await;
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/tasks/{id}"),
+        Some(json!({"title": "same"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Only the on_update timestamp moved
+    let changes = seen(id);
+    assert_eq!(changes.len(), 1);
+    assert!(changes.contains("updated_at"));
+}
+
+#[tokio::test]
+async fn test_batch_update_gets_one_set_per_item() {
+    let app = setup().await;
+    let first = create_task(&app, "first").await;
+    let second = create_task(&app, "second").await;
+
+    let (status, body) = send(
+        &app,
+        "PATCH",
+        "/tasks/batch",
+        Some(json!([
+            {"id": first, "done": true},
+            {"id": second, "title": "second!"}
+        ])),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let first = seen(first);
+    assert_eq!(first.get("done").unwrap().new, Value::from(true));
+    assert!(!first.contains("title"));
+
+    let second = seen(second);
+    assert_eq!(second.get("title").unwrap().old, Value::from("second"));
+    assert!(!second.contains("done"));
+}
+
+#[tokio::test]
+async fn test_secret_values_are_masked() {
+    let app = setup().await;
+    let id = create_task(&app, "guarded").await;
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/tasks/{id}"),
+        Some(json!({"secret": "open sesame", "pin": "1234"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let changes = seen(id);
+    let masked = Value::from("***");
+    for column in ["secret", "pin"] {
+        let change = changes
+            .get(column)
+            .expect("masked columns are still reported");
+        assert_eq!(change.old, masked);
+        assert_eq!(change.new, masked);
+    }
+    let logged = format!("{changes:?}");
+    assert!(!logged.contains("open sesame"), "{logged}");
+    assert!(!logged.contains("4321"), "{logged}");
+}
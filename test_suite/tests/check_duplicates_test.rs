@@ -0,0 +1,137 @@
+// Tests for `#[crudcrate(dedupe_on(...))]`
+// Verifies that `POST /check-duplicates` returns rows matching any listed field
+// case-insensitively, inserts nothing, ignores empty and null values, applies the scope
+// condition, and is left out of the read-only router.
+
+use axum::http::StatusCode;
+use axum::{Extension, Router};
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod customer {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "customers")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Customer",
+        name_singular = "customer",
+        name_plural = "customers",
+        dedupe_on(name, email)
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub name: String,
+
+        pub email: Option<String>,
+
+        pub active: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use customer::Customer;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(customer::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/customers", Customer::router(&db).into());
+    for (name, email, active) in [
+        ("Ada Lovelace", Some("ada@example.com"), true),
+        ("Grace Hopper", None, true),
+        ("Alan Turing", Some("alan@example.com"), false),
+    ] {
+        let body = json!({"name": name, "email": email, "active": active});
+        let (status, body) = send(&app, "POST", "/customers", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    (db, app)
+}
+
+fn names(body: &serde_json::Value) -> Vec<&str> {
+    let mut names: Vec<&str> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["name"].as_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+#[tokio::test]
+async fn test_matches_any_field_ignoring_case() {
+    let (db, app) = setup().await;
+
+    let body = json!({"name": "  grace HOPPER ", "email": "ADA@example.com", "active": true});
+    let (status, body) = send(&app, "POST", "/customers/check-duplicates", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(names(&body), ["Ada Lovelace", "Grace Hopper"]);
+
+    let body = json!({"name": "Katherine Johnson", "active": true});
+    let (status, body) = send(&app, "POST", "/customers/check-duplicates", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!([]));
+
+    // Nothing was inserted
+    assert_eq!(Customer::total_count(&db, &Condition::all()).await, 3);
+}
+
+#[tokio::test]
+async fn test_empty_and_null_values_match_nothing() {
+    let (_, app) = setup().await;
+    let body = json!({"name": "   ", "email": null, "active": true});
+    let (status, body) = send(&app, "POST", "/customers/check-duplicates", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!([]));
+}
+
+#[tokio::test]
+async fn test_scope_hides_rows_outside_it() {
+    let (db, _) = setup().await;
+    let scoped: Router = Customer::router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all().add(customer::Column::Active.eq(true)),
+        }))
+        .into();
+    let app = Router::new().nest("/customers", scoped);
+    let body = json!({"name": "Alan Turing", "email": "ada@example.com", "active": true});
+    let (status, body) = send(&app, "POST", "/customers/check-duplicates", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(names(&body), ["Ada Lovelace"]);
+}
+
+#[tokio::test]
+async fn test_find_duplicates_and_read_only_router() {
+    let (db, _) = setup().await;
+    let create: customer::CustomerCreate =
+        serde_json::from_value(json!({"name": "ALAN TURING", "active": false})).unwrap();
+    let found = crudcrate::core::duplicates::find_duplicates::<Customer>(&db, &create, None)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "Alan Turing");
+    assert_eq!(Customer::dedupe_fields(), ["name", "email"]);
+
+    let app = Router::new().nest("/customers", Customer::read_only_router(&db).into());
+    let body = json!({"name": "Ada Lovelace", "active": true});
+    let (status, _) = send(&app, "POST", "/customers/check-duplicates", Some(body)).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+}
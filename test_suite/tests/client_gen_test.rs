@@ -0,0 +1,162 @@
+// Tests for `crudcrate::client_gen::rust_client`
+// Verifies that the generated client module has each resource's model structs, typed
+// filter and sort helpers for its filterable and sortable fields, relations typed as
+// the related resource, and a client built on the registered path.
+
+use crudcrate::EntityToModels;
+use crudcrate::client_gen::rust_client;
+use crudcrate::registry::ResourceMetadata;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+pub mod shelf {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "shelves")]
+    #[crudcrate(generate_router, api_struct = "Shelf", name_plural = "shelves")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        #[crudcrate(filterable, sortable, fulltext)]
+        pub label: String,
+        #[crudcrate(filterable, sortable)]
+        pub capacity: i32,
+        pub notes: Option<String>,
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, depth = 1))]
+        pub volumes: Vec<super::volume::Volume>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::volume::Entity")]
+        Volumes,
+    }
+
+    impl Related<super::volume::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Volumes.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod volume {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "volumes")]
+    #[crudcrate(generate_router, api_struct = "Volume", derive_partial_eq)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        pub shelf_id: Uuid,
+        #[crudcrate(filterable)]
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::shelf::Entity",
+            from = "Column::ShelfId",
+            to = "super::shelf::Column::Id"
+        )]
+        Shelf,
+    }
+
+    impl Related<super::shelf::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Shelf.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+fn source() -> String {
+    rust_client(&[
+        ResourceMetadata::of::<shelf::Shelf>(),
+        ResourceMetadata::of::<volume::Volume>(),
+    ])
+}
+
+#[test]
+fn test_models_follow_field_flags() {
+    let source = source();
+    assert!(source.contains("pub enum ClientError"), "{source}");
+    assert!(source.contains("pub struct Shelf {"), "{source}");
+    assert!(source.contains("pub struct ShelfList {"), "{source}");
+    assert!(source.contains("pub struct ShelfCreate {"), "{source}");
+    assert!(source.contains("pub struct ShelfUpdate {"), "{source}");
+    assert!(source.contains("    pub id: String,"), "{source}");
+    assert!(
+        source.contains("    pub notes: Option<String>,"),
+        "{source}"
+    );
+    // Update fields are optional; nullable ones can be cleared
+    assert!(
+        source.contains("    pub capacity: Option<i32>,"),
+        "{source}"
+    );
+    assert!(
+        source.contains("    pub notes: Option<Option<String>>,"),
+        "{source}"
+    );
+    // join(one) relations appear on the single-item model only
+    assert!(source.contains("    pub volumes: Vec<Volume>,"), "{source}");
+    assert_eq!(source.matches("pub volumes").count(), 1, "{source}");
+}
+
+#[test]
+fn test_query_mirrors_filters_and_sorts() {
+    let source = source();
+    assert!(source.contains("pub struct ShelfQuery"), "{source}");
+    assert!(
+        source.contains("pub fn label_eq(self, value: impl Into<String>)"),
+        "{source}"
+    );
+    assert!(
+        source.contains("pub fn capacity_gte(self, value: i32)"),
+        "{source}"
+    );
+    assert!(!source.contains("pub fn label_gte"), "{source}");
+    assert!(source.contains("pub fn search(self"), "{source}");
+    assert!(
+        source.contains("pub enum ShelfSort {\n    Label,\n    Capacity,\n}"),
+        "{source}"
+    );
+    assert!(source.contains("pub fn title_neq"), "{source}");
+}
+
+#[test]
+fn test_client_uses_registered_path() {
+    let source = source();
+    assert!(source.contains("pub struct ShelfClient"), "{source}");
+    assert!(
+        source.contains(r#"format!("{}/shelves", base_url"#),
+        "{source}"
+    );
+    assert!(
+        source.contains(r#"format!("{}/volumes", base_url"#),
+        "{source}"
+    );
+    assert!(
+        source.contains(
+            "pub async fn list(&self, query: &ShelfQuery) -> Result<Vec<ShelfList>, ClientError>"
+        ),
+        "{source}"
+    );
+    assert!(
+        source.contains("pub async fn get(&self, id: &str) -> Result<Shelf, ClientError>"),
+        "{source}"
+    );
+    assert!(
+        source.contains("pub async fn delete(&self, id: &str) -> Result<(), ClientError>"),
+        "{source}"
+    );
+}
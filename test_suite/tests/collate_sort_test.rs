@@ -0,0 +1,98 @@
+// Tests for `#[crudcrate(collate = "...")]` on sortable fields
+// Verifies that the collation is exposed through `sort_collations()`, that PostgreSQL
+// orders through it for single and multi-column sorts, and that other backends keep
+// sorting by the column itself.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::filtering::collation::collated_order;
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{PostgresQueryBuilder, Query};
+use sea_orm::{Database, DatabaseBackend, Order, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod person {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "people")]
+    #[crudcrate(generate_router, api_struct = "Person")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        #[crudcrate(sortable, collate = "sv-x-icu")]
+        pub name: String,
+        #[crudcrate(sortable)]
+        pub city: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use person::Person;
+
+#[test]
+fn test_sort_collations_lists_collated_fields() {
+    assert_eq!(Person::sort_collations(), [("name", "sv-x-icu")]);
+}
+
+#[test]
+fn test_postgres_orders_through_collation() {
+    let (expr, direction) =
+        collated_order::<Person>(person::Column::Name, Order::Desc, DatabaseBackend::Postgres)
+            .unwrap();
+    let sql = Query::select()
+        .column(person::Column::Id)
+        .from(person::Entity)
+        .order_by_expr(expr, direction)
+        .to_string(PostgresQueryBuilder);
+    assert!(
+        sql.ends_with(r#"ORDER BY "people"."name" COLLATE "sv-x-icu" DESC"#),
+        "{sql}"
+    );
+
+    let city =
+        collated_order::<Person>(person::Column::City, Order::Asc, DatabaseBackend::Postgres);
+    assert!(city.is_none());
+    let name = collated_order::<Person>(person::Column::Name, Order::Asc, DatabaseBackend::Sqlite);
+    assert!(name.is_none());
+}
+
+#[tokio::test]
+async fn test_sqlite_sorts_by_column() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(person::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/people", Person::router(&db).into());
+    for (name, city) in [("Örjan", "Umeå"), ("Anna", "Lund"), ("Zlatan", "Malmö")] {
+        let body = json!({"name": name, "city": city});
+        let (status, body) = send(&app, "POST", "/people", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+
+    for uri in [
+        "/people?sort_by=name&order=ASC",
+        "/people?sort=name_asc,city_asc",
+    ] {
+        let (status, body) = send(&app, "GET", uri, None).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let names: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|person| person["name"].as_str().unwrap())
+            .collect();
+        // SQLite's binary collation puts `Ö` after `Z`
+        assert_eq!(names, ["Anna", "Zlatan", "Örjan"], "{uri}");
+    }
+}
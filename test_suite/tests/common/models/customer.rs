@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use crudcrate::{EntityToModels, traits::CRUDResource};
+use crudcrate::EntityToModels;
 use sea_orm::entity::prelude::*;
 use uuid::Uuid;
 
@@ -12,7 +12,7 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
     pub id: Uuid,
-    #[crudcrate(filterable, sortable, like_filterable)]
+    #[crudcrate(filterable, sortable)]
     pub name: String,
     #[crudcrate(filterable)]
     pub email: String,
@@ -0,0 +1,206 @@
+// Tests for `computed` non-db fields
+// Verifies that get_one and get_all fill computed fields, that `computed_many` is called
+// once per list instead of once per item, and that computed fields stay out of the
+// create and update models.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, QuerySelect, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod author {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "authors")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Author",
+        name_singular = "author",
+        name_plural = "authors",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub name: String,
+
+        /// Filled with one query per list
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, computed = super::post_count, computed_many = super::post_counts)]
+        pub post_count: i64,
+
+        /// Filled per item
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, computed = super::shout)]
+        pub shouted: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod post {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "posts")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub author_id: Uuid,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use author::{Author, AuthorCreate, AuthorUpdate};
+
+static BATCH_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+async fn post_count(author: &Author, db: &DatabaseConnection) -> Result<i64, ApiError> {
+    let count = post::Entity::find()
+        .filter(post::Column::AuthorId.eq(author.id))
+        .count(db)
+        .await?;
+    Ok(i64::try_from(count).unwrap_or(i64::MAX))
+}
+
+async fn post_counts(authors: &[Author], db: &DatabaseConnection) -> Result<Vec<i64>, ApiError> {
+    BATCH_CALLS.fetch_add(1, Ordering::SeqCst);
+    let ids: Vec<Uuid> = authors.iter().map(|author| author.id).collect();
+    let author_ids: Vec<Uuid> = post::Entity::find()
+        .select_only()
+        .column(post::Column::AuthorId)
+        .filter(post::Column::AuthorId.is_in(ids))
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(authors
+        .iter()
+        .map(|author| {
+            let count = author_ids.iter().filter(|id| **id == author.id).count();
+            i64::try_from(count).unwrap_or(i64::MAX)
+        })
+        .collect())
+}
+
+#[allow(clippy::unused_async)]
+async fn shout(author: &Author, _db: &DatabaseConnection) -> Result<String, ApiError> {
+    Ok(author.name.to_uppercase())
+}
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(author::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(post::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/authors", Author::router(&db).into());
+    (db, app)
+}
+
+async fn create_author(app: &Router, name: &str) -> String {
+    let (status, body) = send(app, "POST", "/authors", Some(json!({"name": name}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    body["id"].as_str().unwrap().to_string()
+}
+
+async fn add_posts(db: &DatabaseConnection, author_id: &str, count: usize) {
+    let author_id = Uuid::parse_str(author_id).unwrap();
+    for _ in 0..count {
+        post::ActiveModel {
+            author_id: sea_orm::ActiveValue::Set(author_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_get_one_fills_computed_fields() {
+    let (db, app) = setup().await;
+    let id = create_author(&app, "ada").await;
+    add_posts(&db, &id, 3).await;
+
+    let (status, body) = send(&app, "GET", &format!("/authors/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["post_count"], 3);
+    assert_eq!(body["shouted"], "ADA");
+}
+
+#[tokio::test]
+async fn test_get_all_uses_batch_function_once() {
+    let (db, app) = setup().await;
+    let ada = create_author(&app, "ada").await;
+    let alan = create_author(&app, "alan").await;
+    create_author(&app, "grace").await;
+    add_posts(&db, &ada, 2).await;
+    add_posts(&db, &alan, 1).await;
+
+    let before = BATCH_CALLS.load(Ordering::SeqCst);
+    let sort = url_escape::encode_component(r#"["name","ASC"]"#);
+    let (status, body) = send(&app, "GET", &format!("/authors?sort={sort}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(BATCH_CALLS.load(Ordering::SeqCst) - before, 1);
+
+    let counts: Vec<_> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|author| (author["shouted"].clone(), author["post_count"].clone()))
+        .collect();
+    assert_eq!(
+        counts,
+        vec![
+            (json!("ADA"), json!(2)),
+            (json!("ALAN"), json!(1)),
+            (json!("GRACE"), json!(0)),
+        ]
+    );
+}
+
+#[test]
+fn test_computed_fields_not_in_write_models() {
+    for schema in [
+        serde_json::to_value(<AuthorCreate as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<AuthorUpdate as utoipa::PartialSchema>::schema()).unwrap(),
+    ] {
+        let properties = &schema["properties"];
+        assert!(properties.get("name").is_some(), "{schema}");
+        assert!(properties.get("post_count").is_none(), "{schema}");
+        assert!(properties.get("shouted").is_none(), "{schema}");
+    }
+}
@@ -0,0 +1,228 @@
+// Tests for `#[crudcrate(counted_relation = "...")]`
+// Verifies that the field holds the number of children in get_one and get_all (0 for a
+// parent without children), that it isn't part of the create/update models, and the
+// grouped count helper directly.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::relationships::count_related;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod vehicle {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "vehicles")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Vehicle",
+        name_singular = "vehicle",
+        name_plural = "vehicles",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub customer_id: Uuid,
+
+        pub make: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::customer::Entity",
+            from = "Column::CustomerId",
+            to = "super::customer::Column::Id"
+        )]
+        Customer,
+    }
+
+    impl Related<super::customer::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Customer.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod customer {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "customers")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Customer",
+        name_singular = "customer",
+        name_plural = "customers",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(
+            non_db_attr,
+            counted_relation = "Vehicles",
+            counted_resource = "super::vehicle::Vehicle"
+        )]
+        pub vehicle_count: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::vehicle::Entity")]
+        Vehicles,
+    }
+
+    impl Related<super::vehicle::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Vehicles.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use customer::{Customer, CustomerCreate, CustomerUpdate};
+use vehicle::Vehicle;
+
+/// Customers "ada" with two vehicles and "bob" with none
+async fn setup() -> (DatabaseConnection, Router, Uuid, Uuid) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(customer::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(vehicle::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/customers", Customer::router(&db).into())
+        .nest("/vehicles", Vehicle::router(&db).into());
+
+    let (status, ada) = send(
+        &app,
+        "POST",
+        "/customers",
+        Some(json!({"name": "ada", "vehicle_count": 99})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{ada}");
+    let (_, bob) = send(&app, "POST", "/customers", Some(json!({"name": "bob"}))).await;
+    for make in ["Volvo", "Saab"] {
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/vehicles",
+            Some(json!({"customer_id": ada["id"], "make": make})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+    let id = |value: &serde_json::Value| value["id"].as_str().unwrap().parse().unwrap();
+    (db, app, id(&ada), id(&bob))
+}
+
+#[tokio::test]
+async fn test_counts_in_get_all() {
+    let (_, app, _, _) = setup().await;
+
+    let (status, body) = send(&app, "GET", "/customers?sort_by=name&order=ASC", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let customers = body.as_array().unwrap();
+    assert_eq!(customers[0]["name"], "ada");
+    assert_eq!(customers[0]["vehicle_count"], 2);
+    assert_eq!(customers[1]["name"], "bob");
+    assert_eq!(customers[1]["vehicle_count"], 0);
+}
+
+#[tokio::test]
+async fn test_counts_in_get_one() {
+    let (_, app, ada, bob) = setup().await;
+
+    let (status, body) = send(&app, "GET", &format!("/customers/{ada}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["vehicle_count"], 2);
+
+    let (_, body) = send(&app, "GET", &format!("/customers/{bob}"), None).await;
+    assert_eq!(body["vehicle_count"], 0);
+}
+
+#[tokio::test]
+async fn test_count_related_helper() {
+    let (db, _, ada, bob) = setup().await;
+
+    let counts = count_related(
+        &db,
+        <customer::Relation as sea_orm::RelationTrait>::def(&customer::Relation::Vehicles),
+        None,
+        vec![ada, bob],
+    )
+    .await
+    .unwrap();
+    assert_eq!(counts.get(&ada), Some(&2));
+    assert_eq!(counts.get(&bob), None);
+
+    let empty = count_related::<Uuid>(
+        &db,
+        <customer::Relation as sea_orm::RelationTrait>::def(&customer::Relation::Vehicles),
+        None,
+        vec![],
+    )
+    .await
+    .unwrap();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_count_not_in_write_models() {
+    for schema in [
+        serde_json::to_value(<CustomerCreate as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<CustomerUpdate as utoipa::PartialSchema>::schema()).unwrap(),
+    ] {
+        assert!(
+            schema["properties"].get("vehicle_count").is_none(),
+            "{schema}"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+// Tests for the generated create model builder and `Default` impl
+// Verifies that `create_builder` generates `<Name>CreateBuilder` with setters and a
+// required-field check, and that create models whose fields are all optional or have
+// `on_create`/`default` expressions implement `Default`.
+
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use uuid::Uuid;
+
+pub mod todo {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "todos")]
+    #[crudcrate(generate_router, api_struct = "Todo", create_builder)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        pub notes: Option<String>,
+
+        #[crudcrate(on_create = "open".to_string())]
+        pub status: String,
+
+        #[crudcrate(default = 3)]
+        pub priority: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod setting {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "settings")]
+    #[crudcrate(generate_router, api_struct = "Setting")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub label: Option<String>,
+
+        #[crudcrate(on_create = true)]
+        pub enabled: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use setting::{Setting, SettingCreate};
+use todo::{Todo, TodoCreate};
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(todo::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(setting::Entity)))
+        .await
+        .unwrap();
+    db
+}
+
+#[test]
+fn test_builder_leaves_optional_fields_unset() {
+    let create = TodoCreate::builder().title("Write docs").build().unwrap();
+
+    assert_eq!(create.title, "Write docs");
+    assert_eq!(create.notes, None);
+    // `on_create` fields stay `None` so the expression applies on insert
+    assert_eq!(create.status, None);
+    assert_eq!(create.priority, 3);
+}
+
+#[test]
+fn test_builder_setters() {
+    let create = TodoCreate::builder()
+        .title("Write docs")
+        .notes("before Friday")
+        .status("blocked")
+        .priority(1)
+        .build()
+        .unwrap();
+
+    assert_eq!(create.notes.as_deref(), Some("before Friday"));
+    assert_eq!(create.status.as_deref(), Some("blocked"));
+    assert_eq!(create.priority, 1);
+}
+
+#[test]
+fn test_builder_requires_fields_without_defaults() {
+    let err = TodoCreate::builder().notes("no title").build().unwrap_err();
+    assert!(err.to_string().contains("title"), "{err}");
+}
+
+#[tokio::test]
+async fn test_built_model_creates_record() {
+    let db = setup().await;
+
+    let todo = Todo::create(
+        &db,
+        TodoCreate::builder().title("Write docs").build().unwrap(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(todo.status, "open");
+    assert_eq!(todo.priority, 3);
+}
+
+#[tokio::test]
+async fn test_default_create_model() {
+    let db = setup().await;
+
+    let create = SettingCreate::default();
+    assert_eq!(create.label, None);
+    assert_eq!(create.enabled, None);
+
+    let setting = Setting::create(&db, create).await.unwrap();
+    assert!(setting.enabled);
+    assert_eq!(setting.label, None);
+}
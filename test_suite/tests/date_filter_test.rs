@@ -0,0 +1,190 @@
+// Tests for calendar filters on date columns
+// Verifies that filterable date/time fields are reported with their storage kind, and that
+// `_date`, `_year` and date-only `_between` filters match whole days/years, honoring an
+// optional UTC offset on the value.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use crudcrate::filtering::dates::DateKind;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub mod event {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "events")]
+    #[crudcrate(generate_router, api_struct = "Event")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub happened_at: DateTime<Utc>,
+
+        #[crudcrate(filterable)]
+        pub due_on: Option<NaiveDate>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use event::{Event, EventCreate};
+
+fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+async fn setup() -> (DatabaseConnection, axum::Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(event::Entity)))
+        .await
+        .unwrap();
+
+    let items = vec![
+        EventCreate {
+            title: "Morning".into(),
+            happened_at: at(2024, 5, 1, 10, 0),
+            due_on: NaiveDate::from_ymd_opt(2024, 5, 3),
+        },
+        EventCreate {
+            title: "Late".into(),
+            happened_at: at(2024, 5, 1, 23, 30),
+            due_on: None,
+        },
+        EventCreate {
+            title: "Midnight".into(),
+            happened_at: at(2024, 5, 2, 0, 30),
+            due_on: NaiveDate::from_ymd_opt(2024, 6, 1),
+        },
+        EventCreate {
+            title: "NewYearsEve".into(),
+            happened_at: at(2023, 12, 31, 23, 0),
+            due_on: None,
+        },
+    ];
+    Event::create_many(&db, items).await.unwrap();
+
+    let app = axum::Router::new().nest("/events", Event::router(&db).into());
+    (db, app)
+}
+
+async fn titles(app: &axum::Router, filter: &serde_json::Value) -> (StatusCode, Vec<String>) {
+    let encoded = url_escape::encode_component(&filter.to_string()).to_string();
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/events?filter={encoded}&sort_by=title&order=ASC"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    if status != StatusCode::OK {
+        return (status, vec![]);
+    }
+    let items: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    let titles = items
+        .iter()
+        .map(|item| item["title"].as_str().unwrap().to_string())
+        .collect();
+    (status, titles)
+}
+
+#[test]
+fn test_date_filterable_columns() {
+    assert_eq!(
+        Event::date_filterable_columns(),
+        vec![
+            ("happened_at", DateKind::TimestampTz),
+            ("due_on", DateKind::Date)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_whole_day_match() {
+    let (_db, app) = setup().await;
+    let (status, happened) = titles(&app, &json!({"happened_at_date": "2024-05-01"})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(happened, vec!["Late", "Morning"]);
+
+    let (_, due) = titles(&app, &json!({"due_on_date": "2024-05-03"})).await;
+    assert_eq!(due, vec!["Morning"]);
+}
+
+#[tokio::test]
+async fn test_whole_day_match_with_offset() {
+    let (_db, app) = setup().await;
+    // In UTC+2, "Late" (23:30Z) and "Midnight" (00:30Z) both fall on May 2nd
+    let (_, titles) = titles(&app, &json!({"happened_at_date": "2024-05-02+02:00"})).await;
+    assert_eq!(titles, vec!["Late", "Midnight"]);
+}
+
+#[tokio::test]
+async fn test_year_match() {
+    let (_db, app) = setup().await;
+    let (_, titles_utc) = titles(&app, &json!({"happened_at_year": 2024})).await;
+    assert_eq!(titles_utc, vec!["Late", "Midnight", "Morning"]);
+
+    // 23:00Z on Dec 31st is already 2024 in UTC+2
+    let (_, titles_local) = titles(&app, &json!({"happened_at_year": "2024+02:00"})).await;
+    assert_eq!(
+        titles_local,
+        vec!["Late", "Midnight", "Morning", "NewYearsEve"]
+    );
+}
+
+#[tokio::test]
+async fn test_date_comparisons_and_between() {
+    let (_db, app) = setup().await;
+    let (_, after) = titles(&app, &json!({"happened_at_date_gte": "2024-05-02"})).await;
+    assert_eq!(after, vec!["Midnight"]);
+
+    let (_, between) = titles(
+        &app,
+        &json!({"happened_at_between": ["2024-05-01", "2024-05-02"]}),
+    )
+    .await;
+    assert_eq!(between, vec!["Late", "Midnight", "Morning"]);
+
+    let (_, any_of) = titles(
+        &app,
+        &json!({"happened_at_date": ["2023-12-31", "2024-05-02"]}),
+    )
+    .await;
+    assert_eq!(any_of, vec!["Midnight", "NewYearsEve"]);
+}
+
+#[tokio::test]
+async fn test_invalid_calendar_values_are_rejected() {
+    let (_db, app) = setup().await;
+    for filter in [
+        json!({"happened_at_date": "May 1st"}),
+        json!({"happened_at_date": "2024-05-01+25:00"}),
+        json!({"happened_at_year": "24"}),
+        json!({"happened_at_date_between": ["2024-05-01"]}),
+    ] {
+        let (status, _) = titles(&app, &filter).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{filter}");
+    }
+}
@@ -0,0 +1,221 @@
+// Tests for Decimal columns
+// Verifies that filterable `Decimal` fields are reported by decimal_filterable_columns(),
+// that numeric strings and numbers compare exactly (including `_between` and IN lists),
+// that malformed decimals are rejected, and that Create/Update models round-trip decimals.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use crudcrate::{CRUDResource, EntityToModels};
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use std::str::FromStr;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub mod part {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "parts")]
+    #[crudcrate(generate_router, api_struct = "Part")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub name: String,
+
+        #[crudcrate(filterable, sortable)]
+        pub price: Decimal,
+
+        #[crudcrate(filterable)]
+        pub discount: Option<Decimal>,
+
+        #[crudcrate(filterable)]
+        pub stock: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use part::{Part, PartCreate};
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+async fn setup() -> (DatabaseConnection, axum::Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(part::Entity)))
+        .await
+        .unwrap();
+
+    let items = vec![
+        PartCreate {
+            name: "Bolt".into(),
+            price: dec("0.25"),
+            discount: None,
+            stock: 500,
+        },
+        PartCreate {
+            name: "Filter".into(),
+            price: dec("19.99"),
+            discount: Some(dec("2.50")),
+            stock: 40,
+        },
+        PartCreate {
+            name: "Pump".into(),
+            price: dec("149.00"),
+            discount: Some(dec("15")),
+            stock: 3,
+        },
+    ];
+    Part::create_many(&db, items).await.unwrap();
+
+    let app = axum::Router::new().nest("/parts", Part::router(&db).into());
+    (db, app)
+}
+
+async fn names(app: &axum::Router, filter: &serde_json::Value) -> (StatusCode, Vec<String>) {
+    let encoded = url_escape::encode_component(&filter.to_string()).to_string();
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/parts?filter={encoded}&sort_by=name&order=ASC"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    if status != StatusCode::OK {
+        return (status, vec![]);
+    }
+    let items: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    let names = items
+        .iter()
+        .map(|item| item["name"].as_str().unwrap().to_string())
+        .collect();
+    (status, names)
+}
+
+#[test]
+fn test_decimal_filterable_columns() {
+    assert_eq!(
+        Part::decimal_filterable_columns(),
+        vec!["price", "discount"]
+    );
+}
+
+#[tokio::test]
+async fn test_decimal_comparisons_accept_strings_and_numbers() {
+    let (_db, app) = setup().await;
+
+    let (status, names_gte) = names(&app, &json!({"price_gte": "19.99"})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names_gte, vec!["Filter", "Pump"]);
+
+    let (_, names_lt) = names(&app, &json!({"price_lt": 19.99})).await;
+    assert_eq!(names_lt, vec!["Bolt"]);
+
+    let (_, names_eq) = names(&app, &json!({"price": "149"})).await;
+    assert_eq!(names_eq, vec!["Pump"]);
+}
+
+#[tokio::test]
+async fn test_decimal_between_and_in() {
+    let (_db, app) = setup().await;
+
+    let (_, between) = names(&app, &json!({"price_between": ["0.25", "20"]})).await;
+    assert_eq!(between, vec!["Bolt", "Filter"]);
+
+    let (_, in_list) = names(&app, &json!({"price": ["0.25", "149.00"]})).await;
+    assert_eq!(in_list, vec!["Bolt", "Pump"]);
+
+    let (_, nulls) = names(&app, &json!({"discount": null})).await;
+    assert_eq!(nulls, vec!["Bolt"]);
+}
+
+#[tokio::test]
+async fn test_between_on_integer_column() {
+    let (_db, app) = setup().await;
+    let (_, names) = names(&app, &json!({"stock_between": [3, 40]})).await;
+    assert_eq!(names, vec!["Filter", "Pump"]);
+}
+
+#[tokio::test]
+async fn test_invalid_decimal_filters_are_rejected() {
+    let (_db, app) = setup().await;
+    for filter in [
+        json!({"price_gte": "cheap"}),
+        json!({"price": "1e5"}),
+        json!({"price_between": ["1"]}),
+        json!({"stock_between": 3}),
+    ] {
+        let (status, _) = names(&app, &filter).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{filter}");
+    }
+}
+
+#[tokio::test]
+async fn test_decimal_create_and_update_round_trip() {
+    let (_db, app) = setup().await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/parts")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({"name": "Gasket", "price": "7.45", "discount": null, "stock": 12})
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let created: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let price = created["price"]
+        .as_str()
+        .expect("decimal serializes as string");
+    assert_eq!(dec(price), dec("7.45"));
+
+    let id = created["id"].as_str().unwrap();
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/parts/{id}"))
+                .header("content-type", "application/json")
+                .body(Body::from(json!({"discount": "0.45"}).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let updated: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(dec(updated["discount"].as_str().unwrap()), dec("0.45"));
+    assert_eq!(dec(updated["price"].as_str().unwrap()), dec("7.45"));
+}
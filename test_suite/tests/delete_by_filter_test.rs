@@ -0,0 +1,163 @@
+// Tests for `DELETE /resource?filter=...`
+// Verifies that matching rows are deleted and counted, that `count_only` is a dry run,
+// that missing, empty or unknown filters are rejected, and that the batch limit applies.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod job {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "jobs")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Job",
+        name_singular = "job",
+        name_plural = "jobs",
+        derive_partial_eq,
+        derive_eq,
+        batch_limit = 3
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub status: String,
+
+        #[crudcrate(filterable)]
+        pub attempts: i32,
+
+        pub notes: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use job::Job;
+
+async fn setup(jobs: &[(&str, i32)]) -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(job::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/jobs", Job::router(&db).into());
+
+    for (state, attempts) in jobs {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/jobs",
+            Some(json!({"status": state, "attempts": attempts, "notes": ""})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    app
+}
+
+async fn delete_where(app: &Router, filter: &Value, extra: &str) -> (StatusCode, Value) {
+    let filter = url_escape::encode_component(&filter.to_string()).to_string();
+    send(
+        app,
+        "DELETE",
+        &format!("/jobs?filter={filter}{extra}"),
+        None,
+    )
+    .await
+}
+
+async fn remaining(app: &Router) -> usize {
+    let (_, body) = send(app, "GET", "/jobs", None).await;
+    body.as_array().unwrap().len()
+}
+
+#[tokio::test]
+async fn test_deletes_matching_rows() {
+    let app = setup(&[("failed", 5), ("failed", 1), ("done", 5)]).await;
+
+    let (status, body) =
+        delete_where(&app, &json!({"status": "failed", "attempts_gte": 3}), "").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!({"matched": 1, "deleted": 1}));
+    assert_eq!(remaining(&app).await, 2);
+
+    let (status, body) = delete_where(&app, &json!({"status": "failed"}), "").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!({"matched": 1, "deleted": 1}));
+    assert_eq!(remaining(&app).await, 1);
+}
+
+#[tokio::test]
+async fn test_count_only_is_a_dry_run() {
+    let app = setup(&[("failed", 1), ("failed", 2), ("done", 1)]).await;
+
+    let (status, body) = delete_where(&app, &json!({"status": "failed"}), "&count_only=true").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!({"matched": 2, "deleted": 0}));
+    assert_eq!(remaining(&app).await, 3);
+}
+
+#[tokio::test]
+async fn test_rejects_missing_empty_and_unknown_filters() {
+    let app = setup(&[("failed", 1), ("done", 1)]).await;
+
+    let (status, _) = send(&app, "DELETE", "/jobs", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    for filter in [
+        json!({}),
+        json!({"notes": ""}),
+        json!({"missing": "x"}),
+        // A known key doesn't make an unknown one safe to ignore
+        json!({"status": "failed", "stauts": "done"}),
+    ] {
+        let (status, body) = delete_where(&app, &filter, "").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{filter}: {body}");
+    }
+    assert_eq!(remaining(&app).await, 2);
+}
+
+#[tokio::test]
+async fn test_batch_limit_applies() {
+    let app = setup(&[("failed", 1), ("failed", 2), ("failed", 3), ("failed", 4)]).await;
+
+    let (status, body) = delete_where(&app, &json!({"status": "failed"}), "").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert_eq!(remaining(&app).await, 4);
+
+    // Counting is not limited
+    let (_, body) = delete_where(&app, &json!({"status": "failed"}), "&count_only=true").await;
+    assert_eq!(body["matched"], 4);
+
+    let (status, body) =
+        delete_where(&app, &json!({"status": "failed", "attempts_lte": 3}), "").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["deleted"], 3);
+    assert_eq!(remaining(&app).await, 1);
+}
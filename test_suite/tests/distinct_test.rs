@@ -0,0 +1,219 @@
+// Tests for the generated `GET /distinct/{field}` endpoint
+// Verifies distinct values in ascending order with and without counts, that the
+// request's filter and scope narrow the rows, that renamed fields are looked up by
+// their external name, and that non-filterable or scope-excluded fields are rejected.
+
+use axum::http::StatusCode;
+use axum::{Extension, Router};
+use crudcrate::testing::send;
+use crudcrate::{EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod task {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "tasks")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Task",
+        name_singular = "task",
+        name_plural = "tasks",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub status: String,
+
+        #[crudcrate(filterable, rename = "prio")]
+        pub priority: i32,
+
+        #[crudcrate(filterable)]
+        pub done: bool,
+
+        #[crudcrate(filterable, exclude(scoped))]
+        pub owner: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use task::Task;
+
+/// Five tasks over three statuses; only "b" has an owner
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(task::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/tasks", Task::router(&db).into());
+
+    for (title, status, priority, done, owner) in [
+        ("a", "open", 1, false, None),
+        ("b", "open", 2, false, Some("ana")),
+        ("c", "done", 2, true, None),
+        ("d", "done", 3, true, None),
+        ("e", "blocked", 1, false, None),
+    ] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/tasks",
+            Some(json!({
+                "title": title,
+                "status": status,
+                "prio": priority,
+                "done": done,
+                "owner": owner
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    (db, app)
+}
+
+fn values(body: &Value) -> Vec<Value> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["value"].clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_distinct_values_in_ascending_order() {
+    let (_, app) = setup().await;
+
+    let (status, body) = send(&app, "GET", "/tasks/distinct/status", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(
+        values(&body),
+        [json!("blocked"), json!("done"), json!("open")]
+    );
+    assert!(body[0].get("count").is_none(), "{body}");
+
+    // Booleans keep their JSON type
+    let (status, body) = send(&app, "GET", "/tasks/distinct/done", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(values(&body), [json!(false), json!(true)]);
+}
+
+#[tokio::test]
+async fn test_counts() {
+    let (_, app) = setup().await;
+
+    let (status, body) = send(&app, "GET", "/tasks/distinct/status?counts=true", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(
+        body,
+        json!([
+            {"value": "blocked", "count": 1},
+            {"value": "done", "count": 2},
+            {"value": "open", "count": 2}
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_filter_narrows_the_rows() {
+    let (_, app) = setup().await;
+
+    let filter = url_escape::encode_component(r#"{"done":false}"#);
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/tasks/distinct/prio?counts=true&filter={filter}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(
+        body,
+        json!([{"value": 1, "count": 2}, {"value": 2, "count": 1}])
+    );
+}
+
+#[tokio::test]
+async fn test_null_is_a_value() {
+    let (_, app) = setup().await;
+
+    let (status, body) = send(&app, "GET", "/tasks/distinct/owner?counts=true", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(
+        body,
+        json!([{"value": null, "count": 4}, {"value": "ana", "count": 1}])
+    );
+}
+
+#[tokio::test]
+async fn test_non_filterable_field_is_rejected() {
+    let (_, app) = setup().await;
+
+    for field in ["title", "missing"] {
+        let (status, body) = send(&app, "GET", &format!("/tasks/distinct/{field}"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{field}: {body}");
+        assert_eq!(
+            body["error"],
+            format!("Field '{field}' is not filterable"),
+            "{body}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_read_only_router_applies_scope() {
+    let (db, _) = setup().await;
+    let public: Router = Task::read_only_router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all().add(task::Column::Done.eq(false)),
+        }))
+        .into();
+    let public = Router::new().nest("/tasks", public);
+
+    let (status, body) = send(&public, "GET", "/tasks/distinct/status", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(values(&body), [json!("blocked"), json!("open")]);
+
+    // Scope-excluded fields can't be listed
+    let (status, _) = send(&public, "GET", "/tasks/distinct/owner", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_openapi_lists_distinct_path() {
+    let (db, _) = setup().await;
+    let (_, openapi) = Task::router(&db).split_for_parts();
+    let openapi = serde_json::to_value(openapi).unwrap();
+    assert_eq!(
+        openapi["paths"]["/distinct/{field}"]["get"]["operationId"],
+        "get_distinct_tasks"
+    );
+}
@@ -0,0 +1,94 @@
+// Tests for the `enum_case_sensitive` struct attribute
+// Verifies that enum filter values must match a variant exactly when the flag is set,
+// and that unknown values are rejected instead of producing an empty condition.
+
+use crudcrate::{CRUDResource, EntityToModels, apply_filters};
+use sea_orm::DatabaseBackend;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod ticket {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        EnumIter,
+        DeriveActiveEnum,
+        Serialize,
+        Deserialize,
+        utoipa::ToSchema,
+        Default,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "Text")]
+    pub enum Priority {
+        #[default]
+        #[sea_orm(string_value = "low")]
+        Low,
+        #[sea_orm(string_value = "high")]
+        High,
+    }
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tickets")]
+    #[crudcrate(generate_router, api_struct = "Ticket", enum_case_sensitive)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub priority: Priority,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use ticket::Ticket;
+
+fn filter(filter: &str) -> Result<String, crudcrate::ApiError> {
+    apply_filters::<Ticket>(
+        Some(filter.to_string()),
+        &Ticket::filterable_columns(),
+        DatabaseBackend::Sqlite,
+    )
+    .map(|condition| format!("{condition:?}"))
+}
+
+#[test]
+fn test_flag_sets_const() {
+    const { assert!(Ticket::ENUM_CASE_SENSITIVE) };
+    assert_eq!(
+        Ticket::enum_variants("priority"),
+        Some(vec!["low".to_string(), "high".to_string()])
+    );
+}
+
+#[test]
+fn test_exact_value_matches_without_upper() {
+    let sql = filter(r#"{"priority": "high"}"#).expect("exact variant is accepted");
+    assert!(sql.contains("high"), "got: {sql}");
+    assert!(!sql.contains("Upper"), "got: {sql}");
+}
+
+#[test]
+fn test_wrong_case_is_rejected() {
+    assert!(filter(r#"{"priority": "HIGH"}"#).is_err());
+    assert!(filter(r#"{"priority": ["low", "High"]}"#).is_err());
+}
+
+#[test]
+fn test_non_enum_fields_unaffected() {
+    // Regular string filters stay case-insensitive
+    let sql = filter(r#"{"title": "Printer"}"#).expect("string filter");
+    assert!(sql.contains("PRINTER"), "got: {sql}");
+}
@@ -13,8 +13,9 @@ use serde_json::json;
 use tower::ServiceExt;
 
 mod common;
-use crate::common::vehicle::{FuelType, Transmission, VehicleList};
+use crate::common::vehicle::{FuelType, Transmission, Vehicle, VehicleList};
 use common::{create_test_customer, setup_test_app, setup_test_db};
+use crudcrate::CRUDResource;
 
 fn encode_filter(filter: &serde_json::Value) -> String {
     url_escape::encode_component(&filter.to_string()).to_string()
@@ -449,3 +450,78 @@ async fn test_second_enum_filter_works_without_annotation() {
     assert!(types.contains(&Some(Transmission::Automatic)));
     assert!(!types.contains(&Some(Transmission::Manual)));
 }
+
+async fn get_vehicles_status(
+    app: &axum::Router,
+    filter: &serde_json::Value,
+) -> (StatusCode, String) {
+    let encoded = encode_filter(filter);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/vehicles?filter={encoded}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8_lossy(&body).to_string())
+}
+
+/// Enum variants are reported for `ActiveEnum` fields only
+#[test]
+fn test_enum_variants_reported() {
+    assert_eq!(
+        Vehicle::enum_variants("fuel_type"),
+        Some(vec![
+            "Gasoline".to_string(),
+            "Diesel".to_string(),
+            "Electric".to_string()
+        ])
+    );
+    assert_eq!(
+        Vehicle::enum_variants("transmission"),
+        Some(vec![
+            "Manual".to_string(),
+            "Automatic".to_string(),
+            "CVT".to_string()
+        ])
+    );
+    assert_eq!(Vehicle::enum_variants("make"), None);
+    assert_eq!(Vehicle::enum_variants("year"), None);
+}
+
+/// Unknown enum values return 400 with the allowed values instead of an empty list
+#[tokio::test]
+async fn test_enum_field_invalid_value_rejected() {
+    let db = setup_test_db()
+        .await
+        .expect("Failed to setup test database");
+    let app = setup_test_app(&db);
+
+    let (status, body) = get_vehicles_status(&app, &json!({"fuel_type": "Hydrogen"})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(
+        body.contains("Gasoline, Diesel, Electric"),
+        "Error should list allowed values: {body}"
+    );
+
+    // One bad value in an IN list rejects the whole filter
+    let (status, _) =
+        get_vehicles_status(&app, &json!({"fuel_type": ["Diesel", "Hydrogen"]})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Operator suffixes are validated too
+    let (status, _) = get_vehicles_status(&app, &json!({"fuel_type_neq": "Steam"})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Valid values in any case still pass
+    let (status, _) = get_vehicles_status(&app, &json!({"fuel_type": "dIeSeL"})).await;
+    assert_eq!(status, StatusCode::OK);
+}
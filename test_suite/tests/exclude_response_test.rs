@@ -0,0 +1,209 @@
+// Tests for `exclude(response)` write-only fields
+// Verifies that the field is accepted on create/update and stored, but never appears
+// in single, list or nested join responses, nor in the OpenAPI schemas.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod member {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "members")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Member",
+        name_singular = "member",
+        name_plural = "members",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub team_id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub email: String,
+
+        #[crudcrate(exclude(response))]
+        pub password_hash: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::team::Entity",
+            from = "Column::TeamId",
+            to = "super::team::Column::Id"
+        )]
+        Team,
+    }
+
+    impl Related<super::team::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Team.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod team {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "teams")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Team",
+        name_singular = "team",
+        name_plural = "teams",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, all, depth = 1))]
+        pub members: Vec<super::member::Member>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::member::Entity")]
+        Members,
+    }
+
+    impl Related<super::member::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Members.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use member::{Member, MemberResponse};
+use team::Team;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(team::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(member::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/teams", Team::router(&db).into())
+        .nest("/members", Member::router(&db).into());
+    (db, app)
+}
+
+async fn seed(app: &Router) -> (Uuid, Uuid) {
+    let (_, team) = send(app, "POST", "/teams", Some(json!({"name": "Core"}))).await;
+    let team_id: Uuid = team["id"].as_str().unwrap().parse().unwrap();
+    let (status, member) = send(
+        app,
+        "POST",
+        "/members",
+        Some(json!({"team_id": team_id, "email": "ada@example.com", "password_hash": "argon2$secret"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(member.get("password_hash").is_none(), "{member}");
+    (team_id, member["id"].as_str().unwrap().parse().unwrap())
+}
+
+#[tokio::test]
+async fn test_field_is_written_but_never_returned() {
+    let (db, app) = setup().await;
+    let (_, member_id) = seed(&app).await;
+
+    let stored = member::Entity::find_by_id(member_id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.password_hash, "argon2$secret");
+
+    let (status, body) = send(&app, "GET", &format!("/members/{member_id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["email"], "ada@example.com");
+    assert!(body.get("password_hash").is_none());
+
+    let (_, body) = send(&app, "GET", "/members", None).await;
+    assert!(body[0].get("password_hash").is_none());
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/members/{member_id}"),
+        Some(json!({"password_hash": "argon2$rotated"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("password_hash").is_none());
+    let stored = member::Entity::find_by_id(member_id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.password_hash, "argon2$rotated");
+}
+
+#[tokio::test]
+async fn test_nested_join_responses_omit_field() {
+    let (_db, app) = setup().await;
+    let (team_id, _) = seed(&app).await;
+
+    let (_, body) = send(&app, "GET", &format!("/teams/{team_id}"), None).await;
+    let members = body["members"].as_array().unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0]["email"], "ada@example.com");
+    assert!(members[0].get("password_hash").is_none());
+
+    let (_, body) = send(&app, "GET", "/teams", None).await;
+    assert!(body[0]["members"][0].get("password_hash").is_none());
+}
+
+#[test]
+fn test_schemas_omit_field() {
+    for schema in [
+        serde_json::to_value(<Member as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<MemberResponse as utoipa::PartialSchema>::schema()).unwrap(),
+    ] {
+        assert!(schema["properties"].get("email").is_some(), "{schema}");
+        assert!(
+            schema["properties"].get("password_hash").is_none(),
+            "{schema}"
+        );
+    }
+}
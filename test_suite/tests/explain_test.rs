@@ -0,0 +1,132 @@
+// Tests for `?__explain=true` (debug feature)
+// Verifies that list endpoints return the query plan instead of the rows, that the
+// explained query carries the request's filter, sort and page, and that lists without
+// the parameter are unaffected.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod task {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "tasks")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Task",
+        name_singular = "task",
+        name_plural = "tasks",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        #[crudcrate(filterable, sortable)]
+        pub priority: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use task::Task;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(task::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/tasks", Task::router(&db).into());
+    (db, app)
+}
+
+#[tokio::test]
+async fn test_explain_returns_plan() {
+    let (_db, app) = setup().await;
+    send(
+        &app,
+        "POST",
+        "/tasks",
+        Some(json!({"title": "a", "priority": 1})),
+    )
+    .await;
+
+    let filter = url_escape::encode_component(&json!({"priority": 1}).to_string()).to_string();
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!(
+            "/tasks?filter={filter}&sort_by=title&order=DESC&page=2&per_page=5&__explain=true"
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["backend"], "sqlite");
+    assert!(!body["plan"].as_array().unwrap().is_empty(), "{body}");
+
+    let sql = body["sql"].as_str().unwrap();
+    assert!(sql.contains("\"priority\" = 1"), "{sql}");
+    assert!(sql.contains("\"title\" DESC"), "{sql}");
+    assert!(sql.contains("LIMIT 5 OFFSET 5"), "{sql}");
+}
+
+#[tokio::test]
+async fn test_lists_unaffected_without_explain() {
+    let (_db, app) = setup().await;
+    send(
+        &app,
+        "POST",
+        "/tasks",
+        Some(json!({"title": "a", "priority": 1})),
+    )
+    .await;
+
+    for uri in ["/tasks", "/tasks?__explain=false"] {
+        let (status, body) = send(&app, "GET", uri, None).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body.as_array().unwrap().len(), 1, "{uri}: {body}");
+    }
+}
+
+#[tokio::test]
+async fn test_explain_from_code() {
+    let (db, _app) = setup().await;
+    let plan =
+        crudcrate::debug::explain_get_all::<Task>(&db, &sea_orm::Condition::all(), &[], 0, 10)
+            .await
+            .unwrap();
+    assert_eq!(plan.backend, "sqlite");
+    assert!(
+        plan.plan.iter().any(|line| line.contains("tasks")),
+        "{plan:?}"
+    );
+}
@@ -0,0 +1,221 @@
+// Tests for `?facets=` on the list endpoint of fulltext-search resources
+// Verifies that the page comes back with value counts of the requested fields over all
+// rows matching the search and filter (not just the page), that renamed fields are
+// requested by their external name, and that unknown fields and resources without
+// fulltext search return 400. Without `facets` the body stays a bare array.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod article {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "articles")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Article",
+        name_singular = "article",
+        name_plural = "articles",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable, fulltext)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub status: String,
+
+        #[crudcrate(filterable, rename = "writtenBy")]
+        pub author: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod note {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub status: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use article::Article;
+use note::Note;
+
+/// Four articles about rust (three published), one about go, and one note
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(article::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/articles", Article::router(&db).into())
+        .nest("/notes", Note::router(&db).into());
+
+    for (title, status, author) in [
+        ("rust basics", "published", "ana"),
+        ("rust traits", "published", "ana"),
+        ("rust macros", "draft", "bo"),
+        ("async rust", "published", "bo"),
+        ("go channels", "published", "cy"),
+    ] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/articles",
+            Some(json!({"title": title, "status": status, "writtenBy": author})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    let (status, _) = send(&app, "POST", "/notes", Some(json!({"status": "open"}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    app
+}
+
+fn filter(json: &str) -> String {
+    format!("filter={}", url_escape::encode_component(json))
+}
+
+async fn list(app: &Router, query: &str) -> (StatusCode, Value) {
+    send(app, "GET", &format!("/articles?{query}"), None).await
+}
+
+#[tokio::test]
+async fn test_facets_count_every_matching_row() {
+    let app = setup().await;
+
+    // A one-row page still counts all four matches
+    let query = format!(
+        "{}&facets=status,writtenBy&page=1&per_page=1",
+        filter(r#"{"q":"rust"}"#)
+    );
+    let (status, body) = list(&app, &query).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["items"].as_array().unwrap().len(), 1, "{body}");
+    assert_eq!(
+        body["facets"],
+        json!({
+            "status": [
+                {"value": "draft", "count": 1},
+                {"value": "published", "count": 3}
+            ],
+            "writtenBy": [
+                {"value": "ana", "count": 2},
+                {"value": "bo", "count": 2}
+            ]
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_facets_follow_the_filter() {
+    let app = setup().await;
+
+    let query = format!(
+        "{}&facets=writtenBy",
+        filter(r#"{"q":"rust","status":"published"}"#)
+    );
+    let (status, body) = list(&app, &query).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["items"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        body["facets"]["writtenBy"],
+        json!([{"value": "ana", "count": 2}, {"value": "bo", "count": 1}])
+    );
+}
+
+#[tokio::test]
+async fn test_without_facets_body_is_an_array() {
+    let app = setup().await;
+
+    let (status, body) = list(&app, &filter(r#"{"q":"rust"}"#)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn test_unknown_facet_is_rejected() {
+    let app = setup().await;
+
+    for field in ["title", "nope"] {
+        let (status, body) = list(&app, &format!("facets=status,{field}")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{field}: {body}");
+        assert_eq!(body["error"], format!("Field '{field}' is not filterable"));
+    }
+}
+
+#[tokio::test]
+async fn test_resources_without_fulltext_reject_facets() {
+    let app = setup().await;
+
+    let (status, body) = send(&app, "GET", "/notes?facets=status", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert_eq!(
+        body["error"],
+        "Facets are only available on resources with fulltext search"
+    );
+}
@@ -0,0 +1,152 @@
+// Tests for generated test data factories (`testing` feature)
+// Verifies placeholder values for unset fields, builder setters, `on_create`
+// expressions, batch creation with distinct values, and the error for fields
+// that have no placeholder.
+
+use chrono::{DateTime, Utc};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod ticket {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        EnumIter,
+        DeriveActiveEnum,
+        Serialize,
+        Deserialize,
+        utoipa::ToSchema,
+        Default,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "Text")]
+    pub enum Priority {
+        #[default]
+        #[sea_orm(string_value = "low")]
+        Low,
+        #[sea_orm(string_value = "high")]
+        High,
+    }
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tickets")]
+    #[crudcrate(generate_router, api_struct = "Ticket")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[sea_orm(unique)]
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        pub points: i32,
+
+        pub done: bool,
+
+        pub notes: Option<String>,
+
+        pub priority: Priority,
+
+        #[crudcrate(on_create = chrono::Utc::now())]
+        pub opened_at: DateTime<Utc>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use ticket::{Priority, Ticket};
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(ticket::Entity)))
+        .await
+        .unwrap();
+    db
+}
+
+#[tokio::test]
+async fn test_factory_fills_placeholders() {
+    let db = setup().await;
+    let before = Utc::now();
+
+    let ticket = Ticket::factory()
+        .priority(Priority::High)
+        .create(&db)
+        .await
+        .unwrap();
+
+    assert!(ticket.title.starts_with("title "));
+    assert!(ticket.points > 0);
+    assert!(!ticket.done);
+    assert_eq!(ticket.notes, None);
+    assert_eq!(ticket.priority, Priority::High);
+    // `on_create` expressions run as they would for an API request
+    assert!(ticket.opened_at >= before);
+    assert!(Ticket::get_one(&db, ticket.id).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_factory_setters_override_placeholders() {
+    let db = setup().await;
+    let opened_at = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+
+    let ticket = Ticket::factory()
+        .title("Broken login")
+        .points(8)
+        .done(true)
+        .notes("from support".to_string())
+        .priority(Priority::Low)
+        .opened_at(opened_at)
+        .create(&db)
+        .await
+        .unwrap();
+
+    assert_eq!(ticket.title, "Broken login");
+    assert_eq!(ticket.points, 8);
+    assert!(ticket.done);
+    assert_eq!(ticket.notes.as_deref(), Some("from support"));
+    assert_eq!(ticket.opened_at, opened_at);
+}
+
+#[tokio::test]
+async fn test_create_batch_uses_distinct_values() {
+    let db = setup().await;
+
+    // `title` is unique, so this only succeeds if every record gets its own placeholder
+    let tickets = Ticket::factory()
+        .priority(Priority::Low)
+        .create_batch(&db, 5)
+        .await
+        .unwrap();
+
+    assert_eq!(tickets.len(), 5);
+    let mut titles: Vec<_> = tickets.iter().map(|t| t.title.clone()).collect();
+    titles.sort();
+    titles.dedup();
+    assert_eq!(titles.len(), 5);
+}
+
+#[test]
+fn test_field_without_placeholder_must_be_set() {
+    let err = Ticket::factory().build().unwrap_err();
+    assert!(err.to_string().contains("priority"), "{err}");
+
+    let create = Ticket::factory().priority(Priority::Low).build().unwrap();
+    assert_eq!(create.priority, Priority::Low);
+    // `on_create` fields stay unset so the expression applies on insert
+    assert!(create.opened_at.is_none());
+}
@@ -0,0 +1,134 @@
+// Tests for the field-level `description` and `example` attributes
+// Verifies that both end up on the property in the OpenAPI schema of every generated model
+// (API struct, Create, Update, List, Response, ScopedList and ScopedResponse), and that
+// they don't change request or response bodies.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod todo {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "todos")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Todo",
+        name_singular = "todo",
+        name_plural = "todos",
+        description = "Things to do",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(
+            filterable,
+            description = "Title of the todo",
+            example = "\"Buy milk\""
+        )]
+        pub title: String,
+
+        #[crudcrate(sortable, description = "Higher is more urgent", example = "3")]
+        pub priority: i32,
+
+        #[crudcrate(exclude(scoped), example = "\"Only visible to the owner\"")]
+        pub notes: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use todo::{
+    Todo, TodoCreate, TodoList, TodoResponse, TodoScopedList, TodoScopedResponse, TodoUpdate,
+};
+
+fn property(schema: &Value, name: &str) -> Value {
+    schema["properties"][name].clone()
+}
+
+fn example(property: &Value) -> Value {
+    property
+        .get("example")
+        .cloned()
+        .or_else(|| property["examples"].get(0).cloned())
+        .unwrap_or(Value::Null)
+}
+
+#[test]
+fn test_schemas_carry_descriptions_and_examples() {
+    for schema in [
+        serde_json::to_value(<Todo as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<TodoCreate as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<TodoUpdate as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<TodoList as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<TodoResponse as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<TodoScopedList as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<TodoScopedResponse as utoipa::PartialSchema>::schema()).unwrap(),
+    ] {
+        let title = property(&schema, "title");
+        assert_eq!(title["description"], "Title of the todo", "{schema}");
+        assert_eq!(example(&title), json!("Buy milk"), "{schema}");
+
+        let priority = property(&schema, "priority");
+        assert_eq!(priority["description"], "Higher is more urgent", "{schema}");
+        assert_eq!(example(&priority), json!(3), "{schema}");
+
+        assert!(
+            property(&schema, "id").get("description").is_none(),
+            "{schema}"
+        );
+    }
+
+    let create = serde_json::to_value(<TodoCreate as utoipa::PartialSchema>::schema()).unwrap();
+    assert_eq!(
+        example(&property(&create, "notes")),
+        json!("Only visible to the owner")
+    );
+}
+
+#[tokio::test]
+async fn test_bodies_unchanged() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(todo::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/todos", Todo::router(&db).into());
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/todos",
+        Some(json!({"title": "Write docs", "priority": 1, "notes": null})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    assert_eq!(body["title"], "Write docs");
+    assert_eq!(body["priority"], 1);
+}
@@ -0,0 +1,172 @@
+// Tests for field-level `rename` and `alias`
+// Verifies that renamed fields use their external name in request and response bodies,
+// in filter and sort parameters, and in the OpenAPI schemas, while the column keeps its
+// snake_case name.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod person {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "people")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Person",
+        name_singular = "person",
+        name_plural = "people",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable, rename = "fullName", alias = "legacy_name")]
+        pub full_name: String,
+
+        #[crudcrate(filterable, sortable, rename = "birthYear")]
+        pub birth_year: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use person::{Person, PersonCreate, PersonList, PersonResponse, PersonUpdate};
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(person::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/people", Person::router(&db).into());
+    (db, app)
+}
+
+async fn seed(app: &Router) {
+    for (name, year) in [("Ada Lovelace", 1815), ("Alan Turing", 1912)] {
+        let (status, body) = send(
+            app,
+            "POST",
+            "/people",
+            Some(json!({"fullName": name, "birthYear": year})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+}
+
+#[tokio::test]
+async fn test_bodies_use_external_names() {
+    let (db, app) = setup().await;
+    seed(&app).await;
+
+    let (_, body) = send(&app, "GET", "/people", None).await;
+    assert_eq!(body.as_array().unwrap().len(), 2);
+    assert!(body[0].get("fullName").is_some(), "{body}");
+    assert!(body[0].get("full_name").is_none(), "{body}");
+
+    let stored = person::Entity::find().all(&db).await.unwrap();
+    assert!(stored.iter().any(|p| p.full_name == "Ada Lovelace"));
+
+    let id = body[0]["id"].as_str().unwrap();
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/people/{id}"),
+        Some(json!({"birthYear": 1900})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["birthYear"], 1900);
+    assert!(body.get("birth_year").is_none(), "{body}");
+}
+
+#[tokio::test]
+async fn test_alias_is_accepted_on_input() {
+    let (_db, app) = setup().await;
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/people",
+        Some(json!({"legacy_name": "Grace Hopper", "birthYear": 1906})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    assert_eq!(body["fullName"], "Grace Hopper");
+}
+
+#[tokio::test]
+async fn test_filter_and_sort_by_external_name() {
+    let (_db, app) = setup().await;
+    seed(&app).await;
+
+    let filter = url_escape::encode_component(r#"{"fullName_neq":"Ada Lovelace"}"#);
+    let (status, body) = send(&app, "GET", &format!("/people?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["fullName"], "Alan Turing");
+
+    let filter = url_escape::encode_component(r#"{"birthYear_lt":1900}"#);
+    let (_, body) = send(&app, "GET", &format!("/people?filter={filter}"), None).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["fullName"], "Ada Lovelace");
+
+    let filter = url_escape::encode_component(r#"{"legacy_name":"Alan Turing"}"#);
+    let (_, body) = send(&app, "GET", &format!("/people?filter={filter}"), None).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let sort = url_escape::encode_component(r#"["birthYear","DESC"]"#);
+    let (_, body) = send(&app, "GET", &format!("/people?sort={sort}"), None).await;
+    assert_eq!(body[0]["fullName"], "Alan Turing");
+
+    let (_, body) = send(&app, "GET", "/people?sort_by=fullName&order=DESC", None).await;
+    assert_eq!(body[0]["fullName"], "Alan Turing");
+}
+
+#[test]
+fn test_schemas_use_external_names() {
+    for schema in [
+        serde_json::to_value(<Person as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<PersonCreate as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<PersonUpdate as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<PersonList as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<PersonResponse as utoipa::PartialSchema>::schema()).unwrap(),
+    ] {
+        assert!(schema["properties"].get("fullName").is_some(), "{schema}");
+        assert!(schema["properties"].get("full_name").is_none(), "{schema}");
+    }
+}
+
+#[test]
+fn test_field_aliases() {
+    let aliases = <Person as crudcrate::CRUDResource>::field_aliases();
+    assert!(aliases.contains(&("fullName", "full_name")));
+    assert!(aliases.contains(&("legacy_name", "full_name")));
+    assert!(aliases.contains(&("birthYear", "birth_year")));
+}
@@ -0,0 +1,326 @@
+// Tests for `#[crudcrate(file_upload(storage = "..."))]`
+// Verifies that multipart create/update requests store files through the registered
+// backend and persist their keys, that replaced and deleted files are removed, that
+// files uploaded for a failed request are cleaned up, that JSON requests still work, and
+// that only uploads set the key, so clients can't have other records' files deleted.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use crudcrate::EntityToModels;
+use crudcrate::storage::{LocalStorage, register_storage};
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fmt::Write;
+use std::path::PathBuf;
+use tower::ServiceExt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod photo {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "photos")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Photo",
+        name_singular = "photo",
+        name_plural = "photos",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        pub year: i32,
+
+        #[crudcrate(file_upload(storage = "photos"))]
+        pub image: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use photo::Photo;
+
+const BOUNDARY: &str = "crudcrate-test-boundary";
+
+fn storage_root() -> PathBuf {
+    std::env::temp_dir().join("crudcrate-file-upload-test")
+}
+
+async fn setup() -> Router {
+    register_storage("photos", LocalStorage::new(storage_root()));
+
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(photo::Entity)))
+        .await
+        .unwrap();
+    Router::new().nest("/photos", Photo::router(&db).into())
+}
+
+/// `(part name, file name, content)` parts as a multipart body
+fn form(parts: &[(&str, Option<&str>, &str)]) -> Vec<u8> {
+    let mut body = String::new();
+    for (name, file_name, content) in parts {
+        let _ = write!(body, "--{BOUNDARY}\r\n");
+        match file_name {
+            Some(file_name) => {
+                let _ = write!(
+                    body,
+                    "Content-Disposition: form-data; name=\"{name}\"; filename=\"{file_name}\"\r\nContent-Type: text/plain\r\n"
+                );
+            }
+            None => {
+                let _ = write!(body, "Content-Disposition: form-data; name=\"{name}\"\r\n");
+            }
+        }
+        let _ = write!(body, "\r\n{content}\r\n");
+    }
+    let _ = write!(body, "--{BOUNDARY}--\r\n");
+    body.into_bytes()
+}
+
+async fn send_form(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    parts: &[(&str, Option<&str>, &str)],
+) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .body(Body::from(form(parts)))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+fn stored_path(body: &Value) -> PathBuf {
+    storage_root().join(body["image"].as_str().expect("image key"))
+}
+
+#[tokio::test]
+async fn test_create_stores_file_and_key() {
+    let app = setup().await;
+    let (status, body) = send_form(
+        &app,
+        "POST",
+        "/photos",
+        &[
+            ("data", None, r#"{"year": 2024}"#),
+            ("title", None, "Cat"),
+            ("image", Some("cat photo.txt"), "meow"),
+        ],
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    assert_eq!(body["title"], "Cat");
+    assert_eq!(body["year"], 2024);
+
+    let key = body["image"].as_str().unwrap();
+    assert!(key.starts_with("photos/"), "{key}");
+    assert!(key.ends_with("-cat_photo.txt"), "{key}");
+    assert_eq!(std::fs::read_to_string(stored_path(&body)).unwrap(), "meow");
+
+    let (status, fetched) = send(
+        &app,
+        "GET",
+        &format!("/photos/{}", body["id"].as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched["image"], body["image"]);
+}
+
+#[tokio::test]
+async fn test_update_replaces_file_and_delete_removes_it() {
+    let app = setup().await;
+    let (_, created) = send_form(
+        &app,
+        "POST",
+        "/photos",
+        &[
+            ("data", None, r#"{"title": "Dog", "year": 2023}"#),
+            ("image", Some("dog.txt"), "woof"),
+        ],
+    )
+    .await;
+    let id = created["id"].as_str().unwrap().to_string();
+    let old_path = stored_path(&created);
+    assert!(old_path.exists());
+
+    let (status, updated) = send_form(
+        &app,
+        "PUT",
+        &format!("/photos/{id}"),
+        &[("image", Some("dog2.txt"), "WOOF")],
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{updated}");
+    assert_eq!(updated["title"], "Dog");
+    let new_path = stored_path(&updated);
+    assert_eq!(std::fs::read_to_string(&new_path).unwrap(), "WOOF");
+    assert!(!old_path.exists(), "replaced file is deleted");
+
+    // A JSON update that leaves the image alone keeps the file
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/photos/{id}"),
+        Some(json!({"year": 2025})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(new_path.exists());
+
+    let (status, _) = send(&app, "DELETE", &format!("/photos/{id}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(!new_path.exists(), "file is deleted with the record");
+}
+
+#[tokio::test]
+async fn test_failed_create_discards_upload() {
+    let app = setup().await;
+    let file_name = format!("orphan-{}.txt", Uuid::new_v4());
+
+    // `year` is missing, so the body doesn't deserialize after the file is stored
+    let (status, body) = send_form(
+        &app,
+        "POST",
+        "/photos",
+        &[
+            ("image", Some(file_name.as_str()), "lost"),
+            ("title", None, "Orphan"),
+        ],
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+
+    let leftovers = std::fs::read_dir(storage_root().join("photos")).map_or(0, |entries| {
+        entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(&file_name))
+            .count()
+    });
+    assert_eq!(leftovers, 0);
+}
+
+#[tokio::test]
+async fn test_rejects_bad_forms() {
+    let app = setup().await;
+    for parts in [
+        vec![
+            ("data", None, r#"{"title": "A", "year": 1}"#),
+            ("thumbnail", Some("a.txt"), "x"),
+        ],
+        vec![
+            ("data", None, r#"{"title": "A", "year": 1}"#),
+            ("image", None, "not a file"),
+        ],
+        vec![("data", None, "[1, 2]")],
+    ] {
+        let (status, body) = send_form(&app, "POST", "/photos", &parts).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{parts:?}: {body}");
+    }
+}
+
+#[tokio::test]
+async fn test_json_requests_still_work() {
+    let app = setup().await;
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/photos",
+        Some(json!({"title": "Plain", "year": 2020, "image": null})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    assert!(body["image"].is_null());
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/photos/{}", body["id"].as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_only_uploads_set_file_keys() {
+    let app = setup().await;
+    let (_, owner) = send_form(
+        &app,
+        "POST",
+        "/photos",
+        &[
+            ("data", None, r#"{"title": "Owner", "year": 2024}"#),
+            ("image", Some("owned.txt"), "mine"),
+        ],
+    )
+    .await;
+    let key = owner["image"].as_str().unwrap();
+
+    let body = json!({"title": "Thief", "year": 2024, "image": key});
+    let (status, body) = send(&app, "POST", "/photos", Some(body)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+
+    let (_, thief) = send(
+        &app,
+        "POST",
+        "/photos",
+        Some(json!({"title": "Thief", "year": 2024})),
+    )
+    .await;
+    let thief_id = thief["id"].as_str().unwrap();
+    let uri = format!("/photos/{thief_id}");
+    let (status, body) = send(&app, "PUT", &uri, Some(json!({"image": key}))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    let data = json!({"image": key}).to_string();
+    let (status, body) = send_form(&app, "PUT", &uri, &[("data", None, &data)]).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+
+    let (status, _) = send(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(
+        stored_path(&owner).exists(),
+        "another record's file is kept"
+    );
+}
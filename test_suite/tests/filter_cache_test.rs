@@ -0,0 +1,151 @@
+// Tests for the compiled filter cache
+// Verifies that repeated filters with the same keys and different values return the
+// rows for the new values, that shapes mixing cached column keys with fulltext and
+// `_between` keys still filter correctly, and that the debug stats count the first
+// request of a shape as a miss and later ones as hits.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod ticket {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "tickets")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Ticket",
+        name_singular = "ticket",
+        name_plural = "tickets",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, fulltext)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub status: String,
+
+        #[crudcrate(filterable)]
+        pub priority: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use ticket::Ticket;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(ticket::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/tickets", Ticket::router(&db).into());
+
+    for (title, status, priority) in [
+        ("login broken", "open", 1),
+        ("slow search", "open", 3),
+        ("typo in footer", "closed", 2),
+        ("login timeout", "closed", 5),
+    ] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/tickets",
+            Some(json!({"title": title, "status": status, "priority": priority})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    app
+}
+
+async fn titles(app: &Router, filter: &Value) -> Vec<String> {
+    let filter = url_escape::encode_component(&filter.to_string()).to_string();
+    let (status, body) = send(app, "GET", &format!("/tickets?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut titles: Vec<String> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ticket| ticket["title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[tokio::test]
+async fn test_same_shape_uses_new_values() {
+    let app = setup().await;
+
+    assert_eq!(
+        titles(&app, &json!({"status": "open", "priority_gte": 2})).await,
+        ["slow search"]
+    );
+    assert_eq!(
+        titles(&app, &json!({"status": "closed", "priority_gte": 2})).await,
+        ["login timeout", "typo in footer"]
+    );
+    assert_eq!(
+        titles(
+            &app,
+            &json!({"status": ["open", "closed"], "priority_gte": 4})
+        )
+        .await,
+        ["login timeout"]
+    );
+}
+
+#[tokio::test]
+async fn test_mixed_shape() {
+    let app = setup().await;
+
+    let filter = json!({"q": "login", "status_neq": "open", "priority_between": [1, 5]});
+    assert_eq!(titles(&app, &filter).await, ["login timeout"]);
+    let filter = json!({"q": "login", "status_neq": "closed", "priority_between": [1, 5]});
+    assert_eq!(titles(&app, &filter).await, ["login broken"]);
+}
+
+#[tokio::test]
+async fn test_stats_count_hits_and_misses() {
+    let app = setup().await;
+    let filter = json!({"title": "search", "priority_lt": 10, "status_neq": "none"});
+
+    let before = crudcrate::debug::filter_cache_stats();
+    assert_eq!(titles(&app, &filter).await, ["slow search"]);
+    let first = crudcrate::debug::filter_cache_stats();
+    assert!(first.misses > before.misses, "{before:?} {first:?}");
+    assert!(first.shapes >= 1);
+
+    assert_eq!(titles(&app, &filter).await, ["slow search"]);
+    let second = crudcrate::debug::filter_cache_stats();
+    assert!(second.hits > first.hits, "{first:?} {second:?}");
+}
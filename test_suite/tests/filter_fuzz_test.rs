@@ -0,0 +1,107 @@
+// Fuzz test for list endpoint query parsing
+// Sends randomised filter, sort and pagination queries against a resource with
+// string, integer, boolean, decimal, date, JSON and optional UUID columns and
+// checks that none of them panics or returns a 5xx status.
+
+use axum::Router;
+use chrono::{DateTime, Utc};
+use crudcrate::EntityToModels;
+use crudcrate::testing::{FuzzConfig, filter_fuzz, filter_fuzz_with, send};
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use uuid::Uuid;
+
+pub mod listing {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "listings")]
+    #[crudcrate(generate_router, api_struct = "Listing")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable, fulltext)]
+        pub title: String,
+
+        #[crudcrate(filterable, sortable)]
+        pub stock: i32,
+
+        #[crudcrate(filterable)]
+        pub active: bool,
+
+        #[sea_orm(column_type = "Decimal(Some((10, 2)))")]
+        #[crudcrate(filterable, sortable)]
+        pub price: Decimal,
+
+        #[crudcrate(filterable, sortable)]
+        pub listed_at: DateTime<Utc>,
+
+        #[crudcrate(filterable)]
+        pub attributes: serde_json::Value,
+
+        #[crudcrate(filterable)]
+        pub seller_id: Option<Uuid>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use listing::Listing;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(listing::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/listings", Listing::router(&db).into());
+    (db, app)
+}
+
+#[tokio::test]
+async fn test_list_endpoint_survives_fuzzing() {
+    let (_db, app) = setup().await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/listings",
+        Some(serde_json::json!({
+            "title": "Desk lamp",
+            "stock": 3,
+            "active": true,
+            "price": "24.50",
+            "listed_at": "2024-05-01T10:00:00Z",
+            "attributes": {"color": "black"},
+            "seller_id": null
+        })),
+    )
+    .await;
+    assert!(status.is_success());
+
+    filter_fuzz::<Listing>(&app, "/listings").await;
+}
+
+#[tokio::test]
+async fn test_other_seeds() {
+    let (_db, app) = setup().await;
+
+    for seed in 1..=3 {
+        filter_fuzz_with::<Listing>(
+            &app,
+            "/listings",
+            FuzzConfig {
+                iterations: 100,
+                seed,
+            },
+        )
+        .await;
+    }
+}
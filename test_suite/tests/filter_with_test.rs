@@ -0,0 +1,161 @@
+// Tests for `#[crudcrate(filter_with = ...)]`
+// Verifies that the function's condition replaces the column filter for the values it
+// handles, that `Ok(None)` and operator keys fall back to the built-in filters, that its
+// errors reach the client, and that the condition also narrows counts.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod task {
+    use super::*;
+
+    /// Day the tests treat as today
+    pub const TODAY: i32 = 10;
+
+    /// `"overdue"`: not done and due before today
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 for `"late"`, pointing at `"overdue"`.
+    pub fn status_filter(value: &serde_json::Value) -> Result<Option<Condition>, ApiError> {
+        match value.as_str() {
+            Some("overdue") => Ok(Some(
+                Condition::all()
+                    .add(Column::Status.ne("done"))
+                    .add(Column::DueDay.lt(TODAY)),
+            )),
+            Some("late") => Err(ApiError::bad_request("Use \"overdue\" instead of \"late\"")),
+            _ => Ok(None),
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tasks")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Task",
+        name_singular = "task",
+        name_plural = "tasks"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        #[crudcrate(filterable, filter_with = status_filter)]
+        pub status: String,
+
+        #[crudcrate(filterable)]
+        pub due_day: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use task::Task;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(task::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/tasks", Task::router(&db).into());
+
+    for (title, status, due_day) in [
+        ("late draft", "open", 3),
+        ("late review", "blocked", 8),
+        ("finished", "done", 2),
+        ("upcoming", "open", 20),
+    ] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/tasks",
+            Some(json!({"title": title, "status": status, "due_day": due_day})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    (db, app)
+}
+
+async fn titles(app: &Router, filter: &Value) -> Vec<String> {
+    let filter = url_escape::encode_component(&filter.to_string()).to_string();
+    let (status, body) = send(app, "GET", &format!("/tasks?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut titles: Vec<String> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn test_custom_filters_are_listed() {
+    let filters = Task::custom_filters();
+    assert_eq!(filters.len(), 1);
+    assert_eq!(filters[0].0, "status");
+}
+
+#[tokio::test]
+async fn test_function_condition_and_fallback() {
+    let (_db, app) = setup().await;
+
+    assert_eq!(
+        titles(&app, &json!({"status": "overdue"})).await,
+        ["late draft", "late review"]
+    );
+    // Combined with the other filters like a built-in condition
+    assert_eq!(
+        titles(&app, &json!({"status": "overdue", "due_day_gt": 5})).await,
+        ["late review"]
+    );
+    // Values the function returns `None` for use the column filter
+    assert_eq!(
+        titles(&app, &json!({"status": "open"})).await,
+        ["late draft", "upcoming"]
+    );
+    // Operator keys never reach the function
+    assert_eq!(
+        titles(&app, &json!({"status_neq": "overdue"})).await.len(),
+        4
+    );
+}
+
+#[tokio::test]
+async fn test_function_errors_reach_the_client() {
+    let (_db, app) = setup().await;
+
+    let filter = url_escape::encode_component(r#"{"status":"late"}"#).to_string();
+    let (status, body) = send(&app, "GET", &format!("/tasks?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.to_string().contains("instead of"), "{body}");
+}
+
+#[tokio::test]
+async fn test_function_condition_narrows_counts() {
+    let (db, _app) = setup().await;
+
+    let condition = crudcrate::filtering::apply_filters::<Task>(
+        Some(r#"{"status":"overdue"}"#.to_string()),
+        &Task::filterable_columns(),
+        db.get_database_backend(),
+    )
+    .unwrap();
+    assert_eq!(Task::total_count(&db, &condition).await, 2);
+}
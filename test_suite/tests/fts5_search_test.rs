@@ -0,0 +1,207 @@
+// Tests for `#[crudcrate(fts5_table = "...")]` on SQLite
+// Verifies that ensure_fts5 creates and fills the FTS5 table, that `q` searches match
+// through it and follow inserts, updates and deletes, that unsorted results are ordered
+// by bm25 relevance, and that FTS5 syntax in user input does not cause errors.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod article {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "articles")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Article",
+        name_singular = "article",
+        name_plural = "articles",
+        derive_partial_eq,
+        derive_eq,
+        fts5_table = "articles_fts"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable, fulltext)]
+        pub title: String,
+
+        #[crudcrate(fulltext)]
+        pub body: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use article::Article;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(article::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/articles", Article::router(&db).into());
+    (db, app)
+}
+
+async fn create(app: &Router, title: &str, body: &str) -> String {
+    let (status, created) = send(
+        app,
+        "POST",
+        "/articles",
+        Some(json!({"title": title, "body": body})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{created}");
+    created["id"].as_str().unwrap().to_string()
+}
+
+async fn search(app: &Router, q: &str, extra: &str) -> (StatusCode, Value) {
+    let filter = url_escape::encode_component(&json!({"q": q}).to_string()).to_string();
+    send(
+        app,
+        "GET",
+        &format!("/articles?filter={filter}{extra}"),
+        None,
+    )
+    .await
+}
+
+fn titles(body: &Value) -> Vec<&str> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["title"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_ensure_fts5_indexes_existing_rows() {
+    let (db, app) = setup().await;
+    create(&app, "Rust ownership", "Borrowing explained").await;
+
+    assert!(
+        crudcrate::database::ensure_fts5::<Article>(&db)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !crudcrate::database::ensure_fts5::<Article>(&db)
+            .await
+            .unwrap()
+    );
+
+    let (status, body) = search(&app, "borrow", "").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["Rust ownership"]);
+}
+
+#[tokio::test]
+async fn test_search_follows_writes() {
+    let (db, app) = setup().await;
+    crudcrate::database::ensure_fts5::<Article>(&db)
+        .await
+        .unwrap();
+
+    let id = create(&app, "Gardening basics", "Tomatoes and peppers").await;
+    create(&app, "Cooking", "Tomato sauce").await;
+
+    let (_, body) = search(&app, "tomato", "&sort_by=title&order=ASC").await;
+    assert_eq!(titles(&body), ["Cooking", "Gardening basics"]);
+
+    // Every word must match
+    let (_, body) = search(&app, "tomato peppers", "").await;
+    assert_eq!(titles(&body), ["Gardening basics"]);
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/articles/{id}"),
+        Some(json!({"body": "Roses"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = search(&app, "tomato", "").await;
+    assert_eq!(titles(&body), ["Cooking"]);
+    let (_, body) = search(&app, "roses", "").await;
+    assert_eq!(titles(&body), ["Gardening basics"]);
+
+    let (status, _) = send(&app, "DELETE", &format!("/articles/{id}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, body) = search(&app, "roses", "").await;
+    assert!(titles(&body).is_empty());
+}
+
+#[tokio::test]
+async fn test_unsorted_results_ordered_by_relevance() {
+    let (db, app) = setup().await;
+    crudcrate::database::ensure_fts5::<Article>(&db)
+        .await
+        .unwrap();
+
+    create(
+        &app,
+        "A",
+        "search engines index documents for later retrieval",
+    )
+    .await;
+    create(&app, "B", "search search search").await;
+    create(&app, "C", "unrelated").await;
+
+    let (status, body) = search(&app, "search", "").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["B", "A"]);
+
+    // An explicit sort wins over relevance
+    let (_, body) = search(&app, "search", "&sort_by=title&order=ASC").await;
+    assert_eq!(titles(&body), ["A", "B"]);
+}
+
+#[tokio::test]
+async fn test_fts5_syntax_in_query_is_literal() {
+    let (db, app) = setup().await;
+    crudcrate::database::ensure_fts5::<Article>(&db)
+        .await
+        .unwrap();
+    create(&app, "Web-dev notes", "NEAR the end").await;
+
+    for q in [
+        "\"unbalanced",
+        "AND OR NOT",
+        "web-dev",
+        "*",
+        "title:web",
+        "NEAR(",
+    ] {
+        let (status, body) = search(&app, q, "").await;
+        assert_eq!(status, StatusCode::OK, "{q}: {body}");
+    }
+
+    let (_, body) = search(&app, "near", "").await;
+    assert_eq!(titles(&body), ["Web-dev notes"]);
+}
@@ -0,0 +1,168 @@
+// Tests for geo fields
+// Verifies field-level `geo` and struct-level `geo(name, lat, lon)` are exposed via
+// geo_fields(), that `_within` filters become spatial conditions per backend, and that
+// sorting by a geo field resolves to a distance ordering.
+
+use crudcrate::filtering::GeoColumn;
+use crudcrate::filtering::geo::distance_order;
+use crudcrate::models::FilterOptions;
+use crudcrate::{CRUDResource, EntityToModels, apply_filters};
+use sea_orm::DatabaseBackend;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+pub mod shop {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "shops")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Shop",
+        geo(name = "position", lat = "latitude", lon = "longitude")
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub name: String,
+
+        pub latitude: f64,
+        pub longitude: f64,
+
+        /// `PostGIS` geography column, stored as text here for compilation only
+        #[crudcrate(geo)]
+        pub location: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use shop::Shop;
+
+fn condition_sql(filter: &str, backend: DatabaseBackend) -> String {
+    let condition = apply_filters::<Shop>(
+        Some(filter.to_string()),
+        &Shop::filterable_columns(),
+        backend,
+    )
+    .expect("valid geo filter");
+    format!("{condition:?}")
+}
+
+#[test]
+fn test_geo_fields() {
+    assert_eq!(
+        Shop::geo_fields(),
+        vec![
+            ("location", GeoColumn::Point { column: "location" }),
+            (
+                "position",
+                GeoColumn::LatLon {
+                    lat: "latitude",
+                    lon: "longitude"
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_within_filter_per_backend() {
+    let sql = condition_sql(
+        r#"{"location_within": "48.8566,2.3522,5"}"#,
+        DatabaseBackend::Postgres,
+    );
+    assert!(sql.contains("ST_DWithin"), "got: {sql}");
+
+    let sql = condition_sql(
+        r#"{"location_within": [48.8566, 2.3522, 5]}"#,
+        DatabaseBackend::MySql,
+    );
+    assert!(sql.contains("ST_Distance_Sphere"), "got: {sql}");
+
+    let sql = condition_sql(
+        r#"{"position_within": "48.8566,2.3522,5"}"#,
+        DatabaseBackend::Sqlite,
+    );
+    assert!(sql.contains("ASIN(SQRT("), "got: {sql}");
+}
+
+#[test]
+fn test_within_filter_combines_with_regular_filters() {
+    let sql = condition_sql(
+        r#"{"name": "Bakery", "position_within": "48.8566,2.3522,5"}"#,
+        DatabaseBackend::Sqlite,
+    );
+    assert!(sql.contains("ASIN(SQRT("), "got: {sql}");
+    assert!(sql.contains("%BAKERY%"), "got: {sql}");
+}
+
+#[test]
+fn test_invalid_within_filters_are_rejected() {
+    for filter in [
+        r#"{"location_within": "48.8566,2.3522"}"#,
+        r#"{"location_within": "north,east,5"}"#,
+        r#"{"position_within": "95,2.3522,5"}"#,
+    ] {
+        let result = apply_filters::<Shop>(
+            Some(filter.to_string()),
+            &Shop::filterable_columns(),
+            DatabaseBackend::Postgres,
+        );
+        assert!(result.is_err(), "should reject {filter}");
+    }
+
+    // Spatial columns need PostGIS or MySQL
+    let result = apply_filters::<Shop>(
+        Some(r#"{"location_within": "48.8566,2.3522,5"}"#.to_string()),
+        &Shop::filterable_columns(),
+        DatabaseBackend::Sqlite,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_distance_sort() {
+    let params = FilterOptions {
+        filter: Some(r#"{"position_within": "48.8566,2.3522,5"}"#.to_string()),
+        sort: Some(r#"["position", "DESC"]"#.to_string()),
+        ..Default::default()
+    };
+    let (expr, order) = distance_order::<Shop>(&params, DatabaseBackend::Sqlite)
+        .expect("valid geo filter")
+        .expect("sorting by a geo field with a centre point");
+    assert!(format!("{expr:?}").contains("ASIN(SQRT("));
+    assert_eq!(order, sea_orm::Order::Desc);
+}
+
+#[test]
+fn test_distance_sort_needs_centre_point() {
+    // No `_within` filter: fall back to regular sorting
+    let params = FilterOptions {
+        sort: Some(r#"["position", "ASC"]"#.to_string()),
+        ..Default::default()
+    };
+    assert!(
+        distance_order::<Shop>(&params, DatabaseBackend::Sqlite)
+            .unwrap()
+            .is_none()
+    );
+
+    // Sorting on a regular column is unaffected
+    let params = FilterOptions {
+        filter: Some(r#"{"position_within": "48.8566,2.3522,5"}"#.to_string()),
+        sort: Some(r#"["name", "ASC"]"#.to_string()),
+        ..Default::default()
+    };
+    assert!(
+        distance_order::<Shop>(&params, DatabaseBackend::Sqlite)
+            .unwrap()
+            .is_none()
+    );
+}
@@ -0,0 +1,253 @@
+// Tests for `#[crudcrate(guard_delete = "...")]`
+// Verifies that deleting a locked row answers 423 naming it, that batch, filter and
+// `BatchRouter` deletes are refused as a whole, that clearing the flag allows the
+// delete, and that a row locked after the check is still kept.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::batch::BatchRouter;
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod invoice {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "invoices")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Invoice",
+        name_singular = "invoice",
+        name_plural = "invoices",
+        guard_delete = "locked"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub number: String,
+
+        pub locked: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod ledger {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "ledgers")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Ledger",
+        name_singular = "ledger",
+        name_plural = "ledgers",
+        guard_delete = "locked",
+        cascade_delete
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub locked: bool,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, depth = 1))]
+        pub entries: Vec<super::entry::Entry>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::entry::Entity")]
+        Entries,
+    }
+
+    impl Related<super::entry::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Entries.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod entry {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "entries")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Entry",
+        name_singular = "entry",
+        name_plural = "entries",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub ledger_id: Uuid,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::ledger::Entity",
+            from = "Column::LedgerId",
+            to = "super::ledger::Column::Id"
+        )]
+        Ledger,
+    }
+
+    impl Related<super::ledger::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Ledger.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use entry::Entry;
+use invoice::Invoice;
+use ledger::Ledger;
+
+/// Invoices "A-1" (locked) and "A-2", by ID
+async fn setup() -> (DatabaseConnection, Router, String, String) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let statement = Schema::new(backend).create_table_from_entity(invoice::Entity);
+    db.execute(backend.build(&statement)).await.unwrap();
+    let app = Router::new().nest("/invoices", Invoice::router(&db).into());
+
+    let mut ids = Vec::new();
+    for (number, locked) in [("A-1", true), ("A-2", false)] {
+        let body = json!({"number": number, "locked": locked});
+        let (status, invoice) = send(&app, "POST", "/invoices", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{invoice}");
+        ids.push(invoice["id"].as_str().unwrap().to_string());
+    }
+    let unlocked = ids.pop().unwrap();
+    (db, app, ids.pop().unwrap(), unlocked)
+}
+
+async fn count(db: &DatabaseConnection) -> u64 {
+    Invoice::total_count(db, &sea_orm::Condition::all()).await
+}
+
+#[tokio::test]
+async fn test_locked_row_is_not_deleted() {
+    let (db, app, locked, _) = setup().await;
+
+    let uri = format!("/invoices/{locked}");
+    let (status, body) = send(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::LOCKED, "{body}");
+    assert_eq!(
+        body["error"],
+        format!("Cannot delete locked invoice: {locked}")
+    );
+    assert_eq!(count(&db).await, 2);
+
+    let (status, _) = send(&app, "PUT", &uri, Some(json!({"locked": false}))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = send(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT, "{body}");
+}
+
+#[tokio::test]
+async fn test_batch_delete_is_refused_as_a_whole() {
+    let (db, app, locked, unlocked) = setup().await;
+
+    let ids = json!([locked, unlocked]);
+    let (status, body) = send(&app, "DELETE", "/invoices/batch", Some(ids)).await;
+    assert_eq!(status, StatusCode::LOCKED, "{body}");
+
+    let filter = url_escape::encode_component(r#"{"number_neq":"none"}"#);
+    let uri = format!("/invoices?filter={filter}");
+    let (status, body) = send(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::LOCKED, "{body}");
+    assert_eq!(count(&db).await, 2);
+
+    let app = app.merge(
+        BatchRouter::new()
+            .resource::<Invoice>()
+            .unwrap()
+            .router(&db),
+    );
+    let operations = json!([
+        {"op": "delete", "resource": "invoices", "id": unlocked},
+        {"op": "delete", "resource": "invoices", "id": locked}
+    ]);
+    let (status, body) = send(&app, "POST", "/batch", Some(operations)).await;
+    assert_eq!(status, StatusCode::LOCKED, "{body}");
+    assert_eq!(body["index"], 1);
+    assert_eq!(count(&db).await, 2);
+
+    let ids = json!([unlocked]);
+    let (status, body) = send(&app, "DELETE", "/invoices/batch", Some(ids)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!([unlocked]));
+}
+
+#[tokio::test]
+async fn test_row_locked_after_the_check_is_kept() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(ledger::Entity),
+        schema.create_table_from_entity(entry::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+    // Stands in for a concurrent update locking the ledger once the delete has checked
+    // it and started removing its entries
+    db.execute_unprepared(
+        "CREATE TRIGGER lock_ledger AFTER DELETE ON entries \
+         BEGIN UPDATE ledgers SET locked = 1 WHERE id = OLD.ledger_id; END",
+    )
+    .await
+    .unwrap();
+    let app = Router::new()
+        .nest("/ledgers", Ledger::router(&db).into())
+        .nest("/entries", Entry::router(&db).into());
+
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let (status, ledger) = send(&app, "POST", "/ledgers", Some(json!({"locked": false}))).await;
+        assert_eq!(status, StatusCode::CREATED, "{ledger}");
+        let id = ledger["id"].as_str().unwrap().to_string();
+        let body = json!({"ledger_id": id});
+        let (status, entry) = send(&app, "POST", "/entries", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{entry}");
+        ids.push(id);
+    }
+
+    let (status, body) = send(&app, "DELETE", &format!("/ledgers/{}", ids[0]), None).await;
+    assert_eq!(status, StatusCode::LOCKED, "{body}");
+    let (status, body) = send(&app, "DELETE", "/ledgers/batch", Some(json!(ids))).await;
+    assert_eq!(status, StatusCode::LOCKED, "{body}");
+
+    let app = app.merge(BatchRouter::new().resource::<Ledger>().unwrap().router(&db));
+    let operations = json!([{"op": "delete", "resource": "ledgers", "id": ids[1]}]);
+    let (status, body) = send(&app, "POST", "/batch", Some(operations)).await;
+    assert_eq!(status, StatusCode::LOCKED, "{body}");
+
+    // The deletes rolled back with their entries
+    let condition = sea_orm::Condition::all();
+    assert_eq!(Ledger::total_count(&db, &condition).await, 2);
+    assert_eq!(Entry::total_count(&db, &condition).await, 2);
+}
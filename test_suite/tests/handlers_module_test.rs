@@ -0,0 +1,162 @@
+// Tests for the generated `<name>_handlers` module
+// Verifies that custom routers built from the re-exported handlers serve the same
+// endpoints under another prefix, with their own middleware, and keep the handlers'
+// OpenAPI operations.
+
+use axum::Router;
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::Response;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use tower::ServiceExt;
+use utoipa_axum::{router::OpenApiRouter, routes};
+use uuid::Uuid;
+
+pub mod todo {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "todos")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Todo",
+        name_singular = "todo",
+        name_plural = "todos",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod tag {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tags")]
+    #[crudcrate(
+        generate_router,
+        openapi = false,
+        api_struct = "Tag",
+        name_singular = "tag",
+        name_plural = "tags",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use tag::tag_handlers;
+use todo::todo_handlers;
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(todo::Entity),
+        schema.create_table_from_entity(tag::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+    db
+}
+
+async fn version_header(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert("x-api-version", HeaderValue::from_static("2"));
+    response
+}
+
+/// A `v2` router exposing only reads and creates, with an extra response header
+fn v2(db: &DatabaseConnection) -> OpenApiRouter {
+    OpenApiRouter::new()
+        .routes(routes!(
+            todo_handlers::get_all_handler,
+            todo_handlers::create_one_handler
+        ))
+        .routes(routes!(todo_handlers::get_one_handler))
+        .layer(axum::middleware::map_response(version_header))
+        .with_state(db.clone())
+}
+
+#[tokio::test]
+async fn test_custom_router_serves_handlers() {
+    let db = setup().await;
+    let app = Router::new().nest("/v2/todos", v2(&db).into());
+
+    let (status, created) = send(&app, "POST", "/v2/todos", Some(json!({"title": "Write"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{created}");
+    let uri = format!("/v2/todos/{}", created["id"].as_str().unwrap());
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["title"], "Write");
+
+    let request = axum::http::Request::builder()
+        .uri("/v2/todos")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-api-version"], "2");
+
+    // Only the mounted routes exist
+    let (status, _) = send(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn test_custom_router_keeps_openapi_operations() {
+    let db = setup().await;
+    let (_, openapi) = v2(&db).split_for_parts();
+    let paths = &openapi.paths.paths;
+    assert_eq!(paths.len(), 2);
+    let list = paths["/"].get.as_ref().unwrap();
+    assert_eq!(list.operation_id.as_deref(), Some("get_all_todos"));
+    assert!(paths["/"].post.is_some());
+    assert!(paths["/{id}"].get.is_some());
+}
+
+#[tokio::test]
+async fn test_handlers_without_openapi() {
+    let db = setup().await;
+    let tags = Router::new()
+        .route(
+            "/",
+            axum::routing::get(tag_handlers::get_all_handler)
+                .post(tag_handlers::create_one_handler),
+        )
+        .with_state(db.clone());
+    let app = Router::new().nest("/v2/tags", tags);
+
+    let (status, _) = send(&app, "POST", "/v2/tags", Some(json!({"name": "home"}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (_, body) = send(&app, "GET", "/v2/tags", None).await;
+    assert_eq!(body[0]["name"], "home");
+}
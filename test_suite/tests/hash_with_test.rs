@@ -0,0 +1,169 @@
+// Tests for `#[crudcrate(hash_with = fn)]` fields
+// Verifies that create and update store the awaited function's output, after any
+// sanitizers, that its errors answer the request and write nothing, that omitted and
+// null values are left alone, and that the field is never returned by the single-item,
+// list or batch endpoints.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Stand-in for a password hash such as Argon2, reversible so tests can check the input.
+/// Refuses empty input, as a hash with a policy check would.
+#[allow(clippy::needless_pass_by_value)]
+async fn fake_hash(password: String) -> Result<String, ApiError> {
+    if password.is_empty() {
+        return Err(ApiError::bad_request("Password can't be empty"));
+    }
+    Ok(format!(
+        "hashed:{}",
+        password.chars().rev().collect::<String>()
+    ))
+}
+
+pub mod account {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "accounts")]
+    #[crudcrate(generate_router, api_struct = "Account")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        #[crudcrate(filterable)]
+        pub email: String,
+        #[crudcrate(sanitize(trim), hash_with = fake_hash)]
+        pub password: String,
+        #[crudcrate(hash_with = fake_hash)]
+        pub recovery_code: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use account::Account;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(account::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/accounts", Account::router(&db).into());
+    (db, app)
+}
+
+async fn stored(db: &DatabaseConnection, id: &str) -> account::Model {
+    account::Entity::find_by_id(Uuid::parse_str(id).unwrap())
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_create_stores_hash_and_hides_it() {
+    let (db, app) = setup().await;
+    let body = json!({"email": "ada@example.com", "password": "  secret ", "recovery_code": null});
+    let (status, body) = send(&app, "POST", "/accounts", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    assert!(body.get("password").is_none(), "{body}");
+    assert!(body.get("recovery_code").is_none(), "{body}");
+
+    let id = body["id"].as_str().unwrap();
+    let row = stored(&db, id).await;
+    assert_eq!(row.password, "hashed:terces");
+    assert_eq!(row.recovery_code, None);
+
+    let (status, body) = send(&app, "GET", &format!("/accounts/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body.get("password").is_none(), "{body}");
+    let (status, body) = send(&app, "GET", "/accounts", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body[0].get("password").is_none(), "{body}");
+    assert_eq!(body[0]["email"], "ada@example.com");
+}
+
+#[tokio::test]
+async fn test_update_rehashes_only_sent_values() {
+    let (db, app) = setup().await;
+    let body = json!({"email": "ada@example.com", "password": "one", "recovery_code": "abc"});
+    let (_, body) = send(&app, "POST", "/accounts", Some(body)).await;
+    let id = body["id"].as_str().unwrap().to_string();
+    assert_eq!(
+        stored(&db, &id).await.recovery_code.as_deref(),
+        Some("hashed:cba")
+    );
+
+    let uri = format!("/accounts/{id}");
+    let (status, body) = send(&app, "PUT", &uri, Some(json!({"email": "ada@example.org"}))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body.get("password").is_none(), "{body}");
+    assert_eq!(stored(&db, &id).await.password, "hashed:eno");
+
+    let update = json!({"password": "two", "recovery_code": null});
+    let (status, body) = send(&app, "PUT", &uri, Some(update)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let row = stored(&db, &id).await;
+    assert_eq!(row.password, "hashed:owt");
+    assert_eq!(row.recovery_code, None);
+}
+
+#[tokio::test]
+async fn test_batch_create_hashes_each_item() {
+    let (db, app) = setup().await;
+    let body = json!([
+        {"email": "a@example.com", "password": "ab", "recovery_code": null},
+        {"email": "b@example.com", "password": "cd", "recovery_code": null}
+    ]);
+    let (status, body) = send(&app, "POST", "/accounts/batch", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    for item in body.as_array().unwrap() {
+        assert!(item.get("password").is_none(), "{item}");
+    }
+    let mut hashes: Vec<String> = account::Entity::find()
+        .all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.password)
+        .collect();
+    hashes.sort();
+    assert_eq!(hashes, ["hashed:ba", "hashed:dc"]);
+    assert!(
+        !Account::filterable_columns()
+            .iter()
+            .any(|(name, _)| *name == "password")
+    );
+}
+
+#[tokio::test]
+async fn test_hash_errors_answer_the_request() {
+    let (db, app) = setup().await;
+    // Trimmed to nothing before it is hashed
+    let body = json!({"email": "ada@example.com", "password": "   ", "recovery_code": null});
+    let (status, body) = send(&app, "POST", "/accounts", Some(body)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert!(account::Entity::find().all(&db).await.unwrap().is_empty());
+
+    let body = json!({"email": "ada@example.com", "password": "one", "recovery_code": null});
+    let (_, body) = send(&app, "POST", "/accounts", Some(body)).await;
+    let id = body["id"].as_str().unwrap().to_string();
+    let uri = format!("/accounts/{id}");
+    let update = json!({"email": "ada@example.org", "password": ""});
+    let (status, body) = send(&app, "PUT", &uri, Some(update)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    let row = stored(&db, &id).await;
+    assert_eq!(row.email, "ada@example.com");
+    assert_eq!(row.password, "hashed:eno");
+}
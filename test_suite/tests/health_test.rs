@@ -0,0 +1,140 @@
+// Tests for `crudcrate::health`
+// Verifies that `/healthz` always answers, that `/readyz` checks the database
+// connection, and that `router_with_table_checks` reports registered resources whose
+// table doesn't exist with 503.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod archive {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "archives")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Archive",
+        name_singular = "archive",
+        name_plural = "archives"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub label: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use archive::Archive;
+use note::Note;
+
+/// A database with the notes table, and the archives table if `with_archives`
+async fn setup(with_archives: bool) -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    if with_archives {
+        db.execute(backend.build(&schema.create_table_from_entity(archive::Entity)))
+            .await
+            .unwrap();
+    }
+    db
+}
+
+/// Both resource routers, then the health router built by `health`
+fn app(db: &DatabaseConnection, health: fn(&DatabaseConnection) -> Router) -> Router {
+    Router::new()
+        .nest("/notes", Note::router(db).into())
+        .nest("/archives", Archive::router(db).into())
+        .merge(health(db))
+}
+
+#[tokio::test]
+async fn test_healthz_and_readyz() {
+    let db = setup(false).await;
+    let app = app(&db, crudcrate::health::router);
+
+    let (status, body) = send(&app, "GET", "/healthz", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"status": "ok"}));
+
+    // Tables aren't checked, so the missing archives table doesn't matter
+    let (status, body) = send(&app, "GET", "/readyz", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!({"status": "ready", "database": true}));
+}
+
+#[tokio::test]
+async fn test_readyz_reports_missing_tables() {
+    let db = setup(false).await;
+    let app = app(&db, crudcrate::health::router_with_table_checks);
+
+    let (status, body) = send(&app, "GET", "/readyz", None).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "{body}");
+    assert_eq!(body["status"], "unavailable");
+    assert_eq!(body["database"], true);
+    assert_eq!(body["missing_tables"], json!(["archives"]));
+
+    let (status, _) = send(&app, "GET", "/healthz", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_readyz_with_all_tables() {
+    let db = setup(true).await;
+    let app = app(&db, crudcrate::health::router_with_table_checks);
+
+    let (status, body) = send(&app, "GET", "/readyz", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!({"status": "ready", "database": true}));
+}
+
+#[tokio::test]
+async fn test_readyz_with_closed_database() {
+    let db = setup(true).await;
+    db.clone().close().await.unwrap();
+
+    let readiness = crudcrate::health::readiness(&db, false).await;
+    assert!(!readiness.is_ready());
+    assert!(!readiness.database);
+}
@@ -112,6 +112,7 @@ async fn validate_before_update(
 async fn audit_after_update(
     _db: &sea_orm::DatabaseConnection,
     _entity: &HookTestItem,
+    _changes: &crudcrate::ChangeSet,
 ) -> Result<(), ApiError> {
     UPDATE_POST_CALLED.store(true, Ordering::SeqCst);
     Ok(())
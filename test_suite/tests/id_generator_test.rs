@@ -0,0 +1,221 @@
+// Tests for `#[crudcrate(id_generator = "...")]`
+// Verifies that uuid_v7 and ULID (in a Uuid) primary keys are generated on create, that
+// lists default to creation order and `sort=id_desc` reverses it, that String ULID and
+// KSUID fields have their text forms, and that OpenAPI documents String keys with
+// their generator as the format.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod event {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "events")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Event",
+        name_singular = "event",
+        name_plural = "events",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), id_generator = "uuid_v7")]
+        pub id: Uuid,
+
+        #[crudcrate(exclude(create, update), id_generator = "ksuid")]
+        pub public_id: String,
+
+        #[crudcrate(filterable)]
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod order {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "orders")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Order",
+        name_singular = "order",
+        name_plural = "orders",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), id_generator = "ulid")]
+        pub id: Uuid,
+
+        #[crudcrate(exclude(create, update), id_generator = "ulid")]
+        pub reference: String,
+
+        #[crudcrate(filterable)]
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use event::Event;
+use order::Order;
+
+const NAMES: [&str; 4] = ["zeta", "alpha", "mu", "beta"];
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(event::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(order::Entity)))
+        .await
+        .unwrap();
+    Router::new()
+        .nest("/events", Event::router(&db).into())
+        .nest("/orders", Order::router(&db).into())
+}
+
+/// Create one row per name, a few milliseconds apart so each key gets its own timestamp
+async fn create_all(app: &Router, path: &str) -> Vec<Value> {
+    let mut created = Vec::new();
+    for name in NAMES {
+        tokio::time::sleep(std::time::Duration::from_millis(3)).await;
+        let (status, body) = send(app, "POST", path, Some(json!({"name": name}))).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        created.push(body);
+    }
+    created
+}
+
+async fn listed_names(app: &Router, uri: &str) -> Vec<String> {
+    let (status, body) = send(app, "GET", uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_uuid_v7_keys_list_in_creation_order() {
+    let app = setup().await;
+    let created = create_all(&app, "/events").await;
+
+    for event in &created {
+        let id = Uuid::parse_str(event["id"].as_str().unwrap()).unwrap();
+        assert_eq!(id.get_version_num(), 7);
+        let public_id = event["public_id"].as_str().unwrap();
+        assert_eq!(public_id.len(), 27);
+        assert!(public_id.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    assert_eq!(listed_names(&app, "/events").await, NAMES);
+    let mut newest_first = NAMES.to_vec();
+    newest_first.reverse();
+    assert_eq!(
+        listed_names(&app, "/events?sort=id_desc").await,
+        newest_first
+    );
+}
+
+#[tokio::test]
+async fn test_ulid_keys_list_in_creation_order() {
+    let app = setup().await;
+    let created = create_all(&app, "/orders").await;
+
+    for order in &created {
+        let reference = order["reference"].as_str().unwrap();
+        assert_eq!(reference.len(), 26);
+        assert!(
+            reference
+                .chars()
+                .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+        );
+    }
+    let references: Vec<&str> = created
+        .iter()
+        .map(|order| order["reference"].as_str().unwrap())
+        .collect();
+    assert!(references.is_sorted(), "{references:?}");
+
+    assert_eq!(listed_names(&app, "/orders").await, NAMES);
+}
+
+#[test]
+fn test_time_sortable_keys_are_sortable() {
+    assert!(
+        Event::sortable_columns()
+            .iter()
+            .any(|(name, _)| *name == "id")
+    );
+    assert!(
+        Event::sortable_columns()
+            .iter()
+            .any(|(name, _)| *name == "public_id")
+    );
+    assert!(
+        Order::sortable_columns()
+            .iter()
+            .any(|(name, _)| *name == "id")
+    );
+}
+
+#[test]
+fn test_schema_formats() {
+    let event = serde_json::to_value(<Event as utoipa::PartialSchema>::schema()).unwrap();
+    assert_eq!(event["properties"]["id"]["format"], "uuid", "{event}");
+    assert_eq!(
+        event["properties"]["public_id"]["format"], "ksuid",
+        "{event}"
+    );
+
+    let order = serde_json::to_value(<Order as utoipa::PartialSchema>::schema()).unwrap();
+    assert_eq!(order["properties"]["id"]["format"], "uuid", "{order}");
+    assert_eq!(
+        order["properties"]["reference"]["format"], "ulid",
+        "{order}"
+    );
+}
@@ -0,0 +1,325 @@
+// Tests for `#[crudcrate(max_join_queries = N, max_joined_rows = N)]`
+// Verifies that list pages, single items and by-ID lookups whose estimated join loading
+// is over the budget get a 400 naming the page size that fits, before any query runs,
+// and that requests within the budget still load their joins.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::complexity::{self, JoinCost};
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod publisher {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "publishers")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Publisher",
+        no_partial_eq,
+        no_eq,
+        max_joined_rows = 100
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, depth = 2))]
+        pub authors: Vec<super::author::Author>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::author::Entity")]
+        Authors,
+    }
+
+    impl Related<super::author::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Authors.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod author {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "authors")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Author",
+        derive_partial_eq,
+        derive_eq,
+        max_join_queries = 30
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub publisher_id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, all, depth = 2, limit = 5))]
+        pub books: Vec<super::book::Book>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::publisher::Entity",
+            from = "Column::PublisherId",
+            to = "super::publisher::Column::Id"
+        )]
+        Publisher,
+        #[sea_orm(has_many = "super::book::Entity")]
+        Books,
+    }
+
+    impl Related<super::publisher::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Publisher.def()
+        }
+    }
+
+    impl Related<super::book::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Books.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod book {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "books")]
+    #[crudcrate(generate_router, api_struct = "Book", derive_partial_eq, derive_eq)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub author_id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::author::Entity",
+            from = "Column::AuthorId",
+            to = "super::author::Column::Id"
+        )]
+        Author,
+    }
+
+    impl Related<super::author::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Author.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use author::Author;
+use book::Book;
+use publisher::Publisher;
+
+/// A publisher with two authors of three books each; returns its ID
+async fn setup() -> (Router, String) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(publisher::Entity),
+        schema.create_table_from_entity(author::Entity),
+        schema.create_table_from_entity(book::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+    let app = Router::new()
+        .nest("/publishers", Publisher::router(&db).into())
+        .nest("/authors", Author::router(&db).into())
+        .nest("/books", Book::router(&db).into());
+
+    let (_, publisher) = send(&app, "POST", "/publishers", Some(json!({"name": "p"}))).await;
+    for name in ["a", "b"] {
+        let (status, author) = send(
+            &app,
+            "POST",
+            "/authors",
+            Some(json!({"publisher_id": publisher["id"], "name": name})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{author}");
+        for title in ["x", "y", "z"] {
+            let (status, book) = send(
+                &app,
+                "POST",
+                "/books",
+                Some(json!({"author_id": author["id"], "title": title})),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED, "{book}");
+        }
+    }
+    (app, publisher["id"].as_str().unwrap().to_string())
+}
+
+#[test]
+fn test_estimates_follow_join_config() {
+    // One query for the page's books (at most 5 per author), then a `get_one` per book
+    assert_eq!(
+        complexity::estimate::<Author>(6, true),
+        JoinCost {
+            queries: 1 + 30,
+            rows: 30
+        }
+    );
+    // Without the list `limit`, ten books are assumed
+    assert_eq!(
+        complexity::estimate::<Author>(1, false),
+        JoinCost {
+            queries: 11,
+            rows: 10
+        }
+    );
+    // Each of ten authors is loaded with its books
+    assert_eq!(
+        complexity::estimate::<Publisher>(1, false),
+        JoinCost {
+            queries: 1 + 10 * 12,
+            rows: 10 + 10 * 10
+        }
+    );
+    // Lists don't load `join(one)` fields
+    assert_eq!(
+        complexity::estimate::<Publisher>(100, true),
+        JoinCost::default()
+    );
+    assert_eq!(complexity::estimate::<Book>(100, true), JoinCost::default());
+}
+
+#[tokio::test]
+async fn test_list_over_budget_names_page_size() {
+    let (app, _) = setup().await;
+
+    // The default page of 10 authors would take 51 queries
+    let (status, body) = send(&app, "GET", "/authors", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let message = body["error"].as_str().unwrap();
+    assert!(message.contains("about 51 queries"), "{message}");
+    assert!(message.contains("at most 5 items per page"), "{message}");
+
+    let (status, body) = send(&app, "GET", "/authors?page=1&per_page=5", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let authors = body.as_array().unwrap();
+    assert_eq!(authors.len(), 2);
+    assert!(
+        authors
+            .iter()
+            .all(|a| a["books"].as_array().unwrap().len() == 3)
+    );
+
+    // Unbudgeted resources are unaffected
+    let (status, _) = send(&app, "GET", "/books?page=1&per_page=1000", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_single_item_over_budget() {
+    let (app, publisher_id) = setup().await;
+
+    let (status, body) = send(&app, "GET", &format!("/publishers/{publisher_id}"), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let message = body["error"].as_str().unwrap();
+    assert!(message.contains("110 rows"), "{message}");
+    assert!(message.contains("single item"), "{message}");
+
+    // Authors fit on their own
+    let (_, authors) = send(&app, "GET", "/authors?page=1&per_page=1", None).await;
+    let author_id = authors[0]["id"].as_str().unwrap();
+    let (status, body) = send(&app, "GET", &format!("/authors/{author_id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["books"].as_array().unwrap().len(), 3);
+
+    let (status, _) = send(&app, "GET", "/publishers", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_by_ids_over_budget() {
+    let (app, _) = setup().await;
+    let (_, authors) = send(&app, "GET", "/authors?page=1&per_page=2", None).await;
+    let mut ids: Vec<String> = authors
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["id"].as_str().unwrap().to_string())
+        .collect();
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/authors/by-ids?ids={}", ids.join(",")),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["items"].as_array().unwrap().len(), 2);
+
+    // Six IDs are estimated like a page of six, missing ones included
+    ids.extend((0..4).map(|_| Uuid::new_v4().to_string()));
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/authors/by-ids?ids={}", ids.join(",")),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let message = body["error"].as_str().unwrap();
+    assert!(message.contains("about 31 queries"), "{message}");
+}
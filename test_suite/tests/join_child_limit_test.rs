@@ -0,0 +1,174 @@
+// Tests for `join(all, limit = N, order_by = "...")`
+// Verifies that list responses hold at most N children per parent, picked by the
+// order_by column, and that get_one still returns every child in that order.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod comment {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "comments")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Comment",
+        name_singular = "comment",
+        name_plural = "comments",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub post_id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub position: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::post::Entity",
+            from = "Column::PostId",
+            to = "super::post::Column::Id"
+        )]
+        Post,
+    }
+
+    impl Related<super::post::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Post.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod post {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "posts")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Post",
+        name_singular = "post",
+        name_plural = "posts",
+        no_partial_eq,
+        no_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub title: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(
+            non_db_attr,
+            join(one, all, depth = 1, limit = 2, order_by = "position desc")
+        )]
+        pub comments: Vec<super::comment::Comment>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::comment::Entity")]
+        Comments,
+    }
+
+    impl Related<super::comment::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Comments.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use comment::Comment;
+use post::Post;
+
+/// Two posts: "a" with comments at positions 1..=4, "b" with a single comment
+async fn setup() -> (Router, String) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(post::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(comment::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/posts", Post::router(&db).into())
+        .nest("/comments", Comment::router(&db).into());
+
+    let (_, a) = send(&app, "POST", "/posts", Some(json!({"title": "a"}))).await;
+    let (_, b) = send(&app, "POST", "/posts", Some(json!({"title": "b"}))).await;
+    for (post, position) in [(&a, 2), (&a, 4), (&b, 1), (&a, 1), (&a, 3)] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/comments",
+            Some(json!({"post_id": post["id"], "position": position})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    (app, a["id"].as_str().unwrap().to_string())
+}
+
+fn positions(comments: &Value) -> Vec<i64> {
+    comments
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["position"].as_i64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_list_caps_children_per_parent() {
+    let (app, _) = setup().await;
+
+    let (status, body) = send(&app, "GET", "/posts?sort_by=title&order=ASC", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let posts = body.as_array().unwrap();
+    assert_eq!(posts.len(), 2);
+    assert_eq!(positions(&posts[0]["comments"]), [4, 3]);
+    assert_eq!(positions(&posts[1]["comments"]), [1]);
+}
+
+#[tokio::test]
+async fn test_get_one_returns_all_children_in_order() {
+    let (app, id) = setup().await;
+
+    let (status, body) = send(&app, "GET", &format!("/posts/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(positions(&body["comments"]), [4, 3, 2, 1]);
+}
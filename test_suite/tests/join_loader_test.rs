@@ -0,0 +1,166 @@
+// Tests for the generated `load_{join}` batch loaders
+// Verifies that `Customer::load_vehicles(db, &ids)` groups each parent's children by
+// parent ID in the join's order, leaves out parents without children and unknown IDs,
+// and that `Option` joins load the related row for each parent.
+
+use crudcrate::EntityToModels;
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Database, DatabaseConnection, Schema};
+use uuid::Uuid;
+
+pub mod vehicle {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "vehicles")]
+    #[crudcrate(generate_router, api_struct = "Vehicle", derive_partial_eq)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub customer_id: Uuid,
+
+        pub make: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, depth = 1))]
+        pub customer: Option<super::customer::Customer>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::customer::Entity",
+            from = "Column::CustomerId",
+            to = "super::customer::Column::Id"
+        )]
+        Customer,
+    }
+
+    impl Related<super::customer::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Customer.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod customer {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "customers")]
+    #[crudcrate(generate_router, api_struct = "Customer", derive_partial_eq)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, all, depth = 1, order_by = "make"))]
+        pub vehicles: Vec<super::vehicle::Vehicle>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::vehicle::Entity")]
+        Vehicles,
+    }
+
+    impl Related<super::vehicle::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Vehicles.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use customer::Customer;
+use vehicle::Vehicle;
+
+/// Ada has no vehicles, Bob one and Cy two; returns their IDs and Bob's vehicle
+async fn setup() -> (DatabaseConnection, [Uuid; 3], Uuid) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(customer::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(vehicle::Entity)))
+        .await
+        .unwrap();
+    let mut customers = [Uuid::nil(); 3];
+    let mut bobs_vehicle = Uuid::nil();
+    for (index, (name, makes)) in [
+        ("Ada", vec![]),
+        ("Bob", vec!["Saab"]),
+        ("Cy", vec!["Volvo", "Audi"]),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let id = Uuid::new_v4();
+        customer::ActiveModel {
+            id: Set(id),
+            name: Set(name.to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        for make in makes {
+            let vehicle = vehicle::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                customer_id: Set(id),
+                make: Set(make.to_string()),
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+            if name == "Bob" {
+                bobs_vehicle = vehicle.id;
+            }
+        }
+        customers[index] = id;
+    }
+    (db, customers, bobs_vehicle)
+}
+
+#[tokio::test]
+async fn test_loads_vec_join_grouped_by_parent() {
+    let (db, [ada, bob, cy], _) = setup().await;
+    let loaded = Customer::load_vehicles(&db, &[ada, bob, cy, Uuid::new_v4()])
+        .await
+        .unwrap();
+
+    assert_eq!(loaded.len(), 2, "only parents with vehicles");
+    assert!(!loaded.contains_key(&ada));
+    let makes = |id: &Uuid| -> Vec<String> {
+        loaded[id]
+            .iter()
+            .map(|vehicle| vehicle.make.clone())
+            .collect()
+    };
+    assert_eq!(makes(&bob), ["Saab"]);
+    assert_eq!(makes(&cy), ["Audi", "Volvo"], "follows order_by");
+
+    let none = Customer::load_vehicles(&db, &[]).await.unwrap();
+    assert!(none.is_empty());
+}
+
+#[tokio::test]
+async fn test_loads_option_join_for_each_parent() {
+    let (db, [_, bob, _], bobs_vehicle) = setup().await;
+    let loaded = Vehicle::load_customer(&db, &[bobs_vehicle, Uuid::new_v4()])
+        .await
+        .unwrap();
+
+    assert_eq!(loaded.len(), 1);
+    let owner = &loaded[&bobs_vehicle];
+    assert_eq!(owner.id, bob);
+    assert_eq!(owner.name, "Bob");
+}
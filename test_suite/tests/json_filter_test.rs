@@ -0,0 +1,158 @@
+// Tests for filtering into JSON columns
+// Verifies that filterable `serde_json::Value` fields accept dot-path filters with
+// operator suffixes, that non-filterable JSON fields are ignored, and that JSON paths
+// take precedence over join dot-notation.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub mod product {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "products")]
+    #[crudcrate(generate_router, api_struct = "Product")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub name: String,
+
+        #[crudcrate(filterable)]
+        pub metadata: serde_json::Value,
+
+        pub internal: Option<serde_json::Value>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use product::{Product, ProductCreate};
+
+async fn setup() -> (DatabaseConnection, axum::Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(product::Entity)))
+        .await
+        .unwrap();
+
+    let items = vec![
+        ProductCreate {
+            name: "Chair".into(),
+            metadata: json!({"color": "Red", "width": 45, "in_stock": true, "tags": ["sale"]}),
+            internal: Some(json!({"secret": "x"})),
+        },
+        ProductCreate {
+            name: "Table".into(),
+            metadata: json!({"color": "oak", "width": 120, "in_stock": false, "tags": ["new"]}),
+            internal: None,
+        },
+        ProductCreate {
+            name: "Lamp".into(),
+            metadata: json!({"color": "red", "width": 20, "dimensions": {"unit": "cm"}}),
+            internal: None,
+        },
+    ];
+    Product::create_many(&db, items).await.unwrap();
+
+    let app = axum::Router::new().nest("/products", Product::router(&db).into());
+    (db, app)
+}
+
+async fn names(app: &axum::Router, filter: &serde_json::Value) -> (StatusCode, Vec<String>) {
+    let encoded = url_escape::encode_component(&filter.to_string()).to_string();
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/products?filter={encoded}&sort_by=name&order=ASC"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    if status != StatusCode::OK {
+        return (status, vec![]);
+    }
+    let items: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    let names = items
+        .iter()
+        .map(|item| item["name"].as_str().unwrap().to_string())
+        .collect();
+    (status, names)
+}
+
+#[test]
+fn test_json_filterable_columns() {
+    // Only filterable JSON fields accept dot paths
+    assert_eq!(Product::json_filterable_columns(), vec!["metadata"]);
+}
+
+#[tokio::test]
+async fn test_json_string_filter_is_case_insensitive() {
+    let (_db, app) = setup().await;
+    let (status, names) = names(&app, &json!({"metadata.color": "RED"})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names, vec!["Chair", "Lamp"]);
+}
+
+#[tokio::test]
+async fn test_json_numeric_operators() {
+    let (_db, app) = setup().await;
+    let (_, names_gte) = names(&app, &json!({"metadata.width_gte": 45})).await;
+    assert_eq!(names_gte, vec!["Chair", "Table"]);
+
+    let (_, names_lt) = names(&app, &json!({"metadata.width_lt": 45})).await;
+    assert_eq!(names_lt, vec!["Lamp"]);
+}
+
+#[tokio::test]
+async fn test_json_bool_nested_and_index_paths() {
+    let (_db, app) = setup().await;
+    let (_, in_stock) = names(&app, &json!({"metadata.in_stock": true})).await;
+    assert_eq!(in_stock, vec!["Chair"]);
+
+    let (_, nested) = names(&app, &json!({"metadata.dimensions.unit": "cm"})).await;
+    assert_eq!(nested, vec!["Lamp"]);
+
+    let (_, tagged) = names(&app, &json!({"metadata.tags.0": "new"})).await;
+    assert_eq!(tagged, vec!["Table"]);
+}
+
+#[tokio::test]
+async fn test_json_filter_combines_with_regular_filters() {
+    let (_db, app) = setup().await;
+    let (_, names) = names(&app, &json!({"metadata.color": "red", "name": "Lamp"})).await;
+    assert_eq!(names, vec!["Lamp"]);
+}
+
+#[tokio::test]
+async fn test_non_filterable_json_column_is_ignored() {
+    let (_db, app) = setup().await;
+    let (status, names) = names(&app, &json!({"internal.secret": "x"})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names, vec!["Chair", "Lamp", "Table"]);
+}
+
+#[tokio::test]
+async fn test_invalid_json_path_is_rejected() {
+    let (_db, app) = setup().await;
+    let (status, _) = names(&app, &json!({"metadata.color')--": "red"})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,167 @@
+// Tests for keyset pagination with `after_id` and `after_value`
+// Verifies that pages continue after the last row seen, that ties on the sort column are
+// broken by ID, that rows inserted between pages don't shift later pages, and that
+// invalid keyset parameters are rejected.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod entry {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "entries")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Entry",
+        name_singular = "entry",
+        name_plural = "entries",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub label: String,
+
+        #[crudcrate(sortable, filterable)]
+        pub score: i32,
+
+        pub notes: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use entry::Entry;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(entry::Entity)))
+        .await
+        .unwrap();
+    Router::new().nest("/entries", Entry::router(&db).into())
+}
+
+async fn create(app: &Router, label: &str, score: i32) {
+    let (status, body) = send(
+        app,
+        "POST",
+        "/entries",
+        Some(json!({"label": label, "score": score, "notes": ""})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+}
+
+async fn page(app: &Router, query: &str) -> Vec<Value> {
+    let (status, body) = send(app, "GET", &format!("/entries?{query}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body.as_array().unwrap().clone()
+}
+
+fn after(last: &Value) -> String {
+    format!(
+        "after_value={}&after_id={}",
+        last["score"],
+        last["id"].as_str().unwrap()
+    )
+}
+
+#[tokio::test]
+async fn test_pages_follow_the_sort_column() {
+    let app = setup().await;
+    for (label, score) in [("a", 10), ("b", 20), ("c", 20), ("d", 20), ("e", 30)] {
+        create(&app, label, score).await;
+    }
+    let sort = url_escape::encode_component(r#"["score","DESC"]"#);
+    let base = format!("sort={sort}&page=1&per_page=2");
+
+    let mut seen = Vec::new();
+    let mut query = base.clone();
+    loop {
+        let rows = page(&app, &query).await;
+        let Some(last) = rows.last() else {
+            break;
+        };
+        seen.extend(
+            rows.iter()
+                .map(|row| row["label"].as_str().unwrap().to_string()),
+        );
+        query = format!("{base}&{}", after(last));
+
+        // A row inserted ahead of the cursor doesn't shift the next page
+        if seen.len() == 2 {
+            create(&app, "new", 99).await;
+        }
+    }
+
+    assert_eq!(seen.len(), 5, "{seen:?}");
+    assert_eq!(seen[0], "e");
+    assert_eq!(seen[4], "a");
+    let mut middle = seen[1..4].to_vec();
+    middle.sort();
+    assert_eq!(middle, ["b", "c", "d"]);
+}
+
+#[tokio::test]
+async fn test_sorting_by_id_needs_only_after_id() {
+    let app = setup().await;
+    for (label, score) in [("a", 1), ("b", 2), ("c", 3)] {
+        create(&app, label, score).await;
+    }
+    let all = page(&app, "sort=id&page=1&per_page=10").await;
+    let first_id = all[0]["id"].as_str().unwrap();
+
+    let rest = page(
+        &app,
+        &format!("sort=id&page=1&per_page=10&after_id={first_id}"),
+    )
+    .await;
+    assert_eq!(rest, all[1..]);
+}
+
+#[tokio::test]
+async fn test_rejects_invalid_keyset_parameters() {
+    let app = setup().await;
+    create(&app, "a", 1).await;
+    let id = Uuid::new_v4();
+    let multi = url_escape::encode_component(r#"[["score","DESC"],["id","ASC"]]"#);
+
+    for query in [
+        format!("sort=score&after_id={id}"),
+        "sort=score&after_value=1".to_string(),
+        "sort=score&after_value=1&after_id=nope".to_string(),
+        format!("sort=score&after_value=high&after_id={id}"),
+        format!("sort=notes&after_value=x&after_id={id}"),
+        format!("sort={multi}&after_value=1&after_id={id}"),
+    ] {
+        let (status, body) = send(&app, "GET", &format!("/entries?{query}"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{query}: {body}");
+    }
+}
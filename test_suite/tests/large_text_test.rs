@@ -0,0 +1,227 @@
+// Tests for `#[crudcrate(large_text)]`
+// Verifies that large text fields are left out of list responses but kept in get-one,
+// that `GET /{id}/{field}` returns the column as plain text (204 when NULL), that
+// unknown IDs, other fields and scope-excluded fields are rejected, that scope applies,
+// and that OpenAPI documents the endpoint.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use axum::{Extension, Router};
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tower::ServiceExt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod post {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "posts")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Post",
+        name_singular = "post",
+        name_plural = "posts",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub published: bool,
+
+        #[crudcrate(large_text)]
+        pub body: Option<String>,
+
+        #[crudcrate(large_text, exclude(scoped))]
+        pub draft_notes: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use post::{Post, PostList};
+
+const BODY: &str = "# Release notes\n\nÜnicode and\nseveral lines.";
+
+async fn setup() -> (DatabaseConnection, Router, Uuid, Uuid) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(post::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/posts", Post::router(&db).into());
+
+    let (status, published) = send(
+        &app,
+        "POST",
+        "/posts",
+        Some(json!({"title": "Launch", "published": true, "body": BODY, "draft_notes": "tbd"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{published}");
+    let (status, draft) = send(
+        &app,
+        "POST",
+        "/posts",
+        Some(json!({"title": "Draft", "published": false, "body": null, "draft_notes": null})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{draft}");
+
+    let id = |post: &serde_json::Value| post["id"].as_str().unwrap().parse().unwrap();
+    (db, app, id(&published), id(&draft))
+}
+
+/// Status, content type and body text of a GET request
+async fn get_text(app: &Router, uri: &str) -> (StatusCode, Option<String>, String) {
+    let response = app
+        .clone()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(bytes.to_vec()).unwrap(),
+    )
+}
+
+#[test]
+fn test_large_text_columns_listed() {
+    let names: Vec<&str> = Post::large_text_columns()
+        .iter()
+        .map(|(name, _)| *name)
+        .collect();
+    assert_eq!(names, ["body", "draft_notes"]);
+}
+
+#[tokio::test]
+async fn test_list_leaves_out_large_text() {
+    let (_, app, id, _) = setup().await;
+
+    let (status, body) = send(&app, "GET", "/posts", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    for post in body.as_array().unwrap() {
+        assert!(post.get("title").is_some(), "{post}");
+        assert!(post.get("body").is_none(), "{post}");
+        assert!(post.get("draft_notes").is_none(), "{post}");
+    }
+
+    let (status, post) = send(&app, "GET", &format!("/posts/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{post}");
+    assert_eq!(post["body"], BODY);
+}
+
+#[tokio::test]
+async fn test_field_endpoint_returns_plain_text() {
+    let (_, app, id, draft_id) = setup().await;
+
+    let (status, content_type, text) = get_text(&app, &format!("/posts/{id}/body")).await;
+    assert_eq!(status, StatusCode::OK, "{text}");
+    assert_eq!(content_type.as_deref(), Some("text/plain; charset=utf-8"));
+    assert_eq!(text, BODY);
+
+    let (status, _, text) = get_text(&app, &format!("/posts/{draft_id}/body")).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(text.is_empty());
+}
+
+#[tokio::test]
+async fn test_field_endpoint_rejects_other_fields_and_ids() {
+    let (_, app, id, _) = setup().await;
+
+    for field in ["title", "missing"] {
+        let (status, body) = send(&app, "GET", &format!("/posts/{id}/{field}"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{field}");
+        assert_eq!(
+            body["error"],
+            format!("Field '{field}' is not a large text field"),
+            "{body}"
+        );
+    }
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/posts/{}/body", Uuid::new_v4()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_read_only_router_applies_scope() {
+    let (db, _, id, draft_id) = setup().await;
+    let public: Router = Post::read_only_router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all().add(post::Column::Published.eq(true)),
+        }))
+        .into();
+    let public = Router::new().nest("/posts", public);
+
+    let (status, _, text) = get_text(&public, &format!("/posts/{id}/body")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(text, BODY);
+
+    // Out-of-scope rows are not found, scope-excluded fields can't be read
+    let (status, _, _) = get_text(&public, &format!("/posts/{draft_id}/body")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = get_text(&public, &format!("/posts/{id}/draft_notes")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_openapi_documents_field_endpoint() {
+    let (db, _, _, _) = setup().await;
+    let (_, openapi) = Post::router(&db).split_for_parts();
+    let openapi = serde_json::to_value(openapi).unwrap();
+    let operation = &openapi["paths"]["/{id}/{field}"]["get"];
+    assert_eq!(
+        operation["operationId"], "get_large_text_post",
+        "{operation}"
+    );
+    assert!(
+        operation["responses"]["200"]["content"]
+            .get("text/plain")
+            .is_some(),
+        "{operation}"
+    );
+
+    let list = serde_json::to_value(<PostList as utoipa::PartialSchema>::schema()).unwrap();
+    assert!(list["properties"].get("body").is_none(), "{list}");
+}
@@ -0,0 +1,361 @@
+// Tests for `join(one, lazy)` and its `GET /{id}/{field}` relation endpoint
+// Verifies that lazy fields stay out of list responses but load in get_one, and that
+// the relation endpoint pages, filters and sorts the children like the child's own list,
+// 404s on unknown or out-of-scope parents, hides scoped-out children, and is mounted on
+// routers with and without OpenAPI.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::{Extension, Router};
+use crudcrate::testing::send;
+use crudcrate::{EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Condition, Database, DatabaseConnection, Schema};
+use serde_json::Value;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub mod book {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "books")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Book",
+        name_singular = "book",
+        name_plural = "books",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub author_id: Uuid,
+
+        pub publisher_id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        #[crudcrate(exclude(scoped))]
+        pub is_draft: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::author::Entity",
+            from = "Column::AuthorId",
+            to = "super::author::Column::Id"
+        )]
+        Author,
+        #[sea_orm(
+            belongs_to = "super::publisher::Entity",
+            from = "Column::PublisherId",
+            to = "super::publisher::Column::Id"
+        )]
+        Publisher,
+    }
+
+    impl Related<super::author::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Author.def()
+        }
+    }
+
+    impl Related<super::publisher::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Publisher.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod author {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "authors")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Author",
+        name_singular = "author",
+        name_plural = "authors",
+        no_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, lazy, depth = 1))]
+        pub books: Vec<super::book::Book>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::book::Entity")]
+        Books,
+    }
+
+    impl Related<super::book::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Books.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod publisher {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "publishers")]
+    #[crudcrate(
+        generate_router,
+        openapi = false,
+        api_struct = "Publisher",
+        name_singular = "publisher",
+        name_plural = "publishers",
+        no_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, lazy, depth = 1))]
+        pub books: Vec<super::book::Book>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::book::Entity")]
+        Books,
+    }
+
+    impl Related<super::book::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Books.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use author::Author;
+use publisher::Publisher;
+
+struct Ids {
+    ada: Uuid,
+    bo: Uuid,
+    press: Uuid,
+}
+
+/// Ada has the books "A", "B" and "C", "C" a draft; Bo has "D". All but "D" are
+/// published by "Press".
+async fn setup() -> (DatabaseConnection, Ids) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(author::Entity),
+        schema.create_table_from_entity(publisher::Entity),
+        schema.create_table_from_entity(book::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+
+    let ids = Ids {
+        ada: Uuid::new_v4(),
+        bo: Uuid::new_v4(),
+        press: Uuid::new_v4(),
+    };
+    for (id, name) in [(ids.ada, "Ada"), (ids.bo, "Bo")] {
+        author::ActiveModel {
+            id: Set(id),
+            name: Set(name.to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    let other = Uuid::new_v4();
+    for (id, name) in [(ids.press, "Press"), (other, "Other")] {
+        publisher::ActiveModel {
+            id: Set(id),
+            name: Set(name.to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    for (author_id, publisher_id, title, is_draft) in [
+        (ids.ada, ids.press, "C", true),
+        (ids.ada, ids.press, "A", false),
+        (ids.ada, ids.press, "B", false),
+        (ids.bo, other, "D", false),
+    ] {
+        book::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            author_id: Set(author_id),
+            publisher_id: Set(publisher_id),
+            title: Set(title.to_string()),
+            is_draft: Set(is_draft),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    (db, ids)
+}
+
+fn app(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .nest("/authors", Author::router(db).into())
+        .nest("/publishers", Publisher::router(db))
+}
+
+fn titles(body: &Value) -> Vec<&str> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|book| book["title"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_lazy_field_loads_only_in_get_one() {
+    let (db, ids) = setup().await;
+    let app = app(&db);
+
+    let (status, body) = send(&app, "GET", "/authors", None).await;
+    assert_eq!(status, StatusCode::OK);
+    for author in body.as_array().unwrap() {
+        assert!(author.get("books").is_none(), "{author}");
+    }
+
+    let (status, body) = send(&app, "GET", &format!("/authors/{}", ids.ada), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["books"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_relation_endpoint_pages_and_sorts() {
+    let (db, ids) = setup().await;
+    let app = app(&db);
+
+    let uri = format!(
+        "/authors/{}/books?sort={}&page=1&per_page=2",
+        ids.ada,
+        url_escape::encode_component(r#"["title","ASC"]"#)
+    );
+    let request = Request::builder().uri(&uri).body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let range = response.headers()["content-range"].to_str().unwrap();
+    assert!(range.ends_with("/3"), "{range}");
+
+    let (_, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(titles(&body), ["A", "B"]);
+    let uri = uri.replace("page=1", "page=2");
+    let (_, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(titles(&body), ["C"]);
+}
+
+#[tokio::test]
+async fn test_relation_endpoint_filters() {
+    let (db, ids) = setup().await;
+    let app = app(&db);
+
+    let uri = format!(
+        "/authors/{}/books?filter={}",
+        ids.ada,
+        url_escape::encode_component(r#"{"title":"B"}"#)
+    );
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["B"]);
+
+    // Another author's books never match
+    let uri = format!(
+        "/authors/{}/books?filter={}",
+        ids.bo,
+        url_escape::encode_component(r#"{"title":"B"}"#)
+    );
+    let (_, body) = send(&app, "GET", &uri, None).await;
+    assert!(titles(&body).is_empty());
+}
+
+#[tokio::test]
+async fn test_relation_endpoint_unknown_parent() {
+    let (db, _) = setup().await;
+    let app = app(&db);
+
+    let uri = format!("/authors/{}/books", Uuid::new_v4());
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{body}");
+
+    let (status, _) = send(&app, "GET", "/authors/not-a-uuid/books", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_relation_endpoint_scoped() {
+    let (db, ids) = setup().await;
+    let scoped: Router = Author::read_only_router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all().add(author::Column::Name.ne("Bo")),
+        }))
+        .into();
+    let app = Router::new().nest("/authors", scoped);
+
+    // Drafts are hidden, and so is the draft flag
+    let (status, body) = send(&app, "GET", &format!("/authors/{}/books", ids.ada), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut visible = titles(&body);
+    visible.sort_unstable();
+    assert_eq!(visible, ["A", "B"]);
+    assert!(body[0].get("is_draft").is_none(), "{body}");
+
+    // Parents outside the scope don't exist
+    let (status, _) = send(&app, "GET", &format!("/authors/{}/books", ids.bo), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_relation_endpoint_without_openapi() {
+    let (db, ids) = setup().await;
+    let app = app(&db);
+
+    let uri = format!("/publishers/{}/books?per_page=10", ids.press);
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body).len(), 3);
+
+    let (_, body) = send(&app, "GET", "/publishers", None).await;
+    assert!(body[0].get("books").is_none(), "{body}");
+}
+
+#[tokio::test]
+async fn test_relation_endpoint_documented() {
+    let (db, _) = setup().await;
+    let (_, openapi) = Author::router(&db).split_for_parts();
+    let path = openapi
+        .paths
+        .paths
+        .get("/{id}/books")
+        .expect("relation path");
+    let operation = path.get.as_ref().unwrap();
+    assert_eq!(operation.operation_id.as_deref(), Some("get_author_books"));
+}
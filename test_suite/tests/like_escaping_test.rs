@@ -0,0 +1,188 @@
+// Tests for LIKE escaping in text filters and fulltext search
+// Runs seeded random terms full of `%`, `_`, backslashes and quotes through
+// `filter={"title": ...}` and `q` on SQLite and checks the rows returned against a plain
+// substring match, so no term can act as a wildcard or break out of its parameter.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde_json::json;
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+pub mod phrase {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "phrases")]
+    #[crudcrate(generate_router, api_struct = "Phrase")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, fulltext)]
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use phrase::Phrase;
+
+/// Splitmix64, so failures replay from the seed
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        usize::try_from(self.next_u64() % n as u64).unwrap()
+    }
+
+    /// Text from a small alphabet of letters and `LIKE`/SQL metacharacters (no commas,
+    /// which split filter values into lists)
+    fn text(&mut self, max_len: usize) -> String {
+        const ALPHABET: &[u8] = b"%_\\'\"[]^*ab AB";
+        let len = 1 + self.below(max_len);
+        (0..len)
+            .map(|_| char::from(ALPHABET[self.below(ALPHABET.len())]))
+            .collect()
+    }
+}
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(phrase::Entity)))
+        .await
+        .unwrap();
+    Router::new().nest("/phrases", Phrase::router(&db).into())
+}
+
+async fn create(app: &Router, title: &str) {
+    let (status, body) = send(app, "POST", "/phrases", Some(json!({ "title": title }))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+}
+
+/// Titles listed for `filter`
+async fn titles(app: &Router, filter: &serde_json::Value) -> BTreeSet<String> {
+    let filter = url_escape::encode_component(&filter.to_string()).to_string();
+    let uri = format!("/phrases?filter={filter}&range=%5B0%2C999%5D");
+    let (status, body) = send(app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{uri}: {body}");
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+/// Titles containing `term`, ignoring ASCII case
+fn expected(all: &BTreeSet<String>, term: &str) -> BTreeSet<String> {
+    let term = term.to_ascii_uppercase();
+    all.iter()
+        .filter(|title| title.to_ascii_uppercase().contains(&term))
+        .cloned()
+        .collect()
+}
+
+#[tokio::test]
+async fn test_metacharacters_match_literally() {
+    let app = setup().await;
+    for title in [
+        "100% sure",
+        "1000 sure",
+        "a_b",
+        "axb",
+        "back\\slash",
+        "backslash",
+    ] {
+        create(&app, title).await;
+    }
+
+    for (term, matches) in [
+        ("%", &["100% sure"][..]),
+        ("0%", &["100% sure"]),
+        ("_", &["a_b"]),
+        ("a_b", &["a_b"]),
+        ("\\", &["back\\slash"]),
+        ("k\\s", &["back\\slash"]),
+        ("\\%", &[]),
+        ("' OR '1'='1", &[]),
+    ] {
+        let expected: BTreeSet<String> = matches.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            titles(&app, &json!({ "title": term })).await,
+            expected,
+            "filter {term:?}"
+        );
+        assert_eq!(
+            titles(&app, &json!({ "q": term })).await,
+            expected,
+            "q {term:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_random_terms_match_substrings() {
+    let app = setup().await;
+    let mut rng = Rng(0x1111_CE5E);
+    let mut all = BTreeSet::new();
+    while all.len() < 40 {
+        let title = rng.text(10).trim().to_string();
+        if !title.is_empty() && all.insert(title.clone()) {
+            create(&app, &title).await;
+        }
+    }
+    let titles_list: Vec<String> = all.iter().cloned().collect();
+
+    for iteration in 0..150 {
+        // Half the terms are cut from stored titles, so most searches find something
+        let term = if rng.below(2) == 0 {
+            let title = &titles_list[rng.below(titles_list.len())];
+            let start = rng.below(title.len());
+            let end = start + 1 + rng.below(title.len() - start);
+            title[start..end].to_string()
+        } else {
+            rng.text(4)
+        };
+        let term = term.trim();
+        if term.is_empty() {
+            continue;
+        }
+        let expected = expected(&all, term);
+        assert_eq!(
+            titles(&app, &json!({ "title": term })).await,
+            expected,
+            "iteration {iteration}: filter {term:?}"
+        );
+        assert_eq!(
+            titles(&app, &json!({ "q": term })).await,
+            expected,
+            "iteration {iteration}: q {term:?}"
+        );
+    }
+}
+
+/// Long queries are cut on a character boundary instead of panicking mid-character
+#[tokio::test]
+async fn test_long_multibyte_query() {
+    let app = setup().await;
+    create(&app, "plain").await;
+    let query = format!("{}é", "a".repeat(9_999));
+    assert!(titles(&app, &json!({ "q": query })).await.is_empty());
+}
@@ -0,0 +1,199 @@
+// Tests for `#[crudcrate(list_only)]`
+// Verifies that list-only `counted_relation` and `computed_many` fields are filled in
+// the list with one batch call per page, and that `GET /{id}` and write responses
+// neither include them nor compute them.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Calls of the `computed` and `computed_many` functions of `Folder::summary`
+static COMPUTED_CALLS: AtomicUsize = AtomicUsize::new(0);
+static COMPUTED_MANY_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+pub mod document {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "documents")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Document",
+        name_singular = "document",
+        name_plural = "documents",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub folder_id: Uuid,
+
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::folder::Entity",
+            from = "Column::FolderId",
+            to = "super::folder::Column::Id"
+        )]
+        Folder,
+    }
+
+    impl Related<super::folder::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Folder.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod folder {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "folders")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Folder",
+        name_singular = "folder",
+        name_plural = "folders"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(
+            non_db_attr,
+            list_only,
+            counted_relation = "Documents",
+            counted_resource = "super::document::Document"
+        )]
+        pub children_count: i64,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, list_only, computed = summary, computed_many = summaries)]
+        pub summary: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::document::Entity")]
+        Documents,
+    }
+
+    impl Related<super::document::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Documents.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    // Only `GET /{id}` would call it
+    #[allow(clippy::unused_async, dead_code)]
+    async fn summary(folder: &Folder, _db: &DatabaseConnection) -> Result<String, ApiError> {
+        COMPUTED_CALLS.fetch_add(1, Ordering::SeqCst);
+        Ok(format!("{} (one)", folder.name))
+    }
+
+    #[allow(clippy::unused_async)]
+    async fn summaries(
+        folders: &[Folder],
+        _db: &DatabaseConnection,
+    ) -> Result<Vec<String>, ApiError> {
+        COMPUTED_MANY_CALLS.fetch_add(1, Ordering::SeqCst);
+        Ok(folders
+            .iter()
+            .map(|folder| format!("{} (page)", folder.name))
+            .collect())
+    }
+}
+
+use document::Document;
+use folder::Folder;
+
+#[tokio::test]
+async fn test_list_only_fields() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(folder::Entity),
+        schema.create_table_from_entity(document::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+    let app = Router::new()
+        .nest("/folders", Folder::router(&db).into())
+        .nest("/documents", Document::router(&db).into());
+
+    // Write responses leave the fields out
+    let (status, inbox) = send(&app, "POST", "/folders", Some(json!({"name": "inbox"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{inbox}");
+    assert!(inbox.get("children_count").is_none(), "{inbox}");
+    assert!(inbox.get("summary").is_none(), "{inbox}");
+    let (_, archive) = send(&app, "POST", "/folders", Some(json!({"name": "archive"}))).await;
+    for name in ["a.txt", "b.txt"] {
+        let body = json!({"folder_id": inbox["id"], "name": name});
+        let (status, _) = send(&app, "POST", "/documents", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let (status, list) = send(&app, "GET", "/folders?sort_by=name", None).await;
+    assert_eq!(status, StatusCode::OK, "{list}");
+    assert_eq!(
+        list,
+        json!([
+            {"id": archive["id"], "name": "archive", "children_count": 0, "summary": "archive (page)"},
+            {"id": inbox["id"], "name": "inbox", "children_count": 2, "summary": "inbox (page)"},
+        ])
+    );
+    assert_eq!(COMPUTED_MANY_CALLS.load(Ordering::SeqCst), 1);
+
+    let (status, one) = send(
+        &app,
+        "GET",
+        &format!("/folders/{}", inbox["id"].as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{one}");
+    assert_eq!(one, json!({"id": inbox["id"], "name": "inbox"}));
+
+    let body = json!({"name": "in"});
+    let uri = format!("/folders/{}", inbox["id"].as_str().unwrap());
+    let (status, updated) = send(&app, "PUT", &uri, Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{updated}");
+    assert_eq!(updated, json!({"id": inbox["id"], "name": "in"}));
+    assert_eq!(COMPUTED_CALLS.load(Ordering::SeqCst), 0);
+}
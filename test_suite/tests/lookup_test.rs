@@ -0,0 +1,144 @@
+// Tests for `#[crudcrate(lookup)]`
+// Verifies that `GET /by/{field}/{value}` finds rows by their alternate keys, by field
+// or renamed name, rejects fields that aren't lookup fields, and only matches the
+// version of a temporal resource valid now.
+
+use axum::Router;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod customer {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "customers")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Customer",
+        name_singular = "customer",
+        name_plural = "customers"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        #[sea_orm(unique)]
+        #[crudcrate(lookup)]
+        pub email: String,
+
+        #[sea_orm(unique)]
+        #[crudcrate(lookup, rename = "accountNumber")]
+        pub account_number: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod plan {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "plans")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Plan",
+        name_singular = "plan",
+        name_plural = "plans",
+        temporal
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(lookup, lookup_not_unique)]
+        pub code: String,
+
+        pub price: i32,
+
+        pub valid_from: DateTime<Utc>,
+
+        pub valid_to: Option<DateTime<Utc>>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use customer::Customer;
+use plan::Plan;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(customer::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(plan::Entity)))
+        .await
+        .unwrap();
+    Router::new()
+        .nest("/customers", Customer::router(&db).into())
+        .nest("/plans", Plan::router(&db).into())
+}
+
+async fn create(app: &Router, name: &str, email: &str, account: &str) -> serde_json::Value {
+    let body = json!({"name": name, "email": email, "accountNumber": account});
+    let (status, customer) = send(app, "POST", "/customers", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{customer}");
+    customer
+}
+
+#[tokio::test]
+async fn test_get_by_lookup_fields() {
+    let app = setup().await;
+    create(&app, "Ada", "ada@example.com", "A-1").await;
+    let grace = create(&app, "Grace", "grace@example.com", "A-2").await;
+
+    let (status, found) = send(&app, "GET", "/customers/by/email/grace@example.com", None).await;
+    assert_eq!(status, StatusCode::OK, "{found}");
+    assert_eq!(found["id"], grace["id"]);
+    assert_eq!(found["name"], "Grace");
+
+    let (status, found) = send(&app, "GET", "/customers/by/accountNumber/A-2", None).await;
+    assert_eq!(status, StatusCode::OK, "{found}");
+    assert_eq!(found["id"], grace["id"]);
+
+    let (status, _) = send(&app, "GET", "/customers/by/email/GRACE@example.com", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, "GET", "/customers/by/name/Grace", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_lookup_matches_the_current_version() {
+    let app = setup().await;
+    let versions = [
+        json!({"code": "pro", "price": 10, "valid_from": "2020-01-01T00:00:00Z", "valid_to": "2024-01-01T00:00:00Z"}),
+        json!({"code": "pro", "price": 12, "valid_from": "2024-01-01T00:00:00Z", "valid_to": null}),
+    ];
+    for version in versions {
+        let (status, plan) = send(&app, "POST", "/plans", Some(version)).await;
+        assert_eq!(status, StatusCode::CREATED, "{plan}");
+    }
+
+    let (status, found) = send(&app, "GET", "/plans/by/code/pro", None).await;
+    assert_eq!(status, StatusCode::OK, "{found}");
+    assert_eq!(found["price"], 12);
+}
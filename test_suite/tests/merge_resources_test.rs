@@ -0,0 +1,130 @@
+// Tests for `crudcrate::router::merge_resources`
+// Verifies that distinct prefixes are nested into one router with a combined OpenAPI
+// document, and that mounting a resource twice or two resources at one prefix returns
+// a descriptive RouteConflict instead of panicking.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::router::{RouteConflict, merge_resources};
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod tag {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tags")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Tag",
+        name_singular = "tag",
+        name_plural = "tags"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub label: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::Note;
+use tag::Tag;
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(tag::Entity)))
+        .await
+        .unwrap();
+    db
+}
+
+#[tokio::test]
+async fn test_distinct_prefixes_merge() {
+    let db = setup().await;
+    let api = merge_resources(&[("/notes", Note::router(&db)), ("/tags", Tag::router(&db))])
+        .expect("distinct prefixes don't conflict");
+    let (router, openapi) = api.split_for_parts();
+
+    let paths: Vec<&str> = openapi.paths.paths.keys().map(String::as_str).collect();
+    assert!(paths.contains(&"/notes/{id}"), "{paths:?}");
+    assert!(paths.contains(&"/tags/{id}"), "{paths:?}");
+
+    let app: Router = router;
+    let (status, body) = send(&app, "POST", "/tags", Some(json!({"label": "rust"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    let (status, body) = send(&app, "GET", "/notes", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+#[tokio::test]
+async fn test_resource_mounted_twice_conflicts() {
+    let db = setup().await;
+    let error = merge_resources(&[
+        ("/notes", Note::router(&db)),
+        ("/notes/", Note::router(&db)),
+    ])
+    .err()
+    .expect("the same routes are registered twice");
+    assert_eq!(error.first, "/notes");
+    assert_eq!(error.second, "/notes/");
+    assert!(error.method.is_some(), "{error:?}");
+    assert!(error.path.starts_with("/notes"), "{error:?}");
+    assert!(
+        error.to_string().starts_with("Route conflict: `"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn test_shared_prefix_conflicts() {
+    let db = setup().await;
+    let error: RouteConflict =
+        merge_resources(&[("/items", Note::router(&db)), ("/items", Tag::router(&db))])
+            .err()
+            .expect("both resources serve /items/{id}");
+    assert_eq!(
+        (error.first.as_str(), error.second.as_str()),
+        ("/items", "/items")
+    );
+}
@@ -0,0 +1,263 @@
+// Tests for `crudcrate::batch::BatchRouter`
+// Verifies that `POST /batch` applies ordered creates, updates and deletes across
+// resources in one transaction, rolls every operation back when one fails and names its
+// index, rejects unknown resources, oversized batches and scoped requests, and refuses
+// to register resources with write hooks or a `view_sql` query.
+
+use axum::http::StatusCode;
+use axum::{Extension, Router};
+use crudcrate::batch::{BatchResourceError, BatchRouter};
+use crudcrate::testing::send;
+use crudcrate::{ApiError, EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(generate_router, api_struct = "Note")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        #[crudcrate(on_create = false)]
+        pub done: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod label {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "labels")]
+    #[crudcrate(generate_router, api_struct = "Label")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod journal {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "journals")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Journal",
+        create::one::pre = super::check_journal
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub entry: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod note_count {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "note_counts")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "NoteCount",
+        name_singular = "note_count",
+        name_plural = "note_counts",
+        view_sql = "SELECT done AS id, COUNT(*) AS notes FROM notes GROUP BY done"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update))]
+        pub id: Uuid,
+        pub notes: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use journal::{Journal, JournalCreate};
+use label::Label;
+use note::Note;
+use note_count::NoteCount;
+
+#[allow(clippy::unused_async)]
+async fn check_journal(_db: &DatabaseConnection, _data: &JournalCreate) -> Result<(), ApiError> {
+    Ok(())
+}
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(label::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/notes", Note::router(&db).into())
+        .nest("/labels", Label::router(&db).into())
+        .merge(
+            BatchRouter::new()
+                .resource::<Note>()
+                .unwrap()
+                .resource::<Label>()
+                .unwrap()
+                .limit(5)
+                .router(&db),
+        );
+    (db, app)
+}
+
+async fn list(app: &Router, path: &str) -> Vec<Value> {
+    let (status, body) = send(app, "GET", path, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body.as_array().unwrap().clone()
+}
+
+#[tokio::test]
+async fn test_applies_operations_in_order() {
+    let (_, app) = setup().await;
+    let (_, label) = send(&app, "POST", "/labels", Some(json!({"name": "home"}))).await;
+    let (_, note) = send(&app, "POST", "/notes", Some(json!({"title": "Draft"}))).await;
+
+    let operations = json!([
+        {"op": "create", "resource": "notes", "body": {"title": "Buy milk"}},
+        {"op": "update", "resource": "notes", "id": note["id"], "body": {"done": true}},
+        {"op": "delete", "resource": "labels", "id": label["id"]},
+        {"op": "create", "resource": "labels", "body": {"name": "work"}}
+    ]);
+    let (status, body) = send(&app, "POST", "/batch", Some(operations)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let statuses: Vec<u64> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["status"].as_u64().unwrap())
+        .collect();
+    assert_eq!(statuses, [201, 200, 204, 201]);
+    assert_eq!(body[0]["body"]["title"], "Buy milk");
+    assert_eq!(body[0]["body"]["done"], false);
+    assert_eq!(body[1]["body"]["done"], true);
+    assert!(body[2].get("body").is_none(), "{body}");
+
+    assert_eq!(list(&app, "/notes").await.len(), 2);
+    let labels = list(&app, "/labels").await;
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0]["name"], "work");
+}
+
+#[tokio::test]
+async fn test_failure_rolls_back_the_batch() {
+    let (_, app) = setup().await;
+    let operations = json!([
+        {"op": "create", "resource": "notes", "body": {"title": "Buy milk"}},
+        {"op": "create", "resource": "labels", "body": {"name": "home"}},
+        {"op": "delete", "resource": "notes", "id": Uuid::new_v4()}
+    ]);
+    let (status, body) = send(&app, "POST", "/batch", Some(operations)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{body}");
+    assert_eq!(body["index"], 2);
+
+    assert!(list(&app, "/notes").await.is_empty());
+    assert!(list(&app, "/labels").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_rejects_invalid_batches() {
+    let (_, app) = setup().await;
+    for (operations, index) in [
+        (
+            json!([{"op": "create", "resource": "projects", "body": {}}]),
+            Some(0),
+        ),
+        (
+            json!([
+                {"op": "create", "resource": "labels", "body": {"name": "home"}},
+                {"op": "create", "resource": "notes", "body": {"name": "home"}}
+            ]),
+            Some(1),
+        ),
+        (
+            json!(vec![
+                json!({"op": "create", "resource": "labels", "body": {"name": "x"}});
+                6
+            ]),
+            None,
+        ),
+    ] {
+        let (status, body) = send(&app, "POST", "/batch", Some(operations)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+        if let Some(index) = index {
+            assert_eq!(body["index"], index, "{body}");
+        }
+    }
+    assert!(list(&app, "/labels").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_scoped_requests_are_refused() {
+    let (db, _) = setup().await;
+    let app = BatchRouter::new()
+        .resource::<Note>()
+        .unwrap()
+        .router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all().add(note::Column::Done.eq(false)),
+        }));
+    let operations = json!([{"op": "create", "resource": "notes", "body": {"title": "Buy milk"}}]);
+    let (status, body) = send(&app, "POST", "/batch", Some(operations)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{body}");
+}
+
+#[test]
+fn test_hooked_and_view_resources_are_refused() {
+    let error = BatchRouter::new().resource::<Journal>().err().unwrap();
+    assert_eq!(
+        error,
+        BatchResourceError::CustomWrites {
+            resource: "journals"
+        }
+    );
+    assert!(error.to_string().contains("hooks"), "{error}");
+
+    let error = BatchRouter::new().resource::<NoteCount>().err().unwrap();
+    assert_eq!(
+        error,
+        BatchResourceError::View {
+            resource: "note_counts"
+        }
+    );
+}
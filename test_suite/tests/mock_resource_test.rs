@@ -0,0 +1,192 @@
+// Tests for the in-memory MockResource from `crudcrate::testing`
+// Verifies that generated handlers mounted with `router_with_ops` read and write the
+// in-memory store, and that hooks of wrapped operations run without a database.
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use crudcrate::testing::{MockResource, mock_connection, send};
+use crudcrate::{ApiError, CRUDOperations, EntityToModels};
+use sea_orm::DatabaseConnection;
+use sea_orm::entity::prelude::*;
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod task {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tasks")]
+    #[crudcrate(generate_router, api_struct = "Task")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub done: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use task::{Task, TaskCreate};
+
+/// Rejects blank titles and refuses deletes
+struct GuardedOps;
+
+#[async_trait]
+impl CRUDOperations for GuardedOps {
+    type Resource = Task;
+
+    async fn before_create(
+        &self,
+        _db: &DatabaseConnection,
+        data: &TaskCreate,
+    ) -> Result<(), ApiError> {
+        if data.title.trim().is_empty() {
+            return Err(ApiError::bad_request("Title must not be blank"));
+        }
+        Ok(())
+    }
+
+    async fn before_delete(&self, _db: &DatabaseConnection, _id: Uuid) -> Result<(), ApiError> {
+        Err(ApiError::forbidden("Tasks cannot be deleted"))
+    }
+}
+
+fn seeded() -> (MockResource<Task>, Uuid) {
+    let id = Uuid::new_v4();
+    let store = MockResource::<Task>::new()
+        .with_items([task::Model {
+            id,
+            title: "Existing".into(),
+            done: false,
+        }])
+        .unwrap();
+    (store, id)
+}
+
+fn app<O>(store: &MockResource<Task, O>) -> axum::Router
+where
+    MockResource<Task, O>: CRUDOperations<Resource = Task> + 'static,
+{
+    axum::Router::new().nest(
+        "/tasks",
+        Task::router_with_ops(&mock_connection(), store.clone()).into(),
+    )
+}
+
+#[tokio::test]
+async fn test_get_one_and_list_read_the_store() {
+    let (store, id) = seeded();
+    let app = app(&store);
+
+    let (status, body) = send(&app, "GET", &format!("/tasks/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["title"], "Existing");
+
+    let (status, body) = send(&app, "GET", "/tasks", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let (status, _) = send(&app, "GET", &format!("/tasks/{}", Uuid::new_v4()), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_create_update_delete_write_the_store() {
+    let (store, id) = seeded();
+    let app = app(&store);
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/tasks",
+        Some(json!({"title": "New", "done": false})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["title"], "New");
+    assert_eq!(store.len(), 2);
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/tasks/{id}"),
+        Some(json!({"title": "Existing", "done": true})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["done"], true);
+    assert!(store.get(id).unwrap().done);
+
+    let (status, _) = send(&app, "DELETE", &format!("/tasks/{id}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(store.get(id).is_none());
+    assert_eq!(
+        store
+            .items()
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect::<Vec<_>>(),
+        vec!["New"]
+    );
+}
+
+#[tokio::test]
+async fn test_hooks_of_wrapped_operations_run() {
+    let (store, id) = seeded();
+    let store = store.with_operations(GuardedOps);
+    let app = app(&store);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/tasks",
+        Some(json!({"title": "  ", "done": false})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(store.len(), 1);
+
+    let (status, _) = send(&app, "DELETE", &format!("/tasks/{id}"), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(store.get(id).is_some());
+}
+
+#[tokio::test]
+async fn test_list_uses_insertion_order_and_pagination() {
+    let (store, _) = seeded();
+    let app = app(&store);
+    send(
+        &app,
+        "POST",
+        "/tasks",
+        Some(json!({"title": "Second", "done": false})),
+    )
+    .await;
+
+    let titles = |body: &serde_json::Value| -> Vec<String> {
+        body.as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["title"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let (_, body) = send(&app, "GET", "/tasks", None).await;
+    assert_eq!(titles(&body), vec!["Existing", "Second"]);
+
+    let sort = url_escape::encode_component(r#"["title","DESC"]"#).to_string();
+    let (_, body) = send(&app, "GET", &format!("/tasks?sort={sort}"), None).await;
+    assert_eq!(titles(&body), vec!["Second", "Existing"]);
+
+    let (_, body) = send(&app, "GET", "/tasks?page=1&per_page=1", None).await;
+    assert_eq!(titles(&body).len(), 1);
+}
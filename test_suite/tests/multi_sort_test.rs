@@ -0,0 +1,150 @@
+// Tests for multi-column sorting
+// Verifies the JSON pair and comma-suffix forms of `sort`, that columns are checked
+// against the sortable list, and that single-column sorting is unchanged.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod ticket {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "tickets")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Ticket",
+        name_singular = "ticket",
+        name_plural = "tickets",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub title: String,
+
+        #[crudcrate(sortable, filterable)]
+        pub priority: i32,
+
+        #[crudcrate(sortable)]
+        pub opened: i32,
+
+        pub notes: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use ticket::Ticket;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(ticket::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/tickets", Ticket::router(&db).into());
+
+    for (title, priority, opened) in [("a", 1, 3), ("b", 2, 2), ("c", 2, 1), ("d", 1, 1)] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/tickets",
+            Some(json!({"title": title, "priority": priority, "opened": opened, "notes": ""})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    app
+}
+
+async fn titles(app: &Router, sort: &str) -> Vec<String> {
+    let sort = url_escape::encode_component(sort);
+    let (status, body) = send(app, "GET", &format!("/tickets?sort={sort}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|ticket| ticket["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_json_pairs() {
+    let app = setup().await;
+    assert_eq!(
+        titles(&app, r#"[["priority","DESC"],["opened","ASC"]]"#).await,
+        ["c", "b", "d", "a"]
+    );
+    assert_eq!(
+        titles(&app, r#"[["priority","ASC"],["opened","DESC"]]"#).await,
+        ["a", "d", "b", "c"]
+    );
+}
+
+#[tokio::test]
+async fn test_comma_suffixes() {
+    let app = setup().await;
+    assert_eq!(
+        titles(&app, "priority_desc,opened_asc").await,
+        ["c", "b", "d", "a"]
+    );
+    // No suffix means ascending
+    assert_eq!(
+        titles(&app, "opened,title_desc").await,
+        ["d", "c", "b", "a"]
+    );
+}
+
+#[tokio::test]
+async fn test_single_column_unchanged() {
+    let app = setup().await;
+    assert_eq!(
+        titles(&app, r#"["title","DESC"]"#).await,
+        ["d", "c", "b", "a"]
+    );
+    // Unknown single columns still fall back to the default sort
+    let sort = url_escape::encode_component(r#"["notes","DESC"]"#);
+    let (status, _) = send(&app, "GET", &format!("/tickets?sort={sort}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rejects_unsortable_columns() {
+    let app = setup().await;
+    for sort in [
+        r#"[["priority","DESC"],["notes","ASC"]]"#,
+        "priority_desc,missing",
+        "priority,opened,title,priority,opened,title,priority,opened,title",
+    ] {
+        let encoded = url_escape::encode_component(sort);
+        let (status, body): (StatusCode, Value) =
+            send(&app, "GET", &format!("/tickets?sort={encoded}"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{sort}: {body}");
+    }
+}
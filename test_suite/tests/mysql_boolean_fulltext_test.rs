@@ -0,0 +1,101 @@
+// Tests for fulltext(mode = "boolean")
+// Verifies boolean-mode fields are exposed via boolean_fulltext_columns(), that MySQL
+// matches them with MATCH ... AGAINST IN BOOLEAN MODE (OR'd with LIKE over the other
+// fulltext fields), that the FULLTEXT index covers exactly those columns, and that
+// other backends treat them as ordinary fulltext fields.
+
+use crudcrate::database::{IndexKind, plan_indexes};
+use crudcrate::filtering::build_fulltext_condition;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::DatabaseBackend;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+pub mod post {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "posts")]
+    #[crudcrate(generate_router, api_struct = "Post")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, fulltext(mode = "boolean"))]
+        pub title: String,
+
+        #[crudcrate(fulltext(mode = "boolean"))]
+        pub body: String,
+
+        #[crudcrate(fulltext)]
+        pub author: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use post::Post;
+
+#[test]
+fn test_boolean_columns() {
+    let boolean: Vec<_> = Post::boolean_fulltext_columns()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(boolean, vec!["title", "body"]);
+
+    let fulltext: Vec<_> = Post::fulltext_searchable_columns()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(fulltext, vec!["title", "body", "author"]);
+}
+
+#[test]
+fn test_mysql_uses_boolean_mode() {
+    let condition = build_fulltext_condition::<Post>("+rust -java (gc)", DatabaseBackend::MySql)
+        .expect("Non-empty query should produce a condition");
+    let sql = format!("{condition:?}");
+
+    assert!(
+        sql.contains("MATCH(`title`, `body`) AGAINST (? IN BOOLEAN MODE)"),
+        "got: {sql}"
+    );
+    assert!(
+        sql.contains("+rust -java gc"),
+        "query is rewritten and bound: {sql}"
+    );
+    assert!(
+        sql.contains("COALESCE(CAST(author AS CHAR), '')"),
+        "other fulltext fields still use LIKE: {sql}"
+    );
+    assert!(
+        !sql.contains("CAST(title AS CHAR)"),
+        "boolean fields are not part of the LIKE chain: {sql}"
+    );
+}
+
+#[test]
+fn test_other_backends_treat_boolean_as_fulltext() {
+    for backend in [DatabaseBackend::Sqlite, DatabaseBackend::Postgres] {
+        let condition = build_fulltext_condition::<Post>("rust", backend)
+            .expect("Non-empty query should produce a condition");
+        let sql = format!("{condition:?}");
+        assert!(!sql.contains("MATCH("), "{backend:?}: {sql}");
+        assert!(sql.contains("title"), "{backend:?}: {sql}");
+    }
+}
+
+#[test]
+fn test_mysql_fulltext_index_covers_boolean_columns() {
+    let fulltext: Vec<_> = plan_indexes::<Post>(DatabaseBackend::MySql)
+        .into_iter()
+        .filter(|index| index.kind == IndexKind::Fulltext)
+        .collect();
+    assert_eq!(fulltext.len(), 1);
+    assert_eq!(fulltext[0].columns, vec!["title", "body"]);
+}
@@ -0,0 +1,225 @@
+// Tests for `#[crudcrate(openapi = false)]`
+// Verifies that the generated models don't implement ToSchema, that the routers are
+// plain `axum::Router`s serving every CRUD endpoint, and that joins, `exclude(response)`
+// and scoped models work without the utoipa attributes.
+
+use axum::http::StatusCode;
+use axum::{Extension, Router};
+use crudcrate::testing::send;
+use crudcrate::{EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod member {
+    use super::*;
+
+    #[derive(
+        Clone, Debug, PartialEq, Eq, DeriveEntityModel, EntityToModels, Serialize, Deserialize,
+    )]
+    #[sea_orm(table_name = "members")]
+    #[crudcrate(
+        generate_router,
+        openapi = false,
+        api_struct = "Member",
+        name_singular = "member",
+        name_plural = "members",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub team_id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub email: String,
+
+        #[crudcrate(exclude(response))]
+        pub password_hash: String,
+
+        #[crudcrate(exclude(scoped))]
+        pub is_private: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::team::Entity",
+            from = "Column::TeamId",
+            to = "super::team::Column::Id"
+        )]
+        Team,
+    }
+
+    impl Related<super::team::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Team.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod team {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize)]
+    #[sea_orm(table_name = "teams")]
+    #[crudcrate(
+        generate_router,
+        openapi = false,
+        api_struct = "Team",
+        name_singular = "team",
+        name_plural = "teams",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, all, depth = 1))]
+        pub members: Vec<super::member::Member>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::member::Entity")]
+        Members,
+    }
+
+    impl Related<super::member::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Members.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use member::{Member, MemberCreate, MemberList, MemberResponse, MemberScopedList};
+use team::Team;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(team::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(member::Entity)))
+        .await
+        .unwrap();
+    let teams: Router = Team::router(&db);
+    let members: Router = Member::router(&db);
+    let app = Router::new()
+        .nest("/teams", teams)
+        .nest("/members", members);
+    (db, app)
+}
+
+async fn create_member(app: &Router, team_id: &str, email: &str, is_private: bool) -> String {
+    let (status, member) = send(
+        app,
+        "POST",
+        "/members",
+        Some(json!({
+            "team_id": team_id,
+            "email": email,
+            "password_hash": "secret",
+            "is_private": is_private
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{member}");
+    assert!(member.get("password_hash").is_none(), "{member}");
+    member["id"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_models_do_not_implement_to_schema() {
+    assert!(!crudcrate::impls!(Member: utoipa::ToSchema));
+    assert!(!crudcrate::impls!(MemberCreate: utoipa::ToSchema));
+    assert!(!crudcrate::impls!(MemberList: utoipa::ToSchema));
+    assert!(!crudcrate::impls!(MemberResponse: utoipa::ToSchema));
+    assert!(!crudcrate::impls!(MemberScopedList: utoipa::ToSchema));
+}
+
+#[tokio::test]
+async fn test_crud_endpoints() {
+    let (_, app) = setup().await;
+    let (status, team) = send(&app, "POST", "/teams", Some(json!({"name": "Core"}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let team_id = team["id"].as_str().unwrap();
+
+    let id = create_member(&app, team_id, "ada@example.com", false).await;
+
+    let (status, body) = send(&app, "GET", "/members", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/members/{id}"),
+        Some(json!({"email": "grace@example.com"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["email"], "grace@example.com");
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/members/batch",
+        Some(json!([
+            {"team_id": team_id, "email": "b@example.com", "password_hash": "x", "is_private": false},
+            {"team_id": team_id, "email": "c@example.com", "password_hash": "x", "is_private": true}
+        ])),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    // Joined members load on the parent
+    let (status, body) = send(&app, "GET", &format!("/teams/{team_id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["members"].as_array().unwrap().len(), 3);
+
+    let (status, _) = send(&app, "DELETE", &format!("/members/{id}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, "GET", &format!("/members/{id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_read_only_router_with_scope() {
+    let (db, app) = setup().await;
+    let (_, team) = send(&app, "POST", "/teams", Some(json!({"name": "Core"}))).await;
+    let team_id = team["id"].as_str().unwrap();
+    create_member(&app, team_id, "public@example.com", false).await;
+    create_member(&app, team_id, "private@example.com", true).await;
+
+    let public: Router = Member::read_only_router(&db).layer(Extension(ScopeCondition {
+        condition: Condition::all().add(member::Column::IsPrivate.eq(false)),
+    }));
+    let public = Router::new().nest("/members", public);
+
+    let (status, body) = send(&public, "GET", "/members", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let items = body.as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["email"], "public@example.com");
+    assert!(items[0].get("is_private").is_none());
+
+    let (status, _) = send(&public, "POST", "/members", Some(json!({}))).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+}
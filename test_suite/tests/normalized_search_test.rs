@@ -0,0 +1,140 @@
+// Tests for `#[crudcrate(normalized_search = "...")]`
+// Verifies that creates and updates store the folded text of the fulltext fields, that
+// `?q=` matches it regardless of accents and case in either the data or the query, and
+// that it combines with filters.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod person {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "people")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Person",
+        name_singular = "person",
+        name_plural = "people",
+        normalized_search = "search_text"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(fulltext, filterable)]
+        pub name: String,
+
+        #[crudcrate(fulltext)]
+        pub city: Option<String>,
+
+        #[crudcrate(exclude(create, update, one, list))]
+        pub search_text: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use person::Person;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let statement = Schema::new(backend).create_table_from_entity(person::Entity);
+    db.execute(backend.build(&statement)).await.unwrap();
+    let app = Router::new().nest("/people", Person::router(&db).into());
+
+    for (name, city) in [
+        ("Jürgen Müller", Some("Zürich")),
+        ("Hans Muller", None),
+        ("Zoë Ünal", Some("São Paulo")),
+    ] {
+        let body = json!({"name": name, "city": city});
+        let (status, body) = send(&app, "POST", "/people", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    (db, app)
+}
+
+async fn search(app: &Router, query: &str) -> Vec<String> {
+    let filter = json!({ "q": query }).to_string();
+    let uri = format!("/people?filter={}", url_escape::encode_component(&filter));
+    let (status, body) = send(app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut names: Vec<String> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+#[tokio::test]
+async fn test_accents_ignored_in_data_and_query() {
+    let (_db, app) = setup().await;
+
+    assert_eq!(
+        search(&app, "muller").await,
+        ["Hans Muller", "Jürgen Müller"]
+    );
+    assert_eq!(
+        search(&app, "MÜLLER").await,
+        ["Hans Muller", "Jürgen Müller"]
+    );
+    assert_eq!(search(&app, "zurich").await, ["Jürgen Müller"]);
+    assert_eq!(search(&app, "sao paulo").await, ["Zoë Ünal"]);
+    assert_eq!(search(&app, "ZOE").await, ["Zoë Ünal"]);
+    assert!(search(&app, "berlin").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_stored_text_follows_updates() {
+    let (db, app) = setup().await;
+
+    let stored = person::Entity::find().all(&db).await.unwrap();
+    let hans = stored.iter().find(|p| p.name == "Hans Muller").unwrap();
+    assert_eq!(hans.search_text, "hans muller");
+    let jurgen = stored.iter().find(|p| p.name == "Jürgen Müller").unwrap();
+    assert_eq!(jurgen.search_text, "jurgen muller zurich");
+
+    // Updating one fulltext field refolds with the stored value of the other
+    let uri = format!("/people/{}", hans.id);
+    let (status, body) = send(&app, "PUT", &uri, Some(json!({"city": "Köln"}))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body.get("search_text").is_none());
+    assert_eq!(search(&app, "koln").await, ["Hans Muller"]);
+
+    let (status, _) = send(&app, "PUT", &uri, Some(json!({"name": "Hans Meier"}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(search(&app, "muller").await, ["Jürgen Müller"]);
+    assert_eq!(search(&app, "meier koln").await, ["Hans Meier"]);
+}
+
+#[tokio::test]
+async fn test_combined_with_filters() {
+    let (_db, app) = setup().await;
+
+    let filter = json!({"q": "müller", "name": "Hans Muller"}).to_string();
+    let uri = format!("/people?filter={}", url_escape::encode_component(&filter));
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let names: Vec<&Value> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| &p["name"])
+        .collect();
+    assert_eq!(names, [&json!("Hans Muller")]);
+}
@@ -0,0 +1,283 @@
+// Tests for one-to-one joins in both directions
+// A user holds `Option<Profile>` (has_one, FK on the profile) and a profile holds
+// `Option<Box<User>>` (belongs_to). Verifies get-one and list loading from either side,
+// users without a profile, nested creation through `join(create)` (single and batch,
+// rolled back on invalid input), scoped list conversion, and the create model schema.
+
+use axum::http::StatusCode;
+use axum::{Extension, Router};
+use crudcrate::testing::send;
+use crudcrate::{EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod user {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "users")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "User",
+        name_singular = "user",
+        name_plural = "users",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub name: String,
+
+        #[crudcrate(filterable)]
+        pub active: bool,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, all, depth = 1, create))]
+        pub profile: Option<super::profile::Profile>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_one = "super::profile::Entity")]
+        Profile,
+    }
+
+    impl Related<super::profile::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Profile.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod profile {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "profiles")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Profile",
+        name_singular = "profile",
+        name_plural = "profiles",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[sea_orm(unique)]
+        #[crudcrate(filterable)]
+        pub user_id: Uuid,
+
+        pub bio: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, exclude(create, update), join(one, all, depth = 1))]
+        pub user: Option<Box<super::user::User>>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::user::Entity",
+            from = "Column::UserId",
+            to = "super::user::Column::Id"
+        )]
+        User,
+    }
+
+    impl Related<super::user::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::User.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use profile::Profile;
+use user::User;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(user::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(profile::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/users", User::router(&db).into())
+        .nest("/profiles", Profile::router(&db).into());
+    (db, app)
+}
+
+/// Creates a user, with a nested profile when `bio` is given
+async fn create_user(app: &Router, name: &str, bio: Option<&str>) -> Value {
+    let mut body = json!({"name": name, "active": true});
+    if let Some(bio) = bio {
+        body["profile"] = json!({"bio": bio});
+    }
+    let (status, user) = send(app, "POST", "/users", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{user}");
+    user
+}
+
+async fn count(app: &Router, uri: &str) -> usize {
+    let (status, body) = send(app, "GET", uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body.as_array().unwrap().len()
+}
+
+#[tokio::test]
+async fn test_create_with_nested_profile() {
+    let (_, app) = setup().await;
+
+    let user = create_user(&app, "ada", Some("Mathematician")).await;
+    assert_eq!(user["profile"]["bio"], "Mathematician", "{user}");
+    assert_eq!(user["profile"]["user_id"], user["id"], "{user}");
+
+    let user = create_user(&app, "alan", None).await;
+    assert!(user["profile"].is_null(), "{user}");
+    assert_eq!(count(&app, "/profiles").await, 1);
+}
+
+#[tokio::test]
+async fn test_get_one_loads_both_directions() {
+    let (_, app) = setup().await;
+    let user = create_user(&app, "ada", Some("Mathematician")).await;
+    let user_id = user["id"].as_str().unwrap();
+    let profile_id = user["profile"]["id"].as_str().unwrap();
+
+    let (status, body) = send(&app, "GET", &format!("/users/{user_id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["profile"]["id"], profile_id, "{body}");
+
+    let (status, body) = send(&app, "GET", &format!("/profiles/{profile_id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["user"]["id"], user_id, "{body}");
+    assert_eq!(body["user"]["name"], "ada", "{body}");
+}
+
+#[tokio::test]
+async fn test_list_loads_both_directions() {
+    let (_, app) = setup().await;
+    let ada = create_user(&app, "ada", Some("Mathematician")).await;
+    let grace = create_user(&app, "grace", Some("Admiral")).await;
+    create_user(&app, "alan", None).await;
+
+    let (status, users) = send(&app, "GET", "/users?sort_by=name&order=ASC", None).await;
+    assert_eq!(status, StatusCode::OK, "{users}");
+    let bios: Vec<&Value> = users
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| &user["profile"]["bio"])
+        .collect();
+    assert_eq!(
+        bios,
+        [&json!("Mathematician"), &Value::Null, &json!("Admiral")]
+    );
+
+    let (status, profiles) = send(&app, "GET", "/profiles", None).await;
+    assert_eq!(status, StatusCode::OK, "{profiles}");
+    for profile in profiles.as_array().unwrap() {
+        let expected = if profile["bio"] == "Mathematician" {
+            &ada
+        } else {
+            &grace
+        };
+        assert_eq!(profile["user"]["id"], expected["id"], "{profile}");
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_nested_profile_creates_nothing() {
+    let (_, app) = setup().await;
+
+    for profile in [json!("not an object"), json!({"unknown": true})] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/users",
+            Some(json!({"name": "ada", "active": true, "profile": profile})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+        assert!(
+            body["error"].as_str().unwrap().contains("profile"),
+            "{body}"
+        );
+    }
+    assert_eq!(count(&app, "/users").await, 0);
+    assert_eq!(count(&app, "/profiles").await, 0);
+}
+
+#[tokio::test]
+async fn test_batch_create_with_nested_profiles() {
+    let (_, app) = setup().await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/users/batch",
+        Some(json!([
+            {"name": "ada", "active": true, "profile": {"bio": "Mathematician"}},
+            {"name": "alan", "active": true},
+        ])),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    let (_, profiles) = send(&app, "GET", "/profiles", None).await;
+    let profiles = profiles.as_array().unwrap();
+    assert_eq!(profiles.len(), 1);
+    assert_eq!(profiles[0]["user"]["name"], "ada");
+}
+
+#[tokio::test]
+async fn test_scoped_list_keeps_one_to_one_joins() {
+    let (db, app) = setup().await;
+    create_user(&app, "ada", Some("Mathematician")).await;
+
+    let public: Router = User::read_only_router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all().add(user::Column::Active.eq(true)),
+        }))
+        .into();
+    let public = Router::new().nest("/users", public);
+
+    let (status, users) = send(&public, "GET", "/users", None).await;
+    assert_eq!(status, StatusCode::OK, "{users}");
+    assert_eq!(users[0]["profile"]["bio"], "Mathematician", "{users}");
+
+    let id = users[0]["id"].as_str().unwrap();
+    let (status, user) = send(&public, "GET", &format!("/users/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{user}");
+    assert_eq!(user["profile"]["bio"], "Mathematician", "{user}");
+}
+
+#[tokio::test]
+async fn test_openapi_documents_nested_create() {
+    let (db, _) = setup().await;
+    let (_, openapi) = User::router(&db).split_for_parts();
+    let openapi = serde_json::to_value(openapi).unwrap();
+    let create = &openapi["components"]["schemas"]["UserCreate"];
+    assert!(create["properties"].get("profile").is_some(), "{create}");
+
+    let create =
+        serde_json::to_value(<profile::ProfileCreate as utoipa::PartialSchema>::schema()).unwrap();
+    assert!(create["properties"].get("user").is_none(), "{create}");
+}
@@ -0,0 +1,182 @@
+// Tests for `crudcrate::openapi`
+// Verifies that `document` nests the routers, tags operations by resource and sets the
+// info, servers and security, and that `json_schema`/`json_schemas` export standalone
+// JSON Schemas with their references under `$defs`.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::openapi::{ApiInfo, document, json_schema, json_schemas};
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod album {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "albums")]
+    #[crudcrate(generate_router, api_struct = "Album", description = "Released albums")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        pub title: String,
+        pub year: Option<i32>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod artist {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "artists")]
+    #[crudcrate(generate_router, api_struct = "Artist")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use album::{Album, AlbumCreate};
+use artist::Artist;
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(album::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(artist::Entity)))
+        .await
+        .unwrap();
+    db
+}
+
+fn info() -> ApiInfo {
+    ApiInfo::new("Music API", "2.1.0")
+        .description("Albums and artists")
+        .server("https://api.example.com")
+        .security("bearer")
+}
+
+#[tokio::test]
+async fn test_document_sets_info_servers_and_security() {
+    let db = setup().await;
+    let (router, openapi) = document(
+        &info(),
+        &[
+            ("/albums", Album::router(&db)),
+            ("/artists", Artist::router(&db)),
+        ],
+    )
+    .expect("distinct prefixes don't conflict");
+    let doc = serde_json::to_value(&openapi).unwrap();
+
+    assert!(doc["openapi"].as_str().unwrap().starts_with("3.1"), "{doc}");
+    assert_eq!(doc["info"]["title"], "Music API");
+    assert_eq!(doc["info"]["version"], "2.1.0");
+    assert_eq!(doc["info"]["description"], "Albums and artists");
+    assert_eq!(doc["servers"], json!([{"url": "https://api.example.com"}]));
+    assert_eq!(
+        doc["paths"]["/albums/{id}"]["get"]["security"],
+        json!([{"bearer": []}])
+    );
+    assert!(
+        doc["components"]["securitySchemes"]["bearer"].is_object(),
+        "{doc}"
+    );
+
+    let app: Router = router;
+    let (status, body) = send(&app, "POST", "/albums", Some(json!({"title": "Blue"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    let (status, body) = send(&app, "GET", "/artists", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+#[tokio::test]
+async fn test_document_tags_operations_by_resource() {
+    let db = setup().await;
+    let (_, openapi) = document(
+        &ApiInfo::new("Music API", "1.0.0"),
+        &[
+            ("/albums", Album::router(&db)),
+            ("/artists", Artist::router(&db)),
+        ],
+    )
+    .unwrap();
+    let doc = serde_json::to_value(&openapi).unwrap();
+
+    assert_eq!(doc["paths"]["/albums"]["post"]["tags"], json!(["albums"]));
+    assert_eq!(
+        doc["paths"]["/artists/{id}"]["get"]["tags"],
+        json!(["artists"])
+    );
+    assert_eq!(
+        doc["tags"][0],
+        json!({"name": "albums", "description": "Released albums"})
+    );
+    assert_eq!(doc["tags"][1]["name"], "artists");
+    assert!(doc.get("servers").is_none(), "{doc}");
+}
+
+#[tokio::test]
+async fn test_document_reports_route_conflicts() {
+    let db = setup().await;
+    let error = document(
+        &info(),
+        &[
+            ("/albums", Album::router(&db)),
+            ("/albums/", Album::router(&db)),
+        ],
+    )
+    .err()
+    .expect("the same routes are registered twice");
+    assert_eq!(error.first, "/albums");
+    assert_eq!(error.second, "/albums/");
+}
+
+#[test]
+fn test_json_schema_of_model() {
+    let schema = json_schema::<AlbumCreate>();
+    assert_eq!(
+        schema["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(schema["title"], "AlbumCreate");
+    assert_eq!(schema["type"], "object");
+    assert!(schema["properties"]["title"].is_object(), "{schema}");
+    assert!(schema["properties"].get("id").is_none(), "{schema}");
+    assert_eq!(schema["required"], json!(["title"]));
+}
+
+#[tokio::test]
+async fn test_json_schemas_of_document() {
+    let db = setup().await;
+    let (_, openapi) = document(&info(), &[("/albums", Album::router(&db))]).unwrap();
+    let schemas = json_schemas(&openapi);
+
+    for name in ["AlbumResponse", "AlbumCreate", "AlbumUpdate", "AlbumList"] {
+        let schema = schemas
+            .get(name)
+            .unwrap_or_else(|| panic!("{name} missing from {:?}", schemas.keys()));
+        assert_eq!(schema["title"], name, "{schema}");
+    }
+    let text = serde_json::to_string(&schemas).unwrap();
+    assert!(!text.contains("#/components/schemas/"), "{text}");
+}
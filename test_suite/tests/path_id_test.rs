@@ -0,0 +1,100 @@
+// Tests for the `{id}` path segment of the generated routes
+// Verifies that an ID that isn't a UUID is answered with a JSON 400 naming the resource
+// and the expected format on every item route, and that valid IDs still reach the handlers.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes",
+        tree(parent_field = "parent_id")
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        pub text: String,
+        #[sea_orm(column_type = "Text")]
+        #[crudcrate(large_text)]
+        pub body: String,
+        pub parent_id: Option<Uuid>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::Note;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    Router::new().nest("/notes", Note::router(&db).into())
+}
+
+#[tokio::test]
+async fn test_invalid_id_names_resource_and_format() {
+    let app = setup().await;
+    let update = json!({"text": "changed"});
+    for (method, uri, body) in [
+        ("GET", "/notes/42", None),
+        ("PUT", "/notes/42", Some(update)),
+        ("DELETE", "/notes/42", None),
+        ("GET", "/notes/42/children", None),
+        ("GET", "/notes/42/ancestors", None),
+        ("GET", "/notes/42/body", None),
+    ] {
+        let (status, body) = send(&app, method, uri, body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{method} {uri}: {body}");
+        assert_eq!(
+            body["error"],
+            "Invalid note id '42': expected a UUID such as 550e8400-e29b-41d4-a716-446655440000",
+            "{method} {uri}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_valid_ids_reach_the_handlers() {
+    let app = setup().await;
+    let (status, body) = send(&app, "GET", &format!("/notes/{}", Uuid::new_v4()), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{body}");
+
+    let note = json!({"text": "root", "body": "long", "parent_id": null});
+    let (status, body) = send(&app, "POST", "/notes", Some(note)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    let id = body["id"].as_str().unwrap();
+    let (status, body) = send(&app, "GET", &format!("/notes/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let (status, body) = send(&app, "GET", &format!("/notes/{id}/children"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+#[test]
+fn test_parse_id() {
+    let id = Uuid::new_v4();
+    let parsed = crudcrate::core::path_id::parse_id::<Note>(&id.to_string()).unwrap();
+    assert_eq!(parsed, id);
+    let error = crudcrate::core::path_id::parse_id::<Note>("abc").unwrap_err();
+    assert!(matches!(error, ApiError::BadRequest { .. }), "{error:?}");
+}
@@ -0,0 +1,282 @@
+// Tests for `#[crudcrate(polymorphic_join(types = [...]))]`
+// Verifies that a comment's (subject_type, subject_id) pair loads the customer or vehicle
+// it points at in get_one and get_all, that create and update reject unknown types and
+// missing targets, and that the loaded field isn't part of the write models.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod customer {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "customers")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Customer",
+        name_singular = "customer",
+        name_plural = "customers",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod vehicle {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "vehicles")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Vehicle",
+        name_singular = "vehicle",
+        name_plural = "vehicles",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub make: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod comment {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "comments")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Comment",
+        name_singular = "comment",
+        name_plural = "comments",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub body: String,
+
+        pub subject_type: String,
+
+        pub subject_id: Uuid,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(
+            non_db_attr,
+            polymorphic_join(types = ["super::customer::Customer", "super::vehicle::Vehicle"])
+        )]
+        pub subject: Option<serde_json::Value>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use comment::{Comment, CommentCreate, CommentUpdate};
+use customer::Customer;
+use vehicle::Vehicle;
+
+/// Customer "ada" and vehicle "Volvo", with ids
+async fn setup() -> (Router, Value, Value) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(customer::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(vehicle::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(comment::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/customers", Customer::router(&db).into())
+        .nest("/vehicles", Vehicle::router(&db).into())
+        .nest("/comments", Comment::router(&db).into());
+
+    let (_, ada) = send(&app, "POST", "/customers", Some(json!({"name": "ada"}))).await;
+    let (_, volvo) = send(&app, "POST", "/vehicles", Some(json!({"make": "Volvo"}))).await;
+    (app, ada["id"].clone(), volvo["id"].clone())
+}
+
+async fn create_comment(app: &Router, body: &str, subject_type: &str, subject_id: &Value) -> Value {
+    let (status, comment) = send(
+        app,
+        "POST",
+        "/comments",
+        Some(json!({"body": body, "subject_type": subject_type, "subject_id": subject_id})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{comment}");
+    comment
+}
+
+#[tokio::test]
+async fn test_get_one_loads_target() {
+    let (app, ada, volvo) = setup().await;
+    let on_customer = create_comment(&app, "nice", "Customer", &ada).await;
+    let on_vehicle = create_comment(&app, "fast", "Vehicle", &volvo).await;
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/comments/{}", on_customer["id"].as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["subject"]["name"], "ada");
+
+    let (_, body) = send(
+        &app,
+        "GET",
+        &format!("/comments/{}", on_vehicle["id"].as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(body["subject"]["make"], "Volvo");
+}
+
+#[tokio::test]
+async fn test_get_all_loads_each_type() {
+    let (app, ada, volvo) = setup().await;
+    create_comment(&app, "a", "Customer", &ada).await;
+    create_comment(&app, "b", "Vehicle", &volvo).await;
+    create_comment(&app, "c", "Customer", &ada).await;
+
+    let (status, body) = send(&app, "GET", "/comments?sort_by=body&order=ASC", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let comments = body.as_array().unwrap();
+    assert_eq!(comments.len(), 3);
+    assert_eq!(comments[0]["subject"]["id"], ada);
+    assert_eq!(comments[1]["subject"]["make"], "Volvo");
+    assert_eq!(comments[2]["subject"]["name"], "ada");
+}
+
+#[tokio::test]
+async fn test_create_rejects_invalid_pairs() {
+    let (app, ada, _) = setup().await;
+
+    for (subject_type, subject_id) in [
+        ("Invoice", ada.clone()),
+        ("Customer", json!(Uuid::new_v4())),
+        ("Vehicle", ada.clone()),
+    ] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/comments",
+            Some(json!({"body": "x", "subject_type": subject_type, "subject_id": subject_id})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{subject_type}: {body}");
+    }
+
+    let (_, body) = send(&app, "GET", "/comments", None).await;
+    assert!(body.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_update_checks_changed_pairs() {
+    let (app, ada, volvo) = setup().await;
+    let comment = create_comment(&app, "nice", "Customer", &ada).await;
+    let uri = format!("/comments/{}", comment["id"].as_str().unwrap());
+
+    // Untouched pair isn't rechecked
+    let (status, body) = send(&app, "PUT", &uri, Some(json!({"body": "nicer"}))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &uri,
+        Some(json!({"subject_type": "Vehicle", "subject_id": volvo})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let (_, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(body["subject"]["make"], "Volvo");
+
+    // Changing only the type leaves the vehicle ID, which isn't a customer
+    let (status, body) = send(&app, "PUT", &uri, Some(json!({"subject_type": "Customer"}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+
+    let (_, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(body["subject_type"], "Vehicle");
+}
+
+#[test]
+fn test_subject_not_in_write_models() {
+    for schema in [
+        serde_json::to_value(<CommentCreate as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<CommentUpdate as utoipa::PartialSchema>::schema()).unwrap(),
+    ] {
+        assert!(schema["properties"].get("subject").is_none(), "{schema}");
+    }
+}
@@ -0,0 +1,130 @@
+// Tests for `::post_async` hooks
+// Verifies that create, update and delete hand their `post_async` hook to the installed
+// `JobQueue` instead of awaiting it, with owned copies of the result (and the change set
+// for updates), and that the queued job runs the hook.
+// Hooks must be async to match their signatures, even when they never await
+#![allow(clippy::unused_async)]
+
+use crudcrate::jobs::{Job, JobQueue};
+use crudcrate::{ApiError, CRUDResource, ChangeSet, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// What the hooks saw, in the order they ran
+static SEEN: Mutex<Vec<String>> = Mutex::new(Vec::new());
+/// Jobs handed to `HeldQueue`, not yet run
+static HELD: Mutex<Vec<Job>> = Mutex::new(Vec::new());
+
+/// Keeps jobs until the test runs them
+struct HeldQueue;
+
+impl JobQueue for HeldQueue {
+    fn enqueue(&self, job: Job) {
+        HELD.lock().unwrap().push(job);
+    }
+}
+
+async fn welcome(db: DatabaseConnection, created: Member) -> Result<(), ApiError> {
+    // The connection is usable from the job
+    let count = Member::total_count(&db, &sea_orm::Condition::all()).await;
+    SEEN.lock()
+        .unwrap()
+        .push(format!("created {} of {count}", created.name));
+    Ok(())
+}
+
+async fn announce_rename(
+    _db: DatabaseConnection,
+    updated: Member,
+    changes: ChangeSet,
+) -> Result<(), ApiError> {
+    let renamed = changes.get("name").is_some();
+    SEEN.lock()
+        .unwrap()
+        .push(format!("updated {} renamed={renamed}", updated.name));
+    Ok(())
+}
+
+async fn farewell(_db: DatabaseConnection, ids: Vec<Uuid>) -> Result<(), ApiError> {
+    SEEN.lock().unwrap().push(format!("deleted {}", ids.len()));
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "members")]
+#[crudcrate(
+    api_struct = "Member",
+    create::one::post_async = welcome,
+    update::one::post_async = announce_rename,
+    delete::many::post_async = farewell,
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+
+    pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Run the held jobs and return what their hooks saw
+async fn run_held() -> Vec<String> {
+    let jobs: Vec<Job> = HELD.lock().unwrap().drain(..).collect();
+    for job in jobs {
+        job.run().await;
+    }
+    SEEN.lock().unwrap().drain(..).collect()
+}
+
+#[tokio::test]
+async fn test_post_async_hooks_are_queued_not_awaited() {
+    crudcrate::jobs::set_queue(HeldQueue);
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(Entity)))
+        .await
+        .unwrap();
+
+    let created = Member::create(
+        &db,
+        MemberCreate {
+            name: "Ada".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    {
+        let held = HELD.lock().unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].resource(), Member::RESOURCE_NAME_SINGULAR);
+        assert_eq!(held[0].operation(), "create");
+    }
+    assert!(SEEN.lock().unwrap().is_empty(), "hook waits for the queue");
+    assert_eq!(run_held().await, ["created Ada of 1"]);
+
+    Member::update(
+        &db,
+        created.id,
+        MemberUpdate {
+            name: Some(Some("Ada L.".to_string())),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(run_held().await, ["updated Ada L. renamed=true"]);
+
+    Member::delete_many(&db, vec![created.id, Uuid::new_v4()])
+        .await
+        .unwrap();
+    assert_eq!(run_held().await, ["deleted 1"]);
+
+    // Operations without a `post_async` hook queue nothing
+    Member::delete(&db, Uuid::new_v4()).await.unwrap_err();
+    assert!(HELD.lock().unwrap().is_empty());
+}
@@ -0,0 +1,144 @@
+// Tests for filters passed as plain query parameters (`crudcrate::filtering::params`)
+// Verifies that `?title_like=urgent&priority_gte=3` matches the same rows as the JSON
+// filter, that repeated parameters and `_between` build lists, that values are typed by
+// their column, and that keys in `filter` take precedence over query parameters.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod ticket {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tickets")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Ticket",
+        name_singular = "ticket",
+        name_plural = "tickets"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub status: String,
+
+        #[crudcrate(filterable)]
+        pub priority: i32,
+
+        #[crudcrate(filterable)]
+        pub done: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use ticket::Ticket;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let statement = Schema::new(backend).create_table_from_entity(ticket::Entity);
+    db.execute(backend.build(&statement)).await.unwrap();
+    let app = Router::new().nest("/tickets", Ticket::router(&db).into());
+
+    for (title, status, priority, done) in [
+        ("Urgent: fix login", "open", 5, false),
+        ("Not so urgent", "review", 2, false),
+        ("Urgent release", "closed", 4, true),
+        ("Update docs", "open", 1, false),
+    ] {
+        let body = json!({"title": title, "status": status, "priority": priority, "done": done});
+        let (status, body) = send(&app, "POST", "/tickets", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    app
+}
+
+/// Sorted titles listed by `GET /tickets?{query}`
+async fn titles(app: &Router, query: &str) -> Vec<String> {
+    let (status, body) = send(app, "GET", &format!("/tickets?{query}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut titles: Vec<String> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort_unstable();
+    titles
+}
+
+fn filter(value: &Value) -> String {
+    format!(
+        "filter={}",
+        url_escape::encode_component(&value.to_string())
+    )
+}
+
+#[tokio::test]
+async fn test_query_parameters_match_json_filter() {
+    let app = setup().await;
+
+    let simple = titles(&app, "title_like=urgent&priority_gte=3").await;
+    assert_eq!(simple, ["Urgent release", "Urgent: fix login"]);
+    let json = titles(
+        &app,
+        &filter(&json!({"title_like": "urgent", "priority_gte": 3})),
+    )
+    .await;
+    assert_eq!(simple, json);
+
+    assert_eq!(
+        titles(&app, "status=open&status=review&done=false").await,
+        ["Not so urgent", "Update docs", "Urgent: fix login"]
+    );
+    assert_eq!(
+        titles(
+            &app,
+            "priority_between=2,4&sort_by=title&page=1&per_page=10"
+        )
+        .await,
+        ["Not so urgent", "Urgent release"]
+    );
+}
+
+#[tokio::test]
+async fn test_filter_takes_precedence() {
+    let app = setup().await;
+
+    // `status` comes from `filter`, `priority_lt` from the query parameter
+    let query = format!(
+        "{}&status=closed&priority_lt=3",
+        filter(&json!({"status": "open"}))
+    );
+    assert_eq!(titles(&app, &query).await, ["Update docs"]);
+}
+
+#[tokio::test]
+async fn test_values_typed_by_column() {
+    let app = setup().await;
+
+    let (status, body) = send(&app, "GET", "/tickets?priority_gte=high", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    let (status, body) = send(&app, "GET", "/tickets?done=yes", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+
+    // Text columns take numeric-looking values as text, and unknown parameters are ignored
+    assert!(titles(&app, "status=5").await.is_empty());
+    assert_eq!(titles(&app, "cache_buster=1").await.len(), 4);
+}
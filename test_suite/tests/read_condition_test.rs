@@ -0,0 +1,558 @@
+// Tests for the `read::many::condition` hook
+// Verifies that the condition limits the list and its total, `GET /{id}`, `by-ids`,
+// distinct values, the tree endpoints, `GET /changes` and lazy relation endpoints,
+// combines with request filters, and leaves writes alone. Parents' joins, counts,
+// `load_{field}` loaders and `__exists`/`__count` filters don't see hidden related rows.
+
+use axum::Router;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod post {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "posts")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Post",
+        name_singular = "post",
+        name_plural = "posts",
+        read::many::condition = published_only
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub category: String,
+
+        pub published: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    fn published_only() -> Condition {
+        Condition::all().add(Column::Published.eq(true))
+    }
+}
+
+pub mod topic {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "topics")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Topic",
+        name_singular = "topic",
+        name_plural = "topics",
+        tree(parent_field = "parent_id"),
+        sync = "updated_at",
+        read::many::condition = listed_only
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub title: String,
+
+        pub parent_id: Option<Uuid>,
+
+        pub listed: bool,
+
+        #[crudcrate(exclude(create, update), on_create = Utc::now(), on_update = Utc::now())]
+        pub updated_at: DateTime<Utc>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    fn listed_only() -> Condition {
+        Condition::all().add(Column::Listed.eq(true))
+    }
+}
+
+pub mod shelf {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "shelves")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Shelf",
+        name_singular = "shelf",
+        name_plural = "shelves",
+        no_partial_eq,
+        read::many::condition = listed_only
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub listed: bool,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, lazy, depth = 1))]
+        pub volumes: Vec<super::volume::Volume>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::volume::Entity")]
+        Volumes,
+    }
+
+    impl Related<super::volume::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Volumes.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    fn listed_only() -> Condition {
+        Condition::all().add(Column::Listed.eq(true))
+    }
+}
+
+pub mod volume {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "volumes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Volume",
+        name_singular = "volume",
+        name_plural = "volumes",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub shelf_id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::shelf::Entity",
+            from = "Column::ShelfId",
+            to = "super::shelf::Column::Id"
+        )]
+        Shelf,
+    }
+
+    impl Related<super::shelf::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Shelf.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod author {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "authors")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Author",
+        name_singular = "author",
+        name_plural = "authors",
+        derive_partial_eq,
+        read::many::condition = active_only
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        pub active: bool,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, all, depth = 1))]
+        pub books: Vec<super::book::Book>,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, counted_relation = "Book")]
+        pub book_count: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::book::Entity")]
+        Book,
+    }
+
+    impl Related<super::book::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Book.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    fn active_only() -> Condition {
+        Condition::all().add(Column::Active.eq(true))
+    }
+}
+
+pub mod book {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "books")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Book",
+        name_singular = "book",
+        name_plural = "books",
+        derive_partial_eq,
+        read::many::condition = in_print_only
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub author_id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub title: String,
+
+        pub in_print: bool,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, all, depth = 1))]
+        pub author: Option<super::author::Author>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::author::Entity",
+            from = "Column::AuthorId",
+            to = "super::author::Column::Id"
+        )]
+        Author,
+    }
+
+    impl Related<super::author::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Author.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    fn in_print_only() -> Condition {
+        Condition::all().add(Column::InPrint.eq(true))
+    }
+}
+
+use author::Author;
+use book::Book;
+use post::Post;
+use shelf::Shelf;
+use topic::Topic;
+use volume::Volume;
+
+/// Posts "Hello" and "News" (published) and "Draft" (not published), by title
+async fn setup() -> (Router, Vec<Value>) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let statement = Schema::new(backend).create_table_from_entity(post::Entity);
+    db.execute(backend.build(&statement)).await.unwrap();
+    let app = Router::new().nest("/posts", Post::router(&db).into());
+
+    let mut posts = Vec::new();
+    for (title, category, published) in [
+        ("Draft", "news", false),
+        ("Hello", "intro", true),
+        ("News", "news", true),
+    ] {
+        let body = json!({"title": title, "category": category, "published": published});
+        let (status, post) = send(&app, "POST", "/posts", Some(body)).await;
+        // Creating an unpublished post still answers with the row
+        assert_eq!(status, StatusCode::CREATED, "{post}");
+        posts.push(post);
+    }
+    (app, posts)
+}
+
+/// Topics by title, created through the API:
+///
+/// ```text
+/// root
+/// ├── open
+/// └── hidden (not listed)
+///     └── nested
+/// ```
+async fn setup_topics() -> (Router, Vec<String>) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let statement = Schema::new(backend).create_table_from_entity(topic::Entity);
+    db.execute(backend.build(&statement)).await.unwrap();
+    crudcrate::sync::ensure_tombstones(&db).await.unwrap();
+    let app = Router::new().nest("/topics", Topic::router(&db).into());
+
+    let mut ids: Vec<String> = Vec::new();
+    for (title, parent, listed) in [
+        ("root", None, true),
+        ("open", Some(0), true),
+        ("hidden", Some(0), false),
+        ("nested", Some(2), true),
+    ] {
+        let parent_id = parent.map(|index: usize| ids[index].clone());
+        let body = json!({"title": title, "parent_id": parent_id, "listed": listed});
+        let (status, topic) = send(&app, "POST", "/topics", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{topic}");
+        ids.push(topic["id"].as_str().unwrap().to_string());
+    }
+    (app, ids)
+}
+
+fn titles(items: &Value) -> Vec<&str> {
+    items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["title"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_list_and_get_one_follow_condition() {
+    let (app, posts) = setup().await;
+
+    let (status, list) = send(&app, "GET", "/posts?sort_by=title", None).await;
+    assert_eq!(status, StatusCode::OK, "{list}");
+    assert_eq!(titles(&list), ["Hello", "News"]);
+
+    // Filters narrow the rows further
+    let (_, list) = send(&app, "GET", "/posts?category=news", None).await;
+    assert_eq!(titles(&list), ["News"]);
+
+    let draft = posts[0]["id"].as_str().unwrap();
+    let (status, _) = send(&app, "GET", &format!("/posts/{draft}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let hello = posts[1]["id"].as_str().unwrap();
+    let (status, post) = send(&app, "GET", &format!("/posts/{hello}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{post}");
+    assert_eq!(post["title"], "Hello");
+
+    let uri = format!("/posts/by-ids?ids={draft},{hello}");
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body["items"]), ["Hello"]);
+    assert_eq!(body["missing"], json!([draft]));
+
+    let (status, values) = send(&app, "GET", "/posts/distinct/category", None).await;
+    assert_eq!(status, StatusCode::OK, "{values}");
+    assert_eq!(values, json!([{"value": "intro"}, {"value": "news"}]));
+}
+
+#[tokio::test]
+async fn test_writes_ignore_condition() {
+    let (app, posts) = setup().await;
+
+    let draft = posts[0]["id"].as_str().unwrap();
+    let body = json!({"title": "Draft 2"});
+    let (status, post) = send(&app, "PUT", &format!("/posts/{draft}"), Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{post}");
+    assert_eq!(post["title"], "Draft 2");
+
+    let body = json!({"published": true});
+    let (status, _) = send(&app, "PUT", &format!("/posts/{draft}"), Some(body)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, list) = send(&app, "GET", "/posts?sort_by=title", None).await;
+    assert_eq!(titles(&list), ["Draft 2", "Hello", "News"]);
+}
+
+#[tokio::test]
+async fn test_children_follow_condition() {
+    let (app, ids) = setup_topics().await;
+
+    let (status, children) = send(&app, "GET", &format!("/topics/{}/children", ids[0]), None).await;
+    assert_eq!(status, StatusCode::OK, "{children}");
+    assert_eq!(titles(&children), ["open"]);
+
+    let uri = format!("/topics/{}/children?depth=2", ids[0]);
+    let (status, descendants) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{descendants}");
+    assert_eq!(titles(&descendants), ["open", "nested"]);
+
+    let uri = format!("/topics/{}/children", ids[2]);
+    let (status, _) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_ancestors_follow_condition() {
+    let (app, ids) = setup_topics().await;
+
+    let uri = format!("/topics/{}/ancestors", ids[3]);
+    let (status, ancestors) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{ancestors}");
+    assert_eq!(titles(&ancestors), ["root"]);
+
+    let uri = format!("/topics/{}/ancestors", ids[2]);
+    let (status, _) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_changes_follow_condition() {
+    let (app, _) = setup_topics().await;
+
+    let (status, body) = send(&app, "GET", "/topics/changes", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body["changed"]), ["root", "open", "nested"]);
+}
+
+#[tokio::test]
+async fn test_relation_endpoint_follows_condition() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(shelf::Entity),
+        schema.create_table_from_entity(volume::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+    let app = Router::new()
+        .nest("/shelves", Shelf::router(&db).into())
+        .nest("/volumes", Volume::router(&db).into());
+
+    let mut shelves = Vec::new();
+    for listed in [true, false] {
+        let (status, shelf) = send(&app, "POST", "/shelves", Some(json!({"listed": listed}))).await;
+        assert_eq!(status, StatusCode::CREATED, "{shelf}");
+        let id = shelf["id"].as_str().unwrap().to_string();
+        let body = json!({"shelf_id": id, "title": format!("on {listed}")});
+        let (status, volume) = send(&app, "POST", "/volumes", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{volume}");
+        shelves.push(id);
+    }
+
+    let uri = format!("/shelves/{}/volumes", shelves[0]);
+    let (status, volumes) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{volumes}");
+    assert_eq!(titles(&volumes), ["on true"]);
+
+    let uri = format!("/shelves/{}/volumes", shelves[1]);
+    let (status, _) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+/// Author "ada" (active) with books "in print" and "out of print", and "ghost"
+/// (inactive) with "ghost book" in print
+async fn setup_authors() -> (sea_orm::DatabaseConnection, Router, Vec<String>) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(author::Entity),
+        schema.create_table_from_entity(book::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+    let app = Router::new()
+        .nest("/authors", Author::router(&db).into())
+        .nest("/books", Book::router(&db).into());
+
+    let mut ids = Vec::new();
+    for (name, active, books) in [
+        (
+            "ada",
+            true,
+            vec![("in print", true), ("out of print", false)],
+        ),
+        ("ghost", false, vec![("ghost book", true)]),
+    ] {
+        let body = json!({"name": name, "active": active});
+        let (status, author) = send(&app, "POST", "/authors", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED, "{author}");
+        ids.push(author["id"].as_str().unwrap().to_string());
+        for (title, in_print) in books {
+            let body = json!({"author_id": author["id"], "title": title, "in_print": in_print});
+            let (status, book) = send(&app, "POST", "/books", Some(body)).await;
+            assert_eq!(status, StatusCode::CREATED, "{book}");
+            ids.push(book["id"].as_str().unwrap().to_string());
+        }
+    }
+    (db, app, ids)
+}
+
+#[tokio::test]
+async fn test_joins_and_counts_follow_related_condition() {
+    let (db, app, ids) = setup_authors().await;
+
+    let (status, ada) = send(&app, "GET", &format!("/authors/{}", ids[0]), None).await;
+    assert_eq!(status, StatusCode::OK, "{ada}");
+    assert_eq!(titles(&ada["books"]), ["in print"]);
+    assert_eq!(ada["book_count"], 1);
+
+    let (status, authors) = send(&app, "GET", "/authors", None).await;
+    assert_eq!(status, StatusCode::OK, "{authors}");
+    assert_eq!(titles(&authors[0]["books"]), ["in print"]);
+    assert_eq!(authors[0]["book_count"], 1);
+
+    let ada_id = ids[0].parse().unwrap();
+    let books = Author::load_books(&db, &[ada_id]).await.unwrap();
+    assert_eq!(books[&ada_id].len(), 1);
+
+    // The ghost's book is listed, without its hidden author
+    let (status, book) = send(&app, "GET", &format!("/books/{}", ids[4]), None).await;
+    assert_eq!(status, StatusCode::OK, "{book}");
+    assert_eq!(book["author"], Value::Null);
+    let (_, books) = send(&app, "GET", "/books?sort_by=title", None).await;
+    assert_eq!(titles(&books), ["ghost book", "in print"]);
+    assert_eq!(books[0]["author"], Value::Null);
+    assert_eq!(books[1]["author"]["name"], "ada");
+}
+
+#[tokio::test]
+async fn test_relation_filters_follow_related_condition() {
+    let (_, app, _) = setup_authors().await;
+
+    for (filter, expected) in [
+        (r#"{"books__count":1}"#, 1),
+        (r#"{"books__count":2}"#, 0),
+        (r#"{"books__exists":true}"#, 1),
+    ] {
+        let uri = format!("/authors?filter={}", url_escape::encode_component(filter));
+        let (status, authors) = send(&app, "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::OK, "{authors}");
+        assert_eq!(authors.as_array().unwrap().len(), expected, "{filter}");
+    }
+}
@@ -0,0 +1,211 @@
+// Tests for the runtime resource registry
+// Verifies that building a router registers the resource with its fields, types,
+// filter/sort flags, model membership and relations, and that `register_at`
+// overrides the mount path.
+
+use crudcrate::EntityToModels;
+use crudcrate::registry::{self, Cardinality};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod book {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        EnumIter,
+        DeriveActiveEnum,
+        Serialize,
+        Deserialize,
+        ToSchema,
+        Default,
+    )]
+    #[sea_orm(rs_type = "String", db_type = "Text")]
+    pub enum Genre {
+        #[default]
+        #[sea_orm(string_value = "Fiction")]
+        Fiction,
+        #[sea_orm(string_value = "Poetry")]
+        Poetry,
+    }
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "books")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Book",
+        name_singular = "book",
+        name_plural = "books",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub author_id: Uuid,
+
+        #[crudcrate(filterable, sortable, fulltext)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub genre: Genre,
+
+        #[crudcrate(sortable)]
+        pub pages: Option<i32>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::author::Entity",
+            from = "Column::AuthorId",
+            to = "super::author::Column::Id"
+        )]
+        Author,
+    }
+
+    impl Related<super::author::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Author.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod author {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "authors")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Author",
+        name_singular = "author",
+        name_plural = "authors",
+        description = "People who write books",
+        no_partial_eq,
+        no_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub name: String,
+
+        #[crudcrate(exclude(one, list))]
+        pub payout_account: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, depth = 2))]
+        pub books: Vec<super::book::Book>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::book::Entity")]
+        Books,
+    }
+
+    impl Related<super::book::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Books.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use author::Author;
+use book::Book;
+
+async fn db() -> DatabaseConnection {
+    Database::connect("sqlite::memory:").await.unwrap()
+}
+
+#[tokio::test]
+async fn test_router_registers_fields_and_flags() {
+    let _ = Book::router(&db().await);
+
+    let books = registry::resource("books").expect("registered by router()");
+    assert_eq!(books.singular, "book");
+    assert_eq!(books.path, "/books");
+    assert_eq!(books.table, "books");
+
+    let id = books.field("id").unwrap();
+    assert!(id.primary_key);
+    assert_eq!(id.rust_type, "Uuid");
+    assert!(!id.create && !id.update && id.response && id.list);
+
+    let title = books.field("title").unwrap();
+    assert!(title.filterable && title.sortable && title.fulltext);
+    assert!(title.create && title.update);
+
+    let genre = books.field("genre").unwrap();
+    assert!(genre.filterable && !genre.sortable);
+    assert_eq!(
+        genre.enum_variants.as_deref(),
+        Some(&["Fiction".to_string(), "Poetry".to_string()][..])
+    );
+
+    let pages = books.field("pages").unwrap();
+    assert_eq!(pages.rust_type, "Option<i32>");
+    assert!(pages.nullable && pages.sortable && !pages.filterable);
+
+    assert!(books.relations.is_empty());
+}
+
+#[tokio::test]
+async fn test_relations_and_excluded_fields() {
+    let _ = Author::read_only_router(&db().await);
+
+    let authors = registry::resource("authors").unwrap();
+    assert_eq!(authors.description, "People who write books");
+
+    let payout = authors.field("payout_account").unwrap();
+    assert!(payout.create && !payout.response && !payout.list);
+
+    // Join fields are relations, not fields
+    assert!(authors.field("books").is_none());
+    let books = &authors.relations[0];
+    assert_eq!(books.name, "books");
+    assert_eq!(books.target, "Book");
+    assert_eq!(books.cardinality, Cardinality::Many);
+    assert!(books.on_one && !books.on_all);
+    assert_eq!(books.depth, 2);
+}
+
+#[tokio::test]
+async fn test_register_at_overrides_path_and_serializes() {
+    registry::register_at::<Author>("/api/v1/writers");
+    // A later router() call keeps the explicit path
+    let _ = Author::router(&db().await);
+
+    let authors = registry::resource("authors").unwrap();
+    assert_eq!(authors.path, "/api/v1/writers");
+    assert!(registry::resources().iter().any(|r| r.name == "authors"));
+
+    let json = serde_json::to_value(&authors).unwrap();
+    assert_eq!(json["relations"][0]["cardinality"], "many");
+    assert_eq!(json["fields"][0]["name"], "id");
+}
@@ -0,0 +1,223 @@
+// Tests for `{join}__exists` and `{join}__count` filters on `Vec` join fields
+// Verifies that the list endpoint selects parents by whether and how many
+// children they have (including childless parents where the test passes for zero),
+// that scoped requests don't count hidden children, that bad values are rejected, and
+// that delete-by-filter and strict mode treat the keys correctly.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::{Extension, Router};
+use crudcrate::testing::send;
+use crudcrate::{EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Condition, Database, DatabaseConnection, Schema};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub mod vehicle {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "vehicles")]
+    #[crudcrate(generate_router, api_struct = "Vehicle", derive_partial_eq)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub customer_id: Uuid,
+
+        pub make: String,
+
+        #[crudcrate(exclude(scoped))]
+        pub is_private: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::customer::Entity",
+            from = "Column::CustomerId",
+            to = "super::customer::Column::Id"
+        )]
+        Customer,
+    }
+
+    impl Related<super::customer::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Customer.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod customer {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "customers")]
+    #[crudcrate(generate_router, api_struct = "Customer", no_partial_eq, strict_query)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, all, depth = 1))]
+        pub vehicles: Vec<super::vehicle::Vehicle>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::vehicle::Entity")]
+        Vehicles,
+    }
+
+    impl Related<super::vehicle::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Vehicles.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use customer::Customer;
+
+/// Ada has no vehicles, Bob one, Cy two and Di three, one of them private
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(customer::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(vehicle::Entity)))
+        .await
+        .unwrap();
+    for (name, vehicles) in [("Ada", 0), ("Bob", 1), ("Cy", 2), ("Di", 3)] {
+        let id = Uuid::new_v4();
+        customer::ActiveModel {
+            id: Set(id),
+            name: Set(name.to_string()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        for index in 0..vehicles {
+            vehicle::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                customer_id: Set(id),
+                make: Set(format!("Make {index}")),
+                is_private: Set(name == "Di" && index == 0),
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+        }
+    }
+    let app = Router::new().nest("/customers", Customer::router(&db).into());
+    (db, app)
+}
+
+async fn names(app: &Router, filter: &str) -> Vec<String> {
+    let uri = format!(
+        "/customers?sort=name&filter={}",
+        url_escape::encode_component(filter)
+    );
+    let (status, body) = send(app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{filter}: {body}");
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_existence_and_count_filters() {
+    let (_, app) = setup().await;
+    for (filter, expected) in [
+        (r#"{"vehicles__exists":true}"#, vec!["Bob", "Cy", "Di"]),
+        (r#"{"vehicles__exists":false}"#, vec!["Ada"]),
+        (r#"{"vehicles__count":2}"#, vec!["Cy"]),
+        (r#"{"vehicles__count":0}"#, vec!["Ada"]),
+        (r#"{"vehicles__count_gte":2}"#, vec!["Cy", "Di"]),
+        (r#"{"vehicles__count_gt":2}"#, vec!["Di"]),
+        (r#"{"vehicles__count_lt":2}"#, vec!["Ada", "Bob"]),
+        (r#"{"vehicles__count_lte":"1"}"#, vec!["Ada", "Bob"]),
+        (r#"{"vehicles__count_neq":1}"#, vec!["Ada", "Cy", "Di"]),
+        (
+            r#"{"vehicles__exists":true,"vehicles__count_lt":3,"name_neq":"Bob"}"#,
+            vec!["Cy"],
+        ),
+    ] {
+        assert_eq!(names(&app, filter).await, expected, "{filter}");
+    }
+
+    // The total count follows the filter
+    let uri = format!(
+        "/customers?filter={}",
+        url_escape::encode_component(r#"{"vehicles__exists":true}"#)
+    );
+    let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let range = response.headers()["content-range"].to_str().unwrap();
+    assert!(range.ends_with("/3"), "{range}");
+}
+
+#[tokio::test]
+async fn test_scoped_requests_ignore_hidden_children() {
+    let (db, _) = setup().await;
+    let scoped: Router = Customer::read_only_router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all(),
+        }))
+        .into();
+    let app = Router::new().nest("/customers", scoped);
+    assert_eq!(names(&app, r#"{"vehicles__count":2}"#).await, ["Cy", "Di"]);
+    let (_, app) = setup().await;
+    assert_eq!(names(&app, r#"{"vehicles__count":2}"#).await, ["Cy"]);
+}
+
+#[tokio::test]
+async fn test_rejects_invalid_values_and_deletes() {
+    let (_, app) = setup().await;
+    for filter in [
+        r#"{"vehicles__exists":"maybe"}"#,
+        r#"{"vehicles__count_gte":-1}"#,
+        r#"{"vehicles__count":1.5}"#,
+        r#"{"vehicles__counts":1}"#,
+        r#"{"trucks__exists":true}"#,
+    ] {
+        let uri = format!("/customers?filter={}", url_escape::encode_component(filter));
+        let (status, body) = send(&app, "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{filter}: {body}");
+    }
+
+    let uri = format!(
+        "/customers?filter={}",
+        url_escape::encode_component(r#"{"vehicles__exists":false}"#)
+    );
+    let (status, body) = send(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert_eq!(names(&app, "{}").await.len(), 4);
+}
+
+#[tokio::test]
+async fn test_strict_mode_lists_relation_filters() {
+    let (_, app) = setup().await;
+    let uri = format!(
+        "/customers?filter={}",
+        url_escape::encode_component(r#"{"vehicle__exists":true}"#)
+    );
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let message = body.to_string();
+    assert!(message.contains("vehicles__exists"), "{message}");
+    assert!(message.contains("vehicles__count"), "{message}");
+}
@@ -0,0 +1,153 @@
+// Tests for struct-level `rename_all`
+// Verifies that every generated struct uses camelCase keys, that an explicit field
+// `rename` still wins, and that filter and sort parameters accept the camelCase names.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod employee {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "employees")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Employee",
+        name_singular = "employee",
+        name_plural = "employees",
+        rename_all = "camelCase",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub first_name: String,
+
+        #[crudcrate(filterable, rename = "surname")]
+        pub last_name: String,
+
+        #[crudcrate(filterable, sortable)]
+        pub start_year: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use employee::{Employee, EmployeeCreate, EmployeeList, EmployeeResponse, EmployeeUpdate};
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(employee::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/employees", Employee::router(&db).into());
+    (db, app)
+}
+
+async fn seed(app: &Router) {
+    for (first, last, year) in [("Ada", "Lovelace", 2019), ("Alan", "Turing", 2022)] {
+        let (status, body) = send(
+            app,
+            "POST",
+            "/employees",
+            Some(json!({"firstName": first, "surname": last, "startYear": year})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+}
+
+#[tokio::test]
+async fn test_bodies_use_camel_case() {
+    let (_db, app) = setup().await;
+    seed(&app).await;
+
+    let (_, body) = send(&app, "GET", "/employees", None).await;
+    let first = &body[0];
+    assert!(first.get("firstName").is_some(), "{first}");
+    assert!(first.get("startYear").is_some(), "{first}");
+    assert!(first.get("surname").is_some(), "{first}");
+    assert!(first.get("first_name").is_none(), "{first}");
+    assert!(first.get("lastName").is_none(), "{first}");
+
+    let id = first["id"].as_str().unwrap();
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/employees/{id}"),
+        Some(json!({"startYear": 2020})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["startYear"], 2020);
+
+    let (_, body) = send(&app, "GET", &format!("/employees/{id}"), None).await;
+    assert_eq!(body["startYear"], 2020);
+}
+
+#[tokio::test]
+async fn test_filter_and_sort_use_camel_case() {
+    let (_db, app) = setup().await;
+    seed(&app).await;
+
+    let filter = url_escape::encode_component(r#"{"firstName":"Alan"}"#);
+    let (status, body) = send(&app, "GET", &format!("/employees?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["surname"], "Turing");
+
+    let filter = url_escape::encode_component(r#"{"startYear_gte":2020,"surname_neq":"x"}"#);
+    let (_, body) = send(&app, "GET", &format!("/employees?filter={filter}"), None).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["firstName"], "Alan");
+
+    let sort = url_escape::encode_component(r#"["startYear","DESC"]"#);
+    let (_, body) = send(&app, "GET", &format!("/employees?sort={sort}"), None).await;
+    assert_eq!(body[0]["firstName"], "Alan");
+
+    let (_, body) = send(&app, "GET", "/employees?sort_by=firstName&order=ASC", None).await;
+    assert_eq!(body[0]["firstName"], "Ada");
+}
+
+#[test]
+fn test_schemas_use_camel_case() {
+    for schema in [
+        serde_json::to_value(<Employee as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<EmployeeCreate as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<EmployeeUpdate as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<EmployeeList as utoipa::PartialSchema>::schema()).unwrap(),
+        serde_json::to_value(<EmployeeResponse as utoipa::PartialSchema>::schema()).unwrap(),
+    ] {
+        let properties = &schema["properties"];
+        assert!(properties.get("firstName").is_some(), "{schema}");
+        assert!(properties.get("surname").is_some(), "{schema}");
+        assert!(properties.get("first_name").is_none(), "{schema}");
+    }
+}
@@ -0,0 +1,110 @@
+// Tests for request IDs in `crudcrate::telemetry`
+// Verifies that the generated routers keep a valid incoming `X-Request-Id` or generate
+// one, echo it in the response header and error bodies, and make it visible to hooks.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use crudcrate::{ApiError, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use std::sync::Mutex;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Request IDs seen by the create hook
+static SEEN: Mutex<Vec<Option<String>>> = Mutex::new(Vec::new());
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        create::one::pre = super::remember_request_id
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::{Note, NoteCreate};
+
+#[allow(clippy::unused_async)]
+async fn remember_request_id(_db: &DatabaseConnection, _data: &NoteCreate) -> Result<(), ApiError> {
+    SEEN.lock()
+        .unwrap()
+        .push(crudcrate::telemetry::request_id());
+    Ok(())
+}
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    Router::new().nest("/notes", Note::router(&db).into())
+}
+
+/// Status, `X-Request-Id` header and JSON body of a request
+async fn send(
+    app: &Router,
+    request: axum::http::request::Builder,
+    body: &str,
+) -> (StatusCode, Option<String>, serde_json::Value) {
+    let request = request
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let id = response
+        .headers()
+        .get("x-request-id")
+        .map(|value| value.to_str().unwrap().to_string());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+    (status, id, body)
+}
+
+#[tokio::test]
+async fn test_request_id_is_propagated() {
+    let app = setup().await;
+
+    let request = Request::post("/notes").header("x-request-id", "checkout-7f3a");
+    let (status, id, _) = send(&app, request, r#"{"title": "hello"}"#).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(id.as_deref(), Some("checkout-7f3a"));
+
+    let (status, id, _) = send(&app, Request::post("/notes"), r#"{"title": "hi"}"#).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let generated = id.expect("generated request id");
+    assert!(Uuid::parse_str(&generated).is_ok(), "{generated}");
+
+    assert_eq!(
+        *SEEN.lock().unwrap(),
+        [Some("checkout-7f3a".to_string()), Some(generated)],
+        "hooks see the request id"
+    );
+
+    let missing = format!("/notes/{}", Uuid::new_v4());
+    let request = Request::get(&missing).header("x-request-id", "checkout-9d2e");
+    let (status, id, body) = send(&app, request, "").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(id.as_deref(), Some("checkout-9d2e"));
+    assert_eq!(body["request_id"], "checkout-9d2e", "{body}");
+}
@@ -0,0 +1,197 @@
+// Tests for `max_filter_length`, `max_filter_depth` and `max_body_size`
+// Verifies that oversized or deeply nested filters are rejected with 400 on list and
+// delete-by-filter, that bodies over the limit get 413, and that the defaults still
+// accept ordinary requests.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod note {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes",
+        derive_partial_eq,
+        derive_eq,
+        max_filter_length = 200,
+        max_filter_depth = 2,
+        max_body_size = 256
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod memo {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "memos")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Memo",
+        name_singular = "memo",
+        name_plural = "memos",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use memo::Memo;
+use note::Note;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(memo::Entity)))
+        .await
+        .unwrap();
+    Router::new()
+        .nest("/notes", Note::router(&db).into())
+        .nest("/memos", Memo::router(&db).into())
+}
+
+async fn with_filter(app: &Router, method: &str, path: &str, filter: &str) -> (StatusCode, Value) {
+    let filter = url_escape::encode_component(filter).to_string();
+    send(app, method, &format!("{path}?filter={filter}"), None).await
+}
+
+#[tokio::test]
+async fn test_filter_length_limit() {
+    let app = setup().await;
+
+    let (status, body) = with_filter(
+        &app,
+        "GET",
+        "/notes",
+        &json!({"title": "short"}).to_string(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let long = json!({"title": "x".repeat(300)}).to_string();
+    let (status, body) = with_filter(&app, "GET", "/notes", &long).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    let (status, _) = with_filter(&app, "DELETE", "/notes", &long).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // The default limit is much larger
+    let (status, body) = with_filter(&app, "GET", "/memos", &long).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+#[tokio::test]
+async fn test_filter_depth_limit() {
+    let app = setup().await;
+
+    let (status, body) = with_filter(
+        &app,
+        "GET",
+        "/notes",
+        &json!({"title": ["a", "b"]}).to_string(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let deep = json!({"title": [["a"]]}).to_string();
+    let (status, body) = with_filter(&app, "GET", "/notes", &deep).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    let (status, _) = with_filter(&app, "DELETE", "/notes", &deep).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Brackets inside string values don't count towards the depth
+    let (status, body) =
+        with_filter(&app, "GET", "/notes", &json!({"title": "[[{{"}).to_string()).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let nested = format!("{}{}", "[".repeat(10), "]".repeat(10));
+    let (status, _) = with_filter(&app, "GET", "/memos", &nested).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_body_size_limit() {
+    let app = setup().await;
+
+    let (status, body) = send(&app, "POST", "/notes", Some(json!({"title": "fits"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/notes",
+        Some(json!({"title": "x".repeat(300)})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    // Resources without the attribute keep Axum's default limit
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/memos",
+        Some(json!({"title": "x".repeat(300)})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+}
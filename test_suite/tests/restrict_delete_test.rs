@@ -0,0 +1,254 @@
+// Tests for `#[crudcrate(restrict_delete)]`
+// Verifies that deleting a customer with vehicles answers 409 with the blocking table
+// and its row count, that batch, filter and `BatchRouter` deletes refuse the whole
+// batch, that `belongs_to` relations don't block, and that the delete goes through once
+// the dependent rows are gone.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::batch::BatchRouter;
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod customer {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "customers")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Customer",
+        name_singular = "customer",
+        name_plural = "customers",
+        restrict_delete
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::vehicle::Entity")]
+        Vehicles,
+    }
+
+    impl Related<super::vehicle::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Vehicles.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod vehicle {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "vehicles")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Vehicle",
+        name_singular = "vehicle",
+        name_plural = "vehicles",
+        restrict_delete
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub customer_id: Uuid,
+
+        pub make: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::customer::Entity",
+            from = "Column::CustomerId",
+            to = "super::customer::Column::Id"
+        )]
+        Customer,
+    }
+
+    impl Related<super::customer::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Customer.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use customer::Customer;
+use vehicle::Vehicle;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(customer::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(vehicle::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/customers", Customer::router(&db).into())
+        .nest("/vehicles", Vehicle::router(&db).into());
+    (db, app)
+}
+
+async fn create(app: &Router, uri: &str, body: serde_json::Value) -> String {
+    let (status, body) = send(app, "POST", uri, Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    body["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_delete_with_dependents_is_refused() {
+    let (db, app) = setup().await;
+    let ada = create(&app, "/customers", json!({"name": "Ada"})).await;
+    let mut vehicles = Vec::new();
+    for make in ["Volvo", "Saab"] {
+        let body = json!({"customer_id": ada, "make": make});
+        vehicles.push(create(&app, "/vehicles", body).await);
+    }
+
+    let (status, mut body) = send(&app, "DELETE", &format!("/customers/{ada}"), None).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+    let request_id = body.as_object_mut().unwrap().remove("request_id");
+    assert!(request_id.is_some(), "error bodies carry the request id");
+    assert_eq!(
+        body,
+        json!({
+            "error": "Cannot delete customer: referenced by vehicles",
+            "dependents": [{"relation": "vehicles", "count": 2}]
+        })
+    );
+    assert_eq!(
+        Customer::total_count(&db, &sea_orm::Condition::all()).await,
+        1
+    );
+
+    // A `belongs_to` relation points at the parent and doesn't block
+    for vehicle in &vehicles {
+        let (status, body) = send(&app, "DELETE", &format!("/vehicles/{vehicle}"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT, "{body}");
+    }
+
+    let (status, body) = send(&app, "DELETE", &format!("/customers/{ada}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT, "{body}");
+}
+
+#[tokio::test]
+async fn test_batch_delete_is_refused_as_a_whole() {
+    let (db, app) = setup().await;
+    let ada = create(&app, "/customers", json!({"name": "Ada"})).await;
+    let grace = create(&app, "/customers", json!({"name": "Grace"})).await;
+    create(
+        &app,
+        "/vehicles",
+        json!({"customer_id": ada, "make": "Volvo"}),
+    )
+    .await;
+
+    let (status, body) = send(
+        &app,
+        "DELETE",
+        "/customers/batch",
+        Some(json!([ada, grace])),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+    assert_eq!(
+        body["dependents"],
+        json!([{"relation": "vehicles", "count": 1}])
+    );
+
+    let filter = url_escape::encode_component(r#"{"name_neq":"nobody"}"#);
+    let (status, body) = send(&app, "DELETE", &format!("/customers?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+    assert_eq!(
+        Customer::total_count(&db, &sea_orm::Condition::all()).await,
+        2
+    );
+
+    let (status, body) = send(&app, "DELETE", "/customers/batch", Some(json!([grace]))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!([grace]));
+}
+
+#[tokio::test]
+async fn test_mixed_batch_delete_is_refused() {
+    let (db, app) = setup().await;
+    let app = app.merge(
+        BatchRouter::new()
+            .resource::<Customer>()
+            .unwrap()
+            .resource::<Vehicle>()
+            .unwrap()
+            .router(&db),
+    );
+    let ada = create(&app, "/customers", json!({"name": "Ada"})).await;
+    create(
+        &app,
+        "/vehicles",
+        json!({"customer_id": ada, "make": "Volvo"}),
+    )
+    .await;
+
+    let operations = json!([
+        {"op": "create", "resource": "customers", "body": {"name": "Grace"}},
+        {"op": "delete", "resource": "customers", "id": ada}
+    ]);
+    let (status, body) = send(&app, "POST", "/batch", Some(operations)).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+    assert_eq!(body["index"], 1);
+    assert_eq!(
+        Customer::total_count(&db, &sea_orm::Condition::all()).await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_dependents_lists_counts() {
+    let (db, app) = setup().await;
+    let ada = create(&app, "/customers", json!({"name": "Ada"})).await;
+    let ada = Uuid::parse_str(&ada).unwrap();
+
+    let dependents =
+        crudcrate::relationships::dependents::<Customer, customer::Relation>(&db, &[ada])
+            .await
+            .unwrap();
+    assert!(dependents.is_empty());
+
+    create(
+        &app,
+        "/vehicles",
+        json!({"customer_id": ada, "make": "Volvo"}),
+    )
+    .await;
+    let dependents =
+        crudcrate::relationships::dependents::<Customer, customer::Relation>(&db, &[ada])
+            .await
+            .unwrap();
+    assert_eq!(
+        dependents,
+        [crudcrate::Dependent {
+            relation: "vehicles".to_string(),
+            count: 1
+        }]
+    );
+}
@@ -0,0 +1,162 @@
+// Tests for `#[crudcrate(retry_attempts = N)]` and `crudcrate::retry`
+// Verifies that default writes blocked by another connection's write transaction on a
+// file-backed SQLite database (SQLITE_BUSY) are retried until the lock is released, and
+// that with retries disabled the busy error surfaces as a 500.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sea_orm::{DatabaseConnection, Schema, SqlxSqliteConnector, TransactionTrait};
+use serde_json::json;
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+
+pub mod task {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tasks")]
+    #[crudcrate(generate_router, api_struct = "Task", retry_attempts = 10)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod event {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "events")]
+    #[crudcrate(generate_router, api_struct = "Event", retry_attempts = 1)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use crudcrate::CRUDResource;
+use event::Event;
+use task::Task;
+
+/// A connection to `path` that fails with `SQLITE_BUSY` instead of waiting for locks
+async fn connect(path: &PathBuf) -> DatabaseConnection {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .busy_timeout(Duration::ZERO);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .unwrap();
+    SqlxSqliteConnector::from_sqlx_sqlite_pool(pool)
+}
+
+struct Setup {
+    app: Router,
+    blocker: DatabaseConnection,
+    dir: PathBuf,
+}
+
+impl Drop for Setup {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+async fn setup() -> Setup {
+    let dir = std::env::temp_dir().join(format!("crudcrate-retry-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("app.db");
+    let db = connect(&path).await;
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(task::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(event::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/tasks", Task::router(&db).into())
+        .nest("/events", Event::router(&db).into());
+    Setup {
+        app,
+        blocker: connect(&path).await,
+        dir,
+    }
+}
+
+/// Hold the database's write lock from another connection for `hold`
+async fn lock_database(blocker: &DatabaseConnection, hold: Duration) {
+    let txn = blocker.begin().await.unwrap();
+    txn.execute_unprepared("INSERT INTO events (id, title) VALUES ('blocker', 'lock')")
+        .await
+        .unwrap();
+    tokio::spawn(async move {
+        tokio::time::sleep(hold).await;
+        txn.rollback().await.unwrap();
+    });
+}
+
+#[tokio::test]
+async fn test_busy_writes_are_retried() {
+    let setup = setup().await;
+    assert_eq!(Task::retry_policy().attempts(), 10);
+
+    lock_database(&setup.blocker, Duration::from_millis(50)).await;
+    let (status, created) = send(&setup.app, "POST", "/tasks", Some(json!({"title": "a"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{created}");
+    let id = created["id"].as_str().unwrap().to_string();
+
+    // Batch updates run in a transaction, retried as a whole
+    lock_database(&setup.blocker, Duration::from_millis(50)).await;
+    let (status, updated) = send(
+        &setup.app,
+        "PATCH",
+        "/tasks/batch",
+        Some(json!([{"id": id, "title": "b"}])),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{updated}");
+    assert_eq!(updated[0]["title"], "b");
+
+    lock_database(&setup.blocker, Duration::from_millis(50)).await;
+    let (status, _) = send(&setup.app, "DELETE", &format!("/tasks/{id}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_busy_writes_fail_without_retries() {
+    let setup = setup().await;
+
+    lock_database(&setup.blocker, Duration::from_millis(200)).await;
+    let (status, _) = send(&setup.app, "POST", "/events", Some(json!({"title": "a"}))).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+
+    // Once the lock is gone the write goes through
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let (status, created) = send(&setup.app, "POST", "/events", Some(json!({"title": "a"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{created}");
+}
@@ -0,0 +1,118 @@
+// Tests for `#[crudcrate(layer = fn)]`
+// Verifies that layers returned by the named functions wrap the generated router and the
+// read-only router, and that several layers can be stacked.
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::DefaultBodyLimit;
+use axum::http::{Request, StatusCode, header};
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tower::ServiceExt;
+use tower_http::cors::CorsLayer;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod note {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes",
+        derive_partial_eq,
+        derive_eq,
+        layer = super::small_bodies,
+        layer = super::cors
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub text: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::Note;
+
+fn small_bodies() -> DefaultBodyLimit {
+    DefaultBodyLimit::max(64)
+}
+
+fn cors() -> CorsLayer {
+    CorsLayer::permissive()
+}
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    db
+}
+
+#[tokio::test]
+async fn test_body_limit_layer_applies() {
+    let db = setup().await;
+    let app = Router::new().nest("/notes", Note::router(&db).into());
+
+    let (status, body) = send(&app, "POST", "/notes", Some(json!({"text": "short"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/notes",
+        Some(json!({"text": "x".repeat(200)})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_layers_apply_to_read_only_router() {
+    let db = setup().await;
+    for app in [
+        Router::new().nest("/notes", Note::router(&db).into()),
+        Router::new().nest("/notes", Note::read_only_router(&db).into()),
+    ] {
+        let request = Request::builder()
+            .uri("/notes")
+            .header(header::ORIGIN, "https://example.com")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            response
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        );
+    }
+}
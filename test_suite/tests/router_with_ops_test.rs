@@ -0,0 +1,168 @@
+// Tests for injecting a CRUDOperations object into a generated router
+// Verifies that `router_with_ops` dispatches through the injected object, that the
+// plain `router` keeps using the static CRUDResource methods, and that an operations
+// object chosen at runtime can be attached with `SharedOperations::from_arc`.
+
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use crudcrate::{
+    ApiError, CRUDOperations, CRUDResource, DefaultCRUDOperations, EntityToModels, SharedOperations,
+};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(generate_router, api_struct = "Note")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::{Note, NoteCreate, NoteList};
+
+/// Upper-cases titles on read and refuses deletes
+struct ShoutingOps;
+
+#[async_trait]
+impl CRUDOperations for ShoutingOps {
+    type Resource = Note;
+
+    async fn after_get_one(
+        &self,
+        _db: &DatabaseConnection,
+        entity: &mut Note,
+    ) -> Result<(), ApiError> {
+        entity.title = entity.title.to_uppercase();
+        Ok(())
+    }
+
+    async fn after_get_all(
+        &self,
+        _db: &DatabaseConnection,
+        entities: &mut Vec<NoteList>,
+    ) -> Result<(), ApiError> {
+        for entity in entities.iter_mut() {
+            entity.title = entity.title.to_uppercase();
+        }
+        Ok(())
+    }
+
+    async fn before_delete(&self, _db: &DatabaseConnection, _id: Uuid) -> Result<(), ApiError> {
+        Err(ApiError::forbidden("Notes are read-only"))
+    }
+}
+
+async fn setup() -> (DatabaseConnection, Uuid) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+
+    let created = Note::create(
+        &db,
+        NoteCreate {
+            title: "hello".into(),
+        },
+    )
+    .await
+    .unwrap();
+    (db, created.id)
+}
+
+async fn send(app: &axum::Router, method: &str, uri: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+    (status, json)
+}
+
+#[tokio::test]
+async fn test_plain_router_uses_static_methods() {
+    let (db, id) = setup().await;
+    let app = axum::Router::new().nest("/notes", Note::router(&db).into());
+
+    let (status, body) = send(&app, "GET", &format!("/notes/{id}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["title"], "hello");
+
+    let (status, _) = send(&app, "DELETE", &format!("/notes/{id}")).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_router_with_ops_dispatches_reads() {
+    let (db, id) = setup().await;
+    let app = axum::Router::new().nest("/notes", Note::router_with_ops(&db, ShoutingOps).into());
+
+    let (status, body) = send(&app, "GET", &format!("/notes/{id}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["title"], "HELLO");
+
+    let (status, body) = send(&app, "GET", "/notes").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body[0]["title"], "HELLO");
+}
+
+#[tokio::test]
+async fn test_router_with_ops_dispatches_writes() {
+    let (db, id) = setup().await;
+    let app = axum::Router::new().nest("/notes", Note::router_with_ops(&db, ShoutingOps).into());
+
+    let (status, _) = send(&app, "DELETE", &format!("/notes/{id}")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // The record survives the refused delete
+    assert!(Note::get_one(&db, id).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_operations_chosen_at_runtime() {
+    let (db, id) = setup().await;
+
+    for shouting in [true, false] {
+        let ops: Arc<dyn CRUDOperations<Resource = Note>> = if shouting {
+            Arc::new(ShoutingOps)
+        } else {
+            Arc::new(DefaultCRUDOperations::new())
+        };
+        let router = Note::router(&db).layer(axum::Extension(SharedOperations::from_arc(ops)));
+        let app = axum::Router::new().nest("/notes", router.into());
+
+        let (status, body) = send(&app, "GET", &format!("/notes/{id}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["title"], if shouting { "HELLO" } else { "hello" });
+    }
+}
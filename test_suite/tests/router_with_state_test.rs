@@ -0,0 +1,124 @@
+// Tests for `router_with_state`
+// Verifies that a router built over a shared application state serves the usual CRUD
+// endpoints through the state's DatabaseConnection, that hooks see the state through
+// `crudcrate::app_state`, and that routers built with `router(&db)` have no state.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+#[derive(Clone)]
+struct AppState {
+    db: DatabaseConnection,
+    banned_word: String,
+}
+
+impl AsRef<DatabaseConnection> for AppState {
+    fn as_ref(&self) -> &DatabaseConnection {
+        &self.db
+    }
+}
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes",
+        create::one::pre = super::reject_banned_word
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::{Note, NoteCreate};
+
+/// Rejects titles containing the application's banned word, when there is a state
+#[allow(clippy::unused_async)]
+async fn reject_banned_word(_db: &DatabaseConnection, data: &NoteCreate) -> Result<(), ApiError> {
+    if let Some(state) = crudcrate::app_state::<AppState>()
+        && data.title.contains(&state.banned_word)
+    {
+        return Err(ApiError::bad_request(format!(
+            "Titles may not contain '{}'",
+            state.banned_word
+        )));
+    }
+    Ok(())
+}
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    db
+}
+
+#[tokio::test]
+async fn test_router_with_state_serves_crud() {
+    let db = setup().await;
+    let state = AppState {
+        db,
+        banned_word: "spam".to_string(),
+    };
+    let app = Router::new().nest("/notes", Note::router_with_state(state).into());
+
+    let (status, note) = send(&app, "POST", "/notes", Some(json!({"title": "hello"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{note}");
+    let id = note["id"].as_str().unwrap();
+
+    let (status, body) = send(&app, "GET", &format!("/notes/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["title"], "hello");
+
+    let (status, body) = send(&app, "GET", "/notes", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_hooks_read_app_state() {
+    let db = setup().await;
+    let state = AppState {
+        db: db.clone(),
+        banned_word: "spam".to_string(),
+    };
+    let app = Router::new().nest("/notes", Note::router_with_state(state).into());
+
+    let (status, body) = send(&app, "POST", "/notes", Some(json!({"title": "buy spam"}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert_eq!(body["error"], "Titles may not contain 'spam'");
+
+    // Without a state the hook lets everything through
+    let plain = Router::new().nest("/notes", Note::router(&db).into());
+    let (status, body) = send(&plain, "POST", "/notes", Some(json!({"title": "buy spam"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+}
+
+#[tokio::test]
+async fn test_app_state_outside_requests() {
+    assert!(crudcrate::app_state::<AppState>().is_none());
+}
@@ -0,0 +1,159 @@
+// Tests for the generic read helpers in `crudcrate::core::crud_operations`
+// Verifies that find_page selects NULL for the given columns and applies order, offset
+// and limit, that find_one applies the scope in the same query, and that the derived
+// list and get-one endpoints built on them keep their behaviour.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::core::crud_operations::{column_order, find_one, find_page};
+use crudcrate::testing::send;
+use crudcrate::{ApiError, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Order, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod photo {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "photos")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Photo",
+        name_singular = "photo",
+        name_plural = "photos",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        #[crudcrate(exclude(list))]
+        pub data: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use photo::Photo;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(photo::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/photos", Photo::router(&db).into());
+    for title in ["b", "a", "c"] {
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/photos",
+            Some(json!({"title": title, "data": format!("{title}-bytes")})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+    (db, app)
+}
+
+#[tokio::test]
+async fn test_find_page_orders_and_pages() {
+    let (db, _) = setup().await;
+    let order = [column_order(photo::Column::Title, Order::Desc)];
+
+    let models = find_page::<Photo>(&db, &Condition::all(), &order, 1, 5, &[])
+        .await
+        .unwrap();
+    let titles: Vec<_> = models.iter().map(|m| m.title.as_str()).collect();
+    assert_eq!(titles, ["b", "a"]);
+    assert!(models.iter().all(|m| m.data.is_some()));
+}
+
+#[tokio::test]
+async fn test_find_page_selects_null_columns() {
+    let (db, _) = setup().await;
+    let order = [column_order(photo::Column::Title, Order::Asc)];
+
+    let models = find_page::<Photo>(
+        &db,
+        &Condition::all().add(photo::Column::Title.ne("c")),
+        &order,
+        0,
+        10,
+        &[photo::Column::Data],
+    )
+    .await
+    .unwrap();
+    let titles: Vec<_> = models.iter().map(|m| m.title.as_str()).collect();
+    assert_eq!(titles, ["a", "b"]);
+    assert!(models.iter().all(|m| m.data.is_none()));
+}
+
+#[tokio::test]
+async fn test_find_one_applies_scope() {
+    let (db, _) = setup().await;
+    let id = find_page::<Photo>(&db, &Condition::all(), &[], 0, 1, &[])
+        .await
+        .unwrap()[0]
+        .id;
+
+    let found = find_one::<Photo>(&db, id, None).await.unwrap();
+    assert_eq!(found.id, id);
+
+    let in_scope = Condition::all().add(photo::Column::Title.eq(found.title.clone()));
+    assert!(find_one::<Photo>(&db, id, Some(&in_scope)).await.is_ok());
+
+    let out_of_scope = Condition::all().add(photo::Column::Title.eq("nope"));
+    let err = find_one::<Photo>(&db, id, Some(&out_of_scope))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ApiError::NotFound { .. }), "{err:?}");
+
+    let err = find_one::<Photo>(&db, Uuid::new_v4(), None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ApiError::NotFound { .. }), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_endpoints_use_helpers() {
+    let (_, app) = setup().await;
+
+    let (status, body) = send(&app, "GET", "/photos?sort_by=title&order=ASC", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let items = body.as_array().unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0]["title"], "a");
+    assert!(items[0].get("data").is_none());
+
+    let id = items[0]["id"].as_str().unwrap();
+    let (status, body) = send(&app, "GET", &format!("/photos/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], "a-bytes");
+
+    let (status, _) = send(&app, "GET", &format!("/photos/{}", Uuid::new_v4()), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
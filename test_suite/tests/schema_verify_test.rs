@@ -0,0 +1,131 @@
+// Tests for `crudcrate::schema::verify`
+// Verifies that a table created from the entity passes, that missing tables and
+// columns, incompatible types, nullability differences and unknown NOT NULL columns are
+// each reported, and that SQLite's text storage of UUIDs and timestamps is accepted.
+
+use chrono::{DateTime, Utc};
+use crudcrate::EntityToModels;
+use crudcrate::schema::{Mismatch, SchemaError, compare, verify};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use uuid::Uuid;
+
+pub mod item {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "items")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Item",
+        name_singular = "item",
+        name_plural = "items"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        pub count: i32,
+
+        pub note: Option<String>,
+
+        pub active: bool,
+
+        #[crudcrate(exclude(create, update), on_create = Utc::now())]
+        pub created_at: DateTime<Utc>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use item::Item;
+
+async fn connect() -> DatabaseConnection {
+    Database::connect("sqlite::memory:").await.unwrap()
+}
+
+#[tokio::test]
+async fn test_table_from_entity_matches() {
+    let db = connect().await;
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(item::Entity)))
+        .await
+        .unwrap();
+
+    assert!(compare::<Item>(&db).await.unwrap().is_empty());
+    verify::<Item>(&db).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_drift_is_reported() {
+    let db = connect().await;
+    db.execute_unprepared(
+        "CREATE TABLE items (
+            id uuid_text NOT NULL PRIMARY KEY,
+            name integer NOT NULL,
+            note text NOT NULL,
+            active boolean,
+            created_at timestamp_with_timezone_text NOT NULL,
+            tenant text NOT NULL,
+            archived boolean NOT NULL DEFAULT 0
+        )",
+    )
+    .await
+    .unwrap();
+
+    let mismatches = compare::<Item>(&db).await.unwrap();
+    assert_eq!(
+        mismatches,
+        [
+            Mismatch::Type {
+                column: "name".to_string(),
+                expected: "text",
+                found: "integer".to_string(),
+            },
+            Mismatch::MissingColumn {
+                column: "count".to_string()
+            },
+            Mismatch::Nullability {
+                column: "note".to_string(),
+                expected_nullable: true,
+            },
+            Mismatch::Nullability {
+                column: "active".to_string(),
+                expected_nullable: false,
+            },
+            Mismatch::UnexpectedRequiredColumn {
+                column: "tenant".to_string()
+            },
+        ]
+    );
+
+    let error = verify::<Item>(&db).await.unwrap_err();
+    assert!(matches!(error, SchemaError::Drift { table: "items", .. }));
+    assert!(
+        error
+            .to_string()
+            .contains("column `count` is missing; column `note` is NOT NULL"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn test_missing_table() {
+    let db = connect().await;
+
+    assert_eq!(
+        compare::<Item>(&db).await.unwrap(),
+        [Mismatch::MissingTable]
+    );
+    let error = verify::<Item>(&db).await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Table `items` doesn't match its entity: the table doesn't exist"
+    );
+}
@@ -0,0 +1,142 @@
+// Tests for `#[crudcrate(security = "...")]`
+// Verifies that every generated operation declares the security requirement, that the
+// `bearer` scheme is added to the document's components, that the read-only router is
+// covered too, and that resources without the attribute stay unchanged.
+
+use crudcrate::EntityToModels;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection};
+use serde_json::{Value, json};
+use utoipa_axum::router::OpenApiRouter;
+use uuid::Uuid;
+
+pub mod secret {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "secrets")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Secret",
+        name_singular = "secret",
+        name_plural = "secrets",
+        security = "bearer"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub value: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::Note;
+use secret::Secret;
+
+async fn connect() -> DatabaseConnection {
+    Database::connect("sqlite::memory:").await.unwrap()
+}
+
+fn document(router: OpenApiRouter) -> Value {
+    let (_, openapi) = router.split_for_parts();
+    serde_json::to_value(openapi).unwrap()
+}
+
+/// The `security` of every operation in the document
+fn operation_security(document: &Value) -> Vec<Value> {
+    document["paths"]
+        .as_object()
+        .unwrap()
+        .values()
+        .flat_map(|item| item.as_object().unwrap().values())
+        .filter_map(|operation| operation.get("operationId").map(|_| operation))
+        .map(|operation| operation.get("security").cloned().unwrap_or(Value::Null))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_operations_require_the_scheme() {
+    let db = connect().await;
+    let document = document(OpenApiRouter::new().nest("/secrets", Secret::router(&db)));
+
+    let security = operation_security(&document);
+    assert_eq!(security.len(), 12, "{document}");
+    assert!(
+        security
+            .iter()
+            .all(|security| *security == json!([{"bearer": []}])),
+        "{security:?}"
+    );
+    assert_eq!(
+        document["components"]["securitySchemes"]["bearer"],
+        json!({"type": "http", "scheme": "bearer"})
+    );
+}
+
+#[tokio::test]
+async fn test_read_only_router_requires_the_scheme() {
+    let db = connect().await;
+    let document = document(OpenApiRouter::new().nest("/secrets", Secret::read_only_router(&db)));
+
+    let security = operation_security(&document);
+    assert_eq!(security.len(), 5, "{document}");
+    assert!(
+        security
+            .iter()
+            .all(|security| *security == json!([{"bearer": []}])),
+        "{security:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_resources_without_security_are_unchanged() {
+    let db = connect().await;
+    let document = document(
+        OpenApiRouter::new()
+            .nest("/notes", Note::router(&db))
+            .nest("/secrets", Secret::router(&db)),
+    );
+
+    let notes = &document["paths"]["/notes/{id}"]["get"];
+    assert!(notes.get("security").is_none(), "{notes}");
+    let secrets = &document["paths"]["/secrets/{id}"]["get"];
+    assert_eq!(secrets["security"], json!([{"bearer": []}]));
+}
+
+#[test]
+fn test_custom_scheme_names_are_not_registered() {
+    let router = crudcrate::router::with_security(OpenApiRouter::<()>::new(), "api_key");
+    let document = document(router);
+    assert!(document["components"]["securitySchemes"].is_null());
+}
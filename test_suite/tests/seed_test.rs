@@ -0,0 +1,249 @@
+// Tests for `crudcrate::seed`
+// Verifies that seed files are created through the create models (applying on_create
+// defaults), that JSON and YAML are both read, that a manifest loads parents before
+// the resources they join as `Vec`, and that bad files, failing rows, unknown
+// dependencies and cycles are reported.
+
+use crudcrate::seed::{SeedError, SeedManifest, load, load_json};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod book {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "books")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Book",
+        name_singular = "book",
+        name_plural = "books",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub author_id: Uuid,
+
+        pub title: String,
+
+        #[crudcrate(on_create = true)]
+        pub in_print: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::author::Entity",
+            from = "Column::AuthorId",
+            to = "super::author::Column::Id"
+        )]
+        Author,
+    }
+
+    impl Related<super::author::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Author.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod author {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "authors")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Author",
+        name_singular = "author",
+        name_plural = "authors",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        // Accepted on create so seed files can give authors fixed ids
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, depth = 1))]
+        pub books: Vec<super::book::Book>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::book::Entity")]
+        Books,
+    }
+
+    impl Related<super::book::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Books.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use author::Author;
+use book::Book;
+
+const ADA: &str = "6f0d3c4e-94d1-4c39-9d3e-6c1b5f1e2a01";
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(author::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(book::Entity)))
+        .await
+        .unwrap();
+    db
+}
+
+/// Writes `contents` to a fresh file named `name` for the test `test`
+fn seed_file(test: &str, name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("crudcrate-seed-test").join(test);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn authors_json(test: &str) -> PathBuf {
+    seed_file(
+        test,
+        "authors.json",
+        &format!(r#"[{{"id": "{ADA}", "name": "Ada"}}, {{"name": "Grace"}}]"#),
+    )
+}
+
+fn books_json(test: &str) -> PathBuf {
+    seed_file(
+        test,
+        "books.json",
+        &format!(
+            r#"[
+                {{"author_id": "{ADA}", "title": "Notes"}},
+                {{"author_id": "{ADA}", "title": "Sketch", "in_print": false}}
+            ]"#
+        ),
+    )
+}
+
+#[tokio::test]
+async fn test_load_json_applies_defaults() {
+    let db = setup().await;
+
+    let authors: Vec<Author> = load_json(&db, authors_json("defaults")).await.unwrap();
+    assert_eq!(authors.len(), 2);
+    assert_eq!(authors[0].id.to_string(), ADA);
+    assert_ne!(authors[1].id, Uuid::nil());
+
+    let books: Vec<Book> = load_json(&db, books_json("defaults")).await.unwrap();
+    assert_eq!(books[0].title, "Notes");
+    assert!(books[0].in_print, "on_create default applies");
+    assert!(!books[1].in_print);
+
+    let ada = Author::get_one(&db, Uuid::parse_str(ADA).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(ada.books.len(), 2);
+}
+
+#[tokio::test]
+async fn test_load_yaml() {
+    let db = setup().await;
+    let path = seed_file(
+        "yaml",
+        "authors.yaml",
+        &format!("- id: {ADA}\n  name: Ada\n- name: Grace\n"),
+    );
+
+    let authors: Vec<Author> = load(&db, path).await.unwrap();
+    let names: Vec<&str> = authors.iter().map(|author| author.name.as_str()).collect();
+    assert_eq!(names, ["Ada", "Grace"]);
+}
+
+#[tokio::test]
+async fn test_manifest_loads_parents_first() {
+    let db = setup().await;
+    let manifest = SeedManifest::new()
+        .resource::<Book>(books_json("manifest"))
+        .resource::<Author>(authors_json("manifest"));
+
+    assert_eq!(manifest.order().unwrap(), ["authors", "books"]);
+    let counts = manifest.load(&db).await.unwrap();
+    assert_eq!(counts, [("authors", 2), ("books", 2)]);
+}
+
+#[tokio::test]
+async fn test_manifest_explicit_dependencies() {
+    let manifest = SeedManifest::new()
+        .resource_after::<Book>("books.json", &["writers"])
+        .resource::<Author>("authors.json");
+    assert!(matches!(
+        manifest.order(),
+        Err(SeedError::UnknownDependency { resource: "books", ref dependency }) if dependency == "writers"
+    ));
+
+    let manifest = SeedManifest::new()
+        .resource_after::<Author>("authors.json", &["books"])
+        .resource::<Book>("books.json");
+    let error = manifest.order().unwrap_err();
+    assert!(
+        matches!(error, SeedError::Cycle { ref resources } if resources == &["authors", "books"]),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn test_seed_errors() {
+    let db = setup().await;
+
+    let missing = std::env::temp_dir().join("crudcrate-seed-test/none/missing.json");
+    let error = load_json::<Author>(&db, &missing).await.unwrap_err();
+    assert!(matches!(error, SeedError::Read { .. }), "{error}");
+
+    let path = seed_file("errors", "authors.json", r#"{"name": "Ada"}"#);
+    let error = load_json::<Author>(&db, &path).await.unwrap_err();
+    assert!(matches!(error, SeedError::Parse { .. }), "{error}");
+
+    // The second row reuses the first row's primary key
+    let path = seed_file(
+        "errors",
+        "duplicates.json",
+        &format!(r#"[{{"id": "{ADA}", "name": "Ada"}}, {{"id": "{ADA}", "name": "Ada"}}]"#),
+    );
+    let error = load_json::<Author>(&db, &path).await.unwrap_err();
+    assert!(
+        matches!(
+            error,
+            SeedError::Create {
+                resource: "authors",
+                index: 1,
+                ..
+            }
+        ),
+        "{error}"
+    );
+    assert_eq!(
+        Author::total_count(&db, &sea_orm::Condition::all()).await,
+        1
+    );
+}
@@ -0,0 +1,124 @@
+// Tests for `#[crudcrate(sensitive)]` fields
+// Verifies that every generated model prints `***` for sensitive values in `Debug`
+// output, that the resource lists them, and that `?__explain=true` keeps their filter
+// values out of the reported query.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn fake_hash(pin: String) -> Result<String, crudcrate::ApiError> {
+    Ok(pin + ":hashed")
+}
+
+pub mod patient {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "patients")]
+    #[crudcrate(generate_router, api_struct = "Patient", create_builder)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        #[crudcrate(filterable)]
+        pub name: String,
+        #[crudcrate(filterable, sensitive)]
+        pub ssn: String,
+        #[crudcrate(hash_with = fake_hash)]
+        pub pin: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use patient::{Patient, PatientCreate, PatientList, PatientResponse, PatientUpdate};
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(patient::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/patients", Patient::router(&db).into());
+    (db, app)
+}
+
+fn patient() -> Patient {
+    Patient {
+        id: Uuid::nil(),
+        name: "Ada".to_string(),
+        ssn: "123-45-6789".to_string(),
+        pin: "4321".to_string(),
+    }
+}
+
+#[test]
+fn test_debug_masks_sensitive_values() {
+    let create = PatientCreate {
+        name: "Ada".to_string(),
+        ssn: "123-45-6789".to_string(),
+        pin: "4321".to_string(),
+    };
+    let update = PatientUpdate {
+        name: None,
+        ssn: Some(Some("123-45-6789".to_string())),
+        pin: Some(Some("4321".to_string())),
+    };
+    let builder = PatientCreate::builder().name("Ada").ssn("123-45-6789");
+    let outputs = [
+        format!("{:?}", patient()),
+        format!("{create:?}"),
+        format!("{update:?}"),
+        format!("{builder:?}"),
+        format!("{:?}", PatientList::from(patient())),
+        format!("{:?}", PatientResponse::from(patient())),
+    ];
+    for output in &outputs {
+        assert!(!output.contains("123-45-6789"), "{output}");
+        assert!(!output.contains("4321"), "{output}");
+        assert!(output.contains("ssn: ***"), "{output}");
+    }
+    assert!(outputs[0].starts_with("Patient { "), "{}", outputs[0]);
+    assert!(outputs[0].contains("name: \"Ada\""), "{}", outputs[0]);
+    assert!(outputs[1].contains("pin: ***"), "{}", outputs[1]);
+}
+
+#[test]
+fn test_sensitive_fields_listed() {
+    assert_eq!(Patient::sensitive_fields(), ["ssn", "pin"]);
+}
+
+#[tokio::test]
+async fn test_explain_hides_sensitive_filter_values() {
+    let (_db, app) = setup().await;
+    let body = json!({"name": "Ada", "ssn": "123-45-6789", "pin": "4321"});
+    let (status, body) = send(&app, "POST", "/patients", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    let filter =
+        url_escape::encode_component(&json!({"ssn": "123-45-6789"}).to_string()).to_string();
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/patients?filter={filter}&__explain=true"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(!body.to_string().contains("123-45-6789"), "{body}");
+    assert!(body["sql"].as_str().unwrap().contains("\"ssn\""), "{body}");
+
+    let (status, body) = send(&app, "GET", &format!("/patients?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 1, "{body}");
+}
@@ -0,0 +1,208 @@
+// Tests for `#[crudcrate(serialize_writes)]` and `crudcrate::locks`
+// Verifies that concurrent updates of one row run one at a time on `serialize_writes`
+// resources, while updates of different rows and of other resources still overlap, that
+// batches sharing rows don't deadlock, and that locks are forgotten once released.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use serial_test::serial;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Updates in the `pre` hook per row, and the most seen at once
+static IN_FLIGHT: Mutex<(BTreeMap<Uuid, usize>, usize)> = Mutex::new((BTreeMap::new(), 0));
+
+/// Stays in the hook long enough for a concurrent update of the row to catch up
+async fn track<U>(_db: &DatabaseConnection, id: Uuid, _data: &U) -> Result<(), ApiError> {
+    {
+        let mut in_flight = IN_FLIGHT.lock().unwrap();
+        let count = in_flight.0.entry(id).or_default();
+        *count += 1;
+        let count = *count;
+        in_flight.1 = in_flight.1.max(count);
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    *IN_FLIGHT.lock().unwrap().0.get_mut(&id).unwrap() -= 1;
+    Ok(())
+}
+
+/// Most concurrent updates of one row since the last call
+fn take_max_in_flight() -> usize {
+    std::mem::take(&mut IN_FLIGHT.lock().unwrap().1)
+}
+
+pub mod account {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "accounts")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Account",
+        serialize_writes,
+        update::one::pre = super::track
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub owner: String,
+        pub email: Option<String>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(generate_router, api_struct = "Note", update::one::pre = super::track)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use account::Account;
+use note::Note;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(account::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    Router::new()
+        .nest("/accounts", Account::router(&db).into())
+        .nest("/notes", Note::router(&db).into())
+}
+
+async fn create(app: &Router, path: &str, body: serde_json::Value) -> String {
+    let (status, created) = send(app, "POST", path, Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{created}");
+    created["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_updates_of_one_row_run_one_at_a_time() {
+    let app = setup().await;
+    let ada = create(&app, "/accounts", json!({"owner": "Ada"})).await;
+    let uri = format!("/accounts/{ada}");
+
+    let (renamed, emailed) = tokio::join!(
+        send(&app, "PUT", &uri, Some(json!({"owner": "Ada L."}))),
+        send(&app, "PUT", &uri, Some(json!({"email": "ada@example.com"}))),
+    );
+    assert_eq!(renamed.0, StatusCode::OK, "{}", renamed.1);
+    assert_eq!(emailed.0, StatusCode::OK, "{}", emailed.1);
+    assert_eq!(take_max_in_flight(), 1);
+
+    // Both merges landed
+    let (_, account) = send(&app, "GET", &uri, None).await;
+    assert_eq!(account["owner"], "Ada L.");
+    assert_eq!(account["email"], "ada@example.com");
+    assert_eq!(
+        crudcrate::locks::locked_rows(),
+        0,
+        "released locks are forgotten"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_other_rows_and_resources_stay_concurrent() {
+    let app = setup().await;
+    let ada = create(&app, "/accounts", json!({"owner": "Ada"})).await;
+    let bob = create(&app, "/accounts", json!({"owner": "Bob"})).await;
+
+    // Different rows: both updates are in the hook at once, each the only one on its row
+    let (ada, bob) = (format!("/accounts/{ada}"), format!("/accounts/{bob}"));
+    let started = std::time::Instant::now();
+    let (first, second) = tokio::join!(
+        send(&app, "PUT", &ada, Some(json!({"owner": "A"}))),
+        send(&app, "PUT", &bob, Some(json!({"owner": "B"}))),
+    );
+    assert_eq!((first.0, second.0), (StatusCode::OK, StatusCode::OK));
+    assert!(
+        started.elapsed() < Duration::from_millis(100),
+        "ran in parallel"
+    );
+    assert_eq!(take_max_in_flight(), 1);
+
+    // Without `serialize_writes` updates of one row interleave
+    let note = create(&app, "/notes", json!({"title": "Draft"})).await;
+    let uri = format!("/notes/{note}");
+    let (first, second) = tokio::join!(
+        send(&app, "PUT", &uri, Some(json!({"title": "One"}))),
+        send(&app, "PUT", &uri, Some(json!({"title": "Two"}))),
+    );
+    assert_eq!((first.0, second.0), (StatusCode::OK, StatusCode::OK));
+    assert_eq!(take_max_in_flight(), 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_batches_sharing_rows_do_not_deadlock() {
+    let app = setup().await;
+    let mut ids = Vec::new();
+    for owner in ["Ada", "Bob", "Cy"] {
+        ids.push(create(&app, "/accounts", json!({"owner": owner})).await);
+    }
+
+    // Opposite orders over the same rows
+    let forward: Vec<_> = ids
+        .iter()
+        .map(|id| json!({"id": id, "email": "f@example.com"}))
+        .collect();
+    let backward: Vec<_> = ids
+        .iter()
+        .rev()
+        .map(|id| json!({"id": id, "email": "b@example.com"}))
+        .collect();
+    let middle = format!("/accounts/{}", ids[1]);
+    let batches = async {
+        tokio::join!(
+            send(&app, "PATCH", "/accounts/batch", Some(json!(forward))),
+            send(&app, "PATCH", "/accounts/batch", Some(json!(backward))),
+            send(&app, "PUT", &middle, Some(json!({"owner": "B"}))),
+        )
+    };
+    let (forward, backward, single) = tokio::time::timeout(Duration::from_secs(5), batches)
+        .await
+        .expect("no deadlock");
+    assert_eq!(forward.0, StatusCode::OK, "{}", forward.1);
+    assert_eq!(backward.0, StatusCode::OK, "{}", backward.1);
+    assert_eq!(single.0, StatusCode::OK, "{}", single.1);
+    take_max_in_flight();
+
+    let (status, deleted) = send(&app, "DELETE", "/accounts/batch", Some(json!(ids))).await;
+    assert_eq!(status, StatusCode::OK, "{deleted}");
+    assert_eq!(deleted.as_array().unwrap().len(), 3);
+    assert_eq!(crudcrate::locks::locked_rows(), 0);
+}
@@ -0,0 +1,200 @@
+// Tests for `Server-Timing` headers (`crudcrate::timing`)
+// The test suite builds crudcrate with the `debug` feature, so every response of a
+// generated router carries the header. Verifies the phases reported by the list
+// endpoint and `GET /{id}` with join fields, `total` on writes and errors, and phases
+// added from hooks.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use crudcrate::{ApiError, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tower::ServiceExt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod track {
+    use super::*;
+
+    #[derive(
+        Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema,
+    )]
+    #[sea_orm(table_name = "tracks")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Track",
+        name_singular = "track",
+        name_plural = "tracks",
+        derive_partial_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub album_id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::album::Entity",
+            from = "Column::AlbumId",
+            to = "super::album::Column::Id"
+        )]
+        Album,
+    }
+
+    impl Related<super::album::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Album.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod album {
+    use super::*;
+
+    #[derive(Clone, Debug, DeriveEntityModel, EntityToModels, Serialize, Deserialize, ToSchema)]
+    #[sea_orm(table_name = "albums")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Album",
+        name_singular = "album",
+        name_plural = "albums",
+        no_partial_eq,
+        no_eq,
+        read::one::transform = price_album
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub title: String,
+
+        #[sea_orm(ignore)]
+        #[crudcrate(non_db_attr, join(one, all, depth = 1))]
+        pub tracks: Vec<super::track::Track>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(has_many = "super::track::Entity")]
+        Tracks,
+    }
+
+    impl Related<super::track::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Tracks.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    async fn price_album(_db: &DatabaseConnection, album: Album) -> Result<Album, ApiError> {
+        crudcrate::timing::measure("pricing", async { Ok(album) }).await
+    }
+}
+
+use album::Album;
+use track::Track;
+
+async fn setup() -> (Router, Uuid) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(album::Entity),
+        schema.create_table_from_entity(track::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+    let album_id = Uuid::new_v4();
+    album::ActiveModel {
+        id: sea_orm::Set(album_id),
+        title: sea_orm::Set("Kind of Blue".into()),
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+    let app = Router::new()
+        .nest("/albums", Album::router(&db).into())
+        .nest("/tracks", Track::router(&db).into());
+    (app, album_id)
+}
+
+/// Status and the phase names of the `Server-Timing` header, in order
+async fn timing(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    body: Option<serde_json::Value>,
+) -> (StatusCode, Vec<String>) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let header = response
+        .headers()
+        .get("server-timing")
+        .expect("Server-Timing header")
+        .to_str()
+        .unwrap();
+    let phases = header
+        .split(", ")
+        .map(|metric| {
+            let (name, duration) = metric.split_once(";dur=").unwrap();
+            assert!(duration.parse::<f64>().unwrap() >= 0.0, "{metric}");
+            name.to_string()
+        })
+        .collect();
+    (response.status(), phases)
+}
+
+#[tokio::test]
+async fn test_list_phases() {
+    let (app, _) = setup().await;
+
+    let (status, phases) = timing(&app, "GET", "/albums", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(phases, ["parse", "joins", "db", "serialize", "total"]);
+
+    // Without join fields there is no `joins` phase
+    let (_, phases) = timing(&app, "GET", "/tracks", None).await;
+    assert_eq!(phases, ["parse", "db", "serialize", "total"]);
+}
+
+#[tokio::test]
+async fn test_get_one_phases_with_hook() {
+    let (app, album_id) = setup().await;
+
+    let (status, phases) = timing(&app, "GET", &format!("/albums/{album_id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(phases, ["joins", "pricing", "db", "serialize", "total"]);
+}
+
+#[tokio::test]
+async fn test_writes_and_errors_report_total() {
+    let (app, album_id) = setup().await;
+
+    let body = json!({"album_id": album_id, "title": "So What"});
+    let (status, phases) = timing(&app, "POST", "/tracks", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(phases, ["total"]);
+
+    let (status, phases) = timing(&app, "GET", &format!("/tracks/{}", Uuid::new_v4()), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(phases, ["db", "total"]);
+}
@@ -0,0 +1,168 @@
+// Tests for `#[crudcrate(singleflight_ms = N)]`
+// Verifies that concurrent identical GET requests run the handler once and all get its
+// response, that successful responses are reused within the TTL and refetched after
+// it, that requests with different credentials or queries are not shared, and that
+// errors and writes are never reused.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use crudcrate::testing::send;
+use crudcrate::{ApiError, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Number of `get_one` calls per note id; tests use their own notes
+static READS: LazyLock<Mutex<HashMap<Uuid, usize>>> = LazyLock::new(Mutex::default);
+
+/// Counts the read and stalls it, so identical requests overlap
+async fn count_read(_db: &DatabaseConnection, id: Uuid) -> Result<(), ApiError> {
+    *READS.lock().unwrap().entry(id).or_default() += 1;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    Ok(())
+}
+
+fn reads(id: Uuid) -> usize {
+    READS.lock().unwrap().get(&id).copied().unwrap_or(0)
+}
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes",
+        singleflight_ms = 300,
+        read::one::pre = super::count_read
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::Note;
+
+/// The notes router and the id of a new note
+async fn setup() -> (Router, Uuid) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/notes", Note::router(&db).into());
+
+    let (status, body) = send(&app, "POST", "/notes", Some(json!({"title": "first"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    let id = body["id"].as_str().unwrap().parse().unwrap();
+    // Create reads the new note back; count from here
+    READS.lock().unwrap().remove(&id);
+    (app, id)
+}
+
+/// `GET uri` with an optional `Authorization` header
+async fn get(
+    app: Router,
+    uri: String,
+    authorization: Option<&'static str>,
+) -> (StatusCode, String) {
+    let mut request = Request::builder().uri(uri);
+    if let Some(authorization) = authorization {
+        request = request.header(header::AUTHORIZATION, authorization);
+    }
+    let response = app
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_concurrent_requests_share_one_read() {
+    let (app, id) = setup().await;
+
+    let mut requests = JoinSet::new();
+    for _ in 0..5 {
+        requests.spawn(get(app.clone(), format!("/notes/{id}"), None));
+    }
+    let responses = requests.join_all().await;
+
+    assert_eq!(reads(id), 1);
+    for (status, body) in &responses {
+        assert_eq!(*status, StatusCode::OK, "{body}");
+        assert_eq!(body, &responses[0].1);
+    }
+}
+
+#[tokio::test]
+async fn test_responses_are_reused_within_ttl() {
+    let (app, id) = setup().await;
+
+    get(app.clone(), format!("/notes/{id}"), None).await;
+    get(app.clone(), format!("/notes/{id}"), None).await;
+    assert_eq!(reads(id), 1);
+
+    // A different query is a different request
+    get(app.clone(), format!("/notes/{id}?v=2"), None).await;
+    assert_eq!(reads(id), 2);
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let (status, _) = get(app.clone(), format!("/notes/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(reads(id), 3);
+}
+
+#[tokio::test]
+async fn test_credentials_are_part_of_the_key() {
+    let (app, id) = setup().await;
+
+    let (alice, bob) = tokio::join!(
+        get(app.clone(), format!("/notes/{id}"), Some("Bearer alice")),
+        get(app.clone(), format!("/notes/{id}"), Some("Bearer bob")),
+    );
+    assert_eq!(alice.0, StatusCode::OK);
+    assert_eq!(bob.0, StatusCode::OK);
+    assert_eq!(reads(id), 2);
+}
+
+#[tokio::test]
+async fn test_errors_and_writes_are_not_reused() {
+    let (app, _) = setup().await;
+    let missing = Uuid::new_v4();
+
+    let (status, _) = get(app.clone(), format!("/notes/{missing}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    get(app.clone(), format!("/notes/{missing}"), None).await;
+    assert_eq!(reads(missing), 2);
+
+    // Identical writes each run
+    for _ in 0..2 {
+        let (status, _) = send(&app, "POST", "/notes", Some(json!({"title": "again"}))).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+    let (_, body) = send(&app, "GET", "/notes", None).await;
+    assert_eq!(body.as_array().unwrap().len(), 3);
+}
@@ -0,0 +1,180 @@
+// Tests for `#[crudcrate(slug_from = "...")]`
+// Verifies that creates fill in unique slugs, that sent slugs are normalised, that
+// `slug_on_update` regenerates the slug when the source changes, and that
+// `GET /slug/{slug}` finds the row, only matching the version of a temporal resource
+// valid now.
+
+use axum::Router;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Database, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod post {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "posts")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Post",
+        name_singular = "post",
+        name_plural = "posts"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        #[sea_orm(unique)]
+        #[crudcrate(slug_from = "title", slug_on_update, exclude(create))]
+        pub slug: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod page {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "pages")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Page",
+        name_singular = "page",
+        name_plural = "pages",
+        temporal
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        #[crudcrate(slug_from = "title", exclude(create))]
+        pub slug: String,
+
+        pub valid_from: DateTime<Utc>,
+
+        pub valid_to: Option<DateTime<Utc>>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use page::Page;
+use post::Post;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let statement = Schema::new(backend).create_table_from_entity(post::Entity);
+    db.execute(backend.build(&statement)).await.unwrap();
+    Router::new().nest("/posts", Post::router(&db).into())
+}
+
+async fn create(app: &Router, title: &str) -> serde_json::Value {
+    let (status, post) = send(app, "POST", "/posts", Some(json!({"title": title}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{post}");
+    post
+}
+
+#[tokio::test]
+async fn test_create_fills_in_unique_slugs() {
+    let app = setup().await;
+
+    let first = create(&app, "Hello, World!").await;
+    assert_eq!(first["slug"], "hello-world");
+    let second = create(&app, "Hello world").await;
+    assert_eq!(second["slug"], "hello-world-2");
+    let third = create(&app, "  hello -- WORLD ").await;
+    assert_eq!(third["slug"], "hello-world-3");
+    let untitled = create(&app, "?!").await;
+    assert_eq!(untitled["slug"], "post");
+
+    let (status, post) = send(&app, "GET", "/posts/slug/hello-world-2", None).await;
+    assert_eq!(status, StatusCode::OK, "{post}");
+    assert_eq!(post["id"], second["id"]);
+    assert_eq!(post["title"], "Hello world");
+
+    let (status, _) = send(&app, "GET", "/posts/slug/missing", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_update_regenerates_slug() {
+    let app = setup().await;
+    let post = create(&app, "Draft").await;
+    create(&app, "Release notes").await;
+    let uri = format!("/posts/{}", post["id"].as_str().unwrap());
+
+    // Sending the same title keeps the slug
+    let (status, updated) = send(&app, "PUT", &uri, Some(json!({"title": "Draft"}))).await;
+    assert_eq!(status, StatusCode::OK, "{updated}");
+    assert_eq!(updated["slug"], "draft");
+
+    let body = json!({"title": "Release Notes"});
+    let (status, updated) = send(&app, "PUT", &uri, Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{updated}");
+    assert_eq!(updated["slug"], "release-notes-2");
+
+    let body = json!({"slug": "My Custom Slug"});
+    let (status, updated) = send(&app, "PUT", &uri, Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{updated}");
+    assert_eq!(updated["slug"], "my-custom-slug");
+    assert_eq!(updated["title"], "Release Notes");
+
+    let (status, found) = send(&app, "GET", "/posts/slug/my-custom-slug", None).await;
+    assert_eq!(status, StatusCode::OK, "{found}");
+    assert_eq!(found["id"], post["id"]);
+}
+
+#[tokio::test]
+async fn test_slug_matches_the_current_version() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let statement = Schema::new(backend).create_table_from_entity(page::Entity);
+    db.execute(backend.build(&statement)).await.unwrap();
+
+    // Versions written outside the API can share a slug
+    let versions = [
+        (
+            "Pricing (2023)",
+            "2020-01-01T00:00:00Z",
+            Some("2024-01-01T00:00:00Z"),
+        ),
+        ("Pricing", "2024-01-01T00:00:00Z", None),
+    ];
+    for (title, from, to) in versions {
+        let time = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+        page::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            title: Set(title.to_string()),
+            slug: Set("pricing".to_string()),
+            valid_from: Set(time(from)),
+            valid_to: Set(to.map(time)),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    let app = Router::new().nest("/pages", Page::router(&db).into());
+
+    let (status, page) = send(&app, "GET", "/pages/slug/pricing", None).await;
+    assert_eq!(status, StatusCode::OK, "{page}");
+    assert_eq!(page["title"], "Pricing");
+}
@@ -0,0 +1,144 @@
+// Tests for the primary key tie-breaker in list ordering
+// Verifies that offset pages sorted on a non-unique column neither repeat nor skip
+// rows, that ties follow the direction of the sort, and that the list endpoint's OpenAPI
+// description states whether ties are ordered, including for `unstable_sort` resources.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Database, DatabaseConnection, Schema};
+use serde_json::Value;
+use uuid::Uuid;
+
+pub mod task {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tasks")]
+    #[crudcrate(generate_router, api_struct = "Task")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub priority: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod event {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "events")]
+    #[crudcrate(generate_router, api_struct = "Event", unstable_sort)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable)]
+        pub priority: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use event::Event;
+use task::Task;
+
+async fn setup() -> (DatabaseConnection, Router, Vec<Uuid>) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(task::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(event::Entity)))
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for index in 0..12 {
+        let id = Uuid::new_v4();
+        task::ActiveModel {
+            id: Set(id),
+            priority: Set(index % 2),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        ids.push(id);
+    }
+    let app = Router::new().nest("/tasks", Task::router(&db).into());
+    (db, app, ids)
+}
+
+fn ids(body: &Value) -> Vec<Uuid> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|row| Uuid::parse_str(row["id"].as_str().unwrap()).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_pages_on_non_unique_column_cover_every_row_once() {
+    let (_, app, mut expected) = setup().await;
+    for direction in ["ASC", "DESC"] {
+        let mut seen = Vec::new();
+        for page in 0..4 {
+            let uri = format!(
+                "/tasks?sort=%5B%22priority%22%2C%22{direction}%22%5D&range=%5B{},{}%5D",
+                page * 3,
+                page * 3 + 2
+            );
+            let (status, body) = send(&app, "GET", &uri, None).await;
+            assert_eq!(status, StatusCode::OK, "{body}");
+            seen.extend(ids(&body));
+        }
+        let mut sorted = seen.clone();
+        sorted.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(sorted, expected, "{direction}: every row exactly once");
+
+        // Within each priority, IDs follow the sort direction
+        let (first, second) = seen.split_at(6);
+        for tied in [first, second] {
+            let mut ordered = tied.to_vec();
+            ordered.sort_unstable();
+            if direction == "DESC" {
+                ordered.reverse();
+            }
+            assert_eq!(tied, ordered, "{direction}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_tie_breaker_is_documented() {
+    let (db, _, _) = setup().await;
+    let description = |openapi: utoipa::openapi::OpenApi| {
+        let doc = serde_json::to_value(&openapi).unwrap();
+        doc["paths"]["/"]["get"]["description"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let stable = description(Task::router(&db).into_openapi());
+    assert!(
+        stable.contains("ordered by `id` in the same direction"),
+        "{stable}"
+    );
+    let unstable = description(Event::router(&db).into_openapi());
+    assert!(unstable.contains("no guaranteed order"), "{unstable}");
+}
@@ -0,0 +1,201 @@
+// Tests for `#[crudcrate(state_machine(...))]`
+// Verifies that updates, batch updates and `POST /{id}/transition` only make declared
+// moves, that errors name the allowed next states, that unknown states are refused, and
+// that the transition endpoint is documented with the allowed moves and left out of
+// the read-only router.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod task {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tasks")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Task",
+        name_singular = "task",
+        name_plural = "tasks",
+        state_machine(field = "status", transitions("todo -> doing", "doing -> done, todo"))
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        pub status: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use task::Task;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(task::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/tasks", Task::router(&db).into());
+    (db, app)
+}
+
+async fn create(app: &Router, status: &str) -> String {
+    let (status_code, body) = send(
+        app,
+        "POST",
+        "/tasks",
+        Some(json!({"title": "Write", "status": status})),
+    )
+    .await;
+    assert_eq!(status_code, StatusCode::CREATED, "{body}");
+    body["id"].as_str().unwrap().to_string()
+}
+
+async fn status_of(app: &Router, id: &str) -> Value {
+    let (_, body) = send(app, "GET", &format!("/tasks/{id}"), None).await;
+    body["status"].clone()
+}
+
+#[tokio::test]
+async fn test_update_follows_transitions() {
+    let (_, app) = setup().await;
+    let id = create(&app, "todo").await;
+    let uri = format!("/tasks/{id}");
+
+    let (status, body) = send(&app, "PUT", &uri, Some(json!({"status": "done"}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["error"],
+        "Can't move `status` from \"todo\" to \"done\". Allowed next states: \"doing\"."
+    );
+
+    let (status, body) = send(&app, "PUT", &uri, Some(json!({"status": "doing"}))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["status"], "doing");
+
+    // Other fields and the same state pass
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &uri,
+        Some(json!({"title": "Edit", "status": "doing"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, "PUT", &uri, Some(json!({"title": "Ship"}))).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_final_state() {
+    let (_, app) = setup().await;
+    let id = create(&app, "done").await;
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/tasks/{id}"),
+        Some(json!({"status": "todo"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .contains("\"done\" is a final state"),
+        "{body}"
+    );
+}
+
+#[tokio::test]
+async fn test_batch_update_is_checked() {
+    let (_, app) = setup().await;
+    let first = create(&app, "todo").await;
+    let second = create(&app, "done").await;
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        "/tasks/batch",
+        Some(json!([
+            {"id": first, "status": "doing"},
+            {"id": second, "status": "todo"},
+        ])),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    // All or nothing
+    assert_eq!(status_of(&app, &first).await, "todo");
+    assert_eq!(status_of(&app, &second).await, "done");
+}
+
+#[tokio::test]
+async fn test_transition_endpoint() {
+    let (_, app) = setup().await;
+    let id = create(&app, "todo").await;
+    let uri = format!("/tasks/{id}/transition");
+
+    let (status, body) = send(&app, "POST", &uri, Some(json!({"to": "doing"}))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["status"], "doing");
+    assert_eq!(body["title"], "Write");
+
+    let (status, body) = send(&app, "POST", &uri, Some(json!({"to": "done"}))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let (status, body) = send(&app, "POST", &uri, Some(json!({"to": "doing"}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .contains("\"done\" is a final state"),
+        "{body}"
+    );
+
+    let (status, body) = send(&app, "POST", &uri, Some(json!({"to": "archived"}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["error"],
+        "\"archived\" is not a state of `status`. States: \"todo\", \"doing\", \"done\"."
+    );
+
+    let missing = format!("/tasks/{}/transition", Uuid::new_v4());
+    let (status, _) = send(&app, "POST", &missing, Some(json!({"to": "doing"}))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_transition_documented_and_read_only() {
+    let (db, _) = setup().await;
+    let (_, openapi) = Task::router(&db).split_for_parts();
+    let operation = openapi.paths.paths["/{id}/transition"]
+        .post
+        .as_ref()
+        .expect("transition operation");
+    assert_eq!(operation.operation_id.as_deref(), Some("transition_task"));
+    let description = operation.description.as_deref().unwrap();
+    assert!(
+        description.contains("- `doing` → \"done\", \"todo\""),
+        "{description}"
+    );
+
+    let (_, openapi) = Task::read_only_router(&db).split_for_parts();
+    assert!(!openapi.paths.paths.contains_key("/{id}/transition"));
+}
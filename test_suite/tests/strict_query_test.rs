@@ -0,0 +1,221 @@
+// Tests for `#[crudcrate(strict_query)]`
+// Verifies that unknown filter keys and sort columns return 400 naming the unknown keys
+// and the valid fields (by their external names), that operator suffixes and renamed
+// fields still work, and that resources without the flag keep ignoring unknown keys.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod task {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "tasks")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Task",
+        name_singular = "task",
+        name_plural = "tasks",
+        strict_query,
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub status: String,
+
+        #[crudcrate(filterable, sortable, rename = "dueDay")]
+        pub due_day: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod note {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, sortable)]
+        pub body: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::Note;
+use task::Task;
+
+/// Tasks "a" (open, day 1) and "b" (done, day 2), and one note
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(task::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/tasks", Task::router(&db).into())
+        .nest("/notes", Note::router(&db).into());
+
+    for (title, status, day) in [("a", "open", 1), ("b", "done", 2)] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/tasks",
+            Some(json!({"title": title, "status": status, "dueDay": day})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    let (status, _) = send(&app, "POST", "/notes", Some(json!({"body": "hi"}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    app
+}
+
+async fn list(app: &Router, resource: &str, query: &str) -> (StatusCode, Value) {
+    send(app, "GET", &format!("/{resource}?{query}"), None).await
+}
+
+fn filter(json: &str) -> String {
+    format!("filter={}", url_escape::encode_component(json))
+}
+
+#[tokio::test]
+async fn test_unknown_filter_key_is_rejected() {
+    let app = setup().await;
+
+    let (status, body) = list(&app, "tasks", &filter(r#"{"staus":"open"}"#)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert_eq!(
+        body["error"],
+        "Unknown filter field 'staus'. Filterable fields: dueDay, status, title"
+    );
+}
+
+#[tokio::test]
+async fn test_known_keys_still_filter() {
+    let app = setup().await;
+
+    let (status, body) = list(&app, "tasks", &filter(r#"{"status":"open"}"#)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    // Operator suffixes and renamed fields are known
+    let (status, body) = list(&app, "tasks", &filter(r#"{"dueDay_gte":2}"#)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body[0]["title"], "b");
+
+    let (status, body) = list(&app, "tasks", &filter(r#"{"title_neq":"a"}"#)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_fulltext_without_fulltext_fields_is_unknown() {
+    let app = setup().await;
+
+    let (status, body) = list(&app, "tasks", &filter(r#"{"q":"a"}"#)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert!(body["error"].as_str().unwrap().contains("'q'"), "{body}");
+}
+
+#[tokio::test]
+async fn test_unknown_sort_column_is_rejected() {
+    let app = setup().await;
+
+    let (status, body) = list(&app, "tasks", "sort_by=status&order=ASC").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert_eq!(
+        body["error"],
+        "Unknown sort field 'status'. Sortable fields: dueDay, title"
+    );
+
+    let (status, body) = list(&app, "tasks", "sort=dueDay&order=DESC").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body[0]["title"], "b");
+}
+
+#[tokio::test]
+async fn test_filter_and_sort_problems_are_reported_together() {
+    let app = setup().await;
+
+    let query = format!(
+        "{}&sort_by=ttle&order=ASC",
+        filter(r#"{"a":1,"b":2,"title":"a"}"#)
+    );
+    let (status, body) = list(&app, "tasks", &query).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    let error = body["error"].as_str().unwrap();
+    assert!(error.contains("Unknown filter fields 'a', 'b'."), "{error}");
+    assert!(error.contains("Unknown sort field 'ttle'."), "{error}");
+}
+
+#[tokio::test]
+async fn test_resources_without_flag_ignore_unknown_keys() {
+    let app = setup().await;
+
+    let (status, body) = list(&app, "notes", &filter(r#"{"bdy":"nope"}"#)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let (status, _) = list(&app, "notes", "sort_by=bdy&order=ASC").await;
+    assert_eq!(status, StatusCode::OK);
+}
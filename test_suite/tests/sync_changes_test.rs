@@ -0,0 +1,230 @@
+// Tests for `#[crudcrate(sync = "...")]`
+// Verifies that `GET /changes` returns the rows changed after the cursor and tombstones
+// for deleted ones as one ordered stream, pages through rows sharing a timestamp with
+// `after_id`, applies the scope condition, is mounted on the read-only router, and
+// rejects malformed cursors.
+
+use axum::http::StatusCode;
+use axum::{Extension, Router};
+use chrono::{DateTime, TimeZone, Utc};
+use crudcrate::testing::send;
+use crudcrate::{EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Condition, Database, DatabaseConnection, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(generate_router, api_struct = "Note", sync = "updated_at")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+
+        #[crudcrate(filterable)]
+        pub shared: bool,
+
+        #[crudcrate(exclude(create, update), on_create = Utc::now(), on_update = Utc::now())]
+        pub updated_at: DateTime<Utc>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::Note;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    crudcrate::sync::ensure_tombstones(&db).await.unwrap();
+    let app = Router::new().nest("/notes", Note::router(&db).into());
+    (db, app)
+}
+
+async fn create(app: &Router, title: &str, shared: bool) -> String {
+    let body = json!({"title": title, "shared": shared});
+    let (status, body) = send(app, "POST", "/notes", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    body["id"].as_str().unwrap().to_string()
+}
+
+fn titles(body: &Value) -> Vec<&str> {
+    body["changed"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["title"].as_str().unwrap())
+        .collect()
+}
+
+fn next(body: &Value) -> String {
+    format!(
+        "since={}&after_id={}",
+        body["since"].as_str().unwrap(),
+        body["after_id"].as_str().unwrap()
+    )
+}
+
+#[tokio::test]
+async fn test_changes_after_cursor_with_tombstones() {
+    let (_, app) = setup().await;
+    create(&app, "Groceries", true).await;
+    let errands = create(&app, "Errands", true).await;
+    let draft = create(&app, "Draft", true).await;
+
+    // First sync: everything, oldest change first
+    let (status, body) = send(&app, "GET", "/notes/changes", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["Groceries", "Errands", "Draft"]);
+    assert_eq!(body["deleted"], json!([]));
+    assert_eq!(body["has_more"], false);
+    assert_eq!(body["after_id"], draft.as_str());
+    let cursor = next(&body);
+
+    // Nothing new: the cursor comes back unchanged
+    let (_, body) = send(&app, "GET", &format!("/notes/changes?{cursor}"), None).await;
+    assert_eq!(titles(&body), Vec::<&str>::new());
+    assert_eq!(next(&body), cursor);
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/notes/{errands}"),
+        Some(json!({"title": "Errands today"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, "DELETE", &format!("/notes/{draft}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, body) = send(&app, "GET", &format!("/notes/changes?{cursor}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["Errands today"]);
+    assert_eq!(body["deleted"].as_array().unwrap().len(), 1, "{body}");
+    assert_eq!(body["deleted"][0]["id"], draft.as_str());
+    assert_eq!(body["since"], body["deleted"][0]["deleted_at"]);
+    assert_eq!(body["after_id"], draft.as_str());
+}
+
+#[tokio::test]
+async fn test_pages_through_rows_sharing_a_timestamp() {
+    let (db, app) = setup().await;
+    let at = Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap();
+    let mut ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+    for (index, id) in ids.iter().enumerate() {
+        note::ActiveModel {
+            id: Set(*id),
+            title: Set(format!("Note {index}")),
+            shared: Set(true),
+            updated_at: Set(at),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    ids.sort_unstable();
+
+    let mut seen = Vec::new();
+    let mut uri = "/notes/changes?limit=2".to_string();
+    loop {
+        let (status, body) = send(&app, "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        for row in body["changed"].as_array().unwrap() {
+            seen.push(Uuid::parse_str(row["id"].as_str().unwrap()).unwrap());
+        }
+        assert_eq!(body["since"], "2024-05-01T10:00:00Z");
+        if body["has_more"] == false {
+            break;
+        }
+        uri = format!("/notes/changes?limit=2&{}", next(&body));
+    }
+    assert_eq!(seen, ids);
+}
+
+#[tokio::test]
+async fn test_batch_and_filter_deletes_leave_tombstones() {
+    let (_, app) = setup().await;
+    let first = create(&app, "First", true).await;
+    let second = create(&app, "Second", true).await;
+    create(&app, "Third", false).await;
+    let (_, body) = send(&app, "GET", "/notes/changes", None).await;
+    let cursor = next(&body);
+
+    let (status, body) = send(&app, "DELETE", "/notes/batch", Some(json!([first, second]))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let (status, body) = send(
+        &app,
+        "DELETE",
+        &format!(
+            "/notes?filter={}",
+            url_escape::encode_component(r#"{"shared":false}"#)
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let (_, body) = send(&app, "GET", &format!("/notes/changes?{cursor}"), None).await;
+    assert_eq!(body["changed"], json!([]));
+    assert_eq!(body["deleted"].as_array().unwrap().len(), 3, "{body}");
+}
+
+#[tokio::test]
+async fn test_scope_and_read_only_router() {
+    let (db, app) = setup().await;
+    create(&app, "Shared", true).await;
+    create(&app, "Private", false).await;
+
+    let scoped: Router = Note::read_only_router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all().add(note::Column::Shared.eq(true)),
+        }))
+        .into();
+    let public = Router::new().nest("/notes", scoped);
+    let (status, body) = send(&public, "GET", "/notes/changes", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(titles(&body), ["Shared"]);
+}
+
+#[tokio::test]
+async fn test_rejects_malformed_cursors() {
+    let (_, app) = setup().await;
+    for query in [
+        "since=yesterday",
+        "since=2024-05-01T10:00:00Z&after_id=42",
+        "after_id=00000000-0000-0000-0000-000000000000",
+        "limit=0",
+    ] {
+        let (status, body) = send(&app, "GET", &format!("/notes/changes?{query}"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{query}: {body}");
+    }
+}
+
+#[tokio::test]
+async fn test_changes_are_documented() {
+    let (db, _) = setup().await;
+    let openapi = Note::router(&db).into_openapi();
+    let doc = serde_json::to_value(&openapi).unwrap();
+    let operation = &doc["paths"]["/changes"]["get"];
+    assert_eq!(operation["operationId"], "get_changes_notes", "{doc}");
+    let names: Vec<&str> = operation["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|parameter| parameter["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["since", "after_id", "limit"]);
+}
@@ -0,0 +1,141 @@
+// Tests for `crudcrate::telemetry`
+// Verifies that the generated routers make an incoming `traceparent` visible to hooks,
+// and that with the `otel` feature the request span continues the caller's trace and
+// the instrumented connection's query spans are children of the request span.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use crudcrate::{ApiError, EntityToModels};
+use opentelemetry::trace::{SpanKind, TracerProvider as _};
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use std::sync::Mutex;
+use tower::ServiceExt;
+use tracing_subscriber::layer::SubscriberExt;
+use uuid::Uuid;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+/// Trace IDs seen by the create hook
+static SEEN: Mutex<Vec<Option<String>>> = Mutex::new(Vec::new());
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        create::one::pre = super::remember_trace
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::{Note, NoteCreate};
+
+#[allow(clippy::unused_async)]
+async fn remember_trace(_db: &DatabaseConnection, _data: &NoteCreate) -> Result<(), ApiError> {
+    let context = crudcrate::telemetry::trace_context();
+    SEEN.lock()
+        .unwrap()
+        .push(context.map(|context| context.trace_id().to_string()));
+    Ok(())
+}
+
+/// Whether `span` is below `ancestor` in the exported `spans`
+fn descends_from<'a>(spans: &'a [SpanData], ancestor: &SpanData, mut span: &'a SpanData) -> bool {
+    loop {
+        if span.parent_span_id == ancestor.span_context.span_id() {
+            return true;
+        }
+        match spans
+            .iter()
+            .find(|parent| parent.span_context.span_id() == span.parent_span_id)
+        {
+            Some(parent) => span = parent,
+            None => return false,
+        }
+    }
+}
+
+async fn setup() -> DatabaseConnection {
+    let mut db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    crudcrate::telemetry::instrument(&mut db);
+    db
+}
+
+async fn create(app: &Router, traceparent: Option<&str>) -> StatusCode {
+    let mut request = Request::post("/notes").header("content-type", "application/json");
+    if let Some(traceparent) = traceparent {
+        request = request.header("traceparent", traceparent);
+    }
+    let request = request.body(Body::from(r#"{"title": "hello"}"#)).unwrap();
+    app.clone().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn test_traced_request() {
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    opentelemetry::global::set_tracer_provider(provider.clone());
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let db = setup().await;
+    let app = Router::new().nest("/notes", Note::router(&db).into());
+
+    assert_eq!(create(&app, Some(TRACEPARENT)).await, StatusCode::CREATED);
+    assert_eq!(
+        create(&app, Some("not-a-traceparent")).await,
+        StatusCode::CREATED
+    );
+    assert_eq!(create(&app, None).await, StatusCode::CREATED);
+    assert_eq!(
+        *SEEN.lock().unwrap(),
+        [Some(TRACE_ID.to_string()), None, None],
+        "hooks see the valid traceparent only"
+    );
+
+    provider.force_flush().unwrap();
+    let spans = exporter.get_finished_spans().unwrap();
+    let request = spans
+        .iter()
+        .find(|span| {
+            span.span_kind == SpanKind::Server
+                && span.span_context.trace_id().to_string() == TRACE_ID
+        })
+        .expect("request span in the caller's trace");
+    assert_eq!(request.parent_span_id.to_string(), "00f067aa0ba902b7");
+
+    // Query spans sit under the request span, possibly below Sea-ORM's own spans
+    let queries: Vec<&SpanData> = spans
+        .iter()
+        .filter(|span| span.span_kind == SpanKind::Client && descends_from(&spans, request, span))
+        .collect();
+    let names: Vec<&str> = queries.iter().map(|span| span.name.as_ref()).collect();
+    assert!(names.contains(&"INSERT notes"), "{names:?}");
+    assert!(names.contains(&"SELECT notes"), "{names:?}");
+    assert!(queries.iter().all(|span| span.start_time <= span.end_time));
+}
@@ -0,0 +1,276 @@
+// Tests for `#[crudcrate(temporal)]`
+// Verifies that lists and `GET /{id}` only return rows valid now or at `?as_of=`, with
+// open `NULL` bounds, custom date bound fields and scoped reads, that malformed `as_of`
+// values are refused, that writes ignore the window, and that `as_of` is documented.
+
+use axum::Router;
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use crudcrate::testing::send;
+use crudcrate::{EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Condition, Database, DatabaseConnection, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod price {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "prices")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Price",
+        name_singular = "price",
+        name_plural = "prices",
+        temporal
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub name: String,
+
+        pub valid_from: DateTime<Utc>,
+
+        pub valid_to: Option<DateTime<Utc>>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod rate {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "rates")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Rate",
+        name_singular = "rate",
+        name_plural = "rates",
+        temporal(from = "starts_on", to = "ends_on")
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub name: String,
+
+        pub starts_on: Date,
+
+        pub ends_on: Option<Date>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use price::Price;
+use rate::Rate;
+
+struct Ids {
+    expired: Uuid,
+    current: Uuid,
+}
+
+/// Prices "expired" (until yesterday), "current" (since yesterday, open-ended) and
+/// "scheduled" (from tomorrow); rates "2023" and "2024" bounded by dates.
+async fn setup() -> (DatabaseConnection, Ids) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(price::Entity),
+        schema.create_table_from_entity(rate::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+
+    let now = Utc::now();
+    let ids = Ids {
+        expired: Uuid::new_v4(),
+        current: Uuid::new_v4(),
+    };
+    for (id, name, valid_from, valid_to) in [
+        (
+            ids.expired,
+            "expired",
+            now - Duration::days(10),
+            Some(now - Duration::days(1)),
+        ),
+        (ids.current, "current", now - Duration::days(1), None),
+        (Uuid::new_v4(), "scheduled", now + Duration::days(1), None),
+    ] {
+        price::ActiveModel {
+            id: Set(id),
+            name: Set(name.to_string()),
+            valid_from: Set(valid_from),
+            valid_to: Set(valid_to),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+
+    let date = |year| NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+    for (name, starts_on, ends_on) in [
+        ("2023", date(2023), Some(date(2024))),
+        ("2024", date(2024), Some(date(2025))),
+    ] {
+        rate::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(name.to_string()),
+            starts_on: Set(starts_on),
+            ends_on: Set(ends_on),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    (db, ids)
+}
+
+fn app(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .nest("/prices", Price::router(db).into())
+        .nest("/rates", Rate::router(db).into())
+}
+
+fn names(body: &Value) -> Vec<&str> {
+    let mut names: Vec<&str> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+fn as_of(days: i64) -> String {
+    url_escape::encode_component(&(Utc::now() + Duration::days(days)).to_rfc3339()).to_string()
+}
+
+#[tokio::test]
+async fn test_list_returns_rows_valid_now() {
+    let (db, _) = setup().await;
+    let app = app(&db);
+
+    let (status, body) = send(&app, "GET", "/prices", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(names(&body), ["current"]);
+
+    let (_, body) = send(&app, "GET", &format!("/prices?as_of={}", as_of(-5)), None).await;
+    assert_eq!(names(&body), ["expired"]);
+    let (_, body) = send(&app, "GET", &format!("/prices?as_of={}", as_of(2)), None).await;
+    assert_eq!(names(&body), ["current", "scheduled"]);
+
+    // Combined with filters
+    let uri = format!(
+        "/prices?as_of={}&filter={}",
+        as_of(2),
+        url_escape::encode_component(r#"{"name":"scheduled"}"#)
+    );
+    let (_, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(names(&body), ["scheduled"]);
+}
+
+#[tokio::test]
+async fn test_get_one_outside_window() {
+    let (db, ids) = setup().await;
+    let app = app(&db);
+
+    let uri = format!("/prices/{}", ids.expired);
+    let (status, _) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, body) = send(&app, "GET", &format!("{uri}?as_of={}", as_of(-5)), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["name"], "expired");
+
+    let (status, _) = send(&app, "GET", &format!("/prices/{}", ids.current), None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_date_bounds() {
+    let (db, _) = setup().await;
+    let app = app(&db);
+
+    let (_, body) = send(&app, "GET", "/rates?as_of=2023-06-01", None).await;
+    assert_eq!(names(&body), ["2023"]);
+    // The end bound is exclusive
+    let (_, body) = send(&app, "GET", "/rates?as_of=2024-01-01", None).await;
+    assert_eq!(names(&body), ["2024"]);
+    let (_, body) = send(&app, "GET", "/rates", None).await;
+    assert!(names(&body).is_empty());
+}
+
+#[tokio::test]
+async fn test_invalid_as_of() {
+    let (db, ids) = setup().await;
+    let app = app(&db);
+
+    let (status, body) = send(&app, "GET", "/prices?as_of=yesterday", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["error"],
+        "'as_of' must be an RFC 3339 timestamp or a date (YYYY-MM-DD)"
+    );
+    let uri = format!("/prices/{}?as_of=soon", ids.current);
+    let (status, _) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_scoped_reads() {
+    let (db, ids) = setup().await;
+    let scoped: Router = Price::read_only_router(&db)
+        .layer(axum::Extension(ScopeCondition {
+            condition: Condition::all().add(price::Column::Name.ne("scheduled")),
+        }))
+        .into();
+    let app = Router::new().nest("/prices", scoped);
+
+    let (_, body) = send(&app, "GET", &format!("/prices?as_of={}", as_of(2)), None).await;
+    assert_eq!(names(&body), ["current"]);
+    let (status, _) = send(&app, "GET", &format!("/prices/{}", ids.expired), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_writes_ignore_window() {
+    let (db, ids) = setup().await;
+    let app = app(&db);
+
+    let uri = format!("/prices/{}", ids.expired);
+    let (status, body) = send(&app, "PUT", &uri, Some(json!({"name": "archived"}))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["name"], "archived");
+    let (status, _) = send(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_as_of_documented() {
+    let (db, _) = setup().await;
+    let (_, openapi) = Price::router(&db).split_for_parts();
+    for (path, operation) in [
+        ("/", openapi.paths.paths["/"].get.as_ref()),
+        ("/{id}", openapi.paths.paths["/{id}"].get.as_ref()),
+    ] {
+        let parameters = operation.unwrap().parameters.as_ref().unwrap();
+        assert!(
+            parameters.iter().any(|parameter| parameter.name == "as_of"),
+            "{path}"
+        );
+    }
+}
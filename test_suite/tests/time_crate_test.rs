@@ -0,0 +1,187 @@
+// Tests for entities with `time` crate date/time fields (`time` feature)
+// Verifies that RFC 3339 filter values match `OffsetDateTime`, `PrimitiveDateTime` and
+// `Date` columns whatever offset they are written with, that `_between` and IN lists
+// bind typed values too, that malformed values are rejected, and that the OpenAPI
+// schema documents the fields as date-time and date strings.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::filtering::dates::DateKind;
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveModelTrait, Database, Schema, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use time::macros::{date, datetime};
+use uuid::Uuid;
+
+pub mod meeting {
+    use super::*;
+
+    #[derive(
+        Clone, Debug, PartialEq, Eq, DeriveEntityModel, EntityToModels, Serialize, Deserialize,
+    )]
+    #[sea_orm(table_name = "meetings")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Meeting",
+        name_singular = "meeting",
+        name_plural = "meetings",
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable)]
+        pub title: String,
+
+        #[crudcrate(filterable, sortable)]
+        pub starts_at: TimeDateTimeWithTimeZone,
+
+        #[crudcrate(filterable)]
+        pub ends_at: TimeDateTime,
+
+        #[crudcrate(filterable)]
+        pub day: TimeDate,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use meeting::Meeting;
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(meeting::Entity)))
+        .await
+        .unwrap();
+
+    for (title, starts_at, ends_at, day) in [
+        (
+            "standup",
+            datetime!(2024-05-01 09:00 UTC),
+            datetime!(2024-05-01 09:15),
+            date!(2024 - 05 - 01),
+        ),
+        (
+            "review",
+            datetime!(2024-05-01 15:00 UTC),
+            datetime!(2024-05-01 16:00),
+            date!(2024 - 05 - 01),
+        ),
+        (
+            "planning",
+            datetime!(2024-05-02 10:00 UTC),
+            datetime!(2024-05-02 12:00),
+            date!(2024 - 05 - 02),
+        ),
+    ] {
+        meeting::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            title: Set(title.to_string()),
+            starts_at: Set(starts_at),
+            ends_at: Set(ends_at),
+            day: Set(day),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    Router::new().nest("/meetings", Meeting::router(&db).into())
+}
+
+async fn titles(app: &Router, filter: &Value) -> Vec<String> {
+    let filter = url_escape::encode_component(&filter.to_string()).to_string();
+    let (status, body) = send(app, "GET", &format!("/meetings?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut titles: Vec<String> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|meeting| meeting["title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn test_time_crate_columns_listed() {
+    assert_eq!(
+        Meeting::time_crate_columns(),
+        vec![
+            ("starts_at", DateKind::TimestampTz),
+            ("ends_at", DateKind::Timestamp),
+            ("day", DateKind::Date),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_rfc3339_values_match_any_offset() {
+    let app = setup().await;
+
+    assert_eq!(
+        titles(&app, &json!({"starts_at": "2024-05-01T11:00:00+02:00"})).await,
+        ["standup"]
+    );
+    assert_eq!(
+        titles(&app, &json!({"starts_at_gte": "2024-05-01T12:00:00Z"})).await,
+        ["planning", "review"]
+    );
+    // Stored as `2024-05-01 09:15:00.0`, matched from RFC 3339
+    assert_eq!(
+        titles(&app, &json!({"ends_at": "2024-05-01T09:15:00Z"})).await,
+        ["standup"]
+    );
+    assert_eq!(
+        titles(&app, &json!({"day": "2024-05-02"})).await,
+        ["planning"]
+    );
+}
+
+#[tokio::test]
+async fn test_between_and_in_lists() {
+    let app = setup().await;
+
+    assert_eq!(
+        titles(
+            &app,
+            &json!({"starts_at_between": ["2024-05-01T08:00:00Z", "2024-05-01T16:00:00Z"]})
+        )
+        .await,
+        ["review", "standup"]
+    );
+    assert_eq!(
+        titles(
+            &app,
+            &json!({"ends_at": ["2024-05-01T18:00:00+02:00", "2024-05-02T12:00:00Z"]})
+        )
+        .await,
+        ["planning", "review"]
+    );
+}
+
+#[tokio::test]
+async fn test_malformed_value_rejected() {
+    let app = setup().await;
+    let filter = url_escape::encode_component(r#"{"starts_at_gt":"next tuesday"}"#);
+    let (status, body) = send(&app, "GET", &format!("/meetings?filter={filter}"), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+}
+
+#[test]
+fn test_schema_formats() {
+    let schema = serde_json::to_value(<Meeting as utoipa::PartialSchema>::schema()).unwrap();
+    assert_eq!(schema["properties"]["starts_at"]["format"], "date-time");
+    assert_eq!(schema["properties"]["ends_at"]["format"], "date-time");
+    assert_eq!(schema["properties"]["day"]["format"], "date");
+}
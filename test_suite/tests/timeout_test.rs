@@ -0,0 +1,137 @@
+// Tests for `#[crudcrate(timeout_ms = N)]`
+// Verifies that handlers finishing within the deadline are unaffected, that a slow
+// handler is cancelled with 504 Gateway Timeout, that its uncommitted transaction is
+// rolled back, and that resources without the attribute never time out.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Database, DatabaseConnection, Schema, TransactionTrait};
+use serde_json::json;
+use std::time::Duration;
+use uuid::Uuid;
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Note",
+        name_singular = "note",
+        name_plural = "notes",
+        timeout_ms = 100,
+        create::one::body = super::create_note
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod tag {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "tags")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Tag",
+        name_singular = "tag",
+        name_plural = "tags"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub label: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use note::{Note, NoteCreate};
+use tag::Tag;
+
+/// Inserts the note in a transaction, stalling before the commit for titles "slow"
+async fn create_note(db: &DatabaseConnection, data: NoteCreate) -> Result<Note, ApiError> {
+    let txn = db.begin().await.map_err(ApiError::database)?;
+    let stall = data.title == "slow";
+    let model = note::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        title: Set(data.title),
+    }
+    .insert(&txn)
+    .await
+    .map_err(ApiError::database)?;
+    if stall {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+    txn.commit().await.map_err(ApiError::database)?;
+    Ok(model.into())
+}
+
+async fn setup() -> Router {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(note::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(tag::Entity)))
+        .await
+        .unwrap();
+    Router::new()
+        .nest("/notes", Note::router(&db).into())
+        .nest("/tags", Tag::router(&db).into())
+}
+
+#[tokio::test]
+async fn test_fast_requests_complete() {
+    let app = setup().await;
+
+    let (status, body) = send(&app, "POST", "/notes", Some(json!({"title": "quick"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    let (status, body) = send(&app, "GET", "/notes", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_slow_request_times_out_and_rolls_back() {
+    let app = setup().await;
+
+    let (status, body) = send(&app, "POST", "/notes", Some(json!({"title": "slow"}))).await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT, "{body}");
+    assert_eq!(body["error"], "The request did not complete within 100 ms");
+
+    // The cancelled handler's transaction never committed
+    let (status, body) = send(&app, "GET", "/notes", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body.as_array().unwrap().len(), 0, "{body}");
+}
+
+#[tokio::test]
+async fn test_timeout_defaults_to_none() {
+    assert_eq!(Note::operation_timeout(), Some(Duration::from_millis(100)));
+    assert_eq!(Tag::operation_timeout(), None);
+
+    let app = setup().await;
+    let (status, body) = send(&app, "POST", "/tags", Some(json!({"label": "rust"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+}
@@ -0,0 +1,257 @@
+// Tests for `#[crudcrate(tree(parent_field = "...", max_depth = N))]`
+// Verifies the generated `/{id}/children` (with `?depth=N` descendants) and
+// `/{id}/ancestors` endpoints, their depth limits and 404s, scope filtering on the
+// read-only router, and that both appear in the OpenAPI document.
+
+use axum::http::StatusCode;
+use axum::{Extension, Router};
+use crudcrate::testing::send;
+use crudcrate::{EntityToModels, ScopeCondition};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub mod category {
+    use super::*;
+
+    #[derive(
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+        DeriveEntityModel,
+        EntityToModels,
+        Serialize,
+        Deserialize,
+        ToSchema,
+    )]
+    #[sea_orm(table_name = "categories")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Category",
+        name_singular = "category",
+        name_plural = "categories",
+        tree(parent_field = "parent_id", max_depth = 3),
+        derive_partial_eq,
+        derive_eq
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(sortable, filterable)]
+        pub name: String,
+
+        pub parent_id: Option<Uuid>,
+
+        pub is_hidden: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use category::Category;
+
+/// ```text
+/// root
+/// ├── a
+/// │   └── a1
+/// │       └── a1x
+/// └── b (hidden)
+/// ```
+async fn setup() -> (DatabaseConnection, Router, Vec<String>) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(category::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/categories", Category::router(&db).into());
+
+    let mut ids: Vec<String> = Vec::new();
+    for (name, parent, is_hidden) in [
+        ("root", None, false),
+        ("a", Some(0), false),
+        ("a1", Some(1), false),
+        ("a1x", Some(2), false),
+        ("b", Some(0), true),
+    ] {
+        let parent_id = parent.map(|index: usize| ids[index].clone());
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/categories",
+            Some(json!({"name": name, "parent_id": parent_id, "is_hidden": is_hidden})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        ids.push(body["id"].as_str().unwrap().to_string());
+    }
+    (db, app, ids)
+}
+
+fn names(body: &Value) -> Vec<&str> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_direct_children() {
+    let (_, app, ids) = setup().await;
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/categories/{}/children", ids[0]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut children = names(&body);
+    children.sort_unstable();
+    assert_eq!(children, ["a", "b"]);
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/categories/{}/children", ids[3]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(names(&body).is_empty());
+}
+
+#[tokio::test]
+async fn test_descendants_by_depth() {
+    let (_, app, ids) = setup().await;
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/categories/{}/children?depth=3", ids[0]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let descendants = names(&body);
+    assert_eq!(descendants.len(), 4);
+    // Ordered by level
+    assert_eq!(&descendants[2..], ["a1", "a1x"]);
+
+    let (_, body) = send(
+        &app,
+        "GET",
+        &format!("/categories/{}/children?depth=2", ids[1]),
+        None,
+    )
+    .await;
+    assert_eq!(names(&body), ["a1", "a1x"]);
+}
+
+#[tokio::test]
+async fn test_depth_limits() {
+    let (_, app, ids) = setup().await;
+
+    for depth in [0, 4] {
+        let (status, body) = send(
+            &app,
+            "GET",
+            &format!("/categories/{}/children?depth={depth}", ids[0]),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "depth={depth}: {body}");
+    }
+}
+
+#[tokio::test]
+async fn test_ancestors_nearest_first() {
+    let (_, app, ids) = setup().await;
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/categories/{}/ancestors", ids[3]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(names(&body), ["a1", "a", "root"]);
+
+    let (_, body) = send(
+        &app,
+        "GET",
+        &format!("/categories/{}/ancestors", ids[0]),
+        None,
+    )
+    .await;
+    assert!(names(&body).is_empty());
+}
+
+#[tokio::test]
+async fn test_unknown_id_is_not_found() {
+    let (_, app, _) = setup().await;
+    let missing = Uuid::new_v4();
+
+    for endpoint in ["children", "ancestors"] {
+        let (status, _) = send(
+            &app,
+            "GET",
+            &format!("/categories/{missing}/{endpoint}"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{endpoint}");
+    }
+}
+
+#[tokio::test]
+async fn test_read_only_router_applies_scope() {
+    let (db, _, ids) = setup().await;
+    let public: Router = Category::read_only_router(&db)
+        .layer(Extension(ScopeCondition {
+            condition: Condition::all().add(category::Column::IsHidden.eq(false)),
+        }))
+        .into();
+    let public = Router::new().nest("/categories", public);
+
+    let (status, body) = send(
+        &public,
+        "GET",
+        &format!("/categories/{}/children", ids[0]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(names(&body), ["a"]);
+
+    let (status, _) = send(
+        &public,
+        "GET",
+        &format!("/categories/{}/ancestors", ids[4]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_openapi_lists_tree_paths() {
+    let (db, _, _) = setup().await;
+    let (_, openapi) = Category::router(&db).split_for_parts();
+    let openapi = serde_json::to_value(openapi).unwrap();
+    for path in ["/{id}/children", "/{id}/ancestors"] {
+        assert!(openapi["paths"].get(path).is_some(), "{path}");
+    }
+}
@@ -0,0 +1,76 @@
+// Tests for fulltext(strategy = "trigram")
+// Verifies trigram fields are exposed via trigram_searchable_columns(), that
+// Postgres combines pg_trgm similarity with the regular ILIKE search, and that
+// other backends treat trigram fields as ordinary fulltext fields.
+
+use crudcrate::filtering::build_fulltext_condition;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::DatabaseBackend;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+pub mod person {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "people")]
+    #[crudcrate(generate_router, api_struct = "Person")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, fulltext(strategy = "trigram"))]
+        pub name: String,
+
+        #[crudcrate(fulltext(strategy = "trigram", threshold = 0.45))]
+        pub nickname: Option<String>,
+
+        #[crudcrate(fulltext)]
+        pub bio: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+#[test]
+fn test_trigram_columns_and_thresholds() {
+    let columns: Vec<_> = person::Person::trigram_searchable_columns()
+        .into_iter()
+        .map(|(name, _, threshold)| (name, threshold))
+        .collect();
+    assert_eq!(columns, vec![("name", 0.3), ("nickname", 0.45)]);
+
+    // Trigram fields remain regular fulltext fields for other backends
+    let fulltext: Vec<_> = person::Person::fulltext_searchable_columns()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(fulltext, vec!["name", "nickname", "bio"]);
+}
+
+#[test]
+fn test_postgres_combines_trigram_and_ilike() {
+    let condition = build_fulltext_condition::<person::Person>("jonh", DatabaseBackend::Postgres)
+        .expect("Non-empty query should produce a condition");
+    let sql = format!("{condition:?}");
+
+    assert_eq!(sql.matches("similarity(").count(), 2, "got: {sql}");
+    assert!(sql.contains("ILIKE"), "bio should still use ILIKE: {sql}");
+    assert!(
+        !sql.contains("COALESCE(name::text"),
+        "trigram fields should not be part of the ILIKE chain: {sql}"
+    );
+}
+
+#[test]
+fn test_sqlite_treats_trigram_as_fulltext() {
+    let condition = build_fulltext_condition::<person::Person>("jonh", DatabaseBackend::Sqlite)
+        .expect("Non-empty query should produce a condition");
+    let sql = format!("{condition:?}");
+    assert!(!sql.contains("similarity("), "got: {sql}");
+    assert!(sql.contains("CAST(name AS TEXT)"), "got: {sql}");
+}
@@ -0,0 +1,113 @@
+// Tests for the tsvector_column attribute
+// Verifies the generated constant, the migration SQL, and that non-Postgres
+// backends keep using the LIKE fallback.
+
+use crudcrate::filtering::build_fulltext_condition;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::DatabaseBackend;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+pub mod document {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "documents")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Document",
+        fulltext_language = "simple",
+        tsvector_column = "search_vector"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(filterable, fulltext)]
+        pub title: String,
+
+        #[crudcrate(fulltext)]
+        pub body: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod note {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "notes")]
+    #[crudcrate(generate_router, api_struct = "Note")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        #[crudcrate(fulltext)]
+        pub text: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+#[test]
+fn test_tsvector_column_constant() {
+    assert_eq!(document::Document::TSVECTOR_COLUMN, Some("search_vector"));
+    assert_eq!(note::Note::TSVECTOR_COLUMN, None);
+}
+
+#[test]
+fn test_tsvector_column_sql() {
+    let sql = crudcrate::database::tsvector_column_sql::<document::Document>()
+        .expect("Document has a tsvector column");
+    assert!(sql.starts_with(
+        "ALTER TABLE \"documents\" ADD COLUMN IF NOT EXISTS \"search_vector\" tsvector"
+    ));
+    assert!(sql.contains("to_tsvector('simple'::regconfig"));
+    assert!(sql.contains("COALESCE(\"title\"::text, '')"));
+    assert!(sql.contains("COALESCE(\"body\"::text, '')"));
+    assert!(sql.ends_with("STORED"));
+
+    let index = crudcrate::database::tsvector_index_sql::<document::Document>()
+        .expect("Document has a tsvector column");
+    assert!(index.contains("USING GIN (\"search_vector\")"));
+
+    assert!(crudcrate::database::tsvector_column_sql::<note::Note>().is_none());
+    assert!(crudcrate::database::tsvector_index_sql::<note::Note>().is_none());
+}
+
+#[test]
+fn test_postgres_condition_uses_tsvector_column() {
+    let condition = build_fulltext_condition::<document::Document>(
+        "quarterly report",
+        DatabaseBackend::Postgres,
+    )
+    .expect("Non-empty query should produce a condition");
+    let sql = format!("{condition:?}");
+    assert!(sql.contains("plainto_tsquery"), "got: {sql}");
+    assert!(!sql.contains("ILIKE"), "got: {sql}");
+
+    // Resources without a tsvector column keep the ILIKE behaviour
+    let condition = build_fulltext_condition::<note::Note>("report", DatabaseBackend::Postgres)
+        .expect("Non-empty query should produce a condition");
+    assert!(format!("{condition:?}").contains("ILIKE"));
+}
+
+#[test]
+fn test_other_backends_ignore_tsvector_column() {
+    for backend in [DatabaseBackend::Sqlite, DatabaseBackend::MySql] {
+        let condition = build_fulltext_condition::<document::Document>("report", backend)
+            .expect("Non-empty query should produce a condition");
+        let sql = format!("{condition:?}");
+        assert!(!sql.contains("plainto_tsquery"), "got: {sql}");
+        assert!(sql.contains("LIKE"), "got: {sql}");
+    }
+}
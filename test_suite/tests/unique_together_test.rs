@@ -0,0 +1,236 @@
+// Tests for `#[crudcrate(unique_together(...))]`
+// Verifies that creates and updates colliding with another row get a 409 naming the
+// fields, that NULL values and rows outside a partial constraint's `condition` don't
+// collide, and that the generated unique index enforces the same constraint.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::EntityToModels;
+use crudcrate::database::{IndexKind, plan_indexes, unique_index_sql};
+use crudcrate::testing::send;
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue, Database, DatabaseBackend, DatabaseConnection, Schema};
+use serde_json::json;
+use uuid::Uuid;
+
+pub mod member {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "members")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Member",
+        name_singular = "member",
+        unique_together(email, org_id)
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub email: String,
+
+        pub org_id: Option<i32>,
+
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod coupon {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "coupons")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Coupon",
+        auto_index,
+        unique_together(code, condition = "deleted_at IS NULL")
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub code: String,
+
+        #[crudcrate(exclude(create, update))]
+        pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use coupon::Coupon;
+use member::Member;
+
+async fn setup() -> (Router, DatabaseConnection) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(&schema.create_table_from_entity(member::Entity)))
+        .await
+        .unwrap();
+    db.execute(backend.build(&schema.create_table_from_entity(coupon::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new()
+        .nest("/members", Member::router(&db).into())
+        .nest("/coupons", Coupon::router(&db).into());
+    (app, db)
+}
+
+#[tokio::test]
+async fn test_create_conflict_names_fields() {
+    let (app, _) = setup().await;
+    let body = json!({"email": "a@example.com", "org_id": 1, "name": "A"});
+    let (status, _) = send(&app, "POST", "/members", Some(body.clone())).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = send(&app, "POST", "/members", Some(body)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(
+        body["error"],
+        "A member with this email and org_id already exists"
+    );
+
+    // The same email in another organisation is fine
+    let body = json!({"email": "a@example.com", "org_id": 2, "name": "A"});
+    let (status, _) = send(&app, "POST", "/members", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_null_values_never_collide() {
+    let (app, _) = setup().await;
+    let body = json!({"email": "a@example.com", "org_id": null, "name": "A"});
+    for _ in 0..2 {
+        let (status, created) = send(&app, "POST", "/members", Some(body.clone())).await;
+        assert_eq!(status, StatusCode::CREATED, "{created}");
+    }
+}
+
+#[tokio::test]
+async fn test_update_conflict() {
+    let (app, _) = setup().await;
+    let (_, first) = send(
+        &app,
+        "POST",
+        "/members",
+        Some(json!({"email": "a@example.com", "org_id": 1, "name": "A"})),
+    )
+    .await;
+    let (_, second) = send(
+        &app,
+        "POST",
+        "/members",
+        Some(json!({"email": "b@example.com", "org_id": 1, "name": "B"})),
+    )
+    .await;
+    let second_url = format!("/members/{}", second["id"].as_str().unwrap());
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &second_url,
+        Some(json!({"email": "a@example.com"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+
+    // A row doesn't collide with itself, whether or not the fields are sent
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &second_url,
+        Some(json!({"email": "b@example.com", "name": "Bee"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let (status, body) = send(&app, "PUT", &second_url, Some(json!({"name": "B"}))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    // Moving the first member to another organisation frees its email
+    let first_url = format!("/members/{}", first["id"].as_str().unwrap());
+    let (status, _) = send(&app, "PUT", &first_url, Some(json!({"org_id": 2}))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &second_url,
+        Some(json!({"email": "a@example.com"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_partial_constraint_skips_other_rows() {
+    let (app, db) = setup().await;
+    let (status, created) = send(&app, "POST", "/coupons", Some(json!({"code": "SAVE10"}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = send(&app, "POST", "/coupons", Some(json!({"code": "SAVE10"}))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Once the first coupon is soft-deleted, the code is free again
+    let id: Uuid = created["id"].as_str().unwrap().parse().unwrap();
+    coupon::ActiveModel {
+        id: ActiveValue::Unchanged(id),
+        deleted_at: ActiveValue::Set(Some(chrono::Utc::now())),
+        ..Default::default()
+    }
+    .update(&db)
+    .await
+    .unwrap();
+    let (status, body) = send(&app, "POST", "/coupons", Some(json!({"code": "SAVE10"}))).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+}
+
+#[test]
+fn test_unique_index_sql() {
+    assert_eq!(
+        unique_index_sql::<Member>(DatabaseBackend::Postgres),
+        [
+            "CREATE UNIQUE INDEX IF NOT EXISTS \"uq_members_email_org_id\" ON \"members\" (\"email\", \"org_id\")"
+        ]
+    );
+    assert_eq!(
+        unique_index_sql::<Coupon>(DatabaseBackend::Sqlite),
+        [
+            "CREATE UNIQUE INDEX IF NOT EXISTS \"uq_coupons_code\" ON \"coupons\" (\"code\") WHERE deleted_at IS NULL"
+        ]
+    );
+    // MySQL can't index the partial constraint
+    assert!(unique_index_sql::<Coupon>(DatabaseBackend::MySql).is_empty());
+}
+
+#[tokio::test]
+async fn test_ensure_indexes_enforces_constraint() {
+    let (_, db) = setup().await;
+    let plan = plan_indexes::<Coupon>(DatabaseBackend::Sqlite);
+    assert!(plan.iter().any(|index| index.kind == IndexKind::Unique));
+
+    let created = crudcrate::database::ensure_indexes::<Coupon>(&db)
+        .await
+        .unwrap();
+    assert!(created.contains(&"uq_coupons_code".to_string()));
+
+    // Writes that skip the handlers hit the index
+    let insert = |deleted_at| coupon::ActiveModel {
+        id: ActiveValue::Set(Uuid::new_v4()),
+        code: ActiveValue::Set("SAVE10".to_string()),
+        deleted_at: ActiveValue::Set(deleted_at),
+    };
+    insert(None).insert(&db).await.unwrap();
+    insert(Some(chrono::Utc::now())).insert(&db).await.unwrap();
+    assert!(insert(None).insert(&db).await.is_err());
+}
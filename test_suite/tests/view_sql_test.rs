@@ -0,0 +1,201 @@
+// Tests for `#[crudcrate(view_sql = "...")]` resources
+// Verifies that list, get-one, distinct and by-ids reads select from the query, that
+// filters and sorting apply to its computed columns, and that `router()` serves no writes.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod customer {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "customers")]
+    #[crudcrate(generate_router, api_struct = "Customer")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        pub name: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod order {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "orders")]
+    #[crudcrate(generate_router, api_struct = "Order")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+        pub customer_id: Uuid,
+        pub total: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod customer_total {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "customer_totals")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "CustomerTotal",
+        name_singular = "customer_total",
+        name_plural = "customer_totals",
+        view_sql = "SELECT c.id, c.name, COUNT(o.id) AS orders, COALESCE(SUM(o.total), 0) AS spent \
+                    FROM customers c LEFT JOIN orders o ON o.customer_id = c.id \
+                    GROUP BY c.id, c.name"
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update))]
+        pub id: Uuid,
+        #[crudcrate(filterable, sortable, fulltext)]
+        pub name: String,
+        #[crudcrate(filterable, sortable)]
+        pub orders: i64,
+        #[crudcrate(filterable, sortable)]
+        pub spent: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use customer::Customer;
+use customer_total::CustomerTotal;
+use order::Order;
+
+async fn setup() -> (DatabaseConnection, Router, Vec<Uuid>) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    for statement in [
+        schema.create_table_from_entity(customer::Entity),
+        schema.create_table_from_entity(order::Entity),
+    ] {
+        db.execute(backend.build(&statement)).await.unwrap();
+    }
+    let app = Router::new()
+        .nest("/customers", Customer::router(&db).into())
+        .nest("/orders", Order::router(&db).into())
+        .nest("/customer-totals", CustomerTotal::router(&db).into());
+
+    let mut ids = Vec::new();
+    for (name, totals) in [
+        ("Ada", vec![10, 20, 5]),
+        ("Grace", vec![40]),
+        ("Linus", vec![]),
+    ] {
+        let (status, body) = send(&app, "POST", "/customers", Some(json!({"name": name}))).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        let id = Uuid::parse_str(body["id"].as_str().unwrap()).unwrap();
+        for total in totals {
+            let order = json!({"customer_id": id, "total": total});
+            let (status, body) = send(&app, "POST", "/orders", Some(order)).await;
+            assert_eq!(status, StatusCode::CREATED, "{body}");
+        }
+        ids.push(id);
+    }
+    (db, app, ids)
+}
+
+fn names(body: &Value) -> Vec<&str> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_list_filters_and_sorts_computed_columns() {
+    let (_, app, _) = setup().await;
+    let (status, body) = send(
+        &app,
+        "GET",
+        "/customer-totals?sort_by=spent&order=DESC",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(names(&body), ["Grace", "Ada", "Linus"]);
+    assert_eq!(body[1]["orders"], 3);
+    assert_eq!(body[1]["spent"], 35);
+
+    let filter = url_escape::encode_component(r#"{"orders_gte":1}"#);
+    let uri = format!("/customer-totals?filter={filter}&sort_by=orders&order=ASC");
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(names(&body), ["Grace", "Ada"]);
+
+    let filter = url_escape::encode_component(r#"{"q":"lin"}"#);
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/customer-totals?filter={filter}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(names(&body), ["Linus"]);
+}
+
+#[tokio::test]
+async fn test_get_one_distinct_and_by_ids_read_the_query() {
+    let (db, app, ids) = setup().await;
+    let (status, body) = send(&app, "GET", &format!("/customer-totals/{}", ids[1]), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["name"], "Grace");
+    assert_eq!(body["spent"], 40);
+
+    let (status, body) = send(&app, "GET", "/customer-totals/distinct/orders", None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let values: Vec<&Value> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| &e["value"])
+        .collect();
+    assert_eq!(values, [&json!(0), &json!(1), &json!(3)]);
+
+    let uri = format!("/customer-totals/by-ids?ids={},{}", ids[2], ids[0]);
+    let (status, body) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(names(&body["items"]), ["Linus", "Ada"]);
+
+    assert_eq!(CustomerTotal::total_count(&db, &Condition::all()).await, 3);
+}
+
+#[tokio::test]
+async fn test_router_serves_no_writes() {
+    let (_, app, ids) = setup().await;
+    let body = json!({"name": "Alan", "orders": 0, "spent": 0});
+    let (status, _) = send(&app, "POST", "/customer-totals", Some(body)).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    let uri = format!("/customer-totals/{}", ids[0]);
+    let (status, _) = send(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    let (status, _) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+}
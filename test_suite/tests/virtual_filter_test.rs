@@ -0,0 +1,177 @@
+// Tests for struct-level `virtual_filter(...)`
+// Verifies that a virtual key expands to its builder's condition alongside the other
+// filters, that builder errors reach the client, that `strict_query` accepts virtual
+// keys and lists them as valid, and that the list endpoint documents them.
+
+use axum::Router;
+use axum::http::StatusCode;
+use crudcrate::testing::send;
+use crudcrate::{ApiError, CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Database, DatabaseConnection, Schema};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+pub mod subscription {
+    use super::*;
+
+    /// Day the tests treat as today
+    pub const TODAY: i32 = 10;
+
+    /// `true`: paid up and not cancelled; `false`: the others
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 if the value isn't a boolean.
+    pub fn active_condition(value: &serde_json::Value) -> Result<Option<Condition>, ApiError> {
+        let active = value
+            .as_bool()
+            .ok_or_else(|| ApiError::bad_request("'active' expects true or false"))?;
+        let condition = Condition::all()
+            .add(Column::PaidUntil.gte(TODAY))
+            .add(Column::Cancelled.eq(false));
+        Ok(Some(if active { condition } else { condition.not() }))
+    }
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+    #[sea_orm(table_name = "subscriptions")]
+    #[crudcrate(
+        generate_router,
+        api_struct = "Subscription",
+        name_singular = "subscription",
+        name_plural = "subscriptions",
+        strict_query,
+        virtual_filter(
+            name = "active",
+            builder = active_condition,
+            description = "true for subscriptions that are paid up and not cancelled"
+        )
+    )]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+        pub id: Uuid,
+
+        pub customer: String,
+
+        #[crudcrate(filterable)]
+        pub plan: String,
+
+        pub paid_until: i32,
+
+        pub cancelled: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+use subscription::Subscription;
+
+async fn setup() -> (DatabaseConnection, Router) {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(subscription::Entity)))
+        .await
+        .unwrap();
+    let app = Router::new().nest("/subscriptions", Subscription::router(&db).into());
+
+    for (customer, plan, paid_until, cancelled) in [
+        ("ada", "pro", 30, false),
+        ("bo", "basic", 12, false),
+        ("cy", "pro", 5, false),
+        ("di", "pro", 40, true),
+    ] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/subscriptions",
+            Some(json!({
+                "customer": customer,
+                "plan": plan,
+                "paid_until": paid_until,
+                "cancelled": cancelled
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+    }
+    (db, app)
+}
+
+async fn list(app: &Router, filter: &Value) -> (StatusCode, Value) {
+    let filter = url_escape::encode_component(&filter.to_string()).to_string();
+    send(app, "GET", &format!("/subscriptions?filter={filter}"), None).await
+}
+
+async fn customers(app: &Router, filter: &Value) -> Vec<String> {
+    let (status, body) = list(app, filter).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut customers: Vec<String> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|subscription| subscription["customer"].as_str().unwrap().to_string())
+        .collect();
+    customers.sort();
+    customers
+}
+
+#[test]
+fn test_virtual_filters_are_listed() {
+    let filters = Subscription::virtual_filters();
+    assert_eq!(filters.len(), 1);
+    assert_eq!(filters[0].name, "active");
+    assert!(filters[0].description.starts_with("true for"));
+}
+
+#[tokio::test]
+async fn test_virtual_filter_expands_to_condition() {
+    let (_db, app) = setup().await;
+
+    assert_eq!(
+        customers(&app, &json!({"active": true})).await,
+        ["ada", "bo"]
+    );
+    assert_eq!(
+        customers(&app, &json!({"active": false})).await,
+        ["cy", "di"]
+    );
+    assert_eq!(
+        customers(&app, &json!({"active": true, "plan": "pro"})).await,
+        ["ada"]
+    );
+}
+
+#[tokio::test]
+async fn test_virtual_filter_errors_and_strict_query() {
+    let (_db, app) = setup().await;
+
+    let (status, body) = list(&app, &json!({"active": "yes"})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.to_string().contains("expects true or false"), "{body}");
+
+    // Unknown keys are still rejected, with the virtual filter among the valid ones
+    let (status, body) = list(&app, &json!({"activ": true})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(
+        body.to_string().contains("Filterable fields: active, plan"),
+        "{body}"
+    );
+}
+
+#[tokio::test]
+async fn test_virtual_filter_is_documented() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let (_, openapi) = Subscription::router(&db).split_for_parts();
+    let openapi = serde_json::to_value(openapi).unwrap();
+    let description = openapi["paths"]["/"]["get"]["description"]
+        .as_str()
+        .unwrap();
+    assert!(
+        description.contains("- active (virtual: true for subscriptions that are paid up"),
+        "{description}"
+    );
+}
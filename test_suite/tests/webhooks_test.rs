@@ -0,0 +1,243 @@
+// Tests for `#[crudcrate(webhooks)]` and `crudcrate::webhooks`
+// Verifies that writes record deliveries for the registered endpoints subscribed to
+// their event with the request ID of the write, that the worker posts them signed with
+// the endpoint's secret, that failed deliveries are retried until `max_attempts` and
+// then given up, and that workers sharing the table post each delivery once.
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use crudcrate::webhooks::{self, Event, NewWebhook, Payload, Worker};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::{Database, DatabaseConnection, Schema};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "orders")]
+#[crudcrate(api_struct = "Order", webhooks)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, exclude(create, update), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+
+    pub item: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Requests received by the receiver: event header, signature header and body
+type Received = Arc<Mutex<Vec<(String, String, String)>>>;
+
+/// Receiver answering `status` to every delivery; returns its URL
+async fn receiver(status: StatusCode) -> (String, Received) {
+    slow_receiver(status, Duration::ZERO).await
+}
+
+/// Receiver answering `status` to every delivery after `delay`; returns its URL
+async fn slow_receiver(status: StatusCode, delay: Duration) -> (String, Received) {
+    let received: Received = Arc::default();
+    let log = Arc::clone(&received);
+    let app = Router::new().route(
+        "/hook",
+        post(move |headers: HeaderMap, body: Bytes| {
+            let log = Arc::clone(&log);
+            async move {
+                let header = |name: &str| {
+                    headers
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                log.lock().unwrap().push((
+                    header("x-crudcrate-event"),
+                    header("x-crudcrate-signature"),
+                    String::from_utf8(body.to_vec()).unwrap(),
+                ));
+                tokio::time::sleep(delay).await;
+                status
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{address}/hook"), received)
+}
+
+async fn setup() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let backend = db.get_database_backend();
+    db.execute(backend.build(&Schema::new(backend).create_table_from_entity(Entity)))
+        .await
+        .unwrap();
+    webhooks::ensure_tables(&db).await.unwrap();
+    db
+}
+
+async fn subscribe(db: &DatabaseConnection, url: &str, events: Vec<Event>) {
+    webhooks::register(
+        db,
+        NewWebhook {
+            resource: Order::RESOURCE_NAME_PLURAL.to_string(),
+            url: url.to_string(),
+            secret: "s3cret".to_string(),
+            events,
+        },
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_writes_are_delivered_signed() {
+    let db = setup().await;
+    let (url, received) = receiver(StatusCode::OK).await;
+    subscribe(&db, &url, vec![Event::Created, Event::Deleted]).await;
+
+    let order = Order::create(
+        &db,
+        OrderCreate {
+            item: "Tea".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    Order::update(
+        &db,
+        order.id,
+        OrderUpdate {
+            item: Some(Some("Coffee".to_string())),
+        },
+    )
+    .await
+    .unwrap();
+    let delete = Order::delete(&db, order.id);
+    crudcrate::telemetry::with_request_id(Some("checkout-7f3a".to_string()), delete)
+        .await
+        .unwrap();
+
+    let worker = Worker::new(db.clone());
+    assert_eq!(
+        worker.run_once().await.unwrap(),
+        2,
+        "no update subscription"
+    );
+    assert_eq!(worker.run_once().await.unwrap(), 0, "delivered once");
+
+    let received = received.lock().unwrap();
+    let events: Vec<&str> = received.iter().map(|(event, ..)| event.as_str()).collect();
+    assert_eq!(
+        events,
+        [
+            format!("{}.created", Order::RESOURCE_NAME_PLURAL),
+            format!("{}.deleted", Order::RESOURCE_NAME_PLURAL),
+        ]
+    );
+    for (_, signature, body) in received.iter() {
+        assert_eq!(*signature, webhooks::sign("s3cret", body.as_bytes()));
+    }
+    let created: Payload = serde_json::from_str(&received[0].2).unwrap();
+    assert_eq!(created.event, Event::Created);
+    assert_eq!(created.id, order.id);
+    assert_eq!(created.data["item"], "Tea");
+    assert_eq!(created.request_id, None);
+    let deleted: Payload = serde_json::from_str(&received[1].2).unwrap();
+    assert_eq!(deleted.id, order.id);
+    assert!(deleted.data.is_null());
+    assert_eq!(deleted.request_id.as_deref(), Some("checkout-7f3a"));
+}
+
+#[tokio::test]
+async fn test_failed_delivery_is_retried_then_given_up() {
+    let db = setup().await;
+    let (url, received) = receiver(StatusCode::SERVICE_UNAVAILABLE).await;
+    subscribe(&db, &url, vec![]).await;
+    Order::create_many(
+        &db,
+        vec![OrderCreate {
+            item: "Tea".to_string(),
+        }],
+    )
+    .await
+    .unwrap();
+
+    let worker = Worker::new(db.clone()).max_attempts(2);
+    assert_eq!(worker.run_once().await.unwrap(), 1);
+    assert_eq!(worker.run_once().await.unwrap(), 0, "waits for the backoff");
+
+    // Make the retry due now
+    db.execute_unprepared(&format!(
+        "UPDATE {} SET next_attempt_at = '2000-01-01T00:00:00+00:00'",
+        webhooks::DELIVERY_TABLE
+    ))
+    .await
+    .unwrap();
+    assert_eq!(worker.run_once().await.unwrap(), 1);
+    assert_eq!(
+        worker.run_once().await.unwrap(),
+        0,
+        "failed after 2 attempts"
+    );
+    assert_eq!(received.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_workers_claim_deliveries() {
+    let db = setup().await;
+    let (url, received) = slow_receiver(StatusCode::OK, Duration::from_millis(50)).await;
+    subscribe(&db, &url, vec![]).await;
+    let orders = ["Tea", "Coffee", "Cocoa"].map(|item| OrderCreate {
+        item: item.to_string(),
+    });
+    Order::create_many(&db, orders.into()).await.unwrap();
+
+    // The second worker polls while the first is still posting its deliveries
+    let first = Worker::new(db.clone());
+    let second = Worker::new(db.clone());
+    let (attempted, other) = tokio::join!(first.run_once(), async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        second.run_once().await
+    });
+    assert_eq!(attempted.unwrap() + other.unwrap(), 3);
+    assert_eq!(
+        received.lock().unwrap().len(),
+        3,
+        "each delivery posted once"
+    );
+    assert_eq!(second.run_once().await.unwrap(), 0, "delivered");
+}
+
+#[tokio::test]
+async fn test_registration() {
+    let db = setup().await;
+    let invalid = webhooks::register(
+        &db,
+        NewWebhook {
+            resource: "orders".to_string(),
+            url: "ftp://example.com".to_string(),
+            secret: "s3cret".to_string(),
+            events: vec![],
+        },
+    )
+    .await;
+    assert!(invalid.is_err());
+
+    subscribe(&db, "https://example.com/a", vec![Event::Updated]).await;
+    let listed = webhooks::list(&db, Some(Order::RESOURCE_NAME_PLURAL))
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].events, [Event::Updated]);
+    assert!(webhooks::list(&db, Some("other")).await.unwrap().is_empty());
+
+    assert!(webhooks::unregister(&db, listed[0].id).await.unwrap());
+    assert!(!webhooks::unregister(&db, listed[0].id).await.unwrap());
+}